@@ -8,29 +8,73 @@ use yew::{function_component, html, Html};
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
 use crate::appheader::AppHeader;
+use crate::dashboard::DashboardWindowManager;
+use crate::find_replace::FindReplaceWindowManager;
+use crate::item_flow::ItemFlowWindowManager;
+use crate::item_usage::ItemUsageWindowManager;
 use crate::modal::ModalManager;
 use crate::node_display::NodeTreeDisplay;
 use crate::notifications::Notifications;
+use crate::pivot::PivotWindowManager;
+use crate::power_report::PowerReportWindowManager;
+use crate::print_view::PrintViewManager;
+use crate::recipe_compare::RecipeCompareWindowManager;
+use crate::reports::ReportsWindowManager;
 use crate::storagenotice::StorageNotice;
-use crate::user_settings::{UserSettingsManager, UserSettingsWindowManager};
-use crate::world::{DbChooserWindowManager, WorldChooserWindowManager, WorldManager};
+use crate::user_settings::{
+    ItemAliases, ThemeApplier, UserSettingsManager, UserSettingsWindowManager,
+};
+use crate::warnings::WarningsWindowManager;
+use crate::world::{
+    DbChooserWindowManager, ItemNotesWindowManager, SessionStatsWindowManager, SyncWindowManager,
+    WorldChooserWindowManager, WorldManager,
+};
 
 #[function_component]
 pub fn App() -> Html {
     html! {
         <ModalManager>
         <UserSettingsManager>
+        <ThemeApplier />
         <WorldManager>
+        <ItemAliases>
             <div class="App">
+                <PrintViewManager>
+                <ItemFlowWindowManager>
+                <PowerReportWindowManager>
                 <UserSettingsWindowManager>
                 <WorldChooserWindowManager>
                 <DbChooserWindowManager>
+                <ItemUsageWindowManager>
+                <RecipeCompareWindowManager>
+                <ItemNotesWindowManager>
+                <ReportsWindowManager>
+                <PivotWindowManager>
+                <FindReplaceWindowManager>
+                <SessionStatsWindowManager>
+                <SyncWindowManager>
+                <DashboardWindowManager>
+                <WarningsWindowManager>
                     <AppHeader />
+                </WarningsWindowManager>
+                </DashboardWindowManager>
+                </SyncWindowManager>
+                </SessionStatsWindowManager>
+                </FindReplaceWindowManager>
+                </PivotWindowManager>
+                </ReportsWindowManager>
+                </ItemNotesWindowManager>
+                </RecipeCompareWindowManager>
+                </ItemUsageWindowManager>
                 </DbChooserWindowManager>
                 </WorldChooserWindowManager>
                 </UserSettingsWindowManager>
                 <NodeTreeDisplay />
+                </PowerReportWindowManager>
+                </ItemFlowWindowManager>
+                </PrintViewManager>
             </div>
+        </ItemAliases>
         </WorldManager>
         <Notifications />
         <StorageNotice />
@@ -0,0 +1,108 @@
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+use yew::{function_component, html, use_callback, use_effect_with, Callback, Html};
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::user_settings::{use_user_settings, KeyBindings};
+use crate::world::{use_world_dispatcher, WorldDispatcher};
+
+/// Depths offered as buttons in the menu bar. Deeper levels are available by keyboard shortcut.
+const DEPTH_BUTTONS: [usize; 3] = [1, 2, 3];
+
+/// Menu bar buttons to collapse or expand every group in the tree at once, along with their
+/// keyboard shortcuts. By default:
+///
+/// * Alt+- collapses every group.
+/// * Alt+= expands every group.
+/// * Alt+1 through Alt+9 expand groups to that depth and collapse anything deeper.
+///
+/// The shortcuts can be changed in the user's key bindings.
+#[function_component]
+pub fn TreeExpansionControls() -> Html {
+    let dispatcher = use_world_dispatcher();
+    let user_settings = use_user_settings();
+    let bindings = &user_settings.key_bindings;
+
+    use_effect_with(
+        (dispatcher.clone(), bindings.clone()),
+        |(dispatcher, bindings)| {
+            let dispatcher = dispatcher.clone();
+            let bindings = bindings.clone();
+            let listener = EventListener::new(&gloo::utils::window(), "keydown", move |event| {
+                let event = event.unchecked_ref::<KeyboardEvent>();
+                if handle_shortcut(&dispatcher, &bindings, event) {
+                    event.prevent_default();
+                }
+            });
+            move || drop(listener)
+        },
+    );
+
+    let collapse_all = use_callback(dispatcher.clone(), |_, dispatcher| {
+        dispatcher.collapse_all();
+    });
+    let expand_all = use_callback(dispatcher.clone(), |_, dispatcher| {
+        dispatcher.expand_all();
+    });
+
+    html! {
+        <>
+            <Button title={format!("Collapse All Groups ({})", bindings.collapse_all.label())}
+                onclick={collapse_all}>
+                {material_icon("unfold_less")}
+            </Button>
+            <span class="expand-depth-buttons">
+                {for DEPTH_BUTTONS.into_iter().map(|depth| {
+                    let dispatcher = dispatcher.clone();
+                    let onclick = Callback::from(move |_| dispatcher.expand_to_depth(depth));
+                    let title = format!(
+                        "Expand Groups to Depth {depth} ({}{depth})",
+                        bindings.expand_to_depth.label()
+                    );
+                    html! {
+                        <Button {title} {onclick}>{depth}</Button>
+                    }
+                })}
+            </span>
+            <Button title={format!("Expand All Groups ({})", bindings.expand_all.label())}
+                onclick={expand_all}>
+                {material_icon("unfold_more")}
+            </Button>
+        </>
+    }
+}
+
+/// Apply the expansion shortcut for the given key press, if it is one. Returns true if the key
+/// press was handled.
+fn handle_shortcut(
+    dispatcher: &WorldDispatcher,
+    bindings: &KeyBindings,
+    event: &KeyboardEvent,
+) -> bool {
+    if is_editing_text(event) {
+        return false;
+    }
+    if bindings.collapse_all.matches(event) {
+        dispatcher.collapse_all();
+    } else if bindings.expand_all.matches(event) {
+        dispatcher.expand_all();
+    } else if let Some(depth) = bindings.expand_depth(event) {
+        dispatcher.expand_to_depth(depth);
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Whether the key press is going to a text input, where shortcuts shouldn't apply.
+fn is_editing_text(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .is_some_and(|element| {
+            element.is_content_editable()
+                || matches!(&*element.tag_name(), "INPUT" | "TEXTAREA" | "SELECT")
+        })
+}
@@ -1,21 +1,35 @@
 use std::borrow::Cow;
 
-use yew::{function_component, html, use_callback, Html};
+use yew::{function_component, html, use_callback, Callback, Html};
 
+use expansion::TreeExpansionControls;
 use menubar::MenuBar;
 use titlebar::TitleBar;
 
 use crate::bugreport::ISSUES_PAGE;
+use crate::dashboard::use_dashboard_window;
+use crate::find_replace::use_find_replace_window;
 use crate::inputs::button::{Button, LinkButton};
+use crate::item_usage::use_item_usage_window;
 use crate::material::material_icon;
+use crate::modal::{use_modal_dispatcher, CancelDelete};
+use crate::pivot::use_pivot_window;
+use crate::print_view::PrintViewButton;
+use crate::recipe_compare::use_recipe_compare_window;
+use crate::reports::use_reports_window;
 use crate::user_settings::{
     use_user_settings, use_user_settings_dispatcher, use_user_settings_window,
+    SettingsProfilePicker,
 };
+use crate::warnings::WarningsButton;
 use crate::world::{
-    use_db_chooser_window, use_db_controller, use_undo_controller, use_world_chooser_window,
-    DatabaseVersionSelector,
+    use_db_chooser_window, use_db_controller, use_item_notes_window, use_read_only,
+    use_session_stats_window, use_sync_controller, use_sync_window, use_undo_controller,
+    use_world_chooser_window, use_world_list_dispatcher, use_world_root, DatabaseVersionSelector,
+    ReadOnlyMode, SyncStatus,
 };
 
+mod expansion;
 mod menubar;
 mod titlebar;
 
@@ -34,6 +48,30 @@ pub fn AppHeader() -> Html {
     let on_redo = use_callback(undo_controller.dispatcher(), |(), undo_dispatcher| {
         undo_dispatcher.redo();
     });
+    let on_open_sandbox = use_callback(undo_controller.dispatcher(), |(), undo_dispatcher| {
+        undo_dispatcher.open_sandbox();
+    });
+    let on_apply_sandbox = use_callback(undo_controller.dispatcher(), |(), undo_dispatcher| {
+        undo_dispatcher.close_sandbox(true);
+    });
+    let modals = use_modal_dispatcher();
+    let on_discard_sandbox = use_callback(
+        (undo_controller.dispatcher(), modals),
+        |(), (undo_dispatcher, modals)| {
+            let undo_dispatcher = undo_dispatcher.clone();
+            modals
+                .builder()
+                .title("Discard Sandbox")
+                .content(html! {
+                    <p>{"Throw away every change made since the sandbox was opened?"}</p>
+                })
+                .kind(CancelDelete::delete(Callback::from(move |()| {
+                    undo_dispatcher.close_sandbox(false)
+                })))
+                .build()
+                .persist();
+        },
+    );
 
     let db_controller = use_db_controller();
     let db_window_dispatcher = use_db_chooser_window();
@@ -41,6 +79,58 @@ pub fn AppHeader() -> Html {
         db_window_dispatcher.toggle_window();
     });
 
+    let item_notes_window_dispatcher = use_item_notes_window();
+    let on_item_notes = use_callback(
+        item_notes_window_dispatcher,
+        |(), item_notes_window_dispatcher| item_notes_window_dispatcher.toggle_window(),
+    );
+
+    let item_usage_window_dispatcher = use_item_usage_window();
+    let on_item_usage = use_callback(
+        item_usage_window_dispatcher,
+        |(), item_usage_window_dispatcher| item_usage_window_dispatcher.toggle_window(),
+    );
+
+    let recipe_compare_window_dispatcher = use_recipe_compare_window();
+    let on_recipe_compare = use_callback(
+        recipe_compare_window_dispatcher,
+        |(), recipe_compare_window_dispatcher| recipe_compare_window_dispatcher.toggle_window(),
+    );
+
+    let reports_window_dispatcher = use_reports_window();
+    let on_reports = use_callback(
+        reports_window_dispatcher,
+        |(), reports_window_dispatcher| reports_window_dispatcher.toggle_window(),
+    );
+
+    let pivot_window_dispatcher = use_pivot_window();
+    let on_pivot = use_callback(pivot_window_dispatcher, |(), pivot_window_dispatcher| {
+        pivot_window_dispatcher.toggle_window()
+    });
+
+    let find_replace_window_dispatcher = use_find_replace_window();
+    let on_find_replace = use_callback(
+        find_replace_window_dispatcher,
+        |(), find_replace_window_dispatcher| find_replace_window_dispatcher.toggle_window(),
+    );
+
+    let dashboard_window_dispatcher = use_dashboard_window();
+    let on_dashboard = use_callback(
+        dashboard_window_dispatcher,
+        |(), dashboard_window_dispatcher| dashboard_window_dispatcher.toggle_window(),
+    );
+
+    let stats_window_dispatcher = use_session_stats_window();
+    let on_stats = use_callback(stats_window_dispatcher, |(), stats_window_dispatcher| {
+        stats_window_dispatcher.toggle_window()
+    });
+
+    let sync_status = use_sync_controller().status().clone();
+    let sync_window_dispatcher = use_sync_window();
+    let on_sync = use_callback(sync_window_dispatcher, |(), sync_window_dispatcher| {
+        sync_window_dispatcher.toggle_window()
+    });
+
     let hide_empty = use_user_settings().hide_empty_balances;
     let settings_dispatcher = use_user_settings_dispatcher();
     let on_toggle_empty = use_callback(settings_dispatcher, |(), settings_dispatcher| {
@@ -53,30 +143,125 @@ pub fn AppHeader() -> Html {
         |(), settings_window_dispatcher| settings_window_dispatcher.toggle_window(),
     );
 
-    let left = html! {
-        <>
-            <Button title="Choose World" onclick={on_choose_world}>
-                {material_icon("folder_open")}
-            </Button>
-            <Button title="Undo" onclick={on_undo} disabled={!undo_controller.has_undo()}>
-                {material_icon("undo")}
-            </Button>
-            <Button title="Redo" onclick={on_redo} disabled={!undo_controller.has_redo()}>
-                {material_icon("redo")}
-            </Button>
-            <Button title="Choose Database" onclick={on_db}>
-                {material_icon("factory")}
-                <span>{db_name(db_controller.current_selector())}</span>
-            </Button>
-            <Button class="hide-empty-button" title="Hide Empty Balances" onclick={on_toggle_empty}>
-                {material_icon("exposure_zero")}
-                if hide_empty {
-                    {material_icon("visibility_off")}
-                } else {
+    let root_id = use_world_root().id();
+
+    let read_only = use_read_only();
+    let world_list_dispatcher = use_world_list_dispatcher();
+    let on_save_copy = use_callback(world_list_dispatcher, |(), world_list_dispatcher| {
+        world_list_dispatcher.save_shared_copy();
+    });
+
+    let left = match read_only.mode() {
+        Some(mode) => html! {
+            <>
+                <span class="read-only-badge" title="This world can't be edited or saved">
                     {material_icon("visibility")}
+                    <span>{"Read Only"}</span>
+                </span>
+                if mode == ReadOnlyMode::SharedLink {
+                    <Button class="green" title="Save a copy of this world to edit it"
+                        onclick={on_save_copy}>
+                        {material_icon("save")}
+                        <span>{"Save a Copy"}</span>
+                    </Button>
                 }
-            </Button>
-        </>
+                <Button title="Where is an Item Used?" onclick={on_item_usage.clone()}>
+                    {material_icon("manage_search")}
+                </Button>
+                <Button title="Compare Recipes" onclick={on_recipe_compare.clone()}>
+                    {material_icon("compare_arrows")}
+                </Button>
+                <Button title="World Reports" onclick={on_reports}>
+                    {material_icon("analytics")}
+                </Button>
+                <Button title="Pivot Table" onclick={on_pivot}>
+                    {material_icon("pivot_table_chart")}
+                </Button>
+                <Button title="World Statistics" onclick={on_dashboard}>
+                    {material_icon("dashboard")}
+                </Button>
+                <WarningsButton />
+                <PrintViewButton id={root_id} />
+                <TreeExpansionControls />
+            </>
+        },
+        None => html! {
+            <>
+                <Button title="Choose World" onclick={on_choose_world}>
+                    {material_icon("folder_open")}
+                </Button>
+                <Button title="Undo" onclick={on_undo} disabled={!undo_controller.has_undo()}>
+                    {material_icon("undo")}
+                </Button>
+                <Button title="Redo" onclick={on_redo} disabled={!undo_controller.has_redo()}>
+                    {material_icon("redo")}
+                </Button>
+                if undo_controller.in_sandbox() {
+                    <span class="sandbox-badge"
+                        title="Changes are kept in memory until they are applied or discarded">
+                        {material_icon("science")}
+                        <span>{"Sandbox"}</span>
+                    </span>
+                    <Button class="green" title="Apply the sandbox changes to this world"
+                        onclick={on_apply_sandbox}>
+                        {material_icon("check")}
+                        <span>{"Apply"}</span>
+                    </Button>
+                    <Button class="red" title="Discard the sandbox changes"
+                        onclick={on_discard_sandbox}>
+                        {material_icon("delete")}
+                        <span>{"Discard"}</span>
+                    </Button>
+                } else {
+                    <Button title="Try out changes in a sandbox without saving them"
+                        onclick={on_open_sandbox}>
+                        {material_icon("science")}
+                    </Button>
+                }
+                <Button title="Choose Database" onclick={on_db}>
+                    {material_icon("factory")}
+                    <span>{db_name(db_controller.current_selector())}</span>
+                </Button>
+                <Button title="Item Notes" onclick={on_item_notes}>
+                    {material_icon("sticky_note_2")}
+                </Button>
+                <Button title="Where is an Item Used?" onclick={on_item_usage.clone()}>
+                    {material_icon("manage_search")}
+                </Button>
+                <Button title="Compare Recipes" onclick={on_recipe_compare.clone()}>
+                    {material_icon("compare_arrows")}
+                </Button>
+                <Button title="World Reports" onclick={on_reports}>
+                    {material_icon("analytics")}
+                </Button>
+                <Button title="Pivot Table" onclick={on_pivot}>
+                    {material_icon("pivot_table_chart")}
+                </Button>
+                <Button title="Find and Replace" onclick={on_find_replace}>
+                    {material_icon("find_replace")}
+                </Button>
+                <Button title="World Statistics" onclick={on_dashboard}>
+                    {material_icon("dashboard")}
+                </Button>
+                <WarningsButton />
+                <PrintViewButton id={root_id} />
+                <TreeExpansionControls />
+                <Button title="Session Stats" onclick={on_stats}>
+                    {material_icon("query_stats")}
+                </Button>
+                <Button title="Sync" onclick={on_sync}>
+                    {material_icon(sync_icon(&sync_status))}
+                </Button>
+                <Button class="hide-empty-button" title="Hide Empty Balances" onclick={on_toggle_empty}>
+                    {material_icon("exposure_zero")}
+                    if hide_empty {
+                        {material_icon("visibility_off")}
+                    } else {
+                        {material_icon("visibility")}
+                    }
+                </Button>
+            </>
+        },
     };
 
     const VERSION: &'static str = env!("CARGO_PKG_VERSION");
@@ -84,9 +269,12 @@ pub fn AppHeader() -> Html {
     let right = html! {
         <>
             <span>{"App Version: "}{VERSION}</span>
-            <Button title="Settings" onclick={on_settings}>
-                {material_icon("settings")}
-            </Button>
+            <SettingsProfilePicker />
+            if !read_only.is_read_only() {
+                <Button title="Settings" onclick={on_settings}>
+                    {material_icon("settings")}
+                </Button>
+            }
             <LinkButton title="Bug Report" target="_blank" href={ISSUES_PAGE}>
                 {material_icon("bug_report")}
             </LinkButton>
@@ -101,6 +289,16 @@ pub fn AppHeader() -> Html {
     }
 }
 
+/// Get the icon for the sync button which reflects the current sync status.
+fn sync_icon(status: &SyncStatus) -> &'static str {
+    match status {
+        SyncStatus::Idle => "cloud",
+        SyncStatus::Syncing => "cloud_sync",
+        SyncStatus::Synced => "cloud_done",
+        SyncStatus::Conflict | SyncStatus::Failed(_) => "cloud_off",
+    }
+}
+
 /// Get a string representing the name of this database choice for the database chooser button.
 fn db_name(version: Option<DatabaseVersionSelector>) -> Cow<'static, str> {
     match version {
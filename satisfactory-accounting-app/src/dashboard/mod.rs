@@ -0,0 +1,286 @@
+//! Window showing an overview of the totals for the whole world.
+
+use satisfactory_accounting::accounting::ResourcePurity;
+use satisfactory_accounting::analysis::{
+    find_node_over_allocations, map_resource_limit, summarize_world,
+};
+use satisfactory_accounting::database::ItemId;
+use yew::{function_component, hook, html, use_callback, use_context, AttrValue, Html};
+
+use crate::node_display::Icon;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_world_root};
+
+pub type DashboardWindowManager = WindowManager<DashboardWindow>;
+pub type DashboardWindowDispatcher = ShowWindowDispatcher<DashboardWindow>;
+
+/// Number of items to list in each of the surplus and deficit sections.
+const TOP_ITEMS: usize = 10;
+
+/// Gets access to the Dashboard window dispatcher which controls showing the dashboard window.
+#[hook]
+pub fn use_dashboard_window() -> DashboardWindowDispatcher {
+    use_context::<DashboardWindowDispatcher>().expect(
+        "use_dashboard_window can only be used from within a child of DashboardWindowManager",
+    )
+}
+
+/// Shows totals for the whole current world.
+#[function_component]
+pub fn DashboardWindow() -> Html {
+    let window_dispatcher = use_dashboard_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let root = use_world_root();
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let power_format = &balance_settings.power_format_settings;
+    let item_format = &balance_settings.item_format_settings;
+
+    let summary = summarize_world(&root);
+
+    let item_info = |id: ItemId| -> (AttrValue, Option<AttrValue>) {
+        match db.get(id) {
+            Some(item) => (item.name.clone().into(), Some(item.image.clone().into())),
+            None => ("Unknown Item".into(), None),
+        }
+    };
+
+    let mut machines: Vec<_> = summary.machines.iter().collect();
+    machines.sort_by(|lhs, rhs| rhs.1.cmp(lhs.1));
+    let machine_rows = machines.into_iter().map(|(&id, &count)| {
+        let (name, icon): (AttrValue, Option<AttrValue>) = match db.get(id) {
+            Some(building) => (
+                building.name.clone().into(),
+                Some(building.image.clone().into()),
+            ),
+            None => ("Unknown Building".into(), None),
+        };
+        html! {
+            <tr key={id.to_string()}>
+                <td class="icon"><Icon {icon} name={name.clone()} /></td>
+                <td class="name">{name}</td>
+                <td class="value">{count}</td>
+            </tr>
+        }
+    });
+
+    let resource_rows = summary.extracted.iter().map(|(&id, &rate)| {
+        let (name, icon) = item_info(id);
+        let limit = map_resource_limit(id, &db);
+        let (limit, usage) = match limit {
+            Some(limit) => {
+                let percent = rate / limit * 100.0;
+                let width = format!("width: {}%", percent.min(100.0));
+                (
+                    html! { {balance_settings.format_rate(limit).to_string()} },
+                    html! {
+                        <div class="usage" title={format!("{percent:.1}%")}>
+                            <div class={if percent > 100.0 { "usage-bar over" } else { "usage-bar" }}
+                                style={width} />
+                        </div>
+                    },
+                )
+            }
+            None => (html! { {"Unlimited"} }, html! {}),
+        };
+        html! {
+            <tr key={id.to_string()}>
+                <td class="icon"><Icon {icon} name={name.clone()} /></td>
+                <td class="name">{name}</td>
+                <td class="value">{balance_settings.format_rate(rate).to_string()}</td>
+                <td class="value">{limit}</td>
+                <td class="usage-cell">{usage}</td>
+            </tr>
+        }
+    });
+
+    let over_allocations = find_node_over_allocations(&summary, &db);
+    let over_allocation_warnings = over_allocations.iter().map(|over| {
+        let (name, _) = item_info(over.resource);
+        let kind = if over.well_pads { "well pads" } else { "nodes" };
+        html! {
+            <li>
+                {format!(
+                    "{} {} {name} {kind} are claimed, but the map only has {}.",
+                    over.claimed,
+                    over.purity.ident(),
+                    over.available,
+                )}
+            </li>
+        }
+    });
+
+    let node_rows = summary
+        .claimed_nodes
+        .iter()
+        .filter_map(|(&id, claimed)| Some((id, claimed, db.resource_nodes(id)?)))
+        .flat_map(|(id, claimed, available)| {
+            [
+                ("Nodes", &claimed.nodes, &available.nodes),
+                ("Well Pads", &claimed.well_pads, &available.well_pads),
+            ]
+            .into_iter()
+            .filter(|(_, claimed, _)| claimed.total() > 0)
+            .map(move |(kind, claimed, available)| (id, kind, *claimed, *available))
+        })
+        .map(|(id, kind, claimed, available)| {
+            let (name, icon) = item_info(id);
+            let purity_cell = |purity: ResourcePurity| {
+                let class = if claimed.get(purity) > available.get(purity) {
+                    "value negative"
+                } else {
+                    "value"
+                };
+                html! {
+                    <td {class}>
+                        {format!("{} / {}", claimed.get(purity), available.get(purity))}
+                    </td>
+                }
+            };
+            html! {
+                <tr key={format!("{id}-{kind}")}>
+                    <td class="icon"><Icon {icon} name={name.clone()} /></td>
+                    <td class="name">{name}</td>
+                    <td class="name">{kind}</td>
+                    {for ResourcePurity::values().map(purity_cell)}
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut balances: Vec<_> = root
+        .balance()
+        .balances
+        .iter()
+        .map(|(&id, &rate)| {
+            let rate = balance_settings.rate_in_unit(rate);
+            (id, rate.round_by_format(item_format))
+        })
+        .filter(|&(_, rate)| rate != 0.0)
+        .collect();
+    balances.sort_by(|lhs, rhs| rhs.1.total_cmp(&lhs.1));
+    let item_row = |&(id, rate): &(_, f32)| {
+        let (name, icon) = item_info(id);
+        let class = if rate > 0.0 {
+            "value positive"
+        } else {
+            "value negative"
+        };
+        html! {
+            <tr key={id.to_string()}>
+                <td class="icon"><Icon {icon} name={name.clone()} /></td>
+                <td class="name">{name}</td>
+                <td {class}>{rate.format(item_format).to_string()}</td>
+            </tr>
+        }
+    };
+    let surplus_rows = balances
+        .iter()
+        .take_while(|(_, rate)| *rate > 0.0)
+        .take(TOP_ITEMS)
+        .map(item_row);
+    let deficit_rows = balances
+        .iter()
+        .rev()
+        .take_while(|(_, rate)| *rate < 0.0)
+        .take(TOP_ITEMS)
+        .map(item_row);
+
+    let net_power = summary.power_produced - summary.power_consumed;
+
+    html! {
+        <OverlayWindow title="World Statistics" class="DashboardWindow" on_close={close}>
+            <div class="dashboard-section power">
+                <h2>{"Power"}</h2>
+                <table>
+                    <tr>
+                        <td class="name">{"Produced"}</td>
+                        <td class="value">
+                            {summary.power_produced.format(power_format).to_string()}{" MW"}
+                        </td>
+                    </tr>
+                    <tr>
+                        <td class="name">{"Consumed"}</td>
+                        <td class="value">
+                            {summary.power_consumed.format(power_format).to_string()}{" MW"}
+                        </td>
+                    </tr>
+                    <tr>
+                        <td class="name">{"Net"}</td>
+                        <td class={if net_power < 0.0 { "value negative" } else { "value positive" }}>
+                            {net_power.format(power_format).to_string()}{" MW"}
+                        </td>
+                    </tr>
+                </table>
+            </div>
+            <div class="dashboard-section machines">
+                <h2>{"Machines"}</h2>
+                if summary.machines.is_empty() {
+                    <p class="empty-section">{"No buildings in this world yet."}</p>
+                } else {
+                    <table>{for machine_rows}</table>
+                }
+            </div>
+            <div class="dashboard-section resources">
+                <h2>{"Raw Resources"}</h2>
+                <p>{"Resources extracted by miners and pumps compared to the most the map can \
+                provide, with every node and well used at 250% clock speed. Map limits are only \
+                shown when this world's database knows how many nodes the map has."}</p>
+                if summary.extracted.is_empty() {
+                    <p class="empty-section">{"Nothing is being extracted yet."}</p>
+                } else {
+                    <table>
+                        <tr>
+                            <th />
+                            <th>{"Resource"}</th>
+                            <th>{"Extracted"}</th>
+                            <th>{"Map Limit"}</th>
+                            <th />
+                        </tr>
+                        {for resource_rows}
+                    </table>
+                }
+            </div>
+            <div class="dashboard-section resource-nodes">
+                <h2>{"Resource Nodes"}</h2>
+                <p>{"Nodes and well pads claimed by miners and pumps compared to how many of each \
+                purity the map has."}</p>
+                if !over_allocations.is_empty() {
+                    <ul class="node-warnings">{for over_allocation_warnings}</ul>
+                }
+                if node_rows.is_empty() {
+                    <p class="empty-section">{"No resource nodes are claimed yet."}</p>
+                } else {
+                    <table>
+                        <tr>
+                            <th />
+                            <th>{"Resource"}</th>
+                            <th />
+                            {for ResourcePurity::values().map(|purity| html! {
+                                <th class="value">{purity.name()}</th>
+                            })}
+                        </tr>
+                        {node_rows}
+                    </table>
+                }
+            </div>
+            <div class="dashboard-section top-items">
+                <div>
+                    <h2>{"Largest Surpluses"}</h2>
+                    <table>{for surplus_rows}</table>
+                </div>
+                <div>
+                    <h2>{"Largest Deficits"}</h2>
+                    <table>{for deficit_rows}</table>
+                </div>
+            </div>
+        </OverlayWindow>
+    }
+}
@@ -0,0 +1,41 @@
+//! Helpers for triggering file downloads from the browser.
+
+use gloo::file::{Blob, ObjectUrl};
+use log::error;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlAnchorElement;
+
+/// Triggers a download of the given text contents as a file with the given name and mime type.
+///
+/// Returns the object url holding the contents. The download may fail if the object url is
+/// dropped before the browser has started the download, so callers should hang onto it until
+/// the component that triggered the download is disposed.
+pub fn download_text(contents: &str, mime_type: &str, filename: &str) -> Option<ObjectUrl> {
+    let blob = Blob::new_with_options(contents, Some(mime_type));
+    let url = ObjectUrl::from(blob);
+    download_url(&url, filename).then_some(url)
+}
+
+/// Triggers a download of the contents of the given url, such as a data url, as a file with the
+/// given name. Returns whether the download was started.
+pub fn download_url(url: &str, filename: &str) -> bool {
+    // To trigger the download, we create an anchor tag that isn't attached to the document
+    // and click it.
+    let a = match gloo::utils::document().create_element("a") {
+        Ok(a) => match a.dyn_into::<HtmlAnchorElement>() {
+            Ok(a) => a,
+            Err(elem) => {
+                error!("Unable to cast element {elem:?} to HtmlAnchorElement");
+                return false;
+            }
+        },
+        Err(e) => {
+            error!("Unable to create an 'a' element to download with: {e:?}");
+            return false;
+        }
+    };
+    a.set_href(url);
+    a.set_download(filename);
+    a.click();
+    true
+}
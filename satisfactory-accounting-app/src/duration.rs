@@ -0,0 +1,22 @@
+//! Helpers for displaying durations of in-game time.
+
+/// Formats a number of minutes as a short human readable duration, e.g. "2h 15m".
+pub fn format_minutes(minutes: f32) -> String {
+    if !minutes.is_finite() {
+        return "\u{221E}".to_owned();
+    }
+    let total_seconds = (minutes * 60.0).ceil() as u64;
+    let days = total_seconds / 86_400;
+    let hours = total_seconds % 86_400 / 3600;
+    let mins = total_seconds % 3600 / 60;
+    let secs = total_seconds % 60;
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m")
+    } else if mins > 0 {
+        format!("{mins}m {secs}s")
+    } else {
+        format!("{secs}s")
+    }
+}
@@ -0,0 +1,278 @@
+//! Window to find every building using a recipe, fuel, or resource and replace it with another.
+
+use std::collections::BTreeSet;
+
+use satisfactory_accounting::accounting::Selection;
+use satisfactory_accounting::analysis::{find_selection_users, replace_selection, SelectionUser};
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::toggle::MaterialCheckbox;
+use crate::material::material_icon;
+use crate::node_display::Icon;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::world::{use_db, use_world_dispatcher, use_world_root};
+
+pub type FindReplaceWindowManager = WindowManager<FindReplaceWindow>;
+pub type FindReplaceWindowDispatcher = ShowWindowDispatcher<FindReplaceWindow>;
+
+/// Gets access to the Find and Replace window dispatcher which controls showing the window.
+#[hook]
+pub fn use_find_replace_window() -> FindReplaceWindowDispatcher {
+    use_context::<FindReplaceWindowDispatcher>().expect(
+        "use_find_replace_window can only be used from within a child of \
+        FindReplaceWindowManager",
+    )
+}
+
+/// Finds the buildings using a recipe, fuel, or resource anywhere in the world and replaces it in
+/// the chosen ones, as a single change.
+#[function_component]
+pub fn FindReplaceWindow() -> Html {
+    let window_dispatcher = use_find_replace_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let db = use_db();
+    let root = use_world_root();
+    let world_dispatcher = use_world_dispatcher();
+    let find = use_state_eq(|| None::<Selection>);
+    let replace = use_state_eq(|| None::<Selection>);
+    // Buildings the user unchecked in the preview.
+    let excluded = use_state_eq(BTreeSet::<Uuid>::new);
+    let replaced = use_state_eq(|| None::<usize>);
+
+    let name_of = |selection: Selection| -> AttrValue {
+        match selection {
+            Selection::Recipe(id) => match db.get(id) {
+                Some(recipe) => recipe.name.clone().into(),
+                None => "Unknown Recipe".into(),
+            },
+            Selection::Item(id) => match db.get(id) {
+                Some(item) => item.name.clone().into(),
+                None => "Unknown Item".into(),
+            },
+        }
+    };
+    let icon_of = |selection: Selection| {
+        let image = match selection {
+            Selection::Recipe(id) => db.get(id).map(|recipe| recipe.image.clone()),
+            Selection::Item(id) => db.get(id).map(|item| item.image.clone()),
+        };
+        html! { <Icon icon={image.map(AttrValue::from)} name={name_of(selection)} /> }
+    };
+    let collator = crate::locale::get_collator();
+    let sort_by_name = |selections: &mut Vec<Selection>| {
+        selections.sort_by(|&a, &b| collator.compare(&name_of(a), &name_of(b)));
+    };
+
+    let used: BTreeSet<Selection> = root
+        .iter()
+        .filter_map(|node| node.building()?.settings.selection())
+        .collect();
+    let (mut recipes, mut items): (Vec<Selection>, Vec<Selection>) = used
+        .iter()
+        .partition(|selection| matches!(selection, Selection::Recipe(_)));
+    sort_by_name(&mut recipes);
+    sort_by_name(&mut items);
+
+    let find_choice = |selection: Selection| {
+        let onclick = {
+            let find = find.clone();
+            let replace = replace.clone();
+            let excluded = excluded.clone();
+            let replaced = replaced.clone();
+            Callback::from(move |()| {
+                find.set(Some(selection));
+                replace.set(None);
+                excluded.set(BTreeSet::new());
+                replaced.set(None);
+            })
+        };
+        let class = classes!((*find == Some(selection)).then_some("selected"));
+        html! {
+            <Button {class} title={name_of(selection)} {onclick}>{icon_of(selection)}</Button>
+        }
+    };
+
+    let users: Vec<SelectionUser> = find
+        .filter(|find| used.contains(find))
+        .map(|find| find_selection_users(&root, &db, find))
+        .unwrap_or_default();
+    let mut alternatives: Vec<Selection> = users
+        .iter()
+        .flat_map(|user| user.alternatives.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    sort_by_name(&mut alternatives);
+
+    let replace_choice = |selection: Selection| {
+        let usable = users
+            .iter()
+            .filter(|user| user.alternatives.contains(&selection))
+            .count();
+        let onclick = {
+            let replace = replace.clone();
+            Callback::from(move |()| replace.set(Some(selection)))
+        };
+        let class = classes!((*replace == Some(selection)).then_some("selected"));
+        let title = format!(
+            "{} ({usable} of {} buildings can use it)",
+            name_of(selection),
+            users.len()
+        );
+        html! {
+            <Button {class} {title} {onclick}>{icon_of(selection)}</Button>
+        }
+    };
+
+    // Buildings which will be changed when the replacement is applied.
+    let chosen: BTreeSet<Uuid> = match *replace {
+        Some(replace) => users
+            .iter()
+            .filter(|user| !excluded.contains(&user.id) && user.alternatives.contains(&replace))
+            .map(|user| user.id)
+            .collect(),
+        None => BTreeSet::new(),
+    };
+    let apply = {
+        let root = root.clone();
+        let db = db.clone();
+        let find = find.clone();
+        let replace = replace.clone();
+        let replaced = replaced.clone();
+        let chosen = chosen.clone();
+        Callback::from(move |()| {
+            let (Some(from), Some(to)) = (*find, *replace) else {
+                return;
+            };
+            let new_root = replace_selection(&root, &db, from, to, &chosen);
+            world_dispatcher.set_root(new_root);
+            replaced.set(Some(chosen.len()));
+            find.set(None);
+            replace.set(None);
+        })
+    };
+    let set_all = |include: bool| {
+        let excluded = excluded.clone();
+        let ids: BTreeSet<Uuid> = users.iter().map(|user| user.id).collect();
+        Callback::from(move |()| {
+            excluded.set(if include {
+                BTreeSet::new()
+            } else {
+                ids.clone()
+            });
+        })
+    };
+
+    let row = |user: &SelectionUser| {
+        let id = user.id;
+        let usable = replace.is_none_or(|replace| user.alternatives.contains(&replace));
+        let included = !excluded.contains(&id);
+        let toggle = {
+            let excluded = excluded.clone();
+            Callback::from(move |_: MouseEvent| {
+                let mut new_excluded = (*excluded).clone();
+                if !new_excluded.remove(&id) {
+                    new_excluded.insert(id);
+                }
+                excluded.set(new_excluded);
+            })
+        };
+        let path = user
+            .names
+            .iter()
+            .map(|name| {
+                if name.is_empty() {
+                    "Unnamed Group"
+                } else {
+                    name.as_str()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" \u{203A} ");
+        let (name, icon): (AttrValue, Option<AttrValue>) = match db.get(user.building) {
+            Some(building) => (
+                building.name.clone().into(),
+                Some(building.image.clone().into()),
+            ),
+            None => ("Unknown Building".into(), None),
+        };
+        let status = match *replace {
+            Some(replace) if !usable => format!("Can't use {}", name_of(replace)),
+            _ => String::new(),
+        };
+        html! {
+            <tr key={id.to_string()} class={classes!((!usable).then_some("unusable"))}>
+                <td>
+                    if usable {
+                        <MaterialCheckbox checked={included} onclick={toggle} />
+                    }
+                </td>
+                <td class="building"><Icon {icon} name={name.clone()} />{name}</td>
+                <td class="copies">{format!("\u{00d7}{}", user.copies)}</td>
+                <td class="path">{path}</td>
+                <td class="status">{status}</td>
+            </tr>
+        }
+    };
+
+    html! {
+        <OverlayWindow title="Find and Replace" class="FindReplaceWindow" on_close={close}>
+            <p>{"Choose a recipe, fuel, or resource used in this world to list every building \
+            using it. Then choose what to use instead and uncheck any buildings to leave alone. \
+            The replacement is applied as a single change, so it can be undone in one step."}</p>
+            if used.is_empty() {
+                <p class="empty">{"No buildings in this world have a recipe, fuel, or resource \
+                chosen."}</p>
+            }
+            if !recipes.is_empty() {
+                <div class="choices">
+                    <span>{"Recipes:"}</span>
+                    {for recipes.into_iter().map(find_choice)}
+                </div>
+            }
+            if !items.is_empty() {
+                <div class="choices">
+                    <span>{"Fuels and Resources:"}</span>
+                    {for items.into_iter().map(find_choice)}
+                </div>
+            }
+            if let Some(count) = *replaced {
+                <p class="replaced">{format!("Replaced in {count} buildings.")}</p>
+            }
+            if let Some(find) = find.filter(|_| !users.is_empty()) {
+                <div class="choices">
+                    <span>{format!("Replace {} with:", name_of(find))}</span>
+                    if alternatives.is_empty() {
+                        <span class="empty">{"Nothing else can be used in these buildings."}</span>
+                    }
+                    {for alternatives.into_iter().map(replace_choice)}
+                </div>
+                <div class="preview-controls">
+                    <span>{format!("{} buildings use {}", users.len(), name_of(find))}</span>
+                    <Button title="Check All" onclick={set_all(true)}>
+                        {material_icon("check_box")}
+                    </Button>
+                    <Button title="Uncheck All" onclick={set_all(false)}>
+                        {material_icon("check_box_outline_blank")}
+                    </Button>
+                    <Button class="green" title="Apply the replacement to the checked buildings"
+                        onclick={apply} disabled={chosen.is_empty()}>
+                        {material_icon("find_replace")}
+                        <span>{format!("Replace in {} buildings", chosen.len())}</span>
+                    </Button>
+                </div>
+                <div class="preview-scroll">
+                    <table class="preview">
+                        {for users.iter().map(row)}
+                    </table>
+                </div>
+            }
+        </OverlayWindow>
+    }
+}
@@ -0,0 +1,124 @@
+//! Short help shown next to widgets which aren't self-explanatory.
+//!
+//! The text of every topic lives in [`HelpTopic::text`] rather than in the components using it,
+//! so help can be added or reworded in one place.
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::markdown::render_markdown;
+use crate::material::material_icon;
+
+/// Widget which help can be shown for.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HelpTopic {
+    /// Purity of the resource node a miner or geothermal generator is placed on.
+    Purity,
+    /// Counts of resource well pads by purity for a resource well pressurizer.
+    MultiPurity,
+    /// Counts of geysers by purity for a cluster of geothermal generators.
+    GeyserCluster,
+    /// Typing or stepping the rate of a balance entry to change the building.
+    BalanceAdjustment,
+    /// How backdriving picks a clock speed and number of buildings.
+    Backdrive,
+}
+
+impl HelpTopic {
+    /// Title shown at the top of the help.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Purity => "Node Purity",
+            Self::MultiPurity => "Resource Well Pads",
+            Self::GeyserCluster => "Geyser Clusters",
+            Self::BalanceAdjustment => "Adjusting Balances",
+            Self::Backdrive => "Backdriving",
+        }
+    }
+
+    /// Body of the help, in the markdown subset supported for notes.
+    pub fn text(self) -> &'static str {
+        match self {
+            Self::Purity => {
+                "How rich the resource node under the building is. Each step of purity doubles \
+                the output at the same clock speed:\n\
+                \n\
+                - **Impure** nodes produce half as much as normal nodes.\n\
+                - **Normal** nodes produce the base rate.\n\
+                - **Pure** nodes produce twice as much as normal nodes.\n\
+                \n\
+                Click the purity to change it."
+            }
+            Self::MultiPurity => {
+                "A resource well pressurizer draws from every extractor pad placed around it. \
+                Enter how many impure, normal, and pure pads the well has. Each pad adds its own \
+                output, so the total is the sum over all pads, multiplied by the clock speed of \
+                the pressurizer."
+            }
+            Self::GeyserCluster => {
+                "A geyser cluster stands for several geothermal generators in one row. Enter how \
+                many impure, normal, and pure geysers the cluster has. Each geyser has its own \
+                generator, so the power is the sum over all geysers, and the build cost counts a \
+                generator for each one. The multiplier copies the whole cluster.\n\
+                \n\
+                The average output per generator is shown next to the counts."
+            }
+            Self::BalanceAdjustment => {
+                "Click an output or input of a building and type the rate you want. The building's \
+                clock speed and multiplier are changed to match, according to the backdriving \
+                settings. The target button next to the building's settings does the same: choose \
+                an item and type its rate.\n\
+                \n\
+                While editing, use the arrow keys for small steps and Page Up and Page Down for \
+                larger ones. Hold Shift for finer steps. The step sizes can be changed in the key \
+                binding settings."
+            }
+            Self::Backdrive => {
+                "Backdriving works out the buildings needed for a rate you type into a balance.\n\
+                \n\
+                - **Mixed clock speed** keeps the clock speed and only changes the multiplier, so \
+                at most one building runs at a different speed.\n\
+                - **Uniform clock speed** uses a whole number of buildings and changes the clock \
+                speed of all of them, never going over the maximum clock speed.\n\
+                \n\
+                After backdriving a building inside a group, the buildings feeding it can be \
+                rescaled too."
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Topic to show help for.
+    pub topic: HelpTopic,
+}
+
+/// Small question mark button which opens a popover with help about a widget.
+#[function_component]
+pub fn HelpButton(&Props { topic }: &Props) -> Html {
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+    // Help is often placed inside widgets which react to clicks themselves, which shouldn't also
+    // see clicks on the help.
+    let stop = Callback::from(|e: MouseEvent| e.stop_propagation());
+
+    html! {
+        <span class="HelpButton" onclick={stop}>
+            <Button class="help-toggle" title={format!("Help: {}", topic.title())}
+                onclick={toggle.clone()}>
+                {material_icon("help_outline")}
+            </Button>
+            if *open {
+                <div class="help-popover">
+                    <div class="help-title">
+                        <span>{topic.title()}</span>
+                        <Button title="Close" onclick={toggle}>
+                            {material_icon("close")}
+                        </Button>
+                    </div>
+                    {render_markdown(topic.text())}
+                </div>
+            }
+        </span>
+    }
+}
@@ -117,6 +117,15 @@ pub struct UploadProps {
     #[prop_or_default]
     pub title: Option<AttrValue>,
 
+    /// File types the button accepts.
+    #[prop_or(AttrValue::Static("application/json"))]
+    pub accept: AttrValue,
+
+    /// Whether several files can be uploaded at once. Each file is passed to `onupload`
+    /// separately.
+    #[prop_or_default]
+    pub multiple: bool,
+
     /// Handler that receives the uploaded bytes.
     #[prop_or_default]
     pub onupload: Callback<UploadedFile>,
@@ -129,57 +138,66 @@ pub fn UploadButton(
         children,
         class,
         title,
+        accept,
+        multiple,
         onupload,
     }: &UploadProps,
 ) -> Html {
     let class = classes!("Button", class.clone());
 
-    let onchange = use_callback(onupload.clone(), |e: Event, onupload| {
-        let input = match e.target_dyn_into::<HtmlInputElement>() {
-            Some(input) => input,
-            None => {
-                error!(
-                    "Cannot handle file upload: Event target does not appear to be an \
-                    HTMLInputElement"
-                );
-                return;
-            }
-        };
-        let files = match input.files() {
-            Some(files) => files,
-            None => {
-                warn!("HTMLInputElement did not have a 'files'");
-                return;
-            }
-        };
-        if files.length() > 1 {
-            warn!("Received more than one input file. Taking only the first file.");
-        }
-        let file = match files.item(0) {
-            Some(file) => file,
-            None => {
+    let onchange = use_callback(
+        (onupload.clone(), *multiple),
+        |e: Event, (onupload, multiple)| {
+            let input = match e.target_dyn_into::<HtmlInputElement>() {
+                Some(input) => input,
+                None => {
+                    error!(
+                        "Cannot handle file upload: Event target does not appear to be an \
+                        HTMLInputElement"
+                    );
+                    return;
+                }
+            };
+            let files = match input.files() {
+                Some(files) => files,
+                None => {
+                    warn!("HTMLInputElement did not have a 'files'");
+                    return;
+                }
+            };
+            if files.length() == 0 {
                 info!("No input files, doing nothing.");
                 return;
             }
-        };
-        let name = file.name();
-        let onupload = onupload.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let blob = Blob::from(file);
-            let data = match gloo::file::futures::read_as_bytes(&blob).await {
-                Ok(data) => data,
-                Err(e) => {
-                    warn!("Unable to read file contents: {e}");
-                    return;
+            let count = if *multiple {
+                files.length()
+            } else {
+                if files.length() > 1 {
+                    warn!("Received more than one input file. Taking only the first file.");
                 }
+                1
             };
-            onupload.emit(UploadedFile { name, data });
-        })
-    });
+            for file in (0..count).filter_map(|i| files.item(i)) {
+                let name = file.name();
+                let onupload = onupload.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let blob = Blob::from(file);
+                    let data = match gloo::file::futures::read_as_bytes(&blob).await {
+                        Ok(data) => data,
+                        Err(e) => {
+                            warn!("Unable to read file contents: {e}");
+                            return;
+                        }
+                    };
+                    onupload.emit(UploadedFile { name, data });
+                })
+            }
+        },
+    );
 
     html! {
         <label class="file-button-wrapper" {title}>
-            <input type="file" accept="application/json" {onchange} />
+            <input type="file" accept={accept.clone()} multiple={*multiple} {onchange} />
             <div {class}>
                 {children.clone()}
             </div>
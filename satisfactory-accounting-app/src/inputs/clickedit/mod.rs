@@ -1,4 +1,5 @@
 use log::warn;
+use serde::{Deserialize, Serialize};
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
@@ -48,8 +49,46 @@ pub struct ValueAdjustment {
     pub dir: AdjustDir,
     /// What scale of adjustment to make.
     pub scale: AdjustScale,
-    /// Modifier to apply to the scale.
-    pub modifier: AdjustModifier,
+    /// Amount to adjust by for the scale and any modifier held, from the `steps` of the
+    /// ClickEdit.
+    pub step: f32,
+}
+
+/// Amount to adjust a value by for each scale and modifier of adjustment.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdjustSteps {
+    /// Step for a fine adjustment (arrow keys).
+    pub fine: f32,
+    /// Step for a coarse adjustment (pg up/down keys).
+    pub coarse: f32,
+    /// Step for a fine adjustment with the smaller modifier held.
+    pub fine_smaller: f32,
+    /// Step for a coarse adjustment with the smaller modifier held.
+    pub coarse_smaller: f32,
+}
+
+impl AdjustSteps {
+    /// Get the step to use for the given scale and modifier.
+    pub fn get(&self, scale: AdjustScale, modifier: AdjustModifier) -> f32 {
+        match (scale, modifier) {
+            (AdjustScale::Fine, AdjustModifier::None) => self.fine,
+            (AdjustScale::Coarse, AdjustModifier::None) => self.coarse,
+            (AdjustScale::Fine, AdjustModifier::Smaller) => self.fine_smaller,
+            (AdjustScale::Coarse, AdjustModifier::Smaller) => self.coarse_smaller,
+        }
+    }
+}
+
+impl Default for AdjustSteps {
+    /// Steps of 1 and 5 with no modifier, or 0.01 and 0.1 with the smaller modifier.
+    fn default() -> Self {
+        Self {
+            fine: 1.0,
+            coarse: 5.0,
+            fine_smaller: 0.01,
+            coarse_smaller: 0.1,
+        }
+    }
 }
 
 #[derive(Debug, Properties, PartialEq)]
@@ -77,6 +116,12 @@ pub struct Props {
     /// editable value.
     #[prop_or_default]
     pub adjust: Option<fn(ValueAdjustment, AttrValue) -> AttrValue>,
+    /// Steps passed to `adjust` for each scale and modifier of adjustment.
+    #[prop_or_default]
+    pub steps: AdjustSteps,
+    /// If true, the value is only displayed and clicking it doesn't start editing.
+    #[prop_or_default]
+    pub read_only: bool,
 }
 
 pub enum Msg {
@@ -88,8 +133,12 @@ pub enum Msg {
     FinishEdit,
     /// Cancel editing without changing the value.
     Cancel,
-    /// Adjust the value by a given amount.
-    Adjust { adjustment: ValueAdjustment },
+    /// Adjust the value in the given direction by the step for the given scale and modifier.
+    Adjust {
+        dir: AdjustDir,
+        scale: AdjustScale,
+        modifier: AdjustModifier,
+    },
 }
 
 /// Helper to display some text with click-to-edit.
@@ -138,32 +187,24 @@ impl Component for ClickEdit {
             onkeyup: link.batch_callback(|e: KeyboardEvent| match &*e.key() {
                 "Esc" | "Escape" => Some(Msg::Cancel),
                 "Up" | "ArrowUp" => Some(Msg::Adjust {
-                    adjustment: ValueAdjustment {
-                        dir: AdjustDir::Up,
-                        scale: AdjustScale::Fine,
-                        modifier: AdjustModifier::interpret(&e),
-                    },
+                    dir: AdjustDir::Up,
+                    scale: AdjustScale::Fine,
+                    modifier: AdjustModifier::interpret(&e),
                 }),
                 "Down" | "ArrowDown" => Some(Msg::Adjust {
-                    adjustment: ValueAdjustment {
-                        dir: AdjustDir::Down,
-                        scale: AdjustScale::Fine,
-                        modifier: AdjustModifier::interpret(&e),
-                    },
+                    dir: AdjustDir::Down,
+                    scale: AdjustScale::Fine,
+                    modifier: AdjustModifier::interpret(&e),
                 }),
                 "PageUp" => Some(Msg::Adjust {
-                    adjustment: ValueAdjustment {
-                        dir: AdjustDir::Up,
-                        scale: AdjustScale::Coarse,
-                        modifier: AdjustModifier::interpret(&e),
-                    },
+                    dir: AdjustDir::Up,
+                    scale: AdjustScale::Coarse,
+                    modifier: AdjustModifier::interpret(&e),
                 }),
                 "PageDown" => Some(Msg::Adjust {
-                    adjustment: ValueAdjustment {
-                        dir: AdjustDir::Down,
-                        scale: AdjustScale::Coarse,
-                        modifier: AdjustModifier::interpret(&e),
-                    },
+                    dir: AdjustDir::Down,
+                    scale: AdjustScale::Coarse,
+                    modifier: AdjustModifier::interpret(&e),
                 }),
                 _ => None,
             }),
@@ -188,6 +229,7 @@ impl Component for ClickEdit {
             || new_props.title != old_props.title
             || new_props.prefix != old_props.prefix
             || new_props.suffix != old_props.suffix
+            || new_props.read_only != old_props.read_only
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -220,7 +262,16 @@ impl Component for ClickEdit {
                 self.edit_text = None;
                 true
             }
-            Msg::Adjust { adjustment } => {
+            Msg::Adjust {
+                dir,
+                scale,
+                modifier,
+            } => {
+                let adjustment = ValueAdjustment {
+                    dir,
+                    scale,
+                    step: ctx.props().steps.get(scale, modifier),
+                };
                 match (ctx.props().adjust.as_ref(), self.edit_text.take()) {
                     (Some(adjuster), Some(value)) => {
                         self.edit_text = Some(adjuster(adjustment, value));
@@ -271,7 +322,7 @@ impl Component for ClickEdit {
                 </form>
             }
         } else {
-            let onclick = self.onclick.clone();
+            let onclick = (!ctx.props().read_only).then(|| self.onclick.clone());
             let value = rounded_value.as_ref().unwrap_or(value);
             html! {
                 <div {class} {title} {onclick}>
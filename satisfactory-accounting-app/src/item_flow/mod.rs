@@ -0,0 +1,465 @@
+//! Window drawing the item flows of a group as a Sankey diagram.
+
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::analysis::{item_flows, ChildFlow};
+use satisfactory_accounting::database::{Database, ItemId};
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::node_name;
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_world_root};
+
+/// Width of the diagram, in SVG units.
+const WIDTH: f64 = 960.0;
+/// Width of the bar drawn for each producer, item, or consumer.
+const BAR_WIDTH: f64 = 14.0;
+/// Space reserved on either side of the diagram for producer and consumer labels.
+const LABEL_WIDTH: f64 = 220.0;
+/// Vertical space between bars in the same column.
+const BAR_GAP: f64 = 10.0;
+/// Vertical space allowed per bar in the tallest column.
+const ROW_HEIGHT: f64 = 32.0;
+/// Smallest height of the diagram.
+const MIN_HEIGHT: f64 = 320.0;
+
+/// Actions for the item flow window.
+enum Action {
+    /// Hide the window.
+    Hide,
+    /// Show the window for the group with the given id.
+    Show(Uuid),
+}
+
+/// State of the item flow window.
+#[derive(Default, PartialEq, Copy, Clone)]
+struct ItemFlowState {
+    /// Whether the window is shown.
+    show_window: bool,
+    /// Group whose flows are drawn.
+    group: Option<Uuid>,
+}
+
+impl Reducible for ItemFlowState {
+    type Action = Action;
+
+    fn reduce(mut self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let state = Rc::make_mut(&mut self);
+        match action {
+            Action::Hide => state.show_window = false,
+            Action::Show(group) => {
+                state.show_window = true;
+                state.group = Some(group);
+            }
+        }
+        self
+    }
+}
+
+/// Dispatcher for showing the item flow window.
+#[derive(PartialEq, Clone)]
+pub struct ItemFlowWindowDispatcher {
+    reducer: UseReducerDispatcher<ItemFlowState>,
+}
+
+impl ItemFlowWindowDispatcher {
+    /// Hides the window.
+    pub fn hide_window(&self) {
+        self.reducer.dispatch(Action::Hide);
+    }
+
+    /// Shows the window drawing the flows of the group with the given id.
+    pub fn show_group(&self, group: Uuid) {
+        self.reducer.dispatch(Action::Show(group));
+    }
+}
+
+/// Gets access to the dispatcher which controls showing the item flow window.
+#[hook]
+pub fn use_item_flow_window() -> ItemFlowWindowDispatcher {
+    use_context::<ItemFlowWindowDispatcher>().expect(
+        "use_item_flow_window can only be used from within a child of ItemFlowWindowManager",
+    )
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ManagerProps {
+    /// Children which can show the window.
+    pub children: Html,
+}
+
+/// Provides the context to show the item flow window and renders the window when shown.
+#[function_component]
+pub fn ItemFlowWindowManager(ManagerProps { children }: &ManagerProps) -> Html {
+    let state = use_reducer_eq(ItemFlowState::default);
+    let dispatcher = ItemFlowWindowDispatcher {
+        reducer: state.dispatcher(),
+    };
+
+    html! {
+        <ContextProvider<ItemFlowWindowDispatcher> context={dispatcher}>
+            { children.clone() }
+            if let Some(group) = state.group.filter(|_| state.show_window) {
+                <ItemFlowWindow {group} />
+            }
+        </ContextProvider<ItemFlowWindowDispatcher>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ButtonProps {
+    /// Id of the group to show the flows of.
+    pub id: Uuid,
+}
+
+/// Button which opens the item flow diagram of a group.
+#[function_component]
+pub fn ItemFlowButton(&ButtonProps { id }: &ButtonProps) -> Html {
+    let window_dispatcher = use_item_flow_window();
+    let onclick = use_callback(window_dispatcher, move |_, window_dispatcher| {
+        window_dispatcher.show_group(id);
+    });
+    html! {
+        <Button {onclick} title="Show Item Flow Diagram">
+            {material_icon("account_tree")}
+        </Button>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct WindowProps {
+    /// Id of the group to draw.
+    group: Uuid,
+}
+
+/// Draws the flows of items through a group.
+#[function_component]
+fn ItemFlowWindow(&WindowProps { group }: &WindowProps) -> Html {
+    let window_dispatcher = use_item_flow_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let root = use_world_root();
+    let node = root.iter().find(|node| node.id() == group);
+    let title = match node.as_ref().and_then(|node| node.group()) {
+        Some(group) if !group.name.is_empty() => format!("Item Flows: {}", group.name),
+        Some(_) if node.as_ref().is_some_and(|node| node.id() == root.id()) => {
+            "Item Flows: World".to_owned()
+        }
+        _ => "Item Flows".to_owned(),
+    };
+
+    html! {
+        <OverlayWindow {title} class="ItemFlowWindow" on_close={close}>
+            if let Some(node) = node {
+                <SankeyDiagram {node} />
+            } else {
+                <p class="no-flows">{"This group no longer exists."}</p>
+            }
+        </OverlayWindow>
+    }
+}
+
+/// Bar for one producer, item, or consumer in the diagram.
+struct Bar {
+    /// Text shown next to the bar.
+    label: String,
+    /// Total rate flowing through the bar.
+    value: f32,
+    /// Position of the top of the bar.
+    top: f64,
+    /// Height of the bar.
+    height: f64,
+}
+
+/// Band connecting a bar in one column to a bar in the next.
+struct Band {
+    /// Index of the bar the band starts from.
+    from: usize,
+    /// Index of the bar the band ends at.
+    to: usize,
+    /// Item carried by the band.
+    item: ItemId,
+    /// Rate of the item.
+    rate: f32,
+}
+
+#[derive(Properties, PartialEq)]
+struct DiagramProps {
+    /// Group to draw.
+    node: Node,
+}
+
+/// Sankey diagram of a group's items, flowing from the children which produce them (and imports)
+/// through each item to the children which consume them (and exports).
+#[function_component]
+fn SankeyDiagram(DiagramProps { node }: &DiagramProps) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+
+    let flows = item_flows(node);
+    let children: Vec<Node> = node.children().collect();
+
+    // Items, largest flow first.
+    let mut throughput: BTreeMap<ItemId, f32> = flows.imports.clone();
+    for flow in &flows.produced {
+        *throughput.entry(flow.item).or_default() += flow.rate;
+    }
+    let mut items: Vec<(ItemId, f32)> = throughput.into_iter().collect();
+    items.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    if items.is_empty() {
+        return html! {
+            <p class="no-flows">{"Nothing in this group produces or consumes items."}</p>
+        };
+    }
+    let item_index: HashMap<ItemId, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(idx, &(item, _))| (item, idx))
+        .collect();
+
+    let item_name = |item: ItemId| match db.get(item) {
+        Some(item) => item.name.to_string(),
+        None => "Unknown Item".to_owned(),
+    };
+    let mut item_bars: Vec<Bar> = items
+        .iter()
+        .map(|&(item, value)| Bar::new(item_name(item), value))
+        .collect();
+    let (mut source_bars, source_bands) = side_bars(
+        &db,
+        &children,
+        &flows.produced,
+        &flows.imports,
+        "Imports",
+        &item_index,
+    );
+    let (mut sink_bars, sink_bands) = side_bars(
+        &db,
+        &children,
+        &flows.consumed,
+        &flows.exports,
+        "Exports",
+        &item_index,
+    );
+
+    let tallest = source_bars.len().max(item_bars.len()).max(sink_bars.len());
+    let height = (tallest as f64 * ROW_HEIGHT).max(MIN_HEIGHT);
+    let total: f32 = items.iter().map(|(_, value)| value).sum();
+    let scale = (height - BAR_GAP * (tallest - 1) as f64) / total as f64;
+    for bars in [&mut source_bars, &mut item_bars, &mut sink_bars] {
+        stack(bars, scale, height);
+    }
+
+    let source_x = LABEL_WIDTH;
+    let item_x = (WIDTH - BAR_WIDTH) / 2.0;
+    let sink_x = WIDTH - LABEL_WIDTH - BAR_WIDTH;
+
+    // Sink bands run from items to sinks, so flip them to keep items as the source.
+    let sink_bands: Vec<Band> = sink_bands
+        .into_iter()
+        .map(|band| Band {
+            from: band.to,
+            to: band.from,
+            ..band
+        })
+        .collect();
+    let paths = band_paths(&source_bars, &item_bars, &source_bands, scale)
+        .into_iter()
+        .zip(&source_bands)
+        .map(|((y0, y1, thickness), band)| (source_x + BAR_WIDTH, y0, item_x, y1, thickness, band))
+        .chain(
+            band_paths(&item_bars, &sink_bars, &sink_bands, scale)
+                .into_iter()
+                .zip(&sink_bands)
+                .map(|((y0, y1, thickness), band)| {
+                    (item_x + BAR_WIDTH, y0, sink_x, y1, thickness, band)
+                }),
+        )
+        .map(|(x0, y0, x1, y1, thickness, band)| {
+            let color = item_color(item_index[&band.item]);
+            let title = format!(
+                "{}: {}",
+                item_name(band.item),
+                balance_settings.format_rate(band.rate)
+            );
+            html! {
+                <path class="flow-band" d={band_path(x0, y0, x1, y1, thickness)}
+                    style={format!("fill: {color}")}>
+                    <title>{title}</title>
+                </path>
+            }
+        })
+        .collect::<Html>();
+
+    let bar_rects = |bars: &[Bar], x: f64, color: &dyn Fn(usize) -> String| {
+        bars.iter()
+            .enumerate()
+            .map(|(idx, bar)| {
+                let title = format!("{}: {}", bar.label, balance_settings.format_rate(bar.value));
+                html! {
+                    <rect class="flow-bar" x={x.to_string()} y={bar.top.to_string()}
+                        width={BAR_WIDTH.to_string()} height={bar.height.to_string()}
+                        style={format!("fill: {}", color(idx))}>
+                        <title>{title}</title>
+                    </rect>
+                }
+            })
+            .collect::<Html>()
+    };
+    let neutral = |_| "var(--flow-bar-color)".to_owned();
+    let labels = |bars: &[Bar], x: f64, anchor: &'static str, with_value: bool| {
+        bars.iter()
+            .map(|bar| {
+                let y = bar.top + bar.height / 2.0;
+                let text = if with_value {
+                    format!("{} {}", bar.label, balance_settings.format_rate(bar.value))
+                } else {
+                    bar.label.clone()
+                };
+                html! {
+                    <text class="flow-label" x={x.to_string()} y={y.to_string()}
+                        text-anchor={anchor} dominant-baseline="middle">
+                        {text}
+                    </text>
+                }
+            })
+            .collect::<Html>()
+    };
+
+    html! {
+        <div class="SankeyDiagram">
+            <svg viewBox={format!("0 0 {WIDTH} {height}")} width={WIDTH.to_string()}
+                height={height.to_string()}>
+                {paths}
+                {bar_rects(&source_bars, source_x, &neutral)}
+                {bar_rects(&item_bars, item_x, &item_color)}
+                {bar_rects(&sink_bars, sink_x, &neutral)}
+                {labels(&source_bars, source_x - 6.0, "end", false)}
+                {labels(&item_bars, item_x + BAR_WIDTH + 6.0, "start", true)}
+                {labels(&sink_bars, sink_x + BAR_WIDTH + 6.0, "start", false)}
+            </svg>
+        </div>
+    }
+}
+
+impl Bar {
+    /// Create a bar which hasn't been positioned yet.
+    fn new(label: String, value: f32) -> Self {
+        Self {
+            label,
+            value,
+            top: 0.0,
+            height: 0.0,
+        }
+    }
+}
+
+/// Build the bars and bands for the producer or consumer side of the diagram. The boundary bar,
+/// for imports or exports, comes first if the group has any.
+fn side_bars(
+    db: &Database,
+    children: &[Node],
+    flows: &[ChildFlow],
+    boundary: &BTreeMap<ItemId, f32>,
+    boundary_label: &str,
+    item_index: &HashMap<ItemId, usize>,
+) -> (Vec<Bar>, Vec<Band>) {
+    let mut bars = Vec::new();
+    let mut bands = Vec::new();
+    if !boundary.is_empty() {
+        bars.push(Bar::new(boundary_label.to_owned(), boundary.values().sum()));
+        bands.extend(boundary.iter().map(|(&item, &rate)| Band {
+            from: 0,
+            to: item_index[&item],
+            item,
+            rate,
+        }));
+    }
+    let mut child_bars: BTreeMap<usize, usize> = BTreeMap::new();
+    for flow in flows {
+        let bar = *child_bars.entry(flow.child).or_insert_with(|| {
+            bars.push(Bar::new(node_name(db, &children[flow.child]), 0.0));
+            bars.len() - 1
+        });
+        bars[bar].value += flow.rate;
+        bands.push(Band {
+            from: bar,
+            to: item_index[&flow.item],
+            item: flow.item,
+            rate: flow.rate,
+        });
+    }
+    (bars, bands)
+}
+
+/// Position the bars of a column from top to bottom, centering the column vertically.
+fn stack(bars: &mut [Bar], scale: f64, height: f64) {
+    let used: f64 = bars
+        .iter()
+        .map(|bar| bar.value as f64 * scale + BAR_GAP)
+        .sum::<f64>()
+        - BAR_GAP;
+    let mut top = ((height - used) / 2.0).max(0.0);
+    for bar in bars {
+        bar.top = top;
+        bar.height = bar.value as f64 * scale;
+        top += bar.height + BAR_GAP;
+    }
+}
+
+/// Find where each band leaves its source bar and joins its destination bar, along with its
+/// thickness. Bands are stacked within each bar in the order of the bars at their other end, so
+/// they cross as little as possible.
+fn band_paths(from: &[Bar], to: &[Bar], bands: &[Band], scale: f64) -> Vec<(f64, f64, f64)> {
+    let mut starts = vec![0.0; bands.len()];
+    let mut ends = vec![0.0; bands.len()];
+    let mut order: Vec<usize> = (0..bands.len()).collect();
+
+    order.sort_by_key(|&idx| (bands[idx].from, bands[idx].to));
+    let mut offsets = vec![0.0; from.len()];
+    for &idx in &order {
+        let band = &bands[idx];
+        starts[idx] = from[band.from].top + offsets[band.from];
+        offsets[band.from] += band.rate as f64 * scale;
+    }
+
+    order.sort_by_key(|&idx| (bands[idx].to, bands[idx].from));
+    let mut offsets = vec![0.0; to.len()];
+    for &idx in &order {
+        let band = &bands[idx];
+        ends[idx] = to[band.to].top + offsets[band.to];
+        offsets[band.to] += band.rate as f64 * scale;
+    }
+
+    bands
+        .iter()
+        .enumerate()
+        .map(|(idx, band)| (starts[idx], ends[idx], band.rate as f64 * scale))
+        .collect()
+}
+
+/// SVG path of a band with the given thickness, curving from (x0, y0) to (x1, y1).
+fn band_path(x0: f64, y0: f64, x1: f64, y1: f64, thickness: f64) -> String {
+    let mid = (x0 + x1) / 2.0;
+    format!(
+        "M {x0} {y0} C {mid} {y0}, {mid} {y1}, {x1} {y1} \
+        L {x1} {y1b} C {mid} {y1b}, {mid} {y0b}, {x0} {y0b} Z",
+        y0b = y0 + thickness,
+        y1b = y1 + thickness,
+    )
+}
+
+/// Color used for the item at the given position, spreading hues around the color wheel.
+fn item_color(idx: usize) -> String {
+    let hue = (idx as f64 * 137.5) % 360.0;
+    format!("hsl({hue:.0}, 60%, 55%)")
+}
@@ -0,0 +1,385 @@
+//! Window listing every building which produces or consumes an item.
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+
+use gloo::file::ObjectUrl;
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::analysis::{top_producers_and_consumers, Contributor};
+use satisfactory_accounting::csv::contributors_csv;
+use satisfactory_accounting::database::{BuildingId, Database, ItemId, ItemIdOrPower};
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::download::download_text;
+use crate::inputs::button::Button;
+use crate::inputs::choose_from_list::{Choice, ChooseFromList};
+use crate::material::material_icon;
+use crate::node_display::{reveal_contributors, Icon};
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_world_dispatcher, use_world_root, NodeMetas};
+
+/// Choices for how many producers and consumers to list. None lists all of them.
+const LIMITS: [Option<usize>; 4] = [Some(5), Some(10), Some(25), None];
+
+/// Actions for the item usage window.
+enum Action {
+    /// Hide the window.
+    Hide,
+    /// Toggle the window, keeping the last selected item.
+    Toggle,
+    /// Show the window for the given item.
+    Show(ItemId),
+}
+
+/// State of the item usage window.
+#[derive(Default, PartialEq, Copy, Clone)]
+struct ItemUsageState {
+    /// Whether the window is shown.
+    show_window: bool,
+    /// Item whose usage is listed.
+    item: Option<ItemId>,
+}
+
+impl Reducible for ItemUsageState {
+    type Action = Action;
+
+    fn reduce(mut self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let state = Rc::make_mut(&mut self);
+        match action {
+            Action::Hide => state.show_window = false,
+            Action::Toggle => state.show_window = !state.show_window,
+            Action::Show(item) => {
+                state.show_window = true;
+                state.item = Some(item);
+            }
+        }
+        self
+    }
+}
+
+/// Dispatcher for showing the item usage window.
+#[derive(PartialEq, Clone)]
+pub struct ItemUsageWindowDispatcher {
+    reducer: UseReducerDispatcher<ItemUsageState>,
+}
+
+impl ItemUsageWindowDispatcher {
+    /// Toggles the window.
+    pub fn toggle_window(&self) {
+        self.reducer.dispatch(Action::Toggle);
+    }
+
+    /// Hides the window.
+    pub fn hide_window(&self) {
+        self.reducer.dispatch(Action::Hide);
+    }
+
+    /// Shows the window listing the usage of the given item.
+    pub fn show_item(&self, item: ItemId) {
+        self.reducer.dispatch(Action::Show(item));
+    }
+}
+
+/// Gets access to the dispatcher which controls showing the item usage window.
+#[hook]
+pub fn use_item_usage_window() -> ItemUsageWindowDispatcher {
+    use_context::<ItemUsageWindowDispatcher>().expect(
+        "use_item_usage_window can only be used from within a child of ItemUsageWindowManager",
+    )
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ManagerProps {
+    /// Children which can show the window.
+    pub children: Html,
+}
+
+/// Provides the context to show the item usage window and renders the window when shown.
+#[function_component]
+pub fn ItemUsageWindowManager(ManagerProps { children }: &ManagerProps) -> Html {
+    let state = use_reducer_eq(ItemUsageState::default);
+    let dispatcher = ItemUsageWindowDispatcher {
+        reducer: state.dispatcher(),
+    };
+
+    html! {
+        <ContextProvider<ItemUsageWindowDispatcher> context={dispatcher}>
+            { children.clone() }
+            if state.show_window {
+                <ItemUsageWindow item={state.item} />
+            }
+        </ContextProvider<ItemUsageWindowDispatcher>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct WindowProps {
+    /// Item to list the usage of, if one has been chosen.
+    item: Option<ItemId>,
+}
+
+/// Lists every building in the world which produces or consumes an item.
+#[function_component]
+fn ItemUsageWindow(&WindowProps { item }: &WindowProps) -> Html {
+    let window_dispatcher = use_item_usage_window();
+    let close = use_callback(window_dispatcher.clone(), |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+    let on_selected = use_callback(window_dispatcher, |item, window_dispatcher| {
+        window_dispatcher.show_item(item);
+    });
+
+    let db = use_db();
+    let root = use_world_root();
+
+    // Only offer items which some building actually uses.
+    let items: BTreeSet<ItemId> = root
+        .iter()
+        .filter(|node| node.building().is_some())
+        .flat_map(|node| node.balance().balances.keys().copied().collect::<Vec<_>>())
+        .collect();
+    let choices: Vec<_> = items.into_iter().map(|id| item_choice(&db, id)).collect();
+
+    let title = match item.and_then(|id| db.get(id)) {
+        Some(item) => format!("Where is {} used?", item.name),
+        None => "Where is an item used?".to_owned(),
+    };
+
+    html! {
+        <OverlayWindow {title} class="ItemUsageWindow" on_close={close}>
+            <div class="item-chooser">
+                <span>{"Item:"}</span>
+                <ChooseFromList<ItemId> title="Choose Item" {choices} {on_selected}
+                    on_cancelled={Callback::noop()} />
+            </div>
+            if let Some(item) = item {
+                <ItemUsageList {item} />
+            }
+        </OverlayWindow>
+    }
+}
+
+/// Build the choice for an item.
+fn item_choice(db: &Database, id: ItemId) -> Choice<ItemId> {
+    match db.get(id) {
+        Some(item) => Choice {
+            id,
+            name: item.name.clone().into(),
+            image: html! { <Icon icon={item.image.clone()} name={item.name.clone()} /> },
+        },
+        None => Choice {
+            id,
+            name: format!("Unknown Item {id}").into(),
+            image: html! { <Icon /> },
+        },
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ListProps {
+    /// Item to list the usage of.
+    item: ItemId,
+}
+
+/// Producers and consumers of an item, largest first, with totals.
+#[function_component]
+fn ItemUsageList(&ListProps { item }: &ListProps) -> Html {
+    let db = use_db();
+    let root = use_world_root();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let limit = use_state_eq(|| None::<usize>);
+    // Keeps the download url alive until the list is disposed.
+    let download_url_retainer: Rc<RefCell<Option<ObjectUrl>>> = use_mut_ref(|| None);
+
+    let export = use_callback(
+        (root.clone(), db.clone(), item, *limit),
+        move |_, (root, db, item, limit)| {
+            let csv = contributors_csv(
+                root,
+                db,
+                ItemIdOrPower::Item(*item),
+                limit.unwrap_or(usize::MAX),
+            );
+            let name = match db.get(*item) {
+                Some(item) => item.name.to_string(),
+                None => item.to_string(),
+            };
+            let filename = format!("{name} Producers and Consumers.csv");
+            if let Some(url) = download_text(&csv, "text/csv", &filename) {
+                *download_url_retainer.borrow_mut() = Some(url);
+            }
+        },
+    );
+
+    let nodes: HashMap<Uuid, Node> = root.iter().map(|node| (node.id(), node)).collect();
+    let top = top_producers_and_consumers(&root, ItemIdOrPower::Item(item), usize::MAX);
+    // Totals include every building, even ones past the limit.
+    let produced: f32 = top.producers.iter().map(|c| c.rate).sum();
+    let consumed: f32 = top.consumers.iter().map(|c| c.rate).sum();
+    let net = produced + consumed;
+    let (mut producers, mut consumers) = (top.producers, top.consumers);
+    if let Some(limit) = *limit {
+        producers.truncate(limit);
+        consumers.truncate(limit);
+    }
+
+    let limit_choice = |choice: Option<usize>| {
+        let onclick = {
+            let limit = limit.clone();
+            Callback::from(move |()| limit.set(choice))
+        };
+        let (label, title) = match choice {
+            Some(n) => (n.to_string(), format!("Show the Top {n}")),
+            None => ("All".to_owned(), "Show All".to_owned()),
+        };
+        html! {
+            <Button class={classes!((*limit == choice).then_some("selected"))} {title} {onclick}>
+                {label}
+            </Button>
+        }
+    };
+
+    let section = |heading: &str, contributors: Vec<Contributor>| {
+        html! {
+            <div class="usage-section">
+                <h2>{heading}</h2>
+                if contributors.is_empty() {
+                    <p class="empty-usage">{"None"}</p>
+                } else {
+                    <ul>
+                        {for contributors.into_iter().enumerate().map(|(i, contributor)| {
+                            let key = contributor.id.to_string();
+                            let rank = i + 1;
+                            let (building, path) = describe(&nodes, &contributor);
+                            html! {
+                                <ItemUsageRow {key} {rank} {contributor} {building} {path} />
+                            }
+                        })}
+                    </ul>
+                }
+            </div>
+        }
+    };
+
+    html! {
+        <div class="ItemUsageList">
+            <div class="usage-totals">
+                <span class="positive">{"Produced: "}{balance_settings.format_rate(produced).to_string()}</span>
+                <span class="negative">{"Consumed: "}{balance_settings.format_rate(consumed).to_string()}</span>
+                <span class={if net < 0.0 { "negative" } else { "positive" }}>
+                    {"Net: "}{balance_settings.format_rate(net).to_string()}
+                </span>
+            </div>
+            <div class="usage-limit">
+                <span>{"Show top:"}</span>
+                {for LIMITS.into_iter().map(limit_choice)}
+                <Button title="Export as CSV" onclick={export}>
+                    {material_icon("table_view")}
+                </Button>
+            </div>
+            {section("Producers", producers)}
+            {section("Consumers", consumers)}
+        </div>
+    }
+}
+
+/// Get the type of a contributing building and the path of groups leading to it.
+fn describe(
+    nodes: &HashMap<Uuid, Node>,
+    contributor: &Contributor,
+) -> (Option<BuildingId>, AttrValue) {
+    let building = nodes
+        .get(&contributor.id)
+        .and_then(|node| node.building())
+        .and_then(|building| building.building);
+    let path = contributor
+        .ancestors
+        .iter()
+        .map(|id| match nodes.get(id).and_then(|node| node.group()) {
+            Some(group) if !group.name.is_empty() => group.name.as_str(),
+            _ => "Unnamed Group",
+        })
+        .collect::<Vec<_>>()
+        .join(" \u{203A} ");
+    (building, path.into())
+}
+
+#[derive(Properties, PartialEq)]
+struct RowProps {
+    /// Position of the building in the list, starting from 1.
+    rank: usize,
+    /// The building to show.
+    contributor: Contributor,
+    /// Type of the building.
+    building: Option<BuildingId>,
+    /// Names of the groups containing the building.
+    path: AttrValue,
+}
+
+/// A single building which produces or consumes the item. Clicking it closes the window and
+/// scrolls to the building.
+#[function_component]
+fn ItemUsageRow(
+    RowProps {
+        rank,
+        contributor,
+        building,
+        path,
+    }: &RowProps,
+) -> Html {
+    let db = use_db();
+    let root = use_world_root();
+    let dispatcher = use_world_dispatcher();
+    let window_dispatcher = use_item_usage_window();
+    let metas = use_context::<NodeMetas>()
+        .expect("ItemUsageRow can only be used from within a child of WorldManager");
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+
+    let onclick = {
+        let contributor = contributor.clone();
+        let root_id = root.id();
+        Callback::from(move |_| {
+            window_dispatcher.hide_window();
+            reveal_contributors(&dispatcher, &metas, root_id, vec![contributor.clone()]);
+        })
+    };
+
+    let building = building.and_then(|id| db.get(id));
+    let (name, icon): (AttrValue, Option<AttrValue>) = match building {
+        Some(building) => (
+            building.name.clone().into(),
+            Some(building.image.clone().into()),
+        ),
+        None => ("Unknown Building".into(), None),
+    };
+    let path = if path.is_empty() {
+        AttrValue::from("World")
+    } else {
+        path.clone()
+    };
+    let class = classes!(
+        "ItemUsageRow",
+        if contributor.rate < 0.0 {
+            "negative"
+        } else {
+            "positive"
+        }
+    );
+    html! {
+        <li>
+            <Button {class} title="Show in World" {onclick}>
+                <span class="usage-rank">{rank}</span>
+                <Icon {icon} name={name.clone()} />
+                <span class="usage-name">{name}</span>
+                <span class="usage-path">{path}</span>
+                <span class="usage-rate">{balance_settings.format_rate(contributor.rate).to_string()}</span>
+                {material_icon("my_location")}
+            </Button>
+        </li>
+    }
+}
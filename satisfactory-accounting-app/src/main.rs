@@ -11,16 +11,30 @@ mod app;
 mod appheader;
 mod bugreport;
 mod collections;
+mod dashboard;
+mod download;
+mod duration;
+mod find_replace;
+mod help;
 mod inputs;
+mod item_flow;
+mod item_usage;
 mod locale;
+mod markdown;
 mod material;
 mod modal;
 mod node_display;
 mod notifications;
 mod overlay_window;
+mod pivot;
+mod power_report;
+mod print_view;
+mod recipe_compare;
 mod refeqrc;
+mod reports;
 mod storagenotice;
 mod user_settings;
+mod warnings;
 mod world;
 
 fn main() {
@@ -0,0 +1,195 @@
+//! Renders a small subset of markdown directly to Html, for user-written notes.
+//!
+//! Supported are paragraphs, `#` headings, bulleted and numbered lists, `[ ]`/`[x]` to-do list
+//! items, `**bold**`, `*italic*`, `` `code` ``, and `[links](https://...)`. Anything else is
+//! shown as plain text. Since notes may come from shared worlds, no raw HTML is ever produced.
+
+use yew::{html, Html};
+
+/// A block of lines which are rendered together.
+enum Block<'a> {
+    /// Consecutive lines of text, rendered with line breaks between them.
+    Paragraph(Vec<&'a str>),
+    /// A heading of the given level, from 1 to 3.
+    Heading(usize, &'a str),
+    /// Consecutive list items.
+    List { ordered: bool, items: Vec<&'a str> },
+}
+
+/// Render markdown text as Html.
+pub fn render_markdown(text: &str) -> Html {
+    let mut blocks: Vec<Block> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            // A blank line ends the current block, which an empty paragraph acts as a marker for.
+            if !matches!(blocks.last(), Some(Block::Paragraph(lines)) if lines.is_empty()) {
+                blocks.push(Block::Paragraph(Vec::new()));
+            }
+        } else if let Some((level, heading)) = heading(line) {
+            blocks.push(Block::Heading(level, heading));
+        } else if let Some((ordered, item)) = list_item(line) {
+            match blocks.last_mut() {
+                Some(Block::List {
+                    ordered: current,
+                    items,
+                }) if *current == ordered => items.push(item),
+                _ => blocks.push(Block::List {
+                    ordered,
+                    items: vec![item],
+                }),
+            }
+        } else {
+            match blocks.last_mut() {
+                Some(Block::Paragraph(lines)) => lines.push(line),
+                _ => blocks.push(Block::Paragraph(vec![line])),
+            }
+        }
+    }
+
+    html! {
+        <>
+            {for blocks.into_iter().map(render_block)}
+        </>
+    }
+}
+
+/// Render a single block.
+fn render_block(block: Block) -> Html {
+    match block {
+        Block::Paragraph(lines) if lines.is_empty() => html! {},
+        Block::Paragraph(lines) => html! {
+            <p>
+                {for lines.into_iter().enumerate().map(|(i, line)| html! {
+                    <>
+                        if i > 0 {
+                            <br />
+                        }
+                        {render_inline(line)}
+                    </>
+                })}
+            </p>
+        },
+        Block::Heading(1, heading) => html! { <h4>{render_inline(heading)}</h4> },
+        Block::Heading(2, heading) => html! { <h5>{render_inline(heading)}</h5> },
+        Block::Heading(_, heading) => html! { <h6>{render_inline(heading)}</h6> },
+        Block::List { ordered, items } => {
+            let items = items.into_iter().map(render_list_item);
+            if ordered {
+                html! { <ol>{for items}</ol> }
+            } else {
+                html! { <ul>{for items}</ul> }
+            }
+        }
+    }
+}
+
+/// Render a list item, showing a checkbox if it is a to-do.
+fn render_list_item(item: &str) -> Html {
+    let todo = if let Some(rest) = item.strip_prefix("[ ] ") {
+        Some((false, rest))
+    } else {
+        item.strip_prefix("[x] ")
+            .or_else(|| item.strip_prefix("[X] "))
+            .map(|rest| (true, rest))
+    };
+    match todo {
+        Some((done, rest)) => html! {
+            <li class="todo">
+                <input type="checkbox" checked={done} disabled=true />
+                {render_inline(rest)}
+            </li>
+        },
+        None => html! { <li>{render_inline(item)}</li> },
+    }
+}
+
+/// If the line is a heading, get its level and text.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=3).contains(&level) {
+        return None;
+    }
+    line[level..]
+        .strip_prefix(' ')
+        .map(|rest| (level, rest.trim()))
+}
+
+/// If the line is a list item, get whether it is from a numbered list and the item's text.
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    let line = line.trim_start();
+    if let Some(item) = ["- ", "* ", "+ "]
+        .into_iter()
+        .find_map(|marker| line.strip_prefix(marker))
+    {
+        return Some((false, item));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(". ").map(|item| (true, item))
+}
+
+/// Render inline formatting within a line.
+fn render_inline(text: &str) -> Html {
+    let mut nodes = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((span, after)) = inline_span(rest) {
+            if !plain.is_empty() {
+                nodes.push(html! { {std::mem::take(&mut plain)} });
+            }
+            nodes.push(span);
+            rest = after;
+        } else {
+            plain.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if !plain.is_empty() {
+        nodes.push(html! { {plain} });
+    }
+    html! {
+        <>
+            {for nodes}
+        </>
+    }
+}
+
+/// If the text starts with a formatted span, render it and return the text after it.
+fn inline_span(text: &str) -> Option<(Html, &str)> {
+    if let Some(rest) = text.strip_prefix('`') {
+        let end = rest.find('`')?;
+        Some((html! { <code>{&rest[..end]}</code> }, &rest[end + 1..]))
+    } else if let Some(rest) = text.strip_prefix("**") {
+        let end = rest.find("**").filter(|&end| end > 0)?;
+        let inner = render_inline(&rest[..end]);
+        Some((html! { <strong>{inner}</strong> }, &rest[end + 2..]))
+    } else if let Some(rest) = text.strip_prefix('*') {
+        // Require text right after the opening marker so `2 * 3 * 4` isn't italicized.
+        if rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let end = rest.find('*').filter(|&end| end > 0)?;
+        let inner = render_inline(&rest[..end]);
+        Some((html! { <em>{inner}</em> }, &rest[end + 1..]))
+    } else if let Some(rest) = text.strip_prefix('[') {
+        let label_end = rest.find("](")?;
+        let after_label = &rest[label_end + 2..];
+        let url_end = after_label.find(')')?;
+        let url = &after_label[..url_end];
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return None;
+        }
+        let label = render_inline(&rest[..label_end]);
+        Some((
+            html! {
+                <a href={url.to_owned()} target="_blank" rel="noopener noreferrer">{label}</a>
+            },
+            &after_label[url_end + 1..],
+        ))
+    } else {
+        None
+    }
+}
@@ -10,10 +10,12 @@ use satisfactory_accounting::database::{
 use serde::{Deserialize, Serialize};
 use yew::{function_component, html, use_callback, Html, Properties};
 
+use crate::help::{HelpButton, HelpTopic};
 use crate::inputs::toggle::MaterialRadio;
 use crate::node_display::clock::ClockSpeed;
 use crate::node_display::NodeDisplay;
-use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+use crate::user_settings::{use_user_settings, use_user_settings_dispatcher, world_only_tag};
+use crate::world::use_settings_overrides;
 
 /// Container for settings related to backdriving.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -152,9 +154,14 @@ enum BackdriveSettingsType {
 /// Displays the settings section for controlling backdrive settings.
 #[function_component]
 pub fn BackdriveSettingsSection() -> Html {
+    let overrides = use_settings_overrides();
     html! {
         <div class="settings-section">
-            <h2>{"Backdriving Settings"}</h2>
+            <h2>
+                {"Backdriving Settings"}
+                <HelpButton topic={HelpTopic::Backdrive} />
+                {world_only_tag(overrides.backdrive_settings.is_some())}
+            </h2>
             <p>{"Backdriving allows you to click on the outputs of a building and type the number \
             of items you want it to produce and have it calculate a clock speed and building \
             multiplier to get that production rate. There are two supported modes for backdriving:"}</p>
@@ -547,8 +554,13 @@ impl NodeDisplay {
             warn!("Unable to backdrive - geothermal can only backdrive power");
             return None;
         }
+        let power_multiplier = gs.power_multiplier();
+        if power_multiplier == 0.0 {
+            warn!("Unable to backdrive - geothermal has no geysers");
+            return None;
+        }
         // Geothermal doesn't allow overclocking so just round up.
-        let multiplier = rate / (g.power * gs.purity.speed_multiplier());
+        let multiplier = rate / (g.power * power_multiplier);
         Some(multiplier.ceil())
     }
 
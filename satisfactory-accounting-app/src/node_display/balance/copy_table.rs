@@ -0,0 +1,79 @@
+use satisfactory_accounting::accounting::Node;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_item_metas};
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Node whose balance is copied.
+    pub node: Node,
+}
+
+/// Button which copies a balance to the system clipboard as a Markdown table, for pasting into
+/// chat or planning documents.
+#[function_component]
+pub fn CopyBalanceTable(Props { node }: &Props) -> Html {
+    let db = use_db();
+    let item_metas = use_item_metas();
+    let user_settings = use_user_settings();
+    let copied = use_state_eq(|| false);
+
+    let onclick = use_callback(
+        (node.clone(), db, item_metas, user_settings, copied.clone()),
+        |(), (node, db, item_metas, user_settings, copied)| {
+            let balance_settings = &user_settings.number_display.balance;
+            let balance = node.balance();
+            let mut rows: Vec<(String, f32, String)> = balance
+                .balances
+                .iter()
+                .filter(|(&id, _)| !item_metas.is_ignored(id))
+                .map(|(&id, &rate)| {
+                    let name = match db.get(id) {
+                        Some(item) => item.name.to_string(),
+                        None => "Unknown Item".to_owned(),
+                    };
+                    let formatted = balance_settings.format_rate(rate);
+                    (name, rate, formatted.to_string())
+                })
+                .collect();
+            // Outputs first, then inputs, largest first within each.
+            rows.sort_by(|(_, lhs, _), (_, rhs, _)| rhs.total_cmp(lhs));
+
+            let mut table = "| Item | Rate |\n| --- | ---: |\n".to_owned();
+            if balance.power != 0.0 {
+                let power = balance
+                    .power
+                    .format(&balance_settings.power_format_settings);
+                table.push_str(&format!("| Power | {power} MW |\n"));
+            }
+            for (name, _, rate) in rows {
+                table.push_str(&format!("| {} | {rate} |\n", name.replace('|', "\\|")));
+            }
+
+            // The returned promise only reports whether the copy succeeded, which the button
+            // already reflects well enough.
+            let _ = gloo::utils::window()
+                .navigator()
+                .clipboard()
+                .write_text(&table);
+            copied.set(true);
+        },
+    );
+    let onmouseleave = use_callback(copied.clone(), |_, copied| copied.set(false));
+
+    html! {
+        <span class="copy-balance-table" {onmouseleave}>
+            <Button {onclick} title="Copy Balance as a Markdown Table">
+                if *copied {
+                    {material_icon("done")}
+                } else {
+                    {material_icon("content_copy")}
+                }
+            </Button>
+        </span>
+    }
+}
@@ -6,20 +6,28 @@ use log::info;
 //   You may obtain a copy of the License at
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
+use std::rc::Rc;
+
 use satisfactory_accounting::accounting::Node;
-use satisfactory_accounting::database::{Item, ItemId, ItemIdOrPower};
+use satisfactory_accounting::analysis::{balance_breakdown, GrossBalance};
+use satisfactory_accounting::database::{Database, Item, ItemId, ItemIdOrPower};
 use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 
-use crate::inputs::clickedit::{
-    AdjustDir, AdjustModifier, AdjustScale, ClickEdit, ValueAdjustment,
-};
+use crate::help::{HelpButton, HelpTopic};
+use crate::inputs::clickedit::{AdjustDir, AdjustSteps, ClickEdit, ValueAdjustment};
 use crate::node_display::icon::Icon;
+use crate::node_display::node_name;
+use crate::node_display::percent::format_share;
 use crate::user_settings::number_format::{
     BalanceDisplaySettings, NumberFormatSettings, NumberStylingMode, UserConfiguredFormat,
 };
 use crate::user_settings::use_user_settings;
-use crate::world::use_db;
+use crate::world::{item_meta_summary, use_db, use_item_metas, ItemMetas};
+
+use copy_table::CopyBalanceTable;
+
+mod copy_table;
 
 /// How entries in the balance should be sorted.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -62,6 +70,37 @@ pub struct Props {
     /// Callback to use for backdriving (setting the clock speed based on item count).
     #[prop_or_default]
     pub on_backdrive: Option<Callback<(ItemIdOrPower, f32)>>,
+    /// Callback for when an entry is clicked, along with whether shift was held. Not used for
+    /// entries which can be backdriven.
+    #[prop_or_default]
+    pub on_select: Option<Callback<(ItemIdOrPower, bool)>>,
+    /// Entry which is currently selected, if any.
+    #[prop_or_default]
+    pub selected: Option<ItemIdOrPower>,
+    /// Totals to show each entry as a percent of, if any.
+    #[prop_or_default]
+    pub share_of: Option<Rc<GrossBalance>>,
+}
+
+/// Interactions and extra information for the entries of a balance.
+#[derive(Clone, Copy)]
+struct EntryOptions<'a> {
+    /// Callback to use for backdriving.
+    on_backdrive: Option<&'a Callback<(ItemIdOrPower, f32)>>,
+    /// Callback for when an entry is clicked.
+    on_select: Option<&'a Callback<(ItemIdOrPower, bool)>>,
+    /// Entry which is currently selected.
+    selected: Option<ItemIdOrPower>,
+    /// Totals to show each entry as a percent of.
+    share_of: Option<&'a GrossBalance>,
+    /// Callback for when the mouse enters an entry.
+    on_hover: Option<&'a Callback<ItemIdOrPower>>,
+    /// Breakdown of the most recently hovered entry, to add to its tooltip.
+    breakdown: Option<&'a (ItemIdOrPower, AttrValue)>,
+    /// Number of copies to divide each entry by to show the balance of a single copy.
+    per_copy_of: Option<f32>,
+    /// Increments for adjusting entries with the keyboard when backdriving.
+    steps: AdjustSteps,
 }
 
 #[function_component]
@@ -70,18 +109,63 @@ pub fn NodeBalance(
         ref node,
         shape,
         ref on_backdrive,
+        ref on_select,
+        selected,
+        ref share_of,
     }: &Props,
 ) -> Html {
     let balance = node.balance();
     let db = use_db();
+    let item_metas = use_item_metas();
     let user_settings = use_user_settings();
     let balance_settings = &user_settings.number_display.balance;
-    let on_backdrive = on_backdrive.as_ref();
+    // Breakdowns walk the whole subtree, so they're only computed for the entry being hovered.
+    let breakdown = use_state_eq(|| None);
+    let on_hover = node.group().is_some().then(|| {
+        let node = node.clone();
+        let db = db.clone();
+        let user_settings = user_settings.clone();
+        let breakdown = breakdown.clone();
+        Callback::from(move |id| {
+            let text = breakdown_text(&node, &db, &user_settings.number_display.balance, id);
+            breakdown.set(Some((id, AttrValue::from(text))));
+        })
+    });
+    // Only buildings with more than one copy have a per copy balance different from the total.
+    let per_copy_of = node
+        .building()
+        .filter(|_| user_settings.show_per_copy_balances)
+        .map(|building| building.copies)
+        .filter(|&copies| copies > 0.0 && copies != 1.0);
+    let options = EntryOptions {
+        on_backdrive: on_backdrive.as_ref(),
+        on_select: on_select.as_ref(),
+        selected,
+        share_of: share_of.as_deref(),
+        on_hover: on_hover.as_ref(),
+        breakdown: breakdown.as_ref(),
+        per_copy_of,
+        steps: user_settings.key_bindings.backdrive_steps,
+    };
 
+    // Items ignored in this world are left out of every balance.
+    let visible_balances = || {
+        balance
+            .balances
+            .iter()
+            .filter(|(&itemid, _)| !item_metas.is_ignored(itemid))
+    };
     let item_balances: Html = match user_settings.balance_sort_mode {
         BalanceSortMode::Item => {
-            let combined_balances = balance.balances.iter().map(|(&itemid, &rate)| {
-                display_item(itemid, db.get(itemid), rate, balance_settings, on_backdrive)
+            let combined_balances = visible_balances().map(|(&itemid, &rate)| {
+                display_item(
+                    itemid,
+                    db.get(itemid),
+                    &item_metas,
+                    rate,
+                    balance_settings,
+                    options,
+                )
             });
             html! {
                 <div class="item-entries combined">
@@ -92,36 +176,51 @@ pub fn NodeBalance(
         BalanceSortMode::IOItem => {
             let display_rate = |rate| {
                 display_rate(
-                    rate,
+                    balance_settings.rate_in_unit(rate),
                     &balance_settings.item_format_settings,
                     balance_settings,
                 )
             };
-            let positive_balances = balance
-                .balances
-                .iter()
+            let positive_balances = visible_balances()
                 .filter(|(_, &rate)| display_rate(rate) > 0.0)
                 .map(|(&itemid, &rate)| {
-                    display_item(itemid, db.get(itemid), rate, balance_settings, on_backdrive)
+                    display_item(
+                        itemid,
+                        db.get(itemid),
+                        &item_metas,
+                        rate,
+                        balance_settings,
+                        options,
+                    )
                 });
-            let negative_balances = balance
-                .balances
-                .iter()
+            let negative_balances = visible_balances()
                 .filter(|(_, &rate)| display_rate(rate) < 0.0)
                 .map(|(&itemid, &rate)| {
-                    display_item(itemid, db.get(itemid), rate, balance_settings, on_backdrive)
+                    display_item(
+                        itemid,
+                        db.get(itemid),
+                        &item_metas,
+                        rate,
+                        balance_settings,
+                        options,
+                    )
                 });
 
-            let neutral_balances = balance
-                .balances
-                .iter()
+            let neutral_balances = visible_balances()
                 // Weird NaN handling? I guess I could probably just use is_nan here?
                 .filter(|(_, &rate)| {
                     let rate = display_rate(rate);
                     rate == 0.0 || !(rate < 0.0 || rate > 0.0)
                 })
                 .map(|(&itemid, &rate)| {
-                    display_item(itemid, db.get(itemid), rate, balance_settings, on_backdrive)
+                    display_item(
+                        itemid,
+                        db.get(itemid),
+                        &item_metas,
+                        rate,
+                        balance_settings,
+                        options,
+                    )
                 });
 
             html! {
@@ -141,37 +240,86 @@ pub fn NodeBalance(
     };
     html! {
         <div class={classes!("NodeBalance", shape.to_class_name())}>
-            {item_row(ItemIdOrPower::Power, "Power".into(), Some("power-line".into()), balance.power, balance_settings, on_backdrive)}
+            {item_row(ItemIdOrPower::Power, "Power".into(), Some("power-line".into()), balance.power, balance_settings, options)}
             { item_balances }
+            if on_backdrive.is_some() {
+                <HelpButton topic={HelpTopic::BalanceAdjustment} />
+            }
+            if shape == BalanceShape::Vertical {
+                <CopyBalanceTable node={node.clone()} />
+            }
         </div>
     }
 }
 
+/// Most children listed in the breakdown of a balance entry.
+const BREAKDOWN_CHILDREN: usize = 3;
+
+/// Describe the gross production and consumption of an item or power in a group, and which of
+/// its children contribute the most.
+fn breakdown_text(
+    node: &Node,
+    db: &Database,
+    settings: &BalanceDisplaySettings,
+    id: ItemIdOrPower,
+) -> String {
+    let format = |rate: f32| match id {
+        ItemIdOrPower::Power => rate.format(&settings.power_format_settings).to_string(),
+        ItemIdOrPower::Item(_) => settings.format_rate(rate).to_string(),
+    };
+    let breakdown = balance_breakdown(node, id, BREAKDOWN_CHILDREN);
+    let mut text = format!(
+        "Produced: {}\nConsumed: {}",
+        format(breakdown.produced),
+        format(breakdown.consumed),
+    );
+    if !breakdown.top_children.is_empty() {
+        text.push_str("\nTop contributors:");
+        let children: Vec<Node> = node.children().collect();
+        for (idx, rate) in breakdown.top_children {
+            let sign = if rate > 0.0 { "+" } else { "" };
+            let name = node_name(db, &children[idx]);
+            text.push_str(&format!("\n  {name}: {sign}{}", format(rate)));
+        }
+    }
+    text
+}
+
 fn display_item(
     id: ItemId,
     item: Option<&Item>,
+    item_metas: &ItemMetas,
     rate: f32,
     balance_settings: &BalanceDisplaySettings,
-    on_backdrive: Option<&Callback<(ItemIdOrPower, f32)>>,
+    options: EntryOptions,
 ) -> Html {
-    match item {
-        Some(item) => item_row(
-            id.into(),
-            item.name.clone().into(),
-            Some(item.image.clone().into()),
-            rate,
-            balance_settings,
-            on_backdrive,
-        ),
-        None => item_row(
-            id.into(),
-            "Unknown Item".into(),
-            None,
-            rate,
-            balance_settings,
-            on_backdrive,
-        ),
+    let (name, icon) = match item {
+        Some(item) => (&*item.name, Some(item.image.clone().into())),
+        None => ("Unknown Item", None),
+    };
+    let mut title = name.to_owned();
+    if let Some(item) = item.filter(|item| item.sink_points > 0) {
+        let points = item.sink_points;
+        title.push_str(&format!("\nSink value: {points} points"));
+        if rate > 0.0 {
+            let points_rate = balance_settings.format_rate(rate * points as f32);
+            let suffix = balance_settings.rate_suffix();
+            title.push_str(&format!(" ({points_rate} points{suffix} if sunk)"));
+        }
+    }
+    // Include any world notes or stockpile target for the item in its tooltip.
+    if let Some(summary) = item_meta_summary(&item_metas.meta(id)) {
+        title.push('\n');
+        title.push_str(&summary);
     }
+    item_row(
+        id.into(),
+        title.into(),
+        icon,
+        rate,
+        balance_settings,
+        options,
+    )
 }
 
 fn item_row(
@@ -180,27 +328,73 @@ fn item_row(
     icon: Option<AttrValue>,
     rate: f32,
     display_settings: &BalanceDisplaySettings,
-    on_backdrive: Option<&Callback<(ItemIdOrPower, f32)>>,
+    options: EntryOptions,
 ) -> Html {
-    let (power_class, rounding) = match id {
-        ItemIdOrPower::Power => (Some("power-entry"), &display_settings.power_format_settings),
-        _ => (None, &display_settings.item_format_settings),
+    let (power_class, rounding, rate_unit) = match id {
+        ItemIdOrPower::Power => (
+            Some("power-entry"),
+            &display_settings.power_format_settings,
+            None,
+        ),
+        _ => (
+            None,
+            &display_settings.item_format_settings,
+            Some(display_settings.rate_unit),
+        ),
     };
+    // Item rates are shown and edited in the configured unit, power is always in MW.
+    let shown = rate_unit.map_or(rate, |unit| unit.convert(rate));
     let class = classes!(
         "entry-row",
-        balance_style(rate, rounding, display_settings),
-        power_class
+        balance_style(shown, rounding, display_settings),
+        power_class,
+        (options.selected == Some(id)).then_some("selected")
     );
 
-    let rounded_value: AttrValue = rate.format(rounding).to_string().into();
+    let rounded_value: AttrValue = shown.format(rounding).to_string().into();
+    let share = options
+        .share_of
+        .and_then(|totals| totals.share(id, rate))
+        .map(|share| html! { <span class="balance-share">{format_share(share)}</span> });
+    let per_copy = options.per_copy_of.map(|copies| {
+        let per_copy = (shown / copies).format(rounding);
+        html! {
+            <span class="balance-per-copy" title="Balance of a single copy">
+                {format!("{per_copy} each")}
+            </span>
+        }
+    });
 
-    match on_backdrive {
-        None => html! {
-            <div {class} {title}>
-                <Icon {icon}/>
-                <div class="balance-value">{rounded_value}</div>
-            </div>
-        },
+    match options.on_backdrive {
+        None => {
+            let onclick = options.on_select.map(|on_select| {
+                let on_select = on_select.clone();
+                Callback::from(move |e: MouseEvent| on_select.emit((id, e.shift_key())))
+            });
+            let title = match options.breakdown {
+                Some((hovered, breakdown)) if *hovered == id => {
+                    format!("{title}\n{breakdown}").into()
+                }
+                _ => title,
+            };
+            let onmouseenter = options.on_hover.map(|on_hover| {
+                let on_hover = on_hover.clone();
+                Callback::from(move |_: MouseEvent| on_hover.emit(id))
+            });
+            let title = match onclick {
+                Some(_) => format!(
+                    "{title}\nClick to highlight contributors, Shift+Click to expand down to them"
+                )
+                .into(),
+                None => title,
+            };
+            html! {
+                <div {class} {title} {onclick} {onmouseenter}>
+                    <Icon {icon}/>
+                    <div class="balance-value">{rounded_value}{share}{per_copy}</div>
+                </div>
+            }
+        }
         Some(on_backdrive) => {
             fn adjust(adjustment: ValueAdjustment, current: AttrValue) -> AttrValue {
                 let current = match current.parse::<f32>() {
@@ -213,34 +407,28 @@ fn item_row(
                     AdjustDir::Up => 1.0 * current.signum(),
                     AdjustDir::Down => -1.0 * current.signum(),
                 };
-                let dist = match (adjustment.scale, adjustment.modifier) {
-                    // Fine adjustment by increments of 1 building.
-                    (AdjustScale::Fine, AdjustModifier::None) => 1.0,
-                    // Coarse adjustment by increments of 5 buildings.
-                    (AdjustScale::Coarse, AdjustModifier::None) => 5.0,
-                    // Small scale adjustments are by 1% and 10% of clock respectively.
-                    (AdjustScale::Fine, AdjustModifier::Smaller) => 0.01,
-                    (AdjustScale::Coarse, AdjustModifier::Smaller) => 0.1,
-                };
-                (current + dir * dist).to_string().into()
+                (current + dir * adjustment.step).to_string().into()
             }
 
             let on_backdrive = on_backdrive.clone();
             let on_commit = Callback::from(move |edit_text: AttrValue| {
                 if let Ok(value) = edit_text.parse::<f32>() {
+                    let value = rate_unit.map_or(value, |unit| unit.to_per_minute(value));
                     on_backdrive.emit((id, value));
                 }
             });
             let prefix = html!(<Icon {icon} />);
+            let suffix = html! { <>{share}{per_copy}</> };
             html! {
-                <ClickEdit {class} {prefix} {title} value={rate.to_string()} {rounded_value}
-                    {on_commit} adjust={adjust as fn(_,_)->_} />
+                <ClickEdit {class} {prefix} {suffix} {title} value={shown.to_string()}
+                    {rounded_value} {on_commit} adjust={adjust as fn(_,_)->_}
+                    steps={options.steps} />
             }
         }
     }
 }
 
-fn balance_style(
+pub(super) fn balance_style(
     balance: f32,
     rounding: &NumberFormatSettings,
     settings: &BalanceDisplaySettings,
@@ -0,0 +1,440 @@
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use satisfactory_accounting::accounting::{Building, BuildingSettings, Node, MIN_CLOCK};
+use satisfactory_accounting::analysis::{diff_trees, TreeDiff};
+use satisfactory_accounting::database::{BuildingId, BuildingKind, BuildingType, Recipe, RecipeId};
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::{material_icon, material_icon_outlined};
+use crate::node_display::clock::parse_clock;
+use crate::node_display::icon::Icon;
+use crate::node_display::{Msg, NodeDisplay};
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::use_db;
+
+impl NodeDisplay {
+    /// Button which shows or hides the batch editor of this group.
+    pub(super) fn batch_edit_button(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.link().callback(|_| Msg::ToggleBatchEdit);
+        let title = if self.batch_edit_open {
+            "Hide Batch Edit"
+        } else {
+            "Batch Edit Buildings"
+        };
+        html! {
+            <Button {onclick} {title}>
+                {material_icon("tune")}
+            </Button>
+        }
+    }
+
+    /// Panel spanning the whole row for changing every building in this group at once, if it is
+    /// open.
+    pub(super) fn batch_edit_panel(&self, ctx: &Context<Self>) -> Html {
+        if !self.batch_edit_open || self.read_only {
+            return html! {};
+        }
+        let on_apply = ctx
+            .link()
+            .callback(|replacement| Msg::ReplaceSelf { replacement });
+        html! {
+            <BatchEdit node={ctx.props().node.clone()} {on_apply} />
+        }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct Props {
+    /// Group whose buildings are edited.
+    node: Node,
+    /// Callback with the group after an edit is applied.
+    on_apply: Callback<Node>,
+}
+
+/// Editor which sets the clock speed or copies of every building in a group and its subgroups,
+/// optionally limited to one building type, as a single change. When limited to one type, the
+/// buildings can also be replaced with another type of the same kind, like a higher mark of miner.
+/// Recipes can be swapped too.
+#[function_component]
+fn BatchEdit(Props { node, on_apply }: &Props) -> Html {
+    let db = use_db();
+    let only = use_state_eq(|| None::<BuildingId>);
+
+    let types: BTreeSet<BuildingId> = node
+        .iter()
+        .filter_map(|node| node.building().and_then(|building| building.building))
+        .collect();
+    let matching = node
+        .iter()
+        .filter_map(|node| node.building().and_then(|building| building.building))
+        .filter(|&id| only.is_none_or(|only| only == id))
+        .count();
+
+    // Apply an update to every building matching the filter.
+    let apply = |parse: fn(&str) -> Option<f32>,
+                 update: fn(&mut Building, &BuildingType, f32) -> bool| {
+        let node = node.clone();
+        let db = db.clone();
+        let on_apply = on_apply.clone();
+        let only = *only;
+        Callback::from(move |edit_text: AttrValue| {
+            let Some(value) = parse(&edit_text) else {
+                return;
+            };
+            let updated = node.update_buildings(&db, &|building, building_type| {
+                only.is_none_or(|only| building.building == Some(only))
+                    && update(building, building_type, value)
+            });
+            on_apply.emit(updated);
+        })
+    };
+    let set_clock = apply(
+        |text| parse_clock(text, false),
+        |building, building_type, clock_speed| {
+            if !building_type.overclockable() {
+                return false;
+            }
+            let clock_speed = clock_speed.clamp(MIN_CLOCK, building_type.max_clock_speed());
+            if building.settings.clock_speed() == clock_speed {
+                return false;
+            }
+            building.settings.set_clock_speed(clock_speed);
+            true
+        },
+    );
+    let set_copies = apply(
+        |text| text.parse().ok(),
+        |building, _, copies| {
+            let copies = copies.abs();
+            if building.copies == copies {
+                return false;
+            }
+            building.copies = copies;
+            true
+        },
+    );
+
+    // Replace every building of the chosen type with another type of the same kind, keeping its
+    // settings.
+    let replace_choice = |from: BuildingId, to: &BuildingType| {
+        let replaceable = node
+            .iter()
+            .filter_map(|node| node.building().cloned())
+            .filter(|building| {
+                building.building == Some(from) && building.settings.upgrade_to(to).is_some()
+            })
+            .count();
+        let onclick = {
+            let node = node.clone();
+            let db = db.clone();
+            let on_apply = on_apply.clone();
+            let to = to.id;
+            Callback::from(move |()| {
+                let Some(to_type) = db.get(to) else {
+                    return;
+                };
+                let updated = node.update_buildings(&db, &|building, _| {
+                    if building.building != Some(from) {
+                        return false;
+                    }
+                    let Some(settings) = building.settings.upgrade_to(to_type) else {
+                        return false;
+                    };
+                    building.building = Some(to);
+                    building.settings = settings;
+                    true
+                });
+                on_apply.emit(updated);
+            })
+        };
+        let title = format!("Replace {replaceable} with {}", to.name);
+        html! {
+            <Button {title} {onclick} disabled={replaceable == 0}>
+                <Icon icon={to.image.clone()} name={to.name.clone()} />
+            </Button>
+        }
+    };
+    let replace_choices = only.and_then(|from| {
+        let from_type = db.get(from)?;
+        let choices: Vec<Html> = db
+            .buildings()
+            .filter(|to| to.id != from && to.kind.kind_id() == from_type.kind.kind_id())
+            .map(|to| replace_choice(from, to))
+            .collect();
+        (!choices.is_empty()).then(|| {
+            html! {
+                <div class="batch-edit-fields">
+                    <span>{format!("Replace {} with:", from_type.name)}</span>
+                    {for choices}
+                </div>
+            }
+        })
+    });
+
+    let filter_choice = |id: Option<BuildingId>| {
+        let onclick = {
+            let only = only.clone();
+            Callback::from(move |()| only.set(id))
+        };
+        let class = classes!((*only == id).then_some("selected"));
+        let label = match id.map(|id| db.get(id)) {
+            None => html! { <span>{"All"}</span> },
+            Some(Some(building)) => html! {
+                <Icon icon={building.image.clone()} name={building.name.clone()} />
+            },
+            Some(None) => html! { <Icon /> },
+        };
+        let title = match id.map(|id| db.get(id)) {
+            None => "All Buildings".to_owned(),
+            Some(Some(building)) => building.name.to_string(),
+            Some(None) => "Unknown Building".to_owned(),
+        };
+        html! {
+            <Button {class} {title} {onclick}>{label}</Button>
+        }
+    };
+
+    html! {
+        <div class="BatchEdit">
+            <div class="batch-edit-filter">
+                <span>{"Buildings:"}</span>
+                {filter_choice(None)}
+                {for types.into_iter().map(|id| filter_choice(Some(id)))}
+            </div>
+            <div class="batch-edit-fields">
+                <span>{format!("Set for {matching} buildings:")}</span>
+                <ClickEdit value="1" title="Clock Speed" on_commit={set_clock}
+                    prefix={material_icon_outlined("timer")} />
+                <ClickEdit value="1" title="Copies" on_commit={set_copies}
+                    prefix={html! { <span>{"\u{00d7}"}</span> }} />
+            </div>
+            {replace_choices}
+            <RecipeSwap node={node.clone()} only={*only} on_apply={on_apply.clone()} />
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct RecipeSwapProps {
+    /// Group whose buildings are edited.
+    node: Node,
+    /// Building type the swap is limited to, if any.
+    only: Option<BuildingId>,
+    /// Callback with the group after the swap is applied.
+    on_apply: Callback<Node>,
+}
+
+/// Result of the last recipe swap, shown until the next one.
+#[derive(PartialEq)]
+struct SwapReport {
+    /// Recipe which was replaced.
+    from: RecipeId,
+    /// Recipe it was replaced with.
+    to: RecipeId,
+    /// Number of buildings changed.
+    swapped: usize,
+    /// How the balance of the group changed.
+    diff: TreeDiff,
+}
+
+/// Editor which replaces one recipe with another producing the same item, such as an alternate
+/// recipe, in every building of a group and its subgroups that can make both, as a single change.
+/// Reports how the group's balance changed.
+#[function_component]
+fn RecipeSwap(
+    RecipeSwapProps {
+        node,
+        only,
+        on_apply,
+    }: &RecipeSwapProps,
+) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let from = use_state_eq(|| None::<RecipeId>);
+    let report = use_state_eq(|| None::<Rc<SwapReport>>);
+
+    // Manufacturers the swap applies to, with their recipe and the recipes their type allows.
+    let manufacturers: Vec<(RecipeId, &[RecipeId])> = node
+        .iter()
+        .filter_map(|node| {
+            let building = node.building()?;
+            let id = building.building?;
+            if only.is_some_and(|only| only != id) {
+                return None;
+            }
+            let BuildingSettings::Manufacturer(ms) = &building.settings else {
+                return None;
+            };
+            let Some(BuildingKind::Manufacturer(m)) = db.get(id).map(|building| &building.kind)
+            else {
+                return None;
+            };
+            Some((ms.recipe?, &m.available_recipes[..]))
+        })
+        .collect();
+    if manufacturers.is_empty() {
+        return html! {};
+    }
+    let used: BTreeSet<RecipeId> = manufacturers.iter().map(|&(recipe, _)| recipe).collect();
+
+    let recipe_label = |recipe: &Recipe| {
+        html! {
+            <Icon icon={recipe.image.clone()} name={recipe.name.clone()} />
+        }
+    };
+    let from_choice = |id: RecipeId| {
+        let onclick = {
+            let from = from.clone();
+            let report = report.clone();
+            Callback::from(move |()| {
+                from.set(Some(id));
+                report.set(None);
+            })
+        };
+        let class = classes!((*from == Some(id)).then_some("selected"));
+        let (title, label) = match db.get(id) {
+            Some(recipe) => (recipe.name.to_string(), recipe_label(recipe)),
+            None => ("Unknown Recipe".to_owned(), html! { <Icon /> }),
+        };
+        html! {
+            <Button {class} {title} {onclick}>{label}</Button>
+        }
+    };
+
+    let to_choices = from.filter(|from| used.contains(from)).and_then(|from| {
+        let from_recipe = db.get(from)?;
+        let mut candidates: Vec<&Recipe> = Vec::new();
+        for product in &from_recipe.products {
+            for rate in db.recipes_producing(product.item) {
+                if rate.recipe.id != from
+                    && !candidates.iter().any(|recipe| recipe.id == rate.recipe.id)
+                {
+                    candidates.push(rate.recipe);
+                }
+            }
+        }
+        let to_choice = |to: &Recipe| {
+            let swappable = manufacturers
+                .iter()
+                .filter(|&&(recipe, available)| recipe == from && available.contains(&to.id))
+                .count();
+            let onclick = {
+                let node = node.clone();
+                let db = db.clone();
+                let on_apply = on_apply.clone();
+                let report = report.clone();
+                let only = *only;
+                let to = to.id;
+                Callback::from(move |()| {
+                    let updated = node.update_buildings(&db, &|building, building_type| {
+                        if only.is_some_and(|only| building.building != Some(only)) {
+                            return false;
+                        }
+                        match (&mut building.settings, &building_type.kind) {
+                            (BuildingSettings::Manufacturer(ms), BuildingKind::Manufacturer(m))
+                                if ms.recipe == Some(from) && m.available_recipes.contains(&to) =>
+                            {
+                                ms.recipe = Some(to);
+                                true
+                            }
+                            _ => false,
+                        }
+                    });
+                    report.set(Some(Rc::new(SwapReport {
+                        from,
+                        to,
+                        swapped: swappable,
+                        diff: diff_trees(&node, &updated),
+                    })));
+                    on_apply.emit(updated);
+                })
+            };
+            let title = format!("Use {} in {swappable} buildings", to.name);
+            html! {
+                <Button {title} {onclick} disabled={swappable == 0}>
+                    {recipe_label(to)}
+                </Button>
+            }
+        };
+        (!candidates.is_empty()).then(|| {
+            html! {
+                <div class="batch-edit-fields">
+                    <span>{format!("Replace {} with:", from_recipe.name)}</span>
+                    {for candidates.into_iter().map(to_choice)}
+                </div>
+            }
+        })
+    });
+
+    let report = report.as_deref().map(|report| {
+        let recipe_name = |id: RecipeId| match db.get(id) {
+            Some(recipe) => recipe.name.to_string(),
+            None => "Unknown Recipe".to_owned(),
+        };
+        let summary = format!(
+            "Replaced {} with {} in {} buildings.",
+            recipe_name(report.from),
+            recipe_name(report.to),
+            report.swapped
+        );
+        let power_format = &balance_settings.power_format_settings;
+        let power = report.diff.power.map(|(before, after)| {
+            html! {
+                <li>
+                    {material_icon("bolt")}
+                    {format!(
+                        "{} \u{2192} {} MW",
+                        before.format(power_format),
+                        after.format(power_format)
+                    )}
+                </li>
+            }
+        });
+        let suffix = balance_settings.rate_suffix();
+        let balances = report.diff.balances.iter().map(|(&id, &(before, after))| {
+            let (name, icon): (AttrValue, Option<AttrValue>) = match db.get(id) {
+                Some(item) => (item.name.clone().into(), Some(item.image.clone().into())),
+                None => ("Unknown Item".into(), None),
+            };
+            html! {
+                <li>
+                    <Icon {icon} name={name.clone()} />
+                    {format!(
+                        "{name}: {} \u{2192} {}{suffix}",
+                        balance_settings.format_rate(before),
+                        balance_settings.format_rate(after)
+                    )}
+                </li>
+            }
+        });
+        html! {
+            <div class="batch-edit-report">
+                <span>{summary}</span>
+                if report.diff.balances.is_empty() && report.diff.power.is_none() {
+                    <span>{" The group's balance didn't change."}</span>
+                } else {
+                    <ul>
+                        {for power}
+                        {for balances}
+                    </ul>
+                }
+            </div>
+        }
+    });
+
+    html! {
+        <>
+            <div class="batch-edit-filter">
+                <span>{"Recipes:"}</span>
+                {for used.iter().map(|&id| from_choice(id))}
+            </div>
+            {to_choices}
+            {report}
+        </>
+    }
+}
@@ -0,0 +1,283 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo::file::ObjectUrl;
+use satisfactory_accounting::accounting::{Group, Node};
+use satisfactory_accounting::satisfactory_tools::{read_plans, ProductionData};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::download::download_text;
+use crate::inputs::button::{Button, UploadButton, UploadedFile};
+use crate::material::material_icon;
+use crate::modal::{use_modal_dispatcher, BinaryChoice, ModalOk};
+use crate::world::{use_db, use_db_controller, DatabaseVersionSelector};
+
+/// Format used for downloadable blueprint files, which hold a single group that can be imported
+/// into any world.
+#[derive(Debug, Serialize, Deserialize)]
+struct BlueprintFile {
+    /// Version of the blueprint file format.
+    blueprint_version: BlueprintVersion,
+    /// Database the blueprint was created with, or None if it used a custom database.
+    #[serde(default)]
+    database: Option<DatabaseVersionSelector>,
+    /// The group stored in this blueprint.
+    root: Node,
+}
+
+/// Versions of the blueprint file format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum BlueprintVersion {
+    /// Initial blueprint format.
+    #[serde(rename = "v1")]
+    V1,
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Group to export as a blueprint.
+    pub node: Node,
+    /// Callback to add an imported blueprint as a child of the group.
+    pub on_import: Callback<Node>,
+}
+
+/// Buttons to export a group as a blueprint file or import a blueprint file into a group, and to
+/// exchange groups with Satisfactory Tools as production plans.
+#[function_component]
+pub fn BlueprintButtons(Props { node, on_import }: &Props) -> Html {
+    let db = use_db();
+    let db_controller = use_db_controller();
+    let current_db = db_controller.current_selector();
+    let modals = use_modal_dispatcher();
+    // Keeps the download url alive until the group is disposed.
+    let download_url_retainer: Rc<RefCell<Option<ObjectUrl>>> = use_mut_ref(|| None);
+
+    let export = use_callback(
+        (node.clone(), current_db, modals.clone()),
+        move |(), (node, current_db, modals)| {
+            let name = node.group().map(|g| g.name.clone()).unwrap_or_default();
+            let blueprint = BlueprintFile {
+                blueprint_version: BlueprintVersion::V1,
+                database: *current_db,
+                root: node.clone(),
+            };
+            let json = match serde_json::to_string(&blueprint) {
+                Ok(json) => json,
+                Err(e) => {
+                    return modals
+                        .builder()
+                        .title("Blueprint could not be serialized")
+                        .class("blueprint-error")
+                        .kind(ModalOk::close())
+                        .content(html! {
+                            <p>{"Unable to serialize blueprint: "}{e.to_string()}</p>
+                        })
+                        .build()
+                        .persist();
+                }
+            };
+            let filename = if name.is_empty() {
+                "Blueprint.blueprint.json".to_owned()
+            } else {
+                format!("{name}.blueprint.json")
+            };
+            if let Some(url) = download_text(&json, "application/json", &filename) {
+                *download_url_retainer.borrow_mut() = Some(url);
+            }
+        },
+    );
+
+    // Keeps the Satisfactory Tools download url alive until the group is disposed.
+    let plan_url_retainer: Rc<RefCell<Option<ObjectUrl>>> = use_mut_ref(|| None);
+    let export_plan = use_callback(
+        (node.clone(), db.clone(), modals.clone()),
+        move |(), (node, db, modals)| {
+            let name = node.group().map(|g| g.name.clone()).unwrap_or_default();
+            let plan = ProductionData::from_node(node, db);
+            let json = match serde_json::to_string(&plan) {
+                Ok(json) => json,
+                Err(e) => {
+                    return modals
+                        .builder()
+                        .title("Production plan could not be serialized")
+                        .class("blueprint-error")
+                        .kind(ModalOk::close())
+                        .content(html! {
+                            <p>{"Unable to serialize production plan: "}{e.to_string()}</p>
+                        })
+                        .build()
+                        .persist();
+                }
+            };
+            let filename = if name.is_empty() {
+                "Production Plan.sftools.json".to_owned()
+            } else {
+                format!("{name}.sftools.json")
+            };
+            if let Some(url) = download_text(&json, "application/json", &filename) {
+                *plan_url_retainer.borrow_mut() = Some(url);
+            }
+        },
+    );
+
+    let import_plan = use_callback(
+        (db.clone(), modals.clone(), on_import.clone()),
+        |file: UploadedFile, (db, modals, on_import)| {
+            let plans = match read_plans(&file.data) {
+                Ok(plans) => plans,
+                Err(e) => {
+                    return modals
+                        .builder()
+                        .title("Invalid Production Plan")
+                        .class("blueprint-error")
+                        .kind(ModalOk::close())
+                        .content(html! {
+                            <>
+                                <p>{"The file \""}{&file.name}{"\" could not be read as a \
+                                Satisfactory Tools production plan."}</p>
+                                <pre>{e.to_string()}</pre>
+                            </>
+                        })
+                        .build()
+                        .persist();
+                }
+            };
+            let mut unsupported = Vec::new();
+            let mut groups: Vec<Node> = plans
+                .iter()
+                .map(|plan| {
+                    let imported = plan.build_group(db);
+                    unsupported.extend(imported.unsupported);
+                    imported.group
+                })
+                .collect();
+            let imported = if groups.len() == 1 {
+                groups.remove(0)
+            } else {
+                Group {
+                    name: file.name.trim_end_matches(".json").to_owned().into(),
+                    children: groups,
+                    ..Group::empty()
+                }
+                .into()
+            };
+            on_import.emit(imported);
+            if !unsupported.is_empty() {
+                modals
+                    .builder()
+                    .title("Production Plan Partially Imported")
+                    .class("blueprint-incompatible")
+                    .kind(ModalOk::close())
+                    .content(html! {
+                        <>
+                            <p>{"Some requested items could not be planned, because they are \
+                            not in this world's database or are requested as a maximum rather \
+                            than a rate per minute:"}</p>
+                            <ul>
+                                {for unsupported.iter().map(|item| html! { <li>{item}</li> })}
+                            </ul>
+                        </>
+                    })
+                    .build()
+                    .persist();
+            }
+        },
+    );
+
+    let import = use_callback(
+        (db, current_db, modals, on_import.clone()),
+        |file: UploadedFile, (db, current_db, modals, on_import)| {
+            let blueprint = match serde_json::from_slice::<BlueprintFile>(&file.data) {
+                Ok(blueprint) if blueprint.root.group().is_some() => blueprint,
+                Ok(_) => {
+                    return modals
+                        .builder()
+                        .title("Invalid Blueprint")
+                        .class("blueprint-error")
+                        .kind(ModalOk::close())
+                        .content(html! {
+                            <p>{"The blueprint \""}{&file.name}{"\" does not contain a group."}</p>
+                        })
+                        .build()
+                        .persist();
+                }
+                Err(e) => {
+                    return modals
+                        .builder()
+                        .title("Invalid Blueprint")
+                        .class("blueprint-error")
+                        .kind(ModalOk::close())
+                        .content(html! {
+                            <>
+                                <p>{"The file \""}{&file.name}{"\" could not be read as a \
+                                blueprint."}</p>
+                                <pre>{e.to_string()}</pre>
+                            </>
+                        })
+                        .build()
+                        .persist();
+                }
+            };
+            // Give the imported groups new IDs so they can't collide with existing groups, and
+            // rebuild against this world's database, which flags anything the database doesn't
+            // know about.
+            let imported = blueprint.root.create_copy().rebuild(db);
+            let errors = imported
+                .iter()
+                .filter(|node| node.warning().is_some())
+                .count();
+            if errors == 0 {
+                on_import.emit(imported);
+                return;
+            }
+
+            let version_note = match blueprint.database {
+                Some(version) if blueprint.database != *current_db => html! {
+                    <p>{"The blueprint was created with database version \""}{version.name()}
+                    {"\", which is different from the one used by this world."}</p>
+                },
+                _ => html! {},
+            };
+            let on_import = on_import.clone();
+            modals
+                .builder()
+                .title("Blueprint Incompatible")
+                .class("blueprint-incompatible")
+                .kind(
+                    BinaryChoice::new(html! {"Cancel"}, html! {"Import Anyway"})
+                        .lhs_title("Don't import the blueprint")
+                        .rhs_title("Import the blueprint with errors")
+                        .on_rhs(Callback::from(move |()| on_import.emit(imported.clone()))),
+                )
+                .content(html! {
+                    <>
+                        <p>{errors}{" buildings or groups in the blueprint \""}{&file.name}
+                        {"\" use items, recipes, or buildings which are not available in this \
+                        world's database. They will be shown with warnings and missing icons \
+                        if imported."}</p>
+                        {version_note}
+                    </>
+                })
+                .build()
+                .persist();
+        },
+    );
+
+    html! {
+        <>
+            <Button title="Download as Blueprint" onclick={export}>
+                {material_icon("download")}
+            </Button>
+            <UploadButton title="Import Blueprint" onupload={import}>
+                {material_icon("upload")}
+            </UploadButton>
+            <Button title="Export as Satisfactory Tools Production Plan" onclick={export_plan}>
+                {material_icon("ios_share")}
+            </Button>
+            <UploadButton title="Import Satisfactory Tools Production Plan" onupload={import_plan}>
+                {material_icon("input")}
+            </UploadButton>
+        </>
+    }
+}
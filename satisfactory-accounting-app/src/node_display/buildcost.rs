@@ -0,0 +1,112 @@
+use satisfactory_accounting::accounting::Node;
+use yew::prelude::*;
+
+use crate::duration::format_minutes;
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::modal::{use_modal_dispatcher, ModalOk};
+use crate::node_display::icon::Icon;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_world_root};
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Node to show the construction cost of.
+    pub node: Node,
+}
+
+/// Button which shows a report of the materials needed to construct a node.
+#[function_component]
+pub fn BuildCostButton(Props { node }: &Props) -> Html {
+    let db = use_db();
+    let root = use_world_root();
+    let user_settings = use_user_settings();
+    let modals = use_modal_dispatcher();
+
+    let onclick = use_callback(
+        (node.clone(), db, root, user_settings, modals),
+        |(), (node, db, root, user_settings, modals)| {
+            let format = &user_settings.number_display.balance.item_format_settings;
+            let cost = node.build_cost(db);
+            // Estimate how long collecting the materials takes based on the whole world's
+            // production, since that is what's available to build with.
+            let estimate = root
+                .balance()
+                .time_to_accumulate(cost.iter().map(|(&item, &amount)| (item, amount)));
+
+            let mut rows: Vec<_> = cost
+                .iter()
+                .map(|(&id, &amount)| {
+                    let (name, icon): (AttrValue, Option<AttrValue>) = match db.get(id) {
+                        Some(item) => (item.name.clone().into(), Some(item.image.clone().into())),
+                        None => ("Unknown Item".into(), None),
+                    };
+                    let rate = root.balance().balances.get(&id).copied().unwrap_or(0.0);
+                    let time = match estimate.per_item.get(&id).copied().flatten() {
+                        Some(minutes) => format_minutes(minutes),
+                        None => "Never".to_owned(),
+                    };
+                    (name, icon, amount, rate, time)
+                })
+                .collect();
+            let collator = crate::locale::get_collator();
+            rows.sort_by(|(lhs, ..), (rhs, ..)| collator.compare(lhs, rhs));
+
+            let rows = rows.into_iter().map(|(name, icon, amount, rate, time)| {
+                html! {
+                    <tr>
+                        <td class="item-name"><Icon {icon} name={name.clone()} /><span>{name}</span></td>
+                        <td class="amount">{amount.format(format).to_string()}</td>
+                        <td class="rate">{rate.format(format).to_string()}</td>
+                        <td class="time">{time}</td>
+                    </tr>
+                }
+            });
+
+            let summary = match estimate.minutes {
+                _ if cost.is_empty() => {
+                    "Nothing in this group has a known construction cost.".to_owned()
+                }
+                Some(minutes) => format!(
+                    "At your world's current production rates, it will take {} to produce all of \
+                    these materials.",
+                    format_minutes(minutes)
+                ),
+                None => "Some of these materials are not produced anywhere in your world, so you \
+                    will need to get them from storage."
+                    .to_owned(),
+            };
+
+            modals
+                .builder()
+                .title("Construction Cost")
+                .class("build-cost-report")
+                .kind(ModalOk::close())
+                .content(html! {
+                    <>
+                        <p>{summary}</p>
+                        if !cost.is_empty() {
+                            <table>
+                                <tr>
+                                    <th>{"Item"}</th>
+                                    <th>{"Needed"}</th>
+                                    <th>{"World Rate"}</th>
+                                    <th>{"Time to Produce"}</th>
+                                </tr>
+                                {for rows}
+                            </table>
+                        }
+                    </>
+                })
+                .build()
+                .persist();
+        },
+    );
+
+    html! {
+        <Button title="Construction Cost" {onclick}>
+            {material_icon("construction")}
+        </Button>
+    }
+}
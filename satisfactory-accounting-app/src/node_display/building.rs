@@ -8,59 +8,160 @@ use log::warn;
 //       http://www.apache.org/licenses/LICENSE-2.0
 use satisfactory_accounting::accounting::{
     BuildError, Building, BuildingSettings, GeneratorSettings, GeothermalSettings,
-    ManufacturerSettings, MinerSettings, PumpSettings, ResourcePurity, StationSettings,
+    ManufacturerSettings, MinerSettings, PumpSettings, ResourcePurity, StationSettings, MAX_CLOCK,
 };
-use satisfactory_accounting::database::{BuildingId, BuildingKind};
+use satisfactory_accounting::database::{BuildingId, BuildingKind, ItemIdOrPower};
 use yew::prelude::*;
 
+use crate::help::{HelpButton, HelpTopic};
+use crate::inputs::button::Button;
+use crate::inputs::toggle::MaterialCheckbox;
+use crate::material::material_icon;
 use crate::node_display::balance::NodeBalance;
 use crate::node_display::clock::ClockSpeed;
 use crate::node_display::copies::VirtualCopies;
+use crate::node_display::summary::node_element_id;
 use crate::node_display::{Msg, NodeDisplay};
+use crate::user_settings::number_format::UserConfiguredFormat;
 
 use building_type::BuildingTypeDisplay;
 use item::ItemDisplay;
+use logistics::BuildingLogistics;
 use multi_purity::MultiPurity;
+use output_transport::OutputTransport;
 use purity::Purity;
 use recipe::RecipeDisplay;
 use station_consumption::StationConsumption;
+use station_manifest::StationManifest;
+use station_traffic::StationTraffic;
+use target_rate::TargetRate;
 
 mod building_type;
 mod item;
+mod logistics;
 mod multi_purity;
+mod output_transport;
 mod purity;
 mod recipe;
 mod station_consumption;
+mod station_manifest;
+mod station_traffic;
+mod target_rate;
 
 impl NodeDisplay {
     /// Build display for a building.
     pub(super) fn view_building(&self, ctx: &Context<Self>, building: &Building) -> Html {
         let update_copies = ctx.link().callback(|copies| Msg::SetCopyCount { copies });
         let on_change_type = ctx.link().callback(|id| Msg::ChangeType { id });
-        let on_backdrive = self.supports_backdrive(building).then(|| {
+        let built = building.built;
+        let set_built = ctx
+            .link()
+            .callback(move |_| Msg::SetBuilt { built: !built });
+        let on_backdrive = (!self.read_only && self.supports_backdrive(building)).then(|| {
             ctx.link()
                 .callback(|(id, rate)| Msg::Backdrive { id, rate })
         });
+        let target_rate = on_backdrive.clone().map(|on_backdrive| {
+            html! { <TargetRate items={self.target_rate_items(ctx)} {on_backdrive} /> }
+        });
         html! {
-            <div class="NodeDisplay building">
+            <div class={classes!("NodeDisplay", "building", self.color_class(),
+                    self.contributor_class(ctx))}
+                id={node_element_id(building.id)}>
                 {self.drag_handle(ctx)}
                 <BuildingTypeDisplay id={building.building} {on_change_type} />
                 {self.view_building_settings(ctx, building)}
+                {target_rate}
                 if ctx.props().node.warning().is_none() {
-                    <NodeBalance node={&ctx.props().node} {on_backdrive} />
+                    <NodeBalance node={&ctx.props().node} {on_backdrive}
+                        share_of={ctx.props().parent_totals.clone()} />
                 }
                 <VirtualCopies copies={building.copies} {update_copies} />
                 <div class="section copy-delete">
+                    {self.move_controls(ctx)}
+                    {self.output_transport(ctx, building)}
+                    {self.logistics(ctx, building)}
+                    {self.chain_backdrive_offer(ctx)}
+                    {self.contribution_badge(ctx)}
                     if let Some(warning) = ctx.props().node.warning() {
                         {self.view_warning(warning)}
                     }
+                    if self.user_settings.track_build_progress && building.building.is_some()
+                        && !self.read_only {
+                        <label class="built" title="Built in game">
+                            <MaterialCheckbox checked={building.built} onclick={set_built} />
+                        </label>
+                    }
+                    {self.footprint_button(ctx)}
+                    {self.circuit_button(ctx)}
+                    {self.color_button(ctx)}
+                    {self.notes_button(ctx)}
                     {self.copy_button(ctx)}
+                    {self.duplicate_button(ctx)}
                     {self.delete_button(ctx)}
                 </div>
+                {self.notes_panel(ctx)}
+                {self.footprint_panel(ctx)}
             </div>
         }
     }
 
+    /// Belt or pipe chooser for extraction buildings, which warns if their output overflows it.
+    fn output_transport(&self, ctx: &Context<Self>, building: &Building) -> Html {
+        let Some(building_type) = building.building.and_then(|id| self.db.get(id)) else {
+            return html! {};
+        };
+        let Some((item, rate)) = building.settings.extraction_output(&building_type.kind) else {
+            return html! {};
+        };
+        let on_change = ctx.link().callback(|tier| Msg::ChangeOutputTier { tier });
+        html! {
+            <OutputTransport kind={self.db.transport_kind_for(item)}
+                tier={building.settings.output_tier()} {rate} {on_change} />
+        }
+    }
+
+    /// Belt and pipe chooser for each input and output of a building, which warns if the building
+    /// moves more than they can carry.
+    fn logistics(&self, ctx: &Context<Self>, building: &Building) -> Html {
+        if building.building.is_none() || ctx.props().node.warning().is_some() {
+            return html! {};
+        }
+        let balance = ctx.props().node.balance();
+        let items: Vec<_> = balance
+            .balances
+            .iter()
+            .map(|(&id, &rate)| (id, rate))
+            .collect();
+        let overflows = building.logistics_overflows(balance, &self.db);
+        let on_change = ctx
+            .link()
+            .callback(|(item, logistics)| Msg::ChangeLogistics { item, logistics });
+        html! {
+            <BuildingLogistics {items} logistics={building.logistics.clone()} {overflows}
+                {on_change} />
+        }
+    }
+
+    /// Items and power of this building which can be given a target rate, outputs first.
+    fn target_rate_items(&self, ctx: &Context<Self>) -> Vec<(ItemIdOrPower, f32)> {
+        if ctx.props().node.warning().is_some() {
+            return Vec::new();
+        }
+        let balance = ctx.props().node.balance();
+        let mut items: Vec<(ItemIdOrPower, f32)> = balance
+            .balances
+            .iter()
+            .map(|(&id, &rate)| (id.into(), rate))
+            .collect();
+        if balance.power != 0.0 {
+            items.push((ItemIdOrPower::Power, balance.power));
+        }
+        // Stable, so items keep database order within outputs and inputs.
+        items.sort_by_key(|&(_, rate)| rate < 0.0);
+        items
+    }
+
     /// Whether a building supports backdriving.
     fn supports_backdrive(&self, building: &Building) -> bool {
         let building_id = match building.building {
@@ -108,7 +209,7 @@ impl NodeDisplay {
                     self.view_pump_settings(ctx, id, building.copies, settings)
                 }
                 BuildingSettings::Geothermal(settings) => {
-                    self.view_geothermal_settings(ctx, settings)
+                    self.view_geothermal_settings(ctx, id, settings)
                 }
                 BuildingSettings::PowerConsumer => html! {},
                 BuildingSettings::Station(settings) => {
@@ -203,17 +304,73 @@ impl NodeDisplay {
                         num_pads={settings.normal_pads} on_update_pads={&on_update_pads} />
                     <MultiPurity purity={ResourcePurity::Pure}
                         num_pads={settings.pure_pads} {on_update_pads} />
+                    <HelpButton topic={HelpTopic::MultiPurity} />
                 </div>
             </>
         }
     }
 
     /// Display the settings for a geothermal plant.
-    fn view_geothermal_settings(&self, ctx: &Context<Self>, settings: &GeothermalSettings) -> Html {
+    fn view_geothermal_settings(
+        &self,
+        ctx: &Context<Self>,
+        building: BuildingId,
+        settings: &GeothermalSettings,
+    ) -> Html {
         let link = ctx.link();
-        let on_set_purity = link.callback(|purity| Msg::ChangePurity { purity });
+        let cluster = settings.geysers.is_some();
+        let toggle_cluster = link.callback(move |()| Msg::SetGeyserCluster { cluster: !cluster });
+        let toggle_title = if cluster {
+            "Use a Single Geyser"
+        } else {
+            "Count Multiple Geysers"
+        };
+        let toggle = (!self.read_only).then(|| {
+            html! {
+                <Button title={toggle_title} onclick={toggle_cluster}>
+                    {material_icon(if cluster { "filter_1" } else { "workspaces" })}
+                </Button>
+            }
+        });
+        let Some(geysers) = settings.geysers else {
+            let on_set_purity = link.callback(|purity| Msg::ChangePurity { purity });
+            return html! {
+                <div class="section multi-purity-group">
+                    <Purity purity={settings.purity} {on_set_purity} />
+                    {toggle}
+                </div>
+            };
+        };
+        let on_update_pads =
+            link.callback(|(purity, num_pads)| Msg::ChangePumpPurity { purity, num_pads });
+        let power = match self.db.get(building).map(|building| &building.kind) {
+            Some(BuildingKind::Geothermal(g)) => g.power,
+            _ => 0.0,
+        };
+        let average = settings.average_multiplier().map(|multiplier| {
+            let power_format = &self
+                .user_settings
+                .number_display
+                .balance
+                .power_format_settings;
+            html! {
+                <span class="geyser-average" title="Average power of each generator">
+                    {format!("{} MW avg", (power * multiplier).format(power_format))}
+                </span>
+            }
+        });
         html! {
-            <Purity purity={settings.purity} {on_set_purity} />
+            <div class="section multi-purity-group">
+                <MultiPurity purity={ResourcePurity::Impure}
+                    num_pads={geysers.impure} on_update_pads={&on_update_pads} />
+                <MultiPurity purity={ResourcePurity::Normal}
+                    num_pads={geysers.normal} on_update_pads={&on_update_pads} />
+                <MultiPurity purity={ResourcePurity::Pure}
+                    num_pads={geysers.pure} {on_update_pads} />
+                {average}
+                {toggle}
+                <HelpButton topic={HelpTopic::GeyserCluster} />
+            </div>
         }
     }
 
@@ -228,11 +385,27 @@ impl NodeDisplay {
         let on_change_item = link.callback(|id| Msg::ChangeItem { id });
         let update_consumption =
             link.callback(|consumption| Msg::ChangeConsumption { consumption });
+        let on_change_traffic = link.callback(|traffic| Msg::ChangeTraffic { traffic });
+        let on_change_manifest = link.callback(|manifest| Msg::ChangeManifest { manifest });
+        let station = match self.db.get(building).map(|building| &building.kind) {
+            Some(BuildingKind::Station(station)) => Some(station),
+            _ => None,
+        };
+        let derived = station.and_then(|station| settings.traffic_consumption(station, &self.db));
         html! {
             <>
                 <ItemDisplay building_id={building} item_id={settings.fuel}
                     {on_change_item} />
-                <StationConsumption consumption={settings.consumption} {update_consumption} />
+                <StationConsumption consumption={settings.consumption} {update_consumption}
+                    {derived} />
+                <div class="station-controls">
+                    if let Some(station) = station.filter(|station| !station.vehicles.is_empty()) {
+                        <StationTraffic vehicles={station.vehicles.clone()}
+                            traffic={settings.traffic.clone()} on_change={on_change_traffic} />
+                    }
+                    <StationManifest manifest={settings.manifest.clone()}
+                        on_change={on_change_manifest} />
+                </div>
             </>
         }
     }
@@ -253,10 +426,13 @@ impl NodeDisplay {
                 if maybe_building.is_none() {
                     warn!("Showing clock controls by default for unknown building {building}");
                 }
+                let max_clock = maybe_building.map_or(MAX_CLOCK, |b| b.max_clock_speed());
                 let on_update_speed = ctx
                     .link()
                     .callback(|clock_speed| Msg::ChangeClockSpeed { clock_speed });
-                Some(html! { <ClockSpeed {clock_speed} {copies} {on_update_speed} /> })
+                Some(html! {
+                    <ClockSpeed {clock_speed} {copies} {max_clock} {on_update_speed} />
+                })
             }
         }
     }
@@ -10,7 +10,7 @@ use yew::prelude::*;
 
 use crate::inputs::choose_from_list::{Choice, ChooseFromList};
 use crate::node_display::icon::Icon;
-use crate::world::use_db;
+use crate::world::{use_db, use_progression, use_read_only, Progression};
 
 #[derive(PartialEq, Properties)]
 pub struct Props {
@@ -24,6 +24,8 @@ pub struct Props {
 #[function_component]
 pub fn BuildingTypeDisplay(Props { id, on_change_type }: &Props) -> Html {
     let db = use_db();
+    let progression = use_progression();
+    let read_only = use_read_only().is_read_only();
 
     let editing = use_state_eq(|| false);
     let setter = editing.setter();
@@ -37,9 +39,10 @@ pub fn BuildingTypeDisplay(Props { id, on_change_type }: &Props) -> Html {
     );
     let on_cancelled = use_callback(setter.clone(), |(), setter| setter.set(false));
     let edit = use_callback(setter, |_, setter| setter.set(true));
+    let edit = (!read_only).then_some(edit);
 
     if *editing {
-        let choices = create_building_choices(&db);
+        let choices = create_building_choices(&db, progression);
         html! {
             <ChooseFromList<BuildingId> class="BuildingTypeDisplay" title="Building Type"
                 {choices} {on_selected} {on_cancelled} />
@@ -60,8 +63,8 @@ pub fn BuildingTypeDisplay(Props { id, on_change_type }: &Props) -> Html {
                 },
                 Some(building) => html! {
                     <div class="BuildingTypeDisplay" title="Building Type" onclick={edit}>
-                        <Icon icon={building.image.clone()}/>
-                        <span>{&building.name}</span>
+                        <Icon icon={building.image.clone()} name={building.name.clone()} />
+                        <span title={building.name.clone()}>{&building.name}</span>
                     </div>
                 },
             },
@@ -69,13 +72,15 @@ pub fn BuildingTypeDisplay(Props { id, on_change_type }: &Props) -> Html {
     }
 }
 
-fn create_building_choices(db: &Database) -> Vec<Choice<BuildingId>> {
-    db.buildings()
+fn create_building_choices(db: &Database, progression: Progression) -> Vec<Choice<BuildingId>> {
+    db.filtered(|recipe| progression.has_recipe(recipe))
+        .buildings()
+        .filter(|building| progression.has_building(building))
         .map(|building| Choice {
             id: building.id,
             name: building.name.clone().into(),
             image: html! {
-                <Icon icon={building.image.clone()}/>
+                <Icon icon={building.image.clone()} name={building.name.clone()} />
             },
         })
         .collect()
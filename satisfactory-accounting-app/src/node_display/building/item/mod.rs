@@ -11,7 +11,7 @@ use yew::prelude::*;
 
 use crate::inputs::choose_from_list::{Choice, ChooseFromList};
 use crate::node_display::icon::Icon;
-use crate::world::use_db;
+use crate::world::{use_db, use_read_only};
 
 #[derive(PartialEq, Properties)]
 pub struct Props {
@@ -33,6 +33,7 @@ pub fn ItemDisplay(
     }: &Props,
 ) -> Html {
     let db = use_db();
+    let read_only = use_read_only().is_read_only();
     let editing = use_state_eq(|| false);
     let setter = editing.setter();
 
@@ -59,7 +60,7 @@ pub fn ItemDisplay(
         }
     } else {
         // Don't allow editing if only 1 choice is available.
-        let edit = (items.len() > 1).then(|| edit);
+        let edit = (items.len() > 1 && !read_only).then(|| edit);
         match item_id {
             None => html! {
                 <div class="ItemDisplay" {title} onclick={edit}>
@@ -75,8 +76,8 @@ pub fn ItemDisplay(
                 },
                 Some(item) => html! {
                     <div class="ItemDisplay" {title} onclick={edit}>
-                        <Icon icon={item.image.clone()} />
-                        <span>{&item.name}</span>
+                        <Icon icon={item.image.clone()} name={item.name.clone()} />
+                        <span title={item.name.clone()}>{&item.name}</span>
                     </div>
                 },
             },
@@ -115,7 +116,7 @@ fn create_item_choices(db: &Database, items: &[ItemId]) -> Vec<Choice<ItemId>> {
                 id: item.id,
                 name: item.name.clone().into(),
                 image: html! {
-                    <Icon icon={item.image.clone()}/>
+                    <Icon icon={item.image.clone()} name={item.name.clone()} />
                 },
             },
             None => Choice {
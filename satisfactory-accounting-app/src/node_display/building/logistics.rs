@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use satisfactory_accounting::accounting::{Logistics, Overflow};
+use satisfactory_accounting::database::ItemId;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_read_only};
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Items the building consumes or produces, with their rates in items per minute. Consumption
+    /// is negative.
+    pub items: Vec<(ItemId, f32)>,
+    /// Belts or pipes chosen for each item.
+    pub logistics: BTreeMap<ItemId, Logistics>,
+    /// Items which move more than their belts or pipes can carry.
+    pub overflows: Vec<Overflow>,
+    /// Callback to change or clear the belts or pipes chosen for an item.
+    pub on_change: Callback<(ItemId, Option<Logistics>)>,
+}
+
+/// Button and editor for the belts and pipes feeding a building's inputs and carrying its outputs,
+/// which warns when the building moves more than they can carry.
+#[function_component]
+pub fn BuildingLogistics(
+    Props {
+        items,
+        logistics,
+        overflows,
+        on_change,
+    }: &Props,
+) -> Html {
+    let db = use_db();
+    let read_only = use_read_only().is_read_only();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let suffix = balance_settings.rate_unit.suffix();
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+
+    if items.is_empty() || db.transports().next().is_none() {
+        return html! {};
+    }
+
+    let item_name = |id: ItemId| match db.get(id) {
+        Some(item) => item.name.to_string(),
+        None => format!("Unknown Item {id}"),
+    };
+    let title = if !overflows.is_empty() {
+        overflows
+            .iter()
+            .map(|overflow| {
+                format!(
+                    "{} moves {}{suffix}, but its belts or pipes only carry {}{suffix}",
+                    item_name(overflow.item),
+                    balance_settings.format_rate(overflow.rate),
+                    balance_settings.format_rate(overflow.capacity),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else if logistics.is_empty() {
+        "Choose the belts and pipes feeding this building".to_owned()
+    } else {
+        "Belts and pipes feeding this building".to_owned()
+    };
+
+    let row = |&(id, rate): &(ItemId, f32)| {
+        let kind = db.transport_kind_for(id);
+        let chosen = logistics.get(&id).copied();
+        let overflow = overflows.iter().any(|overflow| overflow.item == id);
+        let (icon, name) = match db.get(id) {
+            Some(item) => (Some(item.image.clone()), item.name.clone()),
+            None => (None, format!("Unknown Item {id}").into()),
+        };
+        let choice = |tier: Option<u8>, label: Html, title: String| {
+            let onclick = on_change.reform(move |()| {
+                let logistics = tier.map(|tier| Logistics {
+                    tier,
+                    count: chosen.map_or(1, |chosen| chosen.count),
+                });
+                (id, logistics)
+            });
+            let class = classes!(
+                "tier",
+                (tier == chosen.map(|chosen| chosen.tier)).then_some("selected")
+            );
+            html! {
+                <Button {class} {title} {onclick}>{label}</Button>
+            }
+        };
+        let tiers = db
+            .transports()
+            .filter(|transport| transport.kind == kind)
+            .map(|transport| {
+                let title = format!("{} ({}/min)", transport.name, transport.throughput);
+                let label = html! {
+                    <Icon icon={transport.image.clone()} name={transport.name.clone()} />
+                };
+                choice(Some(transport.tier), label, title)
+            });
+        let count = chosen.map(|chosen| {
+            let on_commit = on_change.reform(move |edit_text: AttrValue| {
+                let count = edit_text.parse::<u32>().unwrap_or(chosen.count).max(1);
+                (id, Some(Logistics { count, ..chosen }))
+            });
+            html! {
+                <ClickEdit value={AttrValue::from(chosen.count.to_string())}
+                    title="Number of belts or pipes" prefix={html! {"×"}} {on_commit} />
+            }
+        });
+        html! {
+            <div class={classes!("logistics-entry", overflow.then_some("overflow"))}>
+                <Icon {icon} name={name.clone()} />
+                <span class="item-name">{name}</span>
+                <span class="rate">
+                    {balance_settings.format_rate(rate.abs()).to_string()}{suffix}
+                    {if rate < 0.0 { " in" } else { " out" }}
+                </span>
+                {choice(None, material_icon("block"), "No limit".to_owned())}
+                {for tiers}
+                {count}
+            </div>
+        }
+    };
+
+    let class = classes!(
+        "BuildingLogistics",
+        (!overflows.is_empty()).then_some("overflow"),
+        (!logistics.is_empty()).then_some("active"),
+    );
+    html! {
+        <div {class}>
+            <Button {title} onclick={toggle} disabled={read_only}>
+                if overflows.is_empty() {
+                    {material_icon("swap_horiz")}
+                } else {
+                    {material_icon("warning")}
+                }
+            </Button>
+            if *open && !read_only {
+                <div class="logistics-editor">
+                    {for items.iter().map(row)}
+                </div>
+            }
+        </div>
+    }
+}
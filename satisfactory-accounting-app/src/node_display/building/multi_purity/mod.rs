@@ -10,6 +10,7 @@ use yew::prelude::*;
 
 use crate::inputs::clickedit::ClickEdit;
 use crate::node_display::building::purity::purity_icon;
+use crate::world::use_read_only;
 
 #[derive(Debug, PartialEq, Properties)]
 pub struct Props {
@@ -23,6 +24,7 @@ pub struct Props {
 
 #[function_component]
 pub fn MultiPurity(props: &Props) -> Html {
+    let read_only = use_read_only().is_read_only();
     let on_commit = use_callback(
         (props.purity, props.on_update_pads.clone()),
         |edit_text: AttrValue, &(purity, ref on_update_pads)| {
@@ -40,6 +42,7 @@ pub fn MultiPurity(props: &Props) -> Html {
     let value: &AttrValue = &*use_memo(props.num_pads, |num_pads| num_pads.to_string().into());
 
     html! {
-        <ClickEdit {value} class="MultiPurity" {title} prefix={prefix.clone()} {on_commit} />
+        <ClickEdit {value} class="MultiPurity" {title} prefix={prefix.clone()} {on_commit}
+            {read_only} />
     }
 }
@@ -0,0 +1,110 @@
+use satisfactory_accounting::database::TransportKind;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_read_only};
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Kind of transport which carries the building's output.
+    pub kind: TransportKind,
+    /// Tier of the transport chosen for the output, if any.
+    pub tier: Option<u8>,
+    /// Items per minute of the building's busiest output.
+    pub rate: f32,
+    /// Callback to change the chosen tier.
+    pub on_change: Callback<Option<u8>>,
+}
+
+/// Chooser for the belt or pipe tier carrying an extraction building's output, which warns when
+/// the output is more than the transport can carry.
+#[function_component]
+pub fn OutputTransport(
+    &Props {
+        kind,
+        tier,
+        rate,
+        ref on_change,
+    }: &Props,
+) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let suffix = balance_settings.rate_suffix();
+    let read_only = use_read_only().is_read_only();
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+
+    let transports: Vec<_> = db
+        .transports()
+        .filter(|transport| transport.kind == kind)
+        .collect();
+    if transports.is_empty() {
+        return html! {};
+    }
+
+    let selected = tier.and_then(|tier| db.transport(kind, tier));
+    let overflow = selected.filter(|transport| rate > transport.throughput + 1e-3);
+    let title = match (selected, overflow) {
+        (Some(transport), Some(_)) => format!(
+            "Each output makes {}{suffix}, but {} only carries {}{suffix}",
+            balance_settings.format_rate(rate),
+            transport.name,
+            balance_settings.format_rate(transport.throughput),
+        ),
+        (Some(transport), None) => format!(
+            "Output carried by {} ({}{suffix})",
+            transport.name,
+            balance_settings.format_rate(transport.throughput),
+        ),
+        (None, _) => match kind {
+            TransportKind::Belt => "Choose the belt carrying the output".to_owned(),
+            TransportKind::Pipeline => "Choose the pipe carrying the output".to_owned(),
+        },
+    };
+
+    let choice = |tier: Option<u8>, label: Html| {
+        let onclick = {
+            let on_change = on_change.clone();
+            let open = open.clone();
+            Callback::from(move |()| {
+                open.set(false);
+                on_change.emit(tier);
+            })
+        };
+        let class = classes!(
+            "choice",
+            (tier == selected.map(|t| t.tier)).then_some("selected")
+        );
+        html! {
+            <Button {class} {onclick}>{label}</Button>
+        }
+    };
+
+    html! {
+        <div class={classes!("OutputTransport", overflow.is_some().then_some("overflow"))}>
+            <Button {title} onclick={toggle} disabled={read_only}>
+                if overflow.is_some() {
+                    {material_icon("warning")}
+                } else if let Some(transport) = selected {
+                    <Icon icon={transport.image.clone()} name={transport.name.clone()} />
+                } else {
+                    {material_icon("linear_scale")}
+                }
+            </Button>
+            if *open && !read_only {
+                <div class="transport-editor">
+                    {choice(None, html! { <span>{"No limit"}</span> })}
+                    {for transports.iter().map(|transport| choice(Some(transport.tier), html! {<>
+                        <Icon icon={transport.image.clone()} name={transport.name.clone()} />
+                        <span>{&transport.name}</span>
+                        <span class="throughput">{format!("{}{suffix}", balance_settings.format_rate(transport.throughput))}</span>
+                    </>}))}
+                </div>
+            }
+        </div>
+    }
+}
@@ -8,7 +8,9 @@
 use satisfactory_accounting::accounting::ResourcePurity;
 use yew::prelude::*;
 
+use crate::help::{HelpButton, HelpTopic};
 use crate::inputs::choose_from_list::{Choice, ChooseFromList};
+use crate::world::use_read_only;
 
 #[derive(PartialEq, Properties)]
 pub struct Props {
@@ -25,6 +27,7 @@ pub fn Purity(
         on_set_purity,
     }: &Props,
 ) -> Html {
+    let read_only = use_read_only().is_read_only();
     let editing = use_state_eq(|| false);
     let setter = editing.setter();
 
@@ -37,6 +40,7 @@ pub fn Purity(
     );
     let on_cancelled = use_callback(setter.clone(), |(), setter| setter.set(false));
     let edit = use_callback(setter, |_, setter| setter.set(true));
+    let edit = (!read_only).then_some(edit);
 
     let choices = create_purity_choices();
 
@@ -50,6 +54,7 @@ pub fn Purity(
             <div class="Purity" onclick={edit} title="Resource Node Purity">
                 {purity_icon(*purity)}
                 <span>{purity.name()}</span>
+                <HelpButton topic={HelpTopic::Purity} />
             </div>
         }
     }
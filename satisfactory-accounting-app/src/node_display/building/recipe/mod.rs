@@ -11,7 +11,7 @@ use yew::prelude::*;
 
 use crate::inputs::choose_from_list::{Choice, ChooseFromList};
 use crate::node_display::icon::Icon;
-use crate::world::use_db;
+use crate::world::{use_db, use_progression, use_read_only, Progression};
 
 #[derive(PartialEq, Properties)]
 pub struct Props {
@@ -33,6 +33,8 @@ pub fn RecipeDisplay(
     }: &Props,
 ) -> Html {
     let db = use_db();
+    let progression = use_progression();
+    let read_only = use_read_only().is_read_only();
     let editing = use_state_eq(|| false);
     let setter = editing.setter();
 
@@ -52,7 +54,7 @@ pub fn RecipeDisplay(
     };
 
     if *editing {
-        let choices = create_recipe_choices(&db, recipes);
+        let choices = create_recipe_choices(&db, recipes, progression);
 
         html! {
             <ChooseFromList<RecipeId> class="RecipeDisplay" title="Recipe"
@@ -60,7 +62,7 @@ pub fn RecipeDisplay(
         }
     } else {
         // Don't allow editing if only 1 choice is available.
-        let edit = (recipes.len() > 1).then(move || edit);
+        let edit = (recipes.len() > 1 && !read_only).then(move || edit);
         match recipe_id {
             None => html! {
                 <div class="RecipeDisplay" title="Recipe" onclick={edit}>
@@ -76,8 +78,8 @@ pub fn RecipeDisplay(
                 },
                 Some(recipe) => html! {
                     <div class="RecipeDisplay" title="Recipe" onclick={edit}>
-                        <Icon icon={recipe.image.clone()} />
-                        <span>{&recipe.name}</span>
+                        <Icon icon={recipe.image.clone()} name={recipe.name.clone()} />
+                        <span title={recipe.name.clone()}>{&recipe.name}</span>
                     </div>
                 },
             },
@@ -104,15 +106,22 @@ fn look_up_recipes(db: &Database, building_id: BuildingId) -> Option<&[RecipeId]
     }
 }
 
-fn create_recipe_choices(db: &Database, recipes: &[RecipeId]) -> Vec<Choice<RecipeId>> {
+fn create_recipe_choices(
+    db: &Database,
+    recipes: &[RecipeId],
+    progression: Progression,
+) -> Vec<Choice<RecipeId>> {
+    let unlocked = db.filtered(|recipe| progression.has_recipe(recipe));
     recipes
         .iter()
+        // Unknown recipes are kept so they can still be shown, only known locked ones are hidden.
+        .filter(|&&recipe_id| db.get(recipe_id).is_none() || unlocked.has_recipe(recipe_id))
         .map(|&recipe_id| match db.get(recipe_id) {
             Some(recipe) => Choice {
                 id: recipe.id,
                 name: recipe.name.clone().into(),
                 image: html! {
-                    <Icon icon={recipe.image.clone()} />
+                    <Icon icon={recipe.image.clone()} name={recipe.name.clone()} />
                 },
             },
             None => Choice {
@@ -8,6 +8,8 @@
 use yew::prelude::*;
 
 use crate::inputs::clickedit::ClickEdit;
+use crate::user_settings::use_user_settings;
+use crate::world::use_read_only;
 
 #[derive(Debug, PartialEq, Properties)]
 pub struct Props {
@@ -15,25 +17,42 @@ pub struct Props {
     pub consumption: f32,
     /// Callback to change the actual value.
     pub update_consumption: Callback<f32>,
+    /// Consumption derived from vehicle traffic, which replaces the configured value.
+    #[prop_or_default]
+    pub derived: Option<f32>,
 }
 
 #[function_component]
 pub fn StationConsumption(props: &Props) -> Html {
+    let read_only = use_read_only().is_read_only();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
     let on_commit = use_callback(
-        props.update_consumption.clone(),
-        |edit_text: AttrValue, update_consumption| {
+        (props.update_consumption.clone(), balance_settings.rate_unit),
+        |edit_text: AttrValue, (update_consumption, rate_unit)| {
             if let Ok(value) = edit_text.parse::<f32>() {
-                update_consumption.emit(value.max(0.0));
+                update_consumption.emit(rate_unit.to_per_minute(value).max(0.0));
             }
         },
     );
 
-    let value: AttrValue = props.consumption.to_string().into();
     let prefix = html! {
         <span class="material-icons">{"trending_down"}</span>
     };
+    if let Some(derived) = props.derived {
+        return html! {
+            <div class="StationConsumption derived" title="Fuel Consumption from Vehicle Traffic">
+                {prefix}
+                <span class="current-consumption">{balance_settings.format_rate(derived).to_string()}</span>
+            </div>
+        };
+    }
+    let value: AttrValue = balance_settings
+        .rate_in_unit(props.consumption)
+        .to_string()
+        .into();
     html! {
         <ClickEdit {value} class="StationConsumption" title="Fuel Consumption of Fueled Vehicles"
-            {on_commit} {prefix} />
+            {on_commit} {prefix} {read_only} />
     }
 }
@@ -0,0 +1,192 @@
+use satisfactory_accounting::accounting::{FreightDirection, ManifestEntry};
+use satisfactory_accounting::database::{Database, ItemId};
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::choose_from_list::{Choice, ChooseFromList};
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_read_only};
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Items currently moved through the station.
+    pub manifest: Vec<ManifestEntry>,
+    /// Callback to change the manifest.
+    pub on_change: Callback<Vec<ManifestEntry>>,
+}
+
+/// Button and editor for the freight a station loads and unloads.
+#[function_component]
+pub fn StationManifest(
+    Props {
+        manifest,
+        on_change,
+    }: &Props,
+) -> Html {
+    let db = use_db();
+    let read_only = use_read_only().is_read_only();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let rate_unit = balance_settings.rate_unit;
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+    // Index of the entry whose item is being chosen, if any.
+    let choosing = use_state_eq(|| None::<usize>);
+
+    let title = if manifest.is_empty() {
+        "Add freight moved through the station".to_owned()
+    } else {
+        manifest
+            .iter()
+            .map(|entry| {
+                let name = match entry.item.and_then(|id| db.get(id)) {
+                    Some(item) => item.name.to_string(),
+                    None => "No item".to_owned(),
+                };
+                let arrow = match entry.direction {
+                    FreightDirection::Incoming => "in",
+                    FreightDirection::Outgoing => "out",
+                };
+                let rate = balance_settings.format_rate(entry.rate);
+                format!("{name}: {rate}{} {arrow}", rate_unit.suffix())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let row = |(idx, entry): (usize, &ManifestEntry)| {
+        let item = if *choosing == Some(idx) {
+            let on_selected = {
+                let manifest = manifest.clone();
+                let on_change = on_change.clone();
+                let choosing = choosing.clone();
+                Callback::from(move |id: ItemId| {
+                    choosing.set(None);
+                    let mut manifest = manifest.clone();
+                    manifest[idx].item = Some(id);
+                    on_change.emit(manifest);
+                })
+            };
+            let on_cancelled = {
+                let choosing = choosing.clone();
+                Callback::from(move |()| choosing.set(None))
+            };
+            html! {
+                <ChooseFromList<ItemId> class="manifest-item" title="Freight Item"
+                    choices={item_choices(&db)} {on_selected} {on_cancelled} />
+            }
+        } else {
+            let onclick = {
+                let choosing = choosing.clone();
+                Callback::from(move |()| choosing.set(Some(idx)))
+            };
+            let label = match entry.item.map(|id| (id, db.get(id))) {
+                Some((_, Some(item))) => html! {<>
+                    <Icon icon={item.image.clone()} name={item.name.clone()} />
+                    <span>{&item.name}</span>
+                </>},
+                Some((id, None)) => html! {<>
+                    <Icon />
+                    <span>{"Unknown Item "}{id}</span>
+                </>},
+                None => html! { <span>{"select item"}</span> },
+            };
+            html! {
+                <Button class="manifest-item" title="Freight Item" {onclick}>
+                    {label}
+                </Button>
+            }
+        };
+        let (direction_icon, direction_title) = match entry.direction {
+            FreightDirection::Incoming => ("file_download", "Unloaded here"),
+            FreightDirection::Outgoing => ("file_upload", "Loaded here"),
+        };
+        let reverse = {
+            let manifest = manifest.clone();
+            on_change.reform(move |()| {
+                let mut manifest = manifest.clone();
+                manifest[idx].direction = manifest[idx].direction.reversed();
+                manifest
+            })
+        };
+        let on_commit = {
+            let manifest = manifest.clone();
+            let on_change = on_change.clone();
+            Callback::from(move |edit_text: AttrValue| {
+                if let Ok(rate) = edit_text.parse::<f32>() {
+                    let mut manifest = manifest.clone();
+                    manifest[idx].rate = rate_unit.to_per_minute(rate).max(0.0);
+                    on_change.emit(manifest);
+                }
+            })
+        };
+        let delete = {
+            let manifest = manifest.clone();
+            on_change.reform(move |()| {
+                let mut manifest = manifest.clone();
+                manifest.remove(idx);
+                manifest
+            })
+        };
+        html! {
+            <div class="manifest-entry">
+                {item}
+                <Button title={direction_title} onclick={reverse}>
+                    {material_icon(direction_icon)}
+                </Button>
+                <ClickEdit value={AttrValue::from(rate_unit.convert(entry.rate).to_string())}
+                    title={format!("Items {}", rate_unit.name().to_lowercase())} {on_commit} />
+                <Button class="red" title="Remove" onclick={delete}>
+                    {material_icon("delete")}
+                </Button>
+            </div>
+        }
+    };
+
+    let add = {
+        let manifest = manifest.clone();
+        on_change.reform(move |()| {
+            let mut manifest = manifest.clone();
+            manifest.push(ManifestEntry::default());
+            manifest
+        })
+    };
+
+    html! {
+        <div class="StationManifest">
+            <Button class={classes!((!manifest.is_empty()).then_some("active"))} {title}
+                onclick={toggle} disabled={read_only}>
+                {material_icon("inventory_2")}
+            </Button>
+            if *open && !read_only {
+                <div class="manifest-editor">
+                    {for manifest.iter().enumerate().map(row)}
+                    <Button class="green" title="Add Freight" onclick={add}>
+                        {material_icon("add")}
+                        <span>{"Add Freight"}</span>
+                    </Button>
+                </div>
+            }
+        </div>
+    }
+}
+
+/// Build the list of every item in the database to choose freight from, sorted by name.
+fn item_choices(db: &Database) -> Vec<Choice<ItemId>> {
+    let collator = crate::locale::get_collator();
+    let mut items: Vec<_> = db.items().collect();
+    items.sort_by(|lhs, rhs| collator.compare(&lhs.name, &rhs.name));
+    items
+        .into_iter()
+        .map(|item| Choice {
+            id: item.id,
+            name: item.name.clone().into(),
+            image: html! {
+                <Icon icon={item.image.clone()} name={item.name.clone()} />
+            },
+        })
+        .collect()
+}
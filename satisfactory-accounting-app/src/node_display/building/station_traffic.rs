@@ -0,0 +1,118 @@
+use satisfactory_accounting::accounting::VehicleTraffic;
+use satisfactory_accounting::database::Vehicle;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::world::use_read_only;
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Vehicles which refuel at the station.
+    pub vehicles: Vec<Vehicle>,
+    /// Currently configured vehicle traffic, if any.
+    pub traffic: Option<VehicleTraffic>,
+    /// Callback to change the vehicle traffic.
+    pub on_change: Callback<Option<VehicleTraffic>>,
+}
+
+/// Button and editor for deriving a station's fuel consumption from the vehicles using it.
+#[function_component]
+pub fn StationTraffic(
+    Props {
+        vehicles,
+        traffic,
+        on_change,
+    }: &Props,
+) -> Html {
+    let read_only = use_read_only().is_read_only();
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+
+    let title = match traffic {
+        Some(traffic) => format!(
+            "{} \u{00d7} {}, {}s round trip",
+            traffic.count, traffic.vehicle, traffic.round_trip
+        ),
+        None => "Compute fuel consumption from vehicle traffic".to_owned(),
+    };
+
+    let choice = |vehicle: Option<&Vehicle>| {
+        let selected = match (vehicle, traffic) {
+            (Some(vehicle), Some(traffic)) => *vehicle.name == *traffic.vehicle,
+            (None, None) => true,
+            _ => false,
+        };
+        let new_traffic = vehicle.map(|vehicle| match traffic {
+            Some(traffic) => VehicleTraffic {
+                vehicle: vehicle.name.clone().into(),
+                ..traffic.clone()
+            },
+            None => VehicleTraffic::new(vehicle.name.clone().into()),
+        });
+        let onclick = on_change.reform(move |()| new_traffic.clone());
+        let label = match vehicle {
+            Some(vehicle) => html! {<>
+                <Icon icon={vehicle.image.clone()} name={vehicle.name.clone()} />
+                <span>{&vehicle.name}</span>
+            </>},
+            None => html! { <span>{"Manual rate"}</span> },
+        };
+        html! {
+            <Button class={classes!("choice", selected.then_some("selected"))} {onclick}>
+                {label}
+            </Button>
+        }
+    };
+
+    let fields = traffic.as_ref().map(|traffic| {
+        let field = |title: &'static str, value: String, update: fn(&mut VehicleTraffic, f32)| {
+            let on_commit = {
+                let on_change = on_change.clone();
+                let traffic = traffic.clone();
+                Callback::from(move |edit_text: AttrValue| {
+                    if let Ok(value) = edit_text.parse::<f32>() {
+                        let mut traffic = traffic.clone();
+                        update(&mut traffic, value.max(0.0));
+                        on_change.emit(Some(traffic));
+                    }
+                })
+            };
+            html! {
+                <label class="field">
+                    <span>{title}</span>
+                    <ClickEdit {read_only} value={AttrValue::from(value)} {title} {on_commit} />
+                </label>
+            }
+        };
+        html! {<>
+            {field("Vehicles", traffic.count.to_string(), |traffic, value| {
+                traffic.count = value.round() as u32;
+            })}
+            {field("Round trip (s)", traffic.round_trip.to_string(), |traffic, value| {
+                traffic.round_trip = value;
+            })}
+            {field("Docked (s)", traffic.docked.to_string(), |traffic, value| {
+                traffic.docked = value;
+            })}
+        </>}
+    });
+
+    html! {
+        <div class="StationTraffic">
+            <Button class={classes!(traffic.is_some().then_some("active"))} {title}
+                onclick={toggle} disabled={read_only}>
+                {material_icon("local_shipping")}
+            </Button>
+            if *open && !read_only {
+                <div class="traffic-editor">
+                    {choice(None)}
+                    {for vehicles.iter().map(|vehicle| choice(Some(vehicle)))}
+                    {fields}
+                </div>
+            }
+        </div>
+    }
+}
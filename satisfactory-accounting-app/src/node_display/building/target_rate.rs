@@ -0,0 +1,132 @@
+use satisfactory_accounting::database::ItemIdOrPower;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::use_db;
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Items and power the building consumes or produces, with their rates in items per minute or
+    /// MW, outputs first. Consumption is negative.
+    pub items: Vec<(ItemIdOrPower, f32)>,
+    /// Callback to backdrive the building to the given rate of an item, in items per minute or MW.
+    pub on_backdrive: Callback<(ItemIdOrPower, f32)>,
+}
+
+/// Button and editor to type the rate a building should produce or consume of one of its items,
+/// which backdrives the building the same way as editing its balance.
+#[function_component]
+pub fn TargetRate(
+    Props {
+        items,
+        on_backdrive,
+    }: &Props,
+) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+    let selected = use_state_eq(|| None::<ItemIdOrPower>);
+
+    // Default to the first output if nothing was chosen or the chosen item is gone.
+    let current = selected
+        .and_then(|selected| items.iter().find(|(id, _)| *id == selected))
+        .or_else(|| items.first())
+        .copied();
+    let on_commit = use_callback(
+        (
+            on_backdrive.clone(),
+            open.clone(),
+            current.map(|(target, _)| target),
+            balance_settings.rate_unit,
+        ),
+        |edit_text: AttrValue, (on_backdrive, open, target, rate_unit)| {
+            let (Some(target), Ok(value)) = (*target, edit_text.trim().parse::<f32>()) else {
+                return;
+            };
+            let value = match target {
+                ItemIdOrPower::Power => value,
+                ItemIdOrPower::Item(_) => rate_unit.to_per_minute(value),
+            };
+            on_backdrive.emit((target, value));
+            open.set(false);
+        },
+    );
+    let Some((target, rate)) = current else {
+        return html! {};
+    };
+
+    let name_and_icon = |id: ItemIdOrPower| -> (AttrValue, Option<AttrValue>) {
+        match id {
+            ItemIdOrPower::Power => ("Power".into(), None),
+            ItemIdOrPower::Item(id) => match db.get(id) {
+                Some(item) => (item.name.clone().into(), Some(item.image.clone().into())),
+                None => (format!("Unknown Item {id}").into(), None),
+            },
+        }
+    };
+    let choice = |&(id, _): &(ItemIdOrPower, f32)| {
+        let (name, icon) = name_and_icon(id);
+        let onclick = {
+            let selected = selected.clone();
+            Callback::from(move |()| selected.set(Some(id)))
+        };
+        let class = classes!("choice", (id == target).then_some("selected"));
+        html! {
+            <Button {class} title={name.clone()} {onclick}>
+                if id == ItemIdOrPower::Power {
+                    {material_icon("bolt")}
+                } else {
+                    <Icon {icon} {name} />
+                }
+            </Button>
+        }
+    };
+
+    // Item rates are entered in the configured unit, power is always in MW.
+    let rate_unit = match target {
+        ItemIdOrPower::Power => None,
+        ItemIdOrPower::Item(_) => Some(balance_settings.rate_unit),
+    };
+    let shown = rate_unit.map_or(rate, |unit| unit.convert(rate)).abs();
+    let rounded_value: AttrValue = match target {
+        ItemIdOrPower::Power => shown
+            .format(&balance_settings.power_format_settings)
+            .to_string(),
+        ItemIdOrPower::Item(_) => balance_settings.format_rate(shown).to_string(),
+    }
+    .into();
+    let suffix = match rate_unit {
+        Some(unit) => unit.suffix(),
+        None => " MW",
+    };
+    let (name, _) = name_and_icon(target);
+    let direction = if rate < 0.0 { "in" } else { "out" };
+
+    html! {
+        <div class="TargetRate">
+            <Button title="Set a Target Rate" onclick={toggle}>
+                {material_icon("track_changes")}
+            </Button>
+            if *open {
+                <div class="target-editor">
+                    <div class="choices">
+                        {for items.iter().map(choice)}
+                    </div>
+                    <div class="target-entry">
+                        <span class="item-name">{name}</span>
+                        <ClickEdit value={AttrValue::from(shown.to_string())} {rounded_value}
+                            title="Target rate" {on_commit}
+                            suffix={html! { <span>{suffix}{" "}{direction}</span> }} />
+                    </div>
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use log::info;
+use satisfactory_accounting::accounting::{Balance, Node};
+use satisfactory_accounting::database::{ItemId, ItemIdOrPower};
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::{Msg, NodeDisplay};
+
+/// Changes in consumption smaller than this are ignored when rescaling suppliers.
+const EPSILON: f32 = 1e-4;
+
+impl NodeDisplay {
+    /// Offer to rescale the buildings feeding this one, if it was just backdriven and that changed
+    /// what it consumes.
+    pub(super) fn chain_backdrive_offer(&self, ctx: &Context<Self>) -> Html {
+        let Some((before, after)) = &self.backdriven else {
+            return html! {};
+        };
+        if self.read_only
+            || ctx.props().chain_backdrive.is_none()
+            || *after != ctx.props().node
+            || changed_consumption(before.balance(), after.balance())
+                .next()
+                .is_none()
+        {
+            return html! {};
+        }
+        let chain = ctx.link().callback(|()| Msg::ChainBackdrive);
+        let dismiss = ctx.link().callback(|()| Msg::DismissChainBackdrive);
+        html! {
+            <span class="chain-backdrive">
+                <Button class="green" onclick={chain}
+                    title="Also rescale the buildings in this group which feed this one">
+                    {material_icon("device_hub")}
+                </Button>
+                <Button title="Keep the buildings feeding this one as they are" onclick={dismiss}>
+                    {material_icon("close")}
+                </Button>
+            </span>
+        }
+    }
+
+    /// Rescale the buildings among `children` which supply the child at `idx`, after it was
+    /// backdriven from `before`, so their output follows the change in what it consumes.
+    ///
+    /// Walks upstream through the inputs of each rescaled supplier in turn. Each building is
+    /// changed at most once, so loops in the recipe graph terminate. Only buildings directly in
+    /// the group are rescaled; subgroups are left alone.
+    pub(super) fn chain_backdrive(
+        &self,
+        children: &[Node],
+        idx: usize,
+        before: &Node,
+    ) -> Vec<Node> {
+        let mut children = children.to_vec();
+        let mut visited = HashSet::from([idx]);
+        let mut queue = VecDeque::from([(idx, before.clone())]);
+        while let Some((idx, before)) = queue.pop_front() {
+            let after = children[idx].balance().clone();
+            for (item, delta) in changed_consumption(before.balance(), &after) {
+                let suppliers: Vec<(usize, f32)> = children
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, node)| !visited.contains(i) && node.building().is_some())
+                    .filter_map(|(i, node)| {
+                        let rate = node.balance().balances.get(&item).copied()?;
+                        (rate > 0.0).then_some((i, rate))
+                    })
+                    .collect();
+                let supplied: f32 = suppliers.iter().map(|&(_, rate)| rate).sum();
+                if supplied <= 0.0 {
+                    continue;
+                }
+                let scale = ((supplied + delta) / supplied).max(0.0);
+                info!(
+                    "Chain backdrive {item}: {delta} more consumed, scaling suppliers by {scale}"
+                );
+                for (i, rate) in suppliers {
+                    let supplier = children[i].clone();
+                    if let Some(rescaled) =
+                        self.backdrive(&supplier, ItemIdOrPower::Item(item), rate * scale)
+                    {
+                        children[i] = rescaled;
+                        visited.insert(i);
+                        queue.push_back((i, supplier));
+                    }
+                }
+            }
+        }
+        children
+    }
+}
+
+/// Items whose consumption differs between two balances, with how much more is consumed after.
+fn changed_consumption<'a>(
+    before: &'a Balance,
+    after: &'a Balance,
+) -> impl Iterator<Item = (ItemId, f32)> + 'a {
+    let consumed = |balance: &Balance, item: &ItemId| {
+        -balance
+            .balances
+            .get(item)
+            .copied()
+            .unwrap_or_default()
+            .min(0.0)
+    };
+    before
+        .balances
+        .keys()
+        .chain(after.balances.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(move |item| (*item, consumed(after, item) - consumed(before, item)))
+        .filter(|&(_, delta)| delta.abs() > EPSILON)
+}
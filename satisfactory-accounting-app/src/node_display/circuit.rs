@@ -0,0 +1,115 @@
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::material_icon;
+use crate::node_display::{Msg, NodeDisplay};
+
+impl NodeDisplay {
+    /// Button to choose the power circuit this node is on.
+    pub(super) fn circuit_button(&self, ctx: &Context<Self>) -> Html {
+        if self.read_only {
+            return html! {};
+        }
+        let on_change = ctx
+            .link()
+            .callback(|(circuit, power_storages)| Msg::SetCircuit {
+                circuit,
+                power_storages,
+            });
+        html! {
+            <PowerCircuitPicker circuit={self.meta.circuit.clone()}
+                power_storages={self.meta.power_storages}
+                circuits={self.metas.circuit_names()} {on_change} />
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+struct Props {
+    /// Name of the circuit the node is assigned to, or empty if it uses its parent's circuit.
+    circuit: AttrValue,
+    /// Number of power storages kept with the node.
+    power_storages: u32,
+    /// Names of every circuit in the world.
+    circuits: Vec<AttrValue>,
+    /// Callback to change the circuit and power storages.
+    on_change: Callback<(AttrValue, u32)>,
+}
+
+/// Button which opens an editor to choose the power circuit of a node.
+#[function_component]
+fn PowerCircuitPicker(
+    Props {
+        circuit,
+        power_storages,
+        circuits,
+        on_change,
+    }: &Props,
+) -> Html {
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |_, open| open.set(!**open));
+    let power_storages = *power_storages;
+
+    let title = if circuit.is_empty() {
+        "Power Circuit".to_owned()
+    } else {
+        format!("Power Circuit: {circuit}")
+    };
+
+    let choose = |choice: AttrValue| {
+        let open = open.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |()| {
+            open.set(false);
+            on_change.emit((choice.clone(), power_storages));
+        })
+    };
+    let rename = {
+        let on_change = on_change.clone();
+        Callback::from(move |name: AttrValue| {
+            on_change.emit((name.trim().to_owned().into(), power_storages));
+        })
+    };
+    let set_power_storages = {
+        let on_change = on_change.clone();
+        let circuit = circuit.clone();
+        Callback::from(move |count: AttrValue| {
+            if let Ok(count) = count.parse::<u32>() {
+                on_change.emit((circuit.clone(), count));
+            }
+        })
+    };
+
+    html! {
+        <span class="PowerCircuitPicker">
+            <Button class={classes!((!circuit.is_empty()).then_some("active"))} {title}
+                onclick={toggle}>
+                {material_icon("electrical_services")}
+            </Button>
+            if *open {
+                <span class="circuit-editor">
+                    <ClickEdit value={circuit.clone()} title="Circuit Name"
+                        prefix={html! {<span class="label">{"Circuit:"}</span>}}
+                        on_commit={rename} />
+                    {for circuits.iter().filter(|name| *name != circuit).map(|name| html! {
+                        <Button class="circuit-choice" title={format!("Move to {name}")}
+                            onclick={choose(name.clone())}>
+                            {name.clone()}
+                        </Button>
+                    })}
+                    if !circuit.is_empty() {
+                        <Button class="circuit-choice" title="Use the parent's circuit"
+                            onclick={choose(AttrValue::default())}>
+                            {material_icon("link_off")}
+                            <span>{"Inherit"}</span>
+                        </Button>
+                    }
+                    <ClickEdit value={AttrValue::from(power_storages.to_string())}
+                        title="Power Storages" on_commit={set_power_storages}
+                        prefix={html! {<span class="label">{"Power Storages:"}</span>}} />
+                </span>
+            }
+        </span>
+    }
+}
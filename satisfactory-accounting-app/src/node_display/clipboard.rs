@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use satisfactory_accounting::accounting::Node;
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::modal::{use_modal_dispatcher, BinaryChoice};
+use crate::world::{
+    use_clipboard, use_db, use_db_controller, use_world_dispatcher, use_world_list,
+    ClipboardContent, NodeMetas,
+};
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Group to copy to the clipboard.
+    pub node: Node,
+    /// Callback to add the pasted node as a child of the group.
+    pub on_paste: Callback<Node>,
+}
+
+/// Buttons to copy a group to the clipboard shared by all worlds, or paste the clipboard into a
+/// group.
+#[function_component]
+pub fn ClipboardButtons(Props { node, on_paste }: &Props) -> Html {
+    let db = use_db();
+    let current_db = use_db_controller().current_selector();
+    let clipboard = use_clipboard();
+    let metas = use_context::<NodeMetas>()
+        .expect("ClipboardButtons can only be used from within a child of the WorldManager");
+    let world_name = use_world_list()
+        .get_selected()
+        .map(|world| world.name.clone())
+        .unwrap_or_default();
+    let world_dispatcher = use_world_dispatcher();
+    let modals = use_modal_dispatcher();
+
+    let copy = use_callback(
+        (
+            node.clone(),
+            current_db,
+            metas,
+            world_name,
+            clipboard.clone(),
+        ),
+        |(), (node, current_db, metas, world_name, clipboard)| {
+            let node_metadata = node
+                .iter()
+                .map(|node| (node.id(), metas.meta(node.id())))
+                .collect();
+            clipboard.copy(ClipboardContent {
+                database: *current_db,
+                node: node.clone(),
+                node_metadata,
+                source_world: world_name.clone(),
+            });
+        },
+    );
+
+    let paste = use_callback(
+        (db, current_db, clipboard.clone(), modals, on_paste.clone()),
+        move |(), (db, current_db, clipboard, modals, on_paste)| {
+            let Some(content) = clipboard.content() else {
+                return;
+            };
+            // Give the pasted groups new IDs so they can't collide with existing groups, carrying
+            // their metadata over to the new IDs, and rebuild against this group's database, which
+            // flags anything the database doesn't know about.
+            let new_meta = RefCell::new(HashMap::new());
+            let pasted = content
+                .node
+                .create_copy_with_visitor(&|old: Uuid, new: Uuid| {
+                    if let Some(meta) = content.node_metadata.get(&old) {
+                        new_meta.borrow_mut().insert(new, meta.clone());
+                    }
+                })
+                .rebuild(db);
+            let new_meta = new_meta.into_inner();
+            let errors = pasted
+                .iter()
+                .filter(|node| node.warning().is_some())
+                .count();
+            let finish = {
+                let world_dispatcher = world_dispatcher.clone();
+                let on_paste = on_paste.clone();
+                Callback::from(move |()| {
+                    world_dispatcher.batch_update_node_meta(new_meta.clone());
+                    on_paste.emit(pasted.clone());
+                })
+            };
+            if errors == 0 {
+                finish.emit(());
+                return;
+            }
+
+            let version_note = match content.database {
+                Some(version) if content.database != *current_db => html! {
+                    <p>{"It was copied from a world using database version \""}{version.name()}
+                    {"\", which is different from the one used by this world."}</p>
+                },
+                _ => html! {},
+            };
+            modals
+                .builder()
+                .title("Clipboard Incompatible")
+                .class("clipboard-incompatible")
+                .kind(
+                    BinaryChoice::new(html! {"Cancel"}, html! {"Paste Anyway"})
+                        .lhs_title("Don't paste")
+                        .rhs_title("Paste with errors")
+                        .on_rhs(finish),
+                )
+                .content(html! {
+                    <>
+                        <p>{errors}{" buildings or groups copied from \""}
+                        {&content.source_world}{"\" use items, recipes, or buildings which are \
+                        not available in this database. They will be shown with warnings and \
+                        missing icons if pasted."}</p>
+                        {version_note}
+                    </>
+                })
+                .build()
+                .persist();
+        },
+    );
+
+    let paste_title = match clipboard.content() {
+        Some(content) => {
+            let name = content
+                .node
+                .group()
+                .map(|group| group.name.as_str())
+                .filter(|name| !name.is_empty())
+                .unwrap_or("Unnamed Group");
+            format!("Paste \"{name}\" from {}", content.source_world)
+        }
+        None => "Nothing has been copied".to_owned(),
+    };
+
+    html! {
+        <>
+            <Button title="Copy to Clipboard" onclick={copy}>
+                {material_icon("file_copy")}
+            </Button>
+            <Button title={paste_title} onclick={paste} disabled={clipboard.content().is_none()}>
+                {material_icon("content_paste")}
+            </Button>
+        </>
+    }
+}
@@ -8,12 +8,32 @@
 use satisfactory_accounting::accounting::{SplitCopies, MAX_CLOCK, MIN_CLOCK};
 use yew::prelude::*;
 
-use crate::inputs::clickedit::{
-    AdjustDir, AdjustModifier, AdjustScale, ClickEdit, ValueAdjustment,
-};
+use crate::inputs::clickedit::{AdjustDir, ClickEdit, ValueAdjustment};
 use crate::material::material_icon_outlined;
 use crate::user_settings::number_format::UserConfiguredFormat;
 use crate::user_settings::use_user_settings;
+use crate::world::use_read_only;
+
+/// Parse a clock speed entered as a multiplier like "1.5", a percentage like "150%", or a fraction
+/// like "2/3". If `plain_as_percent` is set, a plain number like "150" is a percentage too, the way
+/// the game shows clock speeds. Returns None if the text isn't a clock speed.
+pub fn parse_clock(text: &str, plain_as_percent: bool) -> Option<f32> {
+    let text = text.trim();
+    if let Some(percent) = text.strip_suffix('%') {
+        return Some(percent.trim().parse::<f32>().ok()? / 100.0);
+    }
+    if let Some((numerator, denominator)) = text.split_once('/') {
+        let numerator = numerator.trim().parse::<f32>().ok()?;
+        let denominator = denominator.trim().parse::<f32>().ok()?;
+        return (denominator != 0.0).then(|| numerator / denominator);
+    }
+    let value = text.parse::<f32>().ok()?;
+    Some(if plain_as_percent {
+        value / 100.0
+    } else {
+        value
+    })
+}
 
 #[derive(Debug, PartialEq, Properties)]
 pub struct Props {
@@ -21,6 +41,9 @@ pub struct Props {
     pub clock_speed: f32,
     /// Number of virtual copies of the building.
     pub copies: f32,
+    /// Highest clock speed the building supports.
+    #[prop_or(MAX_CLOCK)]
+    pub max_clock: f32,
     /// Callback to change the actual value.
     pub on_update_speed: Callback<f32>,
 }
@@ -28,21 +51,40 @@ pub struct Props {
 /// Display and editing for clock speed.
 #[function_component]
 pub fn ClockSpeed(props: &Props) -> Html {
+    let read_only = use_read_only().is_read_only();
+    let user_settings = use_user_settings();
+    let clock_settings = &user_settings.number_display.clock;
     let on_commit = use_callback(
-        props.on_update_speed.clone(),
-        |edit_text: AttrValue, on_update_speed| {
-            if let Ok(value) = edit_text.parse::<f32>() {
-                on_update_speed.emit(value.clamp(MIN_CLOCK, MAX_CLOCK));
+        (
+            props.on_update_speed.clone(),
+            props.max_clock,
+            clock_settings.as_percent,
+        ),
+        |edit_text: AttrValue, (on_update_speed, max_clock, as_percent)| {
+            if let Some(value) = parse_clock(&edit_text, *as_percent) {
+                on_update_speed.emit(value.clamp(MIN_CLOCK, *max_clock));
             }
         },
     );
 
-    let rounding = &use_user_settings().number_display.clock.format;
+    let rounding = &clock_settings.format;
+    // Formats a clock speed for display, as a percentage if the user chose to match the game.
+    let display = |clock: f32| {
+        if clock_settings.as_percent {
+            format!("{}%", (clock * 100.0).format(rounding))
+        } else {
+            clock.format(rounding).to_string()
+        }
+    };
 
     let split = SplitCopies::split(props.copies, props.clock_speed);
 
-    let value: AttrValue = props.clock_speed.to_string().into();
-    let rounded_value: AttrValue = props.clock_speed.format(rounding).to_string().into();
+    let value: AttrValue = if clock_settings.as_percent {
+        format!("{}%", props.clock_speed * 100.0).into()
+    } else {
+        props.clock_speed.to_string().into()
+    };
+    let rounded_value: AttrValue = display(props.clock_speed).into();
     let prefix = material_icon_outlined("timer");
     let suffix = if split.last_clock > 0.0 {
         Some(html! {<>
@@ -54,37 +96,76 @@ pub fn ClockSpeed(props: &Props) -> Html {
             </span>
             {material_icon_outlined("timer")}
             <span class="extra-multiplier fractional">
-                {" "}{split.last_clock.format(rounding)}{" \u{00d7} 1"}
+                {" "}{display(split.last_clock)}{" \u{00d7} 1"}
             </span>
         </>})
     } else {
         None
     };
 
-    fn adjust(adjustment: ValueAdjustment, current: AttrValue) -> AttrValue {
-        let current = match current.parse::<f32>() {
-            Ok(current) => current,
-            Err(_) => return current,
+    fn adjust(
+        adjustment: ValueAdjustment,
+        current: AttrValue,
+        plain_as_percent: bool,
+    ) -> AttrValue {
+        let Some(clock) = parse_clock(&current, plain_as_percent) else {
+            return current;
         };
         let dir = match adjustment.dir {
             AdjustDir::Up => 1.0,
             AdjustDir::Down => -1.0,
         };
-        let dist = match (adjustment.scale, adjustment.modifier) {
-            // Fine adjustment by increments of 1 power slug.
-            (AdjustScale::Fine, AdjustModifier::None) => 0.5,
-            // Coarse adjustment by increments of 100%.
-            (AdjustScale::Coarse, AdjustModifier::None) => 1.0,
-            // Small scale adjustments are by 1% and 10% respectively.
-            (AdjustScale::Fine, AdjustModifier::Smaller) => 0.01,
-            (AdjustScale::Coarse, AdjustModifier::Smaller) => 0.1,
-        };
-        (current + dir * dist).to_string().into()
+        let clock = clock + dir * adjustment.step;
+        // Keep percentages as percentages while editing, but fractions become decimals since the
+        // adjusted value usually isn't a simple fraction.
+        let current = current.trim_end();
+        if current.ends_with('%') {
+            format!("{}%", clock * 100.0).into()
+        } else if plain_as_percent && !current.contains('/') {
+            (clock * 100.0).to_string().into()
+        } else {
+            clock.to_string().into()
+        }
     }
+    let adjust: fn(_, _) -> _ = if clock_settings.as_percent {
+        |adjustment, current| adjust(adjustment, current, true)
+    } else {
+        |adjustment, current| adjust(adjustment, current, false)
+    };
 
     html! {
         <ClickEdit {value} {rounded_value} class="ClockSpeed" title="Clock Speed" {on_commit}
-            {prefix} {suffix}
-            adjust={adjust as fn(_,_) -> _} />
+            {prefix} {suffix} {read_only}
+            {adjust} steps={user_settings.key_bindings.clock_steps} />
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_clock;
+
+    #[test]
+    fn parses_each_format() {
+        assert_eq!(parse_clock("150%", false), Some(1.5));
+        assert_eq!(parse_clock(" 150 % ", true), Some(1.5));
+        assert_eq!(parse_clock("2/3", false), Some(2.0 / 3.0));
+        assert_eq!(parse_clock("2/3", true), Some(2.0 / 3.0));
+        assert_eq!(parse_clock("0.5", false), Some(0.5));
+    }
+
+    #[test]
+    fn plain_numbers_are_percentages_in_percent_mode() {
+        assert_eq!(parse_clock("150", true), Some(1.5));
+        assert_eq!(parse_clock("150", false), Some(150.0));
+    }
+
+    #[test]
+    fn rejects_invalid_text() {
+        assert_eq!(parse_clock("1/0", false), None);
+        assert_eq!(parse_clock("", false), None);
+        assert_eq!(parse_clock("   ", true), None);
+        assert_eq!(parse_clock("fast", false), None);
+        assert_eq!(parse_clock("%", false), None);
+        assert_eq!(parse_clock("1/x", false), None);
     }
 }
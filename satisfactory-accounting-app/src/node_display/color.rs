@@ -0,0 +1,69 @@
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::{Msg, NodeDisplay};
+use crate::world::NodeColor;
+
+impl NodeDisplay {
+    /// CSS class for this node's color, if it has one.
+    pub(super) fn color_class(&self) -> Option<&'static str> {
+        self.meta.color.map(NodeColor::class)
+    }
+
+    /// Button to choose this node's color.
+    pub(super) fn color_button(&self, ctx: &Context<Self>) -> Html {
+        if self.read_only {
+            return html! {};
+        }
+        let on_change = ctx.link().callback(|color| Msg::SetColor { color });
+        html! {
+            <NodeColorPicker color={self.meta.color} {on_change} />
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+struct Props {
+    /// Current color of the node.
+    color: Option<NodeColor>,
+    /// Callback to change the color.
+    on_change: Callback<Option<NodeColor>>,
+}
+
+/// Button which opens a palette to choose the color of a node.
+#[function_component]
+fn NodeColorPicker(Props { color, on_change }: &Props) -> Html {
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |_, open| open.set(!**open));
+
+    let choose = |choice: Option<NodeColor>| {
+        let open = open.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |_| {
+            open.set(false);
+            on_change.emit(choice);
+        })
+    };
+
+    html! {
+        <span class="NodeColorPicker">
+            <Button class={classes!("color-button", color.map(NodeColor::class))}
+                title="Color" onclick={toggle}>
+                {material_icon("palette")}
+            </Button>
+            if *open {
+                <span class="color-palette">
+                    {for NodeColor::ALL.into_iter().map(|choice| html! {
+                        <Button class={classes!("swatch", choice.class(),
+                                (*color == Some(choice)).then_some("selected"))}
+                            title={choice.name()} onclick={choose(Some(choice))} />
+                    })}
+                    <Button class="swatch no-color" title="No Color" onclick={choose(None)}>
+                        {material_icon("format_color_reset")}
+                    </Button>
+                </span>
+            }
+        </span>
+    }
+}
@@ -7,11 +7,10 @@
 //       http://www.apache.org/licenses/LICENSE-2.0
 use yew::prelude::*;
 
-use crate::inputs::clickedit::{
-    AdjustDir, AdjustModifier, AdjustScale, ClickEdit, ValueAdjustment,
-};
+use crate::inputs::clickedit::{AdjustDir, ClickEdit, ValueAdjustment};
 use crate::user_settings::number_format::UserConfiguredFormat;
 use crate::user_settings::use_user_settings;
+use crate::world::use_read_only;
 
 #[derive(Debug, PartialEq, Properties)]
 pub struct Props {
@@ -24,6 +23,7 @@ pub struct Props {
 /// Display and editing for number of coipes.
 #[function_component]
 pub fn VirtualCopies(props: &Props) -> Html {
+    let read_only = use_read_only().is_read_only();
     let on_commit = use_callback(
         props.update_copies.clone(),
         |edit_text: AttrValue, update_copies| {
@@ -51,20 +51,12 @@ pub fn VirtualCopies(props: &Props) -> Html {
             AdjustDir::Up => 1.0,
             AdjustDir::Down => -1.0,
         };
-        let dist = match (adjustment.scale, adjustment.modifier) {
-            // Fine adjustment by increments of 1 building.
-            (AdjustScale::Fine, AdjustModifier::None) => 1.0,
-            // Coarse adjustment by increments of 5 buildings.
-            (AdjustScale::Coarse, AdjustModifier::None) => 5.0,
-            // Small scale adjustments are by 1% and 10% of clock respectively.
-            (AdjustScale::Fine, AdjustModifier::Smaller) => 0.01,
-            (AdjustScale::Coarse, AdjustModifier::Smaller) => 0.1,
-        };
-        (current + dir * dist).to_string().into()
+        (current + dir * adjustment.step).to_string().into()
     }
 
     html! {
         <ClickEdit {value} {rounded_value} class="VirtualCopies" title="Multiplier" {on_commit}
-            {suffix} adjust={adjust as fn(_,_)->_} />
+            {suffix} {read_only} adjust={adjust as fn(_,_)->_}
+            steps={user_settings.key_bindings.copies_steps} />
     }
 }
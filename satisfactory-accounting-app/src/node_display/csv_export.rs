@@ -0,0 +1,63 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo::file::ObjectUrl;
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::csv::{balances_csv, buildings_csv};
+use yew::prelude::*;
+
+use crate::download::download_text;
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::world::use_db;
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Group to export.
+    pub node: Node,
+}
+
+/// Buttons to download a group's balances or buildings as CSV files for use in spreadsheets.
+#[function_component]
+pub fn CsvExportButtons(Props { node }: &Props) -> Html {
+    let db = use_db();
+    // Keeps the download url alive until the group is disposed.
+    let download_url_retainer: Rc<RefCell<Option<ObjectUrl>>> = use_mut_ref(|| None);
+
+    let export_balances = {
+        let download_url_retainer = download_url_retainer.clone();
+        use_callback((node.clone(), db.clone()), move |_, (node, db)| {
+            let csv = balances_csv(node, db);
+            let filename = csv_filename(node, "Balances");
+            if let Some(url) = download_text(&csv, "text/csv", &filename) {
+                *download_url_retainer.borrow_mut() = Some(url);
+            }
+        })
+    };
+    let export_buildings = use_callback((node.clone(), db), move |_, (node, db)| {
+        let csv = buildings_csv(node, db);
+        let filename = csv_filename(node, "Buildings");
+        if let Some(url) = download_text(&csv, "text/csv", &filename) {
+            *download_url_retainer.borrow_mut() = Some(url);
+        }
+    });
+
+    html! {
+        <>
+            <Button title="Export Balances as CSV" onclick={export_balances}>
+                {material_icon("table_view")}
+            </Button>
+            <Button title="Export Buildings as CSV" onclick={export_buildings}>
+                {material_icon("table_rows")}
+            </Button>
+        </>
+    }
+}
+
+/// Name of a downloaded CSV file for a group.
+fn csv_filename(node: &Node, kind: &str) -> String {
+    match node.group() {
+        Some(group) if !group.name.is_empty() => format!("{} {kind}.csv", group.name),
+        _ => format!("{kind}.csv"),
+    }
+}
@@ -8,16 +8,28 @@
 use std::cell::RefCell;
 
 use log::warn;
+use satisfactory_accounting::accounting::Group;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlElement;
 use yew::prelude::*;
 
-use super::{Msg, NodeDisplay, DRAG_INSERT_POINT};
+use super::{graph_manipulation, Msg, NodeDisplay, DRAG_INSERT_POINT};
+use crate::inputs::button::Button;
+use crate::material::material_icon;
 
 thread_local! {
     static DRAGGING: RefCell<Option<Vec<usize>>> = RefCell::new(None);
 }
 
+/// What surrounds a node in its parent group, which decides where the move buttons can move it.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Neighbors {
+    /// Whether there is another node after this one.
+    pub has_next: bool,
+    /// If the node before this one is a group, how many children it has.
+    pub previous_group_len: Option<usize>,
+}
+
 impl NodeDisplay {
     /// Get the insert_pos_chooser for this node.
     fn insert_pos_chooser(&self, ctx: &Context<Self>) -> InsertPosChooser {
@@ -34,9 +46,14 @@ impl NodeDisplay {
     ) -> Callback<DragEvent> {
         let chooser = self.insert_pos_chooser(ctx);
         ctx.link().batch_callback(move |e: DragEvent| {
-            if let Some((insert_pos, would_stay_in_place, _)) = chooser.choose_insert_pos(&e) {
+            let copy = is_copy_drag(&e);
+            if let Some((insert_pos, would_stay_in_place, _)) = chooser.choose_insert_pos(&e, copy)
+            {
                 // If this is a valid drop point, prevent default to indicate that.
                 e.prevent_default();
+                if let Some(data_transfer) = e.data_transfer() {
+                    data_transfer.set_drop_effect(if copy { "copy" } else { "move" });
+                }
                 // Drop points are nested, so if we're dropping here, we need to stop
                 // propagation so we don't get two insert points.
                 e.stop_propagation();
@@ -57,7 +74,9 @@ impl NodeDisplay {
     pub(super) fn drop_handler(&self, ctx: &Context<Self>) -> Callback<DragEvent> {
         let chooser = self.insert_pos_chooser(ctx);
         ctx.link().callback(move |e: DragEvent| {
-            if let Some((insert_pos, would_stay_in_place, src_path)) = chooser.choose_insert_pos(&e)
+            let copy = is_copy_drag(&e);
+            if let Some((insert_pos, would_stay_in_place, src_path)) =
+                chooser.choose_insert_pos(&e, copy)
             {
                 // If this is a valid drop point, prevent default to indicate that.
                 e.prevent_default();
@@ -74,6 +93,7 @@ impl NodeDisplay {
                     Msg::MoveNode {
                         src_path,
                         dest_path,
+                        copy,
                     }
                 }
             } else {
@@ -85,9 +105,83 @@ impl NodeDisplay {
         })
     }
 
+    /// Copy the node at `src_path` to `dest_path`, both of which are full paths from the root and
+    /// must be under the given group, which is this node. The copy gets new ids and a copy of the
+    /// original's metadata.
+    pub(super) fn copy_child_to(
+        &self,
+        ctx: &Context<Self>,
+        group: &Group,
+        src_path: &[usize],
+        dest_path: &[usize],
+    ) -> Option<Group> {
+        let prefix_len = ctx.props().path.len();
+        graph_manipulation::copy_child(
+            group,
+            &src_path[prefix_len..],
+            &dest_path[prefix_len..],
+            |node| self.copy_with_metadata(ctx, node),
+        )
+    }
+
+    /// Buttons to move this node without dragging, shown on touch screens where drag and drop
+    /// doesn't work.
+    pub(super) fn move_controls(&self, ctx: &Context<Self>) -> Html {
+        let path = &ctx.props().path;
+        let Some((&idx, parent)) = path.split_last().filter(|_| !self.read_only) else {
+            return html! {};
+        };
+        let neighbors = ctx.props().neighbors;
+        let move_to = |dest_path: Vec<usize>| {
+            let move_node = ctx.props().move_node.clone();
+            let src_path = path.clone();
+            Callback::from(move |()| move_node.emit((src_path.clone(), dest_path.clone(), false)))
+        };
+        let sibling = |idx: usize| {
+            let mut dest = parent.to_vec();
+            dest.push(idx);
+            dest
+        };
+        // Moving into the group above puts the node after that group's last child.
+        let into_previous = neighbors.previous_group_len.map(|len| {
+            let mut dest = sibling(idx - 1);
+            dest.push(len);
+            move_to(dest)
+        });
+        // Moving out of the group puts the node just after the group.
+        let out_of_parent = parent.split_last().map(|(&parent_idx, grandparent)| {
+            let mut dest = grandparent.to_vec();
+            dest.push(parent_idx + 1);
+            move_to(dest)
+        });
+        html! {
+            <span class="touch-move">
+                <Button title="Move Up" disabled={idx == 0}
+                    onclick={move_to(sibling(idx.saturating_sub(1)))}>
+                    {material_icon("arrow_upward")}
+                </Button>
+                // Insert positions count the node itself, so skip past the next node.
+                <Button title="Move Down" disabled={!neighbors.has_next}
+                    onclick={move_to(sibling(idx + 2))}>
+                    {material_icon("arrow_downward")}
+                </Button>
+                if let Some(onclick) = into_previous {
+                    <Button title="Move Into the Group Above" {onclick}>
+                        {material_icon("subdirectory_arrow_right")}
+                    </Button>
+                }
+                if let Some(onclick) = out_of_parent {
+                    <Button title="Move Out of This Group" {onclick}>
+                        {material_icon("north_west")}
+                    </Button>
+                }
+            </span>
+        }
+    }
+
     /// Creates a drag-handle for this element.
     pub(super) fn drag_handle(&self, ctx: &Context<Self>) -> Html {
-        if ctx.props().path.is_empty() {
+        if ctx.props().path.is_empty() || self.read_only {
             html! {}
         } else {
             let srcpath = ctx.props().path.clone();
@@ -95,7 +189,8 @@ impl NodeDisplay {
                 DRAGGING.with(|dragging| *dragging.borrow_mut() = Some(srcpath.clone()));
             });
             html! {
-                <div class="drag-handle" draggable="true" {ondragstart}>
+                <div class="drag-handle" draggable="true" {ondragstart}
+                    title="Drag to move. Hold Ctrl or Alt while dropping to copy instead.">
                     <span class="material-icons">{"drag_handle"}</span>
                 </div>
             }
@@ -103,6 +198,12 @@ impl NodeDisplay {
     }
 }
 
+/// Whether the node being dragged should be copied rather than moved, which is done by holding
+/// Ctrl or Alt.
+fn is_copy_drag(event: &DragEvent) -> bool {
+    event.ctrl_key() || event.alt_key()
+}
+
 /// Helper to choose an insert position for a Node.
 struct InsertPosChooser {
     /// Children ref of the node. Used to find child client rects.
@@ -121,7 +222,14 @@ impl InsertPosChooser {
     /// bubbles up to the parent.
     ///
     /// Return the src path to use when finding the element to move.
-    fn choose_insert_pos(&self, event: &DragEvent) -> Option<(usize, bool, Vec<usize>)> {
+    ///
+    /// When copying, the node never stays in place, since a copy is added even next to the
+    /// original.
+    fn choose_insert_pos(
+        &self,
+        event: &DragEvent,
+        copy: bool,
+    ) -> Option<(usize, bool, Vec<usize>)> {
         let src_path = DRAGGING.with(|dragging| dragging.borrow().clone())?;
         // If the source path is longer than ours, the node may be a child or a peer's
         // child, but it cannot be a parent or ourself.
@@ -168,7 +276,10 @@ impl InsertPosChooser {
         // If no index was picked so far, insert point is at the end.
 
         // Figure out if insert point would result in the node staying in the same place.
-        if src_path.len() == self.path.len() + 1 && src_path[..self.path.len()] == self.path {
+        if !copy
+            && src_path.len() == self.path.len() + 1
+            && src_path[..self.path.len()] == self.path
+        {
             // node is a child of this node.
             let child_idx = src_path.last().copied().unwrap();
             // Insert places an item in the list position before the specified index.
@@ -0,0 +1,100 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::events::get_value_from_input_event;
+use crate::material::material_icon;
+use crate::node_display::NodeDisplay;
+
+/// Most copies which can be made at once, to avoid accidentally creating a huge tree.
+const MAX_DUPLICATES: usize = 100;
+
+impl NodeDisplay {
+    /// Creates the button to make several copies at once, if the parent allows this node to be
+    /// copied.
+    pub(super) fn duplicate_button(&self, ctx: &Context<Self>) -> Html {
+        match ctx.props().copy.clone() {
+            Some(_) if self.read_only => html! {},
+            Some(copy_from_parent) => {
+                let idx = ctx
+                    .props()
+                    .path
+                    .last()
+                    .copied()
+                    .expect("Parent provided a copy callback, but this is the root node.");
+                let on_duplicate = Callback::from(move |count| copy_from_parent.emit((idx, count)));
+                html! {
+                    <DuplicateButton {on_duplicate} />
+                }
+            }
+            None => html! {},
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+struct Props {
+    /// Callback to make the given number of copies.
+    on_duplicate: Callback<usize>,
+}
+
+/// Button which opens a prompt for how many copies of a node to make.
+#[function_component]
+fn DuplicateButton(Props { on_duplicate }: &Props) -> Html {
+    let open = use_state_eq(|| false);
+    let count = use_state(|| AttrValue::from("2"));
+    let input = use_node_ref();
+
+    use_effect_with((*open, input.clone()), |(open, input)| {
+        if *open {
+            if let Some(input) = input.cast::<HtmlInputElement>() {
+                let _ = input.focus();
+                input.select();
+            }
+        }
+    });
+
+    let toggle = use_callback(open.clone(), |_, open| open.set(!**open));
+    let oninput = use_callback(count.clone(), |e, count| {
+        count.set(get_value_from_input_event(e));
+    });
+    let duplicate = {
+        let open = open.clone();
+        let count = count.clone();
+        let on_duplicate = on_duplicate.clone();
+        move || {
+            if let Ok(count @ 1..=MAX_DUPLICATES) = count.trim().parse::<usize>() {
+                open.set(false);
+                on_duplicate.emit(count);
+            }
+        }
+    };
+    let onkeydown = {
+        let open = open.clone();
+        let duplicate = duplicate.clone();
+        Callback::from(move |e: KeyboardEvent| match &*e.key() {
+            "Enter" => duplicate(),
+            "Escape" => open.set(false),
+            _ => {}
+        })
+    };
+    let confirm = Callback::from(move |_| duplicate());
+
+    html! {
+        <span class="DuplicateButton">
+            <Button class="green" title="Duplicate Multiple Times" onclick={toggle}>
+                {material_icon("library_add")}
+            </Button>
+            if *open {
+                <span class="duplicate-prompt">
+                    <span>{"\u{00D7}"}</span>
+                    <input ref={input} type="number" min="1" max={MAX_DUPLICATES.to_string()}
+                        value={(*count).clone()} {oninput} {onkeydown} />
+                    <Button class="green" title="Make Copies" onclick={confirm}>
+                        {material_icon("check")}
+                    </Button>
+                </span>
+            }
+        </span>
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::planner::RawFootprint;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::node_display::{Msg, NodeDisplay};
+use crate::user_settings::use_user_settings;
+use crate::world::use_db;
+
+/// Differences in rate smaller than this are shown as equal.
+const EPSILON: f32 = 1e-3;
+
+impl NodeDisplay {
+    /// Button which shows or hides the raw resource footprint of this node.
+    pub(super) fn footprint_button(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.link().callback(|_| Msg::ToggleFootprint);
+        let title = if self.footprint_open {
+            "Hide Raw Resource Footprint"
+        } else {
+            "Show Raw Resource Footprint"
+        };
+        html! {
+            <Button {onclick} {title}>
+                {material_icon("landscape")}
+            </Button>
+        }
+    }
+
+    /// Panel spanning the whole row with the raw resources behind this node's outputs, if it is
+    /// open.
+    pub(super) fn footprint_panel(&self, ctx: &Context<Self>) -> Html {
+        if !self.footprint_open {
+            return html! {};
+        }
+        html! {
+            <RawFootprintPanel node={ctx.props().node.clone()} />
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+struct Props {
+    /// Node to show the footprint of.
+    node: Node,
+}
+
+/// Compares the raw resources a node uses with what its outputs would take using standard
+/// recipes.
+#[function_component]
+fn RawFootprintPanel(Props { node }: &Props) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let footprint = use_memo((node.clone(), db.clone()), |(node, db)| {
+        RawFootprint::of(node, db)
+    });
+
+    let items: BTreeSet<_> = footprint
+        .actual
+        .keys()
+        .chain(footprint.standard.keys())
+        .copied()
+        .collect();
+    if items.is_empty() {
+        return html! {
+            <div class="RawFootprint">
+                <span class="footprint-empty">
+                    {"This node doesn't make anything from raw resources."}
+                </span>
+            </div>
+        };
+    }
+    let rate = |rate: Option<&f32>| match rate {
+        Some(&rate) => format!(
+            "{}{}",
+            balance_settings.format_rate(rate),
+            balance_settings.rate_suffix()
+        ),
+        None => "\u{2013}".to_owned(),
+    };
+    let rows = items.into_iter().map(|id| {
+        let (icon, name): (Option<AttrValue>, AttrValue) = match db.get(id) {
+            Some(item) => (Some(item.image.clone().into()), item.name.clone().into()),
+            None => (None, "Unknown Item".into()),
+        };
+        let actual = footprint.actual.get(&id);
+        let standard = footprint.standard.get(&id);
+        let difference = actual.copied().unwrap_or(0.0) - standard.copied().unwrap_or(0.0);
+        let class = if difference < -EPSILON {
+            "cheaper"
+        } else if difference > EPSILON {
+            "costlier"
+        } else {
+            ""
+        };
+        html! {
+            <>
+                <span class="footprint-item">
+                    <Icon {icon} name={name.clone()} />
+                    <span>{name}</span>
+                </span>
+                <span class={classes!("footprint-rate", class)}>{rate(actual)}</span>
+                <span class="footprint-rate">{rate(standard)}</span>
+            </>
+        }
+    });
+
+    html! {
+        <div class="RawFootprint">
+            <span class="footprint-header">{"Raw Resource"}</span>
+            <span class="footprint-header"
+                title="Raw resources consumed here, plus what its inputs take with standard recipes">
+                {"This Setup"}
+            </span>
+            <span class="footprint-header"
+                title="Raw resources needed to make the same outputs with standard recipes">
+                {"Standard Recipes"}
+            </span>
+            {for rows}
+        </div>
+    }
+}
@@ -61,6 +61,48 @@ pub fn move_child(group: &Group, src: &[usize], dest: &[usize]) -> Option<Group>
     Some(new_group)
 }
 
+/// Copy a node from one position in a group to another, using `make_copy` to create the copy.
+/// Both src and dest paths should be rooted at this group. Since the source is left in place,
+/// the paths may overlap, as long as dest isn't inside of src.
+pub fn copy_child(
+    group: &Group,
+    src: &[usize],
+    dest: &[usize],
+    make_copy: impl FnOnce(&Node) -> Node,
+) -> Option<Group> {
+    let (&src_first, src_rest) = src.split_first().expect("source path was empty");
+    let (&dest_first, dest_rest) = dest.split_first().expect("dest path was empty");
+
+    let Some(mut source) = group.children.get(src_first) else {
+        warn!("Attempting to copy from an out of bounds index");
+        return None;
+    };
+    for &idx in src_rest {
+        let Some(child) = source.group().and_then(|group| group.children.get(idx)) else {
+            warn!("Attempting to copy from an invalid path");
+            return None;
+        };
+        source = child;
+    }
+    let copied = make_copy(source);
+
+    let mut new_group = group.clone();
+    if dest_rest.is_empty() {
+        if dest_first > new_group.children.len() {
+            warn!("Attempting to copy to an out of bounds index");
+            return None;
+        }
+        new_group.children.insert(dest_first, copied);
+    } else {
+        let Some(dest_node) = new_group.children.get(dest_first) else {
+            warn!("Attempting to copy to an out of bounds index");
+            return None;
+        };
+        new_group.children[dest_first] = insert_child(dest_node, dest_rest, copied)?;
+    }
+    Some(new_group)
+}
+
 /// Recursively removes a child node. Returns the new group to replace the one modified
 /// and the node that was removed. Returns none if not a group or out of bounds.
 pub fn remove_child(node: &Node, child: &[usize]) -> Option<(Node, Node)> {
@@ -5,18 +5,42 @@
 //   You may obtain a copy of the License at
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
-use satisfactory_accounting::accounting::{Building, Group};
+use satisfactory_accounting::accounting::{Building, Group, Node};
+use satisfactory_accounting::database::Database;
 use yew::prelude::*;
 
 use crate::inputs::button::Button;
+use crate::item_flow::ItemFlowButton;
 use crate::material::material_icon;
-use crate::node_display::balance::{BalanceShape, NodeBalance};
+use crate::node_display::balance::{balance_style, BalanceShape, NodeBalance};
+use crate::node_display::blueprint::BlueprintButtons;
+use crate::node_display::buildcost::BuildCostButton;
+use crate::node_display::clipboard::ClipboardButtons;
 use crate::node_display::copies::VirtualCopies;
+use crate::node_display::csv_export::CsvExportButtons;
+use crate::node_display::drag::Neighbors;
+use crate::node_display::icon::Icon;
+use crate::node_display::image_export::ImageExportButton;
+use crate::node_display::summary::node_element_id;
 use crate::node_display::{Msg, NodeDisplay, NodeMeta, DRAG_INSERT_POINT};
+use crate::power_report::PowerReportButton;
+use crate::print_view::PrintViewButton;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::world::group_database;
 
+use build_progress::GroupBuildProgress;
+use container_loops::ContainerLoops;
+use efficiency::GroupEfficiency;
+use group_database::GroupDatabase;
 use group_name::GroupName;
+use nuclear_warnings::NuclearWarnings;
 
+mod build_progress;
+mod container_loops;
+mod efficiency;
+mod group_database;
 mod group_name;
+mod nuclear_warnings;
 
 impl NodeDisplay {
     /// Build the display for a Group.
@@ -34,10 +58,11 @@ impl NodeDisplay {
         let update_copies = link.callback(|copies| Msg::SetCopyCount { copies });
         let replace = link.callback(|(idx, replacement)| Msg::ReplaceChild { idx, replacement });
         let delete = link.callback(|idx| Msg::DeleteChild { idx });
-        let copy = link.callback(|idx| Msg::CopyChild { idx });
-        let move_node = link.callback(|(src_path, dest_path)| Msg::MoveNode {
+        let copy = link.callback(|(idx, count)| Msg::CopyChild { idx, count });
+        let move_node = link.callback(|(src_path, dest_path, copy)| Msg::MoveNode {
             src_path,
             dest_path,
+            copy,
         });
         let add_group = link.callback(|_| Msg::AddChild {
             child: Group::empty_node(),
@@ -46,6 +71,14 @@ impl NodeDisplay {
             child: Building::empty_node(),
         });
         let rename = link.callback(|name| Msg::Rename { name });
+        let trace = link.callback(|(target, expand)| Msg::TraceItem { target, expand });
+        let child_totals = self.child_totals(group);
+        let set_database = link.callback(|database| Msg::SetGroupDatabase { database });
+        let set_recirculated = link.callback(|items| Msg::SetRecirculated { items });
+        let import_blueprint = link.callback(|child| Msg::AddChild { child });
+        let paste = link.callback(|child| Msg::AddChild { child });
+        let chain_backdrive =
+            link.callback(|(idx, before)| Msg::ChainBackdriveChild { idx, before });
 
         let ondragover = self.drag_over_handler(ctx, |insert_pos| Msg::DragOver { insert_pos });
         let ondragenter = self.drag_over_handler(ctx, |insert_pos| Msg::DragEnter { insert_pos });
@@ -54,42 +87,44 @@ impl NodeDisplay {
 
         let set_metadata = &ctx.props().set_metadata;
         let batch_set_metadata = &ctx.props().batch_set_metadata;
-        html! {
-            <div class="NodeDisplay group expanded" key={group.id.as_u128()}>
-                <div class="header">
-                    {self.drag_handle(ctx)}
-                    <div class="section group-name">
-                        {self.collapse_button(ctx, group)}
-                        <GroupName name={group.name.clone()} {rename} />
-                    </div>
-                    if !ctx.props().path.is_empty() {
-                        <VirtualCopies copies={group.copies as f32} {update_copies} />
-                    }
-                    <div class="section copy-delete">
-                        {self.child_warnings(ctx)}
-                        {self.copy_button(ctx)}
-                        {self.delete_button(ctx)}
-                    </div>
-                </div>
+        let content = html! {
+            <>
+                <NuclearWarnings node={&ctx.props().node} />
                 <div class="body">
                     <div class="children-display node-grid"
                         {ondragover} {ondragenter} {ondragleave} {ondrop}
                         ref={self.children.clone()}>
-                        { for group.children.iter().cloned().enumerate().map(|(i, node)| {
+                        { for group.children.iter().cloned().enumerate()
+                            .filter(|(_, node)| !self.hidden_as_balanced(node))
+                            .map(|(i, node)| {
                             let mut path = ctx.props().path.clone();
                             path.push(i);
+                            let contribution = self.child_contribution(ctx, group, &node);
+                            let parent_totals = child_totals.clone();
+                            let neighbors = Neighbors {
+                                has_next: i + 1 < group.children.len(),
+                                previous_group_len: i.checked_sub(1)
+                                    .and_then(|prev| group.children[prev].group())
+                                    .map(|prev| prev.children.len()),
+                            };
                             html! {
                                 <>
                                     if self.insert_pos == Some(i) {
                                         <div class={DRAG_INSERT_POINT} />
                                     }
-                                    <NodeDisplay {node} {path}
-                                        replace={replace.clone()}
-                                        delete={delete.clone()}
-                                        copy={copy.clone()}
-                                        move_node={move_node.clone()}
-                                        set_metadata={set_metadata.clone()}
-                                        batch_set_metadata={batch_set_metadata.clone()} />
+                                    if self.child_in_window(ctx, i) {
+                                        <NodeDisplay {node} {path}
+                                            replace={replace.clone()}
+                                            delete={delete.clone()}
+                                            copy={copy.clone()}
+                                            move_node={move_node.clone()}
+                                            set_metadata={set_metadata.clone()}
+                                            batch_set_metadata={batch_set_metadata.clone()}
+                                            chain_backdrive={chain_backdrive.clone()}
+                                            {contribution} {parent_totals} {neighbors} />
+                                    } else {
+                                        {self.child_placeholder(&node)}
+                                    }
                                 </>
                             }
                         }) }
@@ -97,18 +132,99 @@ impl NodeDisplay {
                             <div class={DRAG_INSERT_POINT} />
                         }
                     </div>
-                    <NodeBalance node={&ctx.props().node} shape={BalanceShape::Vertical} />
+                    <NodeBalance node={&ctx.props().node} shape={BalanceShape::Vertical}
+                        on_select={trace} selected={self.traced(ctx)}
+                        share_of={ctx.props().parent_totals.clone()} />
                 </div>
+                {self.power_chart_panel(ctx)}
+                {self.footprint_panel(ctx)}
+                {self.batch_edit_panel(ctx)}
+                {self.planner_panel(ctx)}
                 <div class="footer">
-                    <Button class="green" title="Add Group"
-                        onclick={add_group}>
-                        {material_icon("create_new_folder")}
-                    </Button>
-                    <Button class="green" title="Add Building"
-                        onclick={add_building}>
-                        {material_icon("add")}
-                    </Button>
+                    if !self.read_only {
+                        <Button class="green" title="Add Group"
+                            onclick={add_group}>
+                            {material_icon("create_new_folder")}
+                        </Button>
+                        <Button class="green" title="Add Building"
+                            onclick={add_building}>
+                            {material_icon("add")}
+                        </Button>
+                        {self.planner_button(ctx)}
+                        {self.batch_edit_button(ctx)}
+                    }
+                    <BuildCostButton node={&ctx.props().node} />
+                    <CsvExportButtons node={&ctx.props().node} />
+                    <ImageExportButton node={&ctx.props().node} />
+                    {self.power_chart_button(ctx)}
+                    {self.footprint_button(ctx)}
+                    <ItemFlowButton id={group.id} />
+                    <PowerReportButton id={group.id} />
+                    <PrintViewButton id={group.id} />
+                    if !self.read_only {
+                        <BlueprintButtons node={&ctx.props().node} on_import={import_blueprint} />
+                        <ClipboardButtons node={&ctx.props().node} on_paste={paste} />
+                    }
+                </div>
+            </>
+        };
+        html! {
+            <div class={classes!("NodeDisplay", "group", "expanded", self.color_class(),
+                    self.contributor_class(ctx))}
+                key={group.id.as_u128()} id={node_element_id(group.id)}>
+                <div class="header">
+                    {self.drag_handle(ctx)}
+                    <div class="section group-name">
+                        {self.collapse_button(ctx, group)}
+                        <GroupName name={group.name.clone()} {rename} />
+                        {self.build_progress(ctx)}
+                        {self.efficiency(ctx)}
+                    </div>
+                    {self.power_subtotal(ctx)}
+                    if !ctx.props().path.is_empty() {
+                        <VirtualCopies copies={group.copies as f32} {update_copies} />
+                    }
+                    <div class="section copy-delete">
+                        {self.move_controls(ctx)}
+                        {self.contribution_badge(ctx)}
+                        {self.power_share_badge(ctx)}
+                        {self.child_warnings(ctx)}
+                        {self.balanced_badge(ctx)}
+                        {self.pin_button(ctx)}
+                        if !ctx.props().path.is_empty() && !self.read_only {
+                            <GroupDatabase id={group.id} database={group.database}
+                                on_change={set_database} />
+                        }
+                        <ContainerLoops node={&ctx.props().node}
+                            recirculated={group.recirculated.clone()}
+                            on_change={set_recirculated} />
+                        {self.circuit_button(ctx)}
+                        {self.color_button(ctx)}
+                    {self.notes_button(ctx)}
+                        {self.copy_button(ctx)}
+                        {self.duplicate_button(ctx)}
+                        {self.delete_button(ctx)}
+                    </div>
+                    {self.notes_panel(ctx)}
                 </div>
+                {self.with_group_database(group, content)}
+            </div>
+        }
+    }
+
+    /// Power subtotal of an expanded group, aligned with the power column of its children.
+    fn power_subtotal(&self, ctx: &Context<Self>) -> Html {
+        let power = ctx.props().node.balance().power;
+        let balance_settings = &self.user_settings.number_display.balance;
+        let rounding = &balance_settings.power_format_settings;
+        let class = classes!(
+            "group-power",
+            balance_style(power, rounding, balance_settings)
+        );
+        html! {
+            <div {class} title="Power Subtotal">
+                <Icon icon="power-line" name="Power" />
+                <span class="balance-value">{power.format(rounding).to_string()}</span>
             </div>
         }
     }
@@ -116,26 +232,83 @@ impl NodeDisplay {
     fn view_group_collapsed(&self, ctx: &Context<Self>, group: &Group) -> Html {
         let rename = ctx.link().callback(|name| Msg::Rename { name });
         let update_copies = ctx.link().callback(|copies| Msg::SetCopyCount { copies });
+        let trace = ctx
+            .link()
+            .callback(|(target, expand)| Msg::TraceItem { target, expand });
         html! {
-            <div class="NodeDisplay group collapsed" key={group.id.as_u128()}>
+            <div class={classes!("NodeDisplay", "group", "collapsed", self.color_class(),
+                    self.contributor_class(ctx))}
+                key={group.id.as_u128()} id={node_element_id(group.id)}>
                 {self.drag_handle(ctx)}
                 <div class="section group-name">
                     {self.collapse_button(ctx, group)}
                     <GroupName name={group.name.clone()} {rename} />
+                    {self.build_progress(ctx)}
+                    {self.efficiency(ctx)}
                 </div>
-                <NodeBalance node={&ctx.props().node} />
+                {self.with_group_database(group, html! {
+                    <NodeBalance node={&ctx.props().node} on_select={trace}
+                        selected={self.traced(ctx)}
+                        share_of={ctx.props().parent_totals.clone()} />
+                })}
                 if !ctx.props().path.is_empty() {
                     <VirtualCopies copies={group.copies as f32} {update_copies} />
                 }
                 <div class="section copy-delete">
+                    {self.move_controls(ctx)}
+                    {self.contribution_badge(ctx)}
+                    {self.power_share_badge(ctx)}
                     {self.child_warnings(ctx)}
+                    {self.balanced_badge(ctx)}
+                    {self.pin_button(ctx)}
+                    {self.circuit_button(ctx)}
+                    {self.color_button(ctx)}
+                    {self.notes_button(ctx)}
                     {self.copy_button(ctx)}
+                    {self.duplicate_button(ctx)}
                     {self.delete_button(ctx)}
                 </div>
+                {self.notes_panel(ctx)}
             </div>
         }
     }
 
+    /// Wrap content of a group so that it uses the group's pinned database, if it has one.
+    fn with_group_database(&self, group: &Group, content: Html) -> Html {
+        match group.database {
+            Some(_) => html! {
+                <ContextProvider<Database> context={group_database(group, &self.db)}>
+                    {content}
+                </ContextProvider<Database>>
+            },
+            None => content,
+        }
+    }
+
+    /// Show how many machines in this group have been built, if build tracking is enabled.
+    fn build_progress(&self, ctx: &Context<Self>) -> Html {
+        if !self.user_settings.track_build_progress {
+            return html! {};
+        }
+        let progress = ctx.props().node.build_progress();
+        if progress.planned == 0 {
+            return html! {};
+        }
+        html! { <GroupBuildProgress {progress} /> }
+    }
+
+    /// Efficiency of the group's machines, if enabled in the user settings.
+    fn efficiency(&self, ctx: &Context<Self>) -> Html {
+        if !self.user_settings.show_efficiency {
+            return html! {};
+        }
+        let efficiency = ctx.props().node.efficiency();
+        if efficiency.throughput.is_none() && efficiency.input_utilization.is_none() {
+            return html! {};
+        }
+        html! { <GroupEfficiency {efficiency} /> }
+    }
+
     /// Get a collapse/expand button for this node.
     fn collapse_button(&self, ctx: &Context<Self>, group: &Group) -> Html {
         if ctx.props().path.is_empty() {
@@ -167,9 +340,37 @@ impl NodeDisplay {
         }
     }
 
-    /// Show an icon to notify if any children have warnings.
+    /// Whether the given node is a self-sufficient group and balanced groups are being hidden.
+    pub(super) fn hidden_as_balanced(&self, node: &Node) -> bool {
+        self.user_settings.only_unbalanced_groups
+            && node.group().is_some()
+            && is_balanced(node, self.user_settings.balanced_tolerance)
+    }
+
+    /// Show a badge if this group is self-sufficient.
+    fn balanced_badge(&self, ctx: &Context<Self>) -> Html {
+        if is_balanced(&ctx.props().node, self.user_settings.balanced_tolerance) {
+            html! {
+                <span class="balanced-badge material-icons"
+                    title="Self-sufficient: no item or power deficits">
+                    {"verified"}
+                </span>
+            }
+        } else {
+            html! {}
+        }
+    }
+
+    /// Show an icon to notify if this group, such as when its pinned database hasn't been
+    /// fetched, or any children have warnings.
     fn child_warnings(&self, ctx: &Context<Self>) -> Html {
-        if ctx.props().node.children_had_warnings() {
+        if let Some(warning) = ctx.props().node.warning() {
+            html! {
+                <span class="BuildError material-icons warning" title={warning.to_string()}>
+                    {"warning"}
+                </span>
+            }
+        } else if ctx.props().node.children_had_warnings() {
             html! {
                 <span class="BuildError material-icons warning"
                     title="One or more children had errors">
@@ -181,3 +382,9 @@ impl NodeDisplay {
         }
     }
 }
+
+/// Whether a node has something in its balance and has no deficits beyond the tolerance.
+fn is_balanced(node: &Node, tolerance: f32) -> bool {
+    let balance = node.balance();
+    (balance.power != 0.0 || !balance.balances.is_empty()) && balance.is_self_sufficient(tolerance)
+}
@@ -0,0 +1,24 @@
+use satisfactory_accounting::accounting::BuildProgress;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Progress to display.
+    pub progress: BuildProgress,
+}
+
+/// Progress bar showing how many machines in a group have been built.
+#[function_component]
+pub fn GroupBuildProgress(&Props { progress }: &Props) -> Html {
+    let BuildProgress { built, planned } = progress;
+    let class = classes!(
+        "GroupBuildProgress",
+        (built == planned).then_some("complete")
+    );
+    html! {
+        <div {class} title={format!("{built} of {planned} machines built")}>
+            <progress max={planned.to_string()} value={built.to_string()} />
+            <span>{format!("{built}/{planned} built")}</span>
+        </div>
+    }
+}
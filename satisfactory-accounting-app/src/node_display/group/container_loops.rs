@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::analysis::find_container_loops;
+use satisfactory_accounting::database::ItemId;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::toggle::MaterialCheckbox;
+use crate::material::material_icon;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_read_only};
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Group node whose packagers should be checked for closed container loops.
+    pub node: Node,
+    /// Containers the group currently recirculates.
+    pub recirculated: BTreeSet<ItemId>,
+    /// Callback to change which containers the group recirculates.
+    pub on_change: Callback<BTreeSet<ItemId>>,
+}
+
+/// Button and editor for recirculating the containers of packager loops within a group, so that
+/// empty canisters and tanks which never leave the group stop showing up in its balance.
+#[function_component]
+pub fn ContainerLoops(
+    Props {
+        node,
+        recirculated,
+        on_change,
+    }: &Props,
+) -> Html {
+    let db = use_db();
+    let read_only = use_read_only().is_read_only();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let suffix = balance_settings.rate_suffix();
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+
+    let loops = find_container_loops(node, &db);
+    // Containers which are recirculated but no longer loop are still listed so they can be
+    // turned off again.
+    let mut containers: BTreeSet<ItemId> = loops.iter().map(|l| l.container).collect();
+    containers.extend(recirculated.iter().copied());
+    if containers.is_empty() {
+        return html! {};
+    }
+
+    let name = |item: ItemId| match db.get(item) {
+        Some(item) => item.name.to_string(),
+        None => format!("Unknown Item {item}"),
+    };
+    let pending = containers
+        .iter()
+        .filter(|container| !recirculated.contains(container))
+        .count();
+    let title = if pending > 0 {
+        "Packagers in this group fill and empty the same containers; recirculate them to hide \
+         them from the group's balance"
+    } else {
+        "Containers recirculated within this group"
+    };
+
+    let row = |&container: &ItemId| {
+        let checked = recirculated.contains(&container);
+        let mut toggled = recirculated.clone();
+        if !toggled.remove(&container) {
+            toggled.insert(container);
+        }
+        let onclick = on_change.reform(move |_| toggled.clone());
+        let net = loops.iter().find(|l| l.container == container).map(|l| {
+            html! {
+                <span class="net" title="Net rate of containers left over by the loop">
+                    {balance_settings.format_rate(l.net()).to_string()}{suffix}
+                </span>
+            }
+        });
+        html! {
+            <label class="container">
+                <MaterialCheckbox {checked} {onclick} />
+                <span>{name(container)}</span>
+                {net}
+            </label>
+        }
+    };
+
+    let class = classes!(
+        "ContainerLoops",
+        (pending > 0).then_some("pending"),
+        (!recirculated.is_empty()).then_some("active"),
+    );
+    html! {
+        <div {class}>
+            <Button {title} onclick={toggle} disabled={read_only}>
+                {material_icon("recycling")}
+            </Button>
+            if *open && !read_only {
+                <div class="containers">
+                    {for containers.iter().map(row)}
+                </div>
+            }
+        </div>
+    }
+}
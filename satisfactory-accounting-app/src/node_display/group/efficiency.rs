@@ -0,0 +1,38 @@
+use satisfactory_accounting::accounting::Efficiency;
+use yew::prelude::*;
+
+use crate::material::material_icon;
+
+/// Fraction below which a figure is highlighted as low.
+const LOW: f32 = 0.75;
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Efficiency to display.
+    pub efficiency: Efficiency,
+}
+
+/// Badges showing the throughput of a group's machines and how much of its own production of
+/// inputs it uses.
+#[function_component]
+pub fn GroupEfficiency(&Props { efficiency }: &Props) -> Html {
+    let figure = |value: Option<f32>, icon: &'static str, title: &str| match value {
+        Some(value) => {
+            let percent = (value * 100.0).round();
+            html! {
+                <span class={classes!("figure", (value < LOW).then_some("low"))}
+                    title={format!("{title}: {percent}%")}>
+                    {material_icon(icon)}
+                    {format!("{percent}%")}
+                </span>
+            }
+        }
+        None => html! {},
+    };
+    html! {
+        <div class="GroupEfficiency">
+            {figure(efficiency.throughput, "speed", "Throughput of machines at 100% clock speed")}
+            {figure(efficiency.input_utilization, "input", "Share of produced inputs consumed")}
+        </div>
+    }
+}
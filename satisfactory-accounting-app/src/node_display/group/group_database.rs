@@ -0,0 +1,82 @@
+use satisfactory_accounting::database::DatabaseVersion;
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::toggle::MaterialRadio;
+use crate::material::material_icon;
+use crate::user_settings::use_user_settings;
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// ID of the group, used to keep the radio buttons of different groups separate.
+    pub id: Uuid,
+    /// Database version pinned by the group, if any.
+    pub database: Option<DatabaseVersion>,
+    /// Callback to change the pinned database version.
+    pub on_change: Callback<Option<DatabaseVersion>>,
+}
+
+/// Button and chooser for pinning a group to a specific database version.
+#[function_component]
+pub fn GroupDatabase(
+    &Props {
+        id,
+        database,
+        ref on_change,
+    }: &Props,
+) -> Html {
+    let user_settings = use_user_settings();
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+
+    let title = match database {
+        Some(version) => format!("Pinned to database {}", version.name()),
+        None => "Pin a database version for this group".to_owned(),
+    };
+    let name: AttrValue = format!("group-db-{id}").into();
+    let choice = |version: Option<DatabaseVersion>| {
+        let onclick = {
+            let on_change = on_change.clone();
+            let open = open.clone();
+            Callback::from(move |_| {
+                on_change.emit(version);
+                open.set(false);
+            })
+        };
+        let label = match version {
+            Some(version) => version.name(),
+            None => "Same as parent",
+        };
+        html! {
+            <label class="choice">
+                <span>{label}</span>
+                <MaterialRadio name={name.clone()} checked={database == version} {onclick} />
+            </label>
+        }
+    };
+    let versions = DatabaseVersion::ALL
+        .iter()
+        .rev()
+        .filter(|&&version| {
+            user_settings.show_deprecated_databases
+                || !version.is_deprecated()
+                || database == Some(version)
+        })
+        .map(|&version| choice(Some(version)));
+
+    html! {
+        <div class="GroupDatabase">
+            <Button class={classes!(database.is_some().then_some("pinned"))} {title}
+                onclick={toggle}>
+                {material_icon("history")}
+            </Button>
+            if *open {
+                <div class="choices">
+                    {choice(None)}
+                    {for versions}
+                </div>
+            }
+        </div>
+    }
+}
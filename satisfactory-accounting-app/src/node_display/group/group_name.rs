@@ -8,6 +8,7 @@
 use yew::prelude::*;
 
 use crate::inputs::clickedit::ClickEdit;
+use crate::world::use_read_only;
 
 #[derive(PartialEq, Properties)]
 pub struct Props {
@@ -20,12 +21,14 @@ pub struct Props {
 /// Display and editing for number of coipes.
 #[function_component]
 pub fn GroupName(props: &Props) -> Html {
+    let read_only = use_read_only().is_read_only();
     let (value, class) = if props.name.is_empty() {
         ("unnamed".into(), classes!("GroupName", "unnamed"))
     } else {
         (props.name.clone(), classes!("GroupName"))
     };
     html! {
-        <ClickEdit {value} {class} title="Group Name" on_commit={props.rename.clone()} />
+        <ClickEdit {value} {class} title="Group Name" on_commit={props.rename.clone()}
+            {read_only} />
     }
 }
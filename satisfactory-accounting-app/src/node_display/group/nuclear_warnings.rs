@@ -0,0 +1,66 @@
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::analysis::{check_nuclear_chain, NuclearWarning};
+use satisfactory_accounting::database::ItemId;
+use yew::prelude::*;
+
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_item_metas};
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// Group node whose nuclear fuel chain should be checked.
+    pub node: Node,
+}
+
+/// List of problems found in the nuclear fuel chain of a group.
+#[function_component]
+pub fn NuclearWarnings(Props { node }: &Props) -> Html {
+    let db = use_db();
+    let item_metas = use_item_metas();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let suffix = balance_settings.rate_suffix();
+
+    let warnings = check_nuclear_chain(node, &db, |item| item_metas.meta(item).exported);
+    if warnings.is_empty() {
+        return html! {};
+    }
+
+    let name = |item: ItemId| match db.get(item) {
+        Some(item) => item.name.to_string(),
+        None => format!("Unknown Item {item}"),
+    };
+    let describe = |warning: &NuclearWarning| match *warning {
+        NuclearWarning::UnconsumedWaste { item, rate } => format!(
+            "{}{suffix} of {} is never consumed",
+            balance_settings.format_rate(rate),
+            name(item)
+        ),
+        NuclearWarning::RodsWithoutGenerators { item, rate } => format!(
+            "{}{suffix} of {} is made but never burned",
+            balance_settings.format_rate(rate),
+            name(item)
+        ),
+        NuclearWarning::RodsPartlyBurned {
+            item,
+            burned,
+            surplus,
+        } => format!(
+            "{}{suffix} of {} is burned while {}{suffix} is left over to sink or store",
+            balance_settings.format_rate(burned),
+            name(item),
+            balance_settings.format_rate(surplus)
+        ),
+    };
+
+    html! {
+        <ul class="NuclearWarnings" title="Mark an item as exported to hide its warning">
+            {for warnings.iter().map(|warning| html! {
+                <li>
+                    <span class="material-icons warning">{"warning"}</span>
+                    <span>{describe(warning)}</span>
+                </li>
+            })}
+        </ul>
+    }
+}
@@ -7,6 +7,7 @@
 //       http://www.apache.org/licenses/LICENSE-2.0
 use yew::prelude::*;
 
+use crate::user_settings::use_user_settings;
 use crate::world::use_db;
 
 #[derive(PartialEq, Properties)]
@@ -14,16 +15,66 @@ pub struct Props {
     /// Reference to the icon slug.
     #[prop_or_default]
     pub icon: Option<AttrValue>,
+    /// Display name of the thing the icon represents, used for alt text and text-only badges.
+    #[prop_or_default]
+    pub name: Option<AttrValue>,
 }
 
 #[function_component(Icon)]
 pub fn icon(props: &Props) -> Html {
     let db = use_db();
+    let user_settings = use_user_settings();
+    let text_only = user_settings.text_only_icons;
+    // Image URLs which failed to load, so the next candidate can be tried instead.
+    let failed = use_state(Vec::<String>::new);
 
     match &props.icon {
-        Some(icon) => html! {
-            <img src={slug_to_icon(icon, db.icon_prefix())} class="Icon" alt="?" />
-        },
+        // Text-only mode doesn't load any images; the badge falls back to the slug if no name was
+        // given.
+        Some(icon) if text_only => {
+            let label = props.name.as_ref().unwrap_or(icon);
+            html! {
+                <span class="Icon text-badge" aria-label={label.clone()}>
+                    {badge_text(label)}
+                </span>
+            }
+        }
+        Some(icon) => {
+            // Icons from the user's icon pack are tried first, then the built-in icon. If none of
+            // them load, e.g. for a modded item with no icon, a text badge is shown instead.
+            let candidates = user_settings
+                .icon_packs
+                .get(db.icon_prefix())
+                .and_then(|pack| pack.icon_url(icon))
+                .into_iter()
+                .chain([slug_to_icon(icon, db.icon_prefix())]);
+            let src = candidates.into_iter().find(|url| !failed.contains(url));
+            match src {
+                Some(src) => {
+                    let alt = props.name.clone().unwrap_or_else(|| "?".into());
+                    let onerror = {
+                        let failed = failed.clone();
+                        let src = src.clone();
+                        Callback::from(move |_: Event| {
+                            let mut urls = (*failed).clone();
+                            urls.push(src.clone());
+                            failed.set(urls);
+                        })
+                    };
+                    html! {
+                        <img {src} class="Icon" {alt} {onerror} />
+                    }
+                }
+                None => {
+                    let label = props.name.as_ref().unwrap_or(icon);
+                    html! {
+                        <span class="Icon text-badge" aria-label={label.clone()}>
+                            {badge_text(label)}
+                        </span>
+                    }
+                }
+            }
+        }
         None => html! {
             <span class="Icon material-icons error">{"error"}</span>
         },
@@ -35,3 +86,15 @@ fn slug_to_icon(slug: impl AsRef<str>, icon_prefix: &str) -> String {
     let slug = slug.as_ref();
     format!("/images/{icon_prefix}items/{slug}_64.png")
 }
+
+/// Abbreviate a name or slug to at most 3 characters for a text badge, e.g. "Iron Plate" or
+/// "iron-plate" becomes "IP".
+fn badge_text(label: &str) -> String {
+    label
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '.')
+        .filter_map(|word| word.chars().next())
+        .filter(|c| c.is_alphanumeric())
+        .take(3)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
@@ -0,0 +1,361 @@
+//! Rendering a group's summary to a PNG image, for sharing plans without cropping screenshots.
+//!
+//! The image is drawn on a canvas rather than captured from the page, so it always has the same
+//! layout regardless of the window size, theme, or which parts of the group are collapsed. Item
+//! icons are left out, since they would have to be loaded before they could be drawn.
+use log::warn;
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::database::Database;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+
+use crate::download::download_url;
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::user_settings::number_format::{BalanceDisplaySettings, UserConfiguredFormat};
+use crate::user_settings::use_user_settings;
+use crate::world::{group_database, use_db};
+
+/// Font used for the name of the group.
+const TITLE_FONT: &str = "bold 24px Rubik, sans-serif";
+/// Font used for the headings of columns.
+const HEADING_FONT: &str = "bold 15px Rubik, sans-serif";
+/// Font used for everything else.
+const FONT: &str = "15px Rubik, sans-serif";
+/// Height of the title line, in pixels.
+const TITLE_HEIGHT: f64 = 36.0;
+/// Height of every other line, in pixels.
+const LINE_HEIGHT: f64 = 22.0;
+/// Space around the edges of the image and between sections, in pixels.
+const PADDING: f64 = 20.0;
+/// Space between columns, in pixels.
+const COLUMN_GAP: f64 = 18.0;
+/// Scale the image is drawn at, so text stays sharp on high density screens.
+const SCALE: f64 = 2.0;
+
+// The image always uses light colors, whatever the app's theme, so it reads the same wherever it
+// is posted.
+/// Color of the background.
+const BACKGROUND: &str = "#ffffff";
+/// Color of most text.
+const TEXT: &str = "#212529";
+/// Color of headings and labels.
+const MUTED: &str = "#868e96";
+/// Color of the bar down the left edge.
+const ACCENT: &str = "#df691a";
+/// Color of surpluses.
+const POSITIVE: &str = "#3d8b3d";
+/// Color of deficits.
+const NEGATIVE: &str = "#d9534f";
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct Props {
+    /// Group to export.
+    pub node: Node,
+}
+
+/// Button which downloads an image of a group's name, buildings, and balance.
+#[function_component]
+pub fn ImageExportButton(Props { node }: &Props) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let onclick = use_callback(
+        (node.clone(), db, user_settings),
+        |_, (node, db, user_settings)| {
+            let Some(summary) = Summary::new(node, db, &user_settings.number_display.balance)
+            else {
+                return;
+            };
+            match summary.render() {
+                Ok(url) => {
+                    download_url(&url, &format!("{}.png", summary.name));
+                }
+                Err(e) => warn!("Unable to render group image: {e:?}"),
+            }
+        },
+    );
+    html! {
+        <Button title="Export as Image" {onclick}>
+            {material_icon("image")}
+        </Button>
+    }
+}
+
+/// A line of a table, which is either a heading or a row of cells.
+enum Line {
+    /// Headings of the columns of a table.
+    Heading(Vec<&'static str>),
+    /// Cells of a row, with the color each is drawn in.
+    Row(Vec<(String, &'static str)>),
+}
+
+/// A table of text, with the given columns aligned right.
+struct Table {
+    lines: Vec<Line>,
+    right_aligned: &'static [usize],
+}
+
+impl Table {
+    /// Get the width of each column of the table.
+    fn column_widths(&self, ctx: &CanvasRenderingContext2d) -> Result<Vec<f64>, JsValue> {
+        let mut widths = Vec::new();
+        for line in &self.lines {
+            let cells: Vec<(&str, &str)> = match line {
+                Line::Heading(headings) => headings.iter().map(|&h| (h, HEADING_FONT)).collect(),
+                Line::Row(cells) => cells.iter().map(|(c, _)| (c.as_str(), FONT)).collect(),
+            };
+            widths.resize(widths.len().max(cells.len()), 0.0);
+            for ((text, font), width) in cells.into_iter().zip(&mut widths) {
+                ctx.set_font(font);
+                *width = f64::max(*width, ctx.measure_text(text)?.width());
+            }
+        }
+        Ok(widths)
+    }
+
+    /// Get the total width of the table.
+    fn width(&self, ctx: &CanvasRenderingContext2d) -> Result<f64, JsValue> {
+        let widths = self.column_widths(ctx)?;
+        let gaps = widths.len().saturating_sub(1) as f64 * COLUMN_GAP;
+        Ok(widths.iter().sum::<f64>() + gaps)
+    }
+
+    /// Get the height of the table.
+    fn height(&self) -> f64 {
+        self.lines.len() as f64 * LINE_HEIGHT
+    }
+
+    /// Draw the table with its top left corner at the given position.
+    fn draw(&self, ctx: &CanvasRenderingContext2d, x: f64, y: f64) -> Result<(), JsValue> {
+        let widths = self.column_widths(ctx)?;
+        for (i, line) in self.lines.iter().enumerate() {
+            let baseline = y + (i as f64 + 0.75) * LINE_HEIGHT;
+            let cells: Vec<(&str, &str, &str)> = match line {
+                Line::Heading(headings) => {
+                    headings.iter().map(|&h| (h, HEADING_FONT, MUTED)).collect()
+                }
+                Line::Row(cells) => cells
+                    .iter()
+                    .map(|(c, color)| (c.as_str(), FONT, *color))
+                    .collect(),
+            };
+            let mut left = x;
+            for (col, ((text, font, color), width)) in cells.into_iter().zip(&widths).enumerate() {
+                ctx.set_font(font);
+                set_fill(ctx, color);
+                if self.right_aligned.contains(&col) {
+                    ctx.set_text_align("right");
+                    ctx.fill_text(text, left + width, baseline)?;
+                } else {
+                    ctx.set_text_align("left");
+                    ctx.fill_text(text, left, baseline)?;
+                }
+                left += width + COLUMN_GAP;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Everything shown in the image of a group, as text.
+struct Summary {
+    /// Name of the group.
+    name: String,
+    /// Name of the group, with its copies.
+    title: String,
+    /// Buildings and subgroups directly inside the group.
+    buildings: Table,
+    /// Net rates of power and items.
+    balance: Table,
+}
+
+impl Summary {
+    /// Build the summary of a group. Returns None if the node isn't a group.
+    fn new(node: &Node, db: &Database, balance_settings: &BalanceDisplaySettings) -> Option<Self> {
+        let group = node.group()?;
+        let db = group_database(group, db);
+        let power_format = &balance_settings.power_format_settings;
+        let item_format = &balance_settings.item_format_settings;
+        let sign_color = |rate: f32| if rate < 0.0 { NEGATIVE } else { POSITIVE };
+
+        let name = if group.name.is_empty() {
+            "Unnamed Group".to_owned()
+        } else {
+            group.name.to_string()
+        };
+        let title = if group.copies > 1 {
+            format!("{name} \u{00D7} {}", group.copies)
+        } else {
+            name.clone()
+        };
+
+        let mut buildings = vec![Line::Heading(vec![
+            "Building",
+            "Recipe / Item",
+            "Clock",
+            "Copies",
+            "Power (MW)",
+        ])];
+        for child in node.children() {
+            let power = child.balance().power;
+            let power = (power.format(power_format).to_string(), sign_color(power));
+            if let Some(subgroup) = child.group() {
+                let name = if subgroup.name.is_empty() {
+                    "Unnamed Group".to_owned()
+                } else {
+                    subgroup.name.to_string()
+                };
+                buildings.push(Line::Row(vec![
+                    ("Group".to_owned(), MUTED),
+                    (name, TEXT),
+                    (String::new(), TEXT),
+                    (subgroup.copies.to_string(), TEXT),
+                    power,
+                ]));
+                continue;
+            }
+            let Some(building) = child.building() else {
+                continue;
+            };
+            let Some(id) = building.building else {
+                continue;
+            };
+            let name = match db.get(id) {
+                Some(info) => info.name.to_string(),
+                None => "Unknown Building".to_owned(),
+            };
+            buildings.push(Line::Row(vec![
+                (name, TEXT),
+                (
+                    building
+                        .settings
+                        .selection_name(&db)
+                        .unwrap_or_default()
+                        .to_string(),
+                    TEXT,
+                ),
+                (
+                    format!("{:.1}%", building.settings.clock_speed() * 100.0),
+                    TEXT,
+                ),
+                (building.copies.to_string(), TEXT),
+                power,
+            ]));
+        }
+        if buildings.len() == 1 {
+            buildings.clear();
+        }
+
+        let balance = node.balance();
+        let mut items: Vec<(String, f32)> = balance
+            .balances
+            .iter()
+            .map(|(&id, &rate)| {
+                let name = match db.get(id) {
+                    Some(item) => item.name.to_string(),
+                    None => "Unknown Item".to_owned(),
+                };
+                (name, rate)
+            })
+            .collect();
+        let collator = crate::locale::get_collator();
+        items.sort_by(|(lhs, _), (rhs, _)| collator.compare(lhs, rhs));
+        let mut balance_lines = vec![Line::Heading(vec!["Balance", "Rate"])];
+        if balance.power != 0.0 {
+            balance_lines.push(Line::Row(vec![
+                ("Power".to_owned(), TEXT),
+                (
+                    format!("{} MW", balance.power.format(power_format)),
+                    sign_color(balance.power),
+                ),
+            ]));
+        }
+        for (name, rate) in items {
+            let rate = balance_settings.rate_in_unit(rate);
+            balance_lines.push(Line::Row(vec![
+                (name, TEXT),
+                (
+                    format!(
+                        "{}{}",
+                        rate.format(item_format),
+                        balance_settings.rate_suffix()
+                    ),
+                    sign_color(rate),
+                ),
+            ]));
+        }
+        if balance_lines.len() == 1 {
+            balance_lines.clear();
+        }
+
+        Some(Self {
+            name,
+            title,
+            buildings: Table {
+                lines: buildings,
+                right_aligned: &[2, 3, 4],
+            },
+            balance: Table {
+                lines: balance_lines,
+                right_aligned: &[1],
+            },
+        })
+    }
+
+    /// Draw the summary on a new canvas and get it as a PNG data url.
+    fn render(&self) -> Result<String, JsValue> {
+        let canvas: HtmlCanvasElement = gloo::utils::document()
+            .create_element("canvas")?
+            .dyn_into()
+            .map_err(JsValue::from)?;
+        let ctx: CanvasRenderingContext2d = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("Canvas has no 2d context"))?
+            .dyn_into()
+            .map_err(JsValue::from)?;
+
+        let tables: Vec<&Table> = [&self.buildings, &self.balance]
+            .into_iter()
+            .filter(|table| !table.lines.is_empty())
+            .collect();
+        ctx.set_font(TITLE_FONT);
+        let mut width = ctx.measure_text(&self.title)?.width();
+        let mut height = TITLE_HEIGHT;
+        for table in &tables {
+            width = width.max(table.width(&ctx)?);
+            height += PADDING + table.height();
+        }
+        width += 2.0 * PADDING;
+        height += 2.0 * PADDING;
+
+        // Resizing the canvas resets the context, so it has to be done before anything is set.
+        canvas.set_width((width * SCALE).ceil() as u32);
+        canvas.set_height((height * SCALE).ceil() as u32);
+        ctx.scale(SCALE, SCALE)?;
+
+        set_fill(&ctx, BACKGROUND);
+        ctx.fill_rect(0.0, 0.0, width, height);
+        set_fill(&ctx, ACCENT);
+        ctx.fill_rect(0.0, 0.0, 6.0, height);
+
+        ctx.set_font(TITLE_FONT);
+        set_fill(&ctx, TEXT);
+        ctx.set_text_align("left");
+        ctx.fill_text(&self.title, PADDING, PADDING + TITLE_HEIGHT * 0.7)?;
+
+        let mut y = PADDING + TITLE_HEIGHT;
+        for table in tables {
+            y += PADDING;
+            table.draw(&ctx, PADDING, y)?;
+            y += table.height();
+        }
+
+        canvas.to_data_url_with_type("image/png")
+    }
+}
+
+/// Set the color shapes and text are filled with.
+fn set_fill(ctx: &CanvasRenderingContext2d, color: &str) {
+    ctx.set_fill_style(&JsValue::from_str(color));
+}
@@ -6,7 +6,7 @@
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
 
 use log::{error, warn};
@@ -14,32 +14,61 @@ use uuid::Uuid;
 use yew::prelude::*;
 
 use satisfactory_accounting::accounting::{
-    BuildNode, Building, BuildingSettings, GeneratorSettings, GeothermalSettings, Group,
-    ManufacturerSettings, MinerSettings, Node, NodeKind, PumpSettings, ResourcePurity,
-    StationSettings,
+    BuildNode, Building, BuildingSettings, GeneratorSettings, GeothermalSettings, Logistics,
+    ManifestEntry, ManufacturerSettings, MinerSettings, Node, NodeKind, PumpSettings,
+    ResourcePurity, StationSettings, VehicleTraffic,
 };
+use satisfactory_accounting::analysis::{power_draw_shares, GrossBalance};
 use satisfactory_accounting::database::{
-    BuildingId, BuildingKind, BuildingKindId, BuildingType, Database, ItemId, ItemIdOrPower,
-    RecipeId,
+    BuildingId, BuildingKind, BuildingKindId, BuildingType, Database, DatabaseVersion, ItemId,
+    ItemIdOrPower, PurityCounts, RecipeId,
 };
 
 use crate::inputs::button::Button;
 use crate::material::material_icon;
 use crate::user_settings::{use_user_settings, UserSettings};
-use crate::world::{use_world_dispatcher, use_world_root, NodeMeta, NodeMetas};
+use crate::world::{
+    use_hidden_columns, use_world_dispatcher, use_world_root, NodeColor, NodeMeta, NodeMetas,
+    ReadOnly,
+};
 
 pub use self::backdrive::{BackdriveSettings, BackdriveSettingsMsg, BackdriveSettingsSection};
 pub use self::balance::BalanceSortMode;
+use self::drag::Neighbors;
+pub use self::icon::Icon;
+pub use self::percent::format_share;
+use self::percent::PowerShares;
+pub use self::summary::reveal_contributors;
+use self::summary::PinnedSummary;
+use self::virtualize::ChildWindow;
 
 mod backdrive;
 mod balance;
+mod batch_edit;
+mod blueprint;
+mod buildcost;
 mod building;
+mod chain_backdrive;
+mod circuit;
+mod clipboard;
 mod clock;
+mod color;
 mod copies;
+mod csv_export;
 mod drag;
+mod duplicate;
+mod footprint;
 mod graph_manipulation;
 mod group;
 mod icon;
+mod image_export;
+mod notes;
+mod percent;
+mod planner;
+mod power_chart;
+mod summary;
+mod trace;
+mod virtualize;
 
 /// Displays the root of the node tree.
 #[function_component]
@@ -48,11 +77,24 @@ pub fn NodeTreeDisplay() -> Html {
     let dispatcher = use_world_dispatcher();
 
     let user_settings = use_user_settings();
+    let hidden_columns = use_hidden_columns();
+    let power_shares = use_memo(
+        (root.clone(), user_settings.show_percent_contributions),
+        |(root, enabled)| {
+            if *enabled {
+                power_draw_shares(root)
+            } else {
+                HashMap::new()
+            }
+        },
+    );
     let class = classes!(
         "NodeTreeDisplay",
         user_settings
             .hide_empty_balances
-            .then_some("hide-empty-balances")
+            .then_some("hide-empty-balances"),
+        user_settings.compact_layout.then_some("compact"),
+        hidden_columns.classes()
     );
 
     let replace = use_callback(dispatcher.clone(), |(idx, replacement), dispatcher| {
@@ -73,14 +115,32 @@ pub fn NodeTreeDisplay() -> Html {
 
     html! {
         <div {class}>
-            <div class="tree-content-inner node-grid">
-                <NodeDisplay node={root} path={vec![]} {replace} {move_node}
-                    {set_metadata} {batch_set_metadata} />
-            </div>
+            <PinnedSummary />
+            <ContextProvider<PowerShares> context={PowerShares(power_shares)}>
+                <div class="tree-content-inner node-grid">
+                    <NodeDisplay node={root} path={vec![]} {replace} {move_node}
+                        {set_metadata} {batch_set_metadata} />
+                </div>
+            </ContextProvider<PowerShares>>
         </div>
     }
 }
 
+/// Name to show for a node in summaries: the group's name, or the type of the building.
+pub fn node_name(db: &Database, node: &Node) -> String {
+    match node.kind() {
+        NodeKind::Group(group) if !group.name.is_empty() => group.name.to_string(),
+        NodeKind::Group(_) => "Unnamed Group".to_owned(),
+        NodeKind::Building(building) => match building.building {
+            Some(id) => match db.get(id) {
+                Some(building) => building.name.to_string(),
+                None => "Unknown Building".to_owned(),
+            },
+            None => "Unset Building".to_owned(),
+        },
+    }
+}
+
 #[derive(Debug, PartialEq, Properties)]
 struct Props {
     /// The node to display.
@@ -90,17 +150,31 @@ struct Props {
     /// Callback to tell the parent to delete this node.
     #[prop_or_default]
     pub delete: Option<Callback<usize>>,
-    /// Callback to tell the parent to copy this node.
+    /// Callback to tell the parent to copy this node the given number of times.
     #[prop_or_default]
-    pub copy: Option<Callback<usize>>,
+    pub copy: Option<Callback<(usize, usize)>>,
     /// Callback to tell the parent to replace this node.
     pub replace: Callback<(usize, Node)>,
-    /// Callback to tell the parent to move a node.
-    pub move_node: Callback<(Vec<usize>, Vec<usize>)>,
+    /// Callback to tell the parent to move a node, or copy it if the flag is set.
+    pub move_node: Callback<(Vec<usize>, Vec<usize>, bool)>,
     /// Callback to set the metadata of a node.
     pub set_metadata: Callback<(Uuid, NodeMeta)>,
     /// Callback to set the metadata of many nodes at once.
     pub batch_set_metadata: Callback<HashMap<Uuid, NodeMeta>>,
+    /// If the parent is tracing an item or power, the rate this node contributes to it.
+    #[prop_or_default]
+    pub contribution: Option<(ItemIdOrPower, f32)>,
+    /// Totals of this node and its siblings to show its balance as a percent of, if percent
+    /// contributions are shown.
+    #[prop_or_default]
+    pub parent_totals: Option<Rc<GrossBalance>>,
+    /// What surrounds this node in its parent group.
+    #[prop_or_default]
+    pub neighbors: Neighbors,
+    /// Callback to tell the parent to rescale the buildings which feed this one, given how this
+    /// node looked before it was backdriven.
+    #[prop_or_default]
+    pub chain_backdrive: Option<Callback<(usize, Node)>>,
 }
 
 /// Messages which can be sent to a Node.
@@ -110,6 +184,30 @@ enum Msg {
     SetCopyCount {
         copies: f32,
     },
+    /// Show or hide the notes panel.
+    ToggleNotes,
+    /// Change the notes attached to this building or group.
+    SetNotes {
+        notes: AttrValue,
+    },
+    /// Change the color this building or group is tinted with.
+    SetColor {
+        color: Option<NodeColor>,
+    },
+    /// Change the power circuit this node is on and the power storages kept with it.
+    SetCircuit {
+        circuit: AttrValue,
+        power_storages: u32,
+    },
+    /// Pin or unpin this group's balance to the summary panel.
+    TogglePinned,
+    /// Highlight the children contributing to an item or power in this group's balance, or stop
+    /// if it was already highlighted. If `expand` is set, also expand every group down to the
+    /// contributing buildings.
+    TraceItem {
+        target: ItemIdOrPower,
+        expand: bool,
+    },
 
     // Messages for groups:
     /// Replace the child at the given index with the specified node.
@@ -121,9 +219,10 @@ enum Msg {
     DeleteChild {
         idx: usize,
     },
-    /// Copy the child at the specified index.
+    /// Copy the child at the specified index the given number of times.
     CopyChild {
         idx: usize,
+        count: usize,
     },
     /// Add the given node as a child at the end of the list.
     AddChild {
@@ -133,6 +232,26 @@ enum Msg {
     Rename {
         name: AttrValue,
     },
+    /// Pin this group to a specific database version, or follow its parent if None.
+    SetGroupDatabase {
+        database: Option<DatabaseVersion>,
+    },
+    /// Change which containers this group recirculates.
+    SetRecirculated {
+        items: BTreeSet<ItemId>,
+    },
+    /// Show or hide the power breakdown chart.
+    TogglePowerChart,
+    /// Show or hide the raw resource footprint.
+    ToggleFootprint,
+    /// Show or hide the batch editor.
+    ToggleBatchEdit,
+    /// Show or hide the production planner.
+    TogglePlanner,
+    /// Replace this group with an edited copy of itself.
+    ReplaceSelf {
+        replacement: Node,
+    },
     /// When another node starts being dragged over this one.
     DragEnter {
         insert_pos: usize,
@@ -143,11 +262,20 @@ enum Msg {
     },
     /// When another dragging node leaves this one.
     DragLeave,
-    /// Move a node between positions.
+    /// Rescale the children feeding the child at the given index, which was backdriven from
+    /// `before`.
+    ChainBackdriveChild {
+        idx: usize,
+        before: Node,
+    },
+    /// Move a node between positions, or copy it to the destination if `copy` is set.
     MoveNode {
         src_path: Vec<usize>,
         dest_path: Vec<usize>,
+        copy: bool,
     },
+    /// Re-measure children after scrolling to update which ones are rendered.
+    UpdateChildWindow,
 
     // Messages for buildings:
     /// Change the building type of this node.
@@ -162,6 +290,10 @@ enum Msg {
     ChangeItem {
         id: ItemId,
     },
+    /// Mark whether the building has been built in game.
+    SetBuilt {
+        built: bool,
+    },
     /// Change the clock speed for the building.
     ChangeClockSpeed {
         clock_speed: f32,
@@ -170,28 +302,57 @@ enum Msg {
     ChangePurity {
         purity: ResourcePurity,
     },
-    /// Change the number of nodes of a particular purity for a pump.
+    /// Switch a geothermal generator between a single geyser and a cluster of geysers.
+    SetGeyserCluster {
+        cluster: bool,
+    },
+    /// Change the number of nodes of a particular purity for a pump or geyser cluster.
     ChangePumpPurity {
         /// Purity kind to modify.
         purity: ResourcePurity,
         /// New number of pads of that type.
         num_pads: u32,
     },
+    /// Change the tier of belt or pipe carrying the output of an extraction building.
+    ChangeOutputTier {
+        tier: Option<u8>,
+    },
+    /// Change or clear the belts or pipes carrying an input or output of a building.
+    ChangeLogistics {
+        item: ItemId,
+        logistics: Option<Logistics>,
+    },
     /// Change the consumption of a Station.
     ChangeConsumption {
         consumption: f32,
     },
+    /// Change the vehicle traffic used to derive the consumption of a Station.
+    ChangeTraffic {
+        traffic: Option<VehicleTraffic>,
+    },
+    /// Change the freight a Station loads and unloads.
+    ChangeManifest {
+        manifest: Vec<ManifestEntry>,
+    },
     /// Backdrive this node to match the requested rate.
     Backdrive {
         id: ItemIdOrPower,
         rate: f32,
     },
+    /// Ask the parent to rescale the buildings feeding this one after it was backdriven.
+    ChainBackdrive,
+    /// Dismiss the offer to rescale the buildings feeding this one.
+    DismissChainBackdrive,
 
     /// Update the database from the context.
     DbContextChange(Database),
     /// Update the metadata from the context.
     MetaContextChange(NodeMetas),
     UserSettingsChange(Rc<UserSettings>),
+    /// Update the share of power draw of each group from the context.
+    PowerSharesChange(PowerShares),
+    /// Update whether editing is allowed from the context.
+    ReadOnlyChange(ReadOnly),
 }
 
 /// Display for a single AccountingGraph node.
@@ -204,12 +365,18 @@ struct NodeDisplay {
     /// Number of virtual insert markers requested. Used to prevent flicker, since
     /// dragenter happens for a new element before dragleave for the prior element.
     insert_count: usize,
+    /// Which children are rendered, for groups too large to render all of them.
+    child_window: ChildWindow,
 
     /// Maintains the listener for the database context.
     _db_handle: ContextHandle<Database>,
     /// Maintains the listener for the metadata context.
     _meta_handle: ContextHandle<NodeMetas>,
     _user_settings_handle: ContextHandle<Rc<UserSettings>>,
+    /// Maintains the listener for the power shares context.
+    _power_shares_handle: ContextHandle<PowerShares>,
+    /// Maintains the listener for the read-only context.
+    _read_only_handle: ContextHandle<ReadOnly>,
 
     /// Database from the context.
     db: Database,
@@ -219,6 +386,25 @@ struct NodeDisplay {
     meta: NodeMeta,
     /// User settings.
     user_settings: Rc<UserSettings>,
+    /// Share of the world's power draw of each group.
+    power_shares: PowerShares,
+    /// Whether edit controls are hidden.
+    read_only: bool,
+    /// Whether the notes panel is shown.
+    notes_open: bool,
+    /// Whether the power breakdown chart of this group is shown.
+    power_chart_open: bool,
+    /// Whether the raw resource footprint of this node is shown.
+    footprint_open: bool,
+    /// Whether the batch editor of this group is shown.
+    batch_edit_open: bool,
+    /// Whether the production planner of this group is shown.
+    planner_open: bool,
+    /// Item or power selected in this group's balance to highlight its contributors.
+    traced: Option<ItemIdOrPower>,
+    /// How this building looked before and after it was last backdriven, while the parent can
+    /// still be asked to rescale the buildings feeding it.
+    backdriven: Option<(Node, Node)>,
 }
 
 impl Component for NodeDisplay {
@@ -241,39 +427,55 @@ impl Component for NodeDisplay {
             .context(ctx.link().callback(Msg::UserSettingsChange))
             .expect("NodeDisplay must be inside of the UserSettings context providers");
 
-        let meta = ctx
-            .props()
-            .node
-            .group()
-            .map(|g| metas.meta(g.id))
-            .unwrap_or_default();
+        let (power_shares, power_shares_handle) = ctx
+            .link()
+            .context(ctx.link().callback(Msg::PowerSharesChange))
+            .expect("NodeDisplay must be inside of the NodeTreeDisplay's context providers");
+
+        let (read_only, read_only_handle) = ctx
+            .link()
+            .context(ctx.link().callback(Msg::ReadOnlyChange))
+            .expect("NodeDisplay must be inside of the WorldManager's context providers");
+
+        let meta = metas.meta(ctx.props().node.id());
 
         NodeDisplay {
             children: NodeRef::default(),
             insert_pos: None,
             insert_count: 0,
+            child_window: ChildWindow::new(),
 
             _db_handle: db_handle,
             _meta_handle: meta_handle,
             _user_settings_handle: user_settings_handle,
+            _power_shares_handle: power_shares_handle,
+            _read_only_handle: read_only_handle,
 
             db,
             metas,
             meta,
             user_settings,
+            power_shares,
+            read_only: read_only.is_read_only(),
+            notes_open: false,
+            power_chart_open: false,
+            footprint_open: false,
+            batch_edit_open: false,
+            planner_open: false,
+            traced: None,
+            backdriven: None,
         }
     }
 
     fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
-        self.meta = ctx
-            .props()
-            .node
-            .group()
-            .map(|g| self.metas.meta(g.id))
-            .unwrap_or_default();
+        self.meta = self.metas.meta(ctx.props().node.id());
         true
     }
 
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        self.track_child_window(ctx);
+    }
+
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         let our_idx = ctx.props().path.last().copied().unwrap_or_default();
         match msg {
@@ -283,12 +485,7 @@ impl Component for NodeDisplay {
             }
             Msg::MetaContextChange(metas) => {
                 self.metas = metas;
-                let meta = ctx
-                    .props()
-                    .node
-                    .group()
-                    .map(|g| self.metas.meta(g.id))
-                    .unwrap_or_default();
+                let meta = self.metas.meta(ctx.props().node.id());
                 if self.meta != meta {
                     self.meta = meta;
                     true
@@ -297,10 +494,29 @@ impl Component for NodeDisplay {
                 }
             }
             Msg::UserSettingsChange(user_settings) => {
+                // Only build tracking and balanced group settings affect rendering. Backdrive mode
+                // is only read when backdriving.
+                let redraw = self.user_settings.track_build_progress
+                    != user_settings.track_build_progress
+                    || self.user_settings.balanced_tolerance != user_settings.balanced_tolerance
+                    || self.user_settings.only_unbalanced_groups
+                        != user_settings.only_unbalanced_groups
+                    || self.user_settings.show_percent_contributions
+                        != user_settings.show_percent_contributions;
                 self.user_settings = user_settings;
-                // Currently user settings are only used for backdrive mode, so we never need to
-                // redraw when they change.
-                false
+                redraw
+            }
+            Msg::PowerSharesChange(power_shares) => {
+                let id = ctx.props().node.id();
+                let redraw = self.power_shares.0.get(&id) != power_shares.0.get(&id);
+                self.power_shares = power_shares;
+                redraw
+            }
+            Msg::ReadOnlyChange(read_only) => {
+                let read_only = read_only.is_read_only();
+                let redraw = self.read_only != read_only;
+                self.read_only = read_only;
+                redraw
             }
             Msg::SetCopyCount { copies } => {
                 match ctx.props().node.kind() {
@@ -320,6 +536,63 @@ impl Component for NodeDisplay {
                 }
                 false
             }
+            Msg::ToggleNotes => {
+                self.notes_open = !self.notes_open;
+                true
+            }
+            Msg::SetNotes { notes } => {
+                let meta = NodeMeta {
+                    notes,
+                    ..self.meta.clone()
+                };
+                ctx.props().set_metadata.emit((ctx.props().node.id(), meta));
+                false
+            }
+            Msg::SetColor { color } => {
+                let meta = NodeMeta {
+                    color,
+                    ..self.meta.clone()
+                };
+                ctx.props().set_metadata.emit((ctx.props().node.id(), meta));
+                false
+            }
+            Msg::SetCircuit {
+                circuit,
+                power_storages,
+            } => {
+                let meta = NodeMeta {
+                    circuit,
+                    power_storages,
+                    ..self.meta.clone()
+                };
+                ctx.props().set_metadata.emit((ctx.props().node.id(), meta));
+                false
+            }
+            Msg::TogglePinned => {
+                let pin = (!self.meta.pinned).then(|| ctx.props().node.id());
+                ctx.props()
+                    .batch_set_metadata
+                    .emit(self.metas.pin_updates(pin));
+                false
+            }
+            Msg::TraceItem { target, expand } => {
+                if expand {
+                    self.traced = Some(target);
+                    self.expand_contributors(ctx, target);
+                } else if self.traced == Some(target) {
+                    self.traced = None;
+                } else {
+                    self.traced = Some(target);
+                    if self.meta.collapsed {
+                        let meta = NodeMeta {
+                            collapsed: false,
+                            ..self.meta.clone()
+                        };
+                        ctx.props().set_metadata.emit((ctx.props().node.id(), meta));
+                    }
+                }
+                true
+            }
             Msg::ReplaceChild { idx, replacement } => {
                 if let NodeKind::Group(group) = ctx.props().node.kind() {
                     if idx < group.children.len() {
@@ -337,6 +610,23 @@ impl Component for NodeDisplay {
                 }
                 false
             }
+            Msg::ChainBackdriveChild { idx, before } => {
+                if let NodeKind::Group(group) = ctx.props().node.kind() {
+                    if idx < group.children.len() {
+                        let mut new_group = group.clone();
+                        new_group.children = self.chain_backdrive(&group.children, idx, &before);
+                        ctx.props().replace.emit((our_idx, new_group.into()));
+                    } else {
+                        warn!(
+                            "Cannot chain backdrive child index {}; out of range for this group",
+                            idx
+                        );
+                    }
+                } else {
+                    warn!("Cannot chain backdrive child of a non-group");
+                }
+                false
+            }
             Msg::DeleteChild { idx } => {
                 if let NodeKind::Group(group) = ctx.props().node.kind() {
                     if idx < group.children.len() {
@@ -354,23 +644,13 @@ impl Component for NodeDisplay {
                 }
                 false
             }
-            Msg::CopyChild { idx } => {
+            Msg::CopyChild { idx, count } => {
                 if let NodeKind::Group(group) = ctx.props().node.kind() {
                     if idx < group.children.len() {
                         let mut new_group = group.clone();
-                        let new_meta = RefCell::new(HashMap::new());
-                        let (metas, _) = ctx
-                            .link()
-                            .context::<NodeMetas>(Callback::noop())
-                            .expect("NodeDisplay must be in the WorldManager's context");
-                        let copied = new_group.children[idx].create_copy_with_visitor(
-                            &|old: &Group, new: &mut Group| {
-                                let meta = metas.meta(old.id);
-                                new_meta.borrow_mut().insert(new.id, meta);
-                            },
-                        );
-                        new_group.children.insert(idx + 1, copied);
-                        ctx.props().batch_set_metadata.emit(new_meta.into_inner());
+                        let copies =
+                            self.copies_with_metadata(ctx, &new_group.children[idx], count);
+                        new_group.children.splice(idx + 1..idx + 1, copies);
                         ctx.props().replace.emit((our_idx, new_group.into()));
                     } else {
                         warn!(
@@ -406,6 +686,32 @@ impl Component for NodeDisplay {
                 }
                 false
             }
+            Msg::SetGroupDatabase { database } => {
+                if let NodeKind::Group(group) = ctx.props().node.kind() {
+                    if database != group.database {
+                        let mut new_group = group.clone();
+                        new_group.database = database;
+                        // Children need to be rebuilt with the newly resolved database.
+                        let new_node = Node::from(new_group).rebuild(&self.db);
+                        ctx.props().replace.emit((our_idx, new_node));
+                    }
+                } else {
+                    warn!("Cannot set the database of a non-group");
+                }
+                false
+            }
+            Msg::SetRecirculated { items } => {
+                if let NodeKind::Group(group) = ctx.props().node.kind() {
+                    if items != group.recirculated {
+                        let mut new_group = group.clone();
+                        new_group.recirculated = items;
+                        ctx.props().replace.emit((our_idx, new_group.into()));
+                    }
+                } else {
+                    warn!("Cannot recirculate containers in a non-group");
+                }
+                false
+            }
             Msg::DragEnter { insert_pos } => {
                 self.insert_count = self
                     .insert_count
@@ -438,33 +744,41 @@ impl Component for NodeDisplay {
             Msg::MoveNode {
                 src_path,
                 dest_path,
+                copy,
             } => {
                 let path = &ctx.props().path[..];
                 let prefix_len = path.len();
-                debug_assert!(
-                    prefix_len < dest_path.len(),
-                    "Got asked to move a node for a parent."
-                );
+                // Moving a node out of its group puts the destination beside this node, so the
+                // move has to be handled further up.
                 if prefix_len < src_path.len()
+                    && prefix_len < dest_path.len()
                     && path == &src_path[..prefix_len]
                     && path == &dest_path[..prefix_len]
                 {
                     // This node is the common ancestor of the source and destination
                     // paths.
                     if let NodeKind::Group(group) = ctx.props().node.kind() {
-                        if let Some(new_group) = graph_manipulation::move_child(
-                            group,
-                            &src_path[prefix_len..],
-                            &dest_path[prefix_len..],
-                        ) {
-                            ctx.props().replace.emit((our_idx, new_group.into()));
+                        let new_group = if copy {
+                            self.copy_child_to(ctx, group, &src_path, &dest_path)
+                        } else {
+                            graph_manipulation::move_child(
+                                group,
+                                &src_path[prefix_len..],
+                                &dest_path[prefix_len..],
+                            )
+                        };
+                        if let Some(new_group) = new_group {
+                            // The moved node may now be under a group with a different
+                            // pinned database, so rebuild it in its new position.
+                            let new_node = Node::from(new_group).rebuild(&self.db);
+                            ctx.props().replace.emit((our_idx, new_node));
                         }
                     } else {
                         warn!("Attempting to move nodes in a non-group.");
                     }
                 } else {
                     // No common ancestor yet, ask parent to do the move.
-                    ctx.props().move_node.emit((src_path, dest_path));
+                    ctx.props().move_node.emit((src_path, dest_path, copy));
                 }
                 if self.insert_pos.is_some() {
                     self.insert_pos = None;
@@ -473,6 +787,30 @@ impl Component for NodeDisplay {
                     false
                 }
             }
+            Msg::TogglePowerChart => {
+                self.power_chart_open = !self.power_chart_open;
+                true
+            }
+            Msg::ToggleFootprint => {
+                self.footprint_open = !self.footprint_open;
+                true
+            }
+            Msg::ToggleBatchEdit => {
+                self.batch_edit_open = !self.batch_edit_open;
+                true
+            }
+            Msg::TogglePlanner => {
+                self.planner_open = !self.planner_open;
+                true
+            }
+            Msg::ReplaceSelf { replacement } => {
+                ctx.props().replace.emit((our_idx, replacement));
+                false
+            }
+            Msg::UpdateChildWindow => match ctx.props().node.group() {
+                Some(group) => self.update_child_window(group),
+                None => false,
+            },
             Msg::ChangeType { id } => {
                 if let NodeKind::Building(building) = ctx.props().node.kind() {
                     if building.building != Some(id) {
@@ -495,6 +833,21 @@ impl Component for NodeDisplay {
                 }
                 false
             }
+            Msg::SetBuilt { built } => {
+                if let NodeKind::Building(building) = ctx.props().node.kind() {
+                    if building.built != built {
+                        let mut new_bldg = building.clone();
+                        new_bldg.built = built;
+                        match new_bldg.build_node(&self.db) {
+                            Ok(new_node) => ctx.props().replace.emit((our_idx, new_node)),
+                            Err(e) => warn!("Unable to build node: {}", e),
+                        }
+                    }
+                } else {
+                    warn!("Cannot mark a non-building as built");
+                }
+                false
+            }
             Msg::ChangeRecipe { id } => {
                 let building = match ctx.props().node.kind() {
                     NodeKind::Building(building) => building,
@@ -710,6 +1063,39 @@ impl Component for NodeDisplay {
                 }
                 false
             }
+            Msg::ChangeOutputTier { tier } => {
+                if let NodeKind::Building(building) = ctx.props().node.kind() {
+                    if building.settings.output_tier() != tier {
+                        let mut new_bldg = building.clone();
+                        new_bldg.settings.set_output_tier(tier);
+                        match new_bldg.build_node(&self.db) {
+                            Ok(new_node) => ctx.props().replace.emit((our_idx, new_node)),
+                            Err(e) => warn!("Unable to build node: {}", e),
+                        }
+                    }
+                } else {
+                    warn!("Cannot change output tier of a non-building");
+                }
+                false
+            }
+            Msg::ChangeLogistics { item, logistics } => {
+                if let NodeKind::Building(building) = ctx.props().node.kind() {
+                    if building.logistics.get(&item) != logistics.as_ref() {
+                        let mut new_bldg = building.clone();
+                        match logistics {
+                            Some(logistics) => new_bldg.logistics.insert(item, logistics),
+                            None => new_bldg.logistics.remove(&item),
+                        };
+                        match new_bldg.build_node(&self.db) {
+                            Ok(new_node) => ctx.props().replace.emit((our_idx, new_node)),
+                            Err(e) => warn!("Unable to build node: {}", e),
+                        }
+                    }
+                } else {
+                    warn!("Cannot change logistics of a non-building");
+                }
+                false
+            }
             Msg::ChangePurity { purity } => {
                 let building = match ctx.props().node.kind() {
                     NodeKind::Building(building) => building,
@@ -752,6 +1138,56 @@ impl Component for NodeDisplay {
 
                 false
             }
+            Msg::SetGeyserCluster { cluster } => {
+                let building = match ctx.props().node.kind() {
+                    NodeKind::Building(building) => building,
+                    _ => {
+                        warn!("Cannot change geysers of a non-building");
+                        return false;
+                    }
+                };
+                let BuildingSettings::Geothermal(gs) = &building.settings else {
+                    warn!(
+                        "Building kind {:?} does not support geyser clusters",
+                        building.settings.kind_id()
+                    );
+                    return false;
+                };
+                if gs.geysers.is_some() == cluster {
+                    return false;
+                }
+                let mut gs = gs.clone();
+                let copies;
+                match gs.geysers.take() {
+                    // Turn the copies into geysers of the cluster, so the power stays the same.
+                    None => {
+                        let mut geysers = PurityCounts::default();
+                        *geysers.get_mut(gs.purity) = building.copies.round().max(1.0) as u32;
+                        gs.geysers = Some(geysers);
+                        copies = 1.0;
+                    }
+                    // Keep the number of generators, on the most common purity in the cluster.
+                    Some(geysers) => {
+                        if let Some(purity) =
+                            ResourcePurity::values().max_by_key(|&purity| geysers.get(purity))
+                        {
+                            gs.purity = purity;
+                        }
+                        copies = (geysers.total().max(1) * building.copies.round() as u32) as f32;
+                    }
+                }
+                let new_bldg = Building {
+                    settings: gs.into(),
+                    copies,
+                    ..building.clone()
+                };
+                match new_bldg.build_node(&self.db) {
+                    Ok(new_node) => ctx.props().replace.emit((our_idx, new_node)),
+                    Err(e) => warn!("Unable to build node: {}", e),
+                }
+
+                false
+            }
             Msg::ChangePumpPurity { purity, num_pads } => {
                 let building = match ctx.props().node.kind() {
                     NodeKind::Building(building) => building,
@@ -774,6 +1210,13 @@ impl Component for NodeDisplay {
                         }
                         ps.into()
                     }
+                    BuildingSettings::Geothermal(gs) if gs.geysers.is_some() => {
+                        let mut gs = gs.clone();
+                        if let Some(geysers) = &mut gs.geysers {
+                            *geysers.get_mut(purity) = num_pads;
+                        }
+                        gs.into()
+                    }
                     _ => {
                         warn!(
                             "Building kind {:?} does not support multi-purity",
@@ -830,14 +1273,105 @@ impl Component for NodeDisplay {
 
                 false
             }
+            Msg::ChangeTraffic { traffic } => {
+                let building = match ctx.props().node.kind() {
+                    NodeKind::Building(building) => building,
+                    _ => {
+                        warn!("Cannot change station traffic of a non-building");
+                        return false;
+                    }
+                };
+                if building.building.is_none() {
+                    warn!("Cannot change station traffic, building not set");
+                    return false;
+                };
+                let settings = match &building.settings {
+                    BuildingSettings::Station(ss) => StationSettings {
+                        traffic,
+                        ..ss.clone()
+                    }
+                    .into(),
+                    _ => {
+                        warn!(
+                            "Building kind {:?} does not support vehicle traffic",
+                            building.settings.kind_id()
+                        );
+                        return false;
+                    }
+                };
+                let new_bldg = Building {
+                    settings,
+                    ..building.clone()
+                };
+                match new_bldg.build_node(&self.db) {
+                    Ok(new_node) => ctx.props().replace.emit((our_idx, new_node)),
+                    Err(e) => warn!("Unable to build node: {}", e),
+                }
+
+                false
+            }
+            Msg::ChangeManifest { manifest } => {
+                let building = match ctx.props().node.kind() {
+                    NodeKind::Building(building) => building,
+                    _ => {
+                        warn!("Cannot change station manifest of a non-building");
+                        return false;
+                    }
+                };
+                if building.building.is_none() {
+                    warn!("Cannot change station manifest, building not set");
+                    return false;
+                };
+                let settings = match &building.settings {
+                    BuildingSettings::Station(ss) => StationSettings {
+                        manifest,
+                        ..ss.clone()
+                    }
+                    .into(),
+                    _ => {
+                        warn!(
+                            "Building kind {:?} does not support a freight manifest",
+                            building.settings.kind_id()
+                        );
+                        return false;
+                    }
+                };
+                let new_bldg = Building {
+                    settings,
+                    ..building.clone()
+                };
+                match new_bldg.build_node(&self.db) {
+                    Ok(new_node) => ctx.props().replace.emit((our_idx, new_node)),
+                    Err(e) => warn!("Unable to build node: {}", e),
+                }
+
+                false
+            }
             Msg::Backdrive { id, rate } => {
-                if let Some(new_node) = self.backdrive(&ctx.props().node, id, rate) {
+                let node = &ctx.props().node;
+                if let Some(new_node) = self.backdrive(node, id, rate) {
+                    // Keep the original state across repeated backdrives, so a chain backdrive
+                    // covers the whole change.
+                    let before = match self.backdriven.take() {
+                        Some((before, after)) if after == *node => before,
+                        _ => node.clone(),
+                    };
+                    self.backdriven = Some((before, new_node.clone()));
                     ctx.props().replace.emit((our_idx, new_node));
                 }
-                // Never need to redraw because this doesn't change our state, and we will redraw
-                // when the parent calls us back with new props.
+                // Never need to redraw because we will redraw when the parent calls us back with
+                // new props.
                 false
             }
+            Msg::ChainBackdrive => {
+                if let (Some((before, _)), Some(chain_backdrive)) =
+                    (self.backdriven.take(), &ctx.props().chain_backdrive)
+                {
+                    chain_backdrive.emit((our_idx, before));
+                }
+                true
+            }
+            Msg::DismissChainBackdrive => self.backdriven.take().is_some(),
         }
     }
 
@@ -859,6 +1393,7 @@ impl NodeDisplay {
     /// Creates the delete button, if the parent allows this node to be deleted.
     fn delete_button(&self, ctx: &Context<Self>) -> Html {
         match ctx.props().delete.clone() {
+            Some(_) if self.read_only => html! {},
             Some(delete_from_parent) => {
                 let idx = ctx
                     .props()
@@ -877,9 +1412,38 @@ impl NodeDisplay {
         }
     }
 
+    /// Create a true copy of the given node, with new ids, and copy the metadata of the original
+    /// nodes over to the new ids.
+    fn copy_with_metadata(&self, ctx: &Context<Self>, node: &Node) -> Node {
+        self.copies_with_metadata(ctx, node, 1)
+            .pop()
+            .expect("Asked for exactly one copy")
+    }
+
+    /// Make the given number of copies of a node, each with new ids and a copy of the original's
+    /// metadata. The metadata of all copies is set at once.
+    fn copies_with_metadata(&self, ctx: &Context<Self>, node: &Node, count: usize) -> Vec<Node> {
+        let new_meta = RefCell::new(HashMap::new());
+        let (metas, _) = ctx
+            .link()
+            .context::<NodeMetas>(Callback::noop())
+            .expect("NodeDisplay must be in the WorldManager's context");
+        let copies = (0..count)
+            .map(|_| {
+                node.create_copy_with_visitor(&|old: Uuid, new: Uuid| {
+                    let meta = metas.meta(old);
+                    new_meta.borrow_mut().insert(new, meta);
+                })
+            })
+            .collect();
+        ctx.props().batch_set_metadata.emit(new_meta.into_inner());
+        copies
+    }
+
     /// Creates the copy button, if the parent allows this node to be copied.
     fn copy_button(&self, ctx: &Context<Self>) -> Html {
         match ctx.props().copy.clone() {
+            Some(_) if self.read_only => html! {},
             Some(copy_from_parent) => {
                 let idx = ctx
                     .props()
@@ -887,7 +1451,7 @@ impl NodeDisplay {
                     .last()
                     .copied()
                     .expect("Parent provided a copy callback, but this is the root node.");
-                let onclick = Callback::from(move |_| copy_from_parent.emit(idx));
+                let onclick = Callback::from(move |_| copy_from_parent.emit((idx, 1)));
                 html! {
                     <Button {onclick} class="green" title="Copy">
                         {material_icon("content_copy")}
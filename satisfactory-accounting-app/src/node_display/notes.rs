@@ -0,0 +1,115 @@
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::events::get_value_from_textarea_event;
+use crate::markdown::render_markdown;
+use crate::material::{material_icon, material_icon_outlined};
+use crate::node_display::{Msg, NodeDisplay};
+
+impl NodeDisplay {
+    /// Button which shows this node's notes as a tooltip and opens the notes panel.
+    pub(super) fn notes_button(&self, ctx: &Context<Self>) -> Html {
+        let notes = &self.meta.notes;
+        if notes.is_empty() && self.read_only {
+            return html! {};
+        }
+        let onclick = ctx.link().callback(|_| Msg::ToggleNotes);
+        let title = if notes.is_empty() {
+            AttrValue::from("Add Notes")
+        } else {
+            notes.clone()
+        };
+        html! {
+            <Button class="notes-button" {onclick} {title}>
+                if notes.is_empty() {
+                    {material_icon_outlined("sticky_note_2")}
+                } else {
+                    {material_icon("sticky_note_2")}
+                }
+            </Button>
+        }
+    }
+
+    /// Panel spanning the whole row which shows and edits this node's notes, if it is open.
+    pub(super) fn notes_panel(&self, ctx: &Context<Self>) -> Html {
+        if !self.notes_open {
+            return html! {};
+        }
+        let on_change = ctx.link().callback(|notes| Msg::SetNotes { notes });
+        html! {
+            <NodeNotes notes={self.meta.notes.clone()} {on_change} read_only={self.read_only} />
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+struct Props {
+    /// Current notes of the node.
+    notes: AttrValue,
+    /// Callback to change the notes.
+    on_change: Callback<AttrValue>,
+    /// Whether the notes can only be viewed.
+    read_only: bool,
+}
+
+/// Shows a node's notes rendered as markdown, with an editor to change them.
+#[function_component]
+fn NodeNotes(
+    Props {
+        notes,
+        on_change,
+        read_only,
+    }: &Props,
+) -> Html {
+    // Start out editing when there's nothing to show yet.
+    let draft = use_state(|| (notes.is_empty() && !read_only).then(|| notes.clone()));
+
+    let start_edit = use_callback((draft.clone(), notes.clone()), |_, (draft, notes)| {
+        draft.set(Some(notes.clone()));
+    });
+    let oninput = use_callback(draft.clone(), |e, draft| {
+        draft.set(Some(get_value_from_textarea_event(e)));
+    });
+    let save = use_callback(
+        (draft.clone(), on_change.clone()),
+        |_, (draft, on_change)| {
+            if let Some(text) = &**draft {
+                on_change.emit(text.trim_end().to_owned().into());
+            }
+            draft.set(None);
+        },
+    );
+    let cancel = use_callback(draft.clone(), |_, draft| draft.set(None));
+
+    match &*draft {
+        Some(text) if !read_only => html! {
+            <div class="NodeNotes editing">
+                <textarea value={text.clone()} {oninput} rows="5"
+                    placeholder="Notes about this node. Supports **bold**, *italics*, `code`, \
+                    # headings, - lists, and - [ ] to-dos." />
+                <div class="notes-actions">
+                    <Button class="green" title="Save Notes" onclick={save}>
+                        {material_icon("check")}
+                    </Button>
+                    <Button class="red" title="Cancel" onclick={cancel}>
+                        {material_icon("close")}
+                    </Button>
+                </div>
+            </div>
+        },
+        _ => html! {
+            <div class="NodeNotes">
+                <div class="notes-content">
+                    {render_markdown(notes)}
+                </div>
+                if !read_only {
+                    <div class="notes-actions">
+                        <Button title="Edit Notes" onclick={start_edit}>
+                            {material_icon("edit")}
+                        </Button>
+                    </div>
+                }
+            </div>
+        },
+    }
+}
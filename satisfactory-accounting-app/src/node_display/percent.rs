@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use satisfactory_accounting::accounting::Group;
+use satisfactory_accounting::analysis::GrossBalance;
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::material::material_icon;
+use crate::node_display::NodeDisplay;
+
+/// Share of the world's power draw that each group is responsible for.
+#[derive(Debug, Default, Clone)]
+pub struct PowerShares(pub Rc<HashMap<Uuid, f32>>);
+
+impl PartialEq for PowerShares {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Format a fraction as a percentage for display next to a balance.
+pub fn format_share(share: f32) -> String {
+    let percent = share * 100.0;
+    if percent > 0.0 && percent < 1.0 {
+        "<1%".to_owned()
+    } else {
+        format!("{percent:.0}%")
+    }
+}
+
+impl NodeDisplay {
+    /// Totals of this group's children to compare each child's balance against, if percent
+    /// contributions are shown.
+    pub(super) fn child_totals(&self, group: &Group) -> Option<Rc<GrossBalance>> {
+        self.user_settings
+            .show_percent_contributions
+            .then(|| Rc::new(GrossBalance::of_nodes(&group.children)))
+    }
+
+    /// Badge showing this group's share of the world's power draw, if percent contributions are
+    /// shown.
+    pub(super) fn power_share_badge(&self, ctx: &Context<Self>) -> Html {
+        if !self.user_settings.show_percent_contributions || ctx.props().path.is_empty() {
+            return html! {};
+        }
+        match self.power_shares.0.get(&ctx.props().node.id()) {
+            Some(&share) if share > 0.0 => html! {
+                <span class="power-share" title="Share of the whole world's power draw">
+                    {material_icon("bolt")}
+                    {format_share(share)}
+                </span>
+            },
+            _ => html! {},
+        }
+    }
+}
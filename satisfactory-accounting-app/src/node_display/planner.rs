@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::database::{Database, Item, ItemId, RecipeId};
+use satisfactory_accounting::optimizer::{OptimizeGoal, OptimizeRequest};
+use satisfactory_accounting::planner::PlanTarget;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::inputs::choose_from_list::{Choice, ChooseFromList};
+use crate::inputs::clickedit::ClickEdit;
+use crate::inputs::toggle::{MaterialCheckbox, MaterialRadio};
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::node_display::{Msg, NodeDisplay};
+use crate::user_settings::use_user_settings;
+use crate::world::use_db;
+
+/// Rate per minute a new plan starts with.
+const DEFAULT_RATE: f32 = 10.0;
+
+impl NodeDisplay {
+    /// Button which shows or hides the production planner of this group.
+    pub(super) fn planner_button(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.link().callback(|_| Msg::TogglePlanner);
+        let title = if self.planner_open {
+            "Hide Production Planner"
+        } else {
+            "Plan Production of an Item"
+        };
+        html! {
+            <Button class="green" {onclick} {title}>
+                {material_icon("auto_fix_high")}
+            </Button>
+        }
+    }
+
+    /// Panel spanning the whole row for planning a new group which makes an item, if it is open.
+    pub(super) fn planner_panel(&self, ctx: &Context<Self>) -> Html {
+        if !self.planner_open || self.read_only {
+            return html! {};
+        }
+        let on_plan = ctx
+            .link()
+            .batch_callback(|child| vec![Msg::AddChild { child }, Msg::TogglePlanner]);
+        html! {
+            <ProductionPlanner {on_plan} />
+        }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct Props {
+    /// Callback with the planned group.
+    on_plan: Callback<Node>,
+}
+
+/// Planner which creates a group of buildings making an item at a target rate from a chosen set
+/// of recipes, either directly or by optimizing the mix of recipes used.
+#[function_component]
+fn ProductionPlanner(Props { on_plan }: &Props) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let rate_unit = balance_settings.rate_unit;
+    let target = use_state_eq(|| None::<PlanTarget>);
+    let choosing = use_state_eq(|| false);
+    let goal = use_state_eq(OptimizeGoal::default);
+    let inputs = use_state_eq(BTreeMap::<ItemId, f32>::new);
+    let choosing_input = use_state_eq(|| false);
+    let error = use_state_eq(|| None::<AttrValue>);
+
+    let item_button = {
+        let onclick = {
+            let choosing = choosing.clone();
+            Callback::from(move |()| choosing.set(true))
+        };
+        let label = match target.as_ref().map(|target| db.get(target.item)) {
+            Some(Some(item)) => html! {<>
+                <Icon icon={item.image.clone()} name={item.name.clone()} />
+                <span>{&item.name}</span>
+            </>},
+            Some(None) => html! {<>
+                <Icon />
+                <span>{"Unknown Item"}</span>
+            </>},
+            None => html! { <span>{"select item"}</span> },
+        };
+        html! {
+            <Button class="planner-item" title="Item to Make" {onclick}>{label}</Button>
+        }
+    };
+    let item_chooser = {
+        let on_selected = {
+            let db = db.clone();
+            let target = target.clone();
+            let choosing = choosing.clone();
+            Callback::from(move |item: ItemId| {
+                choosing.set(false);
+                let rate = target.as_ref().map_or(DEFAULT_RATE, |target| target.rate);
+                target.set(Some(PlanTarget::new(item, rate, &db)));
+            })
+        };
+        let on_cancelled = {
+            let choosing = choosing.clone();
+            Callback::from(move |()| choosing.set(false))
+        };
+        html! {
+            <ChooseFromList<ItemId> class="planner-item" title="Item to Make"
+                choices={item_choices(&db, |item| !item.produced_by.is_empty())}
+                {on_selected} {on_cancelled} />
+        }
+    };
+
+    let Some(current) = target.as_ref() else {
+        return html! {
+            <div class="ProductionPlanner">
+                <div class="planner-target">
+                    <span>{"Make:"}</span>
+                    if *choosing {
+                        {item_chooser}
+                    } else {
+                        {item_button}
+                    }
+                </div>
+            </div>
+        };
+    };
+
+    let set_rate = {
+        let target = target.clone();
+        Callback::from(move |edit_text: AttrValue| {
+            let (Ok(rate), Some(current)) = (edit_text.parse::<f32>(), target.as_ref()) else {
+                return;
+            };
+            target.set(Some(PlanTarget {
+                rate: rate_unit.to_per_minute(rate.abs()),
+                ..current.clone()
+            }));
+        })
+    };
+    let toggle_recipe = |recipe: RecipeId| {
+        let target = target.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(current) = target.as_ref() else {
+                return;
+            };
+            let mut allowed_recipes = current.allowed_recipes.clone();
+            if !allowed_recipes.remove(&recipe) {
+                allowed_recipes.insert(recipe);
+            }
+            target.set(Some(PlanTarget {
+                allowed_recipes,
+                ..current.clone()
+            }));
+        })
+    };
+    let recipe_row = |id: RecipeId| {
+        let checked = current.allowed_recipes.contains(&id);
+        let onclick = toggle_recipe(id);
+        match db.get(id) {
+            Some(recipe) => html! {
+                <label class="planner-recipe">
+                    <MaterialCheckbox {checked} {onclick} />
+                    <Icon icon={recipe.image.clone()} name={recipe.name.clone()} />
+                    <span>{&recipe.name}</span>
+                    if recipe.is_alternate {
+                        <span class="alternate">{"Alternate"}</span>
+                    }
+                </label>
+            },
+            None => html! {},
+        }
+    };
+    let rate_label = format!(
+        "{}{}",
+        balance_settings.format_rate(current.rate),
+        balance_settings.rate_suffix()
+    );
+    let plan = {
+        let db = db.clone();
+        let on_plan = on_plan.clone();
+        let current = current.clone();
+        let rate_label = rate_label.clone();
+        Callback::from(move |()| on_plan.emit(current.build_group(&db, &rate_label)))
+    };
+    let optimize = {
+        let db = db.clone();
+        let on_plan = on_plan.clone();
+        let error = error.clone();
+        let request = OptimizeRequest {
+            target: current.clone(),
+            inputs: (*inputs).clone(),
+            goal: *goal,
+        };
+        Callback::from(move |()| match request.build_group(&db, &rate_label) {
+            Ok(group) => on_plan.emit(group),
+            Err(e) => error.set(Some(e.to_string().into())),
+        })
+    };
+    let goal_choice = |choice: OptimizeGoal, name: &'static str| {
+        let onclick = {
+            let goal = goal.clone();
+            Callback::from(move |_: MouseEvent| goal.set(choice))
+        };
+        html! {
+            <label class="planner-goal">
+                <MaterialRadio checked={*goal == choice} {onclick} />
+                <span>{name}</span>
+            </label>
+        }
+    };
+    let input_row = |(&item, &rate): (&ItemId, &f32)| {
+        let set_rate = {
+            let inputs = inputs.clone();
+            Callback::from(move |edit_text: AttrValue| {
+                if let Ok(rate) = edit_text.parse::<f32>() {
+                    let mut updated = (*inputs).clone();
+                    updated.insert(item, rate_unit.to_per_minute(rate.abs()));
+                    inputs.set(updated);
+                }
+            })
+        };
+        let remove = {
+            let inputs = inputs.clone();
+            Callback::from(move |()| {
+                let mut updated = (*inputs).clone();
+                updated.remove(&item);
+                inputs.set(updated);
+            })
+        };
+        let label = match db.get(item) {
+            Some(item) => html! {<>
+                <Icon icon={item.image.clone()} name={item.name.clone()} />
+                <span>{&item.name}</span>
+            </>},
+            None => html! {<>
+                <Icon />
+                <span>{"Unknown Item"}</span>
+            </>},
+        };
+        html! {
+            <span class="planner-input">
+                {label}
+                <ClickEdit value={rate_unit.convert(rate).to_string()}
+                    title="Most Available" on_commit={set_rate}
+                    suffix={html! { <span>{rate_unit.suffix()}</span> }} />
+                <Button class="red" title="Remove Input" onclick={remove}>
+                    {material_icon("close")}
+                </Button>
+            </span>
+        }
+    };
+    let add_input = if *choosing_input {
+        let on_selected = {
+            let inputs = inputs.clone();
+            let choosing_input = choosing_input.clone();
+            Callback::from(move |item: ItemId| {
+                choosing_input.set(false);
+                let mut updated = (*inputs).clone();
+                updated.entry(item).or_insert(DEFAULT_RATE);
+                inputs.set(updated);
+            })
+        };
+        let on_cancelled = {
+            let choosing_input = choosing_input.clone();
+            Callback::from(move |()| choosing_input.set(false))
+        };
+        html! {
+            <ChooseFromList<ItemId> class="planner-item" title="Supplied Item"
+                choices={item_choices(&db, |_| true)} {on_selected} {on_cancelled} />
+        }
+    } else {
+        let onclick = {
+            let choosing_input = choosing_input.clone();
+            Callback::from(move |()| choosing_input.set(true))
+        };
+        html! {
+            <Button title="Add an item supplied from elsewhere" {onclick}>
+                {material_icon("add")}
+            </Button>
+        }
+    };
+
+    html! {
+        <div class="ProductionPlanner">
+            <div class="planner-target">
+                <span>{"Make:"}</span>
+                if *choosing {
+                    {item_chooser}
+                } else {
+                    {item_button}
+                }
+                <ClickEdit value={rate_unit.convert(current.rate).to_string()}
+                    title="Target Rate" on_commit={set_rate}
+                    suffix={html! { <span>{rate_unit.suffix()}</span> }} />
+                <Button class="green" title="Create a group which makes this item" onclick={plan}>
+                    {material_icon("add")}
+                    <span>{"Create Group"}</span>
+                </Button>
+            </div>
+            <div class="planner-recipes">
+                <span>{"Allowed recipes:"}</span>
+                {for PlanTarget::chain_recipes(current.item, &db).into_iter().map(recipe_row)}
+            </div>
+            <div class="planner-optimize">
+                <span>{"Supplied inputs:"}</span>
+                {for inputs.iter().map(input_row)}
+                {add_input}
+            </div>
+            <div class="planner-optimize">
+                <span>{"Optimize for:"}</span>
+                {goal_choice(OptimizeGoal::RawResources, "Fewest Raw Resources")}
+                {goal_choice(OptimizeGoal::Power, "Least Power")}
+                <Button class="green" onclick={optimize}
+                    title="Create a group using the best mix of the allowed recipes">
+                    {material_icon("insights")}
+                    <span>{"Optimize"}</span>
+                </Button>
+                if let Some(error) = &*error {
+                    <span class="planner-error">{error}</span>
+                }
+            </div>
+        </div>
+    }
+}
+
+/// Build the list of items matching `filter` to choose from, sorted by name.
+fn item_choices(db: &Database, filter: impl Fn(&Item) -> bool) -> Vec<Choice<ItemId>> {
+    let collator = crate::locale::get_collator();
+    let mut items: Vec<_> = db.items().filter(|item| filter(item)).collect();
+    items.sort_by(|lhs, rhs| collator.compare(&lhs.name, &rhs.name));
+    items
+        .into_iter()
+        .map(|item| Choice {
+            id: item.id,
+            name: item.name.clone().into(),
+            image: html! {
+                <Icon icon={item.image.clone()} name={item.name.clone()} />
+            },
+        })
+        .collect()
+}
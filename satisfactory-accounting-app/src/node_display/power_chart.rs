@@ -0,0 +1,125 @@
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::database::BuildingId;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::percent::format_share;
+use crate::node_display::{node_name, Msg, NodeDisplay};
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::use_db;
+
+/// Most bars shown in the chart. Any remaining consumers are combined into one "Other" bar.
+const MAX_BARS: usize = 8;
+
+impl NodeDisplay {
+    /// Button which shows or hides the power breakdown of this group.
+    pub(super) fn power_chart_button(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.link().callback(|_| Msg::TogglePowerChart);
+        let title = if self.power_chart_open {
+            "Hide Power Breakdown"
+        } else {
+            "Show Power Breakdown"
+        };
+        html! {
+            <Button {onclick} {title}>
+                {material_icon("bar_chart")}
+            </Button>
+        }
+    }
+
+    /// Chart spanning the whole row which breaks down this group's power consumption, if it is
+    /// open.
+    pub(super) fn power_chart_panel(&self, ctx: &Context<Self>) -> Html {
+        if !self.power_chart_open {
+            return html! {};
+        }
+        html! {
+            <PowerBreakdown node={ctx.props().node.clone()} />
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+struct Props {
+    /// Group to break down the power consumption of.
+    node: Node,
+}
+
+/// Bar chart of a group's power consumption, split either by direct child or by building type.
+#[function_component]
+fn PowerBreakdown(Props { node }: &Props) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let format = &user_settings.number_display.balance.power_format_settings;
+    let by_building = use_state_eq(|| false);
+
+    let show_children = use_callback(by_building.clone(), |_, by_building| by_building.set(false));
+    let show_buildings = use_callback(by_building.clone(), |_, by_building| by_building.set(true));
+
+    let building_name = |id: BuildingId| match db.get(id) {
+        Some(building) => building.name.to_string(),
+        None => "Unknown Building".to_owned(),
+    };
+    // Consumption of each entry, as positive numbers.
+    let mut consumers: Vec<(String, f32)> = if *by_building {
+        node.power_by_building()
+            .into_iter()
+            .map(|(id, power)| (building_name(id), -power))
+            .collect()
+    } else {
+        let copies = node.group().map_or(1, |group| group.copies) as f32;
+        node.children()
+            .map(|child| (node_name(&db, &child), -child.balance().power * copies))
+            .collect()
+    };
+    consumers.retain(|(_, power)| *power > 0.0);
+    consumers.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    if consumers.len() > MAX_BARS {
+        let other: f32 = consumers
+            .drain(MAX_BARS - 1..)
+            .map(|(_, power)| power)
+            .sum();
+        consumers.push(("Other".to_owned(), other));
+    }
+
+    let total: f32 = consumers.iter().map(|(_, power)| power).sum();
+    let largest = consumers.first().map_or(0.0, |(_, power)| *power);
+    let bars = consumers.into_iter().map(|(name, power)| {
+        let style = format!("width: {}%", power / largest * 100.0);
+        html! {
+            <div class="power-bar-row">
+                <span class="power-bar-label" title={name.clone()}>{name}</span>
+                <span class="power-bar-track">
+                    <span class="power-bar" {style} />
+                </span>
+                <span class="power-bar-value">
+                    {power.format(format).to_string()}
+                    <span class="power-bar-share">{format_share(power / total)}</span>
+                </span>
+            </div>
+        }
+    });
+
+    html! {
+        <div class="PowerBreakdown">
+            <div class="power-breakdown-header">
+                <span>{"Power Consumption"}</span>
+                <Button class={classes!((!*by_building).then_some("selected"))}
+                    title="Split by Child" onclick={show_children}>
+                    {"By Child"}
+                </Button>
+                <Button class={classes!(by_building.then_some("selected"))}
+                    title="Split by Building Type" onclick={show_buildings}>
+                    {"By Building"}
+                </Button>
+            </div>
+            if total > 0.0 {
+                {for bars}
+            } else {
+                <p class="power-breakdown-empty">{"Nothing in this group consumes power."}</p>
+            }
+        </div>
+    }
+}
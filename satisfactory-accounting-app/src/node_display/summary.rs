@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use gloo::timers::callback::Timeout;
+use satisfactory_accounting::analysis::{top_contributors, Contributor};
+use satisfactory_accounting::database::{ItemId, ItemIdOrPower};
+use uuid::Uuid;
+use web_sys::{ScrollBehavior, ScrollIntoViewOptions, ScrollLogicalPosition};
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::icon::Icon;
+use crate::node_display::{Msg, NodeDisplay};
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{
+    use_db, use_item_metas, use_world_dispatcher, use_world_root, NodeMeta, NodeMetas,
+    WorldDispatcher,
+};
+
+/// Number of contributors highlighted when an entry of the summary is clicked.
+const NUM_CONTRIBUTORS: usize = 3;
+
+/// Delay before scrolling to contributors, giving groups which were expanded time to render.
+const SCROLL_DELAY_MILLIS: u32 = 50;
+
+/// How long contributors stay highlighted.
+const HIGHLIGHT_MILLIS: u32 = 2000;
+
+/// Class applied to contributors while they are highlighted.
+const HIGHLIGHT_CLASS: &str = "contributor-highlight";
+
+/// Get the id of the element which displays the node with the given id.
+pub(super) fn node_element_id(id: Uuid) -> String {
+    format!("node-{id}")
+}
+
+impl NodeDisplay {
+    /// Button to pin this group's balance to the summary panel.
+    pub(super) fn pin_button(&self, ctx: &Context<Self>) -> Html {
+        let pinned = self.meta.pinned;
+        let onclick = ctx.link().callback(|_| Msg::TogglePinned);
+        let title = if pinned {
+            "Unpin from Summary"
+        } else {
+            "Pin to Summary"
+        };
+        html! {
+            <Button class={classes!("pin-button", pinned.then_some("pinned"))} {onclick} {title}>
+                {material_icon("push_pin")}
+            </Button>
+        }
+    }
+}
+
+/// Panel which stays at the top of the screen showing the balance of the pinned group. Clicking
+/// an entry scrolls to the buildings contributing the most to it.
+#[function_component]
+pub fn PinnedSummary() -> Html {
+    let root = use_world_root();
+    let metas = use_context::<NodeMetas>()
+        .expect("PinnedSummary can only be used from within a child of WorldManager");
+    let dispatcher = use_world_dispatcher();
+    let db = use_db();
+    let item_metas = use_item_metas();
+    let user_settings = use_user_settings();
+
+    let Some(pinned) = metas
+        .pinned()
+        .and_then(|id| root.iter().find(|node| node.id() == id))
+    else {
+        return html! {};
+    };
+
+    let name = match pinned.group() {
+        _ if pinned.id() == root.id() => "Whole World".into(),
+        Some(group) if !group.name.is_empty() => group.name.clone(),
+        _ => "Unnamed Group".into(),
+    };
+
+    let unpin = {
+        let dispatcher = dispatcher.clone();
+        let metas = metas.clone();
+        Callback::from(move |()| dispatcher.batch_update_node_meta(metas.pin_updates(None)))
+    };
+
+    let show_contributors = |target: ItemIdOrPower| {
+        let dispatcher = dispatcher.clone();
+        let metas = metas.clone();
+        let pinned = pinned.clone();
+        Callback::from(move |_| {
+            let contributors = top_contributors(&pinned, target, NUM_CONTRIBUTORS);
+            reveal_contributors(&dispatcher, &metas, pinned.id(), contributors);
+        })
+    };
+
+    let balance = pinned.balance();
+    let balance_settings = &user_settings.number_display.balance;
+    let entry = |target: ItemIdOrPower, icon: Option<AttrValue>, name: &str, rate: f32| {
+        let value = match target {
+            ItemIdOrPower::Power => rate
+                .format(&balance_settings.power_format_settings)
+                .to_string(),
+            ItemIdOrPower::Item(_) => balance_settings.format_rate(rate).to_string(),
+        };
+        let direction = if rate < 0.0 { "consumers" } else { "producers" };
+        let title = format!("{name}\nClick to show the biggest {direction}");
+        let class = classes!(
+            "summary-entry",
+            if rate < 0.0 { "negative" } else { "positive" }
+        );
+        html! {
+            <Button {class} {title} onclick={show_contributors(target)}>
+                <Icon {icon} name={name.to_owned()} />
+                <span class="summary-value">{value}</span>
+            </Button>
+        }
+    };
+    let item_entry = |(&id, &rate): (&ItemId, &f32)| {
+        let (name, icon) = match db.get(id) {
+            Some(item) => (&*item.name, Some(item.image.clone().into())),
+            None => ("Unknown Item", None),
+        };
+        entry(ItemIdOrPower::Item(id), icon, name, rate)
+    };
+
+    html! {
+        <div class="PinnedSummary">
+            <div class="summary-title">
+                {material_icon("push_pin")}
+                <span>{name}</span>
+                <Button class="unpin" title="Unpin" onclick={unpin}>
+                    {material_icon("close")}
+                </Button>
+            </div>
+            <div class="summary-entries">
+                {entry(ItemIdOrPower::Power, Some("power-line".into()), "Power", balance.power)}
+                {for balance.balances.iter()
+                    .filter(|(&id, _)| !item_metas.is_ignored(id))
+                    .filter(|(_, &rate)| !user_settings.hide_empty_balances || rate != 0.0)
+                    .map(item_entry)}
+            </div>
+        </div>
+    }
+}
+
+/// Expand the searched node and every group containing one of its contributors so they're
+/// visible, then scroll to the first contributor and briefly highlight all of them.
+pub fn reveal_contributors(
+    dispatcher: &WorldDispatcher,
+    metas: &NodeMetas,
+    searched: Uuid,
+    contributors: Vec<Contributor>,
+) {
+    let expand: HashMap<Uuid, NodeMeta> = std::iter::once(searched)
+        .chain(
+            contributors
+                .iter()
+                .flat_map(|c| c.ancestors.iter().copied()),
+        )
+        .filter_map(|id| {
+            let meta = metas.meta(id);
+            meta.collapsed.then(|| {
+                let meta = NodeMeta {
+                    collapsed: false,
+                    ..meta
+                };
+                (id, meta)
+            })
+        })
+        .collect();
+    if !expand.is_empty() {
+        dispatcher.batch_update_node_meta(expand);
+    }
+    Timeout::new(SCROLL_DELAY_MILLIS, move || {
+        highlight_contributors(&contributors)
+    })
+    .forget();
+}
+
+/// Scroll to the first of the given buildings and briefly highlight all of them.
+fn highlight_contributors(contributors: &[Contributor]) {
+    let document = gloo::utils::document();
+    let elements: Vec<_> = contributors
+        .iter()
+        .filter_map(|c| document.get_element_by_id(&node_element_id(c.id)))
+        .collect();
+    // Large groups only render children near the screen, so if the first contributor isn't
+    // rendered, scroll to its closest ancestor which is.
+    let scroll_target = contributors.first().and_then(|first| {
+        std::iter::once(first.id)
+            .chain(first.ancestors.iter().rev().copied())
+            .find_map(|id| document.get_element_by_id(&node_element_id(id)))
+    });
+    if let Some(first) = scroll_target {
+        let options = ScrollIntoViewOptions::new();
+        options.set_behavior(ScrollBehavior::Smooth);
+        options.set_block(ScrollLogicalPosition::Center);
+        first.scroll_into_view_with_scroll_into_view_options(&options);
+    }
+    for element in &elements {
+        let _ = element.class_list().add_1(HIGHLIGHT_CLASS);
+    }
+    Timeout::new(HIGHLIGHT_MILLIS, move || {
+        for element in &elements {
+            let _ = element.class_list().remove_1(HIGHLIGHT_CLASS);
+        }
+    })
+    .forget();
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use satisfactory_accounting::accounting::{Group, Node};
+use satisfactory_accounting::database::ItemIdOrPower;
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::node_display::icon::Icon;
+use crate::node_display::{NodeDisplay, NodeMeta};
+use crate::user_settings::number_format::UserConfiguredFormat;
+
+/// Get a node's balance of an item or power.
+fn rate_of(node: &Node, target: ItemIdOrPower) -> f32 {
+    let balance = node.balance();
+    match target {
+        ItemIdOrPower::Power => balance.power,
+        ItemIdOrPower::Item(item) => balance.balances.get(&item).copied().unwrap_or_default(),
+    }
+}
+
+impl NodeDisplay {
+    /// Item or power whose contributors are highlighted among this group's children. This is
+    /// either the entry selected in this group's balance, or the one traced by the parent if this
+    /// group is one of its contributors.
+    pub(super) fn traced(&self, ctx: &Context<Self>) -> Option<ItemIdOrPower> {
+        self.traced
+            .or_else(|| ctx.props().contribution.map(|(target, _)| target))
+    }
+
+    /// How much the given child contributes to this group's balance of the traced item, if
+    /// anything is being traced and the child contributes to it.
+    pub(super) fn child_contribution(
+        &self,
+        ctx: &Context<Self>,
+        group: &Group,
+        child: &Node,
+    ) -> Option<(ItemIdOrPower, f32)> {
+        let target = self.traced(ctx)?;
+        let rate = rate_of(child, target) * group.copies as f32;
+        (rate != 0.0).then_some((target, rate))
+    }
+
+    /// CSS class marking this node as contributing to the item traced by its parent.
+    pub(super) fn contributor_class(&self, ctx: &Context<Self>) -> Option<&'static str> {
+        ctx.props().contribution.map(|_| "traced-contributor")
+    }
+
+    /// Badge showing how much this node contributes to the item traced by its parent.
+    pub(super) fn contribution_badge(&self, ctx: &Context<Self>) -> Html {
+        let Some((target, rate)) = ctx.props().contribution else {
+            return html! {};
+        };
+        let balance_settings = &self.user_settings.number_display.balance;
+        let (value, name, icon): (String, AttrValue, Option<AttrValue>) = match target {
+            ItemIdOrPower::Power => (
+                rate.format(&balance_settings.power_format_settings)
+                    .to_string(),
+                "Power".into(),
+                Some("power-line".into()),
+            ),
+            ItemIdOrPower::Item(id) => match self.db.get(id) {
+                Some(item) => (
+                    balance_settings.format_rate(rate).to_string(),
+                    item.name.clone().into(),
+                    Some(item.image.clone().into()),
+                ),
+                None => (
+                    balance_settings.format_rate(rate).to_string(),
+                    "Unknown Item".into(),
+                    None,
+                ),
+            },
+        };
+        let class = classes!(
+            "contribution-badge",
+            if rate < 0.0 { "negative" } else { "positive" }
+        );
+        let title = format!("Contributes to the {name} balance of the parent group");
+        html! {
+            <span {class} {title}>
+                <Icon {icon} {name} />
+                <span>{value}</span>
+            </span>
+        }
+    }
+
+    /// Expand this group and every group inside it which contributes to the target, so the
+    /// buildings responsible for it are visible.
+    pub(super) fn expand_contributors(&self, ctx: &Context<Self>, target: ItemIdOrPower) {
+        let expand: HashMap<Uuid, NodeMeta> = ctx
+            .props()
+            .node
+            .iter()
+            .filter(|node| node.group().is_some() && rate_of(node, target) != 0.0)
+            .filter_map(|node| {
+                let meta = self.metas.meta(node.id());
+                meta.collapsed.then(|| {
+                    let meta = NodeMeta {
+                        collapsed: false,
+                        ..meta
+                    };
+                    (node.id(), meta)
+                })
+            })
+            .collect();
+        if !expand.is_empty() {
+            ctx.props().batch_set_metadata.emit(expand);
+        }
+    }
+}
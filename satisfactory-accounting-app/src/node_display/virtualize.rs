@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use gloo::events::{EventListener, EventListenerOptions, EventListenerPhase};
+use satisfactory_accounting::accounting::{Group, Node};
+use uuid::Uuid;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+use crate::node_display::summary::node_element_id;
+use crate::node_display::{Msg, NodeDisplay, DRAG_INSERT_POINT};
+
+/// Groups with more children than this only render the children near the viewport.
+const VIRTUALIZE_THRESHOLD: usize = 50;
+
+/// Number of children rendered before the first measurement of a virtualized group.
+const INITIAL_WINDOW: usize = 30;
+
+/// Distance outside of the viewport within which children are still rendered, so scrolling
+/// doesn't immediately reveal placeholders.
+const BUFFER_PX: f64 = 1000.0;
+
+/// Estimated height of a single row of the tree, used to size placeholders for children which
+/// haven't been measured yet.
+const ESTIMATED_ROW_HEIGHT: f64 = 41.0;
+
+/// CSS class for the stand-in rendered in place of children outside of the window.
+const PLACEHOLDER_CLASS: &str = "node-placeholder";
+
+/// Tracks which children of a large group are rendered.
+#[derive(Default)]
+pub(super) struct ChildWindow {
+    /// Range of child indexes which are rendered in full. Other children are placeholders.
+    visible: Range<usize>,
+    /// Last measured height of rendered children, used to size their placeholders.
+    heights: HashMap<Uuid, f64>,
+    /// Listen for scrolling and resizing to update the visible range.
+    listeners: Vec<EventListener>,
+}
+
+impl ChildWindow {
+    pub(super) fn new() -> Self {
+        Self {
+            visible: 0..INITIAL_WINDOW,
+            ..Default::default()
+        }
+    }
+}
+
+impl NodeDisplay {
+    /// Whether this node is a group large enough that only part of its children are rendered.
+    fn is_virtualized(ctx: &Context<Self>) -> bool {
+        ctx.props()
+            .node
+            .group()
+            .is_some_and(|group| group.children.len() > VIRTUALIZE_THRESHOLD)
+    }
+
+    /// Whether the child at the given index should be rendered in full.
+    pub(super) fn child_in_window(&self, ctx: &Context<Self>, idx: usize) -> bool {
+        !Self::is_virtualized(ctx) || self.child_window.visible.contains(&idx)
+    }
+
+    /// Lightweight stand-in for a child outside of the window, sized to match the child.
+    pub(super) fn child_placeholder(&self, node: &Node) -> Html {
+        let height = self
+            .child_window
+            .heights
+            .get(&node.id())
+            .copied()
+            .unwrap_or_else(|| self.estimated_rows(node) as f64 * ESTIMATED_ROW_HEIGHT);
+        html! {
+            <div class={PLACEHOLDER_CLASS} id={node_element_id(node.id())}
+                style={format!("height: {height}px")} />
+        }
+    }
+
+    /// Estimate how many rows a node takes up based on which groups are collapsed.
+    fn estimated_rows(&self, node: &Node) -> usize {
+        match node.group() {
+            Some(group) if !self.metas.meta(group.id).collapsed => {
+                // Header and footer, plus the children.
+                2 + group
+                    .children
+                    .iter()
+                    .map(|child| self.estimated_rows(child))
+                    .sum::<usize>()
+            }
+            _ => 1,
+        }
+    }
+
+    /// Start or stop listening for scrolling depending on whether this group is virtualized, and
+    /// update the window after a render.
+    pub(super) fn track_child_window(&mut self, ctx: &Context<Self>) {
+        if !Self::is_virtualized(ctx) || self.meta.collapsed {
+            self.child_window.listeners.clear();
+            return;
+        }
+        if self.child_window.listeners.is_empty() {
+            let window = gloo::utils::window();
+            let update = ctx.link().callback(|()| Msg::UpdateChildWindow);
+            // Scroll events don't bubble, so capture them to see scrolling of any container.
+            let options = EventListenerOptions {
+                phase: EventListenerPhase::Capture,
+                passive: true,
+            };
+            self.child_window.listeners = vec![
+                EventListener::new_with_options(&window, "scroll", options, {
+                    let update = update.clone();
+                    move |_| update.emit(())
+                }),
+                EventListener::new(&window, "resize", move |_| update.emit(())),
+            ];
+        }
+        ctx.link().send_message(Msg::UpdateChildWindow);
+    }
+
+    /// Measure the children and update which ones are rendered. Returns true if the rendered
+    /// children changed.
+    pub(super) fn update_child_window(&mut self, group: &Group) -> bool {
+        let Some(container) = self.children.cast::<HtmlElement>() else {
+            return false;
+        };
+        let viewport = gloo::utils::window()
+            .inner_height()
+            .ok()
+            .and_then(|height| height.as_f64())
+            .unwrap_or_default();
+
+        // Elements correspond to the shown children in order, skipping insert markers.
+        let shown = group
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| !self.hidden_as_balanced(node));
+        let children = container.children();
+        let elements = (0..children.length())
+            .filter_map(|i| children.item(i))
+            .filter(|element| !element.class_list().contains(DRAG_INSERT_POINT));
+
+        let mut first = None;
+        let mut last = None;
+        // Rebuilt from scratch so measurements of removed children are dropped.
+        let mut heights = HashMap::new();
+        for ((idx, node), element) in shown.zip(elements) {
+            let rect = element.get_bounding_client_rect();
+            let id = node.id();
+            if !element.class_list().contains(PLACEHOLDER_CLASS) {
+                heights.insert(id, rect.height());
+            } else if let Some(&height) = self.child_window.heights.get(&id) {
+                heights.insert(id, height);
+            }
+            if rect.bottom() >= -BUFFER_PX && rect.top() <= viewport + BUFFER_PX {
+                first.get_or_insert(idx);
+                last = Some(idx);
+            }
+        }
+        self.child_window.heights = heights;
+
+        let visible = match (first, last) {
+            (Some(first), Some(last)) => first..last + 1,
+            _ => 0..0,
+        };
+        if visible != self.child_window.visible {
+            self.child_window.visible = visible;
+            true
+        } else {
+            false
+        }
+    }
+}
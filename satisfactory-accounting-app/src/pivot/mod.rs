@@ -0,0 +1,143 @@
+//! Window showing the balance of the world as a table of items by top-level group.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo::file::ObjectUrl;
+use satisfactory_accounting::analysis::{pivot_table, PivotRow};
+use satisfactory_accounting::csv::pivot_csv;
+use satisfactory_accounting::database::ItemIdOrPower;
+use yew::prelude::*;
+
+use crate::download::download_text;
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::Icon;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_world_root};
+
+pub type PivotWindowManager = WindowManager<PivotWindow>;
+pub type PivotWindowDispatcher = ShowWindowDispatcher<PivotWindow>;
+
+/// Gets access to the Pivot window dispatcher which controls showing the pivot table window.
+#[hook]
+pub fn use_pivot_window() -> PivotWindowDispatcher {
+    use_context::<PivotWindowDispatcher>()
+        .expect("use_pivot_window can only be used from within a child of PivotWindowManager")
+}
+
+/// Shows the net rate of every item in each top-level group of the world.
+#[function_component]
+pub fn PivotWindow() -> Html {
+    let window_dispatcher = use_pivot_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let db = use_db();
+    let root = use_world_root();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let power_format = &balance_settings.power_format_settings;
+    // Keeps the download url alive until the window is closed.
+    let download_url_retainer: Rc<RefCell<Option<ObjectUrl>>> = use_mut_ref(|| None);
+
+    let export = use_callback((root.clone(), db.clone()), move |_, (root, db)| {
+        let csv = pivot_csv(root, db);
+        if let Some(url) = download_text(&csv, "text/csv", "Pivot Table.csv") {
+            *download_url_retainer.borrow_mut() = Some(url);
+        }
+    });
+
+    let table = pivot_table(&root);
+    let name_of = |row: &PivotRow| match row.target {
+        ItemIdOrPower::Power => "Power".to_owned(),
+        ItemIdOrPower::Item(id) => match db.get(id) {
+            Some(item) => item.name.to_string(),
+            None => "Unknown Item".to_owned(),
+        },
+    };
+    let mut rows = table.rows;
+    let collator = crate::locale::get_collator();
+    rows.sort_by(|a, b| {
+        let a_item = a.target != ItemIdOrPower::Power;
+        let b_item = b.target != ItemIdOrPower::Power;
+        a_item
+            .cmp(&b_item)
+            .then_with(|| collator.compare(&name_of(a), &name_of(b)))
+    });
+
+    let cell = |target: ItemIdOrPower, rate: f32, class: &'static str| {
+        let sign = if rate < 0.0 {
+            Some("negative")
+        } else if rate > 0.0 {
+            Some("positive")
+        } else {
+            None
+        };
+        let text = match target {
+            _ if rate == 0.0 => String::new(),
+            ItemIdOrPower::Power => rate.format(power_format).to_string(),
+            ItemIdOrPower::Item(_) => balance_settings.format_rate(rate).to_string(),
+        };
+        html! { <td class={classes!("value", class, sign)}>{text}</td> }
+    };
+    let row = |row: PivotRow| {
+        let name = name_of(&row);
+        let icon = match row.target {
+            ItemIdOrPower::Power => material_icon("bolt"),
+            ItemIdOrPower::Item(id) => match db.get(id) {
+                Some(item) => html! { <Icon icon={item.image.clone()} name={item.name.clone()} /> },
+                None => html! { <Icon /> },
+            },
+        };
+        let label = match row.target {
+            ItemIdOrPower::Power => "MW".to_owned(),
+            ItemIdOrPower::Item(_) => balance_settings.rate_suffix().to_owned(),
+        };
+        html! {
+            <tr>
+                <th class="name" title={name.clone()}>
+                    {icon}
+                    <span>{name}</span>
+                    <span class="unit">{label}</span>
+                </th>
+                {for row.rates.iter().map(|&rate| cell(row.target, rate, ""))}
+                {cell(row.target, row.total, "total")}
+            </tr>
+        }
+    };
+
+    html! {
+        <OverlayWindow title="Pivot Table" class="PivotWindow" on_close={close}>
+            <div class="pivot-controls">
+                <p>{"Net rate of each item in each top-level group. Totals match the balance of \
+                the whole world."}</p>
+                <Button title="Export as CSV" onclick={export}>
+                    {material_icon("table_view")}
+                </Button>
+            </div>
+            if rows.is_empty() {
+                <p class="empty-pivot">{"Nothing in this world produces or consumes anything."}</p>
+            } else {
+                <div class="pivot-scroll">
+                    <table class="pivot-table">
+                        <tr>
+                            <th class="name">{"Item"}</th>
+                            {for table.columns.iter().map(|column| html! {
+                                <th class="value" title={column.name.clone()}>
+                                    {column.name.clone()}
+                                </th>
+                            })}
+                            <th class="value total">{"Total"}</th>
+                        </tr>
+                        {for rows.into_iter().map(row)}
+                    </table>
+                </div>
+            }
+        </OverlayWindow>
+    }
+}
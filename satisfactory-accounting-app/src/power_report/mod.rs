@@ -0,0 +1,250 @@
+//! Window listing a group's power consumption and production by building type.
+
+use std::rc::Rc;
+
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::database::BuildingId;
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::{format_share, Icon};
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_world_root};
+
+/// Actions for the power report window.
+enum Action {
+    /// Hide the window.
+    Hide,
+    /// Show the window for the group with the given id.
+    Show(Uuid),
+}
+
+/// State of the power report window.
+#[derive(Default, PartialEq, Copy, Clone)]
+struct PowerReportState {
+    /// Whether the window is shown.
+    show_window: bool,
+    /// Group whose power is listed.
+    group: Option<Uuid>,
+}
+
+impl Reducible for PowerReportState {
+    type Action = Action;
+
+    fn reduce(mut self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let state = Rc::make_mut(&mut self);
+        match action {
+            Action::Hide => state.show_window = false,
+            Action::Show(group) => {
+                state.show_window = true;
+                state.group = Some(group);
+            }
+        }
+        self
+    }
+}
+
+/// Dispatcher for showing the power report window.
+#[derive(PartialEq, Clone)]
+pub struct PowerReportWindowDispatcher {
+    reducer: UseReducerDispatcher<PowerReportState>,
+}
+
+impl PowerReportWindowDispatcher {
+    /// Hides the window.
+    pub fn hide_window(&self) {
+        self.reducer.dispatch(Action::Hide);
+    }
+
+    /// Shows the window listing the power of the group with the given id.
+    pub fn show_group(&self, group: Uuid) {
+        self.reducer.dispatch(Action::Show(group));
+    }
+}
+
+/// Gets access to the dispatcher which controls showing the power report window.
+#[hook]
+pub fn use_power_report_window() -> PowerReportWindowDispatcher {
+    use_context::<PowerReportWindowDispatcher>().expect(
+        "use_power_report_window can only be used from within a child of \
+        PowerReportWindowManager",
+    )
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ManagerProps {
+    /// Children which can show the window.
+    pub children: Html,
+}
+
+/// Provides the context to show the power report window and renders the window when shown.
+#[function_component]
+pub fn PowerReportWindowManager(ManagerProps { children }: &ManagerProps) -> Html {
+    let state = use_reducer_eq(PowerReportState::default);
+    let dispatcher = PowerReportWindowDispatcher {
+        reducer: state.dispatcher(),
+    };
+
+    html! {
+        <ContextProvider<PowerReportWindowDispatcher> context={dispatcher}>
+            { children.clone() }
+            if let Some(group) = state.group.filter(|_| state.show_window) {
+                <PowerReportWindow {group} />
+            }
+        </ContextProvider<PowerReportWindowDispatcher>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ButtonProps {
+    /// Id of the group to list the power of.
+    pub id: Uuid,
+}
+
+/// Button which opens the power report of a group.
+#[function_component]
+pub fn PowerReportButton(&ButtonProps { id }: &ButtonProps) -> Html {
+    let window_dispatcher = use_power_report_window();
+    let onclick = use_callback(window_dispatcher, move |_, window_dispatcher| {
+        window_dispatcher.show_group(id);
+    });
+    html! {
+        <Button {onclick} title="Show Power by Building Type">
+            {material_icon("electric_bolt")}
+        </Button>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct WindowProps {
+    /// Id of the group to list.
+    group: Uuid,
+}
+
+/// Lists the power of a group by building type.
+#[function_component]
+fn PowerReportWindow(&WindowProps { group }: &WindowProps) -> Html {
+    let window_dispatcher = use_power_report_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let root = use_world_root();
+    let node = root.iter().find(|node| node.id() == group);
+    let title = match node.as_ref().and_then(|node| node.group()) {
+        Some(group) if !group.name.is_empty() => format!("Power by Building: {}", group.name),
+        Some(_) if node.as_ref().is_some_and(|node| node.id() == root.id()) => {
+            "Power by Building: World".to_owned()
+        }
+        _ => "Power by Building".to_owned(),
+    };
+
+    html! {
+        <OverlayWindow {title} class="PowerReportWindow" on_close={close}>
+            if let Some(node) = node {
+                <PowerReport {node} />
+            } else {
+                <p class="empty-report">{"This group no longer exists."}</p>
+            }
+        </OverlayWindow>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ReportProps {
+    /// Group to list.
+    node: Node,
+}
+
+/// Tables of the power consumed by each building type and produced by each generator type, largest
+/// first.
+#[function_component]
+fn PowerReport(ReportProps { node }: &ReportProps) -> Html {
+    let power = node.power_by_building();
+    let mut consumers: Vec<(BuildingId, f32)> = power
+        .iter()
+        .filter(|(_, &power)| power < 0.0)
+        .map(|(&id, &power)| (id, -power))
+        .collect();
+    consumers.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    let mut producers: Vec<(BuildingId, f32)> = power
+        .into_iter()
+        .filter(|&(_, power)| power > 0.0)
+        .collect();
+    producers.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    html! {
+        <>
+            <div class="report-section">
+                <h2>{"Consumption"}</h2>
+                <p>{"Power drawn by each type of building, including all copies. When the grid \
+                trips, switching off the buildings at the top frees up the most power."}</p>
+                <PowerTable rows={consumers} empty="Nothing in this group consumes power." />
+            </div>
+            <div class="report-section">
+                <h2>{"Production"}</h2>
+                <p>{"Power generated by each type of generator, including all copies."}</p>
+                <PowerTable rows={producers} empty="Nothing in this group produces power." />
+            </div>
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct TableProps {
+    /// Building types with their power, as positive numbers, largest first.
+    rows: Vec<(BuildingId, f32)>,
+    /// Message to show if there are no rows.
+    empty: AttrValue,
+}
+
+/// Table of power by building type, with each type's share of the total.
+#[function_component]
+fn PowerTable(TableProps { rows, empty }: &TableProps) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let power_format = &user_settings.number_display.balance.power_format_settings;
+
+    if rows.is_empty() {
+        return html! {
+            <p class="empty-report">{empty.clone()}</p>
+        };
+    }
+    let total: f32 = rows.iter().map(|(_, power)| power).sum();
+    let row = |&(id, power): &(BuildingId, f32)| {
+        let (name, icon): (AttrValue, Option<AttrValue>) = match db.get(id) {
+            Some(building) => (
+                building.name.clone().into(),
+                Some(building.image.clone().into()),
+            ),
+            None => ("Unknown Building".into(), None),
+        };
+        html! {
+            <tr>
+                <td class="name"><Icon {icon} name={name.clone()} />{name}</td>
+                <td class="value">{format!("{} MW", power.format(power_format))}</td>
+                <td class="value share">{format_share(power / total)}</td>
+            </tr>
+        }
+    };
+
+    html! {
+        <table class="power-by-building">
+            <tr>
+                <th>{"Building"}</th>
+                <th class="value">{"Power"}</th>
+                <th class="value">{"Share"}</th>
+            </tr>
+            {for rows.iter().map(row)}
+            <tr class="total">
+                <td class="name">{"Total"}</td>
+                <td class="value">{format!("{} MW", total.format(power_format))}</td>
+                <td />
+            </tr>
+        </table>
+    }
+}
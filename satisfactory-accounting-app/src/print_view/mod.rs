@@ -0,0 +1,360 @@
+//! Document view of the world or a group, laid out for the browser's print to PDF.
+
+use std::rc::Rc;
+
+use log::warn;
+use satisfactory_accounting::accounting::{Group, Node};
+use satisfactory_accounting::database::Database;
+use uuid::Uuid;
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::markdown::render_markdown;
+use crate::material::material_icon;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{group_database, use_db, use_world_root, NodeMetas};
+
+/// Actions for the print view.
+enum Action {
+    /// Hide the print view.
+    Hide,
+    /// Show the print view for the node with the given id.
+    Show(Uuid),
+}
+
+/// State of the print view.
+#[derive(Default, PartialEq, Copy, Clone)]
+struct PrintViewState {
+    /// Node being printed, if the print view is shown.
+    node: Option<Uuid>,
+}
+
+impl Reducible for PrintViewState {
+    type Action = Action;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        Rc::new(match action {
+            Action::Hide => Self { node: None },
+            Action::Show(id) => Self { node: Some(id) },
+        })
+    }
+}
+
+/// Dispatcher for showing the print view.
+#[derive(PartialEq, Clone)]
+pub struct PrintViewDispatcher {
+    reducer: UseReducerDispatcher<PrintViewState>,
+}
+
+impl PrintViewDispatcher {
+    /// Hides the print view.
+    pub fn hide(&self) {
+        self.reducer.dispatch(Action::Hide);
+    }
+
+    /// Shows the print view of the node with the given id.
+    pub fn show_node(&self, id: Uuid) {
+        self.reducer.dispatch(Action::Show(id));
+    }
+}
+
+/// Gets access to the dispatcher which controls showing the print view.
+#[hook]
+pub fn use_print_view() -> PrintViewDispatcher {
+    use_context::<PrintViewDispatcher>()
+        .expect("use_print_view can only be used from within a child of PrintViewManager")
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ManagerProps {
+    /// Children which can show the print view.
+    pub children: Html,
+}
+
+/// Provides the context to show the print view and renders it when shown.
+#[function_component]
+pub fn PrintViewManager(ManagerProps { children }: &ManagerProps) -> Html {
+    let state = use_reducer_eq(PrintViewState::default);
+    let dispatcher = PrintViewDispatcher {
+        reducer: state.dispatcher(),
+    };
+
+    html! {
+        <ContextProvider<PrintViewDispatcher> context={dispatcher}>
+            { children.clone() }
+            if let Some(id) = state.node {
+                <PrintView {id} />
+            }
+        </ContextProvider<PrintViewDispatcher>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ButtonProps {
+    /// Id of the node to print.
+    pub id: Uuid,
+}
+
+/// Button which opens the print view of a node.
+#[function_component]
+pub fn PrintViewButton(&ButtonProps { id }: &ButtonProps) -> Html {
+    let print_view = use_print_view();
+    let onclick = use_callback(print_view, move |_, print_view| {
+        print_view.show_node(id);
+    });
+    html! {
+        <Button {onclick} title="Print View">
+            {material_icon("print")}
+        </Button>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct Props {
+    /// Id of the node to print.
+    id: Uuid,
+}
+
+/// Covers the app with a document of the node, which is the only thing printed while it is open.
+#[function_component]
+fn PrintView(&Props { id }: &Props) -> Html {
+    let host = use_memo((), |()| {
+        gloo::utils::document()
+            .create_element("div")
+            .expect("Unable to create element")
+    });
+    let host = Element::clone(&*host);
+
+    use_effect_with(host.clone(), |host| {
+        let modal_host = gloo::utils::document()
+            .get_element_by_id("modal-host")
+            .expect("Missing Modal Host");
+
+        if let Err(e) = modal_host.append_child(host) {
+            warn!("Unable to attach print view host element: {e:?}")
+        }
+
+        let host = host.clone();
+        move || {
+            if let Err(e) = modal_host.remove_child(&host) {
+                warn!("Unable to detach print view host element: {e:?}")
+            }
+        }
+    });
+
+    let print_view = use_print_view();
+    let close = use_callback(print_view, |_, print_view| print_view.hide());
+    let print = Callback::from(|_| {
+        if let Err(e) = gloo::utils::window().print() {
+            warn!("Unable to open the print dialog: {e:?}");
+        }
+    });
+
+    let root = use_world_root();
+    let node = root.iter().find(|node| node.id() == id);
+    let is_root = id == root.id();
+
+    let contents = html! {
+        <div class="PrintView">
+            <div class="print-toolbar">
+                <Button title="Print" onclick={print}>
+                    {material_icon("print")}
+                    <span>{"Print"}</span>
+                </Button>
+                <Button title="Close" class="red" onclick={close}>
+                    {material_icon("close")}
+                </Button>
+            </div>
+            <article class="print-document">
+                if let Some(node) = node.filter(|node| node.group().is_some()) {
+                    <PrintGroup {node} depth={1} {is_root} />
+                } else {
+                    <p>{"This group no longer exists."}</p>
+                }
+            </article>
+        </div>
+    };
+    create_portal(contents, host)
+}
+
+#[derive(Properties, PartialEq)]
+struct GroupProps {
+    /// Group to print.
+    node: Node,
+    /// Nesting depth of the group, used for its heading level.
+    depth: usize,
+    /// Whether this is the root of the world.
+    #[prop_or_default]
+    is_root: bool,
+}
+
+/// Prints a group's heading, notes, buildings, and balance, followed by its subgroups.
+#[function_component]
+fn PrintGroup(
+    &GroupProps {
+        ref node,
+        depth,
+        is_root,
+    }: &GroupProps,
+) -> Html {
+    let db = use_db();
+    let metas = use_context::<NodeMetas>()
+        .expect("PrintGroup can only be used from within a child of WorldManager");
+    let Some(group) = node.group() else {
+        return html! {};
+    };
+
+    let name = match &*group.name {
+        _ if is_root => "World",
+        "" => "Unnamed Group",
+        name => name,
+    };
+    let heading = html! {
+        <@{format!("h{}", depth.min(6))}>
+            {name}
+            if group.copies > 1 {
+                <span class="print-copies">{format!(" \u{00D7} {}", group.copies)}</span>
+            }
+        </@>
+    };
+    let notes = metas.meta(group.id).notes;
+
+    let content = html! {
+        <section class="print-group">
+            {heading}
+            if !notes.is_empty() {
+                <div class="print-notes">{render_markdown(&notes)}</div>
+            }
+            <BuildingTable node={node.clone()} />
+            <BalanceTable node={node.clone()} />
+            {for node.children().filter(|child| child.group().is_some()).map(|child| html! {
+                <PrintGroup node={child} depth={depth + 1} />
+            })}
+        </section>
+    };
+    with_group_database(&db, group, content)
+}
+
+/// Wrap the contents of a group so that they use the group's pinned database, if it has one.
+fn with_group_database(db: &Database, group: &Group, content: Html) -> Html {
+    match group.database {
+        Some(_) => html! {
+            <ContextProvider<Database> context={group_database(group, db)}>
+                {content}
+            </ContextProvider<Database>>
+        },
+        None => content,
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct TableProps {
+    /// Group whose contents are printed.
+    node: Node,
+}
+
+/// Table of the buildings directly inside a group.
+#[function_component]
+fn BuildingTable(TableProps { node }: &TableProps) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let power_format = &user_settings.number_display.balance.power_format_settings;
+
+    let rows: Vec<Html> = node
+        .children()
+        .filter_map(|child| {
+            let building = child.building()?;
+            let name = match building.building.and_then(|id| db.get(id)) {
+                Some(info) => info.name.to_string(),
+                None if building.building.is_none() => return None,
+                None => "Unknown Building".to_owned(),
+            };
+            let selection = building.settings.selection_name(&db).unwrap_or_default();
+            let clock = format!("{:.1}%", building.settings.clock_speed() * 100.0);
+            let power = child.balance().power;
+            Some(html! {
+                <tr>
+                    <td>{name}</td>
+                    <td>{selection}</td>
+                    <td class="number">{clock}</td>
+                    <td class="number">{building.copies.to_string()}</td>
+                    <td class="number">{power.format(power_format).to_string()}</td>
+                </tr>
+            })
+        })
+        .collect();
+    if rows.is_empty() {
+        return html! {};
+    }
+    html! {
+        <table class="print-buildings">
+            <thead>
+                <tr>
+                    <th>{"Building"}</th>
+                    <th>{"Recipe / Item"}</th>
+                    <th class="number">{"Clock"}</th>
+                    <th class="number">{"Copies"}</th>
+                    <th class="number">{"Power (MW)"}</th>
+                </tr>
+            </thead>
+            <tbody>{rows}</tbody>
+        </table>
+    }
+}
+
+/// Table of a group's item and power balance.
+#[function_component]
+fn BalanceTable(TableProps { node }: &TableProps) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let balance = node.balance();
+    if balance.power == 0.0 && balance.balances.is_empty() {
+        return html! {};
+    }
+
+    let mut items: Vec<(String, f32)> = balance
+        .balances
+        .iter()
+        .map(|(&id, &rate)| {
+            let name = match db.get(id) {
+                Some(item) => item.name.to_string(),
+                None => "Unknown Item".to_owned(),
+            };
+            (name, rate)
+        })
+        .collect();
+    let collator = crate::locale::get_collator();
+    items.sort_by(|(lhs, _), (rhs, _)| collator.compare(lhs, rhs));
+
+    let row = |name: String, rate: f32, format| {
+        let class = classes!("number", if rate < 0.0 { "negative" } else { "positive" });
+        html! {
+            <tr>
+                <td>{name}</td>
+                <td {class}>{rate.format(format).to_string()}</td>
+            </tr>
+        }
+    };
+    html! {
+        <table class="print-balance">
+            <thead>
+                <tr>
+                    <th>{"Balance"}</th>
+                    <th class="number">{"Rate"}</th>
+                </tr>
+            </thead>
+            <tbody>
+                if balance.power != 0.0 {
+                    {row("Power (MW)".to_owned(), balance.power,
+                        &balance_settings.power_format_settings)}
+                }
+                {for items.into_iter().map(|(name, rate)| {
+                    row(name, balance_settings.rate_in_unit(rate), &balance_settings.item_format_settings)
+                })}
+            </tbody>
+        </table>
+    }
+}
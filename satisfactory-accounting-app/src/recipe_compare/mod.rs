@@ -0,0 +1,306 @@
+//! Window comparing every recipe which produces an item.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use satisfactory_accounting::database::{BuildingKind, Database, ItemId, Recipe};
+use satisfactory_accounting::planner::{raw_resource_cost, StandardRecipes};
+use yew::prelude::*;
+
+use crate::inputs::choose_from_list::{Choice, ChooseFromList};
+use crate::inputs::clickedit::ClickEdit;
+use crate::node_display::Icon;
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::number_format::{NumberFormatSettings, UserConfiguredFormat};
+use crate::user_settings::use_user_settings;
+use crate::world::use_db;
+
+/// Rate per minute machine counts are shown for until the user picks another.
+const DEFAULT_REFERENCE_RATE: f32 = 60.0;
+
+/// Actions for the recipe comparison window.
+enum Action {
+    /// Hide the window.
+    Hide,
+    /// Toggle the window, keeping the last selected item.
+    Toggle,
+    /// Show the window comparing the recipes for the given item.
+    Show(ItemId),
+}
+
+/// State of the recipe comparison window.
+#[derive(Default, PartialEq, Copy, Clone)]
+struct RecipeCompareState {
+    /// Whether the window is shown.
+    show_window: bool,
+    /// Item whose recipes are compared.
+    item: Option<ItemId>,
+}
+
+impl Reducible for RecipeCompareState {
+    type Action = Action;
+
+    fn reduce(mut self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let state = Rc::make_mut(&mut self);
+        match action {
+            Action::Hide => state.show_window = false,
+            Action::Toggle => state.show_window = !state.show_window,
+            Action::Show(item) => {
+                state.show_window = true;
+                state.item = Some(item);
+            }
+        }
+        self
+    }
+}
+
+/// Dispatcher for showing the recipe comparison window.
+#[derive(PartialEq, Clone)]
+pub struct RecipeCompareWindowDispatcher {
+    reducer: UseReducerDispatcher<RecipeCompareState>,
+}
+
+impl RecipeCompareWindowDispatcher {
+    /// Toggles the window.
+    pub fn toggle_window(&self) {
+        self.reducer.dispatch(Action::Toggle);
+    }
+
+    /// Hides the window.
+    pub fn hide_window(&self) {
+        self.reducer.dispatch(Action::Hide);
+    }
+
+    /// Shows the window comparing the recipes for the given item.
+    pub fn show_item(&self, item: ItemId) {
+        self.reducer.dispatch(Action::Show(item));
+    }
+}
+
+/// Gets access to the dispatcher which controls showing the recipe comparison window.
+#[hook]
+pub fn use_recipe_compare_window() -> RecipeCompareWindowDispatcher {
+    use_context::<RecipeCompareWindowDispatcher>().expect(
+        "use_recipe_compare_window can only be used from within a child of \
+        RecipeCompareWindowManager",
+    )
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ManagerProps {
+    /// Children which can show the window.
+    pub children: Html,
+}
+
+/// Provides the context to show the recipe comparison window and renders the window when shown.
+#[function_component]
+pub fn RecipeCompareWindowManager(ManagerProps { children }: &ManagerProps) -> Html {
+    let state = use_reducer_eq(RecipeCompareState::default);
+    let dispatcher = RecipeCompareWindowDispatcher {
+        reducer: state.dispatcher(),
+    };
+
+    html! {
+        <ContextProvider<RecipeCompareWindowDispatcher> context={dispatcher}>
+            { children.clone() }
+            if state.show_window {
+                <RecipeCompareWindow item={state.item} />
+            }
+        </ContextProvider<RecipeCompareWindowDispatcher>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct WindowProps {
+    /// Item to compare the recipes of, if one has been chosen.
+    item: Option<ItemId>,
+}
+
+/// Compares every recipe which produces an item.
+#[function_component]
+fn RecipeCompareWindow(&WindowProps { item }: &WindowProps) -> Html {
+    let window_dispatcher = use_recipe_compare_window();
+    let close = use_callback(window_dispatcher.clone(), |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+    let on_selected = use_callback(window_dispatcher, |item, window_dispatcher| {
+        window_dispatcher.show_item(item);
+    });
+    let user_settings = use_user_settings();
+    let rate_unit = user_settings.number_display.balance.rate_unit;
+    let reference_rate = use_state_eq(|| DEFAULT_REFERENCE_RATE);
+    let set_reference_rate = {
+        let reference_rate = reference_rate.clone();
+        Callback::from(move |edit_text: AttrValue| {
+            if let Ok(rate) = edit_text.parse::<f32>() {
+                reference_rate.set(rate_unit.to_per_minute(rate.abs()));
+            }
+        })
+    };
+
+    let db = use_db();
+    let collator = crate::locale::get_collator();
+    let mut items: Vec<_> = db
+        .items()
+        .filter(|item| !item.produced_by.is_empty())
+        .collect();
+    items.sort_by(|lhs, rhs| collator.compare(&lhs.name, &rhs.name));
+    let choices: Vec<_> = items
+        .into_iter()
+        .map(|item| Choice {
+            id: item.id,
+            name: item.name.clone().into(),
+            image: html! { <Icon icon={item.image.clone()} name={item.name.clone()} /> },
+        })
+        .collect();
+
+    let title = match item.and_then(|id| db.get(id)) {
+        Some(item) => format!("Recipes for {}", item.name),
+        None => "Compare Recipes".to_owned(),
+    };
+
+    html! {
+        <OverlayWindow {title} class="RecipeCompareWindow" on_close={close}>
+            <div class="compare-controls">
+                <span>{"Item:"}</span>
+                <ChooseFromList<ItemId> title="Choose Item" {choices} {on_selected}
+                    on_cancelled={Callback::noop()} />
+                <span>{"Reference rate:"}</span>
+                <ClickEdit value={rate_unit.convert(*reference_rate).to_string()}
+                    title="Rate to count machines for" on_commit={set_reference_rate}
+                    suffix={html! { <span>{rate_unit.suffix()}</span> }} />
+            </div>
+            if let Some(item) = item {
+                <RecipeCompareTable {item} reference_rate={*reference_rate} />
+            }
+        </OverlayWindow>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct TableProps {
+    /// Item whose recipes are compared.
+    item: ItemId,
+    /// Items per minute to count the machines needed for.
+    reference_rate: f32,
+}
+
+/// Table with a row for each recipe which produces the item.
+#[function_component]
+fn RecipeCompareTable(
+    &TableProps {
+        item,
+        reference_rate,
+    }: &TableProps,
+) -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let format = &user_settings.number_display.balance.item_format_settings;
+    let recipes = db.recipes_producing(item);
+    if recipes.is_empty() {
+        return html! {
+            <p class="no-recipes">{"No recipe produces this item."}</p>
+        };
+    }
+    html! {
+        <table class="RecipeCompareTable">
+            <thead>
+                <tr>
+                    <th>{"Recipe"}</th>
+                    <th>{"Inputs per Item"}</th>
+                    <th>{"Byproducts per Item"}</th>
+                    <th title="Energy used to make one item">{"Energy per Item"}</th>
+                    <th>{"Machines"}</th>
+                    <th title="Raw resources per item, making the inputs with standard recipes">
+                        {"Raw Resources per Item"}
+                    </th>
+                </tr>
+            </thead>
+            <tbody>
+                {for recipes.into_iter().map(|rate| {
+                    recipe_row(&db, format, item, rate.recipe, reference_rate)
+                })}
+            </tbody>
+        </table>
+    }
+}
+
+/// Build the row comparing a single recipe.
+fn recipe_row(
+    db: &Database,
+    format: &NumberFormatSettings,
+    item: ItemId,
+    recipe: &Recipe,
+    reference_rate: f32,
+) -> Html {
+    let produced = recipe.product_rate(item);
+    let per_item = |rate: f32| rate / produced;
+
+    let inputs: BTreeMap<ItemId, f32> = recipe
+        .ingredients
+        .iter()
+        .map(|ingredient| {
+            (
+                ingredient.item,
+                per_item(recipe.ingredient_rate(ingredient.item)),
+            )
+        })
+        .collect();
+    let byproducts: BTreeMap<ItemId, f32> = recipe
+        .products
+        .iter()
+        .filter(|product| product.item != item)
+        .map(|product| (product.item, per_item(recipe.product_rate(product.item))))
+        .collect();
+    let raw = raw_resource_cost(&inputs, &StandardRecipes, db);
+
+    let machine = recipe.produced_in.iter().find_map(|&id| db.get(id));
+    let power = match machine.map(|machine| &machine.kind) {
+        Some(BuildingKind::Manufacturer(m)) => m.power_consumption.power,
+        _ => 0.0,
+    };
+    // MW for a minute, spread over the items made in that minute.
+    let energy = power * 60.0 / produced;
+    let machines = reference_rate / produced;
+
+    let amounts = |amounts: &BTreeMap<ItemId, f32>| {
+        if amounts.is_empty() {
+            return html! { <span class="none">{"None"}</span> };
+        }
+        html! {
+            <ul>
+                {for amounts.iter().map(|(&id, &amount)| {
+                    let (icon, name): (Option<AttrValue>, AttrValue) = match db.get(id) {
+                        Some(item) => (Some(item.image.clone().into()), item.name.clone().into()),
+                        None => (None, "Unknown Item".into()),
+                    };
+                    html! {
+                        <li title={name.clone()}>
+                            <Icon {icon} {name} />
+                            <span>{amount.format(format).to_string()}</span>
+                        </li>
+                    }
+                })}
+            </ul>
+        }
+    };
+
+    html! {
+        <tr class={classes!(recipe.is_alternate.then_some("alternate"))}>
+            <td class="recipe-name">
+                <Icon icon={recipe.image.clone()} name={recipe.name.clone()} />
+                <span>{&recipe.name}</span>
+            </td>
+            <td>{amounts(&inputs)}</td>
+            <td>{amounts(&byproducts)}</td>
+            <td>{energy.format(format).to_string()}{" MJ"}</td>
+            <td>
+                {machines.format(format).to_string()}
+                if let Some(machine) = machine {
+                    {" \u{00D7} "}{&machine.name}
+                }
+            </td>
+            <td>{amounts(&raw)}</td>
+        </tr>
+    }
+}
@@ -0,0 +1,191 @@
+//! Window showing reports which analyze the whole world.
+
+use satisfactory_accounting::analysis::{
+    find_unused_groups, power_circuits, PowerCircuit, UnusedGroup,
+};
+use yew::{
+    classes, function_component, hook, html, use_callback, use_context, AttrValue, Html, Properties,
+};
+
+use crate::node_display::Icon;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_item_metas, use_world_root, NodeMetas};
+
+pub type ReportsWindowManager = WindowManager<ReportsWindow>;
+pub type ReportsWindowDispatcher = ShowWindowDispatcher<ReportsWindow>;
+
+/// Gets access to the Reports window dispatcher which controls showing the reports window.
+#[hook]
+pub fn use_reports_window() -> ReportsWindowDispatcher {
+    use_context::<ReportsWindowDispatcher>()
+        .expect("use_reports_window can only be used from within a child of ReportsWindowManager")
+}
+
+/// Shows reports about the current world.
+#[function_component]
+pub fn ReportsWindow() -> Html {
+    let window_dispatcher = use_reports_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    html! {
+        <OverlayWindow title="World Reports" class="ReportsWindow" on_close={close}>
+            <UnusedGroupsReport />
+            <PowerCircuitsReport />
+        </OverlayWindow>
+    }
+}
+
+/// Report listing groups whose outputs aren't used anywhere.
+#[function_component]
+fn UnusedGroupsReport() -> Html {
+    let root = use_world_root();
+    let item_metas = use_item_metas();
+    let unused = find_unused_groups(&root, |item| item_metas.meta(item).exported);
+
+    html! {
+        <div class="report-section">
+            <h2>{"Unused Groups"}</h2>
+            <p>{"These groups produce items which aren't consumed by anything outside of the \
+            group and which aren't marked as exported in Item Notes. They may be forgotten test \
+            factories that are inflating your totals."}</p>
+            if unused.is_empty() {
+                <p class="empty-report">{"No unused groups found."}</p>
+            } else {
+                <ul class="unused-groups">
+                    {for unused.into_iter().map(|group| {
+                        let key = group.id.to_string();
+                        html! { <UnusedGroupRow {key} {group} /> }
+                    })}
+                </ul>
+            }
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct UnusedGroupRowProps {
+    /// The group to display.
+    group: UnusedGroup,
+}
+
+/// Displays a single unused group.
+#[function_component]
+fn UnusedGroupRow(UnusedGroupRowProps { group }: &UnusedGroupRowProps) -> Html {
+    let db = use_db();
+    let path = group
+        .names
+        .iter()
+        .map(|name| {
+            if name.is_empty() {
+                "Unnamed Group"
+            } else {
+                name.as_str()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" \u{203A} ");
+    let items = group.unconsumed.iter().map(|&id| {
+        let (title, icon): (AttrValue, Option<AttrValue>) = match db.get(id) {
+            Some(item) => (item.name.clone().into(), Some(item.image.clone().into())),
+            None => ("Unknown Item".into(), None),
+        };
+        html! {
+            <span class="unconsumed-item" title={title.clone()}><Icon {icon} name={title} /></span>
+        }
+    });
+    html! {
+        <li class="UnusedGroupRow">
+            <span class="group-path">{path}</span>
+            <span class="unconsumed-items">{for items}</span>
+        </li>
+    }
+}
+
+/// Report splitting the world's power between named circuits.
+#[function_component]
+fn PowerCircuitsReport() -> Html {
+    let root = use_world_root();
+    let db = use_db();
+    let metas = use_context::<NodeMetas>()
+        .expect("PowerCircuitsReport can only be used from within a child of WorldManager");
+    let circuits: Vec<_> = power_circuits(&root, &db, |id| metas.circuit_assignment(id))
+        .into_iter()
+        .filter(|circuit| {
+            circuit.produced > 0.0 || circuit.consumed > 0.0 || circuit.power_storages > 0
+        })
+        .collect();
+    let named = circuits.iter().any(|circuit| circuit.name.is_some());
+
+    html! {
+        <div class="report-section">
+            <h2>{"Power Circuits"}</h2>
+            <p>{"Power produced and consumed on each circuit. Assign groups and buildings to a \
+            circuit with their power circuit button. Peak draw assumes every building with \
+            variable power draw peaks at once; if it exceeds production, the circuit's fuses may \
+            trip unless power storages cover the difference."}</p>
+            if !named {
+                <p class="empty-report">{"No nodes have been assigned to a power circuit."}</p>
+            } else {
+                <table class="power-circuits">
+                    <tr>
+                        <th>{"Circuit"}</th>
+                        <th>{"Produced"}</th>
+                        <th>{"Consumed"}</th>
+                        <th>{"Peak"}</th>
+                        <th>{"Storage"}</th>
+                    </tr>
+                    {for circuits.into_iter().map(|circuit| {
+                        let key = circuit.name.clone().unwrap_or_default().to_string();
+                        html! { <PowerCircuitRow {key} {circuit} /> }
+                    })}
+                </table>
+            }
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct PowerCircuitRowProps {
+    /// The circuit to display.
+    circuit: PowerCircuit,
+}
+
+/// Displays the totals of a single power circuit.
+#[function_component]
+fn PowerCircuitRow(PowerCircuitRowProps { circuit }: &PowerCircuitRowProps) -> Html {
+    let user_settings = use_user_settings();
+    let power_format = &user_settings.number_display.balance.power_format_settings;
+    let mw = |power: f32| format!("{} MW", power.format(power_format));
+
+    let warning = circuit.may_trip().then(|| {
+        let title = match circuit.battery_minutes_at_peak() {
+            Some(minutes) if circuit.power_storages > 0 => format!(
+                "Peak draw exceeds production. Power storages cover the deficit for {minutes:.1} \
+                minutes."
+            ),
+            _ => "Peak draw exceeds production, so fuses may trip.".to_owned(),
+        };
+        html! { <span class="material-icons circuit-warning" {title}>{"warning"}</span> }
+    });
+    let name = match &circuit.name {
+        Some(name) => html! { {name.clone()} },
+        None => html! { <i>{"Unassigned"}</i> },
+    };
+
+    html! {
+        <tr class={classes!("PowerCircuitRow", circuit.may_trip().then_some("may-trip"))}>
+            <td class="name">{name}{warning}</td>
+            <td class="value">{mw(circuit.produced)}</td>
+            <td class="value">{mw(circuit.consumed)}</td>
+            <td class="value peak">{mw(circuit.peak_consumed)}</td>
+            <td class="value">
+                {format!("{} MWh", circuit.battery_capacity().format(power_format))}
+            </td>
+        </tr>
+    }
+}
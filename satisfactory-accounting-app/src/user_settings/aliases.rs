@@ -0,0 +1,94 @@
+//! Display names chosen by the user for items.
+use std::rc::Rc;
+
+use satisfactory_accounting::database::{Database, ItemId};
+use yew::{
+    function_component, hook, html, use_context, use_memo, AttrValue, Callback, Children,
+    ContextProvider, Html, Properties,
+};
+
+use crate::inputs::clickedit::ClickEdit;
+use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+use crate::world::use_db;
+
+/// Database as loaded, before the user's item aliases are applied.
+#[derive(Debug, Clone, PartialEq)]
+struct UnaliasedDb(Database);
+
+/// Gets the database with the original item names, ignoring the user's aliases.
+#[hook]
+fn use_unaliased_db() -> Database {
+    use_context::<UnaliasedDb>()
+        .expect("use_unaliased_db can only be used from within a child of ItemAliases")
+        .0
+}
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    pub children: Children,
+}
+
+/// Replaces the database seen by its children with one where items are shown by the names the
+/// user chose for them. Worlds only store item ids, so the aliases never end up in saved data.
+#[function_component]
+pub fn ItemAliases(Props { children }: &Props) -> Html {
+    let db = use_db();
+    let aliases = use_user_settings().item_aliases.clone();
+    let aliased = use_memo((db.clone(), aliases), |(db, aliases)| {
+        db.with_item_names(aliases.iter().map(|(&id, name)| (id, name.as_str())))
+    });
+    html! {
+        <ContextProvider<UnaliasedDb> context={UnaliasedDb(db)}>
+        <ContextProvider<Database> context={(*aliased).clone()}>
+            {children.clone()}
+        </ContextProvider<Database>>
+        </ContextProvider<UnaliasedDb>>
+    }
+}
+
+/// Lets the user choose the names items are shown with.
+#[function_component]
+pub fn ItemAliasesSection() -> Html {
+    let db = use_unaliased_db();
+    let user_settings = use_user_settings();
+    let settings_dispatcher = use_user_settings_dispatcher();
+
+    let mut items: Vec<_> = db
+        .items()
+        .map(|item| (item.id, item.name.clone()))
+        .collect();
+    let collator = crate::locale::get_collator();
+    items.sort_by(|(_, lhs), (_, rhs)| collator.compare(lhs, rhs));
+
+    let item_alias = |(id, name): (ItemId, Rc<str>)| {
+        let alias = user_settings
+            .item_aliases
+            .get(&id)
+            .cloned()
+            .unwrap_or_default();
+        let on_commit = {
+            let settings_dispatcher = settings_dispatcher.clone();
+            Callback::from(move |alias: AttrValue| {
+                settings_dispatcher.set_item_alias(id, alias.trim().to_owned())
+            })
+        };
+        html! {
+            <li>
+                <span class="item-name">{name.to_string()}</span>
+                <ClickEdit class="item-alias" value={AttrValue::from(alias)}
+                    title={format!("Display name for {name}")} {on_commit} />
+            </li>
+        }
+    };
+
+    html! {
+        <div class="settings-section">
+            <h2>{"Item Names"}</h2>
+            <p>{"Choose shorter or translated names to show items with. Click next to an item to \
+            give it a new name, or clear the name to go back to the original."}</p>
+            <ul class="item-aliases">
+                {for items.into_iter().map(item_alias)}
+            </ul>
+        </div>
+    }
+}
@@ -0,0 +1,63 @@
+//! Files containing just the user's settings, used to carry them to another browser or machine.
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::user_settings::UserSettings;
+
+/// Version of the settings file format written by this version of the app.
+const SETTINGS_FILE_VERSION: u32 = 1;
+
+/// Format used for downloadable settings files.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettingsFile {
+    /// Version of the settings file format. This is required, which also keeps worlds and backups
+    /// from being mistaken for settings files.
+    settings_version: u32,
+    /// The user's settings.
+    user_settings: UserSettings,
+}
+
+/// Errors which can occur when reading a settings file.
+#[derive(Error, Debug)]
+pub enum ReadSettingsError {
+    #[error("Unable to parse settings: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Settings file version {0} is newer than this app supports")]
+    UnsupportedVersion(u32),
+    #[error("Balanced tolerance {0} is not a non-negative number")]
+    InvalidTolerance(f32),
+}
+
+impl SettingsFile {
+    /// Create a settings file containing the given settings.
+    pub fn new(user_settings: UserSettings) -> Self {
+        Self {
+            settings_version: SETTINGS_FILE_VERSION,
+            user_settings,
+        }
+    }
+
+    /// Parse and validate a settings file from its contents.
+    pub fn read(data: &[u8]) -> Result<Self, ReadSettingsError> {
+        let file: Self = serde_json::from_slice(data)?;
+        if file.settings_version > SETTINGS_FILE_VERSION {
+            return Err(ReadSettingsError::UnsupportedVersion(file.settings_version));
+        }
+        let tolerance = file.user_settings.balanced_tolerance;
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            return Err(ReadSettingsError::InvalidTolerance(tolerance));
+        }
+        Ok(file)
+    }
+
+    /// Get the imported settings to apply on top of the `current` settings. Which notices have
+    /// been acknowledged is kept from the current settings, since those belong to this browser
+    /// rather than to the user's preferences.
+    pub fn into_settings(self, current: &UserSettings) -> UserSettings {
+        UserSettings {
+            acked_local_storage_notice_version: current.acked_local_storage_notice_version,
+            acked_notification: current.acked_notification,
+            ..self.user_settings
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Alternate icons chosen by the user, for items the built-in icons don't cover.
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use yew::{function_component, html, use_callback, AttrValue, Html};
+
+use crate::inputs::button::{Button, UploadButton, UploadedFile};
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::material_icon;
+use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+use crate::world::use_db;
+
+/// Icons to use in place of the built-in ones for a database, e.g. for modded items or game
+/// versions the app doesn't have icons for yet.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IconPack {
+    /// URL of a directory of icons named like the built-in ones, e.g. `iron-plate_64.png`. Empty
+    /// to only use uploaded icons.
+    #[serde(default)]
+    pub base_url: String,
+    /// Icons uploaded by the user, as data URLs keyed by slug.
+    #[serde(default)]
+    pub uploaded: BTreeMap<String, String>,
+}
+
+impl IconPack {
+    /// Whether the pack has no icons, so the built-in icons are used.
+    pub fn is_empty(&self) -> bool {
+        self.base_url.is_empty() && self.uploaded.is_empty()
+    }
+
+    /// Get the URL of the icon for the given slug, if this pack has one. Uploaded icons take
+    /// priority over the base URL.
+    pub fn icon_url(&self, slug: &str) -> Option<String> {
+        if let Some(url) = self.uploaded.get(slug) {
+            Some(url.clone())
+        } else if !self.base_url.is_empty() {
+            let base = self.base_url.trim_end_matches('/');
+            Some(format!("{base}/{slug}_64.png"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Get the slug and data URL of an uploaded icon. The slug is taken from the file name, with the
+/// `_64` size suffix of the built-in icon names removed if present. Returns None if the file
+/// isn't a supported image type.
+fn uploaded_icon(file: &UploadedFile) -> Option<(String, String)> {
+    let (stem, extension) = file.name.rsplit_once('.')?;
+    let mime = match extension.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+    let slug = stem
+        .strip_suffix("_64")
+        .unwrap_or(stem)
+        .to_ascii_lowercase();
+    let data = STANDARD.encode(&file.data);
+    Some((slug, format!("data:{mime};base64,{data}")))
+}
+
+/// Lets the user choose alternate icons for the current database.
+#[function_component]
+pub fn IconPacksSection() -> Html {
+    let db = use_db();
+    let user_settings = use_user_settings();
+    let settings_dispatcher = use_user_settings_dispatcher();
+
+    let prefix = db.icon_prefix().to_owned();
+    let pack = user_settings
+        .icon_packs
+        .get(&prefix)
+        .cloned()
+        .unwrap_or_default();
+
+    let set_base_url = use_callback(
+        (prefix.clone(), pack.clone(), settings_dispatcher.clone()),
+        |base_url: AttrValue, (prefix, pack, settings_dispatcher)| {
+            let mut pack = pack.clone();
+            pack.base_url = base_url.trim().to_owned();
+            settings_dispatcher.set_icon_pack(prefix.clone(), pack);
+        },
+    );
+    let upload_icon = use_callback(
+        (prefix.clone(), settings_dispatcher.clone()),
+        |file: UploadedFile, (prefix, settings_dispatcher)| match uploaded_icon(&file) {
+            Some((slug, url)) => settings_dispatcher.add_uploaded_icon(prefix.clone(), slug, url),
+            None => warn!(
+                "Ignoring {}, which is not a supported image type",
+                file.name
+            ),
+        },
+    );
+    let clear_uploads = use_callback(
+        (prefix.clone(), pack.clone(), settings_dispatcher.clone()),
+        |_, (prefix, pack, settings_dispatcher)| {
+            let mut pack = pack.clone();
+            pack.uploaded.clear();
+            settings_dispatcher.set_icon_pack(prefix.clone(), pack);
+        },
+    );
+
+    let uploaded = match pack.uploaded.len() {
+        0 => "No uploaded icons".to_owned(),
+        1 => "1 uploaded icon".to_owned(),
+        n => format!("{n} uploaded icons"),
+    };
+
+    html! {
+        <div class="settings-section">
+            <h2>{"Icon Pack"}</h2>
+            <p>{"Use different icons for the current world's database, such as icons for modded \
+            items. Icons are looked up by the item's slug, e.g. iron-plate_64.png, first among \
+            uploaded icons, then at the base URL, and finally among the built-in icons. Uploaded \
+            icons are stored with your settings, so keep them small."}</p>
+            <ul>
+                <li>
+                    <label>
+                        <ClickEdit
+                            class="icon-base-url"
+                            value={AttrValue::from(pack.base_url.clone())}
+                            on_commit={set_base_url}
+                            prefix={html! {
+                                <span class="prefix">{"Base URL"}</span>
+                            }}
+                            title="Base URL" />
+                    </label>
+                </li>
+                <li class="icon-uploads">
+                    <span>{uploaded}</span>
+                    <UploadButton class="green" title="Upload Icons" accept="image/*" multiple=true
+                        onupload={upload_icon}>
+                        {material_icon("upload")}
+                    </UploadButton>
+                    <Button class="red" title="Remove Uploaded Icons"
+                        onclick={clear_uploads}>
+                        {material_icon("delete")}
+                    </Button>
+                </li>
+            </ul>
+        </div>
+    }
+}
@@ -0,0 +1,393 @@
+//! Configurable keyboard shortcuts and adjustment increments.
+use serde::{Deserialize, Serialize};
+use web_sys::KeyboardEvent;
+use yew::{
+    classes, function_component, html, use_callback, use_state_eq, AttrValue, Callback, Html,
+    Properties,
+};
+
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::{AdjustSteps, ClickEdit};
+use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+
+/// Modifier keys held down as part of a shortcut.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Modifiers {
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub meta: bool,
+}
+
+impl Modifiers {
+    /// Just the Alt key.
+    const ALT: Self = Self {
+        ctrl: false,
+        alt: true,
+        shift: false,
+        meta: false,
+    };
+
+    /// Get the modifiers held during the given key press.
+    pub fn of(event: &KeyboardEvent) -> Self {
+        Self {
+            ctrl: event.ctrl_key(),
+            alt: event.alt_key(),
+            shift: event.shift_key(),
+            meta: event.meta_key(),
+        }
+    }
+
+    /// Label for the modifiers, each followed by "+".
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        for (held, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.alt, "Alt+"),
+            (self.shift, "Shift+"),
+            (self.meta, "Meta+"),
+        ] {
+            if held {
+                label.push_str(name);
+            }
+        }
+        label
+    }
+}
+
+/// A key pressed along with some modifiers.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KeyCombo {
+    /// Modifiers which must be held.
+    #[serde(default)]
+    pub modifiers: Modifiers,
+    /// Physical key pressed, as given by `KeyboardEvent.code`. The physical key is used rather
+    /// than the character because modifiers change the character typed on some keyboard layouts.
+    pub code: String,
+}
+
+impl KeyCombo {
+    /// Whether the given key press is this combo.
+    pub fn matches(&self, event: &KeyboardEvent) -> bool {
+        Modifiers::of(event) == self.modifiers && event.code() == self.code
+    }
+
+    /// Label for the combo, like "Alt+-".
+    pub fn label(&self) -> String {
+        format!("{}{}", self.modifiers.label(), code_label(&self.code))
+    }
+}
+
+/// Readable name of the physical key with the given code.
+fn code_label(code: &str) -> &str {
+    match code {
+        "Minus" => "-",
+        "Equal" => "=",
+        _ => code
+            .strip_prefix("Key")
+            .or_else(|| code.strip_prefix("Digit"))
+            .unwrap_or(code),
+    }
+}
+
+/// Keyboard shortcuts for actions and the increments used when adjusting values with the arrow
+/// and page keys.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    /// Collapses every group.
+    #[serde(default = "default_collapse_all")]
+    pub collapse_all: KeyCombo,
+    /// Expands every group.
+    #[serde(default = "default_expand_all")]
+    pub expand_all: KeyCombo,
+    /// Modifiers held with a digit 1 through 9 to expand groups to that depth.
+    #[serde(default = "default_expand_to_depth")]
+    pub expand_to_depth: Modifiers,
+    /// Increments for adjusting clock speeds, as a fraction where 1 is 100%.
+    #[serde(default = "default_clock_steps")]
+    pub clock_steps: AdjustSteps,
+    /// Increments for adjusting building and group multipliers.
+    #[serde(default)]
+    pub copies_steps: AdjustSteps,
+    /// Increments for adjusting balances when backdriving.
+    #[serde(default)]
+    pub backdrive_steps: AdjustSteps,
+}
+
+fn default_collapse_all() -> KeyCombo {
+    KeyCombo {
+        modifiers: Modifiers::ALT,
+        code: "Minus".to_owned(),
+    }
+}
+
+fn default_expand_all() -> KeyCombo {
+    KeyCombo {
+        modifiers: Modifiers::ALT,
+        code: "Equal".to_owned(),
+    }
+}
+
+fn default_expand_to_depth() -> Modifiers {
+    Modifiers::ALT
+}
+
+/// Clock adjustments are by 50% or 100%, or 1% and 10% with the smaller modifier.
+fn default_clock_steps() -> AdjustSteps {
+    AdjustSteps {
+        fine: 0.5,
+        coarse: 1.0,
+        ..AdjustSteps::default()
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            collapse_all: default_collapse_all(),
+            expand_all: default_expand_all(),
+            expand_to_depth: default_expand_to_depth(),
+            clock_steps: default_clock_steps(),
+            copies_steps: AdjustSteps::default(),
+            backdrive_steps: AdjustSteps::default(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Get the depth to expand groups to for the given key press, if it is an expand to depth
+    /// shortcut.
+    pub fn expand_depth(&self, event: &KeyboardEvent) -> Option<usize> {
+        if Modifiers::of(event) != self.expand_to_depth {
+            return None;
+        }
+        match event
+            .code()
+            .strip_prefix("Digit")
+            .and_then(|digit| digit.parse::<usize>().ok())
+        {
+            Some(depth @ 1..=9) => Some(depth),
+            _ => None,
+        }
+    }
+}
+
+/// Settings section for remapping shortcuts and changing adjustment increments.
+#[function_component]
+pub fn KeyBindingsSection() -> Html {
+    let user_settings = use_user_settings();
+    let settings_dispatcher = use_user_settings_dispatcher();
+    let bindings = &user_settings.key_bindings;
+
+    let update = |change: fn(&mut KeyBindings, KeyCombo)| {
+        let settings_dispatcher = settings_dispatcher.clone();
+        let bindings = bindings.clone();
+        Callback::from(move |combo: KeyCombo| {
+            let mut bindings = bindings.clone();
+            change(&mut bindings, combo);
+            settings_dispatcher.set_key_bindings(bindings);
+        })
+    };
+    let set_collapse_all = update(|bindings, combo| bindings.collapse_all = combo);
+    let set_expand_all = update(|bindings, combo| bindings.expand_all = combo);
+    let set_expand_to_depth = update(|bindings, combo| bindings.expand_to_depth = combo.modifiers);
+    let expand_to_depth = KeyCombo {
+        modifiers: bindings.expand_to_depth,
+        code: "Digit1".to_owned(),
+    };
+
+    let update_steps = |change: fn(&mut KeyBindings) -> &mut AdjustSteps| {
+        let settings_dispatcher = settings_dispatcher.clone();
+        let bindings = bindings.clone();
+        Callback::from(move |steps: AdjustSteps| {
+            let mut bindings = bindings.clone();
+            *change(&mut bindings) = steps;
+            settings_dispatcher.set_key_bindings(bindings);
+        })
+    };
+
+    let reset = use_callback(settings_dispatcher.clone(), |(), settings_dispatcher| {
+        settings_dispatcher.set_key_bindings(KeyBindings::default());
+    });
+
+    html! {
+        <div class="settings-section KeyBindingsSection">
+            <h2>{"Keyboard"}</h2>
+            <div class="settings-subsection">
+                <h3>{"Shortcuts"}</h3>
+                <p>{"Click a shortcut, then press the keys to use instead. Shortcuts don't apply \
+                while typing in a text box."}</p>
+                <ul>
+                    <li>
+                        <label>
+                            <span>{"Collapse all groups"}</span>
+                            <KeyComboEdit combo={bindings.collapse_all.clone()}
+                                on_change={set_collapse_all} />
+                        </label>
+                    </li>
+                    <li>
+                        <label>
+                            <span>{"Expand all groups"}</span>
+                            <KeyComboEdit combo={bindings.expand_all.clone()}
+                                on_change={set_expand_all} />
+                        </label>
+                    </li>
+                    <li>
+                        <label>
+                            <span>{"Expand groups to depth 1 through 9"}</span>
+                            <KeyComboEdit combo={expand_to_depth} modifiers_only=true
+                                on_change={set_expand_to_depth} />
+                        </label>
+                    </li>
+                </ul>
+            </div>
+            <div class="settings-subsection">
+                <h3>{"Adjustment Increments"}</h3>
+                <p>{"While editing a number, the arrow keys adjust it by the fine increment and \
+                Page Up and Page Down adjust it by the coarse increment. Holding Shift uses the \
+                smaller increments instead."}</p>
+                <AdjustStepsEdit name="Clock speed (1 = 100%)" steps={bindings.clock_steps}
+                    on_change={update_steps(|bindings| &mut bindings.clock_steps)} />
+                <AdjustStepsEdit name="Multiplier" steps={bindings.copies_steps}
+                    on_change={update_steps(|bindings| &mut bindings.copies_steps)} />
+                <AdjustStepsEdit name="Backdriven balance" steps={bindings.backdrive_steps}
+                    on_change={update_steps(|bindings| &mut bindings.backdrive_steps)} />
+            </div>
+            <div class="reset-key-bindings">
+                <Button title="Reset shortcuts and increments to the defaults" onclick={reset}>
+                    {"Reset to Defaults"}
+                </Button>
+            </div>
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct KeyComboEditProps {
+    /// Current shortcut.
+    combo: KeyCombo,
+    /// If true, only the modifiers of the shortcut are edited and shown.
+    #[prop_or_default]
+    modifiers_only: bool,
+    /// Called with the new shortcut once keys are pressed.
+    on_change: Callback<KeyCombo>,
+}
+
+/// Shows a shortcut, which records a new shortcut from the next keys pressed after it's clicked.
+#[function_component]
+fn KeyComboEdit(
+    KeyComboEditProps {
+        combo,
+        modifiers_only,
+        on_change,
+    }: &KeyComboEditProps,
+) -> Html {
+    let recording = use_state_eq(|| false);
+    let modifiers_only = *modifiers_only;
+
+    let onclick = {
+        let recording = recording.clone();
+        Callback::from(move |_| recording.set(!*recording))
+    };
+    let onblur = {
+        let recording = recording.clone();
+        Callback::from(move |_| recording.set(false))
+    };
+    let onkeydown = {
+        let recording = recording.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if !*recording {
+                return;
+            }
+            e.prevent_default();
+            let code = e.code();
+            if code == "Escape" {
+                recording.set(false);
+                return;
+            }
+            // Wait for a key other than a modifier.
+            if matches!(
+                &*e.key(),
+                "Control" | "Alt" | "Shift" | "Meta" | "AltGraph" | "OS"
+            ) {
+                return;
+            }
+            let modifiers = Modifiers::of(&e);
+            if modifiers_only && modifiers == Modifiers::default() {
+                return;
+            }
+            recording.set(false);
+            on_change.emit(KeyCombo { modifiers, code });
+        })
+    };
+
+    let label = if *recording {
+        "Press keys\u{2026}".to_owned()
+    } else if modifiers_only {
+        format!("{}1\u{2013}9", combo.modifiers.label())
+    } else {
+        combo.label()
+    };
+    html! {
+        <button class={classes!("Button", "KeyComboEdit", recording.then_some("recording"))}
+            title="Click, then press the new shortcut" {onclick} {onblur} {onkeydown}>
+            {label}
+        </button>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct AdjustStepsEditProps {
+    /// Name of the kind of value the steps adjust.
+    name: AttrValue,
+    /// Current steps.
+    steps: AdjustSteps,
+    /// Called with the new steps when one is changed.
+    on_change: Callback<AdjustSteps>,
+}
+
+/// Editor for the increments used to adjust one kind of value.
+#[function_component]
+fn AdjustStepsEdit(
+    AdjustStepsEditProps {
+        name,
+        steps,
+        on_change,
+    }: &AdjustStepsEditProps,
+) -> Html {
+    let steps = *steps;
+    let field = |label: &'static str, value: f32, set: fn(&mut AdjustSteps, f32)| {
+        let on_change = on_change.clone();
+        let on_commit = Callback::from(move |edit_text: AttrValue| {
+            if let Ok(value) = edit_text.parse::<f32>() {
+                if value.is_finite() && value > 0.0 {
+                    let mut steps = steps;
+                    set(&mut steps, value);
+                    on_change.emit(steps);
+                }
+            }
+        });
+        html! {
+            <ClickEdit value={value.to_string()} title={label} {on_commit}
+                prefix={html! {<span class="prefix">{label}</span>}} />
+        }
+    };
+    html! {
+        <div class="AdjustStepsEdit">
+            <h4>{name}</h4>
+            <div class="steps">
+                {field("Fine", steps.fine, |steps, value| steps.fine = value)}
+                {field("Coarse", steps.coarse, |steps, value| steps.coarse = value)}
+                {field("Fine, smaller", steps.fine_smaller,
+                    |steps, value| steps.fine_smaller = value)}
+                {field("Coarse, smaller", steps.coarse_smaller,
+                    |steps, value| steps.coarse_smaller = value)}
+            </div>
+        </div>
+    }
+}
@@ -5,15 +5,19 @@ use std::rc::Rc;
 use gloo::storage::errors::StorageError;
 use gloo::storage::{LocalStorage, Storage as _};
 use log::warn;
+use satisfactory_accounting::database::ItemId;
 use yew::html::Scope;
-use yew::{hook, html, use_context, Component, Context, ContextProvider, Html, Properties};
+use yew::{
+    hook, html, use_context, Callback, Component, Context, ContextProvider, Html, Properties,
+};
 
 use crate::node_display::{BackdriveSettingsMsg, BalanceSortMode};
 use crate::refeqrc::RefEqRc;
 use crate::user_settings::number_format::NumberDisplaySettingsMsg;
+use crate::user_settings::profiles::SettingsProfiles;
 use crate::user_settings::storagemanager::persist_local_storage;
-use crate::user_settings::UserSettings;
-use crate::world::WorldSortSettingsMsg;
+use crate::user_settings::{IconPack, KeyBindings, SettingsOverrides, Theme, UserSettings};
+use crate::world::{UndoSettings, WorldSortSettingsMsg};
 
 /// Local storage key used to save user settings.
 const USER_SETTINGS_KEY: &str = "zstewart.satisfactorydb.usersettings";
@@ -29,6 +33,20 @@ fn save_user_settings(settings: &UserSettings) {
     }
 }
 
+/// Local storage key used to save settings profiles.
+const SETTINGS_PROFILES_KEY: &str = "zstewart.satisfactorydb.usersettings.profiles";
+
+fn load_settings_profiles() -> Result<SettingsProfiles, StorageError> {
+    LocalStorage::get(SETTINGS_PROFILES_KEY)
+}
+
+/// Save the given settings profiles.
+fn save_settings_profiles(profiles: &SettingsProfiles) {
+    if let Err(e) = LocalStorage::set(SETTINGS_PROFILES_KEY, profiles) {
+        warn!("Unable to save settings profiles: {}", e);
+    }
+}
+
 #[derive(PartialEq, Properties)]
 pub struct Props {
     /// Children to render within the context of the UserSettingsManager.
@@ -51,6 +69,65 @@ pub enum Msg {
     },
     /// Toggles the show deprecated databases setting.
     ToggleShowDeprecated,
+    /// Toggles between icon images and text badges.
+    ToggleTextOnlyIcons,
+    /// Toggles whether build progress tracking is shown.
+    ToggleTrackBuildProgress,
+    /// Toggles whether group efficiency is shown.
+    ToggleShowEfficiency,
+    /// Sets the tolerance used to decide whether a group is balanced.
+    SetBalancedTolerance {
+        /// The new tolerance to use.
+        tolerance: f32,
+    },
+    /// Toggles whether only unbalanced groups are shown.
+    ToggleOnlyUnbalancedGroups,
+    /// Toggles whether percent contributions are shown.
+    TogglePercentContributions,
+    /// Toggles whether per copy balances are shown.
+    TogglePerCopyBalances,
+    /// Toggles whether the condensed layout is used.
+    ToggleCompactLayout,
+    /// Sets the color theme.
+    SetTheme {
+        /// The new theme to use.
+        theme: Theme,
+    },
+    /// Replaces the keyboard shortcuts and adjustment increments.
+    SetKeyBindings {
+        /// The new key bindings to use.
+        key_bindings: KeyBindings,
+    },
+    /// Replaces the undo history settings.
+    SetUndoSettings {
+        /// The new undo settings to use.
+        undo: UndoSettings,
+    },
+    /// Sets or clears the name an item is shown with.
+    SetItemAlias {
+        /// Item to rename.
+        item: ItemId,
+        /// Name to show the item with, or empty to use its original name.
+        alias: String,
+    },
+    /// Sets or clears the icon pack used for databases with the given icon prefix.
+    SetIconPack {
+        /// Icon prefix of the databases the pack applies to.
+        prefix: String,
+        /// Icons to use, or an empty pack to go back to the built-in icons.
+        pack: IconPack,
+    },
+    /// Adds an uploaded icon to the icon pack used for databases with the given icon prefix.
+    /// Uploads finish one at a time, so each is added to the pack as it is when the upload
+    /// finishes.
+    AddUploadedIcon {
+        /// Icon prefix of the databases the pack applies to.
+        prefix: String,
+        /// Slug of the item the icon is for.
+        slug: String,
+        /// Data URL of the icon.
+        url: String,
+    },
     /// Acknowledges the use of LocalStorage.
     AckLocalStorage { version: u32 },
     /// Acknowledges a particular welcome message version.
@@ -61,11 +138,37 @@ pub enum Msg {
     UpdateBackdriveSettings { msg: BackdriveSettingsMsg },
     /// Updates the number display settings by applying the given message.
     UpdateNumberDisplaySettings { msg: NumberDisplaySettingsMsg },
+    /// Replaces all settings, such as when restoring a backup.
+    ReplaceAll { settings: UserSettings },
+    /// Saves the current settings as a profile with the given name and makes it active.
+    SaveProfile { name: String },
+    /// Switches to the settings of the named profile, or stops using a profile if None.
+    SwitchProfile { name: Option<String> },
+    /// Deletes the named profile.
+    DeleteProfile { name: String },
+    /// Replaces all settings profiles, such as when restoring a backup.
+    ReplaceProfiles { profiles: SettingsProfiles },
+    /// Sets the settings the current world overrides.
+    SetWorldOverrides {
+        /// Settings overridden by the current world.
+        overrides: SettingsOverrides,
+        /// Called when an overridden setting is changed, so the world can save it.
+        on_change: Callback<SettingsOverrides>,
+    },
 }
 
 pub struct UserSettingsManager {
     /// Current global settings.
     user_settings: Rc<UserSettings>,
+    /// Settings overridden by the current world. Changes to these settings apply to the world
+    /// rather than the global settings.
+    world_overrides: SettingsOverrides,
+    /// Called with the new overrides when an overridden setting is changed.
+    on_overrides_changed: Callback<SettingsOverrides>,
+    /// Global settings with the current world's overrides applied, which is what gets shown.
+    effective_settings: Rc<UserSettings>,
+    /// Named profiles of the global settings.
+    profiles: Rc<SettingsProfiles>,
     /// Whether to set the hide_empty_balances setting to the value from GlobalMetadata when the
     /// World is loaded. This is used to implement backwards compatibility with versions from before
     /// v1.2.0, where hide_empty_balances was stored on the World rather than the user settings.
@@ -107,6 +210,11 @@ impl UserSettingsManager {
 
     /// Message handler for ToggleHideEmptyBalances
     fn toggle_hide_empty_balances(&mut self) -> bool {
+        if let Some(hide_empty_balances) = &mut self.world_overrides.hide_empty_balances {
+            *hide_empty_balances = !*hide_empty_balances;
+            self.on_overrides_changed.emit(self.world_overrides.clone());
+            return true;
+        }
         self.fallback_to_world_global_metadata = false;
         let user_settings = Rc::make_mut(&mut self.user_settings);
         user_settings.hide_empty_balances = !user_settings.hide_empty_balances;
@@ -116,6 +224,14 @@ impl UserSettingsManager {
 
     /// Message handler for SetBalanceSortMode.
     fn set_balance_sort_mode(&mut self, sort_mode: BalanceSortMode) -> bool {
+        if let Some(current) = &mut self.world_overrides.balance_sort_mode {
+            if *current == sort_mode {
+                return false;
+            }
+            *current = sort_mode;
+            self.on_overrides_changed.emit(self.world_overrides.clone());
+            return true;
+        }
         if self.user_settings.balance_sort_mode != sort_mode {
             Rc::make_mut(&mut self.user_settings).balance_sort_mode = sort_mode;
             save_user_settings(&self.user_settings);
@@ -134,6 +250,173 @@ impl UserSettingsManager {
         true
     }
 
+    /// Message handler for ToggleTextOnlyIcons.
+    fn toggle_text_only_icons(&mut self) -> bool {
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings.text_only_icons = !user_settings.text_only_icons;
+        save_user_settings(user_settings);
+        true
+    }
+
+    /// Message handler for ToggleTrackBuildProgress.
+    fn toggle_track_build_progress(&mut self) -> bool {
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings.track_build_progress = !user_settings.track_build_progress;
+        save_user_settings(user_settings);
+        true
+    }
+
+    /// Message handler for ToggleShowEfficiency.
+    fn toggle_show_efficiency(&mut self) -> bool {
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings.show_efficiency = !user_settings.show_efficiency;
+        save_user_settings(user_settings);
+        true
+    }
+
+    /// Message handler for SetBalancedTolerance.
+    fn set_balanced_tolerance(&mut self, tolerance: f32) -> bool {
+        if self.user_settings.balanced_tolerance != tolerance {
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.balanced_tolerance = tolerance;
+            save_user_settings(user_settings);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Message handler for ToggleOnlyUnbalancedGroups.
+    fn toggle_only_unbalanced_groups(&mut self) -> bool {
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings.only_unbalanced_groups = !user_settings.only_unbalanced_groups;
+        save_user_settings(user_settings);
+        true
+    }
+
+    /// Message handler for TogglePercentContributions.
+    fn toggle_percent_contributions(&mut self) -> bool {
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings.show_percent_contributions = !user_settings.show_percent_contributions;
+        save_user_settings(user_settings);
+        true
+    }
+
+    /// Message handler for TogglePerCopyBalances.
+    fn toggle_per_copy_balances(&mut self) -> bool {
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings.show_per_copy_balances = !user_settings.show_per_copy_balances;
+        save_user_settings(user_settings);
+        true
+    }
+
+    /// Message handler for ToggleCompactLayout.
+    fn toggle_compact_layout(&mut self) -> bool {
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings.compact_layout = !user_settings.compact_layout;
+        save_user_settings(user_settings);
+        true
+    }
+
+    /// Message handler for SetTheme.
+    fn set_theme(&mut self, theme: Theme) -> bool {
+        if self.user_settings.theme != theme {
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.theme = theme;
+            save_user_settings(user_settings);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Message handler for SetKeyBindings.
+    fn set_key_bindings(&mut self, key_bindings: KeyBindings) -> bool {
+        if self.user_settings.key_bindings != key_bindings {
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.key_bindings = key_bindings;
+            save_user_settings(user_settings);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Message handler for SetUndoSettings.
+    fn set_undo_settings(&mut self, undo: UndoSettings) -> bool {
+        if self.user_settings.undo != undo {
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.undo = undo;
+            save_user_settings(user_settings);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Message handler for SetItemAlias.
+    fn set_item_alias(&mut self, item: ItemId, alias: String) -> bool {
+        let current = self.user_settings.item_aliases.get(&item);
+        if alias.is_empty() {
+            if current.is_none() {
+                return false;
+            }
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.item_aliases.remove(&item);
+            save_user_settings(user_settings);
+        } else {
+            if current == Some(&alias) {
+                return false;
+            }
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.item_aliases.insert(item, alias);
+            save_user_settings(user_settings);
+        }
+        true
+    }
+
+    /// Message handler for SetIconPack.
+    fn set_icon_pack(&mut self, prefix: String, pack: IconPack) -> bool {
+        let current = self.user_settings.icon_packs.get(&prefix);
+        if pack.is_empty() {
+            if current.is_none() {
+                return false;
+            }
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.icon_packs.remove(&prefix);
+            save_user_settings(user_settings);
+        } else {
+            if current == Some(&pack) {
+                return false;
+            }
+            let user_settings = Rc::make_mut(&mut self.user_settings);
+            user_settings.icon_packs.insert(prefix, pack);
+            save_user_settings(user_settings);
+        }
+        true
+    }
+
+    /// Message handler for AddUploadedIcon.
+    fn add_uploaded_icon(&mut self, prefix: String, slug: String, url: String) -> bool {
+        let current = self
+            .user_settings
+            .icon_packs
+            .get(&prefix)
+            .and_then(|pack| pack.uploaded.get(&slug));
+        if current == Some(&url) {
+            return false;
+        }
+        let user_settings = Rc::make_mut(&mut self.user_settings);
+        user_settings
+            .icon_packs
+            .entry(prefix)
+            .or_default()
+            .uploaded
+            .insert(slug, url);
+        save_user_settings(user_settings);
+        true
+    }
+
     /// Message handler for AckLocalStorage.
     fn ack_local_storage(&mut self, version: u32) -> bool {
         // Don't allow backsliding.
@@ -158,6 +441,100 @@ impl UserSettingsManager {
         }
     }
 
+    /// Message handler for SetWorldOverrides.
+    fn set_world_overrides(
+        &mut self,
+        overrides: SettingsOverrides,
+        on_change: Callback<SettingsOverrides>,
+    ) -> bool {
+        self.on_overrides_changed = on_change;
+        if self.world_overrides != overrides {
+            self.world_overrides = overrides;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Message handler for ReplaceAll.
+    fn replace_all(&mut self, settings: UserSettings) -> bool {
+        self.fallback_to_world_global_metadata = false;
+        if *self.user_settings != settings {
+            self.user_settings = Rc::new(settings);
+            save_user_settings(&self.user_settings);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Message handler for SaveProfile.
+    fn save_profile(&mut self, name: String) -> bool {
+        let profiles = Rc::make_mut(&mut self.profiles);
+        profiles
+            .profiles
+            .insert(name.clone(), (*self.user_settings).clone());
+        profiles.active = Some(name);
+        save_settings_profiles(profiles);
+        true
+    }
+
+    /// Message handler for SwitchProfile.
+    fn switch_profile(&mut self, name: Option<String>) -> bool {
+        if self.profiles.active == name {
+            return false;
+        }
+        let profile = match &name {
+            Some(name) => match self.profiles.profiles.get(name) {
+                Some(profile) => Some(profile.clone()),
+                None => {
+                    warn!("No settings profile named {name:?}");
+                    return false;
+                }
+            },
+            None => None,
+        };
+        let profiles = Rc::make_mut(&mut self.profiles);
+        profiles.active = name;
+        save_settings_profiles(profiles);
+        if let Some(profile) = profile {
+            // Which notices were acknowledged belongs to this browser, not to the profile.
+            let settings = UserSettings {
+                acked_local_storage_notice_version: self
+                    .user_settings
+                    .acked_local_storage_notice_version,
+                acked_notification: self.user_settings.acked_notification,
+                ..profile
+            };
+            self.replace_all(settings);
+        }
+        true
+    }
+
+    /// Message handler for ReplaceProfiles.
+    fn replace_profiles(&mut self, profiles: SettingsProfiles) -> bool {
+        if *self.profiles == profiles {
+            return false;
+        }
+        self.profiles = Rc::new(profiles);
+        save_settings_profiles(&self.profiles);
+        true
+    }
+
+    /// Message handler for DeleteProfile.
+    fn delete_profile(&mut self, name: String) -> bool {
+        if !self.profiles.profiles.contains_key(&name) {
+            return false;
+        }
+        let profiles = Rc::make_mut(&mut self.profiles);
+        profiles.profiles.remove(&name);
+        if profiles.active.as_ref() == Some(&name) {
+            profiles.active = None;
+        }
+        save_settings_profiles(profiles);
+        true
+    }
+
     /// Message handler for UpdateWorldSortSettings.
     fn update_world_sort_settings(&mut self, msg: WorldSortSettingsMsg) -> bool {
         if Rc::make_mut(&mut self.user_settings)
@@ -173,6 +550,13 @@ impl UserSettingsManager {
 
     /// Message handler for UpdateBackdriveSettings.
     fn update_backdrive_settings(&mut self, msg: BackdriveSettingsMsg) -> bool {
+        if let Some(settings) = &mut self.world_overrides.backdrive_settings {
+            let changed = settings.update(msg);
+            if changed {
+                self.on_overrides_changed.emit(self.world_overrides.clone());
+            }
+            return changed;
+        }
         if Rc::make_mut(&mut self.user_settings)
             .backdrive_settings
             .update(msg)
@@ -186,6 +570,13 @@ impl UserSettingsManager {
 
     /// Message handler for UpdateNumberDisplaySettings.
     fn update_number_display_settings(&mut self, msg: NumberDisplaySettingsMsg) -> bool {
+        if let Some(settings) = &mut self.world_overrides.number_display {
+            let changed = settings.update(msg);
+            if changed {
+                self.on_overrides_changed.emit(self.world_overrides.clone());
+            }
+            return changed;
+        }
         if Rc::make_mut(&mut self.user_settings)
             .number_display
             .update(msg)
@@ -220,43 +611,103 @@ impl Component for UserSettingsManager {
             }
         });
 
+        let profiles = Rc::new(match load_settings_profiles() {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                if !matches!(e, StorageError::KeyNotFound(_)) {
+                    warn!("Failed to load settings profiles: {}", e);
+                }
+                SettingsProfiles::default()
+            }
+        });
+
         let dispatcher = UserSettingsDispatcher::new(ctx.link().clone());
         Self {
+            effective_settings: user_settings.clone(),
             user_settings,
+            profiles,
+            world_overrides: SettingsOverrides::default(),
+            on_overrides_changed: Callback::noop(),
             fallback_to_world_global_metadata,
             dispatcher,
         }
     }
 
     fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
+        let changed = match msg {
             Msg::MaybeInitFromWorld {
                 hide_empty_balances_from_deprecated_global_metadata,
             } => self.maybe_init_from_world(hide_empty_balances_from_deprecated_global_metadata),
             Msg::ToggleHideEmptyBalances => self.toggle_hide_empty_balances(),
             Msg::SetBalanceSortMode { sort_mode } => self.set_balance_sort_mode(sort_mode),
             Msg::ToggleShowDeprecated => self.toggle_show_deprecated(),
+            Msg::ToggleTextOnlyIcons => self.toggle_text_only_icons(),
+            Msg::ToggleTrackBuildProgress => self.toggle_track_build_progress(),
+            Msg::ToggleShowEfficiency => self.toggle_show_efficiency(),
+            Msg::SetBalancedTolerance { tolerance } => self.set_balanced_tolerance(tolerance),
+            Msg::ToggleOnlyUnbalancedGroups => self.toggle_only_unbalanced_groups(),
+            Msg::TogglePercentContributions => self.toggle_percent_contributions(),
+            Msg::TogglePerCopyBalances => self.toggle_per_copy_balances(),
+            Msg::ToggleCompactLayout => self.toggle_compact_layout(),
+            Msg::SetTheme { theme } => self.set_theme(theme),
+            Msg::SetKeyBindings { key_bindings } => self.set_key_bindings(key_bindings),
+            Msg::SetUndoSettings { undo } => self.set_undo_settings(undo),
+            Msg::SetItemAlias { item, alias } => self.set_item_alias(item, alias),
+            Msg::SetIconPack { prefix, pack } => self.set_icon_pack(prefix, pack),
+            Msg::AddUploadedIcon { prefix, slug, url } => self.add_uploaded_icon(prefix, slug, url),
             Msg::AckLocalStorage { version } => self.ack_local_storage(version),
             Msg::AckNotification { version } => self.ack_notification(version),
             Msg::UpdateWorldSortSettings { msg } => self.update_world_sort_settings(msg),
             Msg::UpdateBackdriveSettings { msg } => self.update_backdrive_settings(msg),
             Msg::UpdateNumberDisplaySettings { msg } => self.update_number_display_settings(msg),
+            Msg::ReplaceAll { settings } => self.replace_all(settings),
+            Msg::SaveProfile { name } => self.save_profile(name),
+            Msg::SwitchProfile { name } => self.switch_profile(name),
+            Msg::DeleteProfile { name } => self.delete_profile(name),
+            Msg::ReplaceProfiles { profiles } => self.replace_profiles(profiles),
+            Msg::SetWorldOverrides {
+                overrides,
+                on_change,
+            } => self.set_world_overrides(overrides, on_change),
+        };
+        if changed {
+            // Keep the active profile up to date with any change to the global settings.
+            if self.profiles.active.is_some()
+                && Rc::make_mut(&mut self.profiles).sync_active(&self.user_settings)
+            {
+                save_settings_profiles(&self.profiles);
+            }
+            self.effective_settings = if self.world_overrides.is_empty() {
+                self.user_settings.clone()
+            } else {
+                Rc::new(self.world_overrides.apply(&self.user_settings))
+            };
         }
+        changed
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
             // This context provider will never change for the life of the UserSettingsManager.
             <ContextProvider<UserSettingsDispatcher> context={self.dispatcher.clone()}>
-            // This context will change whenever the user settings change.
-            <ContextProvider<Rc<UserSettings>> context={Rc::clone(&self.user_settings)}>
+            // These contexts will change whenever the user settings change.
+            <ContextProvider<Rc<UserSettings>> context={Rc::clone(&self.effective_settings)}>
+            <ContextProvider<GlobalUserSettings>
+                context={GlobalUserSettings(Rc::clone(&self.user_settings))}>
+            <ContextProvider<Rc<SettingsProfiles>> context={Rc::clone(&self.profiles)}>
                 {ctx.props().children.clone()}
+            </ContextProvider<Rc<SettingsProfiles>>>
+            </ContextProvider<GlobalUserSettings>>
             </ContextProvider<Rc<UserSettings>>>
             </ContextProvider<UserSettingsDispatcher>>
         }
     }
 }
 
+/// Global user settings, without the current world's overrides.
+#[derive(Clone, Debug, PartialEq)]
+struct GlobalUserSettings(Rc<UserSettings>);
+
 /// Dispatcher which can be used to update user settings.
 #[derive(Clone, Debug, PartialEq)]
 pub struct UserSettingsDispatcher {
@@ -295,6 +746,80 @@ impl UserSettingsDispatcher {
         self.scope.send_message(Msg::ToggleShowDeprecated);
     }
 
+    /// Toggles whether icons are shown as text badges instead of images.
+    pub fn toggle_text_only_icons(&self) {
+        self.scope.send_message(Msg::ToggleTextOnlyIcons);
+    }
+
+    /// Toggles whether buildings can be marked as built and groups show build progress.
+    pub fn toggle_track_build_progress(&self) {
+        self.scope.send_message(Msg::ToggleTrackBuildProgress);
+    }
+
+    /// Toggles whether groups show the efficiency of their machines.
+    pub fn toggle_show_efficiency(&self) {
+        self.scope.send_message(Msg::ToggleShowEfficiency);
+    }
+
+    /// Sets the largest deficit which still counts as balanced.
+    pub fn set_balanced_tolerance(&self, tolerance: f32) {
+        self.scope
+            .send_message(Msg::SetBalancedTolerance { tolerance });
+    }
+
+    /// Toggles whether self-sufficient groups are hidden.
+    pub fn toggle_only_unbalanced_groups(&self) {
+        self.scope.send_message(Msg::ToggleOnlyUnbalancedGroups);
+    }
+
+    /// Toggles whether percent contributions are shown.
+    pub fn toggle_percent_contributions(&self) {
+        self.scope.send_message(Msg::TogglePercentContributions);
+    }
+
+    /// Toggles whether per copy balances are shown.
+    pub fn toggle_per_copy_balances(&self) {
+        self.scope.send_message(Msg::TogglePerCopyBalances);
+    }
+
+    /// Toggles whether the condensed layout is used.
+    pub fn toggle_compact_layout(&self) {
+        self.scope.send_message(Msg::ToggleCompactLayout);
+    }
+
+    /// Sets the color theme.
+    pub fn set_theme(&self, theme: Theme) {
+        self.scope.send_message(Msg::SetTheme { theme });
+    }
+
+    /// Replaces the keyboard shortcuts and adjustment increments.
+    pub fn set_key_bindings(&self, key_bindings: KeyBindings) {
+        self.scope
+            .send_message(Msg::SetKeyBindings { key_bindings });
+    }
+
+    /// Replaces the undo history settings.
+    pub fn set_undo_settings(&self, undo: UndoSettings) {
+        self.scope.send_message(Msg::SetUndoSettings { undo });
+    }
+
+    /// Sets the name an item is shown with, or clears it if the name is empty.
+    pub fn set_item_alias(&self, item: ItemId, alias: String) {
+        self.scope.send_message(Msg::SetItemAlias { item, alias });
+    }
+
+    /// Sets the icon pack for databases with the given icon prefix, or clears it if the pack is
+    /// empty.
+    pub fn set_icon_pack(&self, prefix: String, pack: IconPack) {
+        self.scope.send_message(Msg::SetIconPack { prefix, pack });
+    }
+
+    /// Adds an uploaded icon to the icon pack for databases with the given icon prefix.
+    pub fn add_uploaded_icon(&self, prefix: String, slug: String, url: String) {
+        self.scope
+            .send_message(Msg::AddUploadedIcon { prefix, slug, url });
+    }
+
     /// Ack the given local storage notice version.
     pub fn ack_local_storage(&self, version: u32) {
         self.scope.send_message(Msg::AckLocalStorage { version });
@@ -305,6 +830,44 @@ impl UserSettingsDispatcher {
         self.scope.send_message(Msg::AckNotification { version });
     }
 
+    /// Sets the settings overridden by the current world. `on_change` is called with the new
+    /// overrides whenever an overridden setting is changed.
+    pub fn set_world_overrides(
+        &self,
+        overrides: SettingsOverrides,
+        on_change: Callback<SettingsOverrides>,
+    ) {
+        self.scope.send_message(Msg::SetWorldOverrides {
+            overrides,
+            on_change,
+        });
+    }
+
+    /// Replaces all settings with the given settings.
+    pub fn replace_all(&self, settings: UserSettings) {
+        self.scope.send_message(Msg::ReplaceAll { settings });
+    }
+
+    /// Saves the current settings as a profile with the given name and switches to it.
+    pub fn save_profile(&self, name: String) {
+        self.scope.send_message(Msg::SaveProfile { name });
+    }
+
+    /// Switches to the named settings profile, or stops using a profile if None.
+    pub fn switch_profile(&self, name: Option<String>) {
+        self.scope.send_message(Msg::SwitchProfile { name });
+    }
+
+    /// Deletes the named settings profile.
+    pub fn delete_profile(&self, name: String) {
+        self.scope.send_message(Msg::DeleteProfile { name });
+    }
+
+    /// Replaces all settings profiles, keeping the current settings.
+    pub fn replace_profiles(&self, profiles: SettingsProfiles) {
+        self.scope.send_message(Msg::ReplaceProfiles { profiles });
+    }
+
     /// Attempts to make local storage persisted.
     pub fn persist_local_storage(&self) {
         wasm_bindgen_futures::spawn_local(async {
@@ -343,6 +906,24 @@ pub fn use_user_settings() -> Rc<UserSettings> {
         .expect("use_user_settings can only be used from within a child of UserSettingsManager.")
 }
 
+/// Get the global settings, ignoring any settings overridden by the current world.
+#[hook]
+pub fn use_global_user_settings() -> Rc<UserSettings> {
+    use_context::<GlobalUserSettings>()
+        .expect(
+            "use_global_user_settings can only be used from within a child of UserSettingsManager.",
+        )
+        .0
+}
+
+/// Get the named settings profiles.
+#[hook]
+pub fn use_settings_profiles() -> Rc<SettingsProfiles> {
+    use_context::<Rc<SettingsProfiles>>().expect(
+        "use_settings_profiles can only be used from within a child of UserSettingsManager.",
+    )
+}
+
 /// Get the UserSettingsDispatcher. Only triggers redraw if the UserSettingsManager is replaced
 /// somehow which shouldn't happen.
 #[hook]
@@ -1,21 +1,37 @@
 //! Management for user settings.
+use std::collections::BTreeMap;
+
+use satisfactory_accounting::database::ItemId;
 use serde::{Deserialize, Serialize};
 
 use crate::node_display::{BackdriveSettings, BalanceSortMode};
+pub use crate::user_settings::aliases::{ItemAliases, ItemAliasesSection};
+pub use crate::user_settings::icon_packs::{IconPack, IconPacksSection};
+pub use crate::user_settings::key_bindings::{KeyBindings, KeyBindingsSection};
 pub use crate::user_settings::manager::{
-    use_user_settings, use_user_settings_dispatcher, UserSettingsDispatcher, UserSettingsManager,
+    use_global_user_settings, use_settings_profiles, use_user_settings,
+    use_user_settings_dispatcher, UserSettingsDispatcher, UserSettingsManager,
 };
+pub use crate::user_settings::profiles::{SettingsProfilePicker, SettingsProfiles};
+pub use crate::user_settings::theme::{Theme, ThemeApplier};
 #[allow(unused_imports)]
 pub use crate::user_settings::window::{
-    use_user_settings_window, UserSettingsWindowDispatcher, UserSettingsWindowManager,
+    use_user_settings_window, world_only_tag, UserSettingsWindowDispatcher,
+    UserSettingsWindowManager,
 };
-use crate::world::WorldSortSettings;
+use crate::world::{UndoSettings, WorldSortSettings};
 
 use self::number_format::NumberDisplaySettings;
 
+mod aliases;
+mod export;
+mod icon_packs;
+mod key_bindings;
 mod manager;
 pub mod number_format;
+mod profiles;
 mod storagemanager;
+mod theme;
 mod window;
 
 /// App-wide settings specific to the user rather than the world.
@@ -42,6 +58,62 @@ pub struct UserSettings {
     #[serde(default)]
     pub number_display: NumberDisplaySettings,
 
+    /// Whether to show short text badges instead of icon images.
+    #[serde(default)]
+    pub text_only_icons: bool,
+
+    /// Whether to show build tracking checkboxes on buildings and progress on groups.
+    #[serde(default)]
+    pub track_build_progress: bool,
+
+    /// Whether to show the efficiency of the machines in each group.
+    #[serde(default)]
+    pub show_efficiency: bool,
+
+    /// Largest deficit per item or MW of power which still counts as balanced when deciding
+    /// whether a group is self-sufficient.
+    #[serde(default)]
+    pub balanced_tolerance: f32,
+
+    /// Whether to hide groups which are self-sufficient, so only unbalanced groups are shown.
+    #[serde(default)]
+    pub only_unbalanced_groups: bool,
+
+    /// Whether to show what percent of its parent's production or consumption each balance entry
+    /// represents, and each group's share of the world's power draw.
+    #[serde(default)]
+    pub show_percent_contributions: bool,
+
+    /// Whether to show the balance of a single copy of a building next to its total balance.
+    #[serde(default)]
+    pub show_per_copy_balances: bool,
+
+    /// Whether to use a condensed layout for the node tree, with smaller icons, tighter rows, and
+    /// shortened names.
+    #[serde(default)]
+    pub compact_layout: bool,
+
+    /// Color theme to show the app with.
+    #[serde(default)]
+    pub theme: Theme,
+
+    /// Keyboard shortcuts and the increments used when adjusting numbers with the keyboard.
+    #[serde(default)]
+    pub key_bindings: KeyBindings,
+
+    /// How much undo history to keep.
+    #[serde(default)]
+    pub undo: UndoSettings,
+
+    /// Names to show items with in place of their names from the database.
+    #[serde(default)]
+    pub item_aliases: BTreeMap<ItemId, String>,
+
+    /// Alternate icons to show in place of the built-in ones, keyed by the icon prefix of the
+    /// database they apply to.
+    #[serde(default)]
+    pub icon_packs: BTreeMap<String, IconPack>,
+
     /// Whether the user has acknowledged the use of local storage.
     #[serde(default)]
     pub acked_local_storage_notice_version: u32,
@@ -61,3 +133,46 @@ pub struct UserSettings {
 const fn notification_serde_default() -> u32 {
     1
 }
+
+/// Settings which a world can keep separately from the user's settings. Each setting which is
+/// set replaces the user's setting while the world is open.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsOverrides {
+    /// Whether empty balance values should be hidden in this world.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_empty_balances: Option<bool>,
+    /// How to sort balances in this world.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_sort_mode: Option<BalanceSortMode>,
+    /// Settings for how to backdrive balances in this world.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backdrive_settings: Option<BackdriveSettings>,
+    /// Settings for how to round and display numbers in this world.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_display: Option<NumberDisplaySettings>,
+}
+
+impl SettingsOverrides {
+    /// Whether the world doesn't override any settings.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Get the given user settings with these overrides applied.
+    pub fn apply(&self, settings: &UserSettings) -> UserSettings {
+        let mut settings = settings.clone();
+        if let Some(hide_empty_balances) = self.hide_empty_balances {
+            settings.hide_empty_balances = hide_empty_balances;
+        }
+        if let Some(balance_sort_mode) = self.balance_sort_mode {
+            settings.balance_sort_mode = balance_sort_mode;
+        }
+        if let Some(backdrive_settings) = &self.backdrive_settings {
+            settings.backdrive_settings = backdrive_settings.clone();
+        }
+        if let Some(number_display) = &self.number_display {
+            settings.number_display = number_display.clone();
+        }
+        settings
+    }
+}
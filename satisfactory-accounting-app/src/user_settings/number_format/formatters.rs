@@ -1,6 +1,6 @@
 use std::fmt;
 
-use super::{NumberFormatMode, NumberFormatSettings};
+use super::{BalanceDisplaySettings, NumberFormatMode, NumberFormatSettings};
 
 /// Helper trait for applying user configured formatting.
 pub trait UserConfiguredFormat {
@@ -69,3 +69,21 @@ impl<'s> fmt::Display for F32Formatter<'s> {
         }
     }
 }
+
+impl BalanceDisplaySettings {
+    /// Convert an item rate per minute into the configured rate unit, without rounding.
+    pub fn rate_in_unit(&self, rate: f32) -> f32 {
+        self.rate_unit.convert(rate)
+    }
+
+    /// Get a formatter for an item rate per minute, converted to the configured rate unit and
+    /// rounded by the item format settings.
+    pub fn format_rate(&self, rate: f32) -> F32Formatter<'_> {
+        self.rate_in_unit(rate).format(&self.item_format_settings)
+    }
+
+    /// Suffix naming the configured rate unit, such as "/min".
+    pub fn rate_suffix(&self) -> &'static str {
+        self.rate_unit.suffix()
+    }
+}
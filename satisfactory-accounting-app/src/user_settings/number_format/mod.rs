@@ -46,6 +46,62 @@ pub struct NumberFormatSettings {
     pub round_decimal_places: u32,
 }
 
+/// Unit of time that item rates are displayed and entered in.
+///
+/// Rates are always stored per minute; conversion only happens when formatting and parsing.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RateUnit {
+    /// Items per second.
+    Second,
+    /// Items per minute, which is how the game displays rates.
+    #[default]
+    Minute,
+    /// Items per hour.
+    Hour,
+}
+
+impl RateUnit {
+    /// All rate units, in the order they are offered in settings.
+    pub const ALL: [RateUnit; 3] = [RateUnit::Second, RateUnit::Minute, RateUnit::Hour];
+
+    /// Name of the unit for settings.
+    pub fn name(self) -> &'static str {
+        match self {
+            RateUnit::Second => "Per Second",
+            RateUnit::Minute => "Per Minute",
+            RateUnit::Hour => "Per Hour",
+        }
+    }
+
+    /// Suffix appended to rates in this unit, such as "/min".
+    pub fn suffix(self) -> &'static str {
+        match self {
+            RateUnit::Second => "/s",
+            RateUnit::Minute => "/min",
+            RateUnit::Hour => "/h",
+        }
+    }
+
+    /// Number of minutes in one of this unit.
+    fn minutes(self) -> f32 {
+        match self {
+            RateUnit::Second => 1.0 / 60.0,
+            RateUnit::Minute => 1.0,
+            RateUnit::Hour => 60.0,
+        }
+    }
+
+    /// Convert a rate per minute into a rate in this unit.
+    pub fn convert(self, rate: f32) -> f32 {
+        rate * self.minutes()
+    }
+
+    /// Convert a rate in this unit into a rate per minute.
+    pub fn to_per_minute(self, rate: f32) -> f32 {
+        rate / self.minutes()
+    }
+}
+
 /// Settings to apply to balance display.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BalanceDisplaySettings {
@@ -60,6 +116,9 @@ pub struct BalanceDisplaySettings {
     pub power_format_settings: NumberFormatSettings,
     /// Format settings to use for items.
     pub item_format_settings: NumberFormatSettings,
+    /// Unit of time item rates are shown and entered in.
+    #[serde(default)]
+    pub rate_unit: RateUnit,
 }
 
 impl Default for BalanceDisplaySettings {
@@ -73,6 +132,7 @@ impl Default for BalanceDisplaySettings {
             hide_style: Default::default(),
             power_format_settings: format.clone(),
             item_format_settings: format,
+            rate_unit: Default::default(),
         }
     }
 }
@@ -82,6 +142,9 @@ impl Default for BalanceDisplaySettings {
 pub struct ClockDisplaySettings {
     /// Number format settings to apply to the clock.
     pub format: NumberFormatSettings,
+    /// Whether to show clock speeds as percentages, like the game does, instead of multipliers.
+    #[serde(default)]
+    pub as_percent: bool,
 }
 
 impl Default for ClockDisplaySettings {
@@ -91,6 +154,7 @@ impl Default for ClockDisplaySettings {
                 mode: NumberFormatMode::DecimalPrecise,
                 round_decimal_places: 6,
             },
+            as_percent: false,
         }
     }
 }
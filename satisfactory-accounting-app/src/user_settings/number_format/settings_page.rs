@@ -1,11 +1,14 @@
-use yew::{function_component, html, use_callback, AttrValue, Callback, Html, Properties};
+use yew::{
+    function_component, html, use_callback, AttrValue, Callback, Html, MouseEvent, Properties,
+};
 
 use crate::inputs::clickedit::{AdjustDir, AdjustScale, ClickEdit, ValueAdjustment};
-use crate::inputs::toggle::MaterialRadio;
+use crate::inputs::toggle::{MaterialCheckbox, MaterialRadio};
 use crate::user_settings::number_format::{
-    NumberFormatMode, NumberFormatSettings, NumberStylingMode,
+    NumberFormatMode, NumberFormatSettings, NumberStylingMode, RateUnit,
 };
-use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+use crate::user_settings::{use_user_settings, use_user_settings_dispatcher, world_only_tag};
+use crate::world::use_settings_overrides;
 
 use super::NumberDisplaySettings;
 
@@ -28,7 +31,9 @@ enum Msg {
     UpdateBalanceHighlightMode { mode: NumberStylingMode },
     UpdateBalanceHideMode { mode: NumberStylingMode },
     UpdateBalanceFormat { settings: NumberFormatSettings },
+    UpdateRateUnit { unit: RateUnit },
     UpdateClockFormat { settings: NumberFormatSettings },
+    UpdateClockAsPercent { as_percent: bool },
     UpdateMultiplierFormat { settings: NumberFormatSettings },
 }
 
@@ -66,6 +71,16 @@ impl NumberDisplaySettings {
         }
     }
 
+    /// Message handler for [Msg::UpdateRateUnit].
+    fn set_rate_unit(&mut self, unit: RateUnit) -> bool {
+        if self.balance.rate_unit != unit {
+            self.balance.rate_unit = unit;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Message handler for [Msg::UpdateClockFormat].
     fn set_clock_format(&mut self, settings: NumberFormatSettings) -> bool {
         if self.clock.format != settings {
@@ -76,6 +91,16 @@ impl NumberDisplaySettings {
         }
     }
 
+    /// Message handler for [Msg::UpdateClockAsPercent].
+    fn set_clock_as_percent(&mut self, as_percent: bool) -> bool {
+        if self.clock.as_percent != as_percent {
+            self.clock.as_percent = as_percent;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Message handler for [Msg::UpdateMultiplierFormat].
     fn set_multiplier_format(&mut self, settings: NumberFormatSettings) -> bool {
         if self.multiplier.format != settings {
@@ -92,7 +117,9 @@ impl NumberDisplaySettings {
             Msg::UpdateBalanceHighlightMode { mode } => self.set_balance_highlight_mode(mode),
             Msg::UpdateBalanceHideMode { mode } => self.set_balance_hide_mode(mode),
             Msg::UpdateBalanceFormat { settings } => self.set_balance_format(settings),
+            Msg::UpdateRateUnit { unit } => self.set_rate_unit(unit),
             Msg::UpdateClockFormat { settings } => self.set_clock_format(settings),
+            Msg::UpdateClockAsPercent { as_percent } => self.set_clock_as_percent(as_percent),
             Msg::UpdateMultiplierFormat { settings } => self.set_multiplier_format(settings),
         }
     }
@@ -102,6 +129,7 @@ impl NumberDisplaySettings {
 #[function_component]
 pub fn NumberDisplaySettingsSection() -> Html {
     let user_settings = use_user_settings();
+    let overrides = use_settings_overrides();
     let num = &user_settings.number_display;
     let user_settings_dispatcher = use_user_settings_dispatcher();
 
@@ -129,6 +157,13 @@ pub fn NumberDisplaySettingsSection() -> Html {
         },
     );
 
+    let change_rate_unit = use_callback(
+        user_settings_dispatcher.clone(),
+        |unit, user_settings_dispatcher| {
+            user_settings_dispatcher.update_number_display_settings(Msg::UpdateRateUnit { unit });
+        },
+    );
+
     let change_clock_format = use_callback(
         user_settings_dispatcher.clone(),
         |settings, user_settings_dispatcher| {
@@ -137,6 +172,15 @@ pub fn NumberDisplaySettingsSection() -> Html {
         },
     );
 
+    let toggle_clock_as_percent = use_callback(
+        (user_settings_dispatcher.clone(), num.clock.as_percent),
+        |_, (user_settings_dispatcher, as_percent)| {
+            user_settings_dispatcher.update_number_display_settings(Msg::UpdateClockAsPercent {
+                as_percent: !as_percent,
+            });
+        },
+    );
+
     let change_multiplier_format = use_callback(
         user_settings_dispatcher,
         |settings, user_settings_dispatcher| {
@@ -147,7 +191,10 @@ pub fn NumberDisplaySettingsSection() -> Html {
 
     html! {
         <div class="NumberFormatSettingsSection settings-section">
-            <h2>{"Number Display Settings"}</h2>
+            <h2>
+                {"Number Display Settings"}
+                {world_only_tag(overrides.number_display.is_some())}
+            </h2>
             <p>{"This section controls how numbers are displayed and styled."}</p>
             <div class="settings-subsection">
                 <h3>{"Balance Display"}</h3>
@@ -164,6 +211,11 @@ pub fn NumberDisplaySettingsSection() -> Html {
                 regardless of your rouding setting, since we use f32."}</p>
                 <FormatSettings current={num.balance.item_format_settings.clone()}
                     on_change={change_balance_format} />
+                <h4>{"Rate Unit"}</h4>
+                <p>{"Item rates in balances, stations, and balance adjustments are shown and \
+                entered in this unit. Rates are still stored per minute, so changing the unit \
+                never changes your plans."}</p>
+                <RateUnitChoice current={num.balance.rate_unit} on_change={change_rate_unit} />
                 <h4>{"Coloring Balances and Hiding Zero Balances"}</h4>
                 <p>{"These settings control how coloring of balances and hiding of zero balances \
                 are affected by the rounding settings."}</p>
@@ -188,6 +240,20 @@ pub fn NumberDisplaySettingsSection() -> Html {
                 <h4>{"Clock Rounding"}</h4>
                 <FormatSettings current={num.clock.format.clone()}
                     on_change={change_clock_format} />
+                <h4>{"Clock Percentages"}</h4>
+                <p>{"Clock speeds can be entered as a multiplier like 1.5, a percentage like \
+                150%, or a fraction like 3/2. A bare number is always a multiplier, so 150 without \
+                the % sign means 15000%. This setting only changes how clock speeds are shown; \
+                showing them as percentages matches the game's UI."}</p>
+                <ul>
+                    <li>
+                        <label>
+                            <span>{"Show Clock Speeds as Percentages"}</span>
+                            <MaterialCheckbox checked={num.clock.as_percent}
+                                onclick={toggle_clock_as_percent} />
+                        </label>
+                    </li>
+                </ul>
                 <h4>{"Multiplier Rounding"}</h4>
                 <FormatSettings current={num.multiplier.format.clone()}
                     on_change={change_multiplier_format} />
@@ -236,6 +302,35 @@ fn StyleMode(props: &StyleModeProps) -> Html {
     }
 }
 
+#[derive(Properties, PartialEq)]
+struct RateUnitChoiceProps {
+    /// Current rate unit.
+    current: RateUnit,
+    /// Callback used when the rate unit changes.
+    on_change: Callback<RateUnit>,
+}
+
+/// Allows selecting the unit of time for item rates.
+#[function_component]
+fn RateUnitChoice(props: &RateUnitChoiceProps) -> Html {
+    let choice = |unit: RateUnit| {
+        let onclick = props.on_change.reform(move |_: MouseEvent| unit);
+        html! {
+            <li>
+                <label>
+                    <span>{unit.name()}{" ("}{unit.suffix()}{")"}</span>
+                    <MaterialRadio checked={props.current == unit} {onclick} />
+                </label>
+            </li>
+        }
+    };
+    html! {
+        <ul>
+            {for RateUnit::ALL.into_iter().map(choice)}
+        </ul>
+    }
+}
+
 #[derive(Properties, PartialEq)]
 struct FormatSettingsProps {
     current: NumberFormatSettings,
@@ -0,0 +1,124 @@
+//! Named profiles of user settings which can be switched between from the header.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use yew::{
+    classes, function_component, html, use_callback, use_state_eq, AttrValue, Callback, Html,
+};
+
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::ClickEdit;
+use crate::material::material_icon;
+use crate::user_settings::{use_settings_profiles, use_user_settings_dispatcher, UserSettings};
+
+/// Named copies of the user's settings, such as one with precise decimals for planning and one
+/// with rounded numbers for presentation.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsProfiles {
+    /// Name of the profile in use, if any. Changes to the settings are saved to this profile.
+    #[serde(default)]
+    pub active: Option<String>,
+    /// Settings saved in each profile, by name.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, UserSettings>,
+}
+
+impl SettingsProfiles {
+    /// Save the given settings to the active profile, if any. Returns true if the profile changed.
+    pub(in crate::user_settings) fn sync_active(&mut self, settings: &UserSettings) -> bool {
+        let Some(active) = &self.active else {
+            return false;
+        };
+        match self.profiles.get_mut(active) {
+            Some(profile) if profile != settings => {
+                *profile = settings.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Header button which shows the active settings profile and opens a menu to switch, save, or
+/// delete profiles.
+#[function_component]
+pub fn SettingsProfilePicker() -> Html {
+    let profiles = use_settings_profiles();
+    let settings_dispatcher = use_user_settings_dispatcher();
+    let open = use_state_eq(|| false);
+    let toggle = use_callback(open.clone(), |(), open| open.set(!**open));
+
+    let switch = |name: Option<String>| {
+        let settings_dispatcher = settings_dispatcher.clone();
+        let open = open.clone();
+        Callback::from(move |()| {
+            settings_dispatcher.switch_profile(name.clone());
+            open.set(false);
+        })
+    };
+    let delete = |name: String| {
+        let settings_dispatcher = settings_dispatcher.clone();
+        Callback::from(move |()| settings_dispatcher.delete_profile(name.clone()))
+    };
+    let save = use_callback(
+        settings_dispatcher.clone(),
+        |name: AttrValue, dispatcher| {
+            let name = name.trim();
+            if !name.is_empty() {
+                dispatcher.save_profile(name.to_owned());
+            }
+        },
+    );
+
+    let title = match &profiles.active {
+        Some(name) => format!("Settings Profile: {name}"),
+        None => "Settings Profiles".to_owned(),
+    };
+    let choice = |name: &String| {
+        let selected = profiles.active.as_ref() == Some(name);
+        html! {
+            <div class={classes!("profile", selected.then_some("selected"))}>
+                <Button class="profile-choice" title={format!("Switch to {name}")}
+                    onclick={switch(Some(name.clone()))}>
+                    {material_icon(if selected {
+                        "radio_button_checked"
+                    } else {
+                        "radio_button_unchecked"
+                    })}
+                    <span>{name.clone()}</span>
+                </Button>
+                <Button class="red" title={format!("Delete {name}")}
+                    onclick={delete(name.clone())}>
+                    {material_icon("delete")}
+                </Button>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="SettingsProfilePicker">
+            <Button {title} onclick={toggle}>
+                {material_icon("tune")}
+                if let Some(name) = &profiles.active {
+                    <span>{name.clone()}</span>
+                }
+            </Button>
+            if *open {
+                <div class="profiles">
+                    {for profiles.profiles.keys().map(choice)}
+                    if profiles.active.is_some() {
+                        <Button class="profile-choice"
+                            title="Stop saving changes to the current profile"
+                            onclick={switch(None)}>
+                            {material_icon("link_off")}
+                            <span>{"No Profile"}</span>
+                        </Button>
+                    }
+                    <ClickEdit value="" title="Save the current settings as a new profile"
+                        prefix={html! {<span class="label">{"Save as:"}</span>}}
+                        on_commit={save} />
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,58 @@
+//! Color themes for the app.
+use serde::{Deserialize, Serialize};
+use yew::{function_component, html, use_effect_with, Html};
+
+use crate::user_settings::use_user_settings;
+
+/// Color theme the app is shown with.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    /// The original light theme.
+    #[default]
+    Light,
+    /// Dark backgrounds with light text.
+    Dark,
+    /// Follow the light or dark preference of the operating system.
+    System,
+}
+
+impl Theme {
+    /// Every theme, in the order they are shown in settings.
+    pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::System];
+
+    /// Name of the theme to show in settings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+            Self::System => "Follow System",
+        }
+    }
+
+    /// Class applied to the document root which selects the theme's colors.
+    fn class(self) -> &'static str {
+        match self {
+            Self::Light => "theme-light",
+            Self::Dark => "theme-dark",
+            Self::System => "theme-system",
+        }
+    }
+}
+
+/// Applies the user's theme to the whole document. Modals and other elements outside of the app
+/// root pick up the theme too, since the theme class is set on the document root.
+#[function_component]
+pub fn ThemeApplier() -> Html {
+    let theme = use_user_settings().theme;
+    use_effect_with(theme, |&theme| {
+        let Some(root) = gloo::utils::document().document_element() else {
+            return;
+        };
+        let class_list = root.class_list();
+        for other in Theme::ALL {
+            let _ = class_list.remove_1(other.class());
+        }
+        let _ = class_list.add_1(theme.class());
+    });
+    html! {}
+}
@@ -1,14 +1,38 @@
 //! Provides the user settings window.
 
-use yew::{function_component, hook, html, use_callback, use_context, Html};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
 
-use crate::inputs::button::Button;
+use gloo::file::ObjectUrl;
+use log::warn;
+use satisfactory_accounting::database::ItemId;
+use web_sys::js_sys::Date;
+use yew::{
+    function_component, hook, html, use_callback, use_context, use_mut_ref, AttrValue, Callback,
+    Html,
+};
+
+use crate::bugreport::file_a_bug;
+use crate::download::download_text;
+use crate::inputs::button::{Button, UploadButton, UploadedFile};
+use crate::inputs::clickedit::ClickEdit;
 use crate::inputs::toggle::{MaterialCheckbox, MaterialRadio};
+use crate::material::material_icon;
+use crate::modal::{use_modal_dispatcher, ModalOk};
 use crate::node_display::{BackdriveSettingsSection, BalanceSortMode};
 use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
 use crate::overlay_window::OverlayWindow;
+use crate::user_settings::export::SettingsFile;
 use crate::user_settings::number_format::NumberDisplaySettingsSection;
-use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+use crate::user_settings::{
+    use_global_user_settings, use_user_settings, use_user_settings_dispatcher, IconPacksSection,
+    ItemAliasesSection, KeyBindingsSection, SettingsOverrides, Theme, UserSettings,
+};
+use crate::world::{
+    use_db, use_hidden_columns, use_item_metas, use_settings_overrides, use_world_dispatcher,
+    use_world_root, GridColumn, ItemMeta, UndoSettingsSection,
+};
 
 pub type UserSettingsWindowManager = WindowManager<UserSettingsWindow>;
 pub type UserSettingsWindowDispatcher = ShowWindowDispatcher<UserSettingsWindow>;
@@ -45,16 +69,79 @@ pub fn UserSettingsWindow() -> Html {
             settings_dispatcher.set_sort_mode(BalanceSortMode::IOItem);
         });
 
-    let persist = use_callback(settings_dispatcher, |(), settings_dispatcher| {
+    let toggle_text_only_icons =
+        use_callback(settings_dispatcher.clone(), |_, settings_dispatcher| {
+            settings_dispatcher.toggle_text_only_icons();
+        });
+
+    let toggle_track_build_progress =
+        use_callback(settings_dispatcher.clone(), |_, settings_dispatcher| {
+            settings_dispatcher.toggle_track_build_progress();
+        });
+
+    let toggle_show_efficiency =
+        use_callback(settings_dispatcher.clone(), |_, settings_dispatcher| {
+            settings_dispatcher.toggle_show_efficiency();
+        });
+
+    let toggle_only_unbalanced =
+        use_callback(settings_dispatcher.clone(), |_, settings_dispatcher| {
+            settings_dispatcher.toggle_only_unbalanced_groups();
+        });
+
+    let toggle_percent_contributions =
+        use_callback(settings_dispatcher.clone(), |_, settings_dispatcher| {
+            settings_dispatcher.toggle_percent_contributions();
+        });
+
+    let toggle_per_copy_balances =
+        use_callback(settings_dispatcher.clone(), |_, settings_dispatcher| {
+            settings_dispatcher.toggle_per_copy_balances();
+        });
+
+    let toggle_compact_layout =
+        use_callback(settings_dispatcher.clone(), |_, settings_dispatcher| {
+            settings_dispatcher.toggle_compact_layout();
+        });
+
+    let theme_choice = |theme: Theme| {
+        let settings_dispatcher = settings_dispatcher.clone();
+        let onclick = Callback::from(move |_| settings_dispatcher.set_theme(theme));
+        html! {
+            <li>
+                <label>
+                    <span>{theme.name()}</span>
+                    <MaterialRadio checked={user_settings.theme == theme} {onclick} />
+                </label>
+            </li>
+        }
+    };
+
+    let set_balanced_tolerance = use_callback(
+        settings_dispatcher.clone(),
+        |value: AttrValue, settings_dispatcher| {
+            if let Ok(tolerance) = value.parse::<f32>() {
+                settings_dispatcher.set_balanced_tolerance(tolerance.abs());
+            }
+        },
+    );
+
+    let persist = use_callback(settings_dispatcher.clone(), |(), settings_dispatcher| {
         settings_dispatcher.persist_local_storage();
     });
 
+    let overrides = use_settings_overrides();
+
     html! {
         <OverlayWindow title="Settings" class="UserSettingsWindow" on_close={close}>
+            <WorldSettingsSection />
             <div class="settings-section">
                 <h2>{"Balance Display"}</h2>
                 <div class="settings-subsection">
-                    <h3>{"Display of Neutral (0) Balances"}</h3>
+                    <h3>
+                        {"Display of Neutral (0) Balances"}
+                        {world_only_tag(overrides.hide_empty_balances.is_some())}
+                    </h3>
                     <p>{"Whether balance entries with a value of 0 should be shown. Hiding neutral \
                     balances lets you filter out fully-consumed intermediate products form higher \
                     level groups, but can make it harder to tell when a group actually has \
@@ -70,7 +157,10 @@ pub fn UserSettingsWindow() -> Html {
                     </ul>
                 </div>
                 <div class="settings-subsection">
-                    <h3>{"Balance Sort Order"}</h3>
+                    <h3>
+                        {"Balance Sort Order"}
+                        {world_only_tag(overrides.balance_sort_mode.is_some())}
+                    </h3>
                     <p>{"Whether balances should be sorted purely by the item or grouped into \
                     inputs and outputs, with the inputs and outputs then sorted by item"}</p>
                     <ul>
@@ -92,9 +182,141 @@ pub fn UserSettingsWindow() -> Html {
                         </li>
                     </ul>
                 </div>
+                <div class="settings-subsection">
+                    <h3>{"Percent Contributions"}</h3>
+                    <p>{"Show what percent of its parent group's production or consumption of \
+                    each item a node's balance entries make up, and what share of the whole \
+                    world's power draw each group is responsible for."}</p>
+                    <ul>
+                        <li>
+                            <label>
+                                <span>{"Show Percent Contributions"}</span>
+                                <MaterialCheckbox checked={user_settings.show_percent_contributions}
+                                    onclick={toggle_percent_contributions} />
+                            </label>
+                        </li>
+                    </ul>
+                </div>
+                <div class="settings-subsection">
+                    <h3>{"Per Copy Balances"}</h3>
+                    <p>{"Next to the total balance of a building with several copies, show what a \
+                    single copy at its clock speed makes and uses. This is handy for working out \
+                    how many more machines to place."}</p>
+                    <ul>
+                        <li>
+                            <label>
+                                <span>{"Show Per Copy Balances"}</span>
+                                <MaterialCheckbox checked={user_settings.show_per_copy_balances}
+                                    onclick={toggle_per_copy_balances} />
+                            </label>
+                        </li>
+                    </ul>
+                </div>
+            </div>
+            <div class="settings-section">
+                <h2>{"Layout"}</h2>
+                <p>{"Use smaller text and icons, tighter rows, and shortened names so more \
+                buildings fit on screen. Hover over a shortened name to see all of it."}</p>
+                <ul>
+                    <li>
+                        <label>
+                            <span>{"Compact layout"}</span>
+                            <MaterialCheckbox checked={user_settings.compact_layout}
+                                onclick={toggle_compact_layout} />
+                        </label>
+                    </li>
+                </ul>
+            </div>
+            <div class="settings-section">
+                <h2>{"Theme"}</h2>
+                <p>{"Choose the colors the app is shown with. The dark theme is easier on the \
+                eyes at night, and following the system switches between light and dark along \
+                with your operating system."}</p>
+                <ul>
+                    {for Theme::ALL.into_iter().map(theme_choice)}
+                </ul>
+            </div>
+            <ColumnsSection />
+            <IgnoredItemsSection />
+            <ItemAliasesSection />
+            <div class="settings-section">
+                <h2>{"Icons"}</h2>
+                <p>{"Show short text badges in place of item and building icons. This avoids \
+                loading any images, which helps on very slow connections, and gives screen readers \
+                the full name of each item."}</p>
+                <ul>
+                    <li>
+                        <label>
+                            <span>{"Text-only icons"}</span>
+                            <MaterialCheckbox checked={user_settings.text_only_icons}
+                                onclick={toggle_text_only_icons} />
+                        </label>
+                    </li>
+                </ul>
+            </div>
+            <IconPacksSection />
+            <div class="settings-section">
+                <h2>{"Balanced Groups"}</h2>
+                <p>{"Groups with no item or power deficits are marked as self-sufficient. Deficits \
+                up to the tolerance below still count as balanced, which helps ignore rounding \
+                errors. You can also hide self-sufficient groups to focus on the ones that still \
+                need work."}</p>
+                <ul>
+                    <li>
+                        <label>
+                            <ClickEdit
+                                class="balanced-tolerance"
+                                value={user_settings.balanced_tolerance.to_string()}
+                                on_commit={set_balanced_tolerance}
+                                prefix={html! {
+                                    <span class="prefix">{"Tolerance"}</span>
+                                }}
+                                title="Tolerance" />
+                        </label>
+                    </li>
+                    <li>
+                        <label>
+                            <span>{"Show only unbalanced groups"}</span>
+                            <MaterialCheckbox checked={user_settings.only_unbalanced_groups}
+                                onclick={toggle_only_unbalanced} />
+                        </label>
+                    </li>
+                </ul>
+            </div>
+            <div class="settings-section">
+                <h2>{"Build Tracking"}</h2>
+                <p>{"Show a checkbox on each building to mark whether it has actually been built \
+                in game or is only planned, and show how many machines in each group have been \
+                built. This lets you use the same world for both planning and tracking \
+                construction."}</p>
+                <ul>
+                    <li>
+                        <label>
+                            <span>{"Track build progress"}</span>
+                            <MaterialCheckbox checked={user_settings.track_build_progress}
+                                onclick={toggle_track_build_progress} />
+                        </label>
+                    </li>
+                </ul>
+            </div>
+            <div class="settings-section">
+                <h2>{"Efficiency"}</h2>
+                <p>{"Show how hard each group's machines are working. Throughput compares what                 the machines make to what the same number of whole machines would make at 100%                 clock speed, so underclocked machines and partial copies lower it. Input use is                 how much of what the group makes for its own use is actually consumed, which is                 low when miners or intermediate lines are over-built."}</p>
+                <ul>
+                    <li>
+                        <label>
+                            <span>{"Show group efficiency"}</span>
+                            <MaterialCheckbox checked={user_settings.show_efficiency}
+                                onclick={toggle_show_efficiency} />
+                        </label>
+                    </li>
+                </ul>
             </div>
             <BackdriveSettingsSection />
             <NumberDisplaySettingsSection />
+            <KeyBindingsSection />
+            <UndoSettingsSection />
+            <TransferSection />
             <div class="settings-section">
                 <h2>{"Storage Persistence"}</h2>
                 <p>{"Satisfactory Accounting stores your worlds and user settings in "}
@@ -120,3 +342,309 @@ pub fn UserSettingsWindow() -> Html {
         </OverlayWindow>
     }
 }
+
+/// Tag shown next to the heading of a setting which the current world keeps separately.
+pub fn world_only_tag(overridden: bool) -> Html {
+    if overridden {
+        html! { <span class="world-only">{"This World Only"}</span> }
+    } else {
+        html! {}
+    }
+}
+
+/// Lets the user choose which settings the current world keeps separately from their other
+/// worlds.
+#[function_component]
+fn WorldSettingsSection() -> Html {
+    let overrides = use_settings_overrides();
+    let global = use_global_user_settings();
+    let world_dispatcher = use_world_dispatcher();
+
+    // Each toggle starts the world's copy of the setting from the current global value.
+    let toggle = |update: fn(&mut SettingsOverrides, &UserSettings)| {
+        let overrides = overrides.clone();
+        let global = global.clone();
+        let world_dispatcher = world_dispatcher.clone();
+        Callback::from(move |_| {
+            let mut overrides = overrides.clone();
+            update(&mut overrides, &global);
+            world_dispatcher.set_settings_overrides(overrides);
+        })
+    };
+    let toggle_hide_empty = toggle(|overrides, global| {
+        overrides.hide_empty_balances = match overrides.hide_empty_balances {
+            Some(_) => None,
+            None => Some(global.hide_empty_balances),
+        };
+    });
+    let toggle_sort_mode = toggle(|overrides, global| {
+        overrides.balance_sort_mode = match overrides.balance_sort_mode {
+            Some(_) => None,
+            None => Some(global.balance_sort_mode),
+        };
+    });
+    let toggle_backdrive = toggle(|overrides, global| {
+        overrides.backdrive_settings = match overrides.backdrive_settings {
+            Some(_) => None,
+            None => Some(global.backdrive_settings.clone()),
+        };
+    });
+    let toggle_number_display = toggle(|overrides, global| {
+        overrides.number_display = match overrides.number_display {
+            Some(_) => None,
+            None => Some(global.number_display.clone()),
+        };
+    });
+
+    html! {
+        <div class="settings-section">
+            <h2>{"Settings for This World"}</h2>
+            <p>{"Choose settings which this world keeps separately from your other worlds. While a \
+            setting is kept separately, changing it below only affects this world. Turning it off \
+            goes back to the setting shared by your other worlds."}</p>
+            <ul>
+                <li>
+                    <label>
+                        <span>{"Display of neutral balances"}</span>
+                        <MaterialCheckbox checked={overrides.hide_empty_balances.is_some()}
+                            onclick={toggle_hide_empty} />
+                    </label>
+                </li>
+                <li>
+                    <label>
+                        <span>{"Balance sort order"}</span>
+                        <MaterialCheckbox checked={overrides.balance_sort_mode.is_some()}
+                            onclick={toggle_sort_mode} />
+                    </label>
+                </li>
+                <li>
+                    <label>
+                        <span>{"Backdriving"}</span>
+                        <MaterialCheckbox checked={overrides.backdrive_settings.is_some()}
+                            onclick={toggle_backdrive} />
+                    </label>
+                </li>
+                <li>
+                    <label>
+                        <span>{"Number display and rounding"}</span>
+                        <MaterialCheckbox checked={overrides.number_display.is_some()}
+                            onclick={toggle_number_display} />
+                    </label>
+                </li>
+            </ul>
+        </div>
+    }
+}
+
+/// Lets the user choose which columns of the node grid are shown in the current world.
+#[function_component]
+fn ColumnsSection() -> Html {
+    let hidden_columns = use_hidden_columns();
+    let world_dispatcher = use_world_dispatcher();
+
+    let column_toggle = |column: GridColumn| {
+        let hidden = hidden_columns.is_hidden(column);
+        let onclick = {
+            let hidden_columns = hidden_columns.clone();
+            let world_dispatcher = world_dispatcher.clone();
+            Callback::from(move |_| {
+                world_dispatcher.set_hidden_columns(hidden_columns.toggled(column));
+            })
+        };
+        html! {
+            <li>
+                <label>
+                    <span>{column.name()}</span>
+                    <MaterialCheckbox checked={!hidden} {onclick} />
+                </label>
+            </li>
+        }
+    };
+
+    html! {
+        <div class="settings-section">
+            <h2>
+                {"Columns"}
+                {world_only_tag(true)}
+            </h2>
+            <p>{"Choose which columns are shown for buildings and groups. Hiding columns you \
+            don't use leaves more room for the rest."}</p>
+            <ul>
+                {for GridColumn::ALL.into_iter().map(column_toggle)}
+            </ul>
+        </div>
+    }
+}
+
+/// Lets the user choose items which are left out of every balance in the current world.
+#[function_component]
+fn IgnoredItemsSection() -> Html {
+    let db = use_db();
+    let root = use_world_root();
+    let item_metas = use_item_metas();
+    let world_dispatcher = use_world_dispatcher();
+
+    // Offer every item in the world balance, plus any already ignored.
+    let items: BTreeSet<ItemId> = root
+        .balance()
+        .balances
+        .keys()
+        .copied()
+        .chain(
+            item_metas
+                .iter()
+                .filter(|(_, meta)| meta.ignored)
+                .map(|(id, _)| id),
+        )
+        .collect();
+    let mut items: Vec<_> = items
+        .into_iter()
+        .map(|id| {
+            let name: AttrValue = match db.get(id) {
+                Some(item) => item.name.clone().into(),
+                None => "Unknown Item".into(),
+            };
+            (id, name)
+        })
+        .collect();
+    let collator = crate::locale::get_collator();
+    items.sort_by(|(_, lhs), (_, rhs)| collator.compare(lhs, rhs));
+
+    let item_toggle = |(id, name): (ItemId, AttrValue)| {
+        let meta = item_metas.meta(id);
+        let ignored = meta.ignored;
+        let onclick = {
+            let world_dispatcher = world_dispatcher.clone();
+            Callback::from(move |_| {
+                world_dispatcher.update_item_meta(
+                    id,
+                    ItemMeta {
+                        ignored: !meta.ignored,
+                        ..meta.clone()
+                    },
+                );
+            })
+        };
+        html! {
+            <li>
+                <label>
+                    <span>{name}</span>
+                    <MaterialCheckbox checked={ignored} {onclick} />
+                </label>
+            </li>
+        }
+    };
+
+    html! {
+        <div class="settings-section">
+            <h2>
+                {"Ignored Items"}
+                {world_only_tag(true)}
+            </h2>
+            <p>{"Choose items to leave out of every group balance and the pinned summary, such \
+            as overflow that is sunk or alien remains you don't care to track. Ignored items are \
+            still counted in the world, they just aren't shown."}</p>
+            <ul class="ignored-items">
+                {for items.into_iter().map(item_toggle)}
+            </ul>
+        </div>
+    }
+}
+
+/// Lets the user download their settings as a file and import them again, such as in another
+/// browser or on another machine.
+#[function_component]
+fn TransferSection() -> Html {
+    let global = use_global_user_settings();
+    let settings_dispatcher = use_user_settings_dispatcher();
+    let modals = use_modal_dispatcher();
+    // Keeps the download url alive until the settings window is closed.
+    let download_url_retainer: Rc<RefCell<Option<ObjectUrl>>> = use_mut_ref(|| None);
+
+    let export = use_callback(
+        (global.clone(), modals.clone()),
+        move |(), (global, modals)| {
+            let json = match serde_json::to_string_pretty(&SettingsFile::new((**global).clone())) {
+                Ok(json) => json,
+                Err(e) => {
+                    warn!("Unable to serialize settings: {e}");
+                    modals
+                        .builder()
+                        .class("settings-export-error")
+                        .kind(ModalOk::close())
+                        .title("Settings could not be serialized")
+                        .content(html! {
+                            <>
+                                <p>{"Your settings couldn't be serialized to create the settings \
+                                file for some reason. This is probably a bug, and you can "}
+                                {file_a_bug()}{". If you file a bug, please include this error \
+                                message:"}</p>
+                                <pre>{e.to_string()}</pre>
+                            </>
+                        })
+                        .build()
+                        .persist();
+                    return;
+                }
+            };
+            let date = String::from(Date::new_0().to_iso_string());
+            let filename = format!(
+                "SatisfactoryAccounting-Settings-{}.json",
+                date.get(..10).unwrap_or(&date)
+            );
+            if let Some(url) = download_text(&json, "application/json", &filename) {
+                *download_url_retainer.borrow_mut() = Some(url);
+            }
+        },
+    );
+
+    let import = use_callback(
+        (global, settings_dispatcher, modals),
+        |file: UploadedFile, (global, settings_dispatcher, modals)| match SettingsFile::read(
+            &file.data,
+        ) {
+            Ok(settings) => settings_dispatcher.replace_all(settings.into_settings(global)),
+            Err(e) => {
+                warn!("Unable to import settings: {e}");
+                modals
+                    .builder()
+                    .class("settings-import-error")
+                    .kind(ModalOk::close())
+                    .title("Could not import Settings")
+                    .content(html! {
+                        <>
+                            <p>{"The file \""}{&file.name}{"\" does not appear to be a \
+                            valid settings file, so your settings were left unchanged. \
+                            Settings files are made with the Export Settings button. If you \
+                            believe this is incorrect you can "}{file_a_bug()}{". If you do \
+                            file a bug, please include this error message:"}</p>
+                            <pre>{e.to_string()}</pre>
+                        </>
+                    })
+                    .build()
+                    .persist();
+            }
+        },
+    );
+
+    html! {
+        <div class="settings-section">
+            <h2>{"Export and Import"}</h2>
+            <p>{"Download your settings as a file, or replace your settings with ones from such a \
+            file, to use the same rounding, sorting, and backdriving preferences in another \
+            browser or on another machine. Settings kept separately by a world are saved with \
+            that world instead."}</p>
+            <div class="settings-transfer">
+                <Button class="green" title="Download your Settings" onclick={export}>
+                    {material_icon("download")}
+                    <span>{"Export Settings"}</span>
+                </Button>
+                <UploadButton class="green" title="Import Settings from a File"
+                    onupload={import}>
+                    {material_icon("upload")}
+                    <span>{"Import Settings"}</span>
+                </UploadButton>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,204 @@
+//! Window collecting every problem found in the world in one place.
+
+use satisfactory_accounting::analysis::{
+    find_diagnostics, Contributor, Diagnostic, DiagnosticKind, NuclearWarning,
+};
+use satisfactory_accounting::database::{Database, ItemId};
+use yew::prelude::*;
+
+use crate::inputs::button::Button;
+use crate::material::material_icon;
+use crate::node_display::reveal_contributors;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::number_format::{BalanceDisplaySettings, UserConfiguredFormat};
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_item_metas, use_world_dispatcher, use_world_root, NodeMetas};
+
+pub type WarningsWindowManager = WindowManager<WarningsWindow>;
+pub type WarningsWindowDispatcher = ShowWindowDispatcher<WarningsWindow>;
+
+/// Gets access to the Warnings window dispatcher which controls showing the warnings window.
+#[hook]
+pub fn use_warnings_window() -> WarningsWindowDispatcher {
+    use_context::<WarningsWindowDispatcher>()
+        .expect("use_warnings_window can only be used from within a child of WarningsWindowManager")
+}
+
+/// Gets every problem in the current world.
+#[hook]
+fn use_diagnostics() -> Vec<Diagnostic> {
+    let root = use_world_root();
+    let db = use_db();
+    let item_metas = use_item_metas();
+    find_diagnostics(&root, &db, |item| item_metas.meta(item).exported)
+}
+
+/// Header button which opens the warnings window, showing how many problems there are.
+#[function_component]
+pub fn WarningsButton() -> Html {
+    let window_dispatcher = use_warnings_window();
+    let onclick = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.toggle_window()
+    });
+    let count = use_diagnostics().len();
+    let title = match count {
+        0 => "Warnings: none".to_owned(),
+        1 => "Warnings: 1 problem".to_owned(),
+        count => format!("Warnings: {count} problems"),
+    };
+    html! {
+        <Button class={classes!("WarningsButton", (count > 0).then_some("has-warnings"))}
+            {title} {onclick}>
+            {material_icon(if count > 0 { "warning" } else { "check_circle" })}
+            if count > 0 {
+                <span class="warning-count">{count}</span>
+            }
+        </Button>
+    }
+}
+
+/// Lists every problem in the current world.
+#[function_component]
+pub fn WarningsWindow() -> Html {
+    let window_dispatcher = use_warnings_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+    let diagnostics = use_diagnostics();
+
+    html! {
+        <OverlayWindow title="Warnings" class="WarningsWindow" on_close={close}>
+            if diagnostics.is_empty() {
+                <p class="no-warnings">{"No problems found in this world."}</p>
+            } else {
+                <ul>
+                    {for diagnostics.into_iter().map(|diagnostic| html! {
+                        <WarningRow {diagnostic} />
+                    })}
+                </ul>
+            }
+        </OverlayWindow>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct WarningRowProps {
+    /// The problem to display.
+    diagnostic: Diagnostic,
+}
+
+/// Displays a single problem, which reveals the node with the problem when clicked.
+#[function_component]
+fn WarningRow(WarningRowProps { diagnostic }: &WarningRowProps) -> Html {
+    let db = use_db();
+    let root_id = use_world_root().id();
+    let dispatcher = use_world_dispatcher();
+    let window_dispatcher = use_warnings_window();
+    let metas = use_context::<NodeMetas>()
+        .expect("WarningRow can only be used from within a child of WorldManager");
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+
+    let onclick = {
+        let contributor = Contributor {
+            id: diagnostic.id,
+            ancestors: diagnostic.ancestors.clone(),
+            rate: 0.0,
+        };
+        Callback::from(move |()| {
+            window_dispatcher.hide_window();
+            reveal_contributors(&dispatcher, &metas, root_id, vec![contributor.clone()]);
+        })
+    };
+
+    let path = diagnostic
+        .names
+        .iter()
+        .map(|name| {
+            if name.is_empty() {
+                "Unnamed Group"
+            } else {
+                name.as_str()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" \u{203A} ");
+    html! {
+        <li>
+            <Button class="WarningRow" title="Show in World" {onclick}>
+                {material_icon("warning")}
+                <span class="warning-message">
+                    {describe(diagnostic.kind, &db, balance_settings)}
+                </span>
+                <span class="warning-path">{path}</span>
+                {material_icon("my_location")}
+            </Button>
+        </li>
+    }
+}
+
+/// Describe a problem for display.
+fn describe(
+    kind: DiagnosticKind,
+    db: &Database,
+    balance_settings: &BalanceDisplaySettings,
+) -> String {
+    let name = |item: ItemId| match db.get(item) {
+        Some(item) => item.name.to_string(),
+        None => format!("Unknown Item {item}"),
+    };
+    let rate = |rate: f32| {
+        format!(
+            "{}{}",
+            balance_settings.format_rate(rate),
+            balance_settings.rate_unit.suffix()
+        )
+    };
+    match kind {
+        DiagnosticKind::BuildError(error) => error.to_string(),
+        DiagnosticKind::MissingRecipe => "No recipe is chosen.".to_owned(),
+        DiagnosticKind::MissingResource => "No resource is chosen.".to_owned(),
+        DiagnosticKind::MissingFuel => "No fuel is chosen.".to_owned(),
+        DiagnosticKind::LogisticsOverflow(overflow) => format!(
+            "{} moves {}, but its belts or pipes only carry {}.",
+            name(overflow.item),
+            rate(overflow.rate),
+            rate(overflow.capacity),
+        ),
+        DiagnosticKind::ExtractionOverflow(overflow) => format!(
+            "Each {} output makes {}, but its belt or pipe only carries {}.",
+            name(overflow.item),
+            rate(overflow.rate),
+            rate(overflow.capacity),
+        ),
+        DiagnosticKind::PowerDeficit { deficit } => format!(
+            "The world is short {} MW of power.",
+            deficit.format(&balance_settings.power_format_settings)
+        ),
+        DiagnosticKind::Nuclear(NuclearWarning::UnconsumedWaste {
+            item,
+            rate: surplus,
+        }) => {
+            format!("{} is left over at {}.", name(item), rate(surplus))
+        }
+        DiagnosticKind::Nuclear(NuclearWarning::RodsWithoutGenerators {
+            item,
+            rate: surplus,
+        }) => format!(
+            "{} is left over at {} with no generators burning it.",
+            name(item),
+            rate(surplus)
+        ),
+        DiagnosticKind::Nuclear(NuclearWarning::RodsPartlyBurned {
+            item,
+            burned,
+            surplus,
+        }) => format!(
+            "{} is burned at {}, but {} is left over.",
+            name(item),
+            rate(burned),
+            rate(surplus)
+        ),
+    }
+}
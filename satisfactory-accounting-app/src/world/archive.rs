@@ -0,0 +1,146 @@
+//! Archives containing every world along with its checkpoints and snapshots, and the user's
+//! templates, settings, and settings profiles, used to back up or move everything at once.
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::user_settings::{SettingsProfiles, UserSettings};
+use crate::world::checkpoints::Checkpoint;
+use crate::world::savefile::VersionedWorldModel;
+use crate::world::snapshots::Snapshot;
+use crate::world::{SaveFile, TemplateList, World, WorldId};
+
+/// Version of the archive format written by this version of the app.
+///
+/// Version 2 added the history of each world and the settings profiles. Both are optional, so
+/// version 1 archives can still be read.
+const ARCHIVE_VERSION: u32 = 2;
+
+/// Format used for downloadable archives of all worlds.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Archive {
+    /// Version of the archive format. This is required, which also keeps single-world save files
+    /// from being mistaken for archives.
+    archive_version: u32,
+    /// ID of the world which was selected when the archive was created.
+    #[serde(default)]
+    selected: Option<WorldId>,
+    /// Every world, each in the same format as a single-world save file.
+    worlds: Vec<SaveFile>,
+    /// Templates the user had saved.
+    #[serde(default)]
+    templates: TemplateList,
+    /// The user's settings, if they were included.
+    #[serde(default)]
+    user_settings: Option<UserSettings>,
+    /// Checkpoints and snapshots of the worlds which had any.
+    #[serde(default)]
+    histories: Vec<WorldHistory>,
+    /// The user's settings profiles, if they were included.
+    #[serde(default)]
+    settings_profiles: Option<SettingsProfiles>,
+}
+
+/// Checkpoints and snapshots of one world.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldHistory {
+    /// ID of the world the history belongs to.
+    pub id: WorldId,
+    /// Checkpoints of the world, oldest first.
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Snapshots of the world, oldest first.
+    #[serde(default)]
+    pub snapshots: VecDeque<Snapshot>,
+}
+
+impl WorldHistory {
+    /// Whether the world has no checkpoints or snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty() && self.snapshots.is_empty()
+    }
+}
+
+/// Errors which can occur when reading an archive.
+#[derive(Error, Debug)]
+pub enum ReadArchiveError {
+    #[error("Unable to parse archive: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Archive version {0} is newer than this app supports")]
+    UnsupportedVersion(u32),
+}
+
+/// Contents of an archive, ready to be restored.
+#[derive(Debug)]
+pub struct UnpackedArchive {
+    /// Worlds in the archive, with the ID each had, if any.
+    pub worlds: Vec<(Option<WorldId>, World)>,
+    /// Number of worlds which were skipped because their model version wasn't recognized.
+    pub skipped: usize,
+    /// ID of the world which was selected when the archive was created.
+    pub selected: Option<WorldId>,
+    /// Templates the user had saved.
+    pub templates: TemplateList,
+    /// The user's settings, if they were included.
+    pub user_settings: Option<UserSettings>,
+    /// Checkpoints and snapshots of the worlds which had any.
+    pub histories: Vec<WorldHistory>,
+    /// The user's settings profiles, if they were included.
+    pub settings_profiles: Option<SettingsProfiles>,
+}
+
+impl Archive {
+    /// Create an archive of the given worlds, their histories, templates, and settings.
+    pub fn new(
+        selected: WorldId,
+        worlds: Vec<SaveFile>,
+        histories: Vec<WorldHistory>,
+        templates: TemplateList,
+        user_settings: UserSettings,
+        settings_profiles: SettingsProfiles,
+    ) -> Self {
+        Self {
+            archive_version: ARCHIVE_VERSION,
+            selected: Some(selected),
+            worlds,
+            templates,
+            user_settings: Some(user_settings),
+            histories,
+            settings_profiles: Some(settings_profiles),
+        }
+    }
+
+    /// Parse an archive from the contents of an archive file.
+    pub fn read(data: &[u8]) -> Result<Self, ReadArchiveError> {
+        let archive: Self = serde_json::from_slice(data)?;
+        if archive.archive_version > ARCHIVE_VERSION {
+            return Err(ReadArchiveError::UnsupportedVersion(
+                archive.archive_version,
+            ));
+        }
+        Ok(archive)
+    }
+
+    /// Extract the contents of the archive, skipping worlds in unrecognized formats.
+    pub fn unpack(self) -> UnpackedArchive {
+        let mut worlds = Vec::with_capacity(self.worlds.len());
+        let mut skipped = 0;
+        for save_file in self.worlds {
+            let id = save_file.id();
+            match save_file.into_versioned_model() {
+                VersionedWorldModel::Version1Minor2(world) => worlds.push((id, world)),
+                VersionedWorldModel::Unknown { .. } => skipped += 1,
+            }
+        }
+        UnpackedArchive {
+            worlds,
+            skipped,
+            selected: self.selected,
+            templates: self.templates,
+            user_settings: self.user_settings,
+            histories: self.histories,
+            settings_profiles: self.settings_profiles,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Named checkpoints of worlds, created by the user and kept until deleted. Unlike snapshots,
+//! checkpoints are never replaced automatically, so they can be used to mark a known-good state
+//! before a large change.
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::AttrValue;
+
+use crate::world::{World, WorldId};
+
+/// A named copy of a world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Name the user gave the checkpoint.
+    pub name: String,
+    /// Timestamp in milliseconds of when the checkpoint was created. Also used to identify the
+    /// checkpoint.
+    pub taken: f64,
+    /// The world as of the checkpoint.
+    pub world: World,
+}
+
+/// Summary of a checkpoint for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointInfo {
+    /// Name the user gave the checkpoint.
+    pub name: AttrValue,
+    /// Timestamp in milliseconds of when the checkpoint was created.
+    pub taken: f64,
+}
+
+/// Get the storage key for the checkpoints of the given world.
+fn checkpoints_key(id: WorldId) -> String {
+    format!("{}.checkpoints", id.as_legacy_dotted())
+}
+
+/// Load the checkpoints of the given world, oldest first.
+pub fn load(id: WorldId) -> Result<Vec<Checkpoint>, StorageError> {
+    match LocalStorage::get(checkpoints_key(id)) {
+        Ok(checkpoints) => Ok(checkpoints),
+        Err(StorageError::KeyNotFound(_)) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Load the checkpoint of the given world taken at the given time, if it still exists.
+pub fn find(id: WorldId, taken: f64) -> Result<Option<Checkpoint>, StorageError> {
+    Ok(load(id)?
+        .into_iter()
+        .find(|checkpoint| checkpoint.taken == taken))
+}
+
+/// List the checkpoints of the given world, newest first.
+pub fn list(id: WorldId) -> Result<Vec<CheckpointInfo>, StorageError> {
+    Ok(load(id)?
+        .into_iter()
+        .rev()
+        .map(|checkpoint| CheckpointInfo {
+            name: checkpoint.name.into(),
+            taken: checkpoint.taken,
+        })
+        .collect())
+}
+
+/// Add a named checkpoint of the world.
+pub fn create(id: WorldId, name: String, world: World, now: f64) -> Result<(), StorageError> {
+    let mut checkpoints = load(id)?;
+    checkpoints.push(Checkpoint {
+        name,
+        taken: now,
+        world,
+    });
+    LocalStorage::set(checkpoints_key(id), &checkpoints)
+}
+
+/// Remove the checkpoint of the given world taken at the given time.
+pub fn remove(id: WorldId, taken: f64) -> Result<(), StorageError> {
+    let mut checkpoints = load(id)?;
+    checkpoints.retain(|checkpoint| checkpoint.taken != taken);
+    if checkpoints.is_empty() {
+        delete(id);
+        Ok(())
+    } else {
+        LocalStorage::set(checkpoints_key(id), &checkpoints)
+    }
+}
+
+/// Replace all checkpoints of the given world, such as when restoring a backup.
+pub fn replace(id: WorldId, checkpoints: &[Checkpoint]) -> Result<(), StorageError> {
+    if checkpoints.is_empty() {
+        delete(id);
+        Ok(())
+    } else {
+        LocalStorage::set(checkpoints_key(id), checkpoints)
+    }
+}
+
+/// Delete all checkpoints of the given world.
+pub fn delete(id: WorldId) {
+    LocalStorage::delete(checkpoints_key(id));
+}
@@ -0,0 +1,270 @@
+//! Storage of a world split into chunks, so that saving only rewrites the parts that changed.
+//!
+//! Every group of the world other than the root is stored under its own key, with each of its
+//! subgroups replaced by an empty placeholder that keeps its id. The world's own key holds the
+//! rest of the world, with the top-level groups replaced the same way. Node and item metadata
+//! are stored under their own keys too, so changing one doesn't rewrite the other or the tree.
+//!
+//! Worlds saved before chunking are stored whole, and worlds saved when only top-level groups
+//! were chunked keep whole subtrees in each chunk. Both still load as-is.
+use std::collections::{HashMap, HashSet};
+
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use log::warn;
+use satisfactory_accounting::accounting::{Group, Node};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::world::{ItemMetas, NodeMetas, World};
+
+/// Stored form of a world.
+#[derive(Serialize, Deserialize)]
+struct StoredWorld {
+    /// The world, with chunked groups replaced by placeholders.
+    #[serde(flatten)]
+    world: World,
+    /// IDs of the groups which are stored in their own chunks, in the order they are reassembled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    chunks: Vec<Uuid>,
+    /// Whether node and item metadata are stored under their own keys rather than in the world.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    separate_metadata: bool,
+}
+
+/// Just the list of chunks of a stored world, which can be read without building the rest.
+#[derive(Deserialize)]
+struct StoredChunkList {
+    #[serde(default)]
+    chunks: Vec<Uuid>,
+}
+
+/// The parts of a world as they were last saved, used to skip rewriting chunks which haven't
+/// changed.
+#[derive(Default)]
+pub(super) struct SavedChunks {
+    /// Chunked groups by id.
+    groups: HashMap<Uuid, Node>,
+    /// Node metadata, if it has been saved under its own key.
+    node_metadata: Option<NodeMetas>,
+    /// Item metadata, if it has been saved under its own key.
+    item_metadata: Option<ItemMetas>,
+}
+
+impl SavedChunks {
+    /// Record the groups of the world which are already stored in their own chunks under the
+    /// given key as saved. Metadata is left unrecorded, so it is written again on the next save.
+    pub fn mark_saved(&mut self, world_key: &str, world: &World) {
+        let stored: HashSet<Uuid> = match LocalStorage::get::<StoredChunkList>(world_key) {
+            Ok(list) => list.chunks.into_iter().collect(),
+            Err(e) => {
+                warn!("Unable to read the chunks of {world_key}: {e}");
+                HashSet::new()
+            }
+        };
+        let mut groups = Vec::new();
+        chunk_groups(&world.root, &mut HashSet::new(), &mut groups);
+        self.groups = groups
+            .into_iter()
+            .filter(|(id, _)| stored.contains(id))
+            .map(|(id, node)| (id, node.clone()))
+            .collect();
+        self.node_metadata = None;
+        self.item_metadata = None;
+    }
+
+    /// Whether the given group was saved and hasn't changed since.
+    fn is_saved(&self, id: Uuid, node: &Node) -> bool {
+        self.groups.get(&id).is_some_and(|prior| prior.ptr_eq(node))
+    }
+}
+
+/// Get the storage key for a chunk of the world stored under `world_key`.
+fn chunk_key(world_key: &str, id: Uuid) -> String {
+    format!("{world_key}.group.{}", id.as_simple())
+}
+
+/// Get the storage key for the node metadata of the world stored under `world_key`.
+fn node_metadata_key(world_key: &str) -> String {
+    format!("{world_key}.nodemeta")
+}
+
+/// Get the storage key for the item metadata of the world stored under `world_key`.
+fn item_metadata_key(world_key: &str) -> String {
+    format!("{world_key}.itemmeta")
+}
+
+/// Collect the groups under `node` which are stored as chunks, in the order they are saved and
+/// reassembled. If two groups somehow share an id, only the first is chunked and the other stays
+/// inline along with everything inside it.
+fn chunk_groups<'a>(node: &'a Node, seen: &mut HashSet<Uuid>, out: &mut Vec<(Uuid, &'a Node)>) {
+    let Some(group) = node.group() else {
+        return;
+    };
+    for child in &group.children {
+        if let Some(child_group) = child.group() {
+            if seen.insert(child_group.id) {
+                out.push((child_group.id, child));
+                chunk_groups(child, seen, out);
+            }
+        }
+    }
+}
+
+/// Placeholder stored in place of a chunked group.
+fn placeholder(id: Uuid) -> Node {
+    Group {
+        id,
+        ..Group::empty()
+    }
+    .into()
+}
+
+/// Load the world stored under the given key, reassembling its chunks.
+pub(super) fn load(world_key: &str) -> Result<World, StorageError> {
+    let StoredWorld {
+        mut world,
+        chunks,
+        separate_metadata,
+    } = LocalStorage::get(world_key)?;
+    if separate_metadata {
+        world.node_metadata = LocalStorage::get(node_metadata_key(world_key))?;
+        world.item_metadata = LocalStorage::get(item_metadata_key(world_key))?;
+    }
+    if chunks.is_empty() {
+        return Ok(world);
+    }
+    let mut loaded = HashMap::with_capacity(chunks.len());
+    for id in chunks {
+        let node: Node = LocalStorage::get(chunk_key(world_key, id))?;
+        loaded.insert(id, node);
+    }
+    if world.root.group().is_none() {
+        warn!("Stored world {world_key} has chunks but its root is not a group");
+        return Ok(world);
+    }
+    world.root = reassemble(&world.root, &mut loaded);
+    Ok(world)
+}
+
+/// Replace the placeholders among the children of `node` with their chunks, and the placeholders
+/// within those chunks in turn. Each chunk is used once, in the same order it was saved.
+fn reassemble(node: &Node, loaded: &mut HashMap<Uuid, Node>) -> Node {
+    let Some(group) = node.group() else {
+        return node.clone();
+    };
+    Group {
+        children: group
+            .children
+            .iter()
+            .map(
+                |child| match child.group().and_then(|group| loaded.remove(&group.id)) {
+                    Some(chunk) => reassemble(&chunk, loaded),
+                    None => child.clone(),
+                },
+            )
+            .collect(),
+        ..group.clone()
+    }
+    .into()
+}
+
+/// Save the world under the given key, only writing chunks and metadata which changed since they
+/// were last saved.
+///
+/// Chunks are written before the groups containing them, the world is written last, and stale
+/// chunks are only removed after, so the stored world stays loadable if saving fails part way
+/// through.
+pub(super) fn save(
+    world_key: &str,
+    world: &World,
+    saved: &mut SavedChunks,
+) -> Result<(), StorageError> {
+    let mut chunks = Vec::new();
+    let mut current = HashMap::new();
+    let root = match world.root.group() {
+        Some(root) => save_children(world_key, root, saved, &mut chunks, &mut current)?.into(),
+        None => world.root.clone(),
+    };
+
+    if !saved
+        .node_metadata
+        .as_ref()
+        .is_some_and(|prior| prior.ptr_eq(&world.node_metadata))
+    {
+        LocalStorage::set(node_metadata_key(world_key), &world.node_metadata)?;
+    }
+    if !saved
+        .item_metadata
+        .as_ref()
+        .is_some_and(|prior| prior.ptr_eq(&world.item_metadata))
+    {
+        LocalStorage::set(item_metadata_key(world_key), &world.item_metadata)?;
+    }
+
+    let stored = StoredWorld {
+        world: World {
+            root,
+            node_metadata: NodeMetas::default(),
+            item_metadata: ItemMetas::default(),
+            ..world.clone()
+        },
+        chunks,
+        separate_metadata: true,
+    };
+    LocalStorage::set(world_key, &stored)?;
+
+    for id in saved.groups.keys().filter(|id| !current.contains_key(id)) {
+        LocalStorage::delete(chunk_key(world_key, *id));
+    }
+    saved.groups = current;
+    saved.node_metadata = Some(world.node_metadata.clone());
+    saved.item_metadata = Some(world.item_metadata.clone());
+    Ok(())
+}
+
+/// Write the chunks of every changed group under `group`, and get `group` as it is stored, with
+/// its chunked children replaced by placeholders. Chunked groups are added to `chunks` and
+/// `current` in the order they are reassembled.
+fn save_children(
+    world_key: &str,
+    group: &Group,
+    saved: &SavedChunks,
+    chunks: &mut Vec<Uuid>,
+    current: &mut HashMap<Uuid, Node>,
+) -> Result<Group, StorageError> {
+    let mut children = Vec::with_capacity(group.children.len());
+    for child in &group.children {
+        let Some(child_group) = child.group().filter(|g| !current.contains_key(&g.id)) else {
+            children.push(child.clone());
+            continue;
+        };
+        let id = child_group.id;
+        chunks.push(id);
+        current.insert(id, child.clone());
+        let stored = save_children(world_key, child_group, saved, chunks, current)?;
+        if !saved.is_saved(id, child) {
+            LocalStorage::set(chunk_key(world_key, id), Node::from(stored))?;
+        }
+        children.push(placeholder(id));
+    }
+    Ok(Group {
+        children,
+        ..group.clone()
+    })
+}
+
+/// Delete the world stored under the given key along with all of its chunks and metadata.
+pub(super) fn delete(world_key: &str) {
+    match LocalStorage::get::<StoredChunkList>(world_key) {
+        Ok(stored) => {
+            for id in stored.chunks {
+                LocalStorage::delete(chunk_key(world_key, id));
+            }
+        }
+        Err(e) => warn!("Unable to read chunks of {world_key} to delete them: {e}"),
+    }
+    LocalStorage::delete(node_metadata_key(world_key));
+    LocalStorage::delete(item_metadata_key(world_key));
+    LocalStorage::delete(world_key);
+}
@@ -0,0 +1,50 @@
+//! Clipboard slot shared by all worlds, so nodes copied in one world can be pasted into another.
+use std::collections::HashMap;
+
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use log::warn;
+use satisfactory_accounting::accounting::Node;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use yew::AttrValue;
+
+use crate::world::{DatabaseVersionSelector, NodeMeta};
+
+/// Local storage key where the clipboard is stored.
+pub(super) const CLIPBOARD_KEY: &str = "zstewart.satisfactorydb.state.clipboard";
+
+/// A node which was copied to the clipboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardContent {
+    /// Database the node was copied from, or None if it used a custom database.
+    #[serde(default)]
+    pub database: Option<DatabaseVersionSelector>,
+    /// The copied node.
+    pub node: Node,
+    /// Metadata of the groups in the copied node, by group ID.
+    #[serde(default)]
+    pub node_metadata: HashMap<Uuid, NodeMeta>,
+    /// Name of the world the node was copied from.
+    #[serde(default)]
+    pub source_world: AttrValue,
+}
+
+impl ClipboardContent {
+    /// Load the clipboard, or None if nothing has been copied.
+    pub fn load() -> Option<Self> {
+        match LocalStorage::get(CLIPBOARD_KEY) {
+            Ok(content) => Some(content),
+            Err(StorageError::KeyNotFound(_)) => None,
+            Err(e) => {
+                warn!("Failed to load the clipboard: {e}");
+                None
+            }
+        }
+    }
+
+    /// Save this as the clipboard content.
+    pub fn save(&self) -> Result<(), StorageError> {
+        LocalStorage::set(CLIPBOARD_KEY, self)
+    }
+}
@@ -1,6 +1,7 @@
+use satisfactory_accounting::accounting::Group;
 use satisfactory_accounting::database::{Database, DatabaseVersion};
+pub use satisfactory_accounting::savefile::DatabaseChoice;
 use serde::{Deserialize, Serialize};
-use yew::html::ImplicitClone;
 
 /// Type for selecting a database version. This allows both pinned versions and special versions
 /// like "Latest".
@@ -35,6 +36,15 @@ impl DatabaseVersionSelector {
         }
     }
 
+    /// Get the corresponding version selector if the choice is a standard database.
+    pub fn for_choice(choice: &DatabaseChoice) -> Option<Self> {
+        match *choice {
+            DatabaseChoice::Latest => Some(Self::Latest),
+            DatabaseChoice::Standard(version) => Some(Self::Pinned(version)),
+            DatabaseChoice::Custom(_) => None,
+        }
+    }
+
     /// Get the name of this selector.
     pub fn name(self) -> &'static str {
         match self {
@@ -55,56 +65,41 @@ impl DatabaseVersionSelector {
     }
 }
 
-/// The choice of database for a particular world.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum DatabaseChoice {
-    /// Always use the latest database version.
-    Latest,
-    /// Use one of the standard databases.
-    Standard(DatabaseVersion),
-    /// This world uses a custom database.
-    Custom(Database),
-}
-
-impl DatabaseChoice {
-    /// Get the database for this database choice.
-    pub(super) fn get(&self) -> Database {
-        match *self {
-            DatabaseChoice::Latest => Database::load_latest(),
-            DatabaseChoice::Standard(version) => version.load_database(),
-            DatabaseChoice::Custom(ref db) => db.clone(),
+impl From<DatabaseVersionSelector> for DatabaseChoice {
+    fn from(value: DatabaseVersionSelector) -> Self {
+        match value {
+            DatabaseVersionSelector::Latest => DatabaseChoice::Latest,
+            DatabaseVersionSelector::Pinned(version) => DatabaseChoice::Standard(version),
         }
     }
+}
 
-    /// Get the corresponding version selector if this is a standard database.
-    pub fn version_selector(&self) -> Option<DatabaseVersionSelector> {
-        match *self {
-            DatabaseChoice::Latest => Some(DatabaseVersionSelector::Latest),
-            DatabaseChoice::Standard(version) => Some(DatabaseVersionSelector::Pinned(version)),
-            DatabaseChoice::Custom(_) => None,
-        }
-    }
+/// Loads databases for display. Versions which aren't built in are fetched when a world uses
+/// them, and until they arrive the latest database is used as a stand-in. The world is rebuilt
+/// with the real database once it has been fetched.
+pub trait LoadDatabase {
+    /// Load the database, or the latest database if it hasn't been fetched yet.
+    fn load_database(&self) -> Database;
 }
 
-impl Default for DatabaseChoice {
-    fn default() -> Self {
-        DatabaseChoice::Latest
+impl LoadDatabase for DatabaseVersion {
+    fn load_database(&self) -> Database {
+        self.try_load_database()
+            .unwrap_or_else(Database::load_latest)
     }
 }
 
-impl ImplicitClone for DatabaseChoice {}
-
-impl From<DatabaseVersion> for DatabaseChoice {
-    fn from(value: DatabaseVersion) -> Self {
-        Self::Standard(value)
+impl LoadDatabase for DatabaseChoice {
+    fn load_database(&self) -> Database {
+        self.try_load_database()
+            .unwrap_or_else(Database::load_latest)
     }
 }
 
-impl From<DatabaseVersionSelector> for DatabaseChoice {
-    fn from(value: DatabaseVersionSelector) -> Self {
-        match value {
-            DatabaseVersionSelector::Latest => DatabaseChoice::Latest,
-            DatabaseVersionSelector::Pinned(version) => DatabaseChoice::Standard(version),
-        }
-    }
+/// Get the database to show the children of a group with, using the latest database as a
+/// stand-in if the group pins a version which hasn't been fetched yet.
+pub fn group_database(group: &Group, inherited: &Database) -> Database {
+    group
+        .resolve_database(inherited)
+        .unwrap_or_else(|_| Database::load_latest())
 }
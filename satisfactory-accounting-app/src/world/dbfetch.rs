@@ -0,0 +1,31 @@
+//! Fetching of database versions which aren't built into the app.
+use gloo::net::http::Request;
+use satisfactory_accounting::database::DatabaseVersion;
+use thiserror::Error;
+
+/// Path, relative to the app, where database versions which aren't built in are served from.
+const DATABASE_PATH: &str = "databases";
+
+/// Errors which can occur when fetching a database version.
+#[derive(Error, Debug)]
+pub enum FetchDatabaseError {
+    #[error("Unable to download the database: {0}")]
+    Network(#[from] gloo::net::Error),
+    #[error("The server responded with status {0}")]
+    Status(u16),
+    #[error("The downloaded database could not be read: {0}")]
+    Parse(String),
+}
+
+/// Fetch the given database version and store it so that it can be loaded.
+pub async fn fetch_database(version: DatabaseVersion) -> Result<(), FetchDatabaseError> {
+    let url = format!("{DATABASE_PATH}/{}", version.file_name());
+    let response = Request::get(&url).send().await?;
+    if !response.ok() {
+        return Err(FetchDatabaseError::Status(response.status()));
+    }
+    let bytes = response.binary().await?;
+    version
+        .store_fetched(&bytes)
+        .map_err(|e| FetchDatabaseError::Parse(e.to_string()))
+}
@@ -5,7 +5,9 @@ use crate::inputs::toggle::{MaterialCheckbox, MaterialRadio};
 use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
 use crate::overlay_window::OverlayWindow;
 use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
-use crate::world::{use_db_controller, DatabaseVersionSelector};
+use crate::world::{
+    use_db_controller, use_progression, use_world_dispatcher, DatabaseVersionSelector, Progression,
+};
 
 pub type DbChooserWindowManager = WindowManager<DbChooserWindow>;
 pub type DbChooserWindowDispatcher = ShowWindowDispatcher<DbChooserWindow>;
@@ -18,6 +20,9 @@ pub fn use_db_chooser_window() -> DbChooserWindowDispatcher {
     )
 }
 
+/// Highest milestone tier in the game.
+const MAX_TIER: u32 = 9;
+
 /// Shows the database chooser window.
 #[function_component]
 pub fn DbChooserWindow() -> Html {
@@ -69,6 +74,16 @@ pub fn DbChooserWindow() -> Html {
                 <DbListRow version={DatabaseVersionSelector::Latest} />
                 {for databases}
             </div>
+            <div class="progression-overview">
+                <h2>{"Progression"}</h2>
+                <p>{"Choose the highest milestone tier you have unlocked in this world to hide \
+                buildings and recipes you don't have access to yet from the building and recipe \
+                choosers. Existing buildings are not changed."}</p>
+            </div>
+            <div class="progression">
+                <ProgressionRow max_tier={None} />
+                {for (0..=MAX_TIER).map(|tier| html! { <ProgressionRow max_tier={tier} /> })}
+            </div>
         </OverlayWindow>
     }
 }
@@ -101,3 +116,34 @@ fn DbListRow(&DbListRowProps { version }: &DbListRowProps) -> Html {
         </label>
     }
 }
+
+#[derive(Properties, PartialEq)]
+struct ProgressionRowProps {
+    /// Highest tier to show content for, or None to show everything.
+    max_tier: Option<u32>,
+}
+
+/// Shows a single progression option in the DbChooserWindow.
+#[function_component]
+fn ProgressionRow(&ProgressionRowProps { max_tier }: &ProgressionRowProps) -> Html {
+    let progression = use_progression();
+    let dispatcher = use_world_dispatcher();
+
+    let checked = progression.max_tier == max_tier;
+    let onclick = use_callback((max_tier, dispatcher), |_, (max_tier, dispatcher)| {
+        dispatcher.set_progression(Progression {
+            max_tier: *max_tier,
+        });
+    });
+
+    let name = match max_tier {
+        Some(tier) => format!("Tier {tier}"),
+        None => "Everything".to_owned(),
+    };
+    html! {
+        <label class="ProgressionRow">
+            <span>{name}</span>
+            <MaterialRadio name="progression-choice" {checked} {onclick}/>
+        </label>
+    }
+}
@@ -41,6 +41,14 @@ impl WorldId {
     pub fn as_base64(&self) -> AsBase64 {
         AsBase64 { id: self }
     }
+
+    /// Get the ID of the world which owns the given local storage key. This covers the world
+    /// itself as well as its chunks and snapshots, which are stored under keys starting with the
+    /// world's legacy dotted id.
+    pub fn owner_of_storage_key(key: &str) -> Option<Self> {
+        let len = LEGACY_DOTTED_PREFIX.len() + Unprefixed::LENGTH;
+        key.get(..len)?.parse().ok()
+    }
 }
 
 /// Error from parsing a [`WorldId`].
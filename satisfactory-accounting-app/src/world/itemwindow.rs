@@ -0,0 +1,257 @@
+use std::collections::BTreeSet;
+
+use satisfactory_accounting::database::ItemId;
+use yew::{function_component, hook, html, use_callback, use_context, AttrValue, Html, Properties};
+
+use crate::duration::format_minutes;
+use crate::inputs::button::Button;
+use crate::inputs::clickedit::ClickEdit;
+use crate::inputs::toggle::MaterialCheckbox;
+use crate::item_usage::use_item_usage_window;
+use crate::material::material_icon;
+use crate::node_display::Icon;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::use_user_settings;
+use crate::world::{use_db, use_item_metas, use_world_dispatcher, use_world_root, ItemMeta};
+
+pub type ItemNotesWindowManager = WindowManager<ItemNotesWindow>;
+pub type ItemNotesWindowDispatcher = ShowWindowDispatcher<ItemNotesWindow>;
+
+/// Gets access to the ItemNotes window dispatcher which controls showing the item notes window.
+#[hook]
+pub fn use_item_notes_window() -> ItemNotesWindowDispatcher {
+    use_context::<ItemNotesWindowDispatcher>().expect(
+        "use_item_notes_window can only be used from within a child of ItemNotesWindowManager",
+    )
+}
+
+/// Shows notes and stockpile targets for items in the current world.
+#[function_component]
+pub fn ItemNotesWindow() -> Html {
+    let window_dispatcher = use_item_notes_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let db = use_db();
+    let root = use_world_root();
+    let item_metas = use_item_metas();
+
+    // Show every item that is either in the world balance or already has notes attached.
+    let items: BTreeSet<ItemId> = root
+        .balance()
+        .balances
+        .keys()
+        .copied()
+        .chain(item_metas.iter().map(|(id, _)| id))
+        .collect();
+    let mut items: Vec<_> = items
+        .into_iter()
+        .map(|id| {
+            let name: AttrValue = match db.get(id) {
+                Some(item) => item.name.clone().into(),
+                None => "Unknown Item".into(),
+            };
+            (id, name)
+        })
+        .collect();
+    let collator = crate::locale::get_collator();
+    items.sort_by(|(_, lhs), (_, rhs)| collator.compare(lhs, rhs));
+
+    // Estimate how long until every stockpile target is reached at the current world rates.
+    let estimate = root.balance().time_to_accumulate(
+        item_metas
+            .iter()
+            .filter_map(|(id, meta)| Some((id, meta.target_stock?))),
+    );
+    let all_targets = match estimate.minutes {
+        _ if estimate.per_item.is_empty() => None,
+        Some(minutes) => Some(format!(
+            "At current rates, all stockpile targets will be reached in {}.",
+            format_minutes(minutes)
+        )),
+        None => Some(
+            "Some stockpile targets will never be reached because their items are not being \
+            produced."
+                .to_owned(),
+        ),
+    };
+
+    let rows = items.into_iter().map(|(id, name)| {
+        let rate = root.balance().balances.get(&id).copied().unwrap_or(0.0);
+        let time_to_target = estimate.per_item.get(&id).copied();
+        html! {
+            <ItemNotesRow key={id.to_string()} {id} {name} {rate} {time_to_target}
+                meta={item_metas.meta(id)} />
+        }
+    });
+
+    html! {
+        <OverlayWindow title="Item Notes" class="ItemNotesWindow" on_close={close}>
+            <div class="overview">
+                <p>{"Attach notes and stockpile targets to items in this world. Items with notes \
+                or targets show them when hovering over the item in any balance. Mark items as \
+                exported if their surplus is intentionally sunk or shipped elsewhere, so groups \
+                producing them aren't reported as unused."}</p>
+                if let Some(all_targets) = all_targets {
+                    <p class="all-targets">{all_targets}</p>
+                }
+            </div>
+            <div class="item-rows">
+                <div class="item-header-row">
+                    <span class="item-name">{"Item"}</span>
+                    <span class="item-rate">{"World Rate"}</span>
+                    <span class="item-target">{"Target Stock"}</span>
+                    <span class="item-time">{"Time to Target"}</span>
+                    <span class="item-exported">{"Exported"}</span>
+                    <span class="item-notes">{"Notes"}</span>
+                </div>
+                {for rows}
+            </div>
+        </OverlayWindow>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct ItemNotesRowProps {
+    /// ID of the item.
+    id: ItemId,
+    /// Display name of the item.
+    name: AttrValue,
+    /// Net rate of the item across the whole world.
+    rate: f32,
+    /// Estimated time to reach the target stock, if the item has a target. The inner value is None
+    /// if the target will never be reached.
+    time_to_target: Option<Option<f32>>,
+    /// Current notes for this item.
+    meta: ItemMeta,
+}
+
+/// Shows a single row in the ItemNotesWindow.
+#[function_component]
+fn ItemNotesRow(
+    &ItemNotesRowProps {
+        id,
+        ref name,
+        rate,
+        time_to_target,
+        ref meta,
+    }: &ItemNotesRowProps,
+) -> Html {
+    let db = use_db();
+    let dispatcher = use_world_dispatcher();
+    let user_settings = use_user_settings();
+    let format = &user_settings.number_display.balance.item_format_settings;
+    let balance_settings = &user_settings.number_display.balance;
+
+    let set_target = use_callback(
+        (id, meta.clone(), dispatcher.clone()),
+        |edit_text: AttrValue, (id, meta, dispatcher)| {
+            let target_stock = if edit_text.trim().is_empty() {
+                None
+            } else {
+                match edit_text.trim().parse::<f32>() {
+                    Ok(value) if value >= 0.0 => Some(value),
+                    _ => return,
+                }
+            };
+            dispatcher.update_item_meta(
+                *id,
+                ItemMeta {
+                    target_stock,
+                    ..meta.clone()
+                },
+            );
+        },
+    );
+    let set_notes = use_callback(
+        (id, meta.clone(), dispatcher.clone()),
+        |notes: AttrValue, (id, meta, dispatcher)| {
+            dispatcher.update_item_meta(
+                *id,
+                ItemMeta {
+                    notes: notes.trim().to_owned().into(),
+                    ..meta.clone()
+                },
+            );
+        },
+    );
+    let toggle_exported = use_callback(
+        (id, meta.clone(), dispatcher.clone()),
+        |_, (id, meta, dispatcher)| {
+            dispatcher.update_item_meta(
+                *id,
+                ItemMeta {
+                    exported: !meta.exported,
+                    ..meta.clone()
+                },
+            );
+        },
+    );
+    let clear = use_callback((id, dispatcher), |(), (id, dispatcher)| {
+        dispatcher.update_item_meta(*id, ItemMeta::default());
+    });
+    let item_usage_window = use_item_usage_window();
+    let item_notes_window = use_item_notes_window();
+    let show_usage = use_callback(
+        (id, item_usage_window, item_notes_window),
+        |(), (id, item_usage_window, item_notes_window)| {
+            item_notes_window.hide_window();
+            item_usage_window.show_item(*id);
+        },
+    );
+
+    let icon: Option<AttrValue> = db.get(id).map(|item| item.image.clone().into());
+    let target: AttrValue = meta
+        .target_stock
+        .map(|target| target.to_string())
+        .unwrap_or_default()
+        .into();
+    let rounded_target: AttrValue = match meta.target_stock {
+        Some(target) => target.format(format).to_string().into(),
+        None => "\u{2013}".into(),
+    };
+
+    let time_to_target = match time_to_target {
+        Some(Some(minutes)) => format_minutes(minutes),
+        Some(None) => "Never".to_owned(),
+        None => "\u{2013}".to_owned(),
+    };
+
+    html! {
+        <div class="ItemNotesRow">
+            <span class="item-name">
+                <Icon {icon} name={name.clone()} />
+                <span>{name}</span>
+            </span>
+            <span class="item-rate">{balance_settings.format_rate(rate).to_string()}</span>
+            <ClickEdit class="item-target" title="Target Stock" value={target}
+                rounded_value={rounded_target} on_commit={set_target} />
+            <span class="item-time">{time_to_target}</span>
+            <label class="item-exported" title="Surplus of this item is intentionally exported or sunk">
+                <MaterialCheckbox checked={meta.exported} onclick={toggle_exported} />
+            </label>
+            <ClickEdit class="item-notes" title="Notes" value={meta.notes.clone()}
+                on_commit={set_notes} />
+            <Button class="item-usage" title="Where is this item used?" onclick={show_usage}>
+                {material_icon("manage_search")}
+            </Button>
+            <Button class="red clear-item-notes" title="Clear notes and target" onclick={clear}
+                disabled={meta.is_empty()}>
+                {material_icon("delete")}
+            </Button>
+        </div>
+    }
+}
+
+/// Builds a tooltip summary of an item's notes and target, if it has any.
+pub fn item_meta_summary(meta: &ItemMeta) -> Option<String> {
+    match (&meta.target_stock, meta.notes.is_empty()) {
+        (None, true) => None,
+        (Some(target), true) => Some(format!("Target: {target}")),
+        (None, false) => Some(meta.notes.to_string()),
+        (Some(target), false) => Some(format!("Target: {target}\n{}", meta.notes)),
+    }
+}
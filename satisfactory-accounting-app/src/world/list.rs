@@ -6,11 +6,16 @@ use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 
 use log::warn;
+use satisfactory_accounting::accounting::{
+    BuildNode, Building, BuildingSettings, GeneratorSettings, Group, MinerSettings, Node,
+};
+use satisfactory_accounting::database::{BuildingId, Database, ItemId};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use uuid::Uuid;
 use yew::AttrValue;
 
-use crate::world::{DatabaseVersionSelector, WorldId};
+use crate::world::{DatabaseVersionSelector, LoadDatabase, World, WorldId};
 
 /// Info about a particular world. Used in the world map to avoid needing to load the
 /// whole world to get info about it.
@@ -27,6 +32,15 @@ pub struct WorldMetadata {
     pub load_error: bool,
 }
 
+/// A world which has been deleted but can still be restored.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct TrashedWorld {
+    /// Metadata the world had when it was deleted.
+    pub meta: WorldMetadata,
+    /// Timestamp in milliseconds of when the world was deleted.
+    pub deleted: f64,
+}
+
 /// Mapping of different worlds.
 #[derive(PartialEq, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -41,7 +55,11 @@ impl WorldList {
         let mut worlds = BTreeMap::new();
         worlds.insert(selected, meta);
         Self {
-            inner: Rc::new(WorldListInner { worlds, selected }),
+            inner: Rc::new(WorldListInner {
+                worlds,
+                selected,
+                trash: BTreeMap::new(),
+            }),
         }
     }
 
@@ -74,6 +92,53 @@ impl WorldList {
         worlds.remove(&id).ok_or(RemoveWorldError::NotFound)
     }
 
+    /// Remove the world with the given ID and move it to the trash.
+    pub fn trash(&mut self, id: WorldId, now: f64) -> Result<(), RemoveWorldError> {
+        let meta = self.remove(id)?;
+        let inner = Rc::make_mut(&mut self.inner);
+        inner.trash.insert(id, TrashedWorld { meta, deleted: now });
+        Ok(())
+    }
+
+    /// Move the world with the given ID out of the trash and back into the world list. Returns
+    /// false if the world wasn't in the trash.
+    pub fn restore_from_trash(&mut self, id: WorldId) -> bool {
+        let inner = Rc::make_mut(&mut self.inner);
+        let Some(trashed) = inner.trash.remove(&id) else {
+            return false;
+        };
+        // If a world with the same ID was somehow added since, keep that one.
+        inner.worlds.entry(id).or_insert(trashed.meta);
+        true
+    }
+
+    /// Remove the world with the given ID from the trash. Returns whether the world's storage can
+    /// be deleted, which is only the case if it was in the trash and hasn't been added back to
+    /// the world list.
+    pub fn remove_from_trash(&mut self, id: WorldId) -> bool {
+        if !self.inner.trash.contains_key(&id) {
+            return false;
+        }
+        let inner = Rc::make_mut(&mut self.inner);
+        inner.trash.remove(&id);
+        !inner.worlds.contains_key(&id)
+    }
+
+    /// Get the IDs of worlds which were moved to the trash before the given time.
+    pub fn trashed_before(&self, cutoff: f64) -> Vec<WorldId> {
+        self.inner
+            .trash
+            .iter()
+            .filter(|(_, trashed)| trashed.deleted < cutoff)
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    /// Gets an iterator over the worlds in the trash.
+    pub fn trash_iter(&self) -> impl Iterator<Item = (WorldId, &TrashedWorld)> {
+        self.inner.trash.iter().map(|(&id, trashed)| (id, trashed))
+    }
+
     /// Get the metadata for a particular world, if it exists.
     pub fn get(&self, id: WorldId) -> Option<WorldMetaRef> {
         self.inner.worlds.get(&id).map(|meta| WorldMetaRef {
@@ -176,6 +241,9 @@ struct WorldListInner {
     worlds: BTreeMap<WorldId, WorldMetadata>,
     /// ID of the currently selected world.
     selected: WorldId,
+    /// Worlds which have been deleted but whose storage is kept so they can be restored.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    trash: BTreeMap<WorldId, TrashedWorld>,
 }
 
 /// Error cases for removing a world from the world list.
@@ -490,3 +558,164 @@ impl<'a> AbsentWorld<'a> {
         *self.selected = id;
     }
 }
+
+/// A world saved as a starting point for new worlds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    /// Name of the template.
+    pub name: AttrValue,
+    /// World which new worlds created from this template start as a copy of.
+    world: World,
+}
+
+/// Templates the user has saved, by ID.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TemplateList {
+    /// Shared inner template map.
+    inner: Rc<BTreeMap<Uuid, Template>>,
+}
+
+/// Templates are only ever replaced wholesale, so identity is enough to tell if they changed.
+impl PartialEq for TemplateList {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl TemplateList {
+    /// Iterate over the saved templates and their IDs.
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, &Template)> {
+        self.inner.iter().map(|(&id, template)| (id, template))
+    }
+
+    /// Returns true if no templates have been saved.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Save a copy of the world as a new template and return its ID.
+    pub(super) fn insert(&mut self, world: World) -> Uuid {
+        let name = match world.name() {
+            name if name.is_empty() => "Unnamed Template".into(),
+            name => name,
+        };
+        let id = Uuid::new_v4();
+        Rc::make_mut(&mut self.inner).insert(id, Template { name, world });
+        id
+    }
+
+    /// Add all of the other templates, replacing any with the same IDs.
+    pub(super) fn merge(&mut self, other: &TemplateList) {
+        Rc::make_mut(&mut self.inner).extend(
+            other
+                .inner
+                .iter()
+                .map(|(&id, template)| (id, template.clone())),
+        );
+    }
+
+    /// Remove the template with the given ID, returning it if it existed.
+    pub(super) fn remove(&mut self, id: Uuid) -> Option<Template> {
+        Rc::make_mut(&mut self.inner).remove(&id)
+    }
+
+    /// Create a world from the chosen template, or None if the template no longer exists.
+    pub(super) fn instantiate(&self, choice: TemplateChoice) -> Option<World> {
+        match choice {
+            TemplateChoice::Empty => Some(World::new()),
+            TemplateChoice::CoalPower => Some(coal_power_world()),
+            TemplateChoice::Saved(id) => self.inner.get(&id).map(|template| template.world.clone()),
+        }
+    }
+}
+
+/// Which template to create a new world from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TemplateChoice {
+    /// A world with nothing in it.
+    Empty,
+    /// A world with a basic coal power plant.
+    CoalPower,
+    /// A template the user saved.
+    Saved(Uuid),
+}
+
+impl TemplateChoice {
+    /// Templates which are always available.
+    pub const BUILT_IN: [(Self, &'static str); 2] = [
+        (Self::Empty, "Empty World"),
+        (Self::CoalPower, "Starter Coal Power"),
+    ];
+}
+
+/// Create the built-in starter coal power world: eight coal generators with just enough coal
+/// miners and water extractors to run them.
+fn coal_power_world() -> World {
+    let mut world = World::new();
+    let database = world.database.load_database();
+    let coal = ItemId::from("Desc_Coal_C");
+    let group = Group {
+        name: "Coal Power".into(),
+        children: [
+            template_building(
+                &database,
+                "Desc_MinerMk1_C",
+                BuildingSettings::Miner(MinerSettings {
+                    resource: Some(coal),
+                    ..Default::default()
+                }),
+                2.0,
+            ),
+            template_building(
+                &database,
+                "Desc_WaterPump_C",
+                BuildingSettings::Miner(MinerSettings {
+                    resource: Some(ItemId::water()),
+                    ..Default::default()
+                }),
+                3.0,
+            ),
+            template_building(
+                &database,
+                "Desc_GeneratorCoal_C",
+                BuildingSettings::Generator(GeneratorSettings {
+                    fuel: Some(coal),
+                    ..Default::default()
+                }),
+                8.0,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+        ..Group::empty()
+    };
+    let mut root = world
+        .root
+        .group()
+        .expect("New world root is a group")
+        .clone();
+    root.children.push(group.into());
+    world.root = root.into();
+    world
+}
+
+/// Build a node for a template building, or None if the database doesn't support it.
+fn template_building(
+    database: &Database,
+    building: &str,
+    settings: BuildingSettings,
+    copies: f32,
+) -> Option<Node> {
+    let building = Building {
+        building: Some(BuildingId::from(building)),
+        settings,
+        copies,
+        ..Building::empty()
+    };
+    building
+        .build_node(database)
+        .inspect_err(|e| warn!("Template building could not be built: {e}"))
+        .ok()
+}
@@ -1,16 +1,23 @@
 use std::cell::{Ref, RefCell};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::mem;
 use std::ops::Deref;
 use std::rc::Rc;
 
+use gloo::events::EventListener;
 use gloo::storage::errors::StorageError;
 use gloo::storage::{LocalStorage, Storage as _};
+use gloo::timers::callback::Interval;
 use log::{error, info, warn};
-use satisfactory_accounting::accounting::{Group, Node, NodeKind};
-use satisfactory_accounting::database::Database;
+use satisfactory_accounting::accounting::{Group, Node, NodeKind, TreeMemory};
+use satisfactory_accounting::analysis::{diff_trees, TreeDiff};
+use satisfactory_accounting::database::{Database, DatabaseVersion, ItemId};
+use satisfactory_accounting::savegame::{SaveGame, SaveGrouping};
 use thiserror::Error;
 use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::js_sys::Date;
+use web_sys::StorageEvent;
 use yew::html::Scope;
 use yew::{
     hook, html, use_context, AttrValue, Callback, Component, Context, ContextHandle,
@@ -20,11 +27,26 @@ use yew::{
 use crate::bugreport::file_a_bug;
 use crate::modal::{ModalDispatcher, ModalOk};
 use crate::refeqrc::RefEqRc;
-use crate::user_settings::UserSettingsDispatcher;
-use crate::world::list::WorldEntry;
+use crate::user_settings::{SettingsOverrides, UserSettings, UserSettingsDispatcher};
+use crate::world::archive::WorldHistory;
+use crate::world::checkpoints;
+use crate::world::chunks;
+use crate::world::clipboard::{ClipboardContent, CLIPBOARD_KEY};
+use crate::world::dbfetch::{fetch_database, FetchDatabaseError};
+use crate::world::list::{TemplateChoice, TemplateList, WorldEntry};
 use crate::world::savefile::VersionedWorldModel;
+use crate::world::share::{
+    has_read_only_flag, take_shared_view, view_summary, DecodeSharedViewError, SharedView,
+};
+use crate::world::snapshots::{self, SnapshotSchedule};
+use crate::world::storageusage::{format_bytes, StorageUsage, ESTIMATED_QUOTA};
+use crate::world::sync::{
+    download_world, list_remote_worlds, upload_world, world_from_save, RemoteVersions, RemoteWorld,
+    SyncConfig, SyncError,
+};
 use crate::world::{
-    v1storage, DatabaseChoice, DatabaseVersionSelector, NodeMeta, NodeMetas, SaveFile, WorldId,
+    v1storage, DatabaseChoice, DatabaseVersionSelector, HiddenColumns, ItemMeta, ItemMetas,
+    LoadDatabase, NodeMeta, NodeMetas, Progression, SaveFile, SessionStats, WorldId,
 };
 use crate::world::{World, WorldList};
 
@@ -48,19 +70,86 @@ pub enum Msg {
     },
     /// Update many node metas at once.
     BatchUpdateNodeMeta(HashMap<Uuid, NodeMeta>),
+    /// Expand groups nested fewer than the given number of levels deep and collapse the rest, or
+    /// expand every group if None.
+    ExpandToDepth(Option<usize>),
+    /// Update the notes and targets of a single item.
+    UpdateItemMeta {
+        /// ID of the item to update the metadata of.
+        id: ItemId,
+        /// New metadata for the item.
+        meta: ItemMeta,
+    },
+    /// Set how far through the game the player is in this world.
+    SetProgression(Progression),
+    /// Set which user settings this world keeps separately.
+    SetSettingsOverrides(SettingsOverrides),
+    /// Set which columns of the node grid are hidden in this world.
+    SetHiddenColumns(HiddenColumns),
     /// Change the most recent undo state, pushing the current state to the redo stack.
     Undo,
     /// Change to the most recent redo state, pushing the current state to the undo stack.
     Redo,
+    /// Fork the current world in memory so it can be edited without saving.
+    OpenSandbox,
+    /// Stop editing the sandbox, either keeping its changes as a single undoable edit or
+    /// returning the world to how it was before the sandbox was opened.
+    CloseSandbox {
+        /// Whether to keep the changes made in the sandbox.
+        apply: bool,
+    },
     /// Switch to the specified DatabaseVersion.
     SetDb(DatabaseVersionSelector),
 
     /// Change to the specified World ID.
     SetWorld(WorldId),
-    /// Permanently delete the world with the given ID.
+    /// Move the world with the given ID to the trash.
     DeleteWorld(WorldId),
+    /// Move the world with the given ID out of the trash.
+    RestoreWorld(WorldId),
+    /// Permanently delete the world with the given ID from the trash.
+    PurgeWorld(WorldId),
     /// Create a new world and switch to it.
     CreateWorld,
+    /// Create a new world from a template and switch to it.
+    CreateWorldFromTemplate(TemplateChoice),
+    /// Save a copy of the world with the given ID as a template.
+    SaveTemplate(WorldId),
+    /// Delete the saved template with the given ID.
+    DeleteTemplate(Uuid),
+    /// Replace the content of the clipboard shared by all worlds.
+    CopyToClipboard(ClipboardContent),
+    /// Copy top-level groups from another world into the current world.
+    ImportGroups {
+        /// ID of the world to copy the groups from.
+        from: WorldId,
+        /// IDs of the top-level groups to copy.
+        groups: Vec<Uuid>,
+    },
+    /// Restore worlds and templates from an archive, replacing worlds with the same IDs.
+    RestoreArchive {
+        /// Worlds to restore, with the ID each had, if any.
+        worlds: Vec<(Option<WorldId>, World)>,
+        /// ID of the world to select afterwards, if it was included.
+        selected: Option<WorldId>,
+        /// Templates to add to the user's templates.
+        templates: TemplateList,
+        /// Checkpoints and snapshots of the restored worlds.
+        histories: Vec<WorldHistory>,
+    },
+    /// Create a new world from the buildings in a Satisfactory game save.
+    ImportGameSave {
+        /// Name of the uploaded file.
+        file_name: String,
+        /// Contents of the save.
+        data: Vec<u8>,
+        /// How to group the buildings from the save.
+        grouping: SaveGrouping,
+    },
+    /// Show a world from a share link the app was opened with, without saving it.
+    OpenSharedView(Result<SharedView, DecodeSharedViewError>),
+    /// Save the world opened from a share link as a new world and allow editing it.
+    SaveSharedCopy,
     /// Mark an error on the given world id.
     MarkError(WorldId),
     /// Create a world from an uploaded file.
@@ -82,6 +171,193 @@ pub enum Msg {
         /// The world that was uploaded.
         uploaded_world: World,
     },
+    /// Replace a world with one of its snapshots.
+    RestoreSnapshot {
+        /// ID of the world to restore.
+        world_id: WorldId,
+        /// Timestamp of the snapshot to restore.
+        taken: f64,
+    },
+    /// Replace a world with one of its named checkpoints.
+    RestoreCheckpoint {
+        /// ID of the world to restore.
+        world_id: WorldId,
+        /// Timestamp of the checkpoint to restore.
+        taken: f64,
+    },
+    /// Check that the stored copy of the world still matches the world in memory, if the app has
+    /// been idle for long enough.
+    VerifySave,
+    /// Another tab changed the value stored under the given key.
+    StorageChanged {
+        /// Local storage key which changed.
+        key: String,
+    },
+    /// A database version which isn't built in has finished fetching.
+    DatabaseFetched {
+        /// Version which was fetched.
+        version: DatabaseVersion,
+        /// Whether the fetch succeeded.
+        result: Result<(), FetchDatabaseError>,
+    },
+    /// Change where and whether worlds are synced.
+    SetSyncConfig(SyncConfig),
+    /// Upload the current world to the sync server.
+    SyncWorld {
+        /// Whether to replace the remote copy even if it changed since the last sync.
+        overwrite: bool,
+    },
+    /// An upload to the sync server has finished.
+    SyncUploaded {
+        /// ID of the world which was uploaded.
+        id: WorldId,
+        /// World which was uploaded.
+        world: World,
+        /// Save file which was uploaded.
+        json: Rc<str>,
+        /// The new remote version of the world, if the upload succeeded.
+        result: Result<Option<String>, SyncError>,
+    },
+    /// Replace the given world with the copy on the sync server, or import it if it isn't in
+    /// this browser yet.
+    DownloadRemoteWorld(WorldId),
+    /// A download from the sync server has finished.
+    RemoteWorldDownloaded {
+        /// ID of the world which was downloaded.
+        id: WorldId,
+        /// The downloaded save file and its remote version, if the download succeeded.
+        result: Result<(SaveFile, Option<String>), SyncError>,
+    },
+    /// List the worlds on the sync server.
+    ListRemoteWorlds,
+    /// Listing the worlds on the sync server has finished.
+    RemoteWorldsListed {
+        /// The remote worlds, if listing succeeded.
+        result: Result<Vec<RemoteWorld>, SyncError>,
+    },
+    /// The user changed how many undo states to keep.
+    SetMaxUndo {
+        /// Maximum number of undo states to keep.
+        max_undo: usize,
+    },
+}
+
+impl Msg {
+    /// Whether this message can be handled while the app is read-only. Only messages which don't
+    /// change the world's content are allowed.
+    fn allowed_when_read_only(&self) -> bool {
+        matches!(
+            self,
+            Msg::UpdateNodeMeta { .. }
+                | Msg::BatchUpdateNodeMeta(_)
+                | Msg::ExpandToDepth(_)
+                | Msg::OpenSharedView(_)
+                | Msg::SaveSharedCopy
+                | Msg::DatabaseFetched { .. }
+                | Msg::SetMaxUndo { .. }
+        )
+    }
+
+    /// Whether this message can be handled while a sandbox is open. Messages which would switch
+    /// away from or replace the current world must wait until the sandbox is closed.
+    fn allowed_in_sandbox(&self) -> bool {
+        !matches!(
+            self,
+            Msg::SetWorld(_)
+                | Msg::DeleteWorld(_)
+                | Msg::CreateWorld
+                | Msg::CreateWorldFromTemplate(_)
+                | Msg::RestoreArchive { .. }
+                | Msg::ImportGameSave { .. }
+                | Msg::OpenSharedView(_)
+                | Msg::UploadWorld { .. }
+                | Msg::FinishUploadAsNew { .. }
+                | Msg::FinishUploadReplacingExisting { .. }
+                | Msg::RestoreSnapshot { .. }
+                | Msg::RestoreCheckpoint { .. }
+                | Msg::SyncWorld { .. }
+                | Msg::DownloadRemoteWorld(_)
+        )
+    }
+
+    /// Whether this message comes from background work rather than the user, so it shouldn't
+    /// count as activity.
+    fn is_background(&self) -> bool {
+        matches!(
+            self,
+            Msg::VerifySave
+                | Msg::StorageChanged { .. }
+                | Msg::SyncUploaded { .. }
+                | Msg::RemoteWorldDownloaded { .. }
+                | Msg::RemoteWorldsListed { .. }
+                | Msg::SetMaxUndo { .. }
+        )
+    }
+}
+
+/// Why the current world can't be edited.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReadOnlyMode {
+    /// The world was opened from a share link and hasn't been saved as a copy.
+    SharedLink,
+    /// The app was opened with the `readonly` url flag.
+    Flag,
+}
+
+/// Whether the app is showing a world which can't be edited or saved.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ReadOnly(Option<ReadOnlyMode>);
+
+impl ReadOnly {
+    /// Whether editing is disabled.
+    pub fn is_read_only(self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Why editing is disabled, if it is.
+    pub fn mode(self) -> Option<ReadOnlyMode> {
+        self.0
+    }
+}
+
+/// Progress of syncing the current world with the sync server.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum SyncStatus {
+    /// Nothing has been synced yet this session.
+    #[default]
+    Idle,
+    /// An upload or download is in progress.
+    Syncing,
+    /// The last sync succeeded.
+    Synced,
+    /// The remote copy changed since the last sync, so the upload was refused.
+    Conflict,
+    /// The last sync failed for another reason.
+    Failed(AttrValue),
+}
+
+/// Progress of listing the worlds on the sync server.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum RemoteWorlds {
+    /// The sync server hasn't been listed yet.
+    #[default]
+    NotListed,
+    /// Listing is in progress.
+    Listing,
+    /// The worlds found on the sync server, sorted by name.
+    Listed(Rc<[RemoteWorld]>),
+    /// Listing failed.
+    Failed(AttrValue),
+}
+
+/// The last world synced with the sync server.
+struct LastSynced {
+    /// ID of the world.
+    id: WorldId,
+    /// The world as synced, used to cheaply tell that it hasn't changed.
+    world: World,
+    /// The save file as synced, used when the world was rebuilt but may not have changed.
+    json: Rc<str>,
 }
 
 /// Helper for when a world matches an existing world.
@@ -166,6 +442,11 @@ struct WorldManagerModalWrapper {
 impl WorldManagerModalWrapper {
     /// Report an error through a persisted modal dialog.
     fn report_error(&self, title: impl Into<AttrValue>, content: Html) {
+        self.show("WorldManagerError", title, content);
+    }
+
+    /// Show some content through a persisted modal dialog with the given class.
+    fn show(&self, class: &'static str, title: impl Into<AttrValue>, content: Html) {
         self.modal_dispatcher
             .borrow()
             .as_ref()
@@ -174,7 +455,7 @@ impl WorldManagerModalWrapper {
                 for initialization.",
             )
             .builder()
-            .class("WorldManagerError")
+            .class(class)
             .kind(ModalOk::close())
             .title(title)
             .content(content)
@@ -186,17 +467,54 @@ impl WorldManagerModalWrapper {
 mod save_tracker {
     use std::ops::{Deref, DerefMut};
 
+    use gloo::storage::errors::StorageError;
     use gloo::storage::{LocalStorage, Storage};
     use log::warn;
-    use serde::Serialize;
+    use satisfactory_accounting::database::Database;
     use yew::html;
 
     use crate::bugreport::file_a_bug;
-    use crate::world::manager::{WorldManagerModalWrapper, WORLD_MAP_KEY};
+    use crate::world::chunks::{self, SavedChunks};
+    use crate::world::list::TemplateList;
+    use crate::world::manager::{WorldManagerModalWrapper, TEMPLATES_KEY, WORLD_MAP_KEY};
+    use crate::world::snapshots;
     use crate::world::{World, WorldId, WorldList};
 
+    /// A value which can be written to local storage by a SaveTracker.
+    pub(super) trait Persist {
+        /// State kept between saves to avoid rewriting parts of the value which haven't changed.
+        type Cache: Default;
+
+        /// Write the value to local storage under the given key.
+        fn persist(&self, key: &str, cache: &mut Self::Cache) -> Result<(), StorageError>;
+    }
+
+    impl Persist for WorldList {
+        type Cache = ();
+
+        fn persist(&self, key: &str, _cache: &mut ()) -> Result<(), StorageError> {
+            LocalStorage::set(key, self)
+        }
+    }
+
+    impl Persist for TemplateList {
+        type Cache = ();
+
+        fn persist(&self, key: &str, _cache: &mut ()) -> Result<(), StorageError> {
+            LocalStorage::set(key, self)
+        }
+    }
+
+    impl Persist for World {
+        type Cache = SavedChunks;
+
+        fn persist(&self, key: &str, cache: &mut SavedChunks) -> Result<(), StorageError> {
+            chunks::save(key, self, cache)
+        }
+    }
+
     /// Tracks whether the given value has been saved.
-    pub(super) struct SaveTracker<T, K> {
+    pub(super) struct SaveTracker<T: Persist, K> {
         /// The value that needs to be stored.
         value: T,
         /// Local storage key used for this item.
@@ -205,20 +523,32 @@ mod save_tracker {
         error_reporter: WorldManagerModalWrapper,
         /// A bool indicating whether the value has been saved yet or not.
         is_saved: bool,
+        /// Whether saving is disabled because the app is in read-only mode.
+        read_only: bool,
+        /// Whether saving is held off because the value is being edited in a sandbox.
+        paused: bool,
+        /// What was written by the last save.
+        cache: T::Cache,
     }
 
     pub type WorldListTracker = SaveTracker<WorldList, &'static str>;
+    pub type TemplateListTracker = SaveTracker<TemplateList, &'static str>;
     pub type WorldTracker = SaveTracker<World, String>;
 
     impl<T, K> SaveTracker<T, K>
     where
-        T: Serialize,
+        T: Persist,
         K: AsRef<str>,
     {
         /// Try to save, updating the is_saved state if successful.
         pub fn try_save_if_unsaved(&mut self) {
-            if !self.is_saved {
-                match LocalStorage::set(self.key.as_ref(), &self.value) {
+            if !self.is_saved && !self.read_only && !self.paused {
+                let mut result = self.value.persist(self.key.as_ref(), &mut self.cache);
+                // Snapshots are only backups, so give up their space rather than fail to save.
+                while result.is_err() && snapshots::drop_oldest() {
+                    result = self.value.persist(self.key.as_ref(), &mut self.cache);
+                }
+                match result {
                     Ok(()) => self.is_saved = true,
                     Err(e) => {
                         let typename = std::any::type_name::<T>();
@@ -251,6 +581,9 @@ mod save_tracker {
                 key: WORLD_MAP_KEY,
                 error_reporter,
                 is_saved: true,
+                read_only: false,
+                paused: false,
+                cache: Default::default(),
             }
         }
 
@@ -261,6 +594,24 @@ mod save_tracker {
                 key: WORLD_MAP_KEY,
                 error_reporter,
                 is_saved: false,
+                read_only: false,
+                paused: false,
+                cache: Default::default(),
+            }
+        }
+    }
+
+    impl SaveTracker<TemplateList, &'static str> {
+        /// Create a SaveTracker for an already saved value.
+        pub fn saved(value: TemplateList, error_reporter: WorldManagerModalWrapper) -> Self {
+            Self {
+                value,
+                key: TEMPLATES_KEY,
+                error_reporter,
+                is_saved: true,
+                read_only: false,
+                paused: false,
+                cache: (),
             }
         }
     }
@@ -273,6 +624,9 @@ mod save_tracker {
                 key: id.as_legacy_dotted().to_string(),
                 error_reporter,
                 is_saved: true,
+                read_only: false,
+                paused: false,
+                cache: Default::default(),
             }
         }
 
@@ -287,11 +641,56 @@ mod save_tracker {
                 key: id.as_legacy_dotted().to_string(),
                 error_reporter,
                 is_saved: false,
+                read_only: false,
+                paused: false,
+                cache: Default::default(),
+            }
+        }
+
+        /// Re-read the stored copy of the world and check whether it still matches the world in
+        /// memory. A world with unsaved changes is expected to differ, so it always matches.
+        ///
+        /// The stored copy is normalized the same way it would be on load, so that rebuilding the
+        /// root and pruning metadata isn't reported as a difference. Buildings stored without ids
+        /// are given the same derived ids they got when the world was loaded, so worlds saved
+        /// before buildings had ids still match.
+        pub fn matches_storage(&self, database: &Database) -> Result<bool, StorageError> {
+            if !self.is_saved {
+                return Ok(true);
             }
+            let mut stored = chunks::load(&self.key)?;
+            stored.node_metadata.prune(&stored.root);
+            stored.root = stored.root.rebuild(database);
+            let stored = serde_json::to_value(&stored).map_err(StorageError::SerdeError)?;
+            let current = serde_json::to_value(&self.value).map_err(StorageError::SerdeError)?;
+            Ok(stored == current)
+        }
+
+        /// Run the post-load steps on the world without marking it as dirty, since they can be
+        /// redone on every load. Returns the database the world uses.
+        pub fn post_load(&mut self) -> Database {
+            let database = self.value.post_load();
+            if self.is_saved {
+                // Rebuilding replaces every node, so record the rebuilt groups as the saved ones
+                // to avoid rewriting every chunk on the next save.
+                self.cache.mark_saved(&self.key, &self.value);
+            }
+            database
         }
     }
 
-    impl<T, K> SaveTracker<T, K> {
+    impl<T: Persist, K> SaveTracker<T, K> {
+        /// Stop this value from ever being saved, so changes only last until the app is closed.
+        pub fn set_read_only(&mut self) {
+            self.read_only = true;
+        }
+
+        /// Hold off or resume saving. Changes made while paused are saved by the first save after
+        /// resuming.
+        pub fn set_paused(&mut self, paused: bool) {
+            self.paused = paused;
+        }
+
         /// Get a mutable reference to the value without marking it as in need of saving.
         pub fn mutate_without_marking_dirty(&mut self) -> &mut T {
             &mut self.value
@@ -304,6 +703,11 @@ mod save_tracker {
             &mut self.value
         }
 
+        /// Whether the value has been saved since it was last changed.
+        pub fn is_saved(&self) -> bool {
+            self.is_saved
+        }
+
         /// Gets a handle to the value for if you aren't sure if you are going to mutate it.
         pub fn maybe_mutate(&mut self) -> MutateHandle<T> {
             MutateHandle {
@@ -313,7 +717,7 @@ mod save_tracker {
         }
     }
 
-    impl<T, K> Deref for SaveTracker<T, K> {
+    impl<T: Persist, K> Deref for SaveTracker<T, K> {
         type Target = T;
 
         #[inline]
@@ -323,7 +727,7 @@ mod save_tracker {
     }
 
     /// DerefMut on a SaveTracker always marks the value as dirty.
-    impl<T, K> DerefMut for SaveTracker<T, K> {
+    impl<T: Persist, K> DerefMut for SaveTracker<T, K> {
         #[inline]
         fn deref_mut(&mut self) -> &mut Self::Target {
             self.mutate_and_mark_dirty()
@@ -367,7 +771,7 @@ mod save_tracker {
     }
 }
 
-use save_tracker::{WorldListTracker, WorldTracker};
+use save_tracker::{TemplateListTracker, WorldListTracker, WorldTracker};
 
 enum SwitchWorldError {
     /// The world ID was not recognized.
@@ -380,6 +784,8 @@ enum SwitchWorldError {
 pub struct WorldManager {
     /// List of available worlds.
     worlds: WorldListTracker,
+    /// Templates the user has saved for creating new worlds.
+    templates: TemplateListTracker,
 
     /// Current state of the world.
     world: WorldTracker,
@@ -389,6 +795,51 @@ pub struct WorldManager {
     undo_stack: VecDeque<UnReDoState>,
     /// Stack of future states for redo.
     redo_stack: VecDeque<UnReDoState>,
+    /// Maximum number of states kept in the undo stack.
+    max_undo: usize,
+    /// Memory used by undo and redo states beyond what the current world uses.
+    undo_memory: TreeMemory,
+    /// Edit and time statistics for the current session.
+    session_stats: SessionStats,
+    /// Timestamp in milliseconds of the last message other than a save verification.
+    last_activity: f64,
+    /// Whether a mismatch between storage and memory has already been reported since the last
+    /// activity, to avoid repeating the warning every time the check runs.
+    save_mismatch_reported: bool,
+    /// Whether the user has already been warned this session that storage is nearly full.
+    storage_warning_reported: bool,
+    /// Interval which periodically triggers save verification.
+    _save_verifier: Interval,
+    /// Listener for changes other tabs make to local storage.
+    _storage_listener: EventListener,
+    /// Tracks when the current world should next be snapshotted.
+    snapshot_schedule: SnapshotSchedule,
+    /// Database versions which have been requested from the server, including ones which failed
+    /// so they aren't requested repeatedly.
+    fetching_databases: BTreeSet<DatabaseVersion>,
+    /// Database versions which must be fetched before the world stored by v1 of the app can be
+    /// migrated. The migration happens once all of them have arrived or failed.
+    v1_databases_pending: BTreeSet<DatabaseVersion>,
+    /// Where and whether worlds are synced.
+    sync_config: SyncConfig,
+    /// Remote version of each world as of its last sync, used to detect conflicting changes.
+    remote_versions: RemoteVersions,
+    /// The last world synced, so unchanged worlds aren't uploaded again.
+    last_synced: Option<LastSynced>,
+    /// Progress of syncing the current world.
+    sync_status: SyncStatus,
+    /// Worlds found on the sync server.
+    remote_worlds: RemoteWorlds,
+    /// Whether editing and saving are disabled.
+    read_only: ReadOnly,
+    /// The sandbox the current world is being edited in, if one is open.
+    sandbox: Option<Sandbox>,
+    /// Node copied to the clipboard shared by all worlds, if any.
+    clipboard: Option<RefEqRc<ClipboardContent>>,
+    /// Dispatcher used to tell the UserSettingsManager which settings the world overrides.
+    user_settings_dispatcher: UserSettingsDispatcher,
+    /// Settings overrides last sent to the UserSettingsManager, if any have been sent yet.
+    sent_settings_overrides: Option<SettingsOverrides>,
 
     /// Cached rc-wrapped link back to this component, used for the context managers it provides.
     link: Link,
@@ -400,6 +851,8 @@ pub struct WorldManager {
     /// Handle which ensure we receive updates to the modal dispatcher used in the error_reporter if
     /// it changes.
     _modal_dispatcher_handle: ContextHandle<ModalDispatcher>,
+    /// Handle which ensures we receive updates to the user's undo settings.
+    _user_settings_handle: ContextHandle<Rc<UserSettings>>,
 }
 
 impl WorldManager {
@@ -411,32 +864,72 @@ impl WorldManager {
             database: mem::replace(&mut self.world.database, state.database),
         };
         if self.world.database != prior_state.database {
-            self.database = self.world.database.get();
+            self.database = self.world.database.load_database();
         }
         prior_state
     }
 
-    /// Add an undo state, clearing the redo states.
+    /// Add an undo state, clearing the redo states. Parts of the current world which are equal to
+    /// the undo state are replaced with the state's nodes so the two share them, and states which
+    /// don't differ from the current world aren't kept at all.
     fn add_undo_state(&mut self, state: UnReDoState) {
+        let shared = self.world.root.share_unchanged(&state.root);
+        // Sharing only swaps nodes for equal ones, so the world doesn't need to be saved again.
+        self.world.mutate_without_marking_dirty().root = shared;
+        if self.world.root.ptr_eq(&state.root) && self.world.database == state.database {
+            return;
+        }
         self.redo_stack.clear();
-        if self.undo_stack.len() >= MAX_UNDO {
-            // Remove all items beyond MAX_UNDO as well as 1 additional item to make room to push
-            // without going over.
-            let to_remove = self.undo_stack.len() - MAX_UNDO + 1;
-            if to_remove > 1 {
-                warn!(
-                    "Undo stack grew larger than {MAX_UNDO} items: {}",
-                    self.undo_stack.len()
-                );
-            }
+        self.undo_stack.push_back(state);
+        self.trim_undo_history();
+    }
+
+    /// Drop the oldest undo states and furthest redo states beyond the limit, and remeasure the
+    /// memory the history uses.
+    fn trim_undo_history(&mut self) {
+        if self.undo_stack.len() > self.max_undo {
+            let to_remove = self.undo_stack.len() - self.max_undo;
             self.undo_stack.drain(..to_remove);
         }
-        self.undo_stack.push_back(state);
+        if self.redo_stack.len() > self.max_undo {
+            let to_remove = self.redo_stack.len() - self.max_undo;
+            self.redo_stack.drain(..to_remove);
+        }
+        self.measure_undo_memory();
+    }
+
+    /// Measure the memory used by undo history which isn't shared with the current world.
+    fn measure_undo_memory(&mut self) {
+        let sandbox = self.sandbox.iter().flat_map(|sandbox| {
+            std::iter::once(&sandbox.original)
+                .chain(&sandbox.undo_stack)
+                .chain(&sandbox.redo_stack)
+        });
+        let states = self
+            .undo_stack
+            .iter()
+            .chain(&self.redo_stack)
+            .chain(sandbox);
+        self.undo_memory = TreeMemory::measure(states.map(|state| &state.root), [&self.world.root]);
+    }
+
+    /// Message handler for SetMaxUndo. Returns true if redraw is needed.
+    fn set_max_undo(&mut self, max_undo: usize) -> bool {
+        if self.max_undo == max_undo {
+            return false;
+        }
+        self.max_undo = max_undo;
+        self.trim_undo_history();
+        true
     }
 
     /// Update the metadata for the currently selected world. Always saves the world list if it is
     /// in the unsaved state, even if the current world's metadata is unchanged.
     fn update_world_metadata(&mut self) {
+        if self.sandbox.is_some() {
+            // The world list should describe the saved world, not the sandbox.
+            return;
+        }
         let world_meta = self.world.metadata();
         {
             let mut handle = self.worlds.maybe_mutate();
@@ -462,6 +955,8 @@ impl WorldManager {
         }
         // Update the world state, tracking the old and new name.
         let old_root = mem::replace(&mut self.world.root, new_root);
+        self.session_stats
+            .record_edit(self.worlds.selected_id(), &old_root, &self.world.root);
         let undo = UnReDoState {
             root: old_root,
             database: self.world.database.clone(),
@@ -471,9 +966,56 @@ impl WorldManager {
         // Save the world, and if necessary update the world's metadata as well.
         self.world.try_save_if_unsaved();
         self.update_world_metadata();
+        self.snapshot_schedule.record_edit();
+        self.snapshot_if_due();
         true
     }
 
+    /// Take a snapshot of the current world if it has changed enough since the last snapshot.
+    fn snapshot_if_due(&mut self) {
+        let now = Date::now();
+        if self.sandbox.is_some() || !self.snapshot_schedule.is_due(now) {
+            return;
+        }
+        let id = self.worlds.selected_id();
+        if let Err(e) = snapshots::take(id, &self.world, now) {
+            warn!("Unable to snapshot world {id:?}: {e}");
+        }
+        // Wait a full interval before trying again, even if this snapshot failed.
+        self.snapshot_schedule = SnapshotSchedule::new(now);
+        self.warn_if_storage_low();
+    }
+
+    /// Warn the user once per session if local storage is nearly full, so they can free up space
+    /// before saves start failing.
+    fn warn_if_storage_low(&mut self) {
+        if self.storage_warning_reported {
+            return;
+        }
+        let usage = match StorageUsage::measure() {
+            Ok(usage) => usage,
+            Err(e) => {
+                warn!("Unable to measure storage usage: {e:?}");
+                return;
+            }
+        };
+        if !usage.is_near_quota() {
+            return;
+        }
+        self.storage_warning_reported = true;
+        let content = html! {
+            <>
+            <p>{"Your worlds are using "}{format_bytes(usage.total())}{" of the roughly "}
+            {format_bytes(ESTIMATED_QUOTA)}{" of storage your browser allows this website. Once \
+            storage is full, changes to your worlds can no longer be saved."}</p>
+            <p>{"You can see how much space each world uses from the world chooser. Consider \
+            downloading a backup and then deleting worlds you no longer need."}</p>
+            </>
+        };
+        self.error_reporter
+            .report_error("Browser Storage Nearly Full", content);
+    }
+
     /// Message handler for SetNodeMeta. Returns true if redraw is needed.
     fn update_node_meta(&mut self, id: Uuid, meta: NodeMeta) -> bool {
         self.world.node_metadata.set_meta(id, meta);
@@ -490,6 +1032,70 @@ impl WorldManager {
         true
     }
 
+    /// Message handler for ExpandToDepth. Returns true if redraw is needed.
+    fn expand_to_depth(&mut self, depth: Option<usize>) -> bool {
+        let updates = self
+            .world
+            .node_metadata
+            .expand_to_depth(&self.world.root, depth);
+        if updates.is_empty() {
+            return false;
+        }
+        self.batch_update_node_meta(updates)
+    }
+
+    /// Message handler for UpdateItemMeta. Returns true if redraw is needed.
+    fn update_item_meta(&mut self, id: ItemId, meta: ItemMeta) -> bool {
+        if self.world.item_metadata.meta(id) == meta {
+            return false;
+        }
+        self.world.item_metadata.set_meta(id, meta);
+        self.world.try_save_if_unsaved();
+        true
+    }
+
+    /// Message handler for SetProgression. Returns true if redraw is needed.
+    fn set_progression(&mut self, progression: Progression) -> bool {
+        if self.world.progression == progression {
+            return false;
+        }
+        self.world.progression = progression;
+        self.world.try_save_if_unsaved();
+        true
+    }
+
+    /// Message handler for SetSettingsOverrides. Returns true if redraw is needed.
+    fn set_settings_overrides(&mut self, overrides: SettingsOverrides) -> bool {
+        if self.world.settings_overrides == overrides {
+            return false;
+        }
+        self.world.settings_overrides = overrides;
+        self.world.try_save_if_unsaved();
+        true
+    }
+
+    /// Message handler for SetHiddenColumns. Returns true if redraw is needed.
+    fn set_hidden_columns(&mut self, hidden_columns: HiddenColumns) -> bool {
+        if self.world.hidden_columns == hidden_columns {
+            return false;
+        }
+        self.world.hidden_columns = hidden_columns;
+        self.world.try_save_if_unsaved();
+        true
+    }
+
+    /// Tell the UserSettingsManager which settings the current world overrides, if they changed
+    /// since they were last sent.
+    fn send_settings_overrides(&mut self, link: &Scope<Self>) {
+        let overrides = &self.world.settings_overrides;
+        if self.sent_settings_overrides.as_ref() == Some(overrides) {
+            return;
+        }
+        self.sent_settings_overrides = Some(overrides.clone());
+        self.user_settings_dispatcher
+            .set_world_overrides(overrides.clone(), link.callback(Msg::SetSettingsOverrides));
+    }
+
     /// Message handler for Undo. Returns true if redraw is needed.
     fn undo(&mut self) -> bool {
         match self.undo_stack.pop_back() {
@@ -498,6 +1104,7 @@ impl WorldManager {
                 // We rely on the limit on the size of the undo stack to limit the size of the redo
                 // stack.
                 self.redo_stack.push_back(next);
+                self.measure_undo_memory();
                 self.world.try_save_if_unsaved();
                 self.update_world_metadata();
                 true
@@ -518,6 +1125,7 @@ impl WorldManager {
                 // limit now.
                 // We can't use add_undo_state because that would clear the redo stack.
                 self.undo_stack.push_back(previous);
+                self.measure_undo_memory();
                 self.world.try_save_if_unsaved();
                 self.update_world_metadata();
                 true
@@ -529,6 +1137,47 @@ impl WorldManager {
         }
     }
 
+    /// Message handler for OpenSandbox. Returns true if redraw is needed.
+    fn open_sandbox(&mut self) -> bool {
+        if self.sandbox.is_some() {
+            warn!("A sandbox is already open");
+            return false;
+        }
+        self.world.try_save_if_unsaved();
+        self.world.set_paused(true);
+        self.sandbox = Some(Sandbox {
+            original: UnReDoState {
+                database: self.world.database.clone(),
+                root: self.world.root.clone(),
+            },
+            undo_stack: mem::take(&mut self.undo_stack),
+            redo_stack: mem::take(&mut self.redo_stack),
+        });
+        true
+    }
+
+    /// Message handler for CloseSandbox. Returns true if redraw is needed.
+    fn close_sandbox(&mut self, apply: bool) -> bool {
+        let Some(sandbox) = self.sandbox.take() else {
+            warn!("No sandbox to close");
+            return false;
+        };
+        self.world.set_paused(false);
+        self.undo_stack = sandbox.undo_stack;
+        self.redo_stack = sandbox.redo_stack;
+        if !apply {
+            self.apply_undo_state(sandbox.original);
+        } else if sandbox.original.root != self.world.root
+            || sandbox.original.database != self.world.database
+        {
+            self.add_undo_state(sandbox.original);
+        }
+        self.trim_undo_history();
+        self.world.try_save_if_unsaved();
+        self.update_world_metadata();
+        true
+    }
+
     /// Message hander for SetDb. Set the current database version.
     fn set_db(&mut self, selector: DatabaseVersionSelector) -> bool {
         self.database = selector.load_database();
@@ -550,10 +1199,13 @@ impl WorldManager {
     fn set_world_inner(&mut self, mut new_world: WorldTracker) {
         // Neither the root rebuild nor metadata pruning should trigger marking the world as dirty,
         // as both of those things can be re-done on future loads without affecting anything else.
-        self.database = new_world.mutate_without_marking_dirty().post_load();
+        self.database = new_world.post_load();
         self.world = new_world;
         self.undo_stack.clear();
         self.redo_stack.clear();
+        // The sandbox belonged to the world being replaced.
+        self.sandbox = None;
+        self.undo_memory = TreeMemory::default();
     }
 
     /// Tries to swtich to the given world. If switching succeeds, returns true. If the world was
@@ -643,8 +1295,8 @@ impl WorldManager {
         }
     }
 
-    /// Message handler for DeleteWorld. Removes the specified world and switches to another one or
-    /// creates a new empty one if the last world was deleted.
+    /// Message handler for DeleteWorld. Moves the specified world to the trash and switches to
+    /// another one or creates a new empty one if the last world was deleted.
     fn delete_world(&mut self, world_id: WorldId) -> bool {
         // Whether we switched to a different world before removing.
         let changed_world: bool;
@@ -697,11 +1349,10 @@ impl WorldManager {
         let removed_world: bool;
         {
             let mut handle = self.worlds.maybe_mutate();
-            match handle.remove(world_id) {
-                Ok(_) => {
+            // The world stays in storage until it is purged from the trash.
+            match handle.trash(world_id, Date::now()) {
+                Ok(()) => {
                     removed_world = true;
-                    // Delete from local storage before persisting the world list.
-                    LocalStorage::delete(world_id.as_legacy_dotted().to_string());
                 }
                 Err(e) => {
                     removed_world = false;
@@ -719,13 +1370,62 @@ impl WorldManager {
         changed_world || removed_world
     }
 
+    /// Message handler for RestoreWorld. Moves the world out of the trash without switching to it.
+    fn restore_world(&mut self, world_id: WorldId) -> bool {
+        let mut handle = self.worlds.maybe_mutate();
+        if !handle.restore_from_trash(world_id) {
+            handle.no_change();
+            warn!("Cannot restore world {world_id:?} because it is not in the trash");
+            return false;
+        }
+        drop(handle);
+        self.worlds.try_save_if_unsaved();
+        true
+    }
+
+    /// Message handler for PurgeWorld. Permanently deletes a world from the trash.
+    fn purge_world(&mut self, world_id: WorldId) -> bool {
+        let mut handle = self.worlds.maybe_mutate();
+        if handle.remove_from_trash(world_id) {
+            // Delete from local storage before persisting the world list.
+            delete_world_storage(world_id);
+        }
+        drop(handle);
+        self.worlds.try_save_if_unsaved();
+        true
+    }
+
+    /// Permanently delete worlds which have been in the trash for longer than the retention
+    /// period.
+    fn empty_expired_trash(&mut self) {
+        let expired = self
+            .worlds
+            .trashed_before(Date::now() - TRASH_RETENTION_MILLIS);
+        if expired.is_empty() {
+            return;
+        }
+        let mut handle = self.worlds.maybe_mutate();
+        for world_id in expired {
+            info!("Permanently deleting world {world_id:?} from the trash");
+            if handle.remove_from_trash(world_id) {
+                delete_world_storage(world_id);
+            }
+        }
+        drop(handle);
+        self.worlds.try_save_if_unsaved();
+    }
+
     /// Message handler for CreateWorld. Creates a new world and switches to it.
     fn create_world(&mut self) -> bool {
+        self.insert_new_world(World::new())
+    }
+
+    /// Shared helper to add a world under a newly allocated ID and switch to it.
+    fn insert_new_world(&mut self, world: World) -> bool {
         // If the current world has unsaved state, save it before creating a new world.
         self.world.try_save_if_unsaved();
 
         let entry = self.worlds.allocate_new_id();
-        let world = World::new();
         let id = entry.id();
         entry.insert_and_select(world.metadata());
         self.set_world_inner(WorldTracker::unsaved(
@@ -738,6 +1438,133 @@ impl WorldManager {
         true
     }
 
+    /// Message handler for CreateWorldFromTemplate.
+    fn create_world_from_template(&mut self, choice: TemplateChoice) -> bool {
+        match self.templates.instantiate(choice) {
+            Some(world) => self.insert_new_world(world),
+            None => {
+                warn!("Template {choice:?} no longer exists");
+                false
+            }
+        }
+    }
+
+    /// Message handler for SaveTemplate.
+    fn save_template(&mut self, world_id: WorldId) -> bool {
+        let world = if world_id == self.worlds.selected_id() {
+            (*self.world).clone()
+        } else {
+            match load_world(world_id) {
+                Ok(world) => world,
+                Err(e) => {
+                    warn!("Unable to load world {world_id:?} to save as a template: {e}");
+                    self.error_reporter.report_error(
+                        "Unable to save template",
+                        html! {
+                            <>
+                            <p>{"We were unable to load the world to save it as a template. \
+                            This may be a bug, and you can "}{file_a_bug()}{". If you file a \
+                            bug, please include this message:"}</p>
+                            <pre>{format!("Unable to load world {world_id:?}: {e}")}</pre>
+                            </>
+                        },
+                    );
+                    return false;
+                }
+            }
+        };
+        self.templates.insert(world);
+        self.templates.try_save_if_unsaved();
+        true
+    }
+
+    /// Message handler for CopyToClipboard.
+    fn copy_to_clipboard(&mut self, content: ClipboardContent) -> bool {
+        if let Err(e) = content.save() {
+            warn!("Unable to save the clipboard: {e}");
+            self.error_reporter.report_error(
+                "Unable to copy",
+                html! {
+                    <>
+                    <p>{"We were unable to store the copied node. You may be out of Browser \
+                    Storage quota, or this may be a bug. If it appears to be a bug, you can "}
+                    {file_a_bug()}{". If you file a bug, please include this message:"}</p>
+                    <pre>{"Unable to save the clipboard: "}{e}</pre>
+                    </>
+                },
+            );
+            return false;
+        }
+        self.clipboard = Some(RefEqRc::new(content));
+        true
+    }
+
+    /// Message handler for ImportGroups. Copies the chosen top-level groups of another world to the
+    /// end of the current world, giving them new IDs and carrying over their metadata.
+    fn import_groups(&mut self, from: WorldId, groups: Vec<Uuid>) -> bool {
+        let source = match load_world(from) {
+            Ok(source) => source,
+            Err(e) => {
+                warn!("Unable to load world {from:?} to import groups from: {e}");
+                self.error_reporter.report_error(
+                    "Unable to import groups",
+                    html! {
+                        <>
+                        <p>{"We were unable to load the world to import groups from. This may \
+                        be a bug, and you can "}{file_a_bug()}{". If you file a bug, please \
+                        include this message:"}</p>
+                        <pre>{format!("Unable to load world {from:?}: {e}")}</pre>
+                        </>
+                    },
+                );
+                return false;
+            }
+        };
+        let (Some(source_root), Some(root)) = (source.root.group(), self.world.root.group()) else {
+            error!("Cannot import groups: world root is not a group");
+            return false;
+        };
+        let new_meta = RefCell::new(HashMap::new());
+        let imported: Vec<Node> = source_root
+            .children
+            .iter()
+            .filter(|child| {
+                child
+                    .group()
+                    .is_some_and(|group| groups.contains(&group.id))
+            })
+            .map(|child| {
+                child
+                    .create_copy_with_visitor(&|old: Uuid, new: Uuid| {
+                        let meta = source.node_metadata.meta(old);
+                        new_meta.borrow_mut().insert(new, meta);
+                    })
+                    .rebuild(&self.database)
+            })
+            .collect();
+        if imported.is_empty() {
+            warn!("None of the groups to import were found in world {from:?}");
+            return false;
+        }
+        let mut root = root.clone();
+        root.children.extend(imported);
+        self.world.node_metadata.batch_update(new_meta.into_inner());
+        self.set_root(root.into())
+    }
+
+    /// Message handler for DeleteTemplate.
+    fn delete_template(&mut self, id: Uuid) -> bool {
+        let mut handle = self.templates.maybe_mutate();
+        if handle.remove(id).is_none() {
+            handle.no_change();
+            warn!("Template {id} to delete was not found");
+            return false;
+        }
+        drop(handle);
+        self.templates.try_save_if_unsaved();
+        true
+    }
+
     /// Message handler for MarkError. Adds an error marker to the given world.
     fn mark_error(&mut self, id: WorldId) -> bool {
         // The error indicator is not saved, so we don't need to mark dirty on this change.
@@ -861,53 +1688,711 @@ impl WorldManager {
         true
     }
 
-    /// Message handler for FinishUploadAsNew.
-    fn finish_upload_as_new(&mut self, uploaded_world: World) -> bool {
-        // If the current world has unsaved state, save it before creating a new world.
-        self.world.try_save_if_unsaved();
-
-        let entry = self.worlds.allocate_new_id();
-        let id = entry.id();
-        entry.insert_and_select(uploaded_world.metadata());
-        self.set_world_inner(WorldTracker::unsaved(
-            uploaded_world,
-            id,
-            self.error_reporter.clone(),
-        ));
-        self.world.try_save_if_unsaved();
-        self.worlds.try_save_if_unsaved();
-        true
+    /// Message handler for ImportGameSave.
+    fn import_game_save(
+        &mut self,
+        file_name: String,
+        data: Vec<u8>,
+        grouping: SaveGrouping,
+    ) -> bool {
+        let mut world = World::new();
+        let database = world.database.load_database();
+        let save = match SaveGame::read(&data, &database) {
+            Ok(save) => save,
+            Err(e) => {
+                warn!("Unable to read game save {file_name}: {e}");
+                let content = html! {
+                    <>
+                    <p>{"We were unable to read the game save \""}{file_name}{"\". Only saves \
+                    from Satisfactory 1.0 or later are supported. If you believe this is a bug, \
+                    you can "}{file_a_bug()}{". If you do file a bug, please include this error \
+                    message:"}</p>
+                    <pre>{e.to_string()}</pre>
+                    </>
+                };
+                self.error_reporter
+                    .report_error("Could not read Game Save", content);
+                return false;
+            }
+        };
+        info!(
+            "Importing {} buildings from game save {file_name}",
+            save.buildings.len()
+        );
+        world.root = save.build_tree(&database, grouping);
+        self.insert_new_world(world)
     }
 
-    /// Message handler for FinishUploadReplaceExisting.
-    fn finish_upload_replace_existing(&mut self, world_id: WorldId, uploaded_world: World) -> bool {
-        /// Helper for when the current world is missing from the world manager or from storage.
-        /// This re-inserts the world using the given ID, clobbering any existing world manager
-        /// entry and saved world state without creating an undo state.
-        ///
-        /// This should only be used when we already know that the world is missing or failed to
-        /// load.
-        fn upload_without_undo(this: &mut WorldManager, world_id: WorldId, uploaded_world: World) {
-            // Save the current world before proceeding.
-            this.world.try_save_if_unsaved();
-
-            let entry = this.worlds.entry(world_id);
-            info!(
-                "While re-inserting, the entry for world {world_id:?} was{}found in the world \
-                list.",
-                entry.exists().then_some(" ").unwrap_or(" not ")
-            );
-            entry.insert_or_update_and_select(uploaded_world.metadata());
-            this.set_world_inner(WorldTracker::unsaved(
-                uploaded_world,
-                world_id,
-                this.error_reporter.clone(),
-            ));
+    /// Message handler for OpenSharedView.
+    fn open_shared_view(
+        &mut self,
+        ctx: &Context<Self>,
+        view: Result<SharedView, DecodeSharedViewError>,
+    ) -> bool {
+        match view {
+            Ok(SharedView::FullTree { world }) => {
+                info!("Opening shared world {}", world.name());
+                // The shared world isn't added to the world list, and never gets saved unless the
+                // user saves a copy.
+                let mut shared =
+                    WorldTracker::unsaved(world, WorldId::new(), self.error_reporter.clone());
+                shared.set_read_only();
+                self.set_world_inner(shared);
+                if self.read_only.mode().is_none() {
+                    self.read_only = ReadOnly(Some(ReadOnlyMode::SharedLink));
+                }
+                true
+            }
+            Ok(SharedView::Summary {
+                name,
+                database,
+                balance,
+            }) => {
+                info!("Opening shared summary of {name}");
+                // Modals are shown outside of the user settings, so read the settings here.
+                let (user_settings, _) = ctx
+                    .link()
+                    .context::<Rc<UserSettings>>(Callback::noop())
+                    .expect("WorldManager must be nested in the UserSettingsManager");
+                let database = database
+                    .map_or_else(Database::load_latest, |selector| selector.load_database());
+                let content = html! {
+                    <>
+                    <p>{"The link you opened shares only the overall balance of this world, not \
+                    its groups and buildings."}</p>
+                    {view_summary(&balance, &database, &user_settings.number_display.balance)}
+                    </>
+                };
+                self.error_reporter.show(
+                    "SharedSummary",
+                    format!("Shared Summary of {name}"),
+                    content,
+                );
+                false
+            }
+            Err(e) => {
+                warn!("Unable to open share link: {e}");
+                let content = html! {
+                    <>
+                    <p>{"We were unable to open the world from the link you followed. The link \
+                    may have been cut off when it was copied. If you believe this is a bug, you \
+                    can "}{file_a_bug()}{". If you do file a bug, please include this error \
+                    message:"}</p>
+                    <pre>{e.to_string()}</pre>
+                    </>
+                };
+                self.error_reporter
+                    .report_error("Could not open Shared Link", content);
+                false
+            }
+        }
+    }
+
+    /// Message handler for SaveSharedCopy.
+    fn save_shared_copy(&mut self) -> bool {
+        if self.read_only.mode() != Some(ReadOnlyMode::SharedLink) {
+            return false;
+        }
+        self.read_only = ReadOnly::default();
+        let world = (*self.world).clone();
+        self.insert_new_world(world)
+    }
+
+    /// Message handler for RestoreArchive.
+    fn restore_archive(
+        &mut self,
+        worlds: Vec<(Option<WorldId>, World)>,
+        selected: Option<WorldId>,
+        templates: TemplateList,
+        histories: Vec<WorldHistory>,
+    ) -> bool {
+        // Save the current world before proceeding, since the archive may replace it.
+        self.world.try_save_if_unsaved();
+
+        let current = self.worlds.selected_id();
+        let mut replaced_current = false;
+        for (id, world) in worlds {
+            let list = self.worlds.mutate_and_mark_dirty();
+            let entry = match id {
+                Some(id) => list.entry(id),
+                None => WorldEntry::Absent(list.allocate_new_id()),
+            };
+            let id = entry.id();
+            replaced_current |= id == current;
+            entry.insert_or_update_and_select(world.metadata());
+            // Each world is written as soon as it is restored so only the selected world needs to
+            // stay in memory.
+            WorldTracker::unsaved(world, id, self.error_reporter.clone()).try_save_if_unsaved();
+        }
+
+        // Histories are only in archives which record them, so a world restored from an older
+        // archive keeps the history it already had.
+        for history in histories {
+            if self.worlds.get(history.id).is_none() {
+                continue;
+            }
+            if let Err(e) = checkpoints::replace(history.id, &history.checkpoints) {
+                warn!(
+                    "Unable to restore checkpoints of world {:?}: {e}",
+                    history.id
+                );
+            }
+            if let Err(e) = snapshots::replace(history.id, &history.snapshots) {
+                warn!("Unable to restore snapshots of world {:?}: {e}", history.id);
+            }
+        }
+
+        if !templates.is_empty() {
+            self.templates.mutate_and_mark_dirty().merge(&templates);
+            self.templates.try_save_if_unsaved();
+        }
+
+        let target = selected
+            .filter(|&id| self.worlds.get(id).is_some())
+            .unwrap_or(current);
+        if let Some(mut entry) = self.worlds.mutate_and_mark_dirty().get_mut(target) {
+            entry.select();
+        }
+        if target != current || replaced_current {
+            match load_world(target) {
+                Ok(world) => self.set_world_inner(WorldTracker::saved(
+                    world,
+                    target,
+                    self.error_reporter.clone(),
+                )),
+                Err(e) => {
+                    warn!("Unable to load world {target:?} after restoring an archive: {e}");
+                    if let Some(mut entry) =
+                        self.worlds.mutate_without_marking_dirty().get_mut(target)
+                    {
+                        entry.meta_mut().load_error = true;
+                    }
+                }
+            }
+        }
+        self.worlds.try_save_if_unsaved();
+        true
+    }
+
+    /// Message handler for FinishUploadAsNew.
+    fn finish_upload_as_new(&mut self, uploaded_world: World) -> bool {
+        self.insert_new_world(uploaded_world)
+    }
+
+    /// Message handler for VerifySave. Never needs a redraw.
+    fn verify_save(&mut self) -> bool {
+        if self.save_mismatch_reported || Date::now() - self.last_activity < SAVE_VERIFY_IDLE_MILLIS
+        {
+            return false;
+        }
+        let problem = match self.world.matches_storage(&self.database) {
+            Ok(true) => return false,
+            Ok(false) => "The copy of this world saved in your browser's storage no longer \
+                matches the world you are viewing."
+                .to_owned(),
+            Err(StorageError::KeyNotFound(_)) => {
+                "This world is no longer present in your browser's storage.".to_owned()
+            }
+            Err(e) => format!("The saved copy of this world could not be read back: {e}"),
+        };
+        warn!(
+            "Save verification failed for world {:?}: {problem}",
+            self.worlds.selected_id()
+        );
+        self.save_mismatch_reported = true;
+        let content = html! {
+            <>
+            <p>{problem}</p>
+            <p>{"This can happen if the app is open in another tab or window, or if a browser \
+            extension modified the site's storage. Making any change here will overwrite the \
+            stored copy with what you see now. Reloading the page will instead discard what you \
+            see and load the stored copy."}</p>
+            </>
+        };
+        self.error_reporter
+            .report_error("Saved World Changed", content);
+        false
+    }
+
+    /// Start fetching any database versions used by the current world or needed to migrate a v1
+    /// world which aren't available yet. Until they arrive, the latest database is used as a
+    /// stand-in.
+    fn fetch_missing_databases(&mut self, link: &Scope<Self>) {
+        let pinned_groups = self
+            .world
+            .root
+            .iter()
+            .filter_map(|node| node.group().and_then(|group| group.database));
+        let needed = DatabaseVersionSelector::for_choice(&self.world.database)
+            .map(DatabaseVersionSelector::select_version)
+            .into_iter()
+            .chain(pinned_groups)
+            .chain(self.v1_databases_pending.iter().copied());
+        for version in needed {
+            if !version.is_available() && self.fetching_databases.insert(version) {
+                info!("Fetching database {version}");
+                let link = link.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let result = fetch_database(version).await;
+                    link.send_message(Msg::DatabaseFetched { version, result });
+                });
+            }
+        }
+    }
+
+    /// Message handler for StorageChanged. Picks up changes another tab made to the world list,
+    /// the current world, the templates, or the clipboard, so that this tab doesn't overwrite them
+    /// with stale copies on its next save.
+    fn storage_changed(&mut self, key: String) -> bool {
+        let current = self.worlds.selected_id();
+        if key == WORLD_MAP_KEY {
+            let mut worlds = match load_worlds_list() {
+                Ok(worlds) => worlds,
+                Err(e) => {
+                    warn!("Unable to load the world list changed by another tab: {e}");
+                    return false;
+                }
+            };
+            // Which world is selected is shared between tabs, but this tab should stay on its own
+            // world. This doesn't need to be saved since the other tab's choice is just as good.
+            let selected = worlds.get_mut(current).map(|mut entry| entry.select());
+            self.worlds = WorldListTracker::saved(worlds, self.error_reporter.clone());
+            if selected.is_none() {
+                // Another tab deleted this world, so follow it to whichever world it switched to.
+                let new_id = self.worlds.selected_id();
+                match load_world(new_id) {
+                    Ok(world) => self.set_world_inner(WorldTracker::saved(
+                        world,
+                        new_id,
+                        self.error_reporter.clone(),
+                    )),
+                    Err(e) => warn!("Unable to load world {new_id:?} selected by another tab: {e}"),
+                }
+                self.error_reporter.report_error(
+                    "World deleted in another tab",
+                    html! {
+                        <p>{"The world you were viewing was deleted in another tab, so we \
+                        switched to the world that tab is using."}</p>
+                    },
+                );
+            }
+            true
+        } else if key == current.as_legacy_dotted().to_string() {
+            if !self.world.is_saved() {
+                // Reloading would throw away changes which haven't been saved, so leave it to the
+                // user to decide which copy to keep.
+                self.error_reporter.report_error(
+                    "World changed in another tab",
+                    html! {
+                        <p>{"This world was changed in another tab while this tab had changes \
+                        which couldn't be saved. Making another change here will overwrite the \
+                        other tab's changes. Reload this page to use the other tab's changes \
+                        instead."}</p>
+                    },
+                );
+                return false;
+            }
+            match load_world(current) {
+                Ok(world) => {
+                    info!("Reloading world {current:?} after it was changed in another tab");
+                    self.set_world_inner(WorldTracker::saved(
+                        world,
+                        current,
+                        self.error_reporter.clone(),
+                    ));
+                    true
+                }
+                Err(StorageError::KeyNotFound(_)) => false,
+                Err(e) => {
+                    warn!("Unable to load world {current:?} changed by another tab: {e}");
+                    false
+                }
+            }
+        } else if key == TEMPLATES_KEY {
+            self.templates =
+                TemplateListTracker::saved(load_templates(), self.error_reporter.clone());
+            true
+        } else if key == CLIPBOARD_KEY {
+            self.clipboard = ClipboardContent::load().map(RefEqRc::new);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Migrate the world stored by v1 of the app now that the databases it may use have been
+    /// fetched, replacing the empty world shown while waiting for them.
+    fn finish_v1_migration(&mut self) {
+        let Some(v1world) = v1storage::try_load_v1() else {
+            self.worlds.set_paused(false);
+            self.world.set_paused(false);
+            return;
+        };
+        let id = self.worlds.selected_id();
+        let (worlds, world) = migrate_v1_world(
+            v1world,
+            id,
+            &self.user_settings_dispatcher,
+            &self.error_reporter,
+            false,
+        );
+        self.worlds = worlds;
+        self.set_world_inner(world);
+    }
+
+    /// Message handler for DatabaseFetched.
+    fn database_fetched(
+        &mut self,
+        version: DatabaseVersion,
+        result: Result<(), FetchDatabaseError>,
+    ) -> bool {
+        if self.v1_databases_pending.remove(&version) {
+            if let Err(e) = &result {
+                // The v1 database is kept as a custom database if it can't be matched, so nothing
+                // is lost.
+                warn!("Failed to fetch database {version} to migrate the v1 world: {e}");
+            }
+            if self.v1_databases_pending.is_empty() {
+                self.finish_v1_migration();
+            }
+            return true;
+        }
+        match result {
+            Ok(()) => {
+                info!("Fetched database {version}");
+                // Rebuilding to replace the stand-in database can be redone on any load, so it
+                // doesn't need to be saved.
+                self.database = self.world.post_load();
+                true
+            }
+            Err(e) => {
+                // Leave the version in fetching_databases so we don't retry in a loop.
+                warn!("Failed to fetch database {version}: {e}");
+                let content = html! {
+                    <>
+                    <p>{"We were unable to download the "}{version.name()}{" database, so the \
+                    latest database is being used in its place for now. Check your connection \
+                    and reload the page to try again."}</p>
+                    <pre>{e.to_string()}</pre>
+                    </>
+                };
+                self.error_reporter
+                    .report_error("Unable to Download Database", content);
+                false
+            }
+        }
+    }
+
+    /// Message handler for SetSyncConfig.
+    fn set_sync_config(&mut self, config: SyncConfig) -> bool {
+        if self.sync_config == config {
+            return false;
+        }
+        if let Err(e) = config.save() {
+            warn!("Unable to save sync settings: {e}");
+        }
+        // A different server won't have the versions or worlds we already found.
+        if self.sync_config.url != config.url {
+            self.last_synced = None;
+            self.remote_worlds = RemoteWorlds::NotListed;
+        }
+        self.sync_config = config;
+        self.sync_status = SyncStatus::Idle;
+        true
+    }
+
+    /// Upload the current world if sync is enabled, the app has been idle long enough, and there
+    /// isn't an unresolved conflict.
+    fn auto_sync(&mut self, link: &Scope<Self>) -> bool {
+        if !self.sync_config.enabled
+            || self.sandbox.is_some()
+            || self.sync_status == SyncStatus::Conflict
+            || Date::now() - self.last_activity < SAVE_VERIFY_IDLE_MILLIS
+        {
+            return false;
+        }
+        self.sync_world(link, false)
+    }
+
+    /// Message handler for SyncWorld. Starts uploading the current world, unless it is unchanged
+    /// since it was last synced.
+    fn sync_world(&mut self, link: &Scope<Self>, overwrite: bool) -> bool {
+        if !self.sync_config.is_configured() || self.sync_status == SyncStatus::Syncing {
+            return false;
+        }
+        let id = self.worlds.selected_id();
+        let last_synced = self.last_synced.as_mut().filter(|synced| synced.id == id);
+        // Comparing the shared parts of the world is cheap, so idle ticks for a world which
+        // hasn't been touched don't need to serialize it.
+        if !overwrite
+            && last_synced
+                .as_ref()
+                .is_some_and(|synced| synced.world.shares_content(&self.world))
+        {
+            return false;
+        }
+        let world = (*self.world).clone();
+        let json: Rc<str> = match serde_json::to_string(&SaveFile::new(id, world.clone())) {
+            Ok(json) => json.into(),
+            Err(e) => {
+                warn!("Unable to serialize world {id:?} for sync: {e}");
+                return false;
+            }
+        };
+        if let Some(synced) = last_synced {
+            if !overwrite && synced.json == json {
+                // Rebuilt but not changed, so remember the new parts to skip serializing next
+                // time.
+                synced.world = world;
+                return false;
+            }
+        }
+        info!("Uploading world {id:?} to the sync server");
+        self.sync_status = SyncStatus::Syncing;
+        let config = self.sync_config.clone();
+        let expected = self.remote_versions.get(id).map(str::to_owned);
+        let link = link.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = upload_world(&config, id, &json, expected.as_deref(), overwrite).await;
+            link.send_message(Msg::SyncUploaded {
+                id,
+                world,
+                json,
+                result,
+            });
+        });
+        true
+    }
 
-            this.world.try_save_if_unsaved();
-            this.worlds.try_save_if_unsaved();
+    /// Message handler for SyncUploaded.
+    fn sync_uploaded(
+        &mut self,
+        id: WorldId,
+        world: World,
+        json: Rc<str>,
+        result: Result<Option<String>, SyncError>,
+    ) -> bool {
+        match result {
+            Ok(etag) => {
+                if let Err(e) = self.remote_versions.set(id, etag) {
+                    warn!("Unable to save the remote version of world {id:?}: {e}");
+                }
+                self.last_synced = Some(LastSynced { id, world, json });
+                self.sync_status = SyncStatus::Synced;
+            }
+            Err(SyncError::Conflict) => {
+                warn!("Remote copy of world {id:?} changed since it was last synced");
+                self.sync_status = SyncStatus::Conflict;
+                let content = html! {
+                    <>
+                    <p>{"The copy of this world on your sync server was changed since this \
+                    browser last synced it, probably from another computer. To avoid losing \
+                    those changes, automatic sync is paused for this world."}</p>
+                    <p>{"Open the Sync window to either download the remote copy, replacing the \
+                    world here (you can still undo back to it), or overwrite the remote copy with \
+                    the world here."}</p>
+                    </>
+                };
+                self.error_reporter.report_error("Sync Conflict", content);
+            }
+            Err(e) => {
+                warn!("Unable to sync world {id:?}: {e}");
+                self.sync_status = SyncStatus::Failed(e.to_string().into());
+            }
+        }
+        true
+    }
+
+    /// Message handler for DownloadRemoteWorld.
+    fn download_remote_world(&mut self, link: &Scope<Self>, id: WorldId) -> bool {
+        if !self.sync_config.is_configured() || self.sync_status == SyncStatus::Syncing {
+            return false;
+        }
+        info!("Downloading world {id:?} from the sync server");
+        self.sync_status = SyncStatus::Syncing;
+        let config = self.sync_config.clone();
+        let link = link.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = download_world(&config, id).await;
+            link.send_message(Msg::RemoteWorldDownloaded { id, result });
+        });
+        true
+    }
+
+    /// Message handler for RemoteWorldDownloaded. Replaces the world with the remote copy, keeping
+    /// an undo state for the local copy, or adds it if this browser doesn't have it yet. Either
+    /// way the downloaded world is selected.
+    fn remote_world_downloaded(
+        &mut self,
+        id: WorldId,
+        result: Result<(SaveFile, Option<String>), SyncError>,
+    ) -> bool {
+        let (save_file, etag) = match result {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                warn!("Unable to download world {id:?}: {e}");
+                self.sync_status = SyncStatus::Failed(e.to_string().into());
+                return true;
+            }
+        };
+        let world = match world_from_save(save_file) {
+            Some(world) => world,
+            None => {
+                warn!("Remote copy of world {id:?} is not a supported world");
+                self.sync_status = SyncStatus::Failed(
+                    "The remote copy of this world is not in a supported format".into(),
+                );
+                return true;
+            }
+        };
+        if let Err(e) = self.remote_versions.set(id, etag) {
+            warn!("Unable to save the remote version of world {id:?}: {e}");
+        }
+        // The downloaded world is what the server has, so there's nothing new to upload.
+        self.last_synced = serde_json::to_string(&SaveFile::new(id, world.clone()))
+            .ok()
+            .map(|json| LastSynced {
+                id,
+                world: world.clone(),
+                json: json.into(),
+            });
+        if self.worlds.get(id).is_some() {
+            self.finish_upload_replace_existing(id, world);
+        } else {
+            info!("Importing world {id:?} from the sync server");
+            self.upload_without_undo(id, world);
+        }
+        self.sync_status = SyncStatus::Synced;
+        true
+    }
+
+    /// Message handler for ListRemoteWorlds.
+    fn list_remote_worlds(&mut self, link: &Scope<Self>) -> bool {
+        if !self.sync_config.is_configured() || self.remote_worlds == RemoteWorlds::Listing {
+            return false;
+        }
+        self.remote_worlds = RemoteWorlds::Listing;
+        let config = self.sync_config.clone();
+        let local_names: BTreeMap<_, _> = self
+            .worlds
+            .iter()
+            .map(|world| (world.id(), world.meta().name.clone()))
+            .collect();
+        let link = link.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = list_remote_worlds(&config, &local_names).await;
+            link.send_message(Msg::RemoteWorldsListed { result });
+        });
+        true
+    }
+
+    /// Message handler for RemoteWorldsListed.
+    fn remote_worlds_listed(&mut self, result: Result<Vec<RemoteWorld>, SyncError>) -> bool {
+        self.remote_worlds = match result {
+            Ok(worlds) => RemoteWorlds::Listed(worlds.into()),
+            Err(e) => {
+                warn!("Unable to list remote worlds: {e}");
+                RemoteWorlds::Failed(e.to_string().into())
+            }
+        };
+        true
+    }
+
+    /// Message handler for RestoreSnapshot. Replaces the world with the snapshot the same way as
+    /// uploading over it, so the restore can be undone.
+    fn restore_snapshot(&mut self, world_id: WorldId, taken: f64) -> bool {
+        match snapshots::find(world_id, taken) {
+            Ok(Some(snapshot)) => self.finish_upload_replace_existing(world_id, snapshot.world),
+            Ok(None) => {
+                warn!("Snapshot {taken} of world {world_id:?} no longer exists");
+                self.error_reporter.report_error(
+                    "Snapshot not found",
+                    html! {
+                        <p>{"The snapshot you chose is no longer stored, most likely because a \
+                        newer snapshot replaced it or its space was needed to save a world."}</p>
+                    },
+                );
+                false
+            }
+            Err(e) => {
+                warn!("Unable to load snapshots of world {world_id:?}: {e}");
+                self.error_reporter.report_error(
+                    "Unable to load snapshot",
+                    html! {
+                        <>
+                        <p>{"We were unable to read the snapshots of this world. This may be a \
+                        bug, and you can "}{file_a_bug()}{". If you file a bug, please include \
+                        this message:"}</p>
+                        <pre>{"Unable to load snapshots: "}{e}</pre>
+                        </>
+                    },
+                );
+                false
+            }
+        }
+    }
+
+    /// Message handler for RestoreCheckpoint. Replaces the world with the checkpoint the same way
+    /// as replacing it with an upload, so the current state is kept in the undo history.
+    fn restore_checkpoint(&mut self, world_id: WorldId, taken: f64) -> bool {
+        match checkpoints::find(world_id, taken) {
+            Ok(Some(checkpoint)) => self.finish_upload_replace_existing(world_id, checkpoint.world),
+            Ok(None) => {
+                warn!("Checkpoint {taken} of world {world_id:?} no longer exists");
+                self.error_reporter.report_error(
+                    "Checkpoint not found",
+                    html! {
+                        <p>{"The checkpoint you chose is no longer stored. It may have been \
+                        deleted in another tab."}</p>
+                    },
+                );
+                false
+            }
+            Err(e) => {
+                warn!("Unable to load checkpoints of world {world_id:?}: {e}");
+                self.error_reporter.report_error(
+                    "Unable to load checkpoint",
+                    html! {
+                        <>
+                        <p>{"We were unable to read the checkpoints of this world. This may be \
+                        a bug, and you can "}{file_a_bug()}{". If you file a bug, please include \
+                        this message:"}</p>
+                        <pre>{"Unable to load checkpoints: "}{e}</pre>
+                        </>
+                    },
+                );
+                false
+            }
         }
+    }
+
+    /// Helper for when a world is missing from the world manager or from storage. This inserts
+    /// the world using the given ID, clobbering any existing world manager entry and saved world
+    /// state without creating an undo state.
+    ///
+    /// This should only be used when we already know that the world is missing or failed to load.
+    fn upload_without_undo(&mut self, world_id: WorldId, uploaded_world: World) {
+        // Save the current world before proceeding.
+        self.world.try_save_if_unsaved();
+
+        let entry = self.worlds.entry(world_id);
+        info!(
+            "While re-inserting, the entry for world {world_id:?} was{}found in the world list.",
+            entry.exists().then_some(" ").unwrap_or(" not ")
+        );
+        entry.insert_or_update_and_select(uploaded_world.metadata());
+        self.set_world_inner(WorldTracker::unsaved(
+            uploaded_world,
+            world_id,
+            self.error_reporter.clone(),
+        ));
+
+        self.world.try_save_if_unsaved();
+        self.worlds.try_save_if_unsaved();
+    }
 
+    /// Message handler for FinishUploadReplaceExisting.
+    fn finish_upload_replace_existing(&mut self, world_id: WorldId, uploaded_world: World) -> bool {
         match self.try_switch_world(world_id) {
             Ok(_) => {
                 let old_world = mem::replace(self.world.mutate_and_mark_dirty(), uploaded_world);
@@ -926,7 +2411,7 @@ impl WorldManager {
                     "Uploaded world {world_id:?} was not found in the world list when we tried to \
                     complete the upload. Re-inserting it."
                 );
-                upload_without_undo(self, world_id, uploaded_world);
+                self.upload_without_undo(world_id, uploaded_world);
                 true
             }
             Err(SwitchWorldError::StorageError(StorageError::KeyNotFound(_))) => {
@@ -934,7 +2419,7 @@ impl WorldManager {
                     "Uploaded world {world_id:?} didn't have an entry in storage, so no undo will \
                     be available"
                 );
-                upload_without_undo(self, world_id, uploaded_world);
+                self.upload_without_undo(world_id, uploaded_world);
                 true
             }
             Err(SwitchWorldError::StorageError(e)) => {
@@ -964,7 +2449,25 @@ impl WorldManager {
     /// Creates the [`DbController`] for the current db.
     fn db_controller(&self) -> DbController {
         DbController {
-            current: self.world.database.version_selector(),
+            current: DatabaseVersionSelector::for_choice(&self.world.database),
+            link: self.link.clone(),
+        }
+    }
+
+    /// Creates the [`SyncController`] for the current sync state.
+    fn sync_controller(&self) -> SyncController {
+        SyncController {
+            config: self.sync_config.clone(),
+            status: self.sync_status.clone(),
+            remote_worlds: self.remote_worlds.clone(),
+            link: self.link.clone(),
+        }
+    }
+
+    /// Creates the [`ClipboardController`] for the current clipboard content.
+    fn clipboard_controller(&self) -> ClipboardController {
+        ClipboardController {
+            content: self.clipboard.clone(),
             link: self.link.clone(),
         }
     }
@@ -974,6 +2477,10 @@ impl WorldManager {
         UndoController {
             has_undo: !self.undo_stack.is_empty(),
             has_redo: !self.redo_stack.is_empty(),
+            undo_len: self.undo_stack.len(),
+            redo_len: self.redo_stack.len(),
+            memory: self.undo_memory,
+            in_sandbox: self.sandbox.is_some(),
             link: self.link.clone(),
         }
     }
@@ -989,6 +2496,15 @@ impl Component for WorldManager {
             .link()
             .context::<UserSettingsDispatcher>(Callback::noop())
             .expect("WorldManager must be nested in the UserSettingsManager");
+        let (user_settings, user_settings_handle) = ctx
+            .link()
+            .context::<Rc<UserSettings>>(ctx.link().callback(|settings: Rc<UserSettings>| {
+                Msg::SetMaxUndo {
+                    max_undo: settings.undo.max_states,
+                }
+            }))
+            .expect("WorldManager must be nested in the UserSettingsManager");
+        let max_undo = user_settings.undo.max_states;
         let modal_dispatcher = Rc::new(RefCell::new(None));
         let (inner_dispatcher, modal_dispatcher_handle) = ctx
             .link()
@@ -1000,6 +2516,14 @@ impl Component for WorldManager {
         *modal_dispatcher.borrow_mut() = Some(inner_dispatcher);
         let error_reporter = WorldManagerModalWrapper { modal_dispatcher };
 
+        let shared_view = take_shared_view();
+        let read_only_flag = has_read_only_flag();
+        // Don't write anything while opening a shared world or with the read-only flag. Changes
+        // made while loading are still saved later if the user starts editing.
+        let defer_saves = shared_view.is_some() || read_only_flag;
+
+        // Database versions to fetch before the v1 world can be migrated.
+        let mut v1_databases_pending = BTreeSet::new();
         let (worlds, mut world) = match load_worlds_list() {
             Ok(worlds) => {
                 // World list is currently saved.
@@ -1081,44 +2605,49 @@ impl Component for WorldManager {
                 };
                 // The worlds list already existed on the system, so we can save if there are any
                 // changes or a new world.
-                world.try_save_if_unsaved();
-                worlds.try_save_if_unsaved();
+                if !defer_saves {
+                    world.try_save_if_unsaved();
+                    worlds.try_save_if_unsaved();
+                }
                 (worlds, world)
             }
             Err(StorageError::KeyNotFound(_)) => {
                 // If the world manager was not found, try to load from a v1 world or just start
-                // from scratch.
+                // from scratch. Nothing is saved while opening a shared world or with the
+                // read-only flag, so the v1 world is migrated on a later load instead of waiting
+                // for databases it needs.
                 let id = WorldId::new();
-                match v1storage::try_load_v1() {
-                    Some(v1world) => {
-                        // If there is a v1 world, we need to persist it under the v1.2.x world
-                        // storage keys.
-                        // In case we loaded a v1 world, try to init the empty balances state.
-                        // This will persist user metadata, which is fine because we loaded data
-                        // from storage already.
-                        #[allow(deprecated)]
-                        user_settings_dispatcher
-                            .maybe_init_from_world(v1world.global_metadata.hide_empty_balances);
-                        let mut world = WorldTracker::unsaved(v1world, id, error_reporter.clone());
-                        let mut worlds = WorldListTracker::unsaved(
-                            WorldList::new(id, world.metadata()),
-                            error_reporter.clone(),
-                        );
-                        // Since there was already a v1 world in the browser, we can persist
-                        // immediately.
-                        world.try_save_if_unsaved();
-                        worlds.try_save_if_unsaved();
-                        (worlds, world)
-                    }
+                if !defer_saves {
+                    v1_databases_pending =
+                        v1storage::versions_needed_for_v1().into_iter().collect();
+                }
+                let v1world = if v1_databases_pending.is_empty() {
+                    v1storage::try_load_v1()
+                } else {
+                    None
+                };
+                match v1world {
+                    Some(v1world) => migrate_v1_world(
+                        v1world,
+                        id,
+                        &user_settings_dispatcher,
+                        &error_reporter,
+                        defer_saves,
+                    ),
                     None => {
                         let world = World::new();
                         let worlds = WorldList::new(id, world.metadata());
                         // If nothing was already in storage, avoid saving unless the user
                         // interacts with the app.
-                        (
-                            WorldListTracker::unsaved(worlds, error_reporter.clone()),
-                            WorldTracker::unsaved(world, id, error_reporter.clone()),
-                        )
+                        let mut worlds = WorldListTracker::unsaved(worlds, error_reporter.clone());
+                        let mut world = WorldTracker::unsaved(world, id, error_reporter.clone());
+                        // While waiting to migrate the v1 world, saving this world would create a
+                        // world list and stop the migration from ever happening.
+                        if !v1_databases_pending.is_empty() {
+                            worlds.set_paused(true);
+                            world.set_paused(true);
+                        }
+                        (worlds, world)
                     }
                 }
             }
@@ -1155,34 +2684,138 @@ impl Component for WorldManager {
         };
         // The post-load operations should not create a new save, since they can be repeated on
         // every load.
-        let database = world.mutate_without_marking_dirty().post_load();
+        let database = world.post_load();
         let world_reader = WorldReader::new(worlds.selected_id(), world.clone());
+        let mut session_stats = SessionStats::default();
+        session_stats.switch_to(worlds.selected_id());
+        let save_verifier = {
+            let link = ctx.link().clone();
+            Interval::new(SAVE_VERIFY_INTERVAL_MILLIS, move || {
+                link.send_message(Msg::VerifySave)
+            })
+        };
 
-        Self {
+        let storage_listener = {
+            let link = ctx.link().clone();
+            EventListener::new(&gloo::utils::window(), "storage", move |event| {
+                // The key is None when another tab clears all of storage, which there's no
+                // sensible way to follow.
+                if let Some(key) = event.unchecked_ref::<StorageEvent>().key() {
+                    link.send_message(Msg::StorageChanged { key });
+                }
+            })
+        };
+
+        let mut manager = Self {
             worlds,
+            templates: TemplateListTracker::saved(load_templates(), error_reporter.clone()),
             world,
             database,
-            undo_stack: VecDeque::with_capacity(MAX_UNDO),
-            redo_stack: VecDeque::with_capacity(MAX_UNDO),
+            undo_stack: VecDeque::with_capacity(max_undo),
+            redo_stack: VecDeque::new(),
+            max_undo,
+            undo_memory: TreeMemory::default(),
+            session_stats,
+            last_activity: Date::now(),
+            save_mismatch_reported: false,
+            storage_warning_reported: false,
+            _save_verifier: save_verifier,
+            _storage_listener: storage_listener,
+            snapshot_schedule: SnapshotSchedule::new(Date::now()),
+            fetching_databases: BTreeSet::new(),
+            v1_databases_pending,
+            sync_config: SyncConfig::load(),
+            remote_versions: RemoteVersions::load(),
+            last_synced: None,
+            sync_status: SyncStatus::Idle,
+            remote_worlds: RemoteWorlds::NotListed,
+            read_only: ReadOnly::default(),
+            sandbox: None,
+            clipboard: ClipboardContent::load().map(RefEqRc::new),
+            user_settings_dispatcher,
+            sent_settings_overrides: None,
             link: Link::new(ctx.link().clone()),
             world_reader,
             error_reporter,
             _modal_dispatcher_handle: modal_dispatcher_handle,
+            _user_settings_handle: user_settings_handle,
+        };
+        if read_only_flag {
+            manager.read_only = ReadOnly(Some(ReadOnlyMode::Flag));
+            manager.world.set_read_only();
+            manager.worlds.set_read_only();
+            manager.templates.set_read_only();
         }
+        manager.fetch_missing_databases(ctx.link());
+        manager.send_settings_overrides(ctx.link());
+        if !defer_saves {
+            manager.empty_expired_trash();
+            manager.warn_if_storage_low();
+        }
+        if let Some(view) = shared_view {
+            ctx.link().send_message(Msg::OpenSharedView(view));
+        }
+        manager
     }
 
     /// Update the WorldManager.
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        if !msg.is_background() {
+            self.last_activity = Date::now();
+            self.save_mismatch_reported = false;
+        }
+        if self.read_only.is_read_only() && !msg.allowed_when_read_only() {
+            return false;
+        }
+        if self.sandbox.is_some() && !msg.allowed_in_sandbox() {
+            warn!("Ignoring {msg:?} while a sandbox is open");
+            self.error_reporter.report_error(
+                "Sandbox Open",
+                html! {
+                    <p>{"Apply or discard the changes in the sandbox before switching or \
+                    replacing this world."}</p>
+                },
+            );
+            return false;
+        }
+        let previous_world = self.worlds.selected_id();
         let redraw = match msg {
             Msg::SetRoot { root } => self.set_root(root),
             Msg::UpdateNodeMeta { id, meta } => self.update_node_meta(id, meta),
             Msg::BatchUpdateNodeMeta(updates) => self.batch_update_node_meta(updates),
+            Msg::ExpandToDepth(depth) => self.expand_to_depth(depth),
+            Msg::UpdateItemMeta { id, meta } => self.update_item_meta(id, meta),
+            Msg::SetProgression(progression) => self.set_progression(progression),
+            Msg::SetSettingsOverrides(overrides) => self.set_settings_overrides(overrides),
+            Msg::SetHiddenColumns(hidden_columns) => self.set_hidden_columns(hidden_columns),
             Msg::Undo => self.undo(),
             Msg::Redo => self.redo(),
+            Msg::OpenSandbox => self.open_sandbox(),
+            Msg::CloseSandbox { apply } => self.close_sandbox(apply),
             Msg::SetDb(selector) => self.set_db(selector),
             Msg::SetWorld(world_id) => self.set_world(world_id),
             Msg::DeleteWorld(world_id) => self.delete_world(world_id),
+            Msg::RestoreWorld(world_id) => self.restore_world(world_id),
+            Msg::PurgeWorld(world_id) => self.purge_world(world_id),
             Msg::CreateWorld => self.create_world(),
+            Msg::CreateWorldFromTemplate(choice) => self.create_world_from_template(choice),
+            Msg::SaveTemplate(world_id) => self.save_template(world_id),
+            Msg::DeleteTemplate(id) => self.delete_template(id),
+            Msg::ImportGroups { from, groups } => self.import_groups(from, groups),
+            Msg::CopyToClipboard(content) => self.copy_to_clipboard(content),
+            Msg::RestoreArchive {
+                worlds,
+                selected,
+                templates,
+                histories,
+            } => self.restore_archive(worlds, selected, templates, histories),
+            Msg::ImportGameSave {
+                file_name,
+                data,
+                grouping,
+            } => self.import_game_save(file_name, data, grouping),
+            Msg::OpenSharedView(view) => self.open_shared_view(ctx, view),
+            Msg::SaveSharedCopy => self.save_shared_copy(),
             Msg::MarkError(id) => self.mark_error(id),
             Msg::UploadWorld {
                 file_name,
@@ -1194,7 +2827,38 @@ impl Component for WorldManager {
                 world_id,
                 uploaded_world,
             } => self.finish_upload_replace_existing(world_id, uploaded_world),
+            Msg::RestoreSnapshot { world_id, taken } => self.restore_snapshot(world_id, taken),
+            Msg::RestoreCheckpoint { world_id, taken } => self.restore_checkpoint(world_id, taken),
+            Msg::VerifySave => {
+                self.snapshot_if_due();
+                let verify_redraw = self.verify_save();
+                self.auto_sync(ctx.link()) || verify_redraw
+            }
+            Msg::StorageChanged { key } => self.storage_changed(key),
+            Msg::DatabaseFetched { version, result } => self.database_fetched(version, result),
+            Msg::SetSyncConfig(config) => self.set_sync_config(config),
+            Msg::SyncWorld { overwrite } => self.sync_world(ctx.link(), overwrite),
+            Msg::SyncUploaded {
+                id,
+                world,
+                json,
+                result,
+            } => self.sync_uploaded(id, world, json, result),
+            Msg::DownloadRemoteWorld(id) => self.download_remote_world(ctx.link(), id),
+            Msg::RemoteWorldDownloaded { id, result } => self.remote_world_downloaded(id, result),
+            Msg::ListRemoteWorlds => self.list_remote_worlds(ctx.link()),
+            Msg::RemoteWorldsListed { result } => self.remote_worlds_listed(result),
+            Msg::SetMaxUndo { max_undo } => self.set_max_undo(max_undo),
         };
+        if self.worlds.selected_id() != previous_world {
+            // A conflict or failure on one world says nothing about the next one.
+            if self.sync_status != SyncStatus::Syncing {
+                self.sync_status = SyncStatus::Idle;
+            }
+            self.snapshot_schedule = SnapshotSchedule::new(Date::now());
+        }
+        self.fetch_missing_databases(ctx.link());
+        self.send_settings_overrides(ctx.link());
         // This should be relatively cheap because all the content of the world is Rc'd.
         // This being held here does prevent the Rcs from ever successfully doing a Rc::make_mut,
         // but Yew holds Rcs for all these things anyway, so those make_mut calls don't work
@@ -1204,35 +2868,51 @@ impl Component for WorldManager {
         // elsewhere regardless if this replacement call causes them to be dropped.
         self.world_reader
             .set(self.worlds.selected_id(), self.world.clone());
+        self.session_stats.switch_to(self.worlds.selected_id());
         redraw
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
             <ContextProvider<WorldList> context={self.worlds.clone()}>
+            <ContextProvider<TemplateList> context={(*self.templates).clone()}>
             <ContextProvider<Database> context={self.database.clone()}>
             <ContextProvider<WorldReader> context={self.world_reader.clone()}>
             <ContextProvider<WorldRoot> context={WorldRoot(self.world.root.clone())}>
             <ContextProvider<NodeMetas> context={self.world.node_metadata.clone()}>
+            <ContextProvider<ItemMetas> context={self.world.item_metadata.clone()}>
+            <ContextProvider<Progression> context={self.world.progression}>
+            <ContextProvider<SettingsOverrides> context={self.world.settings_overrides.clone()}>
+            <ContextProvider<HiddenColumns> context={self.world.hidden_columns.clone()}>
+            <ContextProvider<SessionStats> context={self.session_stats.clone()}>
             <ContextProvider<Link> context={self.link.clone()}>
             <ContextProvider<UndoController> context={self.undo_controller()}>
             <ContextProvider<DbController> context={self.db_controller()}>
+            <ContextProvider<SyncController> context={self.sync_controller()}>
+            <ContextProvider<ClipboardController> context={self.clipboard_controller()}>
+            <ContextProvider<ReadOnly> context={self.read_only}>
                 {ctx.props().children.clone()}
+            </ContextProvider<ReadOnly>>
+            </ContextProvider<ClipboardController>>
+            </ContextProvider<SyncController>>
             </ContextProvider<DbController>>
             </ContextProvider<UndoController>>
             </ContextProvider<Link>>
+            </ContextProvider<SessionStats>>
+            </ContextProvider<HiddenColumns>>
+            </ContextProvider<SettingsOverrides>>
+            </ContextProvider<Progression>>
+            </ContextProvider<ItemMetas>>
             </ContextProvider<NodeMetas>>
             </ContextProvider<WorldRoot>>
             </ContextProvider<WorldReader>>
             </ContextProvider<Database>>
+            </ContextProvider<TemplateList>>
             </ContextProvider<WorldList>>
         }
     }
 }
 
-/// Maximum amount of undo history to keep.
-const MAX_UNDO: usize = 100;
-
 /// State tracked for undo/redo.
 struct UnReDoState {
     /// Database at this undo/redo version.
@@ -1241,17 +2921,83 @@ struct UnReDoState {
     root: Node,
 }
 
+/// World state kept while a sandbox is open.
+struct Sandbox {
+    /// Root and database of the world when the sandbox was opened.
+    original: UnReDoState,
+    /// Undo history from before the sandbox was opened, restored when it closes.
+    undo_stack: VecDeque<UnReDoState>,
+    /// Redo history from before the sandbox was opened, restored when it closes.
+    redo_stack: VecDeque<UnReDoState>,
+}
+
+/// How often to check whether the stored world still matches the world in memory.
+const SAVE_VERIFY_INTERVAL_MILLIS: u32 = 30_000;
+
+/// How long the app must go without any changes before save verification runs.
+const SAVE_VERIFY_IDLE_MILLIS: f64 = 10_000.0;
+
+/// How long deleted worlds are kept in the trash before being permanently deleted.
+const TRASH_RETENTION_MILLIS: f64 = 30.0 * 24.0 * 60.0 * 60_000.0;
+
+/// Delete everything stored for the given world.
+fn delete_world_storage(world_id: WorldId) {
+    chunks::delete(&world_id.as_legacy_dotted().to_string());
+    snapshots::delete(world_id);
+    checkpoints::delete(world_id);
+}
+
 /// Local storage key where the world list map should be stored/loaded.
 const WORLD_MAP_KEY: &str = "zstewart.satisfactorydb.state.world";
 
+/// Local storage key where saved templates are stored.
+const TEMPLATES_KEY: &str = "zstewart.satisfactorydb.state.templates";
+
 /// Load the world list.
 fn load_worlds_list() -> Result<WorldList, StorageError> {
     LocalStorage::get(WORLD_MAP_KEY)
 }
 
+/// Load the saved templates. If they can't be loaded, no templates are available, but the stored
+/// copy isn't overwritten unless a template is added or removed.
+fn load_templates() -> TemplateList {
+    match LocalStorage::get(TEMPLATES_KEY) {
+        Ok(templates) => templates,
+        Err(StorageError::KeyNotFound(_)) => TemplateList::default(),
+        Err(e) => {
+            warn!("Failed to load templates: {e}");
+            TemplateList::default()
+        }
+    }
+}
+
+/// Set up the world list and world for a world stored by v1 of the app, persisting them under
+/// the current storage keys unless saves are deferred.
+fn migrate_v1_world(
+    v1world: World,
+    id: WorldId,
+    user_settings_dispatcher: &UserSettingsDispatcher,
+    error_reporter: &WorldManagerModalWrapper,
+    defer_saves: bool,
+) -> (WorldListTracker, WorldTracker) {
+    // In case we loaded a v1 world, try to init the empty balances state. This will persist user
+    // metadata, which is fine because we loaded data from storage already.
+    #[allow(deprecated)]
+    user_settings_dispatcher.maybe_init_from_world(v1world.global_metadata.hide_empty_balances);
+    let mut world = WorldTracker::unsaved(v1world, id, error_reporter.clone());
+    let mut worlds =
+        WorldListTracker::unsaved(WorldList::new(id, world.metadata()), error_reporter.clone());
+    // Since there was already a v1 world in the browser, we can persist immediately.
+    if !defer_saves {
+        world.try_save_if_unsaved();
+        worlds.try_save_if_unsaved();
+    }
+    (worlds, world)
+}
+
 /// Load the world with the specified id.
 fn load_world(id: WorldId) -> Result<World, StorageError> {
-    let mut world: World = LocalStorage::get(id.as_legacy_dotted().to_string())?;
+    let mut world = chunks::load(&id.as_legacy_dotted().to_string())?;
     // Remove metadata from deleted groups that are definitely no longer in the
     // undo/redo history.
     world.node_metadata.prune(&world.root);
@@ -1300,16 +3046,88 @@ impl WorldListDispatcher {
         self.link.send_message(Msg::SetWorld(world_id));
     }
 
-    /// Permanently deletes this world. Does not trigger a confirmation.
+    /// Moves this world to the trash.
     pub fn delete_world(&self, world_id: WorldId) {
         self.link.send_message(Msg::DeleteWorld(world_id));
     }
 
+    /// Moves this world out of the trash.
+    pub fn restore_world(&self, world_id: WorldId) {
+        self.link.send_message(Msg::RestoreWorld(world_id));
+    }
+
+    /// Permanently deletes this world from the trash. Does not trigger a confirmation.
+    pub fn purge_world(&self, world_id: WorldId) {
+        self.link.send_message(Msg::PurgeWorld(world_id));
+    }
+
+    /// Replaces the world with the snapshot taken at the given time and switches to it.
+    pub fn restore_snapshot(&self, world_id: WorldId, taken: f64) {
+        self.link
+            .send_message(Msg::RestoreSnapshot { world_id, taken });
+    }
+
+    /// Replace the given world with one of its named checkpoints.
+    pub fn restore_checkpoint(&self, world_id: WorldId, taken: f64) {
+        self.link
+            .send_message(Msg::RestoreCheckpoint { world_id, taken });
+    }
+
     /// Creates a new empty world and switches to it.
     pub fn create_world(&self) {
         self.link.send_message(Msg::CreateWorld);
     }
 
+    /// Creates a new world from the chosen template and switches to it.
+    pub fn create_world_from_template(&self, choice: TemplateChoice) {
+        self.link.send_message(Msg::CreateWorldFromTemplate(choice));
+    }
+
+    /// Saves a copy of the world with the given ID as a template.
+    pub fn save_template(&self, world_id: WorldId) {
+        self.link.send_message(Msg::SaveTemplate(world_id));
+    }
+
+    /// Creates a new world from the buildings in a Satisfactory game save.
+    pub fn import_game_save(&self, file_name: String, data: Vec<u8>, grouping: SaveGrouping) {
+        self.link.send_message(Msg::ImportGameSave {
+            file_name,
+            data,
+            grouping,
+        });
+    }
+
+    /// Saves the world opened from a share link as a new world, so it can be edited.
+    pub fn save_shared_copy(&self) {
+        self.link.send_message(Msg::SaveSharedCopy);
+    }
+
+    /// Restores worlds and templates from an archive, replacing any worlds with the same IDs.
+    pub fn restore_archive(
+        &self,
+        worlds: Vec<(Option<WorldId>, World)>,
+        selected: Option<WorldId>,
+        templates: TemplateList,
+        histories: Vec<WorldHistory>,
+    ) {
+        self.link.send_message(Msg::RestoreArchive {
+            worlds,
+            selected,
+            templates,
+            histories,
+        });
+    }
+
+    /// Copies the given top-level groups from another world into the current world.
+    pub fn import_groups(&self, from: WorldId, groups: Vec<Uuid>) {
+        self.link.send_message(Msg::ImportGroups { from, groups });
+    }
+
+    /// Permanently deletes a saved template. Does not trigger a confirmation.
+    pub fn delete_template(&self, id: Uuid) {
+        self.link.send_message(Msg::DeleteTemplate(id));
+    }
+
     /// Create a new world from an uploaded file.
     pub fn upload_world(
         &self,
@@ -1325,6 +3143,13 @@ impl WorldListDispatcher {
     }
 }
 
+/// Gets the templates the user has saved.
+#[hook]
+pub fn use_templates() -> TemplateList {
+    use_context::<TemplateList>()
+        .expect("use_templates can only be used from within a child of WorldManager")
+}
+
 /// Gets the dispatcher used to manage the world list.
 #[hook]
 pub fn use_world_list_dispatcher() -> WorldListDispatcher {
@@ -1346,6 +3171,41 @@ pub fn use_world_root() -> Node {
         .clone()
 }
 
+/// Gets the per-item notes and targets of the world.
+#[hook]
+pub fn use_item_metas() -> ItemMetas {
+    use_context::<ItemMetas>()
+        .expect("use_item_metas can only be used from within a child of WorldManager")
+}
+
+/// Gets the user settings which the world keeps separately.
+#[hook]
+pub fn use_settings_overrides() -> SettingsOverrides {
+    use_context::<SettingsOverrides>()
+        .expect("use_settings_overrides can only be used from within a child of WorldManager")
+}
+
+/// Gets which columns of the node grid are hidden in the world.
+#[hook]
+pub fn use_hidden_columns() -> HiddenColumns {
+    use_context::<HiddenColumns>()
+        .expect("use_hidden_columns can only be used from within a child of WorldManager")
+}
+
+/// Gets which content has been unlocked in the world.
+#[hook]
+pub fn use_progression() -> Progression {
+    use_context::<Progression>()
+        .expect("use_progression can only be used from within a child of WorldManager")
+}
+
+/// Gets the edit and time statistics for the current session.
+#[hook]
+pub fn use_session_stats() -> SessionStats {
+    use_context::<SessionStats>()
+        .expect("use_session_stats can only be used from within a child of WorldManager")
+}
+
 /// Dispatcher used to make changes to the World.
 #[derive(Debug, Clone, PartialEq)]
 pub struct WorldDispatcher {
@@ -1368,6 +3228,42 @@ impl WorldDispatcher {
     pub fn batch_update_node_meta(&self, updates: HashMap<Uuid, NodeMeta>) {
         self.link.send_message(Msg::BatchUpdateNodeMeta(updates));
     }
+
+    /// Collapse every group in the world.
+    pub fn collapse_all(&self) {
+        self.link.send_message(Msg::ExpandToDepth(Some(0)));
+    }
+
+    /// Expand every group in the world.
+    pub fn expand_all(&self) {
+        self.link.send_message(Msg::ExpandToDepth(None));
+    }
+
+    /// Expand groups nested fewer than `depth` levels deep and collapse the rest.
+    pub fn expand_to_depth(&self, depth: usize) {
+        self.link.send_message(Msg::ExpandToDepth(Some(depth)));
+    }
+
+    /// Update the notes and targets of a single item.
+    pub fn update_item_meta(&self, id: ItemId, meta: ItemMeta) {
+        self.link.send_message(Msg::UpdateItemMeta { id, meta });
+    }
+
+    /// Set which content has been unlocked in the world.
+    pub fn set_progression(&self, progression: Progression) {
+        self.link.send_message(Msg::SetProgression(progression));
+    }
+
+    /// Set which user settings the world keeps separately.
+    pub fn set_settings_overrides(&self, overrides: SettingsOverrides) {
+        self.link.send_message(Msg::SetSettingsOverrides(overrides));
+    }
+
+    /// Set which columns of the node grid are hidden in the world.
+    pub fn set_hidden_columns(&self, hidden_columns: HiddenColumns) {
+        self.link
+            .send_message(Msg::SetHiddenColumns(hidden_columns));
+    }
 }
 
 /// Gets the world dispatcher.
@@ -1429,6 +3325,103 @@ pub fn use_db_controller() -> DbController {
         .expect("use_db_controller can only be used from within a child of the WorldManager")
 }
 
+/// Controller for syncing worlds with a remote server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncController {
+    /// Where and whether worlds are synced.
+    config: SyncConfig,
+    /// Progress of syncing the current world.
+    status: SyncStatus,
+    /// Worlds found on the sync server.
+    remote_worlds: RemoteWorlds,
+    /// Link used to send messages to the WorldManager.
+    link: Link,
+}
+
+impl SyncController {
+    /// Gets the current sync configuration.
+    pub fn config(&self) -> &SyncConfig {
+        &self.config
+    }
+
+    /// Gets the progress of syncing the current world.
+    pub fn status(&self) -> &SyncStatus {
+        &self.status
+    }
+
+    /// Changes where and whether worlds are synced.
+    pub fn set_config(&self, config: SyncConfig) {
+        self.link.send_message(Msg::SetSyncConfig(config));
+    }
+
+    /// Uploads the current world now, if it changed since it was last synced.
+    pub fn sync_now(&self) {
+        self.link.send_message(Msg::SyncWorld { overwrite: false });
+    }
+
+    /// Uploads the current world, replacing the remote copy even if it was changed elsewhere.
+    pub fn overwrite_remote(&self) {
+        self.link.send_message(Msg::SyncWorld { overwrite: true });
+    }
+
+    /// Gets the worlds found on the sync server.
+    pub fn remote_worlds(&self) -> &RemoteWorlds {
+        &self.remote_worlds
+    }
+
+    /// Replaces the given world with the remote copy, or imports it if it isn't in this browser.
+    pub fn download_remote(&self, id: WorldId) {
+        self.link.send_message(Msg::DownloadRemoteWorld(id));
+    }
+
+    /// Lists the worlds on the sync server.
+    pub fn list_remote(&self) {
+        self.link.send_message(Msg::ListRemoteWorlds);
+    }
+}
+
+/// Controller for the clipboard shared by all worlds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardController {
+    /// Node copied to the clipboard, if any.
+    content: Option<RefEqRc<ClipboardContent>>,
+    /// Link used to send messages to the WorldManager.
+    link: Link,
+}
+
+impl ClipboardController {
+    /// Gets the node copied to the clipboard, if any.
+    pub fn content(&self) -> Option<&ClipboardContent> {
+        self.content.as_deref()
+    }
+
+    /// Replaces the content of the clipboard.
+    pub fn copy(&self, content: ClipboardContent) {
+        self.link.send_message(Msg::CopyToClipboard(content));
+    }
+}
+
+/// Gets the ClipboardController from the context.
+#[hook]
+pub fn use_clipboard() -> ClipboardController {
+    use_context::<ClipboardController>()
+        .expect("use_clipboard can only be used from within a child of the WorldManager")
+}
+
+/// Gets whether the current world is read-only.
+#[hook]
+pub fn use_read_only() -> ReadOnly {
+    use_context::<ReadOnly>()
+        .expect("use_read_only can only be used from within a child of the WorldManager")
+}
+
+/// Gets the SyncController from the context.
+#[hook]
+pub fn use_sync_controller() -> SyncController {
+    use_context::<SyncController>()
+        .expect("use_sync_controller can only be used from within a child of the WorldManager")
+}
+
 /// Controller for the undo state.
 #[derive(Debug, Clone, PartialEq)]
 pub struct UndoController {
@@ -1436,6 +3429,14 @@ pub struct UndoController {
     has_undo: bool,
     /// Whether there was any state available to redo.
     has_redo: bool,
+    /// Number of states available to undo.
+    undo_len: usize,
+    /// Number of states available to redo.
+    redo_len: usize,
+    /// Memory used by the undo history beyond what the current world uses.
+    memory: TreeMemory,
+    /// Whether the world is being edited in a sandbox.
+    in_sandbox: bool,
     /// Link used to send messages to the WorldManager.
     link: Link,
 }
@@ -1451,6 +3452,26 @@ impl UndoController {
         self.has_redo
     }
 
+    /// Number of states available to undo.
+    pub fn undo_len(&self) -> usize {
+        self.undo_len
+    }
+
+    /// Number of states available to redo.
+    pub fn redo_len(&self) -> usize {
+        self.redo_len
+    }
+
+    /// Memory used by the undo history beyond what the current world uses.
+    pub fn memory(&self) -> TreeMemory {
+        self.memory
+    }
+
+    /// Returns true if the world is being edited in a sandbox.
+    pub fn in_sandbox(&self) -> bool {
+        self.in_sandbox
+    }
+
     /// Gets a dispatcher to trigger undo/redo.
     pub fn dispatcher(&self) -> UndoDispatcher {
         UndoDispatcher {
@@ -1475,6 +3496,17 @@ impl UndoDispatcher {
     pub fn redo(&self) {
         self.link.send_message(Msg::Redo);
     }
+
+    /// Opens a sandbox for editing the world without saving.
+    pub fn open_sandbox(&self) {
+        self.link.send_message(Msg::OpenSandbox);
+    }
+
+    /// Closes the sandbox, keeping its changes as a single undoable edit if `apply` is true or
+    /// discarding them otherwise.
+    pub fn close_sandbox(&self, apply: bool) {
+        self.link.send_message(Msg::CloseSandbox { apply });
+    }
 }
 
 /// Gets the UndoController from the context.
@@ -1574,6 +3606,67 @@ impl SaveFileFetcher {
         let world = load_world(id).inspect_err(|_| self.link.send_message(Msg::MarkError(id)))?;
         Ok(SaveFile::new(id, world))
     }
+
+    /// Get the current state of the given world.
+    fn current_world(&self, id: WorldId) -> Result<World, FetchSaveFileError> {
+        let current = self.reader.borrow();
+        if current.id() == id {
+            Ok(current.world().clone())
+        } else {
+            Ok(load_world(id).inspect_err(|_| self.link.send_message(Msg::MarkError(id)))?)
+        }
+    }
+
+    /// Save the current state of the given world as a named checkpoint.
+    pub fn create_checkpoint(&self, id: WorldId, name: String) -> Result<(), FetchSaveFileError> {
+        let world = self.current_world(id)?;
+        Ok(checkpoints::create(id, name, world, Date::now())?)
+    }
+
+    /// Compare a checkpoint of the given world with the world's current state. Returns None if
+    /// the checkpoint no longer exists.
+    pub fn diff_checkpoint(
+        &self,
+        id: WorldId,
+        taken: f64,
+    ) -> Result<Option<TreeDiff>, FetchSaveFileError> {
+        let Some(checkpoint) = checkpoints::find(id, taken)? else {
+            return Ok(None);
+        };
+        let current = self.current_world(id)?;
+        // Rebuild both with the current database so differences in database versions don't show
+        // up as changes.
+        let db = current.database.load_database();
+        Ok(Some(diff_trees(
+            &checkpoint.world.root.rebuild(&db),
+            &current.root.rebuild(&db),
+        )))
+    }
+
+    /// Get the ID and name of each top-level group in the given world.
+    pub fn top_level_groups(
+        &self,
+        id: WorldId,
+    ) -> Result<Vec<(Uuid, AttrValue)>, FetchSaveFileError> {
+        let current = self.reader.borrow();
+        let root = if current.id() == id {
+            current.world().root.clone()
+        } else {
+            load_world(id)
+                .inspect_err(|_| self.link.send_message(Msg::MarkError(id)))?
+                .root
+        };
+        Ok(root
+            .group()
+            .map(|root| {
+                root.children
+                    .iter()
+                    .filter_map(|child| child.group())
+                    .map(|group| (group.id, group.name.clone()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
 }
 
 /// Gets the SaveFileFetcher.
@@ -1,9 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::rc::Rc;
 
-use satisfactory_accounting::accounting::{Node, NodeKind};
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::database::{BuildingType, ItemId, Recipe};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use yew::{AttrValue, Classes};
 
 /// Mapping of node medatata by node id.
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
@@ -11,11 +13,70 @@ use uuid::Uuid;
 pub struct NodeMetas(Rc<HashMap<Uuid, NodeMeta>>);
 
 impl NodeMetas {
+    /// Whether this and the other are the same shared metadata, rather than just equal.
+    pub(super) fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
     /// Get the metadata for a particular node by id.
     pub fn meta(&self, uuid: Uuid) -> NodeMeta {
         self.0.get(&uuid).cloned().unwrap_or_default()
     }
 
+    /// Get the id of the group whose balance is pinned to the summary panel, if any.
+    pub fn pinned(&self) -> Option<Uuid> {
+        self.0
+            .iter()
+            .find_map(|(&id, meta)| meta.pinned.then_some(id))
+    }
+
+    /// Build the metadata updates which pin the given group to the summary panel, unpinning any
+    /// other group, or unpin everything if None.
+    pub fn pin_updates(&self, pin: Option<Uuid>) -> HashMap<Uuid, NodeMeta> {
+        let mut updates: HashMap<_, _> = self
+            .0
+            .iter()
+            .filter(|&(&id, meta)| meta.pinned && Some(id) != pin)
+            .map(|(&id, meta)| {
+                let meta = NodeMeta {
+                    pinned: false,
+                    ..meta.clone()
+                };
+                (id, meta)
+            })
+            .collect();
+        if let Some(id) = pin {
+            let meta = NodeMeta {
+                pinned: true,
+                ..self.meta(id)
+            };
+            updates.insert(id, meta);
+        }
+        updates
+    }
+
+    /// Get the power circuit a node was assigned to, if any, and the power storages kept with it.
+    pub fn circuit_assignment(&self, uuid: Uuid) -> (Option<AttrValue>, u32) {
+        match self.0.get(&uuid) {
+            Some(meta) => (
+                (!meta.circuit.is_empty()).then(|| meta.circuit.clone()),
+                meta.power_storages,
+            ),
+            None => (None, 0),
+        }
+    }
+
+    /// Get the names of every power circuit nodes are assigned to, sorted and without duplicates.
+    pub fn circuit_names(&self) -> Vec<AttrValue> {
+        let names: BTreeSet<_> = self
+            .0
+            .values()
+            .filter(|meta| !meta.circuit.is_empty())
+            .map(|meta| meta.circuit.clone())
+            .collect();
+        names.into_iter().collect()
+    }
+
     /// Build a version of the metadata with the given value updated. If the metada is shared, this
     /// creates a new copy to make it mutable.
     pub(super) fn set_meta(&mut self, uuid: Uuid, meta: NodeMeta) {
@@ -28,15 +89,60 @@ impl NodeMetas {
         Rc::make_mut(&mut self.0).extend(update);
     }
 
-    /// Prune metadata for anything that isn't referenced from the given node.
-    pub(super) fn prune(&mut self, root: &Node) {
-        let used_uuids: HashSet<_> = root
+    /// Build the metadata updates which expand groups fewer than `depth` levels below the root and
+    /// collapse the rest, or expand every group if `depth` is None. Groups which are already in the
+    /// right state are left out.
+    pub(super) fn expand_to_depth(
+        &self,
+        root: &Node,
+        depth: Option<usize>,
+    ) -> HashMap<Uuid, NodeMeta> {
+        fn visit(
+            metas: &NodeMetas,
+            node: &Node,
+            level: usize,
+            depth: Option<usize>,
+            updates: &mut HashMap<Uuid, NodeMeta>,
+        ) {
+            let Some(group) = node.group() else {
+                return;
+            };
+            for child in &group.children {
+                if let Some(child_group) = child.group() {
+                    let collapsed = depth.is_some_and(|depth| level >= depth);
+                    let meta = metas.meta(child_group.id);
+                    if meta.collapsed != collapsed {
+                        updates.insert(child_group.id, NodeMeta { collapsed, ..meta });
+                    }
+                }
+                visit(metas, child, level + 1, depth, updates);
+            }
+        }
+
+        let mut updates = HashMap::new();
+        visit(self, root, 0, depth, &mut updates);
+        updates
+    }
+
+    /// Build a copy of the metadata with all notes removed, keeping whether nodes are collapsed.
+    pub(super) fn without_notes(&self) -> Self {
+        let metas = self
+            .0
             .iter()
-            .filter_map(|node| match node.kind() {
-                NodeKind::Group(g) => Some(g.id),
-                NodeKind::Building(_) => None,
+            .map(|(&id, meta)| {
+                let meta = NodeMeta {
+                    notes: AttrValue::default(),
+                    ..meta.clone()
+                };
+                (id, meta)
             })
             .collect();
+        Self(Rc::new(metas))
+    }
+
+    /// Prune metadata for anything that isn't referenced from the given node.
+    pub(super) fn prune(&mut self, root: &Node) {
+        let used_uuids: HashSet<_> = root.iter().map(|node| node.id()).collect();
         Rc::make_mut(&mut self.0).retain(|k, _| used_uuids.contains(k));
     }
 }
@@ -44,7 +150,285 @@ impl NodeMetas {
 /// Metadata about a node which isn't stored in the tree and isn't available for
 /// undo/redo.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NodeMeta {
     /// Whether the node should be shown collapsed or expanded.
     pub collapsed: bool,
+    /// Free-form notes about the node, written in simple markdown.
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub notes: AttrValue,
+    /// Color the node is tinted with, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<NodeColor>,
+    /// Whether the group's balance is shown in the summary panel. At most one group is pinned.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Name of the power circuit the node is on, or empty to use the circuit of its parent.
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub circuit: AttrValue,
+    /// Number of power storages kept with the node, which buffer its circuit.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub power_storages: u32,
+}
+
+/// Whether a count is zero, for skipping serialization.
+fn is_zero(count: &u32) -> bool {
+    *count == 0
+}
+
+/// Palette of colors which nodes can be tinted with to visually separate parts of the tree.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Teal,
+    Blue,
+    Purple,
+    Pink,
+    Gray,
+}
+
+impl NodeColor {
+    /// All colors in the palette, in display order.
+    pub const ALL: [NodeColor; 9] = [
+        NodeColor::Red,
+        NodeColor::Orange,
+        NodeColor::Yellow,
+        NodeColor::Green,
+        NodeColor::Teal,
+        NodeColor::Blue,
+        NodeColor::Purple,
+        NodeColor::Pink,
+        NodeColor::Gray,
+    ];
+
+    /// Name of the color for display.
+    pub fn name(self) -> &'static str {
+        match self {
+            NodeColor::Red => "Red",
+            NodeColor::Orange => "Orange",
+            NodeColor::Yellow => "Yellow",
+            NodeColor::Green => "Green",
+            NodeColor::Teal => "Teal",
+            NodeColor::Blue => "Blue",
+            NodeColor::Purple => "Purple",
+            NodeColor::Pink => "Pink",
+            NodeColor::Gray => "Gray",
+        }
+    }
+
+    /// CSS class which applies this color.
+    pub fn class(self) -> &'static str {
+        match self {
+            NodeColor::Red => "color-red",
+            NodeColor::Orange => "color-orange",
+            NodeColor::Yellow => "color-yellow",
+            NodeColor::Green => "color-green",
+            NodeColor::Teal => "color-teal",
+            NodeColor::Blue => "color-blue",
+            NodeColor::Purple => "color-purple",
+            NodeColor::Pink => "color-pink",
+            NodeColor::Gray => "color-gray",
+        }
+    }
+}
+
+/// Mapping of per-item world metadata by item id.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ItemMetas(Rc<HashMap<ItemId, ItemMeta>>);
+
+impl ItemMetas {
+    /// Whether this and the other are the same shared metadata, rather than just equal.
+    pub(super) fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Get the metadata for a particular item by id.
+    pub fn meta(&self, id: ItemId) -> ItemMeta {
+        self.0.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Whether the given item is excluded from balances.
+    pub fn is_ignored(&self, id: ItemId) -> bool {
+        self.0.get(&id).is_some_and(|meta| meta.ignored)
+    }
+
+    /// Iterate over all items which have non-default metadata.
+    pub fn iter(&self) -> impl Iterator<Item = (ItemId, &ItemMeta)> {
+        self.0.iter().map(|(&id, meta)| (id, meta))
+    }
+
+    /// Build a version of the metadata with the given value updated. If the metadata is shared,
+    /// this creates a new copy to make it mutable. Empty metadata is removed rather than stored.
+    pub(super) fn set_meta(&mut self, id: ItemId, meta: ItemMeta) {
+        if meta.is_empty() {
+            if self.0.contains_key(&id) {
+                Rc::make_mut(&mut self.0).remove(&id);
+            }
+        } else {
+            Rc::make_mut(&mut self.0).insert(id, meta);
+        }
+    }
+
+    /// Build a copy of the metadata with all free-form notes removed, keeping targets and export
+    /// and ignore flags.
+    pub(super) fn without_notes(&self) -> Self {
+        let metas = self
+            .0
+            .iter()
+            .map(|(&id, meta)| {
+                let meta = ItemMeta {
+                    notes: AttrValue::default(),
+                    ..meta.clone()
+                };
+                (id, meta)
+            })
+            .filter(|(_, meta)| !meta.is_empty())
+            .collect();
+        Self(Rc::new(metas))
+    }
+}
+
+/// World-level metadata about an item, such as notes and stockpile targets.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemMeta {
+    /// Free-form notes about this item.
+    pub notes: AttrValue,
+    /// Desired number of this item to keep stockpiled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_stock: Option<f32>,
+    /// Whether surplus of this item is intentionally exported or sunk, so it shouldn't be reported
+    /// as unused.
+    pub exported: bool,
+    /// Whether this item is left out of every group balance and the world summary.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub ignored: bool,
+}
+
+impl ItemMeta {
+    /// Whether this metadata has no notes, no target, and isn't marked as exported or ignored.
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.target_stock.is_none() && !self.exported && !self.ignored
+    }
+}
+
+/// How far the player has progressed through the game in a particular world.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progression {
+    /// Highest milestone tier the player has unlocked, or None to show all content.
+    pub max_tier: Option<u32>,
+}
+
+impl Progression {
+    /// Whether the given recipe has been unlocked at this progression.
+    pub fn has_recipe(&self, recipe: &Recipe) -> bool {
+        self.has_tier(recipe.unlock_tier)
+    }
+
+    /// Whether the given building has been unlocked at this progression.
+    pub fn has_building(&self, building: &BuildingType) -> bool {
+        self.has_tier(building.unlock_tier)
+    }
+
+    /// Whether content unlocked at the given tier is available.
+    fn has_tier(&self, tier: u32) -> bool {
+        self.max_tier.is_none_or(|max_tier| tier <= max_tier)
+    }
+}
+
+/// Optional column of the node grid which can be hidden.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum GridColumn {
+    /// Clock speed of buildings, or fuel consumption of stations.
+    Clock,
+    /// Purity of miners, or traffic of stations.
+    Purity,
+    /// Items a node consumes.
+    NegativeBalances,
+    /// Items a node both produces and consumes at the same rate.
+    NeutralBalances,
+    /// Items a node produces.
+    PositiveBalances,
+    /// Power a node produces or consumes.
+    Power,
+    /// Virtual copies of a node.
+    Multiplier,
+}
+
+impl GridColumn {
+    /// All of the columns which can be hidden, in the order they appear in the grid.
+    pub const ALL: [Self; 7] = [
+        Self::Clock,
+        Self::Purity,
+        Self::NegativeBalances,
+        Self::NeutralBalances,
+        Self::PositiveBalances,
+        Self::Power,
+        Self::Multiplier,
+    ];
+
+    /// Name of the column shown to the user.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Clock => "Clock Speed",
+            Self::Purity => "Purity",
+            Self::NegativeBalances => "Consumed Items",
+            Self::NeutralBalances => "Balanced Items",
+            Self::PositiveBalances => "Produced Items",
+            Self::Power => "Power",
+            Self::Multiplier => "Copies",
+        }
+    }
+
+    /// Class added to the node tree to hide this column.
+    fn hide_class(self) -> &'static str {
+        match self {
+            Self::Clock => "hide-clock-column",
+            Self::Purity => "hide-purity-column",
+            Self::NegativeBalances => "hide-negative-column",
+            Self::NeutralBalances => "hide-neutral-column",
+            Self::PositiveBalances => "hide-positive-column",
+            Self::Power => "hide-power-column",
+            Self::Multiplier => "hide-multiplier-column",
+        }
+    }
+}
+
+/// Columns of the node grid which are hidden in a particular world.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HiddenColumns(Rc<BTreeSet<GridColumn>>);
+
+impl HiddenColumns {
+    /// Whether every column is shown.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether the given column is hidden.
+    pub fn is_hidden(&self, column: GridColumn) -> bool {
+        self.0.contains(&column)
+    }
+
+    /// Get a copy of these hidden columns with the given column shown if it was hidden or hidden
+    /// if it was shown.
+    pub fn toggled(&self, column: GridColumn) -> Self {
+        let mut columns = self.clone();
+        let set = Rc::make_mut(&mut columns.0);
+        if !set.remove(&column) {
+            set.insert(column);
+        }
+        columns
+    }
+
+    /// Classes to add to the node tree to hide these columns.
+    pub fn classes(&self) -> Classes {
+        self.0.iter().map(|column| column.hide_class()).collect()
+    }
 }
@@ -4,36 +4,74 @@ use satisfactory_accounting::database::Database;
 use serde::{Deserialize, Serialize};
 use yew::AttrValue;
 
-pub use self::dbchoice::{DatabaseChoice, DatabaseVersionSelector};
+use crate::user_settings::SettingsOverrides;
+
+#[allow(unused_imports)]
+pub use self::clipboard::ClipboardContent;
+pub use self::dbchoice::{group_database, DatabaseChoice, DatabaseVersionSelector, LoadDatabase};
 #[allow(unused_imports)]
 pub use self::dbwindow::{
     use_db_chooser_window, DbChooserWindowDispatcher, DbChooserWindowManager,
 };
 #[allow(unused_imports)]
 pub use self::id::{ParseWorldIdError, WorldId};
-pub use self::list::{WorldList, WorldMetadata};
+#[allow(unused_imports)]
+pub use self::itemwindow::{
+    item_meta_summary, use_item_notes_window, ItemNotesWindow, ItemNotesWindowDispatcher,
+    ItemNotesWindowManager,
+};
+#[allow(unused_imports)]
+pub use self::list::{Template, TemplateChoice, TemplateList, WorldList, WorldMetadata};
 #[allow(unused_imports)]
 pub use self::manager::{
-    use_db, use_db_controller, use_save_file_fetcher, use_undo_controller, use_world_dispatcher,
-    use_world_list, use_world_list_dispatcher, use_world_root, DbController, FetchSaveFileError,
-    SaveFileFetcher, UndoController, UndoDispatcher, WorldDispatcher, WorldListDispatcher,
-    WorldManager,
+    use_clipboard, use_db, use_db_controller, use_hidden_columns, use_item_metas, use_progression,
+    use_read_only, use_save_file_fetcher, use_session_stats, use_settings_overrides,
+    use_sync_controller, use_templates, use_undo_controller, use_world_dispatcher, use_world_list,
+    use_world_list_dispatcher, use_world_root, ClipboardController, DbController,
+    FetchSaveFileError, ReadOnly, ReadOnlyMode, RemoteWorlds, SaveFileFetcher, SyncController,
+    SyncStatus, UndoController, UndoDispatcher, WorldDispatcher, WorldListDispatcher, WorldManager,
+};
+pub use self::meta::{
+    GridColumn, HiddenColumns, ItemMeta, ItemMetas, NodeColor, NodeMeta, NodeMetas, Progression,
 };
-pub use self::meta::{NodeMeta, NodeMetas};
 pub use self::savefile::SaveFile;
 #[allow(unused_imports)]
+pub use self::stats::{SessionStats, WorldSessionStats};
+#[allow(unused_imports)]
+pub use self::statswindow::{
+    use_session_stats_window, SessionStatsWindow, SessionStatsWindowDispatcher,
+    SessionStatsWindowManager,
+};
+#[allow(unused_imports)]
+pub use self::syncwindow::{use_sync_window, SyncWindow, SyncWindowDispatcher, SyncWindowManager};
+pub use self::undo::{UndoSettings, UndoSettingsSection};
+#[allow(unused_imports)]
 pub use self::worldwindow::{
     use_world_chooser_window, WorldChooserWindow, WorldChooserWindowManager, WorldSortSettings,
     WorldSortSettingsMsg,
 };
 
+mod archive;
+mod checkpoints;
+mod chunks;
+mod clipboard;
 mod dbchoice;
+mod dbfetch;
 mod dbwindow;
 mod id;
+mod itemwindow;
 pub mod list;
 mod manager;
 mod meta;
 mod savefile;
+mod share;
+mod snapshots;
+mod stats;
+mod statswindow;
+mod storageusage;
+mod sync;
+mod syncwindow;
+mod undo;
 mod v1storage;
 mod worldwindow;
 
@@ -46,6 +84,18 @@ pub struct World {
     root: Node,
     /// Non-undo metadata about nodes.
     node_metadata: NodeMetas,
+    /// Non-undo notes and targets for items in this world.
+    #[serde(default)]
+    item_metadata: ItemMetas,
+    /// Non-undo setting for which content has been unlocked in this world.
+    #[serde(default)]
+    progression: Progression,
+    /// Non-undo user settings which this world keeps separately.
+    #[serde(default, skip_serializing_if = "SettingsOverrides::is_empty")]
+    settings_overrides: SettingsOverrides,
+    /// Non-undo setting for which columns of the node grid are hidden in this world.
+    #[serde(default, skip_serializing_if = "HiddenColumns::is_empty")]
+    hidden_columns: HiddenColumns,
     /// Non-undo metadata about this particular world.
     /// This has been superceded by the
     #[deprecated]
@@ -61,6 +111,10 @@ impl World {
             database: Default::default(),
             root: Group::empty_node(),
             node_metadata: Default::default(),
+            item_metadata: Default::default(),
+            progression: Default::default(),
+            settings_overrides: Default::default(),
+            hidden_columns: Default::default(),
             global_metadata: Default::default(),
         }
     }
@@ -80,16 +134,28 @@ impl World {
     fn metadata(&self) -> WorldMetadata {
         WorldMetadata {
             name: self.name(),
-            database: self.database.version_selector(),
+            database: DatabaseVersionSelector::for_choice(&self.database),
             // An existing World should never have a load_error.
             load_error: false,
         }
     }
 
+    /// Whether this world has the same content as the other because it shares the other's
+    /// parts. Worlds which are equal but were built separately don't count.
+    fn shares_content(&self, other: &World) -> bool {
+        self.root.ptr_eq(&other.root)
+            && self.node_metadata.ptr_eq(&other.node_metadata)
+            && self.item_metadata.ptr_eq(&other.item_metadata)
+            && self.database == other.database
+            && self.progression == other.progression
+            && self.settings_overrides == other.settings_overrides
+            && self.hidden_columns == other.hidden_columns
+    }
+
     /// Performs the world post-load actions. This fetches the current database, then rebuilds the
     /// root node in place (without creating an undo state). It then returns the database.
     fn post_load(&mut self) -> Database {
-        let db = self.database.get();
+        let db = self.database.load_database();
         self.root = self.root.rebuild(&db);
         db
     }
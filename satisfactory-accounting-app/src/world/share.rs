@@ -0,0 +1,222 @@
+//! Encoding of worlds for sharing with other people, and display of shared summaries.
+//!
+//! This is the single place where shared content gets built so that what is exposed is always
+//! enforced here rather than by whatever displays it. Shared views are deflated and base64 encoded
+//! into the fragment of a share link, so they never get sent to a server.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use log::warn;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use satisfactory_accounting::accounting::Balance;
+use satisfactory_accounting::database::Database;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use wasm_bindgen::JsValue;
+use yew::{classes, html, AttrValue, Html};
+
+use crate::material::material_icon;
+use crate::user_settings::number_format::{BalanceDisplaySettings, UserConfiguredFormat};
+use crate::world::{DatabaseVersionSelector, World};
+
+/// Prefix of the url fragment used for share links.
+const SHARE_FRAGMENT_PREFIX: &str = "#share=";
+
+/// Compression level used for share links. Links are small, so use the best compression.
+const COMPRESSION_LEVEL: u8 = 9;
+
+/// Largest size a shared view may decompress to, to avoid hanging on malicious links.
+const MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+/// Controls which parts of a world are exposed when it is shared.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareOptions {
+    /// How much of the world to include.
+    pub scope: ShareScope,
+    /// Whether to include free-form item and node notes.
+    pub include_notes: bool,
+}
+
+/// How much of a world to include when sharing it.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareScope {
+    /// Only the world's name and overall balance.
+    #[default]
+    Summary,
+    /// The full tree of groups and buildings.
+    FullTree,
+}
+
+/// Content of a shared world, containing only what the [`ShareOptions`] allowed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "scope")]
+pub enum SharedView {
+    /// Only the name and overall balance of the world.
+    Summary {
+        /// Name of the world.
+        name: AttrValue,
+        /// Database used by the world, or None if it used a custom database.
+        database: Option<DatabaseVersionSelector>,
+        /// Balance of the whole world.
+        balance: Balance,
+    },
+    /// The full world, with notes removed unless they were allowed.
+    FullTree {
+        /// The shared world.
+        world: World,
+    },
+}
+
+impl SharedView {
+    /// Build the shared view of a world, leaving out anything the options don't allow.
+    pub fn new(world: &World, options: ShareOptions) -> Self {
+        match options.scope {
+            ShareScope::Summary => Self::Summary {
+                name: world.name(),
+                database: DatabaseVersionSelector::for_choice(&world.database),
+                balance: world.root.balance().clone(),
+            },
+            ShareScope::FullTree => {
+                let mut world = world.clone();
+                if !options.include_notes {
+                    world.item_metadata = world.item_metadata.without_notes();
+                    world.node_metadata = world.node_metadata.without_notes();
+                }
+                Self::FullTree { world }
+            }
+        }
+    }
+
+    /// Encode this view as compressed url-safe text.
+    pub fn encode(&self) -> Result<String, serde_json::Error> {
+        let json = serde_json::to_vec(self)?;
+        Ok(URL_SAFE_NO_PAD.encode(compress_to_vec(&json, COMPRESSION_LEVEL)))
+    }
+
+    /// Decode a view previously produced by [`SharedView::encode`].
+    pub fn decode(encoded: &str) -> Result<Self, DecodeSharedViewError> {
+        let compressed = URL_SAFE_NO_PAD.decode(encoded)?;
+        let json = decompress_to_vec_with_limit(&compressed, MAX_DECOMPRESSED_SIZE)
+            .map_err(|e| DecodeSharedViewError::Decompress(e.to_string()))?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+
+    /// Build a link to the app which opens this view.
+    pub fn link(&self) -> Result<String, serde_json::Error> {
+        let encoded = self.encode()?;
+        let href = gloo::utils::window().location().href().unwrap_or_default();
+        let base = href.split('#').next().unwrap_or_default();
+        Ok(format!("{base}{SHARE_FRAGMENT_PREFIX}{encoded}"))
+    }
+}
+
+/// Show the balance of a shared summary as a table, naming items from the database the shared
+/// world used.
+pub fn view_summary(
+    balance: &Balance,
+    database: &Database,
+    balance_settings: &BalanceDisplaySettings,
+) -> Html {
+    let sign_class = |value: f32| {
+        if value < 0.0 {
+            "negative"
+        } else if value > 0.0 {
+            "positive"
+        } else {
+            "neutral"
+        }
+    };
+    let mut items: Vec<_> = balance
+        .balances
+        .iter()
+        .filter(|(_, &rate)| rate != 0.0)
+        .map(|(&id, &rate)| {
+            let name: AttrValue = match database.get(id) {
+                Some(item) => item.name.clone().into(),
+                None => "Unknown Item".into(),
+            };
+            (name, id, rate)
+        })
+        .collect();
+    items.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+    let items = items.into_iter().map(|(name, id, rate)| {
+        html! {
+            <tr key={id.to_string()}>
+                <td class="name">{name}</td>
+                <td class={classes!("value", sign_class(rate))}>
+                    {balance_settings.format_rate(rate).to_string()}
+                </td>
+            </tr>
+        }
+    });
+    html! {
+        <table>
+            <tr>
+                <td class="name">{material_icon("bolt")}{"Power (MW)"}</td>
+                <td class={classes!("value", sign_class(balance.power))}>
+                    {balance.power.format(&balance_settings.power_format_settings).to_string()}
+                </td>
+            </tr>
+            if !balance.balances.is_empty() {
+                <tr>
+                    <th colspan="2">
+                        {format!("Net Balances ({})", balance_settings.rate_unit.name().to_lowercase())}
+                    </th>
+                </tr>
+                {for items}
+            }
+        </table>
+    }
+}
+
+/// Whether the app was opened with the `readonly` flag in its query string.
+pub fn has_read_only_flag() -> bool {
+    let search = gloo::utils::window()
+        .location()
+        .search()
+        .unwrap_or_default();
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .any(|param| match param.split_once('=') {
+            Some((name, value)) => name == "readonly" && value != "false" && value != "0",
+            None => param == "readonly",
+        })
+}
+
+/// If the app was opened from a share link, decode the shared view and remove it from the url, so
+/// reloading the page doesn't open it again.
+pub fn take_shared_view() -> Option<Result<SharedView, DecodeSharedViewError>> {
+    let window = gloo::utils::window();
+    let location = window.location();
+    let hash = location.hash().ok()?;
+    let encoded = hash.strip_prefix(SHARE_FRAGMENT_PREFIX)?;
+    let result = SharedView::decode(encoded);
+    let without_fragment = format!(
+        "{}{}",
+        location.pathname().unwrap_or_default(),
+        location.search().unwrap_or_default()
+    );
+    if let Ok(history) = window.history() {
+        if let Err(e) = history.replace_state_with_url(&JsValue::NULL, "", Some(&without_fragment))
+        {
+            warn!("Unable to remove the share link from the url: {e:?}");
+        }
+    }
+    Some(result)
+}
+
+/// Error from decoding a [`SharedView`].
+#[derive(Error, Debug)]
+pub enum DecodeSharedViewError {
+    /// The text was not valid base64.
+    #[error("Shared view was not valid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+    /// The decoded content could not be decompressed.
+    #[error("Shared view could not be decompressed: {0}")]
+    Decompress(String),
+    /// The decoded content was not a valid shared view.
+    #[error("Shared view could not be parsed: {0}")]
+    Json(#[from] serde_json::Error),
+}
@@ -0,0 +1,273 @@
+//! Automatic snapshots of worlds, stored separately from the live copy of each world so that a
+//! world can be restored after a bug or an accidental bulk delete.
+//!
+//! Each snapshot is stored under its own key, and snapshots of all worlds together are kept to a
+//! fixed share of the storage quota. Snapshots are only backups, so they are dropped whenever the
+//! space is needed to save a live world.
+use std::collections::VecDeque;
+
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use yew::AttrValue;
+
+use crate::world::storageusage::{stored_bytes, ESTIMATED_QUOTA};
+use crate::world::{World, WorldId};
+
+/// Maximum number of snapshots kept for each world. Once full, the oldest snapshot is replaced.
+const MAX_SNAPSHOTS: usize = 5;
+
+/// Share of the estimated storage quota which snapshots of all worlds together may use.
+const SNAPSHOT_QUOTA_SHARE: f64 = 0.25;
+
+/// Minimum time between snapshots of a world which has been edited.
+const SNAPSHOT_INTERVAL_MILLIS: f64 = 10.0 * 60_000.0;
+
+/// Number of edits after which a snapshot is taken even if the interval hasn't passed.
+const SNAPSHOT_EDITS: u32 = 50;
+
+/// Part of the storage key of each snapshot between the world id and the time it was taken.
+const SNAPSHOT_KEY_SEPARATOR: &str = ".snapshot.";
+
+/// A copy of a world at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Timestamp in milliseconds of when the snapshot was taken.
+    pub taken: f64,
+    /// The world as of the snapshot.
+    pub world: World,
+}
+
+/// Summary of a snapshot for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotInfo {
+    /// Timestamp in milliseconds of when the snapshot was taken.
+    pub taken: f64,
+    /// Name of the world as of the snapshot.
+    pub name: AttrValue,
+}
+
+/// A snapshot found in storage, without its contents.
+struct StoredSnapshot {
+    /// Storage key of the snapshot.
+    key: String,
+    /// World the snapshot is of.
+    world: WorldId,
+    /// Timestamp in milliseconds of when the snapshot was taken.
+    taken: f64,
+    /// Bytes of quota the snapshot uses.
+    bytes: usize,
+}
+
+/// Get the storage key where older versions kept every snapshot of the given world together.
+fn legacy_snapshots_key(id: WorldId) -> String {
+    format!("{}.snapshots", id.as_legacy_dotted())
+}
+
+/// Get the storage key for the snapshot of the given world taken at the given time.
+fn snapshot_key(id: WorldId, taken: f64) -> String {
+    format!("{}{SNAPSHOT_KEY_SEPARATOR}{taken}", id.as_legacy_dotted())
+}
+
+/// Find the stored snapshots of every world, oldest first.
+fn stored_snapshots() -> Vec<StoredSnapshot> {
+    let storage = LocalStorage::raw();
+    let mut snapshots = Vec::new();
+    for i in 0..LocalStorage::length() {
+        let Ok(Some(key)) = storage.key(i) else {
+            continue;
+        };
+        let Some(world) = WorldId::owner_of_storage_key(&key) else {
+            continue;
+        };
+        let Some(taken) = key
+            .split_once(SNAPSHOT_KEY_SEPARATOR)
+            .and_then(|(_, taken)| taken.parse().ok())
+        else {
+            continue;
+        };
+        let value = storage.get_item(&key).ok().flatten().unwrap_or_default();
+        let bytes = stored_bytes(&key, &value);
+        snapshots.push(StoredSnapshot {
+            key,
+            world,
+            taken,
+            bytes,
+        });
+    }
+    snapshots.sort_by(|a, b| a.taken.total_cmp(&b.taken));
+    snapshots
+}
+
+/// Move snapshots stored by older versions of the app to their own keys.
+fn migrate_legacy(id: WorldId) {
+    let key = legacy_snapshots_key(id);
+    let snapshots: VecDeque<Snapshot> = match LocalStorage::get(&key) {
+        Ok(snapshots) => snapshots,
+        Err(StorageError::KeyNotFound(_)) => return,
+        Err(e) => {
+            warn!("Discarding unreadable snapshots of world {id:?}: {e}");
+            VecDeque::new()
+        }
+    };
+    // Delete the old copy first, since there may not be space for both.
+    LocalStorage::delete(&key);
+    for snapshot in &snapshots {
+        if let Err(e) = store(id, snapshot) {
+            warn!("Unable to move snapshot of world {id:?}: {e}");
+        }
+    }
+}
+
+/// Store a snapshot, first dropping the oldest snapshots of this world beyond the limit and then
+/// the oldest snapshots of any world until all snapshots fit in their share of the quota. If the
+/// snapshot can't fit in the share by itself, it isn't stored.
+fn store(id: WorldId, snapshot: &Snapshot) -> Result<(), StorageError> {
+    let key = snapshot_key(id, snapshot.taken);
+    let bytes = stored_bytes(&key, &serde_json::to_string(snapshot)?);
+    let budget = (ESTIMATED_QUOTA as f64 * SNAPSHOT_QUOTA_SHARE) as usize;
+    if bytes > budget {
+        warn!("Snapshot of world {id:?} is too large to keep ({bytes} bytes)");
+        return Ok(());
+    }
+
+    let mut stored: VecDeque<StoredSnapshot> = stored_snapshots()
+        .into_iter()
+        .filter(|stored| stored.key != key)
+        .collect();
+    let mut own = stored.iter().filter(|stored| stored.world == id).count();
+    stored.retain(|stored| {
+        if stored.world == id && own >= MAX_SNAPSHOTS {
+            LocalStorage::delete(&stored.key);
+            own -= 1;
+            false
+        } else {
+            true
+        }
+    });
+    let mut used: usize = stored.iter().map(|stored| stored.bytes).sum();
+    while used + bytes > budget {
+        let Some(oldest) = stored.pop_front() else {
+            break;
+        };
+        LocalStorage::delete(&oldest.key);
+        used -= oldest.bytes;
+    }
+    LocalStorage::set(&key, snapshot)
+}
+
+/// Load the snapshots of the given world, oldest first.
+pub fn load(id: WorldId) -> Result<VecDeque<Snapshot>, StorageError> {
+    migrate_legacy(id);
+    stored_snapshots()
+        .into_iter()
+        .filter(|stored| stored.world == id)
+        .map(|stored| LocalStorage::get(&stored.key))
+        .collect()
+}
+
+/// Load the snapshot of the given world taken at the given time, if it still exists.
+pub fn find(id: WorldId, taken: f64) -> Result<Option<Snapshot>, StorageError> {
+    migrate_legacy(id);
+    match LocalStorage::get(snapshot_key(id, taken)) {
+        Ok(snapshot) => Ok(Some(snapshot)),
+        Err(StorageError::KeyNotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// List the snapshots of the given world, newest first.
+pub fn list(id: WorldId) -> Result<Vec<SnapshotInfo>, StorageError> {
+    Ok(load(id)?
+        .iter()
+        .rev()
+        .map(|snapshot| SnapshotInfo {
+            taken: snapshot.taken,
+            name: snapshot.world.name(),
+        })
+        .collect())
+}
+
+/// Add a snapshot of the world, replacing the oldest snapshot if there are already too many, or
+/// the oldest snapshots of any world if snapshots would use more than their share of storage.
+pub fn take(id: WorldId, world: &World, now: f64) -> Result<(), StorageError> {
+    migrate_legacy(id);
+    store(
+        id,
+        &Snapshot {
+            taken: now,
+            world: world.clone(),
+        },
+    )
+}
+
+/// Replace all snapshots of the given world, such as when restoring a backup.
+pub fn replace(id: WorldId, snapshots: &VecDeque<Snapshot>) -> Result<(), StorageError> {
+    delete(id);
+    snapshots
+        .iter()
+        .try_for_each(|snapshot| store(id, snapshot))
+}
+
+/// Delete all snapshots of the given world.
+pub fn delete(id: WorldId) {
+    LocalStorage::delete(legacy_snapshots_key(id));
+    for stored in stored_snapshots() {
+        if stored.world == id {
+            LocalStorage::delete(&stored.key);
+        }
+    }
+}
+
+/// Delete the oldest snapshot of any world, to make space for something more important. Returns
+/// false if there were no snapshots left to delete.
+pub fn drop_oldest() -> bool {
+    // Snapshots stored by older versions of the app are older than any stored since.
+    let storage = LocalStorage::raw();
+    let legacy = (0..LocalStorage::length())
+        .filter_map(|i| storage.key(i).ok().flatten())
+        .find(|key| {
+            WorldId::owner_of_storage_key(key).is_some_and(|id| *key == legacy_snapshots_key(id))
+        });
+    if let Some(key) = legacy {
+        LocalStorage::delete(key);
+        return true;
+    }
+    match stored_snapshots().first() {
+        Some(oldest) => {
+            LocalStorage::delete(&oldest.key);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Tracks when the current world is due for another snapshot.
+pub struct SnapshotSchedule {
+    /// Timestamp in milliseconds of the last snapshot, or of when tracking started.
+    last_taken: f64,
+    /// Number of edits since the last snapshot.
+    edits: u32,
+}
+
+impl SnapshotSchedule {
+    /// Start tracking with no edits made yet.
+    pub fn new(now: f64) -> Self {
+        Self {
+            last_taken: now,
+            edits: 0,
+        }
+    }
+
+    /// Count an edit to the world.
+    pub fn record_edit(&mut self) {
+        self.edits += 1;
+    }
+
+    /// Whether the world has changed enough since the last snapshot to take another.
+    pub fn is_due(&self, now: f64) -> bool {
+        self.edits >= SNAPSHOT_EDITS
+            || (self.edits > 0 && now - self.last_taken >= SNAPSHOT_INTERVAL_MILLIS)
+    }
+}
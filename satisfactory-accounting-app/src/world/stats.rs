@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use satisfactory_accounting::accounting::Node;
+use web_sys::js_sys::Date;
+
+use crate::world::WorldId;
+
+/// Statistics about edits made to each world during the current browser session. These are never
+/// saved or sent anywhere.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    /// Stats for each world which has been opened this session.
+    worlds: Rc<BTreeMap<WorldId, WorldSessionStats>>,
+    /// World that is currently open and the time (in milliseconds since the epoch) when it was
+    /// opened.
+    current: Option<(WorldId, f64)>,
+}
+
+impl SessionStats {
+    /// Start tracking time for the given world, if it isn't already the current world.
+    pub(super) fn switch_to(&mut self, world: WorldId) {
+        if matches!(self.current, Some((current, _)) if current == world) {
+            return;
+        }
+        let now = Date::now();
+        if let Some((previous, opened)) = self.current.take() {
+            Rc::make_mut(&mut self.worlds)
+                .entry(previous)
+                .or_default()
+                .active_millis += now - opened;
+        }
+        Rc::make_mut(&mut self.worlds).entry(world).or_default();
+        self.current = Some((world, now));
+    }
+
+    /// Record an edit to the given world which replaced `old_root` with `new_root`.
+    pub(super) fn record_edit(&mut self, world: WorldId, old_root: &Node, new_root: &Node) {
+        let stats = Rc::make_mut(&mut self.worlds).entry(world).or_default();
+        stats.edits += 1;
+        let added = new_root
+            .iter()
+            .count()
+            .saturating_sub(old_root.iter().count());
+        stats.nodes_added += added as u32;
+    }
+
+    /// Iterate over the stats for each world opened this session, including time spent in the
+    /// current world so far.
+    pub fn iter(&self) -> impl Iterator<Item = (WorldId, WorldSessionStats)> + '_ {
+        let now = Date::now();
+        self.worlds.iter().map(move |(&id, stats)| {
+            let mut stats = stats.clone();
+            if let Some((current, opened)) = self.current {
+                if current == id {
+                    stats.active_millis += now - opened;
+                }
+            }
+            (id, stats)
+        })
+    }
+}
+
+/// Statistics about a single world for the current session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorldSessionStats {
+    /// Number of changes made to the world's node tree.
+    pub edits: u32,
+    /// Number of buildings and groups added to the world.
+    pub nodes_added: u32,
+    /// Time the world has been open, in milliseconds.
+    pub active_millis: f64,
+}
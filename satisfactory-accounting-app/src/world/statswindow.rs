@@ -0,0 +1,75 @@
+use gloo::timers::callback::Interval;
+use yew::{
+    function_component, hook, html, use_callback, use_context, use_effect_with, use_force_update,
+    AttrValue, Html,
+};
+
+use crate::duration::format_minutes;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::world::{use_session_stats, use_world_list};
+
+pub type SessionStatsWindowManager = WindowManager<SessionStatsWindow>;
+pub type SessionStatsWindowDispatcher = ShowWindowDispatcher<SessionStatsWindow>;
+
+/// Gets access to the SessionStats window dispatcher which controls showing the session stats
+/// window.
+#[hook]
+pub fn use_session_stats_window() -> SessionStatsWindowDispatcher {
+    use_context::<SessionStatsWindowDispatcher>().expect(
+        "use_session_stats_window can only be used from within a child of \
+        SessionStatsWindowManager",
+    )
+}
+
+/// Shows statistics about what has been done in each world this session.
+#[function_component]
+pub fn SessionStatsWindow() -> Html {
+    let window_dispatcher = use_session_stats_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let stats = use_session_stats();
+    let worlds = use_world_list();
+
+    // Redraw periodically so the time in the current world keeps counting up.
+    let force_update = use_force_update();
+    use_effect_with((), move |()| {
+        let interval = Interval::new(1000, move || force_update.force_update());
+        move || drop(interval)
+    });
+
+    let rows = stats.iter().map(|(id, stats)| {
+        let (name, selected): (AttrValue, bool) = match worlds.get(id) {
+            Some(world) => (world.meta().name.clone(), world.is_selected()),
+            None => ("Deleted World".into(), false),
+        };
+        html! {
+            <tr key={id.as_base64().to_string()} class={selected.then_some("selected")}>
+                <td class="world-name">{name}</td>
+                <td class="edits">{stats.edits}</td>
+                <td class="nodes-added">{stats.nodes_added}</td>
+                <td class="active-time">
+                    {format_minutes((stats.active_millis / 60_000.0) as f32)}
+                </td>
+            </tr>
+        }
+    });
+
+    html! {
+        <OverlayWindow title="Session Stats" class="SessionStatsWindow" on_close={close}>
+            <p>{"Activity in each world since this page was opened. These stats are only kept \
+            in this tab and are never saved or sent anywhere."}</p>
+            <table>
+                <tr>
+                    <th>{"World"}</th>
+                    <th>{"Edits"}</th>
+                    <th>{"Nodes Added"}</th>
+                    <th>{"Time Open"}</th>
+                </tr>
+                {for rows}
+            </table>
+        </OverlayWindow>
+    }
+}
@@ -0,0 +1,79 @@
+//! Measures how much of the browser's local storage quota is used, and by which worlds.
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+use wasm_bindgen::JsValue;
+
+use crate::world::WorldId;
+
+/// Local storage quota most browsers give each website, in bytes. Browsers don't report the
+/// actual quota, so this is only an estimate.
+pub const ESTIMATED_QUOTA: usize = 10 * 1024 * 1024;
+
+/// Fraction of the quota above which the user is warned that storage is running low.
+pub const WARN_FRACTION: f64 = 0.8;
+
+/// How much local storage is used.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StorageUsage {
+    /// Bytes used by each world, including its chunks and snapshots.
+    pub worlds: BTreeMap<WorldId, usize>,
+    /// Bytes used by everything not belonging to a particular world, such as the world list,
+    /// templates, settings, and clipboard.
+    pub other: usize,
+}
+
+impl StorageUsage {
+    /// Measure the current local storage usage.
+    pub fn measure() -> Result<Self, JsValue> {
+        let storage = LocalStorage::raw();
+        let mut usage = Self::default();
+        for i in 0..storage.length()? {
+            let Some(key) = storage.key(i)? else {
+                continue;
+            };
+            let value = storage.get_item(&key)?.unwrap_or_default();
+            let bytes = stored_bytes(&key, &value);
+            match WorldId::owner_of_storage_key(&key) {
+                Some(id) => *usage.worlds.entry(id).or_default() += bytes,
+                None => usage.other += bytes,
+            }
+        }
+        Ok(usage)
+    }
+
+    /// Total bytes used.
+    pub fn total(&self) -> usize {
+        self.worlds.values().sum::<usize>() + self.other
+    }
+
+    /// Fraction of the estimated quota which is used.
+    pub fn fraction_used(&self) -> f64 {
+        self.total() as f64 / ESTIMATED_QUOTA as f64
+    }
+
+    /// Whether usage is high enough that the user should be warned.
+    pub fn is_near_quota(&self) -> bool {
+        self.fraction_used() >= WARN_FRACTION
+    }
+}
+
+/// Bytes of quota used by storing the given value under the given key. Browsers store strings as
+/// UTF-16 and count both the key and value against the quota.
+pub fn stored_bytes(key: &str, value: &str) -> usize {
+    2 * (key.encode_utf16().count() + value.encode_utf16().count())
+}
+
+/// Format a number of bytes for display.
+pub fn format_bytes(bytes: usize) -> String {
+    const KIB: f64 = 1024.0;
+    const MIB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= MIB {
+        format!("{:.2} MiB", bytes / MIB)
+    } else if bytes >= KIB {
+        format!("{:.1} KiB", bytes / KIB)
+    } else {
+        format!("{bytes} B")
+    }
+}
@@ -0,0 +1,381 @@
+//! Syncing worlds to a remote WebDAV folder, so the same world can be used from more than one
+//! computer.
+//!
+//! Each world is stored as a save file named after its world id. Conflicts are detected with the
+//! ETag the server returns: uploads only succeed if the remote copy is still the one we last saw.
+//! Worlds another computer synced are found by listing the folder with a WebDAV PROPFIND request.
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use gloo::net::http::{Method, Request, RequestBuilder, Response};
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, SessionStorage, Storage};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use yew::AttrValue;
+
+use crate::world::savefile::VersionedWorldModel;
+use crate::world::{SaveFile, World, WorldId};
+
+/// Local storage key where the sync configuration is stored.
+const SYNC_CONFIG_KEY: &str = "zstewart.satisfactorydb.state.sync";
+
+/// Session storage key where the sync password is kept. It is never written to local storage, so
+/// it is forgotten when the browser session ends.
+const SYNC_PASSWORD_KEY: &str = "zstewart.satisfactorydb.state.sync.password";
+
+/// Local storage key where the remote version of each synced world is stored.
+const REMOTE_VERSIONS_KEY: &str = "zstewart.satisfactorydb.state.sync.versions";
+
+/// Where and whether to sync worlds.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// Whether worlds are automatically uploaded when the app is idle.
+    pub enabled: bool,
+    /// URL of the WebDAV folder which worlds are stored in.
+    pub url: AttrValue,
+    /// User name for the WebDAV server. If empty, no credentials are sent.
+    pub username: AttrValue,
+    /// Password or app token for the WebDAV server. Only kept for the current browser session;
+    /// it is still read from configurations saved before that so they can be migrated.
+    #[serde(skip_serializing)]
+    pub password: AttrValue,
+}
+
+impl SyncConfig {
+    /// Load the sync configuration, or the default (disabled) configuration if none is stored.
+    pub fn load() -> Self {
+        let mut config: Self = LocalStorage::get(SYNC_CONFIG_KEY).unwrap_or_default();
+        if config.password.is_empty() {
+            config.password = SessionStorage::get(SYNC_PASSWORD_KEY).unwrap_or_default();
+        } else if let Err(e) = config.save() {
+            // Older versions stored the password in local storage, so save again to remove it.
+            warn!("Unable to remove the stored sync password: {e}");
+        }
+        config
+    }
+
+    /// Save the sync configuration. The password is only saved for the current session.
+    pub fn save(&self) -> Result<(), StorageError> {
+        LocalStorage::set(SYNC_CONFIG_KEY, self)?;
+        SessionStorage::set(SYNC_PASSWORD_KEY, &self.password)
+    }
+
+    /// Whether enough is configured to attempt a sync.
+    pub fn is_configured(&self) -> bool {
+        !self.url.is_empty() && !self.needs_password()
+    }
+
+    /// Whether a user name is set but the password hasn't been entered this session.
+    pub fn needs_password(&self) -> bool {
+        !self.username.is_empty() && self.password.is_empty()
+    }
+
+    /// URL of the folder worlds are stored in, with a trailing slash.
+    fn folder_url(&self) -> String {
+        format!("{}/", self.url.trim_end_matches('/'))
+    }
+
+    /// URL of the remote copy of the given world.
+    fn world_url(&self, id: WorldId) -> String {
+        format!("{}{}.json", self.folder_url(), id.as_base64())
+    }
+
+    /// Add the credentials, if any, to a request.
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        if self.username.is_empty() {
+            return request;
+        }
+        let credentials = STANDARD.encode(format!("{}:{}", self.username, self.password));
+        request.header("Authorization", &format!("Basic {credentials}"))
+    }
+}
+
+/// ETag of the remote copy of each world as of the last time it was synced.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RemoteVersions(BTreeMap<WorldId, String>);
+
+impl RemoteVersions {
+    /// Load the remote versions, or an empty set if none are stored.
+    pub fn load() -> Self {
+        LocalStorage::get(REMOTE_VERSIONS_KEY).unwrap_or_default()
+    }
+
+    /// Get the last seen remote version of the given world.
+    pub fn get(&self, id: WorldId) -> Option<&str> {
+        self.0.get(&id).map(String::as_str)
+    }
+
+    /// Record the remote version of a world and save the updated versions.
+    pub fn set(&mut self, id: WorldId, etag: Option<String>) -> Result<(), StorageError> {
+        match etag {
+            Some(etag) => self.0.insert(id, etag),
+            None => self.0.remove(&id),
+        };
+        LocalStorage::set(REMOTE_VERSIONS_KEY, &self.0)
+    }
+}
+
+/// A world found in the sync folder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteWorld {
+    /// ID of the world, from the name of its file.
+    pub id: WorldId,
+    /// Name of the world.
+    pub name: AttrValue,
+    /// When the remote copy was last changed, as reported by the server.
+    pub modified: Option<AttrValue>,
+}
+
+/// A world save file found by listing the sync folder, before it is read.
+#[derive(Debug, Clone, PartialEq)]
+struct ListedFile {
+    /// ID of the world, from the name of the file.
+    id: WorldId,
+    /// When the file was last changed, as reported by the server.
+    modified: Option<String>,
+}
+
+/// Errors which can occur while syncing a world.
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("Unable to reach the sync server: {0}")]
+    Network(#[from] gloo::net::Error),
+    #[error("The sync server responded with status {0}")]
+    Status(u16),
+    #[error("The remote copy of this world was changed since it was last synced")]
+    Conflict,
+    #[error("There is no remote copy of this world")]
+    NotFound,
+    #[error("The remote copy of this world could not be read: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Upload a world's save file.
+///
+/// If `expected` is set, the upload only succeeds if the remote copy still has that ETag. If it
+/// isn't set, the upload only succeeds if there is no remote copy yet. If `overwrite` is true, the
+/// remote copy is replaced regardless. Returns the ETag of the newly uploaded copy.
+pub async fn upload_world(
+    config: &SyncConfig,
+    id: WorldId,
+    json: &str,
+    expected: Option<&str>,
+    overwrite: bool,
+) -> Result<Option<String>, SyncError> {
+    let url = config.world_url(id);
+    let mut request = config
+        .authorize(Request::put(&url))
+        .header("Content-Type", "application/json");
+    if !overwrite {
+        request = match expected {
+            Some(etag) => request.header("If-Match", etag),
+            None => request.header("If-None-Match", "*"),
+        };
+    }
+    let response = request.body(json)?.send().await?;
+    check_status(&response)?;
+    match etag(&response) {
+        Some(etag) => Ok(Some(etag)),
+        // Not every server returns the new ETag from a PUT, so ask for it separately.
+        None => {
+            let response = config
+                .authorize(RequestBuilder::new(&url).method(Method::HEAD))
+                .header("Cache-Control", "no-cache")
+                .send()
+                .await?;
+            check_status(&response)?;
+            Ok(etag(&response))
+        }
+    }
+}
+
+/// Download the remote copy of a world, along with its ETag.
+pub async fn download_world(
+    config: &SyncConfig,
+    id: WorldId,
+) -> Result<(SaveFile, Option<String>), SyncError> {
+    let response = config
+        .authorize(Request::get(&config.world_url(id)))
+        .header("Cache-Control", "no-cache")
+        .send()
+        .await?;
+    check_status(&response)?;
+    let etag = etag(&response);
+    let save_file = serde_json::from_str(&response.text().await?)?;
+    Ok((save_file, etag))
+}
+
+/// List the worlds in the sync folder, sorted by name.
+///
+/// The folder listing only has file names, so each world's save file is downloaded to find its
+/// name, except for worlds whose names are given in `local_names` because they are already in
+/// this browser.
+pub async fn list_remote_worlds(
+    config: &SyncConfig,
+    local_names: &BTreeMap<WorldId, AttrValue>,
+) -> Result<Vec<RemoteWorld>, SyncError> {
+    let propfind = Method::from_bytes(b"PROPFIND").expect("PROPFIND is a valid method");
+    let response = config
+        .authorize(RequestBuilder::new(&config.folder_url()).method(propfind))
+        .header("Depth", "1")
+        .header("Content-Type", "application/xml")
+        .body(PROPFIND_BODY)?
+        .send()
+        .await?;
+    check_status(&response)?;
+    let mut worlds = Vec::new();
+    for file in parse_listing(&response.text().await?) {
+        let name = match local_names.get(&file.id) {
+            Some(name) => name.clone(),
+            None => match download_world(config, file.id).await {
+                Ok((save_file, _)) => match world_from_save(save_file) {
+                    Some(world) => world.name(),
+                    None => continue,
+                },
+                // Deleted since the folder was listed, or not a world save file.
+                Err(SyncError::NotFound | SyncError::Parse(_)) => continue,
+                Err(e) => return Err(e),
+            },
+        };
+        worlds.push(RemoteWorld {
+            id: file.id,
+            name,
+            modified: file.modified.map(Into::into),
+        });
+    }
+    worlds.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(worlds)
+}
+
+/// Get the world from a downloaded save file, if it is one this version of the app can use.
+pub fn world_from_save(save_file: SaveFile) -> Option<World> {
+    match save_file.into_versioned_model() {
+        VersionedWorldModel::Version1Minor2(world) if world.root.group().is_some() => Some(world),
+        _ => None,
+    }
+}
+
+/// Body of the PROPFIND request used to list the sync folder, asking only for what is shown.
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getlastmodified/></d:prop></d:propfind>"#;
+
+/// Find the world save files in the response to a PROPFIND request. Elements are matched by name
+/// ignoring their namespace prefix, which servers choose freely. Other files in the folder, and
+/// the folder itself, are skipped.
+fn parse_listing(xml: &str) -> Vec<ListedFile> {
+    let mut files = Vec::new();
+    let mut href = None;
+    let mut modified = None;
+    for element in xml.split('<').skip(1) {
+        let Some((tag, text)) = element.split_once('>') else {
+            continue;
+        };
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        let name = name.rsplit(':').next().unwrap_or(name);
+        match (closing, name) {
+            (false, "response") => {
+                href = None;
+                modified = None;
+            }
+            (false, "href") => href = Some(unescape_xml(text.trim())),
+            (false, "getlastmodified") => modified = Some(unescape_xml(text.trim())),
+            (true, "response") => {
+                if let Some(id) = href.as_deref().and_then(world_id_of_href) {
+                    files.push(ListedFile {
+                        id,
+                        modified: modified.take(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    files
+}
+
+/// Get the ID of the world saved at the given path, if it is a world save file.
+fn world_id_of_href(href: &str) -> Option<WorldId> {
+    let file_name = href.rsplit('/').next()?;
+    format!("worlds/{}", file_name.strip_suffix(".json")?)
+        .parse()
+        .ok()
+}
+
+/// Replace the entities which can appear in the text of an XML element.
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Convert unsuccessful responses to errors.
+fn check_status(response: &Response) -> Result<(), SyncError> {
+    match response.status() {
+        _ if response.ok() => Ok(()),
+        404 => Err(SyncError::NotFound),
+        412 => Err(SyncError::Conflict),
+        status => Err(SyncError::Status(status)),
+    }
+}
+
+/// Get the ETag of a response, if the server provided one.
+fn etag(response: &Response) -> Option<String> {
+    response.headers().get("ETag")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_world_files() {
+        let id = WorldId::new();
+        let other = WorldId::new();
+        let xml = format!(
+            r#"<?xml version="1.0"?>
+            <d:multistatus xmlns:d="DAV:">
+                <d:response>
+                    <d:href>/dav/worlds/</d:href>
+                    <d:propstat><d:prop><d:getlastmodified>Mon, 12 Jan 2026 10:00:00 GMT</d:getlastmodified></d:prop></d:propstat>
+                </d:response>
+                <d:response>
+                    <d:href>/dav/worlds/{id}.json</d:href>
+                    <d:propstat><d:prop><d:getlastmodified>Tue, 13 Jan 2026 11:00:00 GMT</d:getlastmodified></d:prop></d:propstat>
+                </d:response>
+                <d:response>
+                    <d:href>/dav/worlds/notes.txt</d:href>
+                </d:response>
+                <D:response xmlns:D="DAV:">
+                    <D:href>https://example.com/dav/worlds/{other}.json</D:href>
+                    <D:propstat><D:prop><D:getlastmodified/></D:prop></D:propstat>
+                </D:response>
+            </d:multistatus>"#,
+            id = id.as_base64(),
+            other = other.as_base64(),
+        );
+        assert_eq!(
+            parse_listing(&xml),
+            [
+                ListedFile {
+                    id,
+                    modified: Some("Tue, 13 Jan 2026 11:00:00 GMT".to_owned()),
+                },
+                ListedFile {
+                    id: other,
+                    modified: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use wasm_bindgen::JsValue;
+use web_sys::js_sys::Date;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, hook, html, use_callback, use_context, AttrValue, Callback, Event, Html,
+    TargetCast,
+};
+
+use crate::inputs::button::Button;
+use crate::inputs::toggle::MaterialCheckbox;
+use crate::material::material_icon;
+use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
+use crate::overlay_window::OverlayWindow;
+use crate::world::sync::SyncConfig;
+use crate::world::{use_sync_controller, use_world_list, RemoteWorlds, SyncStatus};
+
+pub type SyncWindowManager = WindowManager<SyncWindow>;
+pub type SyncWindowDispatcher = ShowWindowDispatcher<SyncWindow>;
+
+/// Gets access to the Sync window dispatcher which controls showing the sync window.
+#[hook]
+pub fn use_sync_window() -> SyncWindowDispatcher {
+    use_context::<SyncWindowDispatcher>()
+        .expect("use_sync_window can only be used from within a child of SyncWindowManager")
+}
+
+/// Window for configuring where worlds are synced and resolving sync conflicts.
+#[function_component]
+pub fn SyncWindow() -> Html {
+    let window_dispatcher = use_sync_window();
+    let close = use_callback(window_dispatcher, |(), window_dispatcher| {
+        window_dispatcher.hide_window();
+    });
+
+    let controller = use_sync_controller();
+    let config = controller.config();
+    let world_list = use_world_list();
+
+    let text_setter = |update: fn(&mut SyncConfig, AttrValue)| {
+        let controller = controller.clone();
+        move |e: Event| {
+            let value: AttrValue = e.target_unchecked_into::<HtmlInputElement>().value().into();
+            let mut config = controller.config().clone();
+            update(&mut config, value);
+            controller.set_config(config);
+        }
+    };
+    let on_url = text_setter(|config, url| config.url = url);
+    let on_username = text_setter(|config, username| config.username = username);
+    let on_password = text_setter(|config, password| config.password = password);
+    let toggle_enabled = {
+        let controller = controller.clone();
+        move |_| {
+            let mut config = controller.config().clone();
+            config.enabled = !config.enabled;
+            controller.set_config(config);
+        }
+    };
+    let sync_now = {
+        let controller = controller.clone();
+        move |()| controller.sync_now()
+    };
+    let overwrite = {
+        let controller = controller.clone();
+        move |()| controller.overwrite_remote()
+    };
+    let download = {
+        let controller = controller.clone();
+        let id = world_list.selected_id();
+        move |()| controller.download_remote(id)
+    };
+    let list_remote = {
+        let controller = controller.clone();
+        move |()| controller.list_remote()
+    };
+
+    let status = match controller.status() {
+        SyncStatus::Idle if config.needs_password() => {
+            html! { {"Enter your password to sync this session."} }
+        }
+        SyncStatus::Idle => html! { {"Not synced yet this session."} },
+        SyncStatus::Syncing => html! { {"Syncing\u{2026}"} },
+        SyncStatus::Synced => html! { {"Up to date with the remote copy."} },
+        SyncStatus::Conflict => html! {
+            <span class="conflict">
+                {material_icon("warning")}
+                {"The remote copy was changed elsewhere. Download it or overwrite it to resume \
+                syncing."}
+            </span>
+        },
+        SyncStatus::Failed(message) => html! {
+            <span class="failed">{material_icon("error")}{message}</span>
+        },
+    };
+    let busy = *controller.status() == SyncStatus::Syncing;
+    let unconfigured = !config.is_configured();
+
+    let remote_worlds = match controller.remote_worlds() {
+        RemoteWorlds::NotListed => html! {},
+        RemoteWorlds::Listing => html! { <p>{"Looking for worlds\u{2026}"}</p> },
+        RemoteWorlds::Listed(worlds) if worlds.is_empty() => {
+            html! { <p>{"There are no worlds in the sync folder yet."}</p> }
+        }
+        RemoteWorlds::Listed(worlds) => worlds
+            .iter()
+            .map(|world| {
+                let modified = world.modified.as_ref().map(|modified| {
+                    String::from(
+                        Date::new(&JsValue::from_str(modified))
+                            .to_locale_string("default", &JsValue::UNDEFINED),
+                    )
+                });
+                let action = if world_list.get(world.id).is_some() {
+                    html! { <span class="local">{"On this computer"}</span> }
+                } else {
+                    let import = {
+                        let controller = controller.clone();
+                        let id = world.id;
+                        Callback::from(move |()| controller.download_remote(id))
+                    };
+                    html! {
+                        <Button class="green" title="Add this world to this computer"
+                            onclick={import} disabled={busy}>
+                            {material_icon("cloud_download")}<span>{"Import"}</span>
+                        </Button>
+                    }
+                };
+                html! {
+                    <div class="remote-world">
+                        <span class="remote-world-name">{&world.name}</span>
+                        <span class="remote-world-modified">{modified}</span>
+                        {action}
+                    </div>
+                }
+            })
+            .collect(),
+        RemoteWorlds::Failed(message) => html! {
+            <p class="failed">{material_icon("error")}{message}</p>
+        },
+    };
+    let listing = *controller.remote_worlds() == RemoteWorlds::Listing;
+
+    html! {
+        <OverlayWindow title="Sync" class="SyncWindow" on_close={close}>
+            <p>{"Sync stores each world in a WebDAV folder, such as one on Nextcloud, so you can \
+            use the same world from more than one computer. The server must allow requests from \
+            this site (CORS). The folder URL and user name are kept in this browser's storage, but \
+            your password is forgotten when you close the browser and must be entered again each \
+            session. An app-specific password is still recommended. Worlds synced from another \
+            computer can be imported from the list of remote worlds."}</p>
+            <div class="settings-section">
+                <label>
+                    <span>{"WebDAV folder URL"}</span>
+                    <input type="url" value={config.url.clone()} onchange={on_url} />
+                </label>
+                <label>
+                    <span>{"User name"}</span>
+                    <input type="text" value={config.username.clone()} onchange={on_username} />
+                </label>
+                <label>
+                    <span>{"Password"}</span>
+                    <input type="password" value={config.password.clone()}
+                        onchange={on_password} />
+                </label>
+                <label>
+                    <span>{"Sync automatically when idle"}</span>
+                    <MaterialCheckbox checked={config.enabled} onclick={toggle_enabled} />
+                </label>
+            </div>
+            <div class="settings-section">
+                <p class="status">{status}</p>
+                <div class="actions">
+                    <Button title="Upload this world if it changed" onclick={sync_now}
+                        disabled={busy || unconfigured}>
+                        {material_icon("cloud_upload")}<span>{"Sync Now"}</span>
+                    </Button>
+                    <Button title="Replace this world with the remote copy" onclick={download}
+                        disabled={busy || unconfigured}>
+                        {material_icon("cloud_download")}<span>{"Download Remote Copy"}</span>
+                    </Button>
+                    if *controller.status() == SyncStatus::Conflict {
+                        <Button class="red" title="Replace the remote copy with this world"
+                            onclick={overwrite} disabled={busy}>
+                            {material_icon("upload")}<span>{"Overwrite Remote Copy"}</span>
+                        </Button>
+                    }
+                </div>
+            </div>
+            <div class="settings-section">
+                <h3>{"Remote Worlds"}</h3>
+                <div class="actions">
+                    <Button title="List the worlds in the sync folder" onclick={list_remote}
+                        disabled={listing || unconfigured}>
+                        {material_icon("cloud_sync")}<span>{"Find Remote Worlds"}</span>
+                    </Button>
+                </div>
+                <div class="remote-worlds">{remote_worlds}</div>
+            </div>
+        </OverlayWindow>
+    }
+}
@@ -0,0 +1,81 @@
+//! Settings and diagnostics for the undo history.
+use serde::{Deserialize, Serialize};
+use yew::{function_component, html, AttrValue, Callback, Html};
+
+use crate::inputs::clickedit::ClickEdit;
+use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+use crate::world::use_undo_controller;
+
+/// Largest number of undo states the user can choose to keep.
+const MAX_UNDO_LIMIT: usize = 1000;
+
+/// Settings for how much undo history is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UndoSettings {
+    /// Maximum number of edits which can be undone.
+    pub max_states: usize,
+}
+
+impl Default for UndoSettings {
+    fn default() -> Self {
+        Self { max_states: 100 }
+    }
+}
+
+/// Lets the user choose how much undo history is kept, and shows how much memory it uses.
+#[function_component]
+pub fn UndoSettingsSection() -> Html {
+    let user_settings = use_user_settings();
+    let settings_dispatcher = use_user_settings_dispatcher();
+    let undo = use_undo_controller();
+    let memory = undo.memory();
+
+    let max_states = user_settings.undo.max_states;
+    let set_max_states = Callback::from(move |edit_text: AttrValue| {
+        if let Ok(max_states) = edit_text.trim().parse::<usize>() {
+            settings_dispatcher.set_undo_settings(UndoSettings {
+                max_states: max_states.clamp(1, MAX_UNDO_LIMIT),
+            });
+        }
+    });
+
+    html! {
+        <div class="settings-section">
+            <h2>{"Undo History"}</h2>
+            <p>{"Choose how many edits can be undone. Unchanged parts of the world are shared \
+            between undo steps, but very large worlds with long histories can still use a lot of \
+            memory. Lowering the limit drops the oldest steps right away."}</p>
+            <ul>
+                <li>
+                    <label>
+                        <ClickEdit class="undo-limit" value={AttrValue::from(max_states.to_string())}
+                            on_commit={set_max_states} title="Maximum undo steps"
+                            prefix={html! { <span class="prefix">{"Undo steps"}</span> }} />
+                    </label>
+                </li>
+            </ul>
+            <details class="undo-memory">
+                <summary>{"Memory usage"}</summary>
+                <table>
+                    <tr>
+                        <th>{"Undo steps"}</th>
+                        <td>{undo.undo_len()}</td>
+                    </tr>
+                    <tr>
+                        <th>{"Redo steps"}</th>
+                        <td>{undo.redo_len()}</td>
+                    </tr>
+                    <tr>
+                        <th>{"Nodes kept only for history"}</th>
+                        <td>{memory.nodes}</td>
+                    </tr>
+                    <tr>
+                        <th>{"Estimated size"}</th>
+                        <td>{format!("{:.1} KiB", memory.bytes as f64 / 1024.0)}</td>
+                    </tr>
+                </table>
+            </details>
+        </div>
+    }
+}
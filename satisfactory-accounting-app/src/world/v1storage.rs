@@ -40,14 +40,42 @@ pub fn try_load_v1() -> Option<World> {
                 database,
                 root,
                 node_metadata,
+                item_metadata: Default::default(),
+                progression: Default::default(),
+                settings_overrides: Default::default(),
+                hidden_columns: Default::default(),
                 global_metadata,
             })
         }
     }
 }
 
+/// Get the database versions which must be fetched before the v1 world can be migrated. The v1
+/// database is matched against every standard version to find which one it is, and a version
+/// which hasn't been fetched can't be compared, so migrating before it arrives would turn the
+/// database into a custom one. Empty if there is no v1 database or it already matches.
+pub fn versions_needed_for_v1() -> Vec<DatabaseVersion> {
+    match read_v1_db() {
+        Some(database) if matching_version(&database).is_none() => DatabaseVersion::ALL
+            .iter()
+            .copied()
+            .filter(|version| !version.is_available())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 /// Try to load a v1 database, or fall back to defaults.
 fn load_v1_db() -> Option<DatabaseChoice> {
+    let database = read_v1_db()?;
+    Some(match matching_version(&database) {
+        Some(version) => DatabaseChoice::Standard(version),
+        None => DatabaseChoice::Custom(database),
+    })
+}
+
+/// Read the v1 database, if there is one.
+fn read_v1_db() -> Option<Database> {
     match LocalStorage::get::<Database>(DB_KEY) {
         Ok(mut database) => {
             // All databases in the DB_KEY should be pre-U6 which means they shouldn't
@@ -56,17 +84,7 @@ fn load_v1_db() -> Option<DatabaseChoice> {
             if database.icon_prefix().is_empty() {
                 database.set_icon_prefix("u5/");
             }
-            Some(
-                DatabaseVersion::ALL
-                    .iter()
-                    .find_map(|&version| match version.load_database() {
-                        db if database.compare_ignore_prefix(&db) => {
-                            Some(DatabaseChoice::Standard(version))
-                        }
-                        _ => None,
-                    })
-                    .unwrap_or_else(move || DatabaseChoice::Custom(database)),
-            )
+            Some(database)
         }
         Err(e) => {
             if !matches!(e, StorageError::KeyNotFound(_)) {
@@ -77,6 +95,16 @@ fn load_v1_db() -> Option<DatabaseChoice> {
     }
 }
 
+/// Find the standard database version which the v1 database is a copy of, among the versions
+/// which are available.
+fn matching_version(database: &Database) -> Option<DatabaseVersion> {
+    DatabaseVersion::ALL.iter().copied().find(|version| {
+        version
+            .try_load_database()
+            .is_some_and(|db| database.compare_ignore_prefix(&db))
+    })
+}
+
 /// Try to load a v1 graph's root node.
 fn load_v1_root_node() -> Option<Node> {
     match LocalStorage::get(GRAPH_KEY) {
@@ -1,31 +1,49 @@
 use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::collections::BTreeSet;
 use std::rc::Rc;
 
-use gloo::file::{Blob, ObjectUrl};
+use gloo::file::ObjectUrl;
 use gloo::storage::errors::StorageError;
-use log::{error, warn};
+use log::warn;
+use satisfactory_accounting::savegame::SaveGrouping;
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::JsCast;
-use web_sys::HtmlAnchorElement;
+use uuid::Uuid;
+use wasm_bindgen::JsValue;
+use web_sys::js_sys::Date;
 use yew::{
-    classes, function_component, hook, html, use_callback, use_context, use_mut_ref, AttrValue,
-    Callback, Html, Properties,
+    classes, function_component, hook, html, use_callback, use_context, use_force_update, use_memo,
+    use_mut_ref, use_state_eq, AttrValue, Callback, Html, InputEvent, Properties, SubmitEvent,
 };
 
 use crate::bugreport::file_a_bug;
+use crate::download::download_text;
 use crate::inputs::button::{Button, UploadButton, UploadedFile};
+use crate::inputs::events::get_value_from_input_event;
+use crate::inputs::toggle::{MaterialCheckbox, MaterialRadio};
 use crate::material::material_icon;
 use crate::modal::{
     use_modal_dispatcher, BinaryChoice, CancelDelete, ModalDispatcher, ModalHandle, ModalOk,
 };
+use crate::node_display::Icon;
 use crate::overlay_window::controller::{ShowWindowDispatcher, WindowManager};
 use crate::overlay_window::OverlayWindow;
-use crate::user_settings::{use_user_settings, use_user_settings_dispatcher};
+use crate::user_settings::number_format::UserConfiguredFormat;
+use crate::user_settings::{
+    use_global_user_settings, use_settings_profiles, use_user_settings,
+    use_user_settings_dispatcher,
+};
+use crate::world::archive::{Archive, UnpackedArchive, WorldHistory};
+use crate::world::checkpoints;
 use crate::world::manager::PendingUpload;
+use crate::world::savefile::VersionedWorldModel;
+use crate::world::share::{ShareOptions, ShareScope, SharedView};
+use crate::world::snapshots;
+use crate::world::storageusage::{format_bytes, StorageUsage, ESTIMATED_QUOTA, WARN_FRACTION};
 use crate::world::{
-    use_save_file_fetcher, use_world_list, use_world_list_dispatcher, DatabaseVersionSelector,
-    FetchSaveFileError, WorldId, WorldMetadata,
+    use_db, use_save_file_fetcher, use_templates, use_world_list, use_world_list_dispatcher,
+    use_world_root, DatabaseVersionSelector, FetchSaveFileError, TemplateChoice, WorldId,
+    WorldMetadata,
 };
 
 /// Message to control WorlSortSettings.
@@ -132,7 +150,7 @@ pub fn WorldChooserWindow() -> Html {
     // This is used to keep the modal alive until the world window is closed.
     let upload_modal_handle = use_mut_ref(|| None::<ModalHandle>);
     let on_matches_existing = use_callback(
-        modal_dispatcher,
+        modal_dispatcher.clone(),
         move |pending: PendingUpload, modal_dispatcher| {
             let lhs = html! { <span>{"Upload as new World"}</span> };
             let rhs = html! { <span>{"Replace existing World"}</span> };
@@ -182,6 +200,7 @@ pub fn WorldChooserWindow() -> Html {
             *upload_modal_handle.borrow_mut() = Some(handle);
         },
     );
+    let import_modal_handle = use_mut_ref(|| None::<ModalHandle>);
 
     let upload_world = use_callback(
         (world_list_dispatcher.clone(), on_matches_existing),
@@ -190,6 +209,44 @@ pub fn WorldChooserWindow() -> Html {
         },
     );
 
+    let import_game_save = use_callback(
+        (world_list_dispatcher.clone(), modal_dispatcher),
+        move |file: UploadedFile, (world_list_dispatcher, modal_dispatcher)| {
+            let lhs = html! { <span>{"By Location"}</span> };
+            let rhs = html! { <span>{"By Power Circuit"}</span> };
+            let content = html! {
+                <p>{"Buildings from the save \""}{&file.name}{"\" can be grouped into factories \
+                of nearby buildings, or by the power circuit they are connected to."}</p>
+            };
+            let file = Rc::new(RefCell::new(Some(file)));
+            let import_with = |grouping| {
+                let file = file.clone();
+                let world_list_dispatcher = world_list_dispatcher.clone();
+                Callback::from(move |()| {
+                    if let Some(file) = file.take() {
+                        world_list_dispatcher.import_game_save(file.name, file.data, grouping);
+                    } else {
+                        warn!("Game save import already finished");
+                    }
+                })
+            };
+            let handle = modal_dispatcher
+                .builder()
+                .title("Group Buildings How?")
+                .content(content)
+                .class("import-game-save-grouping-choice")
+                .kind(
+                    BinaryChoice::new(lhs, rhs)
+                        .lhs_title("Group buildings which are near each other")
+                        .rhs_title("Group buildings connected to the same power circuit")
+                        .on_lhs(import_with(SaveGrouping::Proximity))
+                        .on_rhs(import_with(SaveGrouping::PowerCircuit)),
+                )
+                .build();
+            *import_modal_handle.borrow_mut() = Some(handle);
+        },
+    );
+
     let create_world = use_callback(world_list_dispatcher, |(), world_list_dispatcher| {
         world_list_dispatcher.create_world();
     });
@@ -297,6 +354,12 @@ pub fn WorldChooserWindow() -> Html {
                             {material_icon("upload")}
                             <span>{"Upload World"}</span>
                         </UploadButton>
+                        <UploadButton class="green"
+                            title="Create a World from the buildings in a Satisfactory save (.sav)"
+                            onupload={import_game_save}>
+                            {material_icon("factory")}
+                            <span>{"Import Game Save"}</span>
+                        </UploadButton>
                         <Button class="green" onclick={create_world} title="Create">
                             {material_icon("add")}
                             <span>{"Create New World"}</span>
@@ -305,6 +368,10 @@ pub fn WorldChooserWindow() -> Html {
                 </div>
                 {for world_rows}
             </div>
+            <TrashList />
+            <TemplatePicker />
+            <ArchiveControls />
+            <StorageUsagePanel />
         </OverlayWindow>
     }
 }
@@ -333,37 +400,42 @@ fn WorldListRow(
         dispatcher.set_world(*id);
     });
 
-    let modal_handle: Rc<RefCell<Option<ModalHandle>>> = use_mut_ref(Default::default);
     let modals = use_modal_dispatcher();
 
-    let delete_forever = use_callback((id, dispatcher), |(), (id, dispatcher)| {
+    let delete_world = use_callback((id, dispatcher.clone()), |(), (id, dispatcher)| {
         dispatcher.delete_world(*id);
     });
 
     let download = use_download_callback(id, meta.name.clone(), modals.clone());
 
-    let delete_world = use_callback(
-        (modals, delete_forever, meta.name.clone()),
-        move |(), (modals, delete_forever, name)| {
-            let modal = modals
-                .builder()
-                .title("Confirm Delete")
-                .content(html! {
-                   <div class="delete-content">
-                       <p>{"Are you sure you want to delete the world "}{name}{"?"}</p>
-                       <h2>{"This CANNOT be undone!"}</h2>
-                   </div>
-                })
-                .class("modal-delete-forever")
-                .kind(CancelDelete::delete(delete_forever.clone()))
-                .build();
-            *modal_handle.borrow_mut() = Some(modal);
-        },
-    );
+    let save_template = use_callback((id, dispatcher), |(), (id, dispatcher)| {
+        dispatcher.save_template(*id);
+    });
+
+    let show_snapshots = use_state_eq(|| false);
+    let toggle_snapshots = use_callback(show_snapshots.clone(), |(), show_snapshots| {
+        show_snapshots.set(!**show_snapshots);
+    });
+
+    let show_checkpoints = use_state_eq(|| false);
+    let toggle_checkpoints = use_callback(show_checkpoints.clone(), |(), show_checkpoints| {
+        show_checkpoints.set(!**show_checkpoints);
+    });
+
+    let show_share = use_state_eq(|| false);
+    let toggle_share = use_callback(show_share.clone(), |(), show_share| {
+        show_share.set(!**show_share);
+    });
+
+    let show_import = use_state_eq(|| false);
+    let toggle_import = use_callback(show_import.clone(), |(), show_import| {
+        show_import.set(!**show_import);
+    });
 
     let classes = classes!("WorldListRow", selected.then_some("selected"));
 
     html! {
+        <>
         <div class={classes}>
             <span class="world-name">{&meta.name}</span>
             <span class="world-version">
@@ -379,6 +451,24 @@ fn WorldListRow(
                     }
                 </Button>
             }
+            if !selected {
+                <Button key="import" class="import-groups"
+                    title="Import Groups into the Current World" onclick={toggle_import}>
+                    {material_icon("move_to_inbox")}
+                </Button>
+            }
+            <Button key="template" class="save-template" title="Save as Template"
+                onclick={save_template}>
+                {material_icon("bookmark_add")}
+            </Button>
+            <Button key="snapshots" class="world-snapshots" title="Restore from Snapshot"
+                onclick={toggle_snapshots}>
+                {material_icon("history")}
+            </Button>
+            <Button key="checkpoints" class="world-checkpoints" title="Named Checkpoints"
+                onclick={toggle_checkpoints}>
+                {material_icon("flag")}
+            </Button>
             <Button key="download" class="download-world" title="Download World" onclick={download}>
                 if meta.load_error {
                     {material_icon("warning")}
@@ -386,10 +476,932 @@ fn WorldListRow(
                     {material_icon("download")}
                 }
             </Button>
-            <Button key="delete" class="red delete-world" title="Delete World" onclick={delete_world}>
+            <Button key="share" class="share-world" title="Share Link" onclick={toggle_share}>
+                {material_icon("share")}
+            </Button>
+            <Button key="delete" class="red delete-world" title="Move World to the Trash" onclick={delete_world}>
                 {material_icon("delete")}
             </Button>
         </div>
+        if *show_snapshots {
+            <SnapshotList {id} />
+        }
+        if *show_checkpoints {
+            <CheckpointList {id} />
+        }
+        if *show_import && !selected {
+            <ImportGroupsList {id} />
+        }
+        if *show_share {
+            <ShareLinkPanel {id} />
+        }
+        </>
+    }
+}
+
+/// Lists the worlds in the trash, allowing them to be restored or permanently deleted.
+#[function_component]
+fn TrashList() -> Html {
+    let world_list = use_world_list();
+    let dispatcher = use_world_list_dispatcher();
+    let modals = use_modal_dispatcher();
+    let modal_handle: Rc<RefCell<Option<ModalHandle>>> = use_mut_ref(Default::default);
+
+    let mut trashed: Vec<_> = world_list.trash_iter().collect();
+    if trashed.is_empty() {
+        return html! {};
+    }
+    trashed.sort_by(|(_, lhs), (_, rhs)| rhs.deleted.total_cmp(&lhs.deleted));
+    let rows = trashed.into_iter().map(|(id, trashed)| {
+        let restore = {
+            let dispatcher = dispatcher.clone();
+            Callback::from(move |()| dispatcher.restore_world(id))
+        };
+        let delete_forever = {
+            let dispatcher = dispatcher.clone();
+            let modals = modals.clone();
+            let modal_handle = modal_handle.clone();
+            let name = trashed.meta.name.clone();
+            Callback::from(move |()| {
+                let dispatcher = dispatcher.clone();
+                let modal = modals
+                    .builder()
+                    .title("Confirm Delete")
+                    .content(html! {
+                       <div class="delete-content">
+                           <p>{"Are you sure you want to permanently delete the world "}{&name}
+                           {"?"}</p>
+                           <h2>{"This CANNOT be undone!"}</h2>
+                       </div>
+                    })
+                    .class("modal-delete-forever")
+                    .kind(CancelDelete::delete(Callback::from(move |()| {
+                        dispatcher.purge_world(id)
+                    })))
+                    .build();
+                *modal_handle.borrow_mut() = Some(modal);
+            })
+        };
+        let date = String::from(
+            Date::new(&JsValue::from_f64(trashed.deleted))
+                .to_locale_string("default", &JsValue::UNDEFINED),
+        );
+        html! {
+            <div class="trashed-world" key={id.as_base64().to_string()}>
+                <span class="trashed-name">{&trashed.meta.name}</span>
+                <span class="trashed-date">{"Deleted "}{date}</span>
+                <Button class="green" title="Restore this World" onclick={restore}>
+                    {material_icon("restore_from_trash")}
+                    <span>{"Restore"}</span>
+                </Button>
+                <Button class="red" title="Permanently Delete this World" onclick={delete_forever}>
+                    {material_icon("delete_forever")}
+                </Button>
+            </div>
+        }
+    });
+
+    html! {
+        <div class="TrashList">
+            <h3>{"Trash"}</h3>
+            <p>{"Deleted worlds are kept here for 30 days before they are permanently deleted."}</p>
+            {for rows}
+        </div>
+    }
+}
+
+/// Lists the templates new worlds can be created from.
+#[function_component]
+fn TemplatePicker() -> Html {
+    let templates = use_templates();
+    let dispatcher = use_world_list_dispatcher();
+
+    let built_in = TemplateChoice::BUILT_IN.into_iter().map(|(choice, name)| {
+        let create = {
+            let dispatcher = dispatcher.clone();
+            Callback::from(move |()| dispatcher.create_world_from_template(choice))
+        };
+        html! {
+            <div class="template-row">
+                <span class="template-name">{name}</span>
+                <Button class="green" title="Create a World from this Template" onclick={create}>
+                    {material_icon("add")}
+                    <span>{"Create"}</span>
+                </Button>
+            </div>
+        }
+    });
+    let saved = templates.iter().map(|(id, template)| {
+        let create = {
+            let dispatcher = dispatcher.clone();
+            Callback::from(move |()| {
+                dispatcher.create_world_from_template(TemplateChoice::Saved(id))
+            })
+        };
+        let delete = {
+            let dispatcher = dispatcher.clone();
+            Callback::from(move |()| dispatcher.delete_template(id))
+        };
+        html! {
+            <div class="template-row" key={id.to_string()}>
+                <span class="template-name">{&template.name}</span>
+                <Button class="green" title="Create a World from this Template" onclick={create}>
+                    {material_icon("add")}
+                    <span>{"Create"}</span>
+                </Button>
+                <Button class="red" title="Delete Template" onclick={delete}>
+                    {material_icon("delete")}
+                </Button>
+            </div>
+        }
+    });
+
+    html! {
+        <div class="TemplatePicker">
+            <h3>{"Templates"}</h3>
+            {for built_in}
+            {for saved}
+            if templates.is_empty() {
+                <p class="template-hint">{"Save any world as a template with its "}
+                {material_icon("bookmark_add")}{" button to start new worlds from it."}</p>
+            }
+        </div>
+    }
+}
+
+/// Buttons to download every world as a single backup file, or restore such a backup.
+#[function_component]
+fn ArchiveControls() -> Html {
+    let world_list = use_world_list();
+    let world_list_dispatcher = use_world_list_dispatcher();
+    let templates = use_templates();
+    let user_settings = use_global_user_settings();
+    let settings_profiles = use_settings_profiles();
+    let user_settings_dispatcher = use_user_settings_dispatcher();
+    let save_file_fetcher = use_save_file_fetcher();
+    let modals = use_modal_dispatcher();
+    // Keeps the download url alive until the world chooser is closed.
+    let download_url_retainer: Rc<RefCell<Option<ObjectUrl>>> = use_mut_ref(|| None);
+
+    let download_all = use_callback(
+        (
+            world_list,
+            templates,
+            user_settings,
+            settings_profiles,
+            save_file_fetcher,
+            modals.clone(),
+        ),
+        move |(), (world_list, templates, user_settings, settings_profiles, fetcher, modals)| {
+            let mut worlds = Vec::with_capacity(world_list.iter().len());
+            let mut histories = Vec::new();
+            let mut missing = Vec::new();
+            for meta in world_list.iter() {
+                let id = meta.id();
+                match fetcher.get_save_file(id) {
+                    Ok(save_file) => worlds.push(save_file),
+                    Err(e) => {
+                        warn!("Leaving world {id:?} out of the backup: {e}");
+                        missing.push(meta.name.clone());
+                        continue;
+                    }
+                }
+                let history = WorldHistory {
+                    id,
+                    checkpoints: checkpoints::load(id).unwrap_or_else(|e| {
+                        warn!("Leaving checkpoints of world {id:?} out of the backup: {e}");
+                        Vec::new()
+                    }),
+                    snapshots: snapshots::load(id).unwrap_or_else(|e| {
+                        warn!("Leaving snapshots of world {id:?} out of the backup: {e}");
+                        Default::default()
+                    }),
+                };
+                if !history.is_empty() {
+                    histories.push(history);
+                }
+            }
+            let archive = Archive::new(
+                world_list.selected_id(),
+                worlds,
+                histories,
+                templates.clone(),
+                (**user_settings).clone(),
+                (**settings_profiles).clone(),
+            );
+            let json = match serde_json::to_string(&archive) {
+                Ok(json) => json,
+                Err(e) => {
+                    return modals
+                        .builder()
+                        .class("world-download-error")
+                        .kind(ModalOk::close())
+                        .title("Backup could not be serialized")
+                        .content(html! {
+                            <>
+                                <p>{"We loaded your worlds but couldn't serialize them to create \
+                                the backup file for some reason. This is probably a bug, and you \
+                                can "}{file_a_bug()}{". If you file a bug, please include this \
+                                error message:"}</p>
+                                <pre>
+                                    {"Unable to serialize backup: "}{e}
+                                </pre>
+                            </>
+                        })
+                        .build()
+                        .persist();
+                }
+            };
+            let date = String::from(Date::new_0().to_iso_string());
+            let filename = format!(
+                "SatisfactoryAccounting-Backup-{}.json",
+                date.get(..10).unwrap_or(&date)
+            );
+            if let Some(url) = download_text(&json, "application/json", &filename) {
+                *download_url_retainer.borrow_mut() = Some(url);
+            }
+            if !missing.is_empty() {
+                modals
+                    .builder()
+                    .class("world-download-error")
+                    .kind(ModalOk::close())
+                    .title("Some worlds were left out")
+                    .content(html! {
+                        <>
+                            <p>{"These worlds could not be loaded from your browser's storage, \
+                            so they are missing from the backup:"}</p>
+                            <ul>
+                                {for missing.into_iter().map(|name| html! { <li>{name}</li> })}
+                            </ul>
+                        </>
+                    })
+                    .build()
+                    .persist();
+            }
+        },
+    );
+
+    let restore = use_callback(
+        (world_list_dispatcher, user_settings_dispatcher, modals),
+        |file: UploadedFile, (world_list_dispatcher, user_settings_dispatcher, modals)| {
+            let archive = match Archive::read(&file.data) {
+                Ok(archive) => archive.unpack(),
+                Err(e) => {
+                    warn!("Unable to read backup: {e}");
+                    return modals
+                        .builder()
+                        .class("restore-backup-error")
+                        .kind(ModalOk::close())
+                        .title("Could not read Backup")
+                        .content(html! {
+                            <>
+                                <p>{"The file \""}{&file.name}{"\" does not appear to be a \
+                                backup of all worlds. To add a single world, use the Upload World \
+                                button instead. If you believe this is incorrect you can "}
+                                {file_a_bug()}{". If you do file a bug, please include this error \
+                                message:"}</p>
+                                <pre>{e.to_string()}</pre>
+                            </>
+                        })
+                        .build()
+                        .persist();
+                }
+            };
+            let count = archive.worlds.len();
+            let skipped = archive.skipped;
+            let includes_settings = archive.user_settings.is_some();
+            let includes_profiles = archive.settings_profiles.is_some();
+            // The archive is moved into the callback instead of being cloned every time the modal
+            // is rendered.
+            let archive = Rc::new(RefCell::new(Some(archive)));
+            let on_restore = {
+                let world_list_dispatcher = world_list_dispatcher.clone();
+                let user_settings_dispatcher = user_settings_dispatcher.clone();
+                Callback::from(move |()| {
+                    let Some(UnpackedArchive {
+                        worlds,
+                        selected,
+                        templates,
+                        user_settings,
+                        histories,
+                        settings_profiles,
+                        ..
+                    }) = archive.take()
+                    else {
+                        warn!("Backup already restored");
+                        return;
+                    };
+                    world_list_dispatcher.restore_archive(worlds, selected, templates, histories);
+                    if let Some(settings) = user_settings {
+                        user_settings_dispatcher.replace_all(settings);
+                    }
+                    // Replaced after the settings, so the active profile isn't updated with the
+                    // settings from before the restore.
+                    if let Some(profiles) = settings_profiles {
+                        user_settings_dispatcher.replace_profiles(profiles);
+                    }
+                })
+            };
+            modals
+                .builder()
+                .title("Restore Backup?")
+                .class("restore-backup-choice")
+                .kind(
+                    BinaryChoice::new(html! {"Cancel"}, html! {"Restore"})
+                        .lhs_title("Don't restore the backup")
+                        .rhs_title("Restore the worlds in the backup")
+                        .on_rhs(on_restore),
+                )
+                .content(html! {
+                    <>
+                        <p>{"The backup \""}{&file.name}{"\" contains "}{count}{" worlds. \
+                        Restoring it replaces any of your worlds with the same IDs, along with \
+                        their checkpoints and snapshots if the backup has them, while your other \
+                        worlds are kept."}</p>
+                        if includes_settings && includes_profiles {
+                            <p>{"Your settings and settings profiles will also be replaced with \
+                            the ones from the backup."}</p>
+                        } else if includes_settings {
+                            <p>{"Your settings will also be replaced with the settings from the \
+                            backup."}</p>
+                        }
+                        if skipped > 0 {
+                            <p>{skipped}{" worlds in the backup were made by an unrecognized \
+                            version of Satisfactory Accounting and will be skipped."}</p>
+                        }
+                    </>
+                })
+                .build()
+                .persist();
+        },
+    );
+
+    html! {
+        <div class="ArchiveControls">
+            <h3>{"Backup"}</h3>
+            <p>{"Download every world along with its checkpoints and snapshots, your templates, \
+            and your settings and settings profiles as a single file, or restore everything from \
+            such a file, such as when moving to another computer."}</p>
+            <div class="archive-buttons">
+                <Button class="green" title="Download a Backup of All Worlds"
+                    onclick={download_all}>
+                    {material_icon("download")}
+                    <span>{"Download All Worlds"}</span>
+                </Button>
+                <UploadButton class="green" title="Restore Worlds from a Backup"
+                    onupload={restore}>
+                    {material_icon("upload")}
+                    <span>{"Restore Backup"}</span>
+                </UploadButton>
+            </div>
+        </div>
+    }
+}
+
+/// Shows how much of the browser's local storage each world uses.
+#[function_component]
+fn StorageUsagePanel() -> Html {
+    // Re-measured whenever the world list changes, such as after saving a world.
+    let world_list = use_world_list();
+    let refresh = use_force_update();
+    let refresh = Callback::from(move |()| refresh.force_update());
+
+    let content = match StorageUsage::measure() {
+        Ok(usage) => {
+            let mut worlds: Vec<_> = usage.worlds.iter().collect();
+            worlds.sort_by(|(_, lhs), (_, rhs)| rhs.cmp(lhs));
+            let rows = worlds.into_iter().map(|(&id, &bytes)| {
+                let name = world_list
+                    .iter()
+                    .find(|meta| meta.id() == id)
+                    .map(|meta| meta.name.to_string())
+                    .or_else(|| {
+                        world_list
+                            .trash_iter()
+                            .find(|&(trashed_id, _)| trashed_id == id)
+                            .map(|(_, trashed)| format!("{} (in Trash)", trashed.meta.name))
+                    })
+                    .unwrap_or_else(|| format!("Unlisted World ({})", id.as_base64()));
+                html! {
+                    <tr key={id.as_base64().to_string()}>
+                        <td>{name}</td>
+                        <td class="storage-bytes">{format_bytes(bytes)}</td>
+                    </tr>
+                }
+            });
+            let fraction = usage.fraction_used();
+            html! {<>
+                <div class="storage-total">
+                    <meter min="0" max="1" high={WARN_FRACTION.to_string()}
+                        value={fraction.to_string()} />
+                    <span>{format!(
+                        "{} of about {} ({:.0}%)",
+                        format_bytes(usage.total()),
+                        format_bytes(ESTIMATED_QUOTA),
+                        fraction * 100.0,
+                    )}</span>
+                    <Button title="Measure Storage Usage Again" onclick={refresh}>
+                        {material_icon("refresh")}
+                    </Button>
+                </div>
+                if usage.is_near_quota() {
+                    <p class="storage-warning">{"Your browser storage is nearly full. Once it is \
+                    full, changes to your worlds can no longer be saved. Download a backup, then \
+                    delete worlds you no longer need to free up space."}</p>
+                }
+                <table class="storage-breakdown">
+                    {for rows}
+                    <tr>
+                        <td>{"Settings, Templates, and Clipboard"}</td>
+                        <td class="storage-bytes">{format_bytes(usage.other)}</td>
+                    </tr>
+                </table>
+            </>}
+        }
+        Err(e) => html! {
+            <p class="storage-warning">
+                {"Unable to measure storage usage: "}{format!("{e:?}")}
+            </p>
+        },
+    };
+
+    html! {
+        <div class="StorageUsagePanel">
+            <h3>{"Storage Usage"}</h3>
+            <p>{"Worlds are saved in your browser's local storage, which browsers usually limit \
+            to about 10 MiB per website. Each world's usage includes its snapshots."}</p>
+            {content}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct ImportGroupsListProps {
+    /// ID of the world to import groups from.
+    id: WorldId,
+}
+
+/// Lists the top-level groups of another world, allowing them to be copied into the current world.
+#[function_component]
+fn ImportGroupsList(&ImportGroupsListProps { id }: &ImportGroupsListProps) -> Html {
+    let dispatcher = use_world_list_dispatcher();
+    let fetcher = use_save_file_fetcher();
+    let groups = use_memo(id, move |&id| fetcher.top_level_groups(id));
+    let chosen = use_state_eq(BTreeSet::<Uuid>::new);
+
+    let content = match &*groups {
+        Ok(groups) if groups.is_empty() => html! {
+            <p>{"This world has no top-level groups to import."}</p>
+        },
+        Ok(groups) => {
+            let rows = groups.iter().map(|(group_id, name)| {
+                let group_id = *group_id;
+                let toggle = {
+                    let chosen = chosen.clone();
+                    Callback::from(move |_| {
+                        let mut new_chosen = (*chosen).clone();
+                        if !new_chosen.remove(&group_id) {
+                            new_chosen.insert(group_id);
+                        }
+                        chosen.set(new_chosen);
+                    })
+                };
+                html! {
+                    <label class="import-group" key={group_id.to_string()}>
+                        <MaterialCheckbox checked={chosen.contains(&group_id)} onclick={toggle} />
+                        <span>{if name.is_empty() { "Unnamed Group" } else { name }}</span>
+                    </label>
+                }
+            });
+            let import = {
+                let chosen = chosen.clone();
+                Callback::from(move |()| {
+                    dispatcher.import_groups(id, chosen.iter().copied().collect());
+                    chosen.set(BTreeSet::new());
+                })
+            };
+            html! {<>
+                {for rows}
+                <Button class="green" title="Copy the chosen groups into the current world"
+                    onclick={import} disabled={chosen.is_empty()}>
+                    {material_icon("move_to_inbox")}
+                    <span>{"Import Selected"}</span>
+                </Button>
+            </>}
+        }
+        Err(e) => html! {
+            <p class="import-error">
+                {material_icon("warning")}
+                {format!("Unable to read the groups of this world: {e}")}
+            </p>
+        },
+    };
+
+    html! {
+        <div class="ImportGroupsList">
+            <p>{"Imported groups are added to the end of the current world as copies, along \
+            with their notes and whether they are collapsed."}</p>
+            {content}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct ShareLinkPanelProps {
+    /// ID of the world to share.
+    id: WorldId,
+}
+
+/// Shows a link which opens a copy of a world, for sharing it with other people.
+#[function_component]
+fn ShareLinkPanel(&ShareLinkPanelProps { id }: &ShareLinkPanelProps) -> Html {
+    let fetcher = use_save_file_fetcher();
+    let scope = use_state_eq(ShareScope::default);
+    let include_notes = use_state_eq(|| false);
+    let link = use_memo(
+        (id, *scope, *include_notes),
+        move |&(id, scope, include_notes)| {
+            let save_file = fetcher.get_save_file(id).map_err(|e| e.to_string())?;
+            let world = match save_file.into_versioned_model() {
+                VersionedWorldModel::Version1Minor2(world) => world,
+                VersionedWorldModel::Unknown { model_version } => {
+                    return Err(format!(
+                        "The world has an unrecognized model version {model_version:?}"
+                    ));
+                }
+            };
+            let options = ShareOptions {
+                scope,
+                include_notes,
+            };
+            SharedView::new(&world, options)
+                .link()
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    let share_summary = use_callback(scope.clone(), |_, scope| {
+        scope.set(ShareScope::Summary);
+    });
+    let share_full_tree = use_callback(scope.clone(), |_, scope| {
+        scope.set(ShareScope::FullTree);
+    });
+    let toggle_notes = use_callback(include_notes.clone(), |_, include_notes| {
+        include_notes.set(!**include_notes);
+    });
+    let copy = use_callback(link.clone(), |(), link| {
+        if let Ok(link) = &**link {
+            // The returned promise only reports whether the copy succeeded, which is visible to
+            // the user anyway.
+            let _ = gloo::utils::window()
+                .navigator()
+                .clipboard()
+                .write_text(link);
+        }
+    });
+
+    let content = match &*link {
+        Ok(link) => html! {<>
+            <div class="share-link">
+                <input type="text" readonly=true value={link.clone()} />
+                <Button class="green" title="Copy the Link" onclick={copy}>
+                    {material_icon("content_copy")}
+                    <span>{"Copy"}</span>
+                </Button>
+            </div>
+            if *scope == ShareScope::FullTree {
+                <label class="share-option">
+                    <MaterialCheckbox checked={*include_notes} onclick={toggle_notes} />
+                    <span>{"Include item and node notes"}</span>
+                </label>
+            }
+        </>},
+        Err(e) => html! {
+            <p class="share-error">
+                {material_icon("warning")}
+                {format!("Unable to create a link for this world: {e}")}
+            </p>
+        },
+    };
+
+    html! {
+        <div class="ShareLinkPanel">
+            <label class="share-option">
+                <MaterialRadio checked={*scope == ShareScope::Summary} onclick={share_summary} />
+                <span>{"Share only the world's name and overall balance"}</span>
+            </label>
+            <label class="share-option">
+                <MaterialRadio checked={*scope == ShareScope::FullTree} onclick={share_full_tree} />
+                <span>{"Share a copy of the whole world"}</span>
+            </label>
+            if *scope == ShareScope::FullTree {
+                <p>{"Anyone who opens this link gets their own copy of the world. The world is \
+                stored in the link itself, so very large worlds may make links too long for some \
+                chat apps."}</p>
+            } else {
+                <p>{"Anyone who opens this link sees the world's overall balance, but not its \
+                groups, buildings or notes."}</p>
+            }
+            {content}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct SnapshotListProps {
+    /// ID of the world to list snapshots of.
+    id: WorldId,
+}
+
+/// Lists the automatic snapshots of a world, allowing any of them to be restored.
+#[function_component]
+fn SnapshotList(&SnapshotListProps { id }: &SnapshotListProps) -> Html {
+    let dispatcher = use_world_list_dispatcher();
+    let snapshots = use_memo(id, |&id| snapshots::list(id));
+
+    let content = match &*snapshots {
+        Ok(snapshots) if snapshots.is_empty() => html! {
+            <p>{"No snapshots yet. A snapshot of a world is taken every few minutes while you \
+            edit it."}</p>
+        },
+        Ok(snapshots) => snapshots
+            .iter()
+            .map(|snapshot| {
+                let taken = snapshot.taken;
+                let restore = {
+                    let dispatcher = dispatcher.clone();
+                    Callback::from(move |()| dispatcher.restore_snapshot(id, taken))
+                };
+                let date = String::from(
+                    Date::new(&JsValue::from_f64(taken))
+                        .to_locale_string("default", &JsValue::UNDEFINED),
+                );
+                html! {
+                    <div class="snapshot">
+                        <span class="snapshot-taken">{date}</span>
+                        <span class="snapshot-name">{&snapshot.name}</span>
+                        <Button class="green" title="Restore this Snapshot" onclick={restore}>
+                            {material_icon("restore")}
+                            <span>{"Restore"}</span>
+                        </Button>
+                    </div>
+                }
+            })
+            .collect(),
+        Err(e) => html! {
+            <p class="snapshot-error">
+                {material_icon("warning")}
+                {format!("Unable to read snapshots of this world: {e}")}
+            </p>
+        },
+    };
+
+    html! {
+        <div class="SnapshotList">
+            <p>{"Restoring a snapshot replaces the world, but the current state is kept in the \
+            undo history."}</p>
+            {content}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct CheckpointListProps {
+    /// ID of the world to list checkpoints of.
+    id: WorldId,
+}
+
+/// Lists the named checkpoints of a world, allowing new ones to be created and any of them to be
+/// compared with the current world or restored.
+#[function_component]
+fn CheckpointList(&CheckpointListProps { id }: &CheckpointListProps) -> Html {
+    let dispatcher = use_world_list_dispatcher();
+    let fetcher = use_save_file_fetcher();
+    // Incremented after creating or deleting a checkpoint so the list is reloaded.
+    let revision = use_state_eq(|| 0u32);
+    let checkpoints = use_memo((id, *revision), |&(id, _)| checkpoints::list(id));
+    let name = use_state_eq(AttrValue::default);
+    let comparing = use_state_eq(|| None::<f64>);
+    let error = use_state_eq(|| None::<String>);
+
+    let oninput = use_callback(name.clone(), |e: InputEvent, name| {
+        name.set(get_value_from_input_event(e));
+    });
+    let create = use_callback(
+        (id, fetcher, name.clone(), revision.clone(), error.clone()),
+        |e: SubmitEvent, (id, fetcher, name, revision, error)| {
+            e.prevent_default();
+            let checkpoint_name = match name.trim() {
+                "" => "Unnamed Checkpoint".to_owned(),
+                trimmed => trimmed.to_owned(),
+            };
+            match fetcher.create_checkpoint(*id, checkpoint_name) {
+                Ok(()) => {
+                    name.set(AttrValue::default());
+                    revision.set(**revision + 1);
+                    error.set(None);
+                }
+                Err(e) => {
+                    warn!("Unable to create a checkpoint of world {id:?}: {e}");
+                    error.set(Some(format!("Unable to create checkpoint: {e}")));
+                }
+            }
+        },
+    );
+
+    let content = match &*checkpoints {
+        Ok(checkpoints) if checkpoints.is_empty() => html! {
+            <p>{"No checkpoints yet."}</p>
+        },
+        Ok(checkpoints) => checkpoints
+            .iter()
+            .map(|checkpoint| {
+                let taken = checkpoint.taken;
+                let compare = {
+                    let comparing = comparing.clone();
+                    Callback::from(move |()| {
+                        comparing.set((*comparing != Some(taken)).then_some(taken))
+                    })
+                };
+                let restore = {
+                    let dispatcher = dispatcher.clone();
+                    Callback::from(move |()| dispatcher.restore_checkpoint(id, taken))
+                };
+                let delete = {
+                    let revision = revision.clone();
+                    let error = error.clone();
+                    Callback::from(move |()| match checkpoints::remove(id, taken) {
+                        Ok(()) => revision.set(*revision + 1),
+                        Err(e) => {
+                            warn!("Unable to delete checkpoint {taken} of world {id:?}: {e}");
+                            error.set(Some(format!("Unable to delete checkpoint: {e}")));
+                        }
+                    })
+                };
+                let date = String::from(
+                    Date::new(&JsValue::from_f64(taken))
+                        .to_locale_string("default", &JsValue::UNDEFINED),
+                );
+                let is_comparing = *comparing == Some(taken);
+                html! {
+                    <>
+                    <div class="checkpoint">
+                        <span class="checkpoint-taken">{date}</span>
+                        <span class="checkpoint-name">{&checkpoint.name}</span>
+                        <Button class={classes!(is_comparing.then_some("active"))}
+                            title="Compare with the Current World" onclick={compare}>
+                            {material_icon("difference")}
+                        </Button>
+                        <Button class="green" title="Restore this Checkpoint" onclick={restore}>
+                            {material_icon("restore")}
+                            <span>{"Restore"}</span>
+                        </Button>
+                        <Button class="red" title="Delete this Checkpoint" onclick={delete}>
+                            {material_icon("delete")}
+                        </Button>
+                    </div>
+                    if is_comparing {
+                        <CheckpointDiff {id} {taken} />
+                    }
+                    </>
+                }
+            })
+            .collect(),
+        Err(e) => html! {
+            <p class="checkpoint-error">
+                {material_icon("warning")}
+                {format!("Unable to read checkpoints of this world: {e}")}
+            </p>
+        },
+    };
+
+    html! {
+        <div class="CheckpointList">
+            <p>{"Checkpoints are named copies of a world which are kept until you delete them. \
+            Restoring a checkpoint replaces the world, but the current state is kept in the undo \
+            history."}</p>
+            <form class="create-checkpoint" onsubmit={create}>
+                <input type="text" placeholder="Checkpoint name" value={(*name).clone()}
+                    {oninput} />
+                // Buttons submit their enclosing form, so this doesn't need an onclick.
+                <Button class="green" title="Create a Checkpoint">
+                    {material_icon("add")}
+                    <span>{"Create Checkpoint"}</span>
+                </Button>
+            </form>
+            if let Some(error) = &*error {
+                <p class="checkpoint-error">
+                    {material_icon("warning")}
+                    {error}
+                </p>
+            }
+            {content}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct CheckpointDiffProps {
+    /// ID of the world the checkpoint belongs to.
+    id: WorldId,
+    /// Timestamp of the checkpoint to compare.
+    taken: f64,
+}
+
+/// Shows how the current world differs from one of its checkpoints.
+#[function_component]
+fn CheckpointDiff(&CheckpointDiffProps { id, taken }: &CheckpointDiffProps) -> Html {
+    let fetcher = use_save_file_fetcher();
+    let db = use_db();
+    // Recompare whenever the current world changes.
+    let root = use_world_root();
+    let diff = use_memo((id, taken.to_bits(), root), move |&(id, taken, _)| {
+        fetcher.diff_checkpoint(id, f64::from_bits(taken))
+    });
+    let user_settings = use_user_settings();
+    let balance_settings = &user_settings.number_display.balance;
+    let power_format = &balance_settings.power_format_settings;
+
+    let content = match &*diff {
+        Ok(Some(diff)) if diff.is_empty() => html! {
+            <p>{"No changes to item balances, power, or machine counts since this checkpoint."}</p>
+        },
+        Ok(Some(diff)) => {
+            let power = diff.power.map(|(before, after)| {
+                html! {
+                    <tr>
+                        <td class="icon">{material_icon("bolt")}</td>
+                        <td class="name">{"Power (MW)"}</td>
+                        <td class="value">{before.format(power_format).to_string()}</td>
+                        <td class="value">{after.format(power_format).to_string()}</td>
+                    </tr>
+                }
+            });
+            let machines = diff.machines.iter().map(|(&id, &(before, after))| {
+                let (name, icon): (AttrValue, Option<AttrValue>) = match db.get(id) {
+                    Some(building) => (
+                        building.name.clone().into(),
+                        Some(building.image.clone().into()),
+                    ),
+                    None => ("Unknown Building".into(), None),
+                };
+                html! {
+                    <tr key={id.to_string()}>
+                        <td class="icon"><Icon {icon} name={name.clone()} /></td>
+                        <td class="name">{name}</td>
+                        <td class="value">{before}</td>
+                        <td class="value">{after}</td>
+                    </tr>
+                }
+            });
+            let balances = diff.balances.iter().map(|(&id, &(before, after))| {
+                let (name, icon): (AttrValue, Option<AttrValue>) = match db.get(id) {
+                    Some(item) => (item.name.clone().into(), Some(item.image.clone().into())),
+                    None => ("Unknown Item".into(), None),
+                };
+                html! {
+                    <tr key={id.to_string()}>
+                        <td class="icon"><Icon {icon} name={name.clone()} /></td>
+                        <td class="name">{name}</td>
+                        <td class="value">{balance_settings.format_rate(before).to_string()}</td>
+                        <td class="value">{balance_settings.format_rate(after).to_string()}</td>
+                    </tr>
+                }
+            });
+            html! {
+                <table>
+                    <tr>
+                        <th colspan="2"></th>
+                        <th class="value">{"Checkpoint"}</th>
+                        <th class="value">{"Now"}</th>
+                    </tr>
+                    {for power}
+                    if !diff.machines.is_empty() {
+                        <tr><th colspan="4">{"Machines"}</th></tr>
+                        {for machines}
+                    }
+                    if !diff.balances.is_empty() {
+                        <tr><th colspan="4">{format!("Net Balances ({})", balance_settings.rate_unit.name().to_lowercase())}</th></tr>
+                        {for balances}
+                    }
+                </table>
+            }
+        }
+        Ok(None) => html! {
+            <p>{"This checkpoint no longer exists."}</p>
+        },
+        Err(e) => html! {
+            <p class="checkpoint-error">
+                {material_icon("warning")}
+                {format!("Unable to compare with this checkpoint: {e}")}
+            </p>
+        },
+    };
+
+    html! {
+        <div class="CheckpointDiff">
+            {content}
+        </div>
     }
 }
 
@@ -467,34 +1479,14 @@ fn use_download_callback(id: WorldId, name: AttrValue, modals: ModalDispatcher)
                         .persist();
                 }
             };
-            let blob = Blob::new_with_options(json.as_str(), Some("application/json"));
-            let url = ObjectUrl::from(blob);
-
-            // To trigger the download, we create an anchor tag that isn't attached to the document
-            // and click it.
-            let a = match gloo::utils::document().create_element("a") {
-                Ok(a) => match a.dyn_into::<HtmlAnchorElement>() {
-                    Ok(a) => a,
-                    Err(elem) => {
-                        error!("Unable to cast element {elem:?} to HtmlAnchorElement");
-                        return;
-                    }
-                },
-                Err(e) => {
-                    error!("Unable to create an 'a' element to download with: {e:?}");
-                    return;
-                }
-            };
-            a.set_href(&url);
             let filename = if name.is_empty() {
                 format!("SatisfactoryAccounting-{}.json", id.as_base64())
             } else {
                 format!("{name}-{}.json", id.as_base64())
             };
-            a.set_download(&filename);
-            a.click();
-
-            *download_url_retainer.borrow_mut() = Some(url.clone());
+            if let Some(url) = download_text(&json, "application/json", &filename) {
+                *download_url_retainer.borrow_mut() = Some(url);
+            }
         },
     )
 }
@@ -0,0 +1,247 @@
+//! Command line tool which computes balances and reports from downloaded world files, without
+//! the web app.
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::database::{Database, DatabaseVersion};
+use satisfactory_accounting::savefile::{DatabaseChoice, SaveFile};
+
+/// Build an [`Error`] from a format string.
+macro_rules! error {
+    ($($arg:tt)*) => {
+        $crate::Error(format!($($arg)*))
+    };
+}
+
+mod report;
+
+use report::{Format, Report};
+
+const USAGE: &str = "\
+Usage: satisfactory-accounting [OPTIONS] <WORLD.json>
+
+Computes balances for a world file downloaded from Satisfactory Accounting.
+
+Options:
+  --report <REPORT>      Report to print: summary, groups, buildings, top, or pivot
+                         [default: summary]
+  --item <NAME>          Item the top report ranks producers and consumers of, or power
+  --limit <N>            Producers and consumers listed by the top report [default: 10]
+  --format <FORMAT>      Output format: text, json, or csv [default: text]
+  --db-version <NAME>    Database version to use instead of the world's, e.g. v1.0-sink
+  --db <FILE.json>       Custom database file to use instead of the world's
+  --db-dir <DIR>         Directory holding binary databases which aren't built in, for
+                         the world and any groups pinned to another version
+  --check                Exit with an error if any building has a warning
+  --list-db-versions     Print the known database versions and exit
+  -h, --help             Print this help
+";
+
+/// Where to get the database from instead of the world's own choice.
+enum DatabaseOverride {
+    Version(DatabaseVersion),
+    File(PathBuf),
+}
+
+/// Options parsed from the command line.
+struct Options {
+    world: PathBuf,
+    report: Report,
+    item: Option<String>,
+    limit: usize,
+    format: Format,
+    database: Option<DatabaseOverride>,
+    db_dir: Option<PathBuf>,
+    check: bool,
+}
+
+/// Error which stops the tool.
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run() -> Result<ExitCode, Error> {
+    let Some(options) = parse_args(std::env::args().skip(1))? else {
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let text = fs::read_to_string(&options.world)
+        .map_err(|e| error!("Unable to read {}: {e}", options.world.display()))?;
+    let mut save = SaveFile::from_json(&text)
+        .map_err(|e| error!("Unable to read {}: {e}", options.world.display()))?;
+    let db = load_database(&save.database, &options)?;
+    load_pinned_databases(&save.root, &options)?;
+    save.rebuild(&db);
+    let root = save.root;
+
+    let table = options
+        .report
+        .build(&root, &db, options.item.as_deref(), options.limit)?;
+    print!("{}", table.render(options.format));
+
+    if options.check {
+        let warnings: Vec<_> = root
+            .iter()
+            .filter_map(|node| node.warning().map(|warning| (node.id(), warning)))
+            .collect();
+        for (id, warning) in &warnings {
+            eprintln!("warning: node {id}: {warning}");
+        }
+        if !warnings.is_empty() {
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parse the command line. Returns None if the tool should exit without doing anything else.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, Error> {
+    let mut world = None;
+    let mut report = Report::Summary;
+    let mut item = None;
+    let mut limit = 10;
+    let mut format = Format::Text;
+    let mut database = None;
+    let mut db_dir = None;
+    let mut check = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| error!("Missing value for {name}"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Ok(None);
+            }
+            "--list-db-versions" => {
+                for &version in DatabaseVersion::ALL {
+                    let built_in = if version.is_embedded() {
+                        " (built in)"
+                    } else {
+                        ""
+                    };
+                    println!("{}\t{version}{built_in}", version_ident(version));
+                }
+                return Ok(None);
+            }
+            "--report" => report = value("--report")?.parse()?,
+            "--item" => item = Some(value("--item")?),
+            "--limit" => {
+                let n = value("--limit")?;
+                limit = n
+                    .parse()
+                    .map_err(|_| error!("Invalid value for --limit: {n}"))?;
+            }
+            "--format" => format = value("--format")?.parse()?,
+            "--db-version" => {
+                let name = value("--db-version")?;
+                let version = DatabaseVersion::ALL
+                    .iter()
+                    .copied()
+                    .find(|&v| version_ident(v) == name || v.name() == name)
+                    .ok_or_else(|| error!("Unknown database version {name}"))?;
+                database = Some(DatabaseOverride::Version(version));
+            }
+            "--db" => database = Some(DatabaseOverride::File(value("--db")?.into())),
+            "--db-dir" => db_dir = Some(value("--db-dir")?.into()),
+            "--check" => check = true,
+            flag if flag.starts_with('-') => {
+                return Err(error!("Unknown option {flag}\n\n{USAGE}"))
+            }
+            _ if world.is_some() => return Err(error!("Only one world file can be given")),
+            _ => world = Some(arg.into()),
+        }
+    }
+
+    let world = world.ok_or_else(|| error!("No world file given\n\n{USAGE}"))?;
+    Ok(Some(Options {
+        world,
+        report,
+        item,
+        limit,
+        format,
+        database,
+        db_dir,
+        check,
+    }))
+}
+
+/// Get the short name of a database version used on the command line, which is the name of its
+/// file without the `db-` prefix or extension, e.g. `v1.0-sink`.
+fn version_ident(version: DatabaseVersion) -> &'static str {
+    let file = version.file_name();
+    let file = file.strip_prefix("db-").unwrap_or(file);
+    file.strip_suffix(".bin").unwrap_or(file)
+}
+
+/// Load the database to compute balances with, from the command line if one was chosen there,
+/// otherwise the one chosen by the world.
+fn load_database(choice: &DatabaseChoice, options: &Options) -> Result<Database, Error> {
+    let version = match (&options.database, choice) {
+        (Some(DatabaseOverride::File(path)), _) => {
+            let text = fs::read_to_string(path)
+                .map_err(|e| error!("Unable to read {}: {e}", path.display()))?;
+            return serde_json::from_str(&text)
+                .map_err(|e| error!("Unable to parse database {}: {e}", path.display()));
+        }
+        (Some(DatabaseOverride::Version(version)), _) => *version,
+        (None, DatabaseChoice::Custom(db)) => return Ok(db.clone()),
+        (None, &DatabaseChoice::Standard(version)) => version,
+        (None, DatabaseChoice::Latest) => DatabaseVersion::LATEST,
+    };
+    make_available(version, options)?;
+    version
+        .try_load_database()
+        .ok_or_else(|| error!("Database {version} could not be loaded"))
+}
+
+/// Load the database versions pinned by groups in the world, so that none of them are missing
+/// when the balances are computed.
+fn load_pinned_databases(root: &Node, options: &Options) -> Result<(), Error> {
+    let pinned: BTreeSet<DatabaseVersion> = root
+        .iter()
+        .filter_map(|node| node.group()?.database)
+        .collect();
+    for version in pinned {
+        make_available(version, options)?;
+    }
+    Ok(())
+}
+
+/// Read the given database version from `--db-dir` if it isn't built in.
+fn make_available(version: DatabaseVersion, options: &Options) -> Result<(), Error> {
+    if version.is_available() {
+        return Ok(());
+    }
+    let dir = options.db_dir.as_ref().ok_or_else(|| {
+        error!(
+            "Database {version} isn't built in. Pass --db-dir with a directory containing {}",
+            version.file_name()
+        )
+    })?;
+    let path = dir.join(version.file_name());
+    let bytes = fs::read(&path).map_err(|e| error!("Unable to read {}: {e}", path.display()))?;
+    version
+        .store_fetched(&bytes)
+        .map_err(|e| error!("Unable to parse database {}: {e}", path.display()))
+}
@@ -0,0 +1,212 @@
+//! Reports which can be printed, and the formats they can be printed in.
+use std::str::FromStr;
+
+use satisfactory_accounting::accounting::Node;
+use satisfactory_accounting::csv::{
+    balances_table, buildings_table, contributors_table, pivot_rows, table_csv, BALANCES_HEADER,
+    BUILDINGS_HEADER, CONTRIBUTORS_HEADER,
+};
+use satisfactory_accounting::database::{Database, ItemIdOrPower};
+use serde_json::{Map, Number, Value};
+
+use crate::Error;
+
+/// Which report to print.
+#[derive(Debug, Copy, Clone)]
+pub enum Report {
+    /// Net balance of the whole world.
+    Summary,
+    /// Net balance of every group.
+    Groups,
+    /// Every building with its settings.
+    Buildings,
+    /// Largest producers and consumers of one item or power.
+    Top,
+    /// Net balance of each top-level group, with one row per item.
+    Pivot,
+}
+
+impl FromStr for Report {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "summary" => Ok(Self::Summary),
+            "groups" => Ok(Self::Groups),
+            "buildings" => Ok(Self::Buildings),
+            "top" => Ok(Self::Top),
+            "pivot" => Ok(Self::Pivot),
+            _ => Err(error!("Unknown report {s}")),
+        }
+    }
+}
+
+impl Report {
+    /// Build this report for the given tree. `item` is the name of the item the top report is
+    /// for, or `power`, and `limit` is how many producers and consumers it lists.
+    pub fn build(
+        self,
+        root: &Node,
+        db: &Database,
+        item: Option<&str>,
+        limit: usize,
+    ) -> Result<Table, Error> {
+        Ok(match self {
+            Self::Summary => summary_table(root, db),
+            Self::Groups => Table::new(&BALANCES_HEADER, balances_table(root, db)),
+            Self::Buildings => Table::new(&BUILDINGS_HEADER, buildings_table(root, db)),
+            Self::Top => {
+                let item = item.ok_or_else(|| error!("The top report needs an --item"))?;
+                let target = find_target(db, item)?;
+                Table::new(
+                    &CONTRIBUTORS_HEADER,
+                    contributors_table(root, db, target, limit),
+                )
+            }
+            Self::Pivot => {
+                let (header, rows) = pivot_rows(root, db);
+                Table { header, rows }
+            }
+        })
+    }
+}
+
+/// Format to print a report in.
+#[derive(Debug, Copy, Clone)]
+pub enum Format {
+    /// Aligned columns for reading in a terminal.
+    Text,
+    /// An array with one object per row.
+    Json,
+    /// Comma separated values with a header row.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(error!("Unknown format {s}")),
+        }
+    }
+}
+
+/// A report, as rows of cells under a header.
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new<const N: usize>(header: &[&str; N], rows: Vec<[String; N]>) -> Self {
+        Self {
+            header: header.iter().map(|&h| h.to_owned()).collect(),
+            rows: rows.into_iter().map(Vec::from).collect(),
+        }
+    }
+
+    /// Render the table in the given format.
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.text(),
+            Format::Json => self.json(),
+            Format::Csv => {
+                let header: Vec<&str> = self.header.iter().map(String::as_str).collect();
+                table_csv(&header, &self.rows)
+            }
+        }
+    }
+
+    /// Render the table with each column padded to the same width. Columns of numbers are
+    /// aligned right.
+    fn text(&self) -> String {
+        let mut widths: Vec<usize> = self.header.iter().map(|h| h.chars().count()).collect();
+        let mut numeric = vec![!self.rows.is_empty(); self.header.len()];
+        for row in &self.rows {
+            for ((width, numeric), cell) in widths.iter_mut().zip(&mut numeric).zip(row) {
+                *width = (*width).max(cell.chars().count());
+                *numeric &= cell.parse::<f64>().is_ok();
+            }
+        }
+        let mut text = String::new();
+        for row in std::iter::once(&self.header).chain(&self.rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths.iter().zip(&numeric))
+                .map(|(cell, (&width, &numeric))| {
+                    if numeric {
+                        format!("{cell:>width$}")
+                    } else {
+                        format!("{cell:<width$}")
+                    }
+                })
+                .collect();
+            text.push_str(cells.join("  ").trim_end());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Render the table as a JSON array of objects keyed by the header. Numbers are written as
+    /// JSON numbers.
+    fn json(&self) -> String {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = self
+                    .header
+                    .iter()
+                    .zip(row)
+                    .map(|(key, cell)| {
+                        let value = match cell.parse::<f64>().ok().and_then(Number::from_f64) {
+                            Some(number) => Value::Number(number),
+                            None => Value::String(cell.clone()),
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        let mut json = serde_json::to_string_pretty(&rows).expect("JSON values always serialize");
+        json.push('\n');
+        json
+    }
+}
+
+/// Find the item with the given name, ignoring case, or power.
+fn find_target(db: &Database, name: &str) -> Result<ItemIdOrPower, Error> {
+    if name.eq_ignore_ascii_case("power") {
+        return Ok(ItemIdOrPower::Power);
+    }
+    db.items()
+        .find(|item| item.name.eq_ignore_ascii_case(name))
+        .map(|item| ItemIdOrPower::Item(item.id))
+        .ok_or_else(|| error!("Unknown item {name}"))
+}
+
+/// Build a table of the net balance of the whole world, with power first and items by name.
+fn summary_table(root: &Node, db: &Database) -> Table {
+    let balance = root.balance();
+    let mut items: Vec<[String; 2]> = balance
+        .balances
+        .iter()
+        .map(|(&id, rate)| {
+            let name = match db.get(id) {
+                Some(item) => item.name.to_string(),
+                None => id.to_string(),
+            };
+            [name, rate.to_string()]
+        })
+        .collect();
+    items.sort();
+    let rows = std::iter::once(["Power (MW)".to_owned(), balance.power.to_string()])
+        .chain(items)
+        .collect();
+    Table::new(&["Item", "Rate"], rows)
+}
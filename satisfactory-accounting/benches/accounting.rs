@@ -0,0 +1,104 @@
+//! Benchmarks for computing balances of large worlds.
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use satisfactory_accounting::accounting::{BuildNode, Building, Group, ManufacturerSettings, Node};
+use satisfactory_accounting::database::{BuildingKind, Database};
+
+/// Every manufacturer in the database paired with each recipe it can make.
+fn manufacturers(db: &Database) -> Vec<Building> {
+    db.buildings()
+        .filter_map(|building| match &building.kind {
+            BuildingKind::Manufacturer(m) => Some((building.id, m)),
+            _ => None,
+        })
+        .flat_map(|(id, m)| {
+            m.available_recipes.iter().map(move |&recipe| Building {
+                building: Some(id),
+                settings: ManufacturerSettings {
+                    recipe: Some(recipe),
+                    clock_speed: 1.0,
+                }
+                .into(),
+                ..Building::empty()
+            })
+        })
+        .collect()
+}
+
+/// Build a tree `depth` groups deep where every group has `width` subgroups, and the innermost
+/// groups each hold `width` buildings, cycling through `buildings`.
+fn world(db: &Database, buildings: &[Building], width: usize, depth: usize) -> Node {
+    fn build(
+        db: &Database,
+        buildings: &mut impl Iterator<Item = Building>,
+        width: usize,
+        depth: usize,
+    ) -> Node {
+        let children = if depth == 0 {
+            buildings
+                .take(width)
+                .map(|building| building.create_copy().build_node(db).unwrap())
+                .collect()
+        } else {
+            (0..width)
+                .map(|_| build(db, buildings, width, depth - 1))
+                .collect()
+        };
+        Group {
+            children,
+            ..Group::empty()
+        }
+        .into()
+    }
+    build(db, &mut buildings.iter().cloned().cycle(), width, depth)
+}
+
+fn balance_aggregation(c: &mut Criterion) {
+    let db = Database::load_latest();
+    let buildings = manufacturers(&db);
+    let leaves: Vec<Node> = buildings
+        .iter()
+        .cycle()
+        .take(1000)
+        .map(|building| building.create_copy().build_node(&db).unwrap())
+        .collect();
+    c.bench_function("balance aggregation, 1000 buildings", |b| {
+        b.iter_batched(
+            || Group {
+                children: leaves.clone(),
+                ..Group::empty()
+            },
+            |group| Node::from(black_box(group)),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn rebuild(c: &mut Criterion) {
+    let db = Database::load_latest();
+    let buildings = manufacturers(&db);
+    // 8^3 groups of 8 buildings each.
+    let world = world(&db, &buildings, 8, 3);
+    c.bench_function("rebuild, 4096 buildings", |b| {
+        b.iter(|| black_box(&world).rebuild(&db))
+    });
+}
+
+fn backdrive(c: &mut Criterion) {
+    let db = Database::load_latest();
+    let buildings = manufacturers(&db);
+    // Backdriving settles on a number of copies and a clock speed, then rebuilds the building
+    // with them. This covers the part done by the accounting crate.
+    c.bench_function("backdrive rebuild, every recipe", |b| {
+        b.iter(|| {
+            for building in &buildings {
+                let mut building = building.clone();
+                building.copies = black_box(3.7);
+                building.settings.set_clock_speed(black_box(1.5));
+                black_box(building.build_node(&db).unwrap());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, balance_aggregation, rebuild, backdrive);
+criterion_main!(benches);
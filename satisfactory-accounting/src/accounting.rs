@@ -5,6 +5,12 @@
 //   You may obtain a copy of the License at
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
+//! The tree of groups and buildings which makes up a world, and the balances computed for it.
+//!
+//! Nodes are immutable and cheap to clone. Changing a node means building a new one, which
+//! recomputes its balance from its settings and the database, or from the balances of its
+//! children for groups.
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::iter::FusedIterator;
 use std::rc::Rc;
@@ -15,10 +21,11 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 use uuid::Uuid;
 
-pub use self::balance::Balance;
+pub use self::balance::{AccumulationEstimate, Balance};
 use crate::database::{
-    BuildingId, BuildingKind, BuildingKindId, Database, Generator, Geothermal, ItemId,
-    Manufacturer, Miner, Pump, RecipeId, Station,
+    BuildingId, BuildingKind, BuildingKindId, BuildingType, Database, DatabaseVersion, Fuel,
+    Generator, Geothermal, ItemId, Manufacturer, Miner, Pump, PurityCounts, RecipeId, Station,
+    TransportKind, Vehicle,
 };
 
 mod balance;
@@ -56,16 +63,18 @@ impl SplitCopies {
     }
 }
 
-/// Trait for types which can visit groups when creating copies.
-pub trait GroupCopyVisitor {
-    fn visit(&self, original: &Group, copy: &mut Group);
+/// Trait for types which can visit groups and buildings when creating copies. The visitor is
+/// given the id of the original node and the id assigned to its copy.
+pub trait NodeCopyVisitor {
+    /// Called for each group and building which is copied.
+    fn visit(&self, original: Uuid, copy: Uuid);
 }
 
-impl<F> GroupCopyVisitor for F
+impl<F> NodeCopyVisitor for F
 where
-    F: Fn(&Group, &mut Group),
+    F: Fn(Uuid, Uuid),
 {
-    fn visit(&self, original: &Group, copy: &mut Group) {
+    fn visit(&self, original: Uuid, copy: Uuid) {
         self(original, copy)
     }
 }
@@ -80,21 +89,52 @@ pub trait BuildNode: private::Sealed {
 /// Error found when building a [`Node`].
 #[derive(Error, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BuildError {
+    /// The building is not in the database.
     #[error("Building ID {0} is not in the database.")]
     UnknownBuilding(BuildingId),
+    /// The recipe is not in the database.
     #[error("Recipe ID {0} is not in the database.")]
     UnknownRecipe(RecipeId),
+    /// The item is not in the database.
     #[error("Item ID {0} is not in the database.")]
     UnknownItem(ItemId),
+    /// The item chosen as fuel for a generator can't be burned.
     #[error("Item ID {0} is not a fuel.")]
     NotFuel(ItemId),
     #[error("Recipe {recipe} is not compatible with building {building}.")]
+    /// The recipe can't be made in the building.
     IncompatibleRecipe {
+        /// The recipe which was chosen.
         recipe: RecipeId,
+        /// The building the recipe was chosen for.
         building: BuildingId,
     },
     #[error("Item {item} is not compatible with building {building}.")]
-    IncompatibleItem { item: ItemId, building: BuildingId },
+    /// The item can't be used by the building, such as a resource a miner can't extract.
+    IncompatibleItem {
+        /// The item which was chosen.
+        item: ItemId,
+        /// The building the item was chosen for.
+        building: BuildingId,
+    },
+    #[error("Building {building} is not served by the configured vehicle.")]
+    /// The vehicle chosen for a station is not in the database.
+    UnknownVehicle {
+        /// The station the vehicle was chosen for.
+        building: BuildingId,
+    },
+    /// The clock speed is higher than the building supports.
+    #[error("Building {building} can only be clocked up to {max_clock}.")]
+    ClockTooHigh {
+        /// The building which was overclocked.
+        building: BuildingId,
+        /// Highest clock speed the building supports.
+        max_clock: f32,
+    },
+    /// The database version pinned by a group isn't built in and hasn't been fetched.
+    #[error("Database {0} has not been loaded.")]
+    DatabaseUnavailable(DatabaseVersion),
+    /// The settings are for a different kind of building than the building chosen.
     #[error("Mismatched BuildingKind between Building ({settings_kind:?}) and BuildingType ({type_kind:?}).")]
     MismatchedKind {
         /// BuildingKindId of the settings for the [`Building`].
@@ -128,6 +168,9 @@ impl<'de> Deserialize<'de> for Node {
     {
         // Recompute children_had_warnings on deserialization.
         let mut node_inner = NodeInner::deserialize(deserializer)?;
+        if let NodeKind::Group(group) = &mut node_inner.kind {
+            group.assign_legacy_building_ids();
+        }
         node_inner.children_had_warnings = check_for_child_warnings(&node_inner.kind);
         Ok(Node(Rc::new(node_inner)))
     }
@@ -196,32 +239,59 @@ impl Node {
         self.kind().group()
     }
 
+    /// Whether this node and the other are the same shared node, rather than just equal. Nodes
+    /// which are unchanged by an edit stay shared, so this is a cheap way to detect changes.
+    pub fn ptr_eq(&self, other: &Node) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
     /// Get the Building if this is a Building, otherwise None.
     pub fn building(&self) -> Option<&Building> {
         self.kind().building()
     }
 
-    /// Create a copy of this node. This is a true copy, with Uuids of Groups changed to
-    /// represent newly created, but identical groups.
+    /// Get the id of the group or building in this node.
+    pub fn id(&self) -> Uuid {
+        match self.kind() {
+            NodeKind::Group(group) => group.id,
+            NodeKind::Building(building) => building.id,
+        }
+    }
+
+    /// Create a copy of this node. This is a true copy, with Uuids of Groups and Buildings
+    /// changed to represent newly created, but identical nodes.
     pub fn create_copy(&self) -> Self {
         match self.kind() {
             NodeKind::Group(group) => group.create_copy().into(),
-            // Buidings have no identity and can be copied verbatim.
-            NodeKind::Building(_) => self.clone(),
+            NodeKind::Building(building) => self.with_building(building.create_copy()),
         }
     }
 
-    /// Create a copy of this node. This is a true copy, with Uuids of Groups changed to
-    /// represent newly created, but identical groups. A visitor can be provided to view
-    /// the newly created groups, e.g. to copy non-tree data such as metadata.
-    pub fn create_copy_with_visitor(&self, visitor: &impl GroupCopyVisitor) -> Self {
+    /// Create a copy of this node. This is a true copy, with Uuids of Groups and Buildings
+    /// changed to represent newly created, but identical nodes. A visitor can be provided to
+    /// view the ids of the newly created nodes, e.g. to copy non-tree data such as metadata.
+    pub fn create_copy_with_visitor(&self, visitor: &impl NodeCopyVisitor) -> Self {
         match self.kind() {
             NodeKind::Group(group) => group.create_copy_with_visitor(visitor).into(),
-            // Buidings have no identity and can be copied verbatim.
-            NodeKind::Building(_) => self.clone(),
+            NodeKind::Building(building) => {
+                let copy = building.create_copy();
+                visitor.visit(building.id, copy.id);
+                self.with_building(copy)
+            }
         }
     }
 
+    /// Build a node for a copy of the building in this node, keeping this node's balance and
+    /// warning since the building's settings are unchanged.
+    fn with_building(&self, building: Building) -> Self {
+        Self(Rc::new(NodeInner {
+            kind: building.into(),
+            balance: self.balance().clone(),
+            warning: self.warning(),
+            children_had_warnings: false,
+        }))
+    }
+
     /// Rebuild this node with a new database.
     pub fn rebuild(&self, new_db: &Database) -> Self {
         match self.kind() {
@@ -230,6 +300,39 @@ impl Node {
         }
     }
 
+    /// Rebuild this node with `update` applied to every building in it whose type is in the
+    /// database. Subgroups use their pinned databases. Buildings for which `update` returns false
+    /// are kept as they were.
+    pub fn update_buildings(
+        &self,
+        database: &Database,
+        update: &impl Fn(&mut Building, &BuildingType) -> bool,
+    ) -> Self {
+        match self.kind() {
+            NodeKind::Group(group) => {
+                let Ok(database) = group.resolve_database(database) else {
+                    return self.clone();
+                };
+                let mut copy = group.clone();
+                for child in &mut copy.children {
+                    *child = child.update_buildings(&database, update);
+                }
+                copy.into()
+            }
+            NodeKind::Building(building) => {
+                let Some(building_type) = building.building.and_then(|id| database.get(id)) else {
+                    return self.clone();
+                };
+                let mut copy = building.clone();
+                if update(&mut copy, building_type) {
+                    copy.rebuild(database)
+                } else {
+                    self.clone()
+                }
+            }
+        }
+    }
+
     /// Get the children of this node, if any.
     pub fn children(
         &self,
@@ -241,14 +344,332 @@ impl Node {
         }
     }
 
+    /// Get the total items needed to construct this node, including all copies. Fractional
+    /// building copies are rounded up, since a partially used building must still be built in full.
+    pub fn build_cost(&self, database: &Database) -> BTreeMap<ItemId, f32> {
+        let mut cost = BTreeMap::new();
+        self.add_build_cost(database, 1.0, &mut cost);
+        cost
+    }
+
+    /// Accumulate the build cost of this node multiplied by `multiplier` into `cost`.
+    fn add_build_cost(
+        &self,
+        database: &Database,
+        multiplier: f32,
+        cost: &mut BTreeMap<ItemId, f32>,
+    ) {
+        match self.kind() {
+            NodeKind::Group(group) => {
+                let multiplier = multiplier * group.copies as f32;
+                let Ok(database) = group.resolve_database(database) else {
+                    return;
+                };
+                for child in &group.children {
+                    child.add_build_cost(&database, multiplier, cost);
+                }
+            }
+            NodeKind::Building(building) => {
+                let Some(building_type) = building.building.and_then(|id| database.get(id)) else {
+                    return;
+                };
+                let multiplier = multiplier
+                    * building.copies.abs().ceil()
+                    * building.settings.machines_per_copy() as f32;
+                for ingredient in &building_type.build_cost {
+                    *cost.entry(ingredient.item).or_default() += ingredient.amount * multiplier;
+                }
+            }
+        }
+    }
+
+    /// Get the net power of each type of building in this node, including all copies. Negative
+    /// values are consumption and positive values are production. Buildings with no type chosen
+    /// are skipped.
+    pub fn power_by_building(&self) -> BTreeMap<BuildingId, f32> {
+        let mut power = BTreeMap::new();
+        self.add_power_by_building(1.0, &mut power);
+        power
+    }
+
+    /// Accumulate the power of each building type in this node multiplied by `multiplier` into
+    /// `power`.
+    fn add_power_by_building(&self, multiplier: f32, power: &mut BTreeMap<BuildingId, f32>) {
+        match self.kind() {
+            NodeKind::Group(group) => {
+                let multiplier = multiplier * group.copies as f32;
+                for child in &group.children {
+                    child.add_power_by_building(multiplier, power);
+                }
+            }
+            NodeKind::Building(building) => {
+                if let Some(id) = building.building {
+                    *power.entry(id).or_default() += self.balance().power * multiplier;
+                }
+            }
+        }
+    }
+
+    /// Count how many machines in this node have been built in game, including all copies.
+    /// Like for build costs, fractional building copies are rounded up.
+    pub fn build_progress(&self) -> BuildProgress {
+        let mut progress = BuildProgress::default();
+        self.add_build_progress(1, &mut progress);
+        progress
+    }
+
+    /// Accumulate the build progress of this node multiplied by `multiplier` into `progress`.
+    fn add_build_progress(&self, multiplier: u32, progress: &mut BuildProgress) {
+        match self.kind() {
+            NodeKind::Group(group) => {
+                let multiplier = multiplier * group.copies;
+                for child in &group.children {
+                    child.add_build_progress(multiplier, progress);
+                }
+            }
+            NodeKind::Building(building) => {
+                if building.building.is_none() {
+                    return;
+                }
+                let machines = multiplier
+                    * building.copies.abs().ceil() as u32
+                    * building.settings.machines_per_copy();
+                progress.planned += machines;
+                if building.built {
+                    progress.built += machines;
+                }
+            }
+        }
+    }
+
+    /// Measure how much of the capacity of the machines in this node is used, including all
+    /// copies. Like for build costs, fractional building copies are rounded up to whole machines.
+    pub fn efficiency(&self) -> Efficiency {
+        let mut totals = EfficiencyTotals::default();
+        self.add_efficiency(1, &mut totals);
+        let input_produced: f32 = totals
+            .consumed
+            .keys()
+            .filter_map(|item| totals.produced.get(item))
+            .sum();
+        let input_consumed: f32 = totals
+            .consumed
+            .iter()
+            .filter_map(|(item, &consumed)| Some(consumed.min(*totals.produced.get(item)?)))
+            .sum();
+        Efficiency {
+            throughput: (totals.capacity > 0.0).then(|| totals.running / totals.capacity),
+            input_utilization: (input_produced > 0.0).then(|| input_consumed / input_produced),
+        }
+    }
+
+    /// Accumulate the machine capacity and item flows of this node multiplied by `multiplier` into
+    /// `totals`.
+    fn add_efficiency(&self, multiplier: u32, totals: &mut EfficiencyTotals) {
+        match self.kind() {
+            NodeKind::Group(group) => {
+                let multiplier = multiplier * group.copies;
+                for child in &group.children {
+                    child.add_efficiency(multiplier, totals);
+                }
+            }
+            NodeKind::Building(building) => {
+                if building.building.is_none() {
+                    return;
+                }
+                let multiplier = multiplier as f32;
+                // Only buildings with a clock speed have a throughput which can be compared to
+                // their capacity.
+                if matches!(
+                    building.settings,
+                    BuildingSettings::Manufacturer(_)
+                        | BuildingSettings::Miner(_)
+                        | BuildingSettings::Generator(_)
+                        | BuildingSettings::Pump(_)
+                ) {
+                    let copies = building.copies.abs();
+                    totals.running += multiplier * copies * building.settings.clock_speed();
+                    totals.capacity += multiplier * copies.ceil();
+                }
+                for (&item, &rate) in &self.balance().balances {
+                    if rate > 0.0 {
+                        *totals.produced.entry(item).or_default() += rate * multiplier;
+                    } else if rate < 0.0 {
+                        *totals.consumed.entry(item).or_default() -= rate * multiplier;
+                    }
+                }
+            }
+        }
+    }
+
     /// Pre-order traversal iterator of this node.
     pub fn iter(&self) -> NodeIter {
         NodeIter {
             to_visit: vec![self.clone()],
         }
     }
+
+    /// Get a version of this node which reuses the nodes of `prior` wherever they are equal, so
+    /// that keeping both trees, such as in undo history, only stores the parts which differ once.
+    /// Children of groups are matched with the prior children by group id, and buildings by
+    /// position.
+    pub fn share_unchanged(&self, prior: &Node) -> Node {
+        if self.ptr_eq(prior) {
+            return prior.clone();
+        }
+        let (Some(group), Some(prior_group)) = (self.group(), prior.group()) else {
+            return if self.building().is_some() && self == prior {
+                prior.clone()
+            } else {
+                self.clone()
+            };
+        };
+        if group.id != prior_group.id {
+            return self.clone();
+        }
+        let prior_groups: HashMap<Uuid, &Node> = prior_group
+            .children
+            .iter()
+            .filter_map(|child| child.group().map(|group| (group.id, child)))
+            .collect();
+        let children: Vec<Node> = group
+            .children
+            .iter()
+            .enumerate()
+            .map(|(idx, child)| {
+                let candidate = match child.group() {
+                    Some(child_group) => prior_groups.get(&child_group.id).copied(),
+                    None => prior_group
+                        .children
+                        .get(idx)
+                        .filter(|candidate| candidate.building().is_some()),
+                };
+                match candidate {
+                    Some(candidate) => child.share_unchanged(candidate),
+                    None => child.clone(),
+                }
+            })
+            .collect();
+        let all_shared = children.len() == prior_group.children.len()
+            && children
+                .iter()
+                .zip(&prior_group.children)
+                .all(|(child, prior)| child.ptr_eq(prior));
+        let same_settings = group.name == prior_group.name
+            && group.copies == prior_group.copies
+            && group.database == prior_group.database
+            && group.recirculated == prior_group.recirculated;
+        if all_shared && same_settings {
+            return prior.clone();
+        }
+        if children
+            .iter()
+            .zip(&group.children)
+            .all(|(shared, child)| shared.ptr_eq(child))
+        {
+            return self.clone();
+        }
+        // Shared children are equal to the ones they replace, so the balance is unchanged.
+        Self(Rc::new(NodeInner {
+            kind: Group {
+                children,
+                ..group.clone()
+            }
+            .into(),
+            balance: self.balance().clone(),
+            warning: self.warning(),
+            children_had_warnings: self.children_had_warnings(),
+        }))
+    }
+}
+
+/// Approximate memory used by a set of trees, counting nodes shared between them only once.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct TreeMemory {
+    /// Number of distinct nodes.
+    pub nodes: usize,
+    /// Estimated number of bytes used by those nodes.
+    pub bytes: usize,
+}
+
+impl TreeMemory {
+    /// Measure the nodes of `roots` which aren't also part of any tree in `exclude`.
+    pub fn measure<'a>(
+        roots: impl IntoIterator<Item = &'a Node>,
+        exclude: impl IntoIterator<Item = &'a Node>,
+    ) -> Self {
+        let mut seen = HashSet::new();
+        let mut memory = Self::default();
+        for root in exclude {
+            Self::visit(root, &mut seen, None);
+        }
+        for root in roots {
+            Self::visit(root, &mut seen, Some(&mut memory));
+        }
+        memory
+    }
+
+    /// Visit every node under `node` which hasn't been seen yet, adding them to `memory` if
+    /// given. Nodes which have been seen are skipped along with everything inside them, since
+    /// their children have been seen too.
+    fn visit(node: &Node, seen: &mut HashSet<*const NodeInner>, mut memory: Option<&mut Self>) {
+        if !seen.insert(Rc::as_ptr(&node.0)) {
+            return;
+        }
+        if let Some(memory) = memory.as_deref_mut() {
+            memory.nodes += 1;
+            // The node itself plus the reference counts, and each balance entry.
+            memory.bytes += std::mem::size_of::<NodeInner>()
+                + 2 * std::mem::size_of::<usize>()
+                + node.balance().balances.len() * std::mem::size_of::<(ItemId, f32)>();
+            if let Some(group) = node.group() {
+                memory.bytes +=
+                    group.children.capacity() * std::mem::size_of::<Node>() + group.name.len();
+            }
+        }
+        for child in node.group().into_iter().flat_map(|group| &group.children) {
+            Self::visit(child, seen, memory.as_deref_mut());
+        }
+    }
+}
+
+/// Number of machines which have been built in game out of the number planned.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct BuildProgress {
+    /// Number of machines which have been built.
+    pub built: u32,
+    /// Total number of machines, whether built or not.
+    pub planned: u32,
 }
 
+/// How much of the capacity of the machines in a node is used, from [`Node::efficiency`].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Efficiency {
+    /// Throughput of the machines as a fraction of what the same number of whole machines would
+    /// make at 100% clock speed. Underclocked machines and fractional copies lower it and
+    /// overclocking raises it above 1. None if there are no machines with a clock speed.
+    pub throughput: Option<f32>,
+    /// Fraction of the production of items which are also consumed in the node that is actually
+    /// consumed there, counting each item's consumption only up to its production. Items which
+    /// are only produced, like the final outputs, are left out. None if nothing produced in the
+    /// node is consumed there.
+    pub input_utilization: Option<f32>,
+}
+
+/// Running totals for [`Node::efficiency`].
+#[derive(Default)]
+struct EfficiencyTotals {
+    /// Sum of copies times clock speed of machines.
+    running: f32,
+    /// Number of whole machines.
+    capacity: f32,
+    /// Amount of each item produced by buildings.
+    produced: BTreeMap<ItemId, f32>,
+    /// Amount of each item consumed by buildings, as positive numbers.
+    consumed: BTreeMap<ItemId, f32>,
+}
+
+/// Iterator over a node and all of its descendants, created by [`Node::iter`].
 pub struct NodeIter {
     // Node stack.
     to_visit: Vec<Node>,
@@ -287,7 +708,9 @@ struct NodeInner {
 /// Kind of node.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum NodeKind {
+    /// A group of other nodes.
     Group(Group),
+    /// A single building, with any number of copies.
     Building(Building),
 }
 
@@ -365,6 +788,15 @@ pub struct Group {
     /// Number of virtual copies of this group. This acts as a multiplier on the balance.
     #[serde(default = "default_group_copies")]
     pub copies: u32,
+    /// Database version pinned for this group and its children, overriding the database of
+    /// the world or any ancestor group. If None, the inherited database is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<DatabaseVersion>,
+    /// Container items, like empty canisters, which circulate in a closed loop within this group.
+    /// They are left out of the group's balance, so parents don't see a phantom surplus or
+    /// deficit of containers.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub recirculated: BTreeSet<ItemId>,
 
     /// Uniquely identifies a group, even when the node is shared between trees (e.g. when
     /// saving nodes for undo/redo purposes).
@@ -378,6 +810,8 @@ impl Group {
             name: Default::default(),
             children: Default::default(),
             copies: 1,
+            database: None,
+            recirculated: BTreeSet::new(),
             id: Uuid::new_v4(),
         }
     }
@@ -390,8 +824,11 @@ impl Group {
     /// Compute the net balance for this group, using the *cached* values of child nodes.
     /// Caller is responsible for recaching child balances first if necessary.
     fn compute_balance(&self) -> Balance {
-        let mut balance = self.children.iter().map(|node| node.balance()).sum();
+        let mut balance: Balance = self.children.iter().map(|node| node.balance()).sum();
         balance *= self.copies as f32;
+        for item in &self.recirculated {
+            balance.balances.remove(item);
+        }
         balance
     }
 
@@ -412,17 +849,19 @@ impl Group {
                 .map(|child| child.create_copy())
                 .collect(),
             copies: self.copies,
+            database: self.database,
+            recirculated: self.recirculated.clone(),
             id: Uuid::new_v4(),
         }
     }
 
     /// Create a true copy of this group, with a newly assigned Uuid. Unlike the result of
     /// `Clone`, the new value doesn't represent the same group, so can be used in the
-    /// same tree as the original. A visitor can be used to view the original group and
-    /// copy simultaneously. This can be used e.g. to copy out-of-tree related data such
+    /// same tree as the original. A visitor is given the ids of each original group or
+    /// building and its copy. This can be used e.g. to copy out-of-tree related data such
     /// as metadata.
-    pub fn create_copy_with_visitor(&self, visitor: &impl GroupCopyVisitor) -> Self {
-        let mut copy = Group {
+    pub fn create_copy_with_visitor(&self, visitor: &impl NodeCopyVisitor) -> Self {
+        let copy = Group {
             name: self.name.clone(),
             children: self
                 .children
@@ -430,17 +869,56 @@ impl Group {
                 .map(|child| child.create_copy_with_visitor(visitor))
                 .collect(),
             copies: self.copies,
+            database: self.database,
+            recirculated: self.recirculated.clone(),
             id: Uuid::new_v4(),
         };
-        visitor.visit(self, &mut copy);
+        visitor.visit(self.id, copy.id);
         copy
     }
 
-    /// Rebuild this node with a new database.
+    /// Give child buildings which were saved before buildings had ids an id derived from this
+    /// group's id and their position in it. Deriving the id rather than making a new one means the
+    /// building gets the same id every time it is loaded, so data kept outside the tree, such as
+    /// notes, stays attached to it even if the group isn't saved again.
+    fn assign_legacy_building_ids(&mut self) {
+        /// Odd multiplier which spreads the child index over all of the bits of the id.
+        const SPREAD: u128 = 0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835;
+        let group_id = self.id.as_u128();
+        for (index, child) in self.children.iter_mut().enumerate() {
+            if let Some(building) = child.building().filter(|building| building.id.is_nil()) {
+                let id = Uuid::from_u128(group_id ^ (index as u128 + 1).wrapping_mul(SPREAD));
+                *child = child.with_building(Building {
+                    id,
+                    ..building.clone()
+                });
+            }
+        }
+    }
+
+    /// Get the database used for children of this group, given the database inherited from
+    /// its parent. Fails if the group pins a database version which isn't built in and hasn't been
+    /// fetched.
+    pub fn resolve_database(&self, inherited: &Database) -> Result<Database, BuildError> {
+        match self.database {
+            Some(version) => version
+                .try_load_database()
+                .ok_or(BuildError::DatabaseUnavailable(version)),
+            None => Ok(inherited.clone()),
+        }
+    }
+
+    /// Rebuild this node with a new database. Children are rebuilt with this group's pinned
+    /// database if it has one. If the pinned database isn't available, the group is left with a
+    /// warning and no balance.
     fn rebuild(&self, new_db: &Database) -> Node {
+        let database = match self.resolve_database(new_db) {
+            Ok(database) => database,
+            Err(err) => return err.into_warning_node(self.clone()),
+        };
         let mut copy = self.clone();
         for child in &mut copy.children {
-            *child = child.rebuild(new_db);
+            *child = child.rebuild(&database);
         }
         copy.into()
     }
@@ -472,6 +950,19 @@ pub struct Building {
         serialize_with = "serialize_building_copies"
     )]
     pub copies: f32,
+    /// Whether this building has actually been constructed in game, rather than just planned.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub built: bool,
+    /// Belts or pipes feeding each input and carrying each output of all copies of this building,
+    /// for items where they have been chosen.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub logistics: BTreeMap<ItemId, Logistics>,
+
+    /// Identifies a building for data kept outside the tree, such as notes. Buildings saved
+    /// before they had ids load with the nil id, and the group containing them then derives a
+    /// stable id for them.
+    #[serde(default)]
+    pub id: Uuid,
 }
 
 impl Building {
@@ -485,6 +976,31 @@ impl Building {
         Node::new(Self::empty(), Balance::empty())
     }
 
+    /// Create a true copy of this building, with a newly assigned Uuid.
+    pub fn create_copy(&self) -> Self {
+        Building {
+            id: Uuid::new_v4(),
+            ..self.clone()
+        }
+    }
+
+    /// Find the inputs and outputs of this building which move more items than the chosen belts or
+    /// pipes can carry, given the building's balance.
+    pub fn logistics_overflows(&self, balance: &Balance, database: &Database) -> Vec<Overflow> {
+        self.logistics
+            .iter()
+            .filter_map(|(&item, logistics)| {
+                let rate = balance.balances.get(&item).copied().unwrap_or(0.0).abs();
+                let capacity = logistics.capacity(database.transport_kind_for(item), database)?;
+                (rate > capacity + 1e-3).then_some(Overflow {
+                    item,
+                    rate,
+                    capacity,
+                })
+            })
+            .collect()
+    }
+
     /// Rebuild this node with a new database, converting errors to warnings.
     fn rebuild(&self, new_db: &Database) -> Node {
         match self.clone().build_node(new_db) {
@@ -506,6 +1022,15 @@ impl BuildNode for Building {
                 // integer.
                 self.copies = self.copies.round();
             }
+            // Allow a small tolerance so clock speeds that were rounded when entered aren't
+            // rejected.
+            let max_clock = building.max_clock_speed();
+            if building.overclockable() && self.settings.clock_speed() > max_clock + 1e-4 {
+                return Err(BuildError::ClockTooHigh {
+                    building: building_id,
+                    max_clock,
+                });
+            }
             match (&self.settings, &building.kind) {
                 (BuildingSettings::Manufacturer(ms), BuildingKind::Manufacturer(m)) => {
                     balance = ms.get_balance(building_id, m, self.copies, database)?;
@@ -546,19 +1071,67 @@ impl Default for Building {
             building: None,
             settings: BuildingSettings::PowerConsumer,
             copies: 1.0,
+            built: false,
+            logistics: BTreeMap::new(),
+            id: Uuid::new_v4(),
         }
     }
 }
 
+/// Belts or pipes carrying one of a building's inputs or outputs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Logistics {
+    /// Tier of the belts or pipes. Whether they are belts or pipes depends on the item.
+    pub tier: u8,
+    /// Number of parallel belts or pipes.
+    pub count: u32,
+}
+
+impl Logistics {
+    /// Get the most items per minute these belts or pipes can carry, or None if the database
+    /// doesn't have a transport of this tier.
+    pub fn capacity(&self, kind: TransportKind, database: &Database) -> Option<f32> {
+        let transport = database.transport(kind, self.tier)?;
+        Some(transport.throughput * self.count as f32)
+    }
+}
+
+/// An input or output of a building which moves more than its belts or pipes can carry.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Overflow {
+    /// Item being moved.
+    pub item: ItemId,
+    /// Items per minute the building moves, as a positive number.
+    pub rate: f32,
+    /// Items per minute the chosen belts or pipes can carry.
+    pub capacity: f32,
+}
+
+/// The recipe, fuel, or resource a building is set to use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Selection {
+    /// Recipe of a manufacturer.
+    Recipe(RecipeId),
+    /// Fuel of a generator or station, or resource of a miner or pump.
+    Item(ItemId),
+}
+
 /// Settings for a building of a particular kind.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BuildingSettings {
+    /// Settings for a building which makes items with a recipe.
     Manufacturer(ManufacturerSettings),
+    /// Settings for a building which extracts a resource from a node.
     Miner(MinerSettings),
+    /// Settings for a building which burns fuel to make power.
     Generator(GeneratorSettings),
+    /// Settings for a building which extracts a resource from a well.
     Pump(PumpSettings),
+    /// Settings for a geothermal generator.
     Geothermal(GeothermalSettings),
+    /// A building which only uses power and has no settings.
     PowerConsumer,
+    /// Settings for a train or truck station.
     Station(StationSettings),
 }
 
@@ -589,6 +1162,87 @@ impl BuildingSettings {
         }
     }
 
+    /// Get the number of machines each copy of the building stands for. Only geothermal clusters
+    /// stand for more than one.
+    pub fn machines_per_copy(&self) -> u32 {
+        match self {
+            Self::Geothermal(g) => g.generators(),
+            _ => 1,
+        }
+    }
+
+    /// Get the recipe, resource, or fuel this building is set to use, if it has one.
+    pub fn selection(&self) -> Option<Selection> {
+        match self {
+            Self::Manufacturer(m) => m.recipe.map(Selection::Recipe),
+            Self::Miner(m) => m.resource.map(Selection::Item),
+            Self::Generator(g) => g.fuel.map(Selection::Item),
+            Self::Pump(p) => p.resource.map(Selection::Item),
+            Self::Station(s) => s.fuel.map(Selection::Item),
+            Self::Geothermal(_) | Self::PowerConsumer => None,
+        }
+    }
+
+    /// Get these settings changed to use a different recipe, resource, or fuel, keeping
+    /// everything else. Returns None if a building of `kind` can't use it.
+    pub fn with_selection(&self, kind: &BuildingKind, selection: Selection) -> Option<Self> {
+        if !kind.allowed_selections().contains(&selection) {
+            return None;
+        }
+        let mut settings = self.clone();
+        match (&mut settings, selection) {
+            (Self::Manufacturer(m), Selection::Recipe(id)) => m.recipe = Some(id),
+            (Self::Miner(m), Selection::Item(id)) => m.resource = Some(id),
+            (Self::Generator(g), Selection::Item(id)) => g.fuel = Some(id),
+            (Self::Pump(p), Selection::Item(id)) => p.resource = Some(id),
+            // Traffic-derived consumption is recomputed for the new fuel.
+            (Self::Station(s), Selection::Item(id)) => s.fuel = Some(id),
+            _ => return None,
+        }
+        Some(settings)
+    }
+
+    /// Get the name of the recipe, resource, or fuel this building is set to use, if it has one.
+    pub fn selection_name(&self, database: &Database) -> Option<Rc<str>> {
+        match self {
+            Self::Manufacturer(m) => Some(database.get(m.recipe?)?.name.clone()),
+            Self::Miner(m) => Some(database.get(m.resource?)?.name.clone()),
+            Self::Generator(g) => Some(database.get(g.fuel?)?.name.clone()),
+            Self::Pump(p) => Some(database.get(p.resource?)?.name.clone()),
+            Self::Station(s) => Some(database.get(s.fuel?)?.name.clone()),
+            Self::Geothermal(_) | Self::PowerConsumer => None,
+        }
+    }
+
+    /// Get the resource an extraction building produces and the rate of its busiest output, in
+    /// items per minute. Each copy of a miner and each pad of a pump has its own output. None for
+    /// other buildings or if no resource is chosen.
+    pub fn extraction_output(&self, kind: &BuildingKind) -> Option<(ItemId, f32)> {
+        match (self, kind) {
+            (Self::Miner(ms), BuildingKind::Miner(m)) => Some((ms.resource?, ms.output_rate(m))),
+            (Self::Pump(ps), BuildingKind::Pump(p)) => Some((ps.resource?, ps.peak_pad_rate(p))),
+            _ => None,
+        }
+    }
+
+    /// Get the tier of belt or pipe chosen for the outputs of an extraction building.
+    pub fn output_tier(&self) -> Option<u8> {
+        match self {
+            Self::Miner(m) => m.output_tier,
+            Self::Pump(p) => p.output_tier,
+            _ => None,
+        }
+    }
+
+    /// Set the tier of belt or pipe carrying the outputs of an extraction building if possible.
+    pub fn set_output_tier(&mut self, tier: Option<u8>) {
+        match self {
+            Self::Miner(m) => m.output_tier = tier,
+            Self::Pump(p) => p.output_tier = tier,
+            _ => {}
+        }
+    }
+
     /// Set the clock speed of the building if possible.
     pub fn set_clock_speed(&mut self, clock_speed: f32) {
         match self {
@@ -602,6 +1256,19 @@ impl BuildingSettings {
         }
     }
 
+    /// Get settings for switching this building to another type of the same kind, such as a
+    /// higher mark of miner, keeping its recipe, resource, fuel, purity, and clock speed. Returns
+    /// None if the new type can't keep all of them.
+    pub fn upgrade_to(&self, new_type: &BuildingType) -> Option<Self> {
+        if self.kind_id() != new_type.kind.kind_id()
+            || (new_type.overclockable() && self.clock_speed() > new_type.max_clock_speed() + 1e-4)
+        {
+            return None;
+        }
+        let new_settings = self.build_new_settings(&new_type.kind);
+        (new_settings == *self).then_some(new_settings)
+    }
+
     /// Get replacment settings for changing a building, by copying the settings a much as
     /// possible.
     pub fn build_new_settings(&self, new_kind: &BuildingKind) -> Self {
@@ -741,8 +1408,11 @@ impl ManufacturerSettings {
 /// Purity of a source resource.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResourcePurity {
+    /// Half the normal output.
     Impure,
+    /// The base output.
     Normal,
+    /// Twice the normal output.
     Pure,
 }
 
@@ -829,6 +1499,9 @@ pub struct MinerSettings {
     pub clock_speed: f32,
     /// Purity of the node this miner is built on.
     pub purity: ResourcePurity,
+    /// Tier of belt or pipe carrying the output of each copy of this miner, if chosen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tier: Option<u8>,
 }
 
 impl Default for MinerSettings {
@@ -837,6 +1510,7 @@ impl Default for MinerSettings {
             resource: None,
             clock_speed: 1.0,
             purity: Default::default(),
+            output_tier: None,
         }
     }
 }
@@ -868,16 +1542,18 @@ impl MinerSettings {
                 .power_consumption
                 .get_consumption_rate(clock_split.last_clock);
             balance.power = base_power * clock_split.whole_copies + last_power;
-            let cycles_per_minute =
-                60.0 / m.cycle_time * self.clock_speed * self.purity.speed_multiplier() * copies;
-
             balance
                 .balances
-                .insert(resource_id, m.items_per_cycle * cycles_per_minute);
+                .insert(resource_id, self.output_rate(m) * copies);
         }
         Ok(balance)
     }
 
+    /// Get the items per minute produced by one copy of this miner.
+    pub fn output_rate(&self, m: &Miner) -> f32 {
+        60.0 / m.cycle_time * self.clock_speed * self.purity.speed_multiplier() * m.items_per_cycle
+    }
+
     /// Create a copy of these settings for a different miner.
     fn copy_settings(&self, m: &Miner) -> Self {
         let mut ms = self.clone();
@@ -1004,6 +1680,9 @@ pub struct PumpSettings {
     /// Number of normal resource pads. If no pads are set, will still consume power but
     /// will not produce any resources.
     pub impure_pads: u32,
+    /// Tier of pipe carrying the output of each pad, if chosen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tier: Option<u8>,
 }
 
 impl Default for PumpSettings {
@@ -1014,6 +1693,7 @@ impl Default for PumpSettings {
             pure_pads: 0,
             normal_pads: 0,
             impure_pads: 0,
+            output_tier: None,
         }
     }
 }
@@ -1056,6 +1736,21 @@ impl PumpSettings {
         Ok(balance)
     }
 
+    /// Get the items per minute produced by the busiest pad of one copy of this pump, or 0 if
+    /// it has no pads.
+    pub fn peak_pad_rate(&self, p: &Pump) -> f32 {
+        let purity = if self.pure_pads > 0 {
+            ResourcePurity::Pure
+        } else if self.normal_pads > 0 {
+            ResourcePurity::Normal
+        } else if self.impure_pads > 0 {
+            ResourcePurity::Impure
+        } else {
+            return 0.0;
+        };
+        60.0 / p.cycle_time * self.clock_speed * p.items_per_cycle * purity.speed_multiplier()
+    }
+
     /// Create a copy of these settings for a different pump.
     fn copy_settings(&self, p: &Pump) -> Self {
         let mut ps = self.clone();
@@ -1081,19 +1776,46 @@ impl PumpSettings {
 pub struct GeothermalSettings {
     /// Purity of the pad, affects generated power.
     pub purity: ResourcePurity,
+    /// Number of geysers of each purity, when the building stands for a cluster of geysers with a
+    /// generator on each. If not set, each copy is a single generator on a geyser of `purity`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geysers: Option<PurityCounts>,
 }
 
 impl Default for GeothermalSettings {
     fn default() -> Self {
         Self {
             purity: Default::default(),
+            geysers: None,
         }
     }
 }
 
 impl GeothermalSettings {
     fn get_balance(&self, g: &Geothermal, copies: f32) -> Balance {
-        Balance::power_only(self.purity.speed_multiplier() * g.power * copies.round())
+        Balance::power_only(self.power_multiplier() * g.power * copies.round())
+    }
+
+    /// Get the number of generators in one copy of this building.
+    pub fn generators(&self) -> u32 {
+        self.geysers.map_or(1, |geysers| geysers.total())
+    }
+
+    /// Get the sum of the purity multipliers of every geyser in one copy of this building.
+    pub fn power_multiplier(&self) -> f32 {
+        match self.geysers {
+            Some(geysers) => ResourcePurity::values()
+                .map(|purity| geysers.get(purity) as f32 * purity.speed_multiplier())
+                .sum(),
+            None => self.purity.speed_multiplier(),
+        }
+    }
+
+    /// Get the average purity multiplier of the geysers in one copy of this building, or None if
+    /// it has no geysers.
+    pub fn average_multiplier(&self) -> Option<f32> {
+        let generators = self.generators();
+        (generators > 0).then(|| self.power_multiplier() / generators as f32)
     }
 }
 
@@ -1104,6 +1826,13 @@ pub struct StationSettings {
     pub fuel: Option<ItemId>,
     /// Configured fuel consumption rate.
     pub consumption: f32,
+    /// Vehicle traffic to derive the fuel consumption from. When set, this replaces the
+    /// configured consumption rate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traffic: Option<VehicleTraffic>,
+    /// Freight loaded or unloaded at the station, in addition to its fuel.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub manifest: Vec<ManifestEntry>,
 }
 
 impl StationSettings {
@@ -1116,7 +1845,7 @@ impl StationSettings {
     ) -> Result<Balance, BuildError> {
         let mut balance = Balance::empty();
         if let Some(fuel_id) = self.fuel {
-            database
+            let fuel = database
                 .get(fuel_id)
                 .ok_or(BuildError::UnknownItem(fuel_id))?;
 
@@ -1127,14 +1856,45 @@ impl StationSettings {
                 });
             }
 
+            let consumption = match &self.traffic {
+                Some(traffic) => {
+                    let vehicle =
+                        s.vehicle(&traffic.vehicle)
+                            .ok_or(BuildError::UnknownVehicle {
+                                building: building_id,
+                            })?;
+                    let energy = fuel.fuel.as_ref().ok_or(BuildError::NotFuel(fuel_id))?;
+                    traffic.consumption(vehicle, energy)
+                }
+                None => self.consumption,
+            };
+
             balance.power = -s.power * copies.round();
             balance
                 .balances
-                .insert(fuel_id, -self.consumption * copies.round());
+                .insert(fuel_id, -consumption * copies.round());
+        }
+        for entry in &self.manifest {
+            let Some(item) = entry.item else {
+                continue;
+            };
+            if database.get(item).is_none() {
+                return Err(BuildError::UnknownItem(item));
+            }
+            *balance.balances.entry(item).or_default() += entry.balance() * copies.round();
         }
         Ok(balance)
     }
 
+    /// Get the fuel consumption derived from the vehicle traffic, or None if no traffic is set
+    /// or it doesn't match the station and fuel.
+    pub fn traffic_consumption(&self, s: &Station, database: &Database) -> Option<f32> {
+        let traffic = self.traffic.as_ref()?;
+        let vehicle = s.vehicle(&traffic.vehicle)?;
+        let fuel = database.get(self.fuel?)?.fuel.as_ref()?;
+        Some(traffic.consumption(vehicle, fuel))
+    }
+
     /// Create a copy of these settings for a different pump.
     fn copy_settings(&self, s: &Station) -> Self {
         let mut ss = self.clone();
@@ -1151,10 +1911,99 @@ impl StationSettings {
         } else if s.allowed_fuel.len() == 1 {
             ss.fuel = s.allowed_fuel.first().copied();
         }
+        // Drop the vehicle traffic if the new station doesn't serve that vehicle.
+        if let Some(traffic) = &ss.traffic {
+            if s.vehicle(&traffic.vehicle).is_none() {
+                ss.traffic = None;
+            }
+        }
         ss
     }
 }
 
+/// Item moved through a station, with the direction it travels and how fast.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Item being moved, if one has been chosen.
+    pub item: Option<ItemId>,
+    /// Whether the item is unloaded or loaded at this station.
+    #[serde(default)]
+    pub direction: FreightDirection,
+    /// Items per minute moved through the station.
+    pub rate: f32,
+}
+
+impl ManifestEntry {
+    /// Get the rate this entry adds to the station's balance: positive for items unloaded here,
+    /// negative for items loaded here.
+    pub fn balance(&self) -> f32 {
+        match self.direction {
+            FreightDirection::Incoming => self.rate,
+            FreightDirection::Outgoing => -self.rate,
+        }
+    }
+}
+
+/// Direction freight travels through a station.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FreightDirection {
+    /// Unloaded at this station, supplying the factory around it.
+    #[default]
+    Incoming,
+    /// Loaded at this station, sending items away from the factory around it.
+    Outgoing,
+}
+
+impl FreightDirection {
+    /// Get the opposite direction.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Incoming => Self::Outgoing,
+            Self::Outgoing => Self::Incoming,
+        }
+    }
+}
+
+/// Fleet of vehicles refueling at a station, used to derive the station's fuel consumption.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VehicleTraffic {
+    /// Name of the vehicle type, matching a [`Vehicle`] served by the station.
+    pub vehicle: IString,
+    /// Number of vehicles running the route.
+    pub count: u32,
+    /// Time for one vehicle to complete its round trip, in seconds.
+    pub round_trip: f32,
+    /// Time each vehicle spends docked at stations during its round trip, in seconds.
+    #[serde(default)]
+    pub docked: f32,
+}
+
+impl VehicleTraffic {
+    /// Create traffic for a single vehicle of the given type.
+    pub fn new(vehicle: IString) -> Self {
+        Self {
+            vehicle,
+            count: 1,
+            round_trip: 60.0,
+            docked: 0.0,
+        }
+    }
+
+    /// Fraction of each round trip which the vehicles spend moving and burning fuel.
+    pub fn moving_fraction(&self) -> f32 {
+        if self.round_trip > 0.0 {
+            ((self.round_trip - self.docked) / self.round_trip).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Get the fuel burned per minute by all of the vehicles together.
+    pub fn consumption(&self, vehicle: &Vehicle, fuel: &Fuel) -> f32 {
+        self.count as f32 * vehicle.fuel_per_minute(fuel) * self.moving_fraction()
+    }
+}
+
 mod private {
     use super::*;
 
@@ -11,7 +11,7 @@ use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssi
 
 use serde::{Deserialize, Serialize};
 
-use crate::database::ItemId;
+use crate::database::{Database, ItemId};
 
 /// The balance of a node, including items produced or consumed and power used.
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
@@ -43,6 +43,58 @@ impl Balance {
             balances: balances.into_iter().collect(),
         }
     }
+
+    /// Whether no item or power in this balance has a deficit larger than the given tolerance.
+    pub fn is_self_sufficient(&self, tolerance: f32) -> bool {
+        self.power >= -tolerance && self.balances.values().all(|&rate| rate >= -tolerance)
+    }
+
+    /// Get the AWESOME Sink points per minute that would be earned by sinking all of the surplus
+    /// items in this balance. Items with a deficit or which are missing from the database
+    /// contribute nothing.
+    pub fn sink_points_per_minute(&self, db: &Database) -> f32 {
+        self.balances
+            .iter()
+            .filter(|(_, &rate)| rate > 0.0)
+            .filter_map(|(&item, &rate)| Some(rate * db.get(item)?.sink_points as f32))
+            .sum()
+    }
+
+    /// Estimate how long it will take to accumulate the given amounts of items at the rates in
+    /// this balance. All items are assumed to be accumulated in parallel.
+    pub fn time_to_accumulate(
+        &self,
+        amounts: impl IntoIterator<Item = (ItemId, f32)>,
+    ) -> AccumulationEstimate {
+        let per_item: BTreeMap<_, _> = amounts
+            .into_iter()
+            .map(|(item, amount)| {
+                let rate = self.balances.get(&item).copied().unwrap_or(0.0);
+                let minutes = if amount <= 0.0 {
+                    Some(0.0)
+                } else if rate > 0.0 {
+                    Some(amount / rate)
+                } else {
+                    None
+                };
+                (item, minutes)
+            })
+            .collect();
+        let minutes = per_item
+            .values()
+            .try_fold(0.0f32, |total, &minutes| Some(total.max(minutes?)));
+        AccumulationEstimate { minutes, per_item }
+    }
+}
+
+/// Estimate of the time needed to accumulate a set of items.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AccumulationEstimate {
+    /// Minutes until every item has been accumulated, or None if some item is not being produced.
+    pub minutes: Option<f32>,
+    /// Minutes until each individual item has been accumulated, or None if that item is not being
+    /// produced.
+    pub per_item: BTreeMap<ItemId, Option<f32>>,
 }
 
 impl Add for Balance {
@@ -0,0 +1,1350 @@
+//! Analyses which look across a whole node tree to find potential problems.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use implicit_clone::unsync::IString;
+use uuid::Uuid;
+
+use crate::accounting::{
+    BuildError, Building, BuildingSettings, Node, NodeKind, Overflow, ResourcePurity, Selection,
+};
+use crate::database::{
+    BuildingId, BuildingKind, Database, ItemId, ItemIdOrPower, PurityCounts, ResourceNodes,
+};
+
+/// A group whose outputs aren't used anywhere else in the tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnusedGroup {
+    /// Path of child indexes from the root to the group.
+    pub path: Vec<usize>,
+    /// Id of the group.
+    pub id: Uuid,
+    /// Names of the group and each of its ancestors, starting from the root.
+    pub names: Vec<IString>,
+    /// Items which the group produces but nothing else consumes.
+    pub unconsumed: Vec<ItemId>,
+}
+
+/// Find groups whose net outputs aren't consumed by any building outside of the group.
+///
+/// Items for which `is_exported` returns true are treated as consumed, e.g. because they are
+/// intentionally sunk or shipped elsewhere. Groups which don't produce anything are never reported,
+/// and descendants of a reported group are not reported separately.
+pub fn find_unused_groups(root: &Node, is_exported: impl Fn(ItemId) -> bool) -> Vec<UnusedGroup> {
+    // Paths of every building which consumes each item.
+    let mut consumers: HashMap<ItemId, Vec<Vec<usize>>> = HashMap::new();
+    visit_with_path(root, &mut Vec::new(), &mut |node, path| {
+        if let NodeKind::Building(_) = node.kind() {
+            for (&item, &rate) in &node.balance().balances {
+                if rate < 0.0 {
+                    consumers.entry(item).or_default().push(path.to_vec());
+                }
+            }
+        }
+    });
+
+    let mut unused = Vec::new();
+    let mut names = Vec::new();
+    find_unused_in(
+        root,
+        &mut Vec::new(),
+        &mut names,
+        &consumers,
+        &is_exported,
+        &mut unused,
+    );
+    unused
+}
+
+/// Recursive helper for [`find_unused_groups`].
+fn find_unused_in(
+    node: &Node,
+    path: &mut Vec<usize>,
+    names: &mut Vec<IString>,
+    consumers: &HashMap<ItemId, Vec<Vec<usize>>>,
+    is_exported: &impl Fn(ItemId) -> bool,
+    unused: &mut Vec<UnusedGroup>,
+) {
+    let Some(group) = node.group() else {
+        return;
+    };
+    names.push(group.name.clone());
+    // The root group can't be unused, since there's nothing outside of it.
+    if !path.is_empty() {
+        let outputs: BTreeSet<ItemId> = node
+            .balance()
+            .balances
+            .iter()
+            .filter(|(_, &rate)| rate > 0.0)
+            .map(|(&item, _)| item)
+            .collect();
+        let unconsumed: Vec<ItemId> = outputs
+            .iter()
+            .copied()
+            .filter(|&item| {
+                !is_exported(item)
+                    && !consumers
+                        .get(&item)
+                        .into_iter()
+                        .flatten()
+                        .any(|consumer| !consumer.starts_with(path))
+            })
+            .collect();
+        if !outputs.is_empty() && unconsumed.len() == outputs.len() {
+            unused.push(UnusedGroup {
+                path: path.clone(),
+                id: group.id,
+                names: names.clone(),
+                unconsumed,
+            });
+            names.pop();
+            return;
+        }
+    }
+    for (i, child) in group.children.iter().enumerate() {
+        path.push(i);
+        find_unused_in(child, path, names, consumers, is_exported, unused);
+        path.pop();
+    }
+    names.pop();
+}
+
+/// A problem found in the nuclear fuel chain of a subtree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NuclearWarning {
+    /// Nuclear waste is left over with nothing in the subtree consuming it.
+    UnconsumedWaste {
+        /// The waste item.
+        item: ItemId,
+        /// Surplus rate in items per minute.
+        rate: f32,
+    },
+    /// Fuel rods are left over and nothing in the subtree burns them.
+    RodsWithoutGenerators {
+        /// The fuel rod item.
+        item: ItemId,
+        /// Surplus rate in items per minute.
+        rate: f32,
+    },
+    /// Some fuel rods are burned while the rest are left over to be sunk or stored.
+    RodsPartlyBurned {
+        /// The fuel rod item.
+        item: ItemId,
+        /// Rate at which generators in the subtree burn the rods, in items per minute.
+        burned: f32,
+        /// Surplus rate in items per minute.
+        surplus: f32,
+    },
+}
+
+impl NuclearWarning {
+    /// The item which this warning is about.
+    pub fn item(&self) -> ItemId {
+        match *self {
+            Self::UnconsumedWaste { item, .. } => item,
+            Self::RodsWithoutGenerators { item, .. } => item,
+            Self::RodsPartlyBurned { item, .. } => item,
+        }
+    }
+}
+
+/// Check the fuel rod to waste chain of the given subtree for imbalances.
+///
+/// Fuel rods are any generator fuels which leave a byproduct, and waste is those byproducts, so
+/// this covers uranium, plutonium, and ficsonium alike. Items for which `is_exported` returns true
+/// are treated as intentionally sunk or stored and are not reported.
+pub fn check_nuclear_chain(
+    node: &Node,
+    database: &Database,
+    is_exported: impl Fn(ItemId) -> bool,
+) -> Vec<NuclearWarning> {
+    let mut rods = BTreeSet::new();
+    let mut waste = BTreeSet::new();
+    for building in database.buildings() {
+        let BuildingKind::Generator(generator) = &building.kind else {
+            continue;
+        };
+        for &fuel_id in &generator.allowed_fuel {
+            let Some(fuel) = database.get(fuel_id).and_then(|item| item.fuel.as_ref()) else {
+                continue;
+            };
+            if !fuel.byproducts.is_empty() {
+                rods.insert(fuel_id);
+                waste.extend(fuel.byproducts.iter().map(|byproduct| byproduct.item));
+            }
+        }
+    }
+
+    let balances = &node.balance().balances;
+    let surplus = |item: &ItemId| {
+        balances
+            .get(item)
+            .copied()
+            .filter(|&rate| rate > 0.0 && !is_exported(*item))
+    };
+
+    let mut warnings = Vec::new();
+    let surplus_rods: BTreeMap<ItemId, f32> = rods
+        .iter()
+        .filter_map(|rod| surplus(rod).map(|rate| (*rod, rate)))
+        .collect();
+    if !surplus_rods.is_empty() {
+        let mut burned = BTreeMap::new();
+        sum_burned(node, 1.0, &surplus_rods, &mut burned);
+        for (&item, &rate) in &surplus_rods {
+            match burned.get(&item) {
+                Some(&burned) if burned > 0.0 => warnings.push(NuclearWarning::RodsPartlyBurned {
+                    item,
+                    burned,
+                    surplus: rate,
+                }),
+                _ => warnings.push(NuclearWarning::RodsWithoutGenerators { item, rate }),
+            }
+        }
+    }
+    warnings.extend(
+        waste
+            .iter()
+            .filter_map(|item| surplus(item).map(|rate| (*item, rate)))
+            .map(|(item, rate)| NuclearWarning::UnconsumedWaste { item, rate }),
+    );
+    warnings
+}
+
+/// Add up the rate at which generators in the subtree burn each of the given fuels, accounting for
+/// the copies of each enclosing group.
+fn sum_burned(
+    node: &Node,
+    multiplier: f32,
+    fuels: &BTreeMap<ItemId, f32>,
+    burned: &mut BTreeMap<ItemId, f32>,
+) {
+    match node.kind() {
+        NodeKind::Group(group) => {
+            let multiplier = multiplier * group.copies as f32;
+            for child in &group.children {
+                sum_burned(child, multiplier, fuels, burned);
+            }
+        }
+        NodeKind::Building(building) => {
+            if let BuildingSettings::Generator(settings) = &building.settings {
+                if let Some(fuel) = settings.fuel.filter(|fuel| fuels.contains_key(fuel)) {
+                    let rate = node.balance().balances.get(&fuel).copied().unwrap_or(0.0);
+                    *burned.entry(fuel).or_default() -= rate * multiplier;
+                }
+            }
+        }
+    }
+}
+
+/// Id of the packager, which packs fluids into containers and unpacks them again.
+const PACKAGER: &str = "Desc_Packager_C";
+
+/// Containers which a subtree both packs fluids into and unpacks fluids from, so that they could
+/// circulate in a closed loop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContainerLoop {
+    /// The container item, like empty canisters or empty fluid tanks.
+    pub container: ItemId,
+    /// Rate at which packagers in the subtree fill the container, in items per minute.
+    pub packed: f32,
+    /// Rate at which packagers in the subtree empty the container, in items per minute.
+    pub unpacked: f32,
+}
+
+impl ContainerLoop {
+    /// Net rate of containers left over by the loop. Negative if the loop needs more containers
+    /// than it returns.
+    pub fn net(&self) -> f32 {
+        self.unpacked - self.packed
+    }
+}
+
+/// Find containers which packagers in the given subtree both fill and empty, accounting for the
+/// copies of each enclosing group.
+///
+/// A container is any solid item packed alongside a fluid by a packager recipe. Containers already
+/// recirculated by a group nested within the subtree are not counted for that group's packagers,
+/// but those recirculated by the given group itself are, so the group's own loops can be shown.
+/// Recipes are looked up in the database pinned by each packager's nearest group, if any.
+pub fn find_container_loops(node: &Node, database: &Database) -> Vec<ContainerLoop> {
+    let mut flows = BTreeMap::new();
+    match node.group() {
+        Some(group) => {
+            let Ok(database) = group.resolve_database(database) else {
+                return Vec::new();
+            };
+            for child in &group.children {
+                container_flows_in(
+                    child,
+                    &database,
+                    group.copies as f32,
+                    &BTreeSet::new(),
+                    &mut flows,
+                );
+            }
+        }
+        None => container_flows_in(node, database, 1.0, &BTreeSet::new(), &mut flows),
+    }
+    flows
+        .into_iter()
+        .filter(|(_, (packed, unpacked))| *packed > 0.0 && *unpacked > 0.0)
+        .map(|(container, (packed, unpacked))| ContainerLoop {
+            container,
+            packed,
+            unpacked,
+        })
+        .collect()
+}
+
+/// Recursive helper for [`find_container_loops`]. Adds the rate at which each container is packed
+/// and unpacked by packagers in the subtree.
+fn container_flows_in(
+    node: &Node,
+    database: &Database,
+    multiplier: f32,
+    recirculated: &BTreeSet<ItemId>,
+    flows: &mut BTreeMap<ItemId, (f32, f32)>,
+) {
+    match node.kind() {
+        NodeKind::Group(group) => {
+            let Ok(database) = group.resolve_database(database) else {
+                return;
+            };
+            let multiplier = multiplier * group.copies as f32;
+            let recirculated = recirculated.union(&group.recirculated).copied().collect();
+            for child in &group.children {
+                container_flows_in(child, &database, multiplier, &recirculated, flows);
+            }
+        }
+        NodeKind::Building(building) => {
+            if building.building != Some(BuildingId::from(PACKAGER)) {
+                return;
+            }
+            let BuildingSettings::Manufacturer(settings) = &building.settings else {
+                return;
+            };
+            let Some(recipe) = settings.recipe.and_then(|recipe| database.get(recipe)) else {
+                return;
+            };
+            let is_fluid = |item: ItemId| database.get(item).is_some_and(|item| item.is_fluid);
+            let balances = &node.balance().balances;
+            for amounts in [&recipe.ingredients, &recipe.products] {
+                if !amounts.iter().any(|amount| is_fluid(amount.item)) {
+                    continue;
+                }
+                for amount in amounts {
+                    if is_fluid(amount.item) || recirculated.contains(&amount.item) {
+                        continue;
+                    }
+                    let rate = balances.get(&amount.item).copied().unwrap_or(0.0) * multiplier;
+                    let (packed, unpacked) = flows.entry(amount.item).or_default();
+                    if rate < 0.0 {
+                        *packed -= rate;
+                    } else {
+                        *unpacked += rate;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A problem found somewhere in a node tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// Id of the node with the problem.
+    pub id: Uuid,
+    /// Ids of the groups between the root and the node, outermost first, not including the root.
+    pub ancestors: Vec<Uuid>,
+    /// Names of the groups from the root down to the node, including the node if it is a group.
+    pub names: Vec<IString>,
+    /// What the problem is.
+    pub kind: DiagnosticKind,
+}
+
+/// Kinds of problems found by [`find_diagnostics`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticKind {
+    /// The building or group could not be computed, e.g. because it refers to something which
+    /// isn't in the database after switching database versions, or because a group is pinned to a
+    /// database version which isn't available.
+    BuildError(BuildError),
+    /// A manufacturer has no recipe chosen.
+    MissingRecipe,
+    /// A miner or pump has no resource chosen.
+    MissingResource,
+    /// A generator has no fuel chosen.
+    MissingFuel,
+    /// An input or output of a building moves more than its chosen belts or pipes can carry.
+    LogisticsOverflow(Overflow),
+    /// An extraction building outputs more than its chosen belt or pipe can carry.
+    ExtractionOverflow(Overflow),
+    /// The world consumes more power than it produces.
+    PowerDeficit {
+        /// Missing power in MW, as a positive number.
+        deficit: f32,
+    },
+    /// A problem with the nuclear fuel chain of the world.
+    Nuclear(NuclearWarning),
+}
+
+/// Find problems anywhere in the tree, in tree order, followed by problems with the world as a
+/// whole. Buildings are checked against the database pinned by their nearest group, if any. Items
+/// for which `is_exported` returns true are not reported as nuclear waste or leftover fuel rods.
+pub fn find_diagnostics(
+    root: &Node,
+    database: &Database,
+    is_exported: impl Fn(ItemId) -> bool,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut ancestors = Vec::new();
+    let mut names = Vec::new();
+    diagnostics_in(root, database, &mut ancestors, &mut names, &mut diagnostics);
+
+    let world = |kind| Diagnostic {
+        id: root.id(),
+        ancestors: Vec::new(),
+        names: root
+            .group()
+            .map(|group| group.name.clone())
+            .into_iter()
+            .collect(),
+        kind,
+    };
+    let power = root.balance().power;
+    if power < 0.0 {
+        diagnostics.push(world(DiagnosticKind::PowerDeficit { deficit: -power }));
+    }
+    for warning in check_nuclear_chain(root, database, is_exported) {
+        diagnostics.push(world(DiagnosticKind::Nuclear(warning)));
+    }
+    diagnostics
+}
+
+/// Recursive helper for [`find_diagnostics`].
+fn diagnostics_in(
+    node: &Node,
+    database: &Database,
+    ancestors: &mut Vec<Uuid>,
+    names: &mut Vec<IString>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    match node.kind() {
+        NodeKind::Group(group) => {
+            let is_root = names.is_empty();
+            names.push(group.name.clone());
+            // A group whose pinned database can't be loaded is reported itself, and its children
+            // aren't checked since they can't be computed.
+            let database = group.resolve_database(database);
+            if let Some(error) = node.warning().or_else(|| database.as_ref().err().copied()) {
+                diagnostics.push(Diagnostic {
+                    id: group.id,
+                    ancestors: ancestors.clone(),
+                    names: names.clone(),
+                    kind: DiagnosticKind::BuildError(error),
+                });
+            }
+            if let Ok(database) = database {
+                if !is_root {
+                    ancestors.push(group.id);
+                }
+                for child in &group.children {
+                    diagnostics_in(child, &database, ancestors, names, diagnostics);
+                }
+                if !is_root {
+                    ancestors.pop();
+                }
+            }
+            names.pop();
+        }
+        NodeKind::Building(building) => {
+            let mut report = |kind| {
+                diagnostics.push(Diagnostic {
+                    id: building.id,
+                    ancestors: ancestors.clone(),
+                    names: names.clone(),
+                    kind,
+                })
+            };
+            if let Some(error) = node.warning() {
+                report(DiagnosticKind::BuildError(error));
+                return;
+            }
+            let Some(building_type) = building.building.and_then(|id| database.get(id)) else {
+                return;
+            };
+            match &building.settings {
+                BuildingSettings::Manufacturer(ms) if ms.recipe.is_none() => {
+                    report(DiagnosticKind::MissingRecipe)
+                }
+                BuildingSettings::Miner(ms) if ms.resource.is_none() => {
+                    report(DiagnosticKind::MissingResource)
+                }
+                BuildingSettings::Pump(ps) if ps.resource.is_none() => {
+                    report(DiagnosticKind::MissingResource)
+                }
+                BuildingSettings::Generator(gs) if gs.fuel.is_none() => {
+                    report(DiagnosticKind::MissingFuel)
+                }
+                _ => {}
+            }
+            for overflow in building.logistics_overflows(node.balance(), database) {
+                report(DiagnosticKind::LogisticsOverflow(overflow));
+            }
+            let extraction = building.settings.extraction_output(&building_type.kind);
+            if let (Some((item, rate)), Some(tier)) = (extraction, building.settings.output_tier())
+            {
+                let transport = database.transport(database.transport_kind_for(item), tier);
+                if let Some(transport) = transport.filter(|t| rate > t.throughput + 1e-3) {
+                    report(DiagnosticKind::ExtractionOverflow(Overflow {
+                        item,
+                        rate,
+                        capacity: transport.throughput,
+                    }));
+                }
+            }
+        }
+    }
+}
+
+/// Totals describing everything in a node tree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorldSummary {
+    /// Total power produced by generators, in MW.
+    pub power_produced: f32,
+    /// Total power consumed, in MW, as a positive number.
+    pub power_consumed: f32,
+    /// Number of machines of each building type. Fractional building copies are rounded up, and
+    /// geothermal clusters count each of their generators.
+    pub machines: BTreeMap<BuildingId, u32>,
+    /// Rate at which miners and pumps extract each resource, in units per minute.
+    pub extracted: BTreeMap<ItemId, f32>,
+    /// Resource nodes and well pads of each purity claimed by miners and pumps. Fractional
+    /// building copies are rounded up.
+    pub claimed_nodes: BTreeMap<ItemId, ResourceNodes>,
+}
+
+/// Add up the power, machines, and resource extraction of the whole tree, accounting for the
+/// copies of each group.
+pub fn summarize_world(root: &Node) -> WorldSummary {
+    let mut summary = WorldSummary::default();
+    summarize_in(root, 1, &mut summary);
+    summary
+}
+
+/// Recursive helper for [`summarize_world`].
+fn summarize_in(node: &Node, multiplier: u32, summary: &mut WorldSummary) {
+    match node.kind() {
+        NodeKind::Group(group) => {
+            let multiplier = multiplier * group.copies;
+            for child in &group.children {
+                summarize_in(child, multiplier, summary);
+            }
+        }
+        NodeKind::Building(building) => {
+            let Some(building_id) = building.building else {
+                return;
+            };
+            let balance = node.balance();
+            let power = balance.power * multiplier as f32;
+            if power > 0.0 {
+                summary.power_produced += power;
+            } else {
+                summary.power_consumed -= power;
+            }
+            let copies = multiplier * building.copies.abs().ceil() as u32;
+            *summary.machines.entry(building_id).or_default() +=
+                copies * building.settings.machines_per_copy();
+            match &building.settings {
+                BuildingSettings::Miner(settings) => {
+                    if let Some(resource) = settings.resource {
+                        let claimed = summary.claimed_nodes.entry(resource).or_default();
+                        *claimed.nodes.get_mut(settings.purity) += copies;
+                    }
+                }
+                BuildingSettings::Pump(settings) => {
+                    if let Some(resource) = settings.resource {
+                        let claimed = summary.claimed_nodes.entry(resource).or_default();
+                        claimed.well_pads.impure += copies * settings.impure_pads;
+                        claimed.well_pads.normal += copies * settings.normal_pads;
+                        claimed.well_pads.pure += copies * settings.pure_pads;
+                    }
+                }
+                _ => {}
+            }
+            if let BuildingSettings::Miner(_) | BuildingSettings::Pump(_) = building.settings {
+                for (&item, &rate) in &balance.balances {
+                    if rate > 0.0 {
+                        *summary.extracted.entry(item).or_default() += rate * multiplier as f32;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Resource nodes of one purity which are claimed by more extractors than the map has.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeOverAllocation {
+    /// Resource the nodes provide.
+    pub resource: ItemId,
+    /// Whether the nodes are resource well pads rather than regular nodes.
+    pub well_pads: bool,
+    /// Purity of the over-allocated nodes.
+    pub purity: ResourcePurity,
+    /// Number of nodes claimed by miners and pumps.
+    pub claimed: u32,
+    /// Number of nodes on the map.
+    pub available: u32,
+}
+
+/// Find resource nodes which the world claims more of than the map in the database has. Resources
+/// whose node counts aren't known by the database are never reported.
+pub fn find_node_over_allocations(
+    summary: &WorldSummary,
+    db: &Database,
+) -> Vec<NodeOverAllocation> {
+    let mut over = Vec::new();
+    for (&resource, claimed) in &summary.claimed_nodes {
+        let Some(available) = db.resource_nodes(resource) else {
+            continue;
+        };
+        let mut check = |well_pads: bool, claimed: &PurityCounts, available: &PurityCounts| {
+            for purity in ResourcePurity::values() {
+                if claimed.get(purity) > available.get(purity) {
+                    over.push(NodeOverAllocation {
+                        resource,
+                        well_pads,
+                        purity,
+                        claimed: claimed.get(purity),
+                        available: available.get(purity),
+                    });
+                }
+            }
+        };
+        check(false, &claimed.nodes, &available.nodes);
+        check(true, &claimed.well_pads, &available.well_pads);
+    }
+    over
+}
+
+/// Energy stored by one power storage, in MWh.
+pub const POWER_STORAGE_CAPACITY: f32 = 100.0;
+
+/// Power totals for one named power circuit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerCircuit {
+    /// Name of the circuit, or None for nodes not assigned to any circuit.
+    pub name: Option<IString>,
+    /// Power produced by generators on the circuit, in MW.
+    pub produced: f32,
+    /// Average power consumed on the circuit, in MW, as a positive number.
+    pub consumed: f32,
+    /// Power consumed on the circuit if every building with variable power draw is at its peak at
+    /// once, in MW, as a positive number.
+    pub peak_consumed: f32,
+    /// Number of power storages on the circuit.
+    pub power_storages: u32,
+}
+
+impl PowerCircuit {
+    /// Whether the circuit's fuses could trip when variable power buildings reach their peak.
+    pub fn may_trip(&self) -> bool {
+        self.peak_consumed > self.produced + 1e-3
+    }
+
+    /// Energy the circuit's power storages can hold, in MWh.
+    pub fn battery_capacity(&self) -> f32 {
+        self.power_storages as f32 * POWER_STORAGE_CAPACITY
+    }
+
+    /// Minutes the circuit's power storages could cover the deficit at peak draw, or None if there
+    /// is no deficit at peak.
+    pub fn battery_minutes_at_peak(&self) -> Option<f32> {
+        let deficit = self.peak_consumed - self.produced;
+        (deficit > 1e-3).then(|| self.battery_capacity() / deficit * 60.0)
+    }
+}
+
+/// Split the power of the tree between named circuits. `assignment` gives the circuit each node
+/// was explicitly assigned to, if any, and the number of power storages kept with it. Nodes which
+/// aren't assigned belong to the circuit of their nearest assigned ancestor. Circuits are sorted by
+/// name, with unassigned nodes first. Peak draw is looked up in the database pinned by each
+/// building's nearest group, if any.
+pub fn power_circuits(
+    root: &Node,
+    database: &Database,
+    assignment: impl Fn(Uuid) -> (Option<IString>, u32),
+) -> Vec<PowerCircuit> {
+    let mut circuits = BTreeMap::new();
+    circuits_in(root, database, &assignment, None, 1, &mut circuits);
+    circuits
+        .into_iter()
+        .map(|(name, circuit)| PowerCircuit { name, ..circuit })
+        .collect()
+}
+
+/// Recursive helper for [`power_circuits`].
+fn circuits_in(
+    node: &Node,
+    database: &Database,
+    assignment: &impl Fn(Uuid) -> (Option<IString>, u32),
+    inherited: Option<IString>,
+    multiplier: u32,
+    circuits: &mut BTreeMap<Option<IString>, PowerCircuit>,
+) {
+    let (assigned, power_storages) = assignment(node.id());
+    let name = assigned.or(inherited);
+    circuits.entry(name.clone()).or_default().power_storages += power_storages * multiplier;
+    match node.kind() {
+        NodeKind::Group(group) => {
+            // Groups whose pinned database isn't available have no balance to add.
+            let Ok(database) = group.resolve_database(database) else {
+                return;
+            };
+            let multiplier = multiplier * group.copies;
+            for child in &group.children {
+                circuits_in(
+                    child,
+                    &database,
+                    assignment,
+                    name.clone(),
+                    multiplier,
+                    circuits,
+                );
+            }
+        }
+        NodeKind::Building(building) => {
+            let circuit = circuits.entry(name).or_default();
+            let power = node.balance().power * multiplier as f32;
+            if power > 0.0 {
+                circuit.produced += power;
+            } else {
+                circuit.consumed -= power;
+                circuit.peak_consumed -= power * peak_factor(building, database);
+            }
+        }
+    }
+}
+
+/// Get the ratio of peak to average power draw of a building.
+fn peak_factor(building: &Building, database: &Database) -> f32 {
+    let BuildingSettings::Manufacturer(settings) = &building.settings else {
+        return 1.0;
+    };
+    settings
+        .recipe
+        .and_then(|id| database.get(id))
+        .and_then(|recipe| recipe.variable_power)
+        .map_or(1.0, |power| power.peak_factor())
+}
+
+/// A building which contributes to the balance of an item or power.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contributor {
+    /// Id of the building.
+    pub id: Uuid,
+    /// Ids of the groups between the searched node and the building, outermost first.
+    pub ancestors: Vec<Uuid>,
+    /// Rate the building contributes, accounting for the copies of enclosing groups. Positive is
+    /// production and negative is consumption.
+    pub rate: f32,
+}
+
+/// Find the buildings in the subtree which contribute the most to the balance of the given item
+/// or power, largest first. If the subtree has a net surplus, producers are returned, otherwise
+/// consumers are. At most `limit` contributors are returned.
+pub fn top_contributors(node: &Node, target: ItemIdOrPower, limit: usize) -> Vec<Contributor> {
+    let net = rate_of(node, target);
+    let mut contributors = all_contributors(node, target);
+    contributors.retain(|contributor| {
+        if net >= 0.0 {
+            contributor.rate > 0.0
+        } else {
+            contributor.rate < 0.0
+        }
+    });
+    contributors.truncate(limit);
+    contributors
+}
+
+/// Find every building in the subtree which produces or consumes the given item or power,
+/// largest first.
+pub fn all_contributors(node: &Node, target: ItemIdOrPower) -> Vec<Contributor> {
+    let mut contributors = Vec::new();
+    find_contributors_in(node, target, 1, &mut Vec::new(), &mut contributors);
+    contributors.sort_by(|a, b| b.rate.abs().total_cmp(&a.rate.abs()));
+    contributors
+}
+
+/// The buildings which produce and consume the most of an item or power in a subtree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopContributors {
+    /// Buildings with the largest production, largest first.
+    pub producers: Vec<Contributor>,
+    /// Buildings with the largest consumption, largest first.
+    pub consumers: Vec<Contributor>,
+}
+
+/// Find the buildings in the subtree which produce and consume the most of the given item or
+/// power, largest first. At most `limit` producers and `limit` consumers are returned.
+pub fn top_producers_and_consumers(
+    node: &Node,
+    target: ItemIdOrPower,
+    limit: usize,
+) -> TopContributors {
+    let (mut producers, mut consumers): (Vec<_>, Vec<_>) = all_contributors(node, target)
+        .into_iter()
+        .partition(|contributor| contributor.rate > 0.0);
+    producers.truncate(limit);
+    consumers.truncate(limit);
+    TopContributors {
+        producers,
+        consumers,
+    }
+}
+
+/// How a group's net balance of an item or power is made up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BalanceBreakdown {
+    /// Total produced by buildings in the subtree.
+    pub produced: f32,
+    /// Total consumed by buildings in the subtree, as a positive number.
+    pub consumed: f32,
+    /// Index and net contribution of the direct children which contribute the most, largest
+    /// first. Contributions include the copies of the group.
+    pub top_children: Vec<(usize, f32)>,
+}
+
+/// Break a node's net balance of an item or power into its gross production and consumption and
+/// the direct children contributing the most to it. At most `limit` children are returned.
+pub fn balance_breakdown(node: &Node, target: ItemIdOrPower, limit: usize) -> BalanceBreakdown {
+    let mut breakdown = BalanceBreakdown::default();
+    for contributor in all_contributors(node, target) {
+        if contributor.rate > 0.0 {
+            breakdown.produced += contributor.rate;
+        } else {
+            breakdown.consumed -= contributor.rate;
+        }
+    }
+    let copies = node.group().map_or(1, |group| group.copies) as f32;
+    breakdown.top_children = node
+        .children()
+        .enumerate()
+        .map(|(idx, child)| (idx, rate_of(&child, target) * copies))
+        .filter(|&(_, rate)| rate != 0.0)
+        .collect();
+    breakdown
+        .top_children
+        .sort_by(|(_, a), (_, b)| b.abs().total_cmp(&a.abs()));
+    breakdown.top_children.truncate(limit);
+    breakdown
+}
+
+/// Get a node's balance of an item or power.
+fn rate_of(node: &Node, target: ItemIdOrPower) -> f32 {
+    let balance = node.balance();
+    match target {
+        ItemIdOrPower::Power => balance.power,
+        ItemIdOrPower::Item(item) => balance.balances.get(&item).copied().unwrap_or_default(),
+    }
+}
+
+/// Recursive helper for [`all_contributors`].
+fn find_contributors_in(
+    node: &Node,
+    target: ItemIdOrPower,
+    multiplier: u32,
+    ancestors: &mut Vec<Uuid>,
+    contributors: &mut Vec<Contributor>,
+) {
+    match node.kind() {
+        NodeKind::Group(group) => {
+            let multiplier = multiplier * group.copies;
+            for child in &group.children {
+                if child.group().is_some() {
+                    ancestors.push(child.id());
+                    find_contributors_in(child, target, multiplier, ancestors, contributors);
+                    ancestors.pop();
+                } else {
+                    find_contributors_in(child, target, multiplier, ancestors, contributors);
+                }
+            }
+        }
+        NodeKind::Building(building) => {
+            let rate = rate_of(node, target) * multiplier as f32;
+            if rate != 0.0 {
+                contributors.push(Contributor {
+                    id: building.id,
+                    ancestors: ancestors.clone(),
+                    rate,
+                });
+            }
+        }
+    }
+}
+
+/// Production and consumption of items and power across several nodes, kept separate rather than
+/// netted against each other.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GrossBalance {
+    /// Power produced, in MW.
+    pub power_produced: f32,
+    /// Power consumed, in MW, as a positive number.
+    pub power_consumed: f32,
+    /// Amount of each item produced, in units per minute.
+    pub items_produced: BTreeMap<ItemId, f32>,
+    /// Amount of each item consumed, in units per minute, as positive numbers.
+    pub items_consumed: BTreeMap<ItemId, f32>,
+}
+
+impl GrossBalance {
+    /// Sum the balances of the given nodes, keeping production and consumption separate.
+    pub fn of_nodes<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> Self {
+        let mut gross = Self::default();
+        for node in nodes {
+            let balance = node.balance();
+            if balance.power > 0.0 {
+                gross.power_produced += balance.power;
+            } else {
+                gross.power_consumed -= balance.power;
+            }
+            for (&item, &rate) in &balance.balances {
+                if rate > 0.0 {
+                    *gross.items_produced.entry(item).or_default() += rate;
+                } else if rate < 0.0 {
+                    *gross.items_consumed.entry(item).or_default() -= rate;
+                }
+            }
+        }
+        gross
+    }
+
+    /// Get the fraction of the total production of the item or power which the given rate makes
+    /// up if it is positive, or of the total consumption if it is negative. Returns None if the
+    /// rate is zero or there's no total to compare against.
+    pub fn share(&self, target: ItemIdOrPower, rate: f32) -> Option<f32> {
+        let total = match target {
+            ItemIdOrPower::Power if rate > 0.0 => self.power_produced,
+            ItemIdOrPower::Power => self.power_consumed,
+            ItemIdOrPower::Item(item) if rate > 0.0 => {
+                self.items_produced.get(&item).copied().unwrap_or_default()
+            }
+            ItemIdOrPower::Item(item) => {
+                self.items_consumed.get(&item).copied().unwrap_or_default()
+            }
+        };
+        (rate != 0.0 && total > 0.0).then(|| rate.abs() / total)
+    }
+}
+
+/// Find the share of the tree's total power consumption which each group is responsible for,
+/// accounting for the copies of the group and its ancestors. Consumption is counted per building,
+/// so generators inside a group don't offset its draw.
+pub fn power_draw_shares(root: &Node) -> HashMap<Uuid, f32> {
+    let mut draws = HashMap::new();
+    let total = power_draw_in(root, 1.0, &mut draws);
+    if total > 0.0 {
+        for draw in draws.values_mut() {
+            *draw /= total;
+        }
+    } else {
+        draws.clear();
+    }
+    draws
+}
+
+/// Recursive helper for [`power_draw_shares`]. Records the draw of each group within the node,
+/// multiplied by the copies of its ancestors, and returns the draw of the node including its own
+/// copies.
+fn power_draw_in(node: &Node, multiplier: f32, draws: &mut HashMap<Uuid, f32>) -> f32 {
+    match node.kind() {
+        NodeKind::Group(group) => {
+            let copies = group.copies as f32;
+            let draw = copies
+                * group
+                    .children
+                    .iter()
+                    .map(|child| power_draw_in(child, multiplier * copies, draws))
+                    .sum::<f32>();
+            draws.insert(group.id, draw * multiplier);
+            draw
+        }
+        NodeKind::Building(_) => (-node.balance().power).max(0.0),
+    }
+}
+
+/// Rate at which one child of a group produces or consumes an item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChildFlow {
+    /// Index of the child within the group.
+    pub child: usize,
+    /// The item produced or consumed.
+    pub item: ItemId,
+    /// Rate in units per minute, including the copies of the group, as a positive number.
+    pub rate: f32,
+}
+
+/// Movement of items between the children of a group and in or out of the group.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemFlows {
+    /// Items produced by each child.
+    pub produced: Vec<ChildFlow>,
+    /// Items consumed by each child.
+    pub consumed: Vec<ChildFlow>,
+    /// Items the group consumes more of than it produces, with the deficit as a positive number.
+    pub imports: BTreeMap<ItemId, f32>,
+    /// Items the group produces more of than it consumes, with the surplus.
+    pub exports: BTreeMap<ItemId, f32>,
+}
+
+/// Split the item balance of a group into what each of its direct children produce and consume,
+/// plus what has to come from or goes to outside the group. For every item, production plus
+/// imports equals consumption plus exports.
+pub fn item_flows(node: &Node) -> ItemFlows {
+    let copies = node.group().map_or(1, |group| group.copies) as f32;
+    let mut flows = ItemFlows::default();
+    for (child_idx, child) in node.children().enumerate() {
+        for (&item, &rate) in &child.balance().balances {
+            let rate = rate * copies;
+            let flow = ChildFlow {
+                child: child_idx,
+                item,
+                rate: rate.abs(),
+            };
+            if rate > 0.0 {
+                flows.produced.push(flow);
+            } else if rate < 0.0 {
+                flows.consumed.push(flow);
+            }
+        }
+    }
+    for (&item, &rate) in &node.balance().balances {
+        if rate > 0.0 {
+            flows.exports.insert(item, rate);
+        } else if rate < 0.0 {
+            flows.imports.insert(item, -rate);
+        }
+    }
+    flows
+}
+
+/// Changes smaller than this are ignored when comparing trees.
+const DIFF_EPSILON: f32 = 1e-3;
+
+/// Differences between two versions of a node tree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeDiff {
+    /// Net rate of each item whose balance changed, as (before, after), in units per minute.
+    pub balances: BTreeMap<ItemId, (f32, f32)>,
+    /// Net power before and after, in MW, if it changed.
+    pub power: Option<(f32, f32)>,
+    /// Number of machines of each building type whose count changed, as (before, after).
+    pub machines: BTreeMap<BuildingId, (u32, u32)>,
+}
+
+impl TreeDiff {
+    /// Whether nothing changed between the two trees.
+    pub fn is_empty(&self) -> bool {
+        self.balances.is_empty() && self.power.is_none() && self.machines.is_empty()
+    }
+}
+
+/// Compare the net balances and machine counts of two versions of a tree.
+pub fn diff_trees(before: &Node, after: &Node) -> TreeDiff {
+    let before_balance = before.balance();
+    let after_balance = after.balance();
+    let items: BTreeSet<ItemId> = before_balance
+        .balances
+        .keys()
+        .chain(after_balance.balances.keys())
+        .copied()
+        .collect();
+    let balances = items
+        .into_iter()
+        .filter_map(|item| {
+            let old = before_balance.balances.get(&item).copied().unwrap_or(0.0);
+            let new = after_balance.balances.get(&item).copied().unwrap_or(0.0);
+            ((old - new).abs() > DIFF_EPSILON).then_some((item, (old, new)))
+        })
+        .collect();
+    let power = ((before_balance.power - after_balance.power).abs() > DIFF_EPSILON)
+        .then_some((before_balance.power, after_balance.power));
+
+    let before_machines = summarize_world(before).machines;
+    let after_machines = summarize_world(after).machines;
+    let buildings: BTreeSet<BuildingId> = before_machines
+        .keys()
+        .chain(after_machines.keys())
+        .copied()
+        .collect();
+    let machines = buildings
+        .into_iter()
+        .filter_map(|building| {
+            let old = before_machines.get(&building).copied().unwrap_or(0);
+            let new = after_machines.get(&building).copied().unwrap_or(0);
+            (old != new).then_some((building, (old, new)))
+        })
+        .collect();
+
+    TreeDiff {
+        balances,
+        power,
+        machines,
+    }
+}
+
+/// A building which uses the recipe, fuel, or resource being searched for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionUser {
+    /// Id of the building.
+    pub id: Uuid,
+    /// Type of the building.
+    pub building: BuildingId,
+    /// Number of copies of the building, not including the copies of groups containing it.
+    pub copies: f32,
+    /// Names of the groups from the root down to the building.
+    pub names: Vec<IString>,
+    /// Every recipe, fuel, or resource the building could use instead.
+    pub alternatives: Vec<Selection>,
+}
+
+/// Find every building in the tree, in tree order, which is set to use the given recipe, fuel, or
+/// resource. Subgroups pinned to a different database are checked against that database.
+pub fn find_selection_users(
+    root: &Node,
+    database: &Database,
+    selection: Selection,
+) -> Vec<SelectionUser> {
+    let mut users = Vec::new();
+    selection_users_in(root, database, selection, &mut Vec::new(), &mut users);
+    users
+}
+
+/// Recursive helper for [`find_selection_users`].
+fn selection_users_in(
+    node: &Node,
+    database: &Database,
+    selection: Selection,
+    names: &mut Vec<IString>,
+    users: &mut Vec<SelectionUser>,
+) {
+    match node.kind() {
+        NodeKind::Group(group) => {
+            let Ok(database) = group.resolve_database(database) else {
+                return;
+            };
+            names.push(group.name.clone());
+            for child in &group.children {
+                selection_users_in(child, &database, selection, names, users);
+            }
+            names.pop();
+        }
+        NodeKind::Building(building) => {
+            if building.settings.selection() != Some(selection) {
+                return;
+            }
+            let Some(building_type) = building.building.and_then(|id| database.get(id)) else {
+                return;
+            };
+            let mut alternatives = building_type.kind.allowed_selections();
+            alternatives.retain(|&alternative| alternative != selection);
+            users.push(SelectionUser {
+                id: building.id,
+                building: building_type.id,
+                copies: building.copies,
+                names: names.clone(),
+                alternatives,
+            });
+        }
+    }
+}
+
+/// Rebuild the tree with the buildings with the given ids switched from one recipe, fuel, or
+/// resource to another. Buildings which can't use the replacement are left as they were.
+pub fn replace_selection(
+    root: &Node,
+    database: &Database,
+    find: Selection,
+    replace: Selection,
+    ids: &BTreeSet<Uuid>,
+) -> Node {
+    root.update_buildings(database, &|building, building_type| {
+        if !ids.contains(&building.id) || building.settings.selection() != Some(find) {
+            return false;
+        }
+        match building
+            .settings
+            .with_selection(&building_type.kind, replace)
+        {
+            Some(settings) => {
+                building.settings = settings;
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// A column of a [`PivotTable`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotColumn {
+    /// Id of the top-level group, or None for the column combining buildings placed directly in
+    /// the root.
+    pub id: Option<Uuid>,
+    /// Name to show for the column.
+    pub name: String,
+}
+
+/// A row of a [`PivotTable`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotRow {
+    /// Item or power the row is for.
+    pub target: ItemIdOrPower,
+    /// Net rate in each column, in the same order as [`PivotTable::columns`].
+    pub rates: Vec<f32>,
+    /// Net rate of the whole tree, which is the sum of `rates`.
+    pub total: f32,
+}
+
+/// Net rates of every item and power broken down by the top-level groups of a tree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PivotTable {
+    /// One column per top-level group, in order, followed by a column for buildings placed
+    /// directly in the root if there are any.
+    pub columns: Vec<PivotColumn>,
+    /// One row for power if any is produced or consumed, followed by one row per item.
+    pub rows: Vec<PivotRow>,
+}
+
+/// Break the balance of the root down by its top-level groups. Rates include the copies of the
+/// root, so the row totals match the root's balance.
+pub fn pivot_table(root: &Node) -> PivotTable {
+    let Some(group) = root.group() else {
+        return PivotTable::default();
+    };
+    let copies = group.copies as f32;
+    let mut columns = Vec::new();
+    let mut balances = Vec::new();
+    let mut loose = Vec::new();
+    for child in &group.children {
+        match child.group() {
+            Some(child_group) => {
+                let name = if child_group.name.is_empty() {
+                    "Unnamed Group".to_owned()
+                } else {
+                    child_group.name.to_string()
+                };
+                columns.push(PivotColumn {
+                    id: Some(child_group.id),
+                    name,
+                });
+                balances.push(net_balance([child]));
+            }
+            None => loose.push(child),
+        }
+    }
+    if !loose.is_empty() {
+        columns.push(PivotColumn {
+            id: None,
+            name: "Other Buildings".to_owned(),
+        });
+        balances.push(net_balance(loose));
+    }
+
+    let mut targets = BTreeSet::new();
+    for (_, balance) in &balances {
+        targets.extend(balance.keys().copied());
+    }
+    let power = balances.iter().any(|(power, _)| *power != 0.0);
+    let rows = power
+        .then_some(ItemIdOrPower::Power)
+        .into_iter()
+        .chain(targets.into_iter().map(ItemIdOrPower::Item))
+        .map(|target| {
+            let rates: Vec<f32> = balances
+                .iter()
+                .map(|(power, items)| {
+                    let rate = match target {
+                        ItemIdOrPower::Power => *power,
+                        ItemIdOrPower::Item(item) => items.get(&item).copied().unwrap_or_default(),
+                    };
+                    rate * copies
+                })
+                .collect();
+            let total = rates.iter().sum();
+            PivotRow {
+                target,
+                rates,
+                total,
+            }
+        })
+        .collect();
+    PivotTable { columns, rows }
+}
+
+/// Sum the net power and item balances of the given nodes.
+fn net_balance<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> (f32, BTreeMap<ItemId, f32>) {
+    let mut power = 0.0;
+    let mut items = BTreeMap::new();
+    for node in nodes {
+        let balance = node.balance();
+        power += balance.power;
+        for (&item, &rate) in &balance.balances {
+            *items.entry(item).or_default() += rate;
+        }
+    }
+    (power, items)
+}
+
+/// Get the most of the given resource that can be extracted from the map in the database, in units
+/// per minute, assuming every node and well pad is used with the best extractor at its highest
+/// clock speed. None if the database doesn't know how many nodes the resource has, like water.
+pub fn map_resource_limit(item: ItemId, database: &Database) -> Option<f32> {
+    let nodes = database.resource_nodes(item)?;
+    Some(nodes_limit(item, nodes, database))
+}
+
+/// Get the map limit of every resource whose nodes are counted by the database, in units per
+/// minute.
+pub fn map_resource_limits(database: &Database) -> impl Iterator<Item = (ItemId, f32)> + '_ {
+    database
+        .all_resource_nodes()
+        .map(|(&item, nodes)| (item, nodes_limit(item, nodes, database)))
+}
+
+/// Most of a resource that can be extracted from the given nodes. Regular nodes are used by miners
+/// and well pads by pumps.
+fn nodes_limit(item: ItemId, nodes: &ResourceNodes, database: &Database) -> f32 {
+    let mut best_miner = 0.0f32;
+    let mut best_pump = 0.0f32;
+    for building in database.buildings() {
+        let max_clock = building.max_clock_speed();
+        match &building.kind {
+            BuildingKind::Miner(m) if m.allowed_resources.contains(&item) => {
+                best_miner = best_miner.max(60.0 / m.cycle_time * m.items_per_cycle * max_clock);
+            }
+            BuildingKind::Pump(p) if p.allowed_resources.contains(&item) => {
+                best_pump = best_pump.max(60.0 / p.cycle_time * p.items_per_cycle * max_clock);
+            }
+            _ => {}
+        }
+    }
+    let normal_equivalent = |counts: &PurityCounts| -> f32 {
+        ResourcePurity::values()
+            .map(|purity| counts.get(purity) as f32 * purity.speed_multiplier())
+            .sum()
+    };
+    best_miner * normal_equivalent(&nodes.nodes) + best_pump * normal_equivalent(&nodes.well_pads)
+}
+
+/// Visit every node in the tree in pre-order, along with its path from the root.
+fn visit_with_path(node: &Node, path: &mut Vec<usize>, visitor: &mut impl FnMut(&Node, &[usize])) {
+    visitor(node, path);
+    for (i, child) in node.children().enumerate() {
+        path.push(i);
+        visit_with_path(&child, path, visitor);
+        path.pop();
+    }
+}
@@ -0,0 +1,287 @@
+//! Export of balances and buildings as CSV, for analysis in spreadsheets.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use uuid::Uuid;
+
+use crate::accounting::{Node, NodeKind};
+use crate::analysis::{pivot_table, top_producers_and_consumers, Contributor};
+use crate::database::{Database, ItemIdOrPower};
+
+/// Name used for the root of an exported tree in group paths if it doesn't have one.
+const ROOT_NAME: &str = "World";
+
+/// Separator between group names in paths.
+const PATH_SEPARATOR: &str = " / ";
+
+/// Columns of the table built by [`balances_table`].
+pub const BALANCES_HEADER: [&str; 3] = ["Group", "Item", "Rate"];
+
+/// Columns of the table built by [`buildings_table`].
+pub const BUILDINGS_HEADER: [&str; 7] = [
+    "Group",
+    "Building",
+    "Recipe / Item",
+    "Clock Speed",
+    "Copies",
+    "Group Multiplier",
+    "Power (MW)",
+];
+
+/// Columns of the table built by [`contributors_table`].
+pub const CONTRIBUTORS_HEADER: [&str; 5] = ["Role", "Rank", "Group", "Building", "Rate"];
+
+/// Build a CSV listing the net balance of every item and power in each group of the tree, with
+/// columns for the group path, the item, and the rate per minute. Rates include the copies of the
+/// group itself but not of its ancestors, matching the balances shown in the app.
+pub fn balances_csv(root: &Node, database: &Database) -> String {
+    table_csv(&BALANCES_HEADER, &balances_table(root, database))
+}
+
+/// Get the rows of [`balances_csv`], without the header.
+pub fn balances_table(root: &Node, database: &Database) -> Vec<[String; 3]> {
+    let mut rows = Vec::new();
+    visit_groups(
+        root,
+        database,
+        root_name(root),
+        1.0,
+        &mut |node, db, path, _| {
+            let balance = node.balance();
+            if balance.power != 0.0 {
+                rows.push([
+                    path.to_owned(),
+                    "Power (MW)".to_owned(),
+                    balance.power.to_string(),
+                ]);
+            }
+            for (&id, rate) in &balance.balances {
+                let name = match db.get(id) {
+                    Some(item) => item.name.to_string(),
+                    None => id.to_string(),
+                };
+                rows.push([path.to_owned(), name, rate.to_string()]);
+            }
+        },
+    );
+    rows
+}
+
+/// Build a CSV listing every building in the tree, with the path of the group containing it, its
+/// type, recipe or item, clock speed in percent, copies, the multiplier from the copies of its
+/// enclosing groups, and its power in MW for its own copies.
+pub fn buildings_csv(root: &Node, database: &Database) -> String {
+    table_csv(&BUILDINGS_HEADER, &buildings_table(root, database))
+}
+
+/// Get the rows of [`buildings_csv`], without the header.
+pub fn buildings_table(root: &Node, database: &Database) -> Vec<[String; 7]> {
+    let mut rows = Vec::new();
+    visit_groups(
+        root,
+        database,
+        root_name(root),
+        1.0,
+        &mut |node, db, path, multiplier| {
+            for child in node.children() {
+                let Some(building) = child.building() else {
+                    continue;
+                };
+                let Some(id) = building.building else {
+                    continue;
+                };
+                let name = match db.get(id) {
+                    Some(info) => info.name.to_string(),
+                    None => id.to_string(),
+                };
+                let selection = building.settings.selection_name(db).unwrap_or_default();
+                rows.push([
+                    path.to_owned(),
+                    name,
+                    selection.to_string(),
+                    (building.settings.clock_speed() * 100.0).to_string(),
+                    building.copies.to_string(),
+                    multiplier.to_string(),
+                    child.balance().power.to_string(),
+                ]);
+            }
+        },
+    );
+    rows
+}
+
+/// Build a CSV ranking the buildings in the tree which produce and consume the most of the given
+/// item or power, with columns for whether the building is a producer or consumer, its rank, the
+/// path of the group containing it, its type, and its rate including the copies of every enclosing
+/// group. At most `limit` producers and `limit` consumers are listed.
+pub fn contributors_csv(
+    root: &Node,
+    database: &Database,
+    target: ItemIdOrPower,
+    limit: usize,
+) -> String {
+    table_csv(
+        &CONTRIBUTORS_HEADER,
+        &contributors_table(root, database, target, limit),
+    )
+}
+
+/// Get the rows of [`contributors_csv`], without the header.
+pub fn contributors_table(
+    root: &Node,
+    database: &Database,
+    target: ItemIdOrPower,
+    limit: usize,
+) -> Vec<[String; 5]> {
+    // Path and type name of every building, looked up in the database of its own group.
+    let mut buildings: HashMap<Uuid, (String, String)> = HashMap::new();
+    visit_groups(
+        root,
+        database,
+        root_name(root),
+        1.0,
+        &mut |node, db, path, _| {
+            for child in node.children() {
+                let Some(building) = child.building() else {
+                    continue;
+                };
+                let name = match building.building {
+                    Some(id) => match db.get(id) {
+                        Some(info) => info.name.to_string(),
+                        None => id.to_string(),
+                    },
+                    None => String::new(),
+                };
+                buildings.insert(building.id, (path.to_owned(), name));
+            }
+        },
+    );
+
+    let top = top_producers_and_consumers(root, target, limit);
+    let rows = |role: &str, contributors: Vec<Contributor>| {
+        let buildings = &buildings;
+        let role = role.to_owned();
+        contributors
+            .into_iter()
+            .enumerate()
+            .map(move |(i, contributor)| {
+                let (path, name) = buildings.get(&contributor.id).cloned().unwrap_or_default();
+                [
+                    role.clone(),
+                    (i + 1).to_string(),
+                    path,
+                    name,
+                    contributor.rate.to_string(),
+                ]
+            })
+    };
+    rows("Producer", top.producers)
+        .chain(rows("Consumer", top.consumers))
+        .collect()
+}
+
+/// Build a CSV with one row per item and power and one column per top-level group of the tree,
+/// each cell holding the group's net rate, followed by a column with the total for the whole tree.
+/// Power is listed first, then items by name.
+pub fn pivot_csv(root: &Node, database: &Database) -> String {
+    let (header, rows) = pivot_rows(root, database);
+    let header: Vec<&str> = header.iter().map(String::as_str).collect();
+    table_csv(&header, &rows)
+}
+
+/// Get the header and rows of [`pivot_csv`]. Unlike the other tables, the columns depend on the
+/// tree, so the header is returned along with the rows.
+pub fn pivot_rows(root: &Node, database: &Database) -> (Vec<String>, Vec<Vec<String>>) {
+    let table = pivot_table(root);
+    let header = std::iter::once("Item".to_owned())
+        .chain(table.columns.into_iter().map(|column| column.name))
+        .chain(std::iter::once("Total".to_owned()))
+        .collect();
+    let mut rows: Vec<(bool, String, Vec<String>)> = table
+        .rows
+        .into_iter()
+        .map(|row| {
+            let name = match row.target {
+                ItemIdOrPower::Power => "Power (MW)".to_owned(),
+                ItemIdOrPower::Item(id) => match database.get(id) {
+                    Some(item) => item.name.to_string(),
+                    None => id.to_string(),
+                },
+            };
+            let cells = std::iter::once(name.clone())
+                .chain(row.rates.iter().map(|rate| rate.to_string()))
+                .chain(std::iter::once(row.total.to_string()))
+                .collect();
+            (row.target != ItemIdOrPower::Power, name, cells)
+        })
+        .collect();
+    rows.sort_by(|(a_item, a, _), (b_item, b, _)| (a_item, a).cmp(&(b_item, b)));
+    (
+        header,
+        rows.into_iter().map(|(_, _, cells)| cells).collect(),
+    )
+}
+
+/// Build a CSV with the given header and rows, quoting fields where needed.
+pub fn table_csv<R: AsRef<[String]>>(header: &[&str], rows: &[R]) -> String {
+    let mut csv = String::new();
+    write_row(&mut csv, header.iter().copied());
+    for row in rows {
+        write_row(&mut csv, row.as_ref().iter().map(String::as_str));
+    }
+    csv
+}
+
+/// Call `visitor` with every group in the tree, along with the database it uses, its path, and
+/// the product of the copies of the group and the groups containing it.
+fn visit_groups(
+    node: &Node,
+    database: &Database,
+    path: String,
+    multiplier: f32,
+    visitor: &mut impl FnMut(&Node, &Database, &str, f32),
+) {
+    let NodeKind::Group(group) = node.kind() else {
+        return;
+    };
+    let Ok(database) = group.resolve_database(database) else {
+        return;
+    };
+    let multiplier = multiplier * group.copies as f32;
+    visitor(node, &database, &path, multiplier);
+    for child in &group.children {
+        if let Some(child_group) = child.group() {
+            let name = if child_group.name.is_empty() {
+                "Unnamed Group"
+            } else {
+                child_group.name.as_str()
+            };
+            let child_path = format!("{path}{PATH_SEPARATOR}{name}");
+            visit_groups(child, &database, child_path, multiplier, visitor);
+        }
+    }
+}
+
+/// Get the name to start group paths with for the root of the export.
+fn root_name(root: &Node) -> String {
+    match root.group() {
+        Some(group) if !group.name.is_empty() => group.name.to_string(),
+        _ => ROOT_NAME.to_owned(),
+    }
+}
+
+/// Append a row of fields to the CSV, quoting fields where needed.
+fn write_row<'a>(csv: &mut String, fields: impl IntoIterator<Item = &'a str>) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            csv.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            let _ = write!(csv, "\"{}\"", field.replace('"', "\"\""));
+        } else {
+            csv.push_str(field);
+        }
+    }
+    csv.push_str("\r\n");
+}
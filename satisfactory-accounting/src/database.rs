@@ -5,9 +5,11 @@
 //   You may obtain a copy of the License at
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
+//! The game data which balances are computed from: items, recipes, and buildings, for each
+//! supported version of the game.
 use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::hash::Hash;
 use std::ops::Index;
@@ -18,7 +20,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::accounting::{
     BuildingSettings, GeneratorSettings, ManufacturerSettings, MinerSettings, PumpSettings,
-    StationSettings,
+    ResourcePurity, Selection, StationSettings, MAX_CLOCK,
 };
 
 /// Enum which identifies versions of the database.
@@ -35,10 +37,16 @@ pub enum DatabaseVersion {
     V1_0(V1_0Subversion),
 }
 
+thread_local! {
+    /// Database versions which aren't built in but which have been fetched.
+    static FETCHED_DATABASES: RefCell<BTreeMap<DatabaseVersion, Database>> = Default::default();
+}
+
 macro_rules! db_version_info {
     ($({
         version: DatabaseVersion::$dbv:ident($dbsv:path),
         file: $file:literal,
+        embedded: $embedded:tt,
         name: $name:literal,
         description: $description:literal $(,)?
     }),* $(,)?) => {
@@ -46,6 +54,7 @@ macro_rules! db_version_info {
             version_pat: DatabaseVersion::$dbv($dbsv),
             version_expr: DatabaseVersion::$dbv($dbsv),
             file: $file,
+            embedded: $embedded,
             name: $name,
             description: $description,
         },)*);
@@ -56,6 +65,7 @@ macro_rules! db_version_info {
         version_pat: $version_pat:pat,
         version_expr: $version_expr:expr,
         file: $file:literal,
+        embedded: $embedded:tt,
         name: $name:literal,
         description: $description:literal $(,)?
     }),* $(,)?) => {
@@ -64,31 +74,29 @@ macro_rules! db_version_info {
             $($version_expr,)*
         ];
 
-        /// Load the database at a particuler version.
-        pub fn load_database(self) -> Database {
+        /// Get the database at this version if it is built in or has already been fetched.
+        pub fn try_load_database(self) -> Option<Database> {
             match self {
-                $(
-                    $version_pat => {
-                        const SERIALIZED_DB: &str = include_str!($file);
-                        thread_local! {
-                            static SHARED_INNER: RefCell<Weak<DatabaseInner>> = Default::default();
-                        }
-                        SHARED_INNER.with_borrow_mut(|shared_inner| {
-                            match shared_inner.upgrade() {
-                                Some(inner) => Database { inner },
-                                None => {
-                                    let inner: Rc<DatabaseInner> = serde_json::from_str(SERIALIZED_DB)
-                                        .expect(concat!("Failed to parse ", $file));
-                                    *shared_inner = Rc::downgrade(&inner);
-                                    Database { inner }
-                                }
-                            }
-                        })
-                    }
-                )*
+                $($version_pat => db_version_info!(@load $embedded, $file, $version_expr),)*
             }
         }
 
+        /// Whether this database version is built into the app. Other versions must be fetched
+        /// and stored with [`DatabaseVersion::store_fetched`] before they can be loaded.
+        pub const fn is_embedded(self) -> bool {
+            match self {
+                $($version_pat => $embedded,)*
+            }
+        }
+
+        /// Name of the binary file containing this database version.
+        pub fn file_name(self) -> &'static str {
+            let file = match self {
+                $($version_pat => $file,)*
+            };
+            file.trim_start_matches("../")
+        }
+
         /// Get the displayable name for this database version.
         pub const fn name(self) -> &'static str {
             match self {
@@ -103,39 +111,66 @@ macro_rules! db_version_info {
             }
         }
     };
+
+    (@load true, $file:literal, $version_expr:expr) => {{
+        const SERIALIZED_DB: &[u8] = include_bytes!($file);
+        thread_local! {
+            static SHARED_INNER: RefCell<Weak<DatabaseInner>> = Default::default();
+        }
+        SHARED_INNER.with_borrow_mut(|shared_inner| {
+            match shared_inner.upgrade() {
+                Some(inner) => Some(Database { inner }),
+                None => {
+                    let inner: Rc<DatabaseInner> = postcard::from_bytes(SERIALIZED_DB)
+                        .expect(concat!("Failed to parse ", $file));
+                    *shared_inner = Rc::downgrade(&inner);
+                    Some(Database { inner })
+                }
+            }
+        })
+    }};
+
+    (@load false, $file:literal, $version_expr:expr) => {
+        FETCHED_DATABASES.with_borrow(|fetched| fetched.get(&$version_expr).cloned())
+    };
 }
 
 impl DatabaseVersion {
     db_version_info! [
         {
             version: DatabaseVersion::U5(U5Subversion::Initial),
-            file: "../db-u5-initial.json",
+            file: "../db-u5-initial.bin",
+            embedded: false,
             name: "U5 \u{2013} Initial",
             description: "This is the first version of the database released for U5. Fuel
                 generators in this version consume 1000x too much fuel.",
         },
         {
             version: DatabaseVersion::U5(U5Subversion::Final),
-            file: "../db-u5-final.json",
+            file: "../db-u5-final.bin",
+            embedded: false,
             name: "U5 \u{2013} Final",
             description: "This is the final version of the database released for U5.",
         },
         {
             version: DatabaseVersion::U6(U6Subversion::Beta),
-            file: "../db-u6-beta.json",
+            file: "../db-u6-beta.bin",
+            embedded: false,
             name: "U6 \u{2013} Beta",
             description: "This is the first version of the Satisfactory Accounting database \
                 released after the U6 update.",
         },
         {
             version: DatabaseVersion::U7(U7Subversion::Initial),
-            file: "../db-u7-initial.json",
+            file: "../db-u7-initial.bin",
+            embedded: false,
             name: "U7 \u{2013} Initial",
             description: "This is the first version of the database released for U7.",
         },
         {
             version: DatabaseVersion::V1_0(V1_0Subversion::Initial),
-            file: "../db-v1.0-initial.json",
+            file: "../db-v1.0-initial.bin",
+            embedded: false,
             name: "1.0 \u{2013} Initial",
             description: "This is the first version of the Satisfactory Accounting database \
                 released for Satisfactory 1.0. In this version, Water Extractors produce 0 water, \
@@ -145,7 +180,8 @@ impl DatabaseVersion {
         },
         {
             version: DatabaseVersion::V1_0(V1_0Subversion::Wetter),
-            file: "../db-v1.0-wetter.json",
+            file: "../db-v1.0-wetter.bin",
+            embedded: false,
             name: "1.0 \u{2013} Wetter",
             description: "This minor update to the database for 1.0 fixes Water Extractors so they \
                 produce water again and fixes the Resource Well Extractor to be correctly handled \
@@ -153,7 +189,8 @@ impl DatabaseVersion {
         },
         {
             version: DatabaseVersion::V1_0(V1_0Subversion::Semiquantum),
-            file: "../db-v1.0-semiquantum.json",
+            file: "../db-v1.0-semiquantum.bin",
+            embedded: false,
             name: "1.0 \u{2013} Semiquantum",
             description: "This update to the databse for Satisfactory 1.0 adds some recipies that \
                 were missing related to late-game technologies, though it doesn't add the Alien \
@@ -161,7 +198,8 @@ impl DatabaseVersion {
         },
         {
             version: DatabaseVersion::V1_0(V1_0Subversion::Powerline),
-            file: "../db-v1.0-powerline.json",
+            file: "../db-v1.0-powerline.bin",
+            embedded: false,
             name: "1.0 \u{2013} Powerline",
             description: "This update to the databse for Satisfactory 1.0 fixes power generators \
                 so they scale linearly with changes to their clock speed, which has been how the
@@ -169,16 +207,40 @@ impl DatabaseVersion {
         },
         {
             version: DatabaseVersion::V1_0(V1_0Subversion::Rocket),
-            file: "../db-v1.0-rocket.json",
+            file: "../db-v1.0-rocket.bin",
+            embedded: false,
             name: "1.0 \u{2013} Rocket",
             description: "This update to the databse for Satisfactory 1.0 corrects the production \
                 rate of the Nitro Rocket Fuel alternate recipe.",
         },
+        {
+            version: DatabaseVersion::V1_0(V1_0Subversion::Sink),
+            file: "../db-v1.0-sink.bin",
+            embedded: true,
+            name: "1.0 \u{2013} Sink",
+            description: "This update to the database for Satisfactory 1.0 adds AWESOME Sink point \
+                values to items, construction costs, unlock tiers, and power shard and somersloop \
+                slots to buildings, unlock tiers and variable power draw to recipes, conveyor belt \
+                and pipeline throughput, fuel burn rates for vehicles which refuel at Truck \
+                Stations, and the number of resource nodes of each purity on the map.",
+        },
     ];
 
     /// Latest version of the database.
     pub const LATEST: DatabaseVersion = Self::ALL[Self::ALL.len() - 1];
 
+    /// Whether this database version can be loaded without fetching it first.
+    pub fn is_available(self) -> bool {
+        self.is_embedded() || FETCHED_DATABASES.with_borrow(|fetched| fetched.contains_key(&self))
+    }
+
+    /// Store the fetched binary data for this database version so it can be loaded.
+    pub fn store_fetched(self, bytes: &[u8]) -> Result<(), postcard::Error> {
+        let database = Database::from_binary(bytes)?;
+        FETCHED_DATABASES.with_borrow_mut(|fetched| fetched.insert(self, database));
+        Ok(())
+    }
+
     /// Identifies which database versions are considered deprecated.
     ///
     /// This is mainly used in `satisfactory-accounting-app` to hide versions which have
@@ -228,6 +290,10 @@ pub enum V1_0Subversion {
     /// Update to fix the production rate of nitro rocket fuel, released in Satisfactory Accounting
     /// 1.2.8.
     Rocket,
+    /// Update adding AWESOME Sink point values to items, along with the other data added since
+    /// [`V1_0Subversion::Rocket`]. Released versions are never changed, so new data only goes in
+    /// this version.
+    Sink,
 }
 
 impl fmt::Display for DatabaseVersion {
@@ -252,6 +318,8 @@ impl Database {
         recipes: BTreeMap<RecipeId, Recipe>,
         items: BTreeMap<ItemId, Item>,
         buildings: BTreeMap<BuildingId, BuildingType>,
+        transports: Vec<Transport>,
+        resource_nodes: BTreeMap<ItemId, ResourceNodes>,
     ) -> Self {
         Self {
             inner: Rc::new(DatabaseInner {
@@ -259,6 +327,8 @@ impl Database {
                 recipes,
                 items,
                 buildings,
+                transports,
+                resource_nodes,
             }),
         }
     }
@@ -267,11 +337,78 @@ impl Database {
     pub fn buildings(&self) -> BuildingsIter {
         self.inner.buildings.values()
     }
+
+    /// Gets an iterator over the items in the database.
+    pub fn items(&self) -> ItemsIter<'_> {
+        self.inner.items.values()
+    }
+
+    /// Build a view of this database which only includes recipes matching the filter.
+    /// Manufacturers which are left with no recipes are hidden as well. The underlying database is
+    /// shared with the view rather than copied.
+    pub fn filtered(&self, filter: impl Fn(&Recipe) -> bool) -> FilteredDatabase {
+        let recipes = self
+            .inner
+            .recipes
+            .values()
+            .filter(|recipe| filter(recipe))
+            .map(|recipe| recipe.id)
+            .collect();
+        FilteredDatabase {
+            database: self.clone(),
+            recipes: Rc::new(recipes),
+        }
+    }
+
+    /// Build a copy of this database with the names of the given items replaced, such as to show
+    /// shorter or translated names. Items which aren't in the database are skipped. The database
+    /// is only copied if at least one item is renamed.
+    pub fn with_item_names<'a>(
+        &self,
+        names: impl IntoIterator<Item = (ItemId, &'a str)>,
+    ) -> Database {
+        let mut database = self.clone();
+        for (id, name) in names {
+            if database.inner.items.contains_key(&id) {
+                let inner = Rc::make_mut(&mut database.inner);
+                if let Some(item) = inner.items.get_mut(&id) {
+                    item.name = name.into();
+                }
+            }
+        }
+        database
+    }
+
+    /// Gets an iterator over the conveyor belt and pipeline tiers in the database, ordered by kind
+    /// then tier.
+    pub fn transports(&self) -> TransportsIter<'_> {
+        self.inner.transports.iter()
+    }
+
+    /// Get the number of nodes of the given resource on the map, or None if the database doesn't
+    /// know how many there are.
+    pub fn resource_nodes(&self, resource: ItemId) -> Option<&ResourceNodes> {
+        self.inner.resource_nodes.get(&resource)
+    }
+
+    /// Gets an iterator over the resources whose node counts are known, with their nodes.
+    pub fn all_resource_nodes(&self) -> ResourceNodesIter<'_> {
+        self.inner.resource_nodes.iter()
+    }
 }
 
 /// Iterator over the list of available buildings.
 pub type BuildingsIter<'a> = std::collections::btree_map::Values<'a, BuildingId, BuildingType>;
 
+/// Iterator over the list of available items.
+pub type ItemsIter<'a> = std::collections::btree_map::Values<'a, ItemId, Item>;
+
+/// Iterator over the list of available conveyor belts and pipelines.
+pub type TransportsIter<'a> = std::slice::Iter<'a, Transport>;
+
+/// Iterator over the resource node counts of the map.
+pub type ResourceNodesIter<'a> = std::collections::btree_map::Iter<'a, ItemId, ResourceNodes>;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct DatabaseInner {
     /// Prefix used for static paths for icons in this version of the database.
@@ -283,6 +420,12 @@ struct DatabaseInner {
     items: BTreeMap<ItemId, Item>,
     /// Core buildings storage.
     buildings: BTreeMap<BuildingId, BuildingType>,
+    /// Conveyor belt and pipeline tiers, ordered by kind then tier.
+    #[serde(default)]
+    transports: Vec<Transport>,
+    /// Number of nodes of each resource on the map.
+    #[serde(default)]
+    resource_nodes: BTreeMap<ItemId, ResourceNodes>,
 }
 
 impl Database {
@@ -293,7 +436,23 @@ impl Database {
 
     /// Load the default version of the database.
     pub fn load_latest() -> Database {
-        DatabaseVersion::LATEST.load_database()
+        DatabaseVersion::LATEST
+            .try_load_database()
+            .expect("The latest database is always built in")
+    }
+
+    /// Serialize this database in the compact binary format used for the databases built into
+    /// the app. User-facing import and export should use JSON instead.
+    pub fn to_binary(&self) -> Vec<u8> {
+        postcard::to_allocvec(&*self.inner).expect("Database serialization cannot fail")
+    }
+
+    /// Load a database from the binary format produced by [`Database::to_binary`].
+    pub fn from_binary(bytes: &[u8]) -> Result<Database, postcard::Error> {
+        let inner = postcard::from_bytes(bytes)?;
+        Ok(Database {
+            inner: Rc::new(inner),
+        })
     }
 
     /// Compare this database to another database, ignoring their icon prefixes.
@@ -301,6 +460,8 @@ impl Database {
         self.inner.recipes == other.inner.recipes
             && self.inner.items == other.inner.items
             && self.inner.buildings == other.inner.buildings
+            && self.inner.transports == other.inner.transports
+            && self.inner.resource_nodes == other.inner.resource_nodes
     }
 
     /// Prefix used for static paths for icons in this version of the database.
@@ -315,6 +476,127 @@ impl Database {
     {
         Rc::make_mut(&mut self.inner).icon_prefix = prefix.into();
     }
+
+    /// Get all recipes which produce the given item, along with the rate that each produces it at
+    /// 100% clock speed. Results are sorted by descending rate, then by recipe name.
+    pub fn recipes_producing(&self, item: ItemId) -> Vec<RecipeRate<'_>> {
+        let Some(item) = self.get(item) else {
+            return Vec::new();
+        };
+        self.recipe_rates(&item.produced_by, |recipe| recipe.product_rate(item.id))
+    }
+
+    /// Get all recipes which consume the given item, along with the rate that each consumes it at
+    /// 100% clock speed. Results are sorted by descending rate, then by recipe name.
+    pub fn recipes_consuming(&self, item: ItemId) -> Vec<RecipeRate<'_>> {
+        let Some(item) = self.get(item) else {
+            return Vec::new();
+        };
+        self.recipe_rates(&item.consumed_by, |recipe| recipe.ingredient_rate(item.id))
+    }
+
+    /// Get the kind of transport needed to move the given item. Unknown items are assumed to be
+    /// solid.
+    pub fn transport_kind_for(&self, item: ItemId) -> TransportKind {
+        match self.get(item) {
+            Some(item) if item.is_fluid => TransportKind::Pipeline,
+            _ => TransportKind::Belt,
+        }
+    }
+
+    /// Get the transport of the given kind and tier, if the database has it.
+    pub fn transport(&self, kind: TransportKind, tier: u8) -> Option<&Transport> {
+        self.transports()
+            .find(|transport| transport.kind == kind && transport.tier == tier)
+    }
+
+    /// Get the highest tier of the given kind of transport, if the database has any.
+    pub fn fastest_transport(&self, kind: TransportKind) -> Option<&Transport> {
+        self.transports()
+            .filter(|transport| transport.kind == kind)
+            .max_by_key(|transport| transport.tier)
+    }
+
+    /// Look up the given recipes and compute their rates, sorted by descending rate then name.
+    fn recipe_rates(
+        &self,
+        recipes: &[RecipeId],
+        rate: impl Fn(&Recipe) -> f32,
+    ) -> Vec<RecipeRate<'_>> {
+        let mut rates: Vec<_> = recipes
+            .iter()
+            .filter_map(|&id| self.get(id))
+            .map(|recipe| RecipeRate {
+                recipe,
+                rate: rate(recipe),
+            })
+            .collect();
+        rates.sort_by(|lhs, rhs| {
+            rhs.rate
+                .total_cmp(&lhs.rate)
+                .then_with(|| lhs.recipe.name.cmp(&rhs.recipe.name))
+        });
+        rates
+    }
+}
+
+/// A view of a [`Database`] which only includes some of its recipes, created by
+/// [`Database::filtered`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilteredDatabase {
+    /// The full database.
+    database: Database,
+    /// Recipes which are included in this view.
+    recipes: Rc<BTreeSet<RecipeId>>,
+}
+
+impl FilteredDatabase {
+    /// Get the full database this view was created from.
+    pub fn database(&self) -> &Database {
+        &self.database
+    }
+
+    /// Whether the given recipe is included in this view.
+    pub fn has_recipe(&self, recipe: RecipeId) -> bool {
+        self.recipes.contains(&recipe)
+    }
+
+    /// Get the included recipes which can be made in the given building. Empty if the building is
+    /// unknown or isn't a manufacturer.
+    pub fn recipes_for(&self, building: BuildingId) -> Vec<RecipeId> {
+        match self.database.get(building).map(|building| &building.kind) {
+            Some(BuildingKind::Manufacturer(m)) => m
+                .available_recipes
+                .iter()
+                .copied()
+                .filter(|&recipe| self.has_recipe(recipe))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Gets an iterator over the buildings in this view. Manufacturers are only included if at
+    /// least one of their recipes is.
+    pub fn buildings(&self) -> impl Iterator<Item = &BuildingType> {
+        self.database
+            .buildings()
+            .filter(|building| match &building.kind {
+                BuildingKind::Manufacturer(m) => m
+                    .available_recipes
+                    .iter()
+                    .any(|&recipe| self.has_recipe(recipe)),
+                _ => true,
+            })
+    }
+}
+
+/// A recipe paired with the rate at which it produces or consumes a particular item.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RecipeRate<'a> {
+    /// The recipe which produces or consumes the item.
+    pub recipe: &'a Recipe,
+    /// Items per minute produced or consumed by one building at 100% clock speed.
+    pub rate: f32,
 }
 
 impl PartialEq for Database {
@@ -341,6 +623,7 @@ impl<T: Id> Index<T> for Database {
 pub trait Id:
     fmt::Display + fmt::Debug + Eq + PartialEq + Copy + Clone + Hash + private::Sealed
 {
+    /// Type of the database entry identified by this kind of id.
     type Info;
 
     /// Fetch the item of the correct type with this id from the database.
@@ -456,6 +739,7 @@ typed_symbol! {
         map = items,
     }
 
+    /// Id of a building.
     BuildingId {
         info = BuildingType,
         map = buildings,
@@ -496,8 +780,59 @@ pub struct Recipe {
     pub products: Vec<ItemAmount>,
     /// True if this is an alternate recipe.
     pub is_alternate: bool,
+    /// Milestone tier at which this recipe and a building to produce it are unlocked. Zero if
+    /// available from the start or if the database predates unlock tiers.
+    #[serde(default)]
+    pub unlock_tier: u32,
     /// Buildings which can produce this recipe.
     pub produced_in: Vec<BuildingId>,
+    /// Range of power drawn while making this recipe, for recipes whose power draw varies over
+    /// each cycle. None if the building draws constant power.
+    #[serde(default)]
+    pub variable_power: Option<VariablePower>,
+}
+
+/// Range of power drawn by a building whose power draw varies over each production cycle.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariablePower {
+    /// Least power drawn at 100% clock speed, in MW.
+    pub min: f32,
+    /// Most power drawn at 100% clock speed, in MW.
+    pub max: f32,
+}
+
+impl VariablePower {
+    /// Get the ratio of the most power drawn to the average power drawn over a cycle.
+    pub fn peak_factor(&self) -> f32 {
+        let average = (self.min + self.max) / 2.0;
+        if average > 0.0 {
+            self.max / average
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Recipe {
+    /// Get the number of the given item produced per minute at 100% clock speed.
+    pub fn product_rate(&self, item: ItemId) -> f32 {
+        self.rate_of(&self.products, item)
+    }
+
+    /// Get the number of the given item consumed per minute at 100% clock speed.
+    pub fn ingredient_rate(&self, item: ItemId) -> f32 {
+        self.rate_of(&self.ingredients, item)
+    }
+
+    /// Sum the per-minute rate of the given item in a list of item amounts.
+    fn rate_of(&self, amounts: &[ItemAmount], item: ItemId) -> f32 {
+        let amount: f32 = amounts
+            .iter()
+            .filter(|ia| ia.item == item)
+            .map(|ia| ia.amount)
+            .sum();
+        amount * 60.0 / self.time
+    }
 }
 
 /// An input or output: a certain number of items produced or consumed.
@@ -532,6 +867,91 @@ pub struct Item {
     pub mined_by: Vec<BuildingId>,
     /// Speed that this resource is mined at.
     pub mining_speed: f32,
+    /// Points awarded for each of this item fed into the AWESOME Sink. Zero if the item can't be
+    /// sunk or the database predates sink points.
+    #[serde(default)]
+    pub sink_points: u32,
+    /// True if this item is a liquid or gas which is moved by pipelines rather than belts.
+    #[serde(default)]
+    pub is_fluid: bool,
+}
+
+/// A tier of conveyor belt or pipeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transport {
+    /// Name of the transport tier.
+    pub name: Rc<str>,
+    /// ID of the image for this transport.
+    pub image: Rc<str>,
+    /// Whether this is a belt or a pipeline.
+    pub kind: TransportKind,
+    /// Tier of this transport within its kind, starting at 1 for Mk.1.
+    pub tier: u8,
+    /// Maximum throughput in items (or cubic meters of fluid) per minute.
+    pub throughput: f32,
+}
+
+impl Transport {
+    /// Get the number of parallel lines of this transport needed to carry the given rate.
+    pub fn lines_needed(&self, rate: f32) -> u32 {
+        (rate.abs() / self.throughput).ceil() as u32
+    }
+}
+
+/// Kinds of transport used to move items between buildings.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TransportKind {
+    /// Conveyor belts, which carry solid items.
+    Belt,
+    /// Pipelines, which carry fluids.
+    Pipeline,
+}
+
+/// Nodes of a single resource, either on the map or claimed by extractors.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceNodes {
+    /// Nodes which a miner or oil extractor is built on.
+    #[serde(default)]
+    pub nodes: PurityCounts,
+    /// Pads around resource wells, which are extracted with a resource well pressurizer.
+    #[serde(default)]
+    pub well_pads: PurityCounts,
+}
+
+/// Count of resource nodes of each purity.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurityCounts {
+    /// Number of impure nodes.
+    pub impure: u32,
+    /// Number of normal nodes.
+    pub normal: u32,
+    /// Number of pure nodes.
+    pub pure: u32,
+}
+
+impl PurityCounts {
+    /// Get the number of nodes of the given purity.
+    pub fn get(&self, purity: ResourcePurity) -> u32 {
+        match purity {
+            ResourcePurity::Impure => self.impure,
+            ResourcePurity::Normal => self.normal,
+            ResourcePurity::Pure => self.pure,
+        }
+    }
+
+    /// Get mutable access to the number of nodes of the given purity.
+    pub fn get_mut(&mut self, purity: ResourcePurity) -> &mut u32 {
+        match purity {
+            ResourcePurity::Impure => &mut self.impure,
+            ResourcePurity::Normal => &mut self.normal,
+            ResourcePurity::Pure => &mut self.pure,
+        }
+    }
+
+    /// Total number of nodes of every purity.
+    pub fn total(&self) -> u32 {
+        self.impure + self.normal + self.pure
+    }
 }
 
 /// Settings for an item used as fuel.
@@ -566,6 +986,26 @@ pub struct BuildingType {
     pub description: String,
     /// Kind of the building.
     pub kind: BuildingKind,
+    /// Items needed to construct one of this building. Empty if the database predates build costs.
+    #[serde(default)]
+    pub build_cost: Vec<ItemAmount>,
+    /// Milestone tier at which this building is unlocked. Zero if available from the start or if
+    /// the database predates unlock tiers.
+    #[serde(default)]
+    pub unlock_tier: u32,
+    /// Number of power shards which can be installed in this building. Older databases don't
+    /// record this, so it defaults to the usual 3.
+    #[serde(default = "default_power_shard_slots")]
+    pub power_shard_slots: u32,
+    /// Number of somersloops which can be installed to amplify this building's production. Zero
+    /// if the building can't be amplified or if the database predates somersloop slots.
+    #[serde(default)]
+    pub somersloop_slots: u32,
+}
+
+/// Default number of power shard slots for databases which don't record them.
+fn default_power_shard_slots() -> u32 {
+    3
 }
 
 impl BuildingType {
@@ -586,6 +1026,16 @@ impl BuildingType {
             BuildingKind::Station(_) => false,
         }
     }
+
+    /// Highest clock speed this building supports with all of its power shard slots filled. Each
+    /// shard adds 50% on top of the base 100%.
+    pub fn max_clock_speed(&self) -> f32 {
+        if self.overclockable() {
+            (1.0 + 0.5 * self.power_shard_slots as f32).min(MAX_CLOCK)
+        } else {
+            1.0
+        }
+    }
 }
 
 /// Which kind of building this is (affects how resources are produced/consumed).
@@ -609,6 +1059,43 @@ pub enum BuildingKind {
 }
 
 impl BuildingKind {
+    /// Get every recipe, fuel, or resource a building of this kind can be set to use.
+    pub fn allowed_selections(&self) -> Vec<Selection> {
+        match self {
+            Self::Manufacturer(m) => m
+                .available_recipes
+                .iter()
+                .copied()
+                .map(Selection::Recipe)
+                .collect(),
+            Self::Miner(m) => m
+                .allowed_resources
+                .iter()
+                .copied()
+                .map(Selection::Item)
+                .collect(),
+            Self::Generator(g) => g
+                .allowed_fuel
+                .iter()
+                .copied()
+                .map(Selection::Item)
+                .collect(),
+            Self::Pump(p) => p
+                .allowed_resources
+                .iter()
+                .copied()
+                .map(Selection::Item)
+                .collect(),
+            Self::Station(s) => s
+                .allowed_fuel
+                .iter()
+                .copied()
+                .map(Selection::Item)
+                .collect(),
+            Self::Geothermal(_) | Self::PowerConsumer(_) => Vec::new(),
+        }
+    }
+
     /// Get the ID of this buiilding kind.
     pub fn kind_id(&self) -> BuildingKindId {
         match self {
@@ -819,6 +1306,34 @@ pub struct Station {
     pub power: f32,
     /// Allowed fuels for vehicles at this station.
     pub allowed_fuel: Vec<ItemId>,
+    /// Fuel-burning vehicles which dock at this station.
+    #[serde(default)]
+    pub vehicles: Vec<Vehicle>,
+}
+
+impl Station {
+    /// Get the vehicle with the given name, if this station serves it.
+    pub fn vehicle(&self, name: &str) -> Option<&Vehicle> {
+        self.vehicles.iter().find(|vehicle| &*vehicle.name == name)
+    }
+}
+
+/// A fuel-burning vehicle which refuels at a station.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vehicle {
+    /// Name of the vehicle.
+    pub name: Rc<str>,
+    /// ID of the image for this vehicle.
+    pub image: Rc<str>,
+    /// Power burned from fuel while the vehicle is moving, in MW.
+    pub power: f32,
+}
+
+impl Vehicle {
+    /// Get the number of items of the given fuel burned per minute while the vehicle is moving.
+    pub fn fuel_per_minute(&self, fuel: &Fuel) -> f32 {
+        self.power * 60.0 / fuel.energy
+    }
 }
 
 mod private {
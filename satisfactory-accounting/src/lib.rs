@@ -5,5 +5,40 @@
 //   You may obtain a copy of the License at
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
+//! The accounting engine behind [Satisfactory Accounting](https://satisfactory-accounting.github.io),
+//! for tools which read or change Satisfactory Accounting worlds.
+//!
+//! A world is a tree of [`Node`][accounting::Node]s: groups, which contain other nodes, and
+//! buildings, which produce and consume items according to their
+//! [`BuildingSettings`][accounting::BuildingSettings] and the game data in a
+//! [`Database`][database::Database]. Every node caches its [`Balance`][accounting::Balance], the
+//! net rate of each item and of power. World files downloaded from the app are read and written
+//! with [`SaveFile`][savefile::SaveFile].
+//!
+//! ```no_run
+//! use satisfactory_accounting::savefile::SaveFile;
+//!
+//! let json = std::fs::read_to_string("my-world.json").unwrap();
+//! let mut save = SaveFile::from_json(&json).unwrap();
+//! let db = save.database.try_load_database().expect("database needs to be fetched");
+//! save.rebuild(&db);
+//! for (item, rate) in &save.root.balance().balances {
+//!     println!("{}: {rate}/min", db.get(*item).unwrap().name);
+//! }
+//! ```
+//!
+//! # Stability
+//!
+//! The [`accounting`], [`database`], and [`savefile`] modules follow semver. The other modules
+//! are used by the app and may change in any release.
+#![warn(missing_docs)]
+
 pub mod accounting;
+pub mod analysis;
+pub mod csv;
 pub mod database;
+pub mod optimizer;
+pub mod planner;
+pub mod satisfactory_tools;
+pub mod savefile;
+pub mod savegame;
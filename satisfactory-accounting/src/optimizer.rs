@@ -0,0 +1,200 @@
+//! Choosing between recipes with a linear program.
+//!
+//! Where the [`planner`][crate::planner] always uses the allowed recipe that makes the most of
+//! each item, the optimizer weighs every allowed recipe at once and picks the mix which makes the
+//! target using the fewest raw resources or the least power.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use microlp::{ComparisonOp, LinearExpr, OptimizationDirection, Problem, Variable};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::accounting::{Group, Node};
+use crate::analysis::{map_resource_limit, map_resource_limits};
+use crate::database::{BuildingKind, BuildingType, Database, ItemId, Recipe};
+use crate::planner::{first_machine, is_raw_resource, recipe_node, PlanTarget};
+
+/// Machine counts smaller than this are left out of the result.
+const EPSILON: f64 = 1e-4;
+
+/// Cost added for each machine, so that among otherwise equal solutions the one with the fewest
+/// machines is chosen.
+const MACHINE_COST: f64 = 1e-3;
+
+/// Cost added for each unit of raw resource when minimizing power, so that resources aren't
+/// wasted on recipes which happen to use no extra power.
+const RESOURCE_TIEBREAK: f64 = 1e-4;
+
+/// Weight of raw resources which have no limit on the map, like water.
+const UNLIMITED_WEIGHT: f64 = 0.01;
+
+/// What the optimizer minimizes.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptimizeGoal {
+    /// Use as little of the raw resources as possible, weighting each resource by how scarce it
+    /// is on the map.
+    #[default]
+    RawResources,
+    /// Use as little power as possible, counting both the machines and the extractors which
+    /// supply their raw resources.
+    Power,
+}
+
+/// Errors which can occur when optimizing.
+#[derive(Error, Debug)]
+pub enum OptimizeError {
+    /// The target can't be made from the allowed recipes and inputs.
+    #[error("No mix of the allowed recipes and inputs can make the target")]
+    Infeasible,
+    /// The solver failed.
+    #[error("Unable to optimize: {0}")]
+    Solver(#[from] microlp::Error),
+}
+
+/// Request to make a target using the best mix of its allowed recipes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizeRequest {
+    /// Item and rate to make, and the recipes which may be used.
+    pub target: PlanTarget,
+    /// Items supplied from elsewhere, with the most available per minute. Raw resources which
+    /// aren't listed are limited by how much the map has.
+    pub inputs: BTreeMap<ItemId, f32>,
+    /// What to minimize.
+    pub goal: OptimizeGoal,
+}
+
+impl OptimizeRequest {
+    /// Solve for the best mix of recipes and create a group of buildings running them. The target
+    /// rate is shown in the group's name as `rate_label`, so the caller can format it for display.
+    pub fn build_group(
+        &self,
+        database: &Database,
+        rate_label: &str,
+    ) -> Result<Node, OptimizeError> {
+        let recipes: Vec<(&Recipe, &BuildingType)> = self
+            .target
+            .allowed_recipes
+            .iter()
+            .filter_map(|&id| database.get(id))
+            .filter_map(|recipe| Some((recipe, first_machine(recipe, database)?)))
+            .collect();
+        let items: BTreeSet<ItemId> = recipes
+            .iter()
+            .flat_map(|(recipe, _)| recipe.ingredients.iter().chain(&recipe.products))
+            .map(|amount| amount.item)
+            .chain([self.target.item])
+            .chain(self.inputs.keys().copied())
+            .collect();
+
+        let most_abundant = largest_map_resource_limit(database);
+        let mut problem = Problem::new(OptimizationDirection::Minimize);
+        let machines: Vec<Variable> = recipes
+            .iter()
+            .map(|(_, machine)| {
+                let cost = match self.goal {
+                    OptimizeGoal::RawResources => 0.0,
+                    OptimizeGoal::Power => machine_power(machine),
+                };
+                problem.add_var(cost + MACHINE_COST, (0.0, f64::INFINITY))
+            })
+            .collect();
+        for &item in &items {
+            let mut expr = LinearExpr::empty();
+            for ((recipe, _), &machine) in recipes.iter().zip(&machines) {
+                let net = recipe.product_rate(item) - recipe.ingredient_rate(item);
+                if net != 0.0 {
+                    expr.add(machine, net as f64);
+                }
+            }
+            let supplied = self.inputs.get(&item).copied();
+            let raw = is_raw_resource(item, database);
+            if supplied.is_some() || raw {
+                let limit = match (supplied, map_resource_limit(item, database)) {
+                    (Some(limit), _) | (None, Some(limit)) => limit as f64,
+                    (None, None) => f64::INFINITY,
+                };
+                let weight = if !raw {
+                    0.0
+                } else {
+                    match map_resource_limit(item, database) {
+                        Some(limit) => most_abundant / limit as f64,
+                        None => UNLIMITED_WEIGHT,
+                    }
+                };
+                let cost = match self.goal {
+                    OptimizeGoal::RawResources => weight,
+                    OptimizeGoal::Power if raw => {
+                        extraction_power(item, database) + weight * RESOURCE_TIEBREAK
+                    }
+                    OptimizeGoal::Power => 0.0,
+                };
+                expr.add(problem.add_var(cost, (0.0, limit)), 1.0);
+            }
+            let demand = if item == self.target.item {
+                self.target.rate as f64
+            } else {
+                0.0
+            };
+            problem.add_constraint(expr, ComparisonOp::Ge, demand);
+        }
+
+        let solution = problem.solve().map_err(|e| match e {
+            microlp::Error::Infeasible => OptimizeError::Infeasible,
+            e => OptimizeError::Solver(e),
+        })?;
+        let children = recipes
+            .iter()
+            .zip(&machines)
+            .map(|(&(recipe, machine), &var)| (recipe, machine, solution[var]))
+            .filter(|&(_, _, count)| count > EPSILON)
+            .map(|(recipe, machine, count)| recipe_node(recipe, machine, count as f32, database))
+            .collect();
+        let name = match database.get(self.target.item) {
+            Some(item) => format!("Optimized {rate_label} {}", item.name),
+            None => "Optimized Production".to_owned(),
+        };
+        Ok(Group {
+            name: name.into(),
+            children,
+            ..Group::empty()
+        }
+        .into())
+    }
+}
+
+/// Power used by one machine at 100% clock speed, in MW.
+fn machine_power(machine: &BuildingType) -> f64 {
+    match &machine.kind {
+        BuildingKind::Manufacturer(m) => m.power_consumption.power as f64,
+        _ => 0.0,
+    }
+}
+
+/// Least power in MW used to extract one unit per minute of a raw resource, by any miner which
+/// can extract it running at 100% clock speed on a normal purity node. Resource well pressurizers
+/// are left out since their power is shared by however many pads the well has, so resources which
+/// only come from wells, like nitrogen, cost nothing.
+fn extraction_power(item: ItemId, database: &Database) -> f64 {
+    let Some(item) = database.get(item) else {
+        return 0.0;
+    };
+    item.mined_by
+        .iter()
+        .filter_map(|&id| match &database.get(id)?.kind {
+            BuildingKind::Miner(m) => {
+                let rate = 60.0 / m.cycle_time as f64 * m.items_per_cycle as f64;
+                (rate > 0.0).then(|| m.power_consumption.power as f64 / rate)
+            }
+            _ => None,
+        })
+        .reduce(f64::min)
+        .unwrap_or(0.0)
+}
+
+/// Limit of the most abundant resource on the map, which resource weights are relative to.
+fn largest_map_resource_limit(database: &Database) -> f64 {
+    map_resource_limits(database)
+        .map(|(_, limit)| limit as f64)
+        .fold(0.0, f64::max)
+}
@@ -0,0 +1,337 @@
+//! Planning of production chains, by working backwards from the items to produce through a
+//! choice of recipes.
+//!
+//! Byproducts are credited towards later needs and raw resources are left as deficits for the
+//! user to supply. Plans are made of ordinary buildings, so a planned group can be edited like
+//! any hand-made one.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::accounting::{BuildNode, Building, BuildingSettings, Group, Node};
+use crate::analysis::balance_breakdown;
+use crate::database::{BuildingType, Database, ItemId, ItemIdOrPower, Recipe, RecipeId};
+
+/// Rates smaller than this are treated as zero when resolving plans.
+const EPSILON: f32 = 1e-4;
+
+/// Most recipe steps taken when resolving a plan, which stops recipe loops from running forever.
+const MAX_STEPS: usize = 1000;
+
+/// Decides which recipes and buildings a plan is made with.
+pub trait RecipeChooser {
+    /// Choose the recipe used to make the given item, or None if it is a raw resource or no
+    /// allowed recipe makes it.
+    fn choose_recipe<'db>(&self, item: ItemId, database: &'db Database) -> Option<&'db Recipe>;
+
+    /// Choose the building used to run the given recipe, or None if no allowed building can.
+    fn machine_for<'db>(
+        &self,
+        recipe: &Recipe,
+        database: &'db Database,
+    ) -> Option<&'db BuildingType>;
+}
+
+/// Create the buildings needed to make up the deficits in `net`, which maps items to their
+/// starting rate per minute. Negative rates are items to produce and positive rates are items
+/// supplied from elsewhere.
+///
+/// Buildings are returned in the order their recipes were first needed, with one building per
+/// recipe whose machines all run at the same clock speed.
+pub fn plan_buildings(
+    net: BTreeMap<ItemId, f32>,
+    chooser: &impl RecipeChooser,
+    database: &Database,
+) -> Vec<Node> {
+    let (machines, _) = resolve(net, chooser, database);
+    machines
+        .into_iter()
+        .filter_map(|(recipe, count)| {
+            let recipe = database.get(recipe)?;
+            let machine = chooser.machine_for(recipe, database)?;
+            Some(recipe_node(recipe, machine, count, database))
+        })
+        .collect()
+}
+
+/// Find the raw resources per minute needed to make the given items at the given rates per
+/// minute, by working back through the recipes the chooser picks. Items which no chosen recipe
+/// makes are counted as they are.
+pub fn raw_resource_cost(
+    items: &BTreeMap<ItemId, f32>,
+    chooser: &impl RecipeChooser,
+    database: &Database,
+) -> BTreeMap<ItemId, f32> {
+    let net = items.iter().map(|(&item, &rate)| (item, -rate)).collect();
+    let (_, net) = resolve(net, chooser, database);
+    net.into_iter()
+        .filter(|&(_, rate)| rate < -EPSILON)
+        .map(|(item, rate)| (item, -rate))
+        .collect()
+}
+
+/// Raw resources behind what a node makes, both as it is built and as it would be with standard
+/// recipes.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RawFootprint {
+    /// Raw resources per minute the node's buildings consume, plus what it would take to make the
+    /// other items the node takes in with standard recipes.
+    pub actual: BTreeMap<ItemId, f32>,
+    /// Raw resources per minute needed to make the node's net outputs with standard recipes.
+    pub standard: BTreeMap<ItemId, f32>,
+}
+
+impl RawFootprint {
+    /// Work out the raw resource footprint of a node. Raw resources the node passes through, like
+    /// the output of miners, don't count as outputs.
+    pub fn of(node: &Node, database: &Database) -> Self {
+        let balance = node.balance();
+        let (outputs, inputs): (BTreeMap<_, _>, BTreeMap<_, _>) = balance
+            .balances
+            .iter()
+            .filter(|&(&item, &rate)| rate.abs() > EPSILON && !is_raw_resource(item, database))
+            .map(|(&item, &rate)| (item, rate))
+            .partition(|&(_, rate)| rate > 0.0);
+        let inputs = inputs
+            .into_iter()
+            .map(|(item, rate)| (item, -rate))
+            .collect();
+
+        let mut actual = raw_resource_cost(&inputs, &StandardRecipes, database);
+        let raw_items: BTreeSet<ItemId> = node
+            .iter()
+            .filter(|node| node.building().is_some())
+            .flat_map(|node| node.balance().balances.keys().copied().collect::<Vec<_>>())
+            .filter(|&item| is_raw_resource(item, database))
+            .collect();
+        for item in raw_items {
+            let consumed = balance_breakdown(node, ItemIdOrPower::Item(item), 0).consumed;
+            if consumed > EPSILON {
+                *actual.entry(item).or_default() += consumed;
+            }
+        }
+        Self {
+            actual,
+            standard: raw_resource_cost(&outputs, &StandardRecipes, database),
+        }
+    }
+}
+
+/// Work back through recipes until every deficit in `net` is made up or can't be made. Returns
+/// the number of machines running each recipe, in the order the recipes were first needed, and
+/// the remaining net rate of every item.
+fn resolve<'db>(
+    mut net: BTreeMap<ItemId, f32>,
+    chooser: &impl RecipeChooser,
+    database: &'db Database,
+) -> (Vec<(RecipeId, f32)>, BTreeMap<ItemId, f32>) {
+    // Machines running each recipe, in the order the recipes were first needed.
+    let mut machines: Vec<(RecipeId, f32)> = Vec::new();
+    let mut chosen: HashMap<ItemId, Option<&'db Recipe>> = HashMap::new();
+    for _ in 0..MAX_STEPS {
+        let needed = net.iter().find_map(|(&item, &rate)| {
+            if rate >= -EPSILON {
+                return None;
+            }
+            let recipe = *chosen
+                .entry(item)
+                .or_insert_with(|| chooser.choose_recipe(item, database));
+            recipe.map(|recipe| (item, -rate, recipe))
+        });
+        let Some((item, deficit, recipe)) = needed else {
+            break;
+        };
+        let count = deficit / recipe.product_rate(item);
+        for product in &recipe.products {
+            *net.entry(product.item).or_default() += recipe.product_rate(product.item) * count;
+        }
+        for ingredient in &recipe.ingredients {
+            *net.entry(ingredient.item).or_default() -=
+                recipe.ingredient_rate(ingredient.item) * count;
+        }
+        match machines.iter_mut().find(|(id, _)| *id == recipe.id) {
+            Some((_, existing)) => *existing += count,
+            None => machines.push((recipe.id, count)),
+        }
+    }
+    (machines, net)
+}
+
+/// Create the node for a building running the given recipe on the given number of machines.
+/// All machines run at the same clock speed.
+pub(crate) fn recipe_node(
+    recipe: &Recipe,
+    machine: &BuildingType,
+    count: f32,
+    database: &Database,
+) -> Node {
+    let whole = (count - EPSILON).ceil().max(1.0);
+    let mut settings = machine.get_default_settings();
+    if machine.overclockable() {
+        settings.set_clock_speed((count / whole * 10_000.0).round() / 10_000.0);
+    }
+    if let BuildingSettings::Manufacturer(settings) = &mut settings {
+        settings.recipe = Some(recipe.id);
+    }
+    let building = Building {
+        building: Some(machine.id),
+        settings,
+        copies: whole,
+        ..Building::empty()
+    };
+    building
+        .clone()
+        .build_node(database)
+        .unwrap_or_else(|e| e.into_warning_node(building))
+}
+
+/// Whether the given item is a raw resource, which plans leave for the user to supply.
+pub(crate) fn is_raw_resource(item: ItemId, database: &Database) -> bool {
+    database
+        .get(item)
+        .is_some_and(|item| !item.mined_by.is_empty())
+}
+
+/// Use the allowed recipe that produces the most of the item. Recipes with an ingredient that is
+/// itself made from the item, like unpackaging, are only used if nothing else is allowed.
+fn choose_allowed_recipe(
+    item: ItemId,
+    database: &Database,
+    allowed: impl Fn(&Recipe) -> bool,
+) -> Option<&Recipe> {
+    if is_raw_resource(item, database) {
+        return None;
+    }
+    let usable: Vec<_> = database
+        .recipes_producing(item)
+        .into_iter()
+        .map(|rate| rate.recipe)
+        .filter(|recipe| allowed(recipe) && first_machine(recipe, database).is_some())
+        .collect();
+    let circular = |recipe: &Recipe| {
+        recipe.ingredients.iter().any(|ingredient| {
+            database
+                .recipes_producing(ingredient.item)
+                .iter()
+                .filter(|rate| allowed(rate.recipe))
+                .any(|rate| rate.recipe.ingredients.iter().any(|ing| ing.item == item))
+        })
+    };
+    usable
+        .iter()
+        .find(|recipe| !circular(recipe))
+        .or(usable.first())
+        .copied()
+}
+
+/// The first building listed as able to run the recipe.
+pub(crate) fn first_machine<'db>(
+    recipe: &Recipe,
+    database: &'db Database,
+) -> Option<&'db BuildingType> {
+    recipe.produced_in.iter().find_map(|&id| database.get(id))
+}
+
+/// Chooses from every standard recipe, for estimating what items cost to make without
+/// alternates.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct StandardRecipes;
+
+impl RecipeChooser for StandardRecipes {
+    fn choose_recipe<'db>(&self, item: ItemId, database: &'db Database) -> Option<&'db Recipe> {
+        choose_allowed_recipe(item, database, |recipe| !recipe.is_alternate)
+    }
+
+    fn machine_for<'db>(
+        &self,
+        recipe: &Recipe,
+        database: &'db Database,
+    ) -> Option<&'db BuildingType> {
+        first_machine(recipe, database)
+    }
+}
+
+/// Request to make a single item at a fixed rate using only some recipes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanTarget {
+    /// Item to make.
+    pub item: ItemId,
+    /// Items per minute to make.
+    pub rate: f32,
+    /// Recipes the plan may use.
+    pub allowed_recipes: BTreeSet<RecipeId>,
+}
+
+impl PlanTarget {
+    /// Create a target which may use every standard recipe that can take part in making the
+    /// item, but no alternates.
+    pub fn new(item: ItemId, rate: f32, database: &Database) -> Self {
+        let allowed_recipes = Self::chain_recipes(item, database)
+            .into_iter()
+            .filter(|&id| database.get(id).is_some_and(|recipe| !recipe.is_alternate))
+            .collect();
+        Self {
+            item,
+            rate,
+            allowed_recipes,
+        }
+    }
+
+    /// Every recipe which could take part in making the given item, in the order they are found
+    /// working upstream from it.
+    pub fn chain_recipes(item: ItemId, database: &Database) -> Vec<RecipeId> {
+        let mut recipes = Vec::new();
+        let mut seen_items = BTreeSet::from([item]);
+        let mut pending = vec![item];
+        while let Some(item) = pending.pop() {
+            if is_raw_resource(item, database) {
+                continue;
+            }
+            for rate in database.recipes_producing(item) {
+                if recipes.contains(&rate.recipe.id) {
+                    continue;
+                }
+                recipes.push(rate.recipe.id);
+                for ingredient in &rate.recipe.ingredients {
+                    if seen_items.insert(ingredient.item) {
+                        pending.push(ingredient.item);
+                    }
+                }
+            }
+        }
+        recipes
+    }
+
+    /// Create a group containing the buildings which make the target, named after it. The rate
+    /// is shown in the name as `rate_label`, so the caller can format it for display.
+    pub fn build_group(&self, database: &Database, rate_label: &str) -> Node {
+        let net = BTreeMap::from([(self.item, -self.rate)]);
+        let children = plan_buildings(net, self, database);
+        let name = match database.get(self.item) {
+            Some(item) => format!("{rate_label} {}", item.name),
+            None => "Planned Production".to_owned(),
+        };
+        Group {
+            name: name.into(),
+            children,
+            ..Group::empty()
+        }
+        .into()
+    }
+}
+
+impl RecipeChooser for PlanTarget {
+    /// Uses the allowed recipe that produces the most of the item, avoiding circular recipes.
+    fn choose_recipe<'db>(&self, item: ItemId, database: &'db Database) -> Option<&'db Recipe> {
+        choose_allowed_recipe(item, database, |recipe| {
+            self.allowed_recipes.contains(&recipe.id)
+        })
+    }
+
+    fn machine_for<'db>(
+        &self,
+        recipe: &Recipe,
+        database: &'db Database,
+    ) -> Option<&'db BuildingType> {
+        first_machine(recipe, database)
+    }
+}
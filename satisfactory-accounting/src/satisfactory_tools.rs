@@ -0,0 +1,307 @@
+//! Conversion between groups and the production plan format used by the Satisfactory Tools
+//! production planner.
+//!
+//! Satisfactory Tools plans are requests to its solver rather than lists of buildings: they
+//! specify which items to produce, which items are supplied from elsewhere, and which alternate
+//! recipes may be used. A group is exported as a plan to produce its net outputs from its net
+//! inputs using the recipes it already uses. Imported plans are turned back into buildings by
+//! working backwards from each requested item through the allowed recipes.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::accounting::{BuildingSettings, Group, Node};
+use crate::analysis::map_resource_limits;
+use crate::database::{BuildingType, Database, ItemId, Recipe};
+use crate::planner::{plan_buildings, RecipeChooser};
+
+/// Schema version of the production plan format written by the exporter.
+const SCHEMA_VERSION: u32 = 1;
+
+/// Game version written into exported plans.
+const GAME_VERSION: &str = "1";
+
+/// Type of production item which produces a fixed amount per minute.
+const PER_MINUTE: &str = "perMinute";
+
+/// Errors which can occur when reading production plans.
+#[derive(Error, Debug)]
+pub enum ReadPlanError {
+    /// The file isn't valid JSON in the expected format.
+    #[error("Unable to parse production plan: {0}")]
+    Parse(#[from] serde_json::Error),
+    /// The file parsed but had no production plans in it.
+    #[error("The file does not contain any production plans")]
+    Empty,
+}
+
+/// Satisfactory Tools exports either a single tab or a list of tabs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PlanFile {
+    Single(Box<ProductionData>),
+    Multiple(Vec<ProductionData>),
+}
+
+/// Result of converting a production plan to buildings.
+#[derive(Debug, Clone)]
+pub struct ImportedPlan {
+    /// Group containing the buildings for the plan.
+    pub group: Node,
+    /// Requested items which could not be planned, e.g. because they are unknown or are
+    /// requested with a type other than a fixed rate per minute.
+    pub unsupported: Vec<String>,
+}
+
+/// A single production plan tab, as exported by Satisfactory Tools.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductionData {
+    /// Name and version information about the plan.
+    pub metadata: ProductionMetadata,
+    /// What the solver is asked to produce.
+    pub request: ProductionRequest,
+}
+
+/// Metadata about a production plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductionMetadata {
+    /// Name of the tab.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Icon shown for the tab.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Version of the production plan format.
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Version of the game the plan was made for.
+    #[serde(default)]
+    pub game_version: String,
+}
+
+/// Settings for the production planner's solver.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProductionRequest {
+    /// Alternate recipes the solver may use, by recipe class name.
+    pub allowed_alternate_recipes: Vec<String>,
+    /// Standard recipes the solver may not use.
+    pub blocked_recipes: Vec<String>,
+    /// Buildings the solver may not use.
+    pub blocked_machines: Vec<String>,
+    /// Raw resources the solver may not use.
+    pub blocked_resources: Vec<String>,
+    /// Items which may be sent to the sink.
+    pub sinkable_resources: Vec<String>,
+    /// Items to produce.
+    pub production: Vec<ProductionItem>,
+    /// Items supplied from outside the plan.
+    pub input: Vec<ProductionInput>,
+    /// Most of each raw resource the solver may use, per minute.
+    pub resource_max: BTreeMap<String, f32>,
+    /// Relative cost of each raw resource used by the solver.
+    pub resource_weight: BTreeMap<String, f32>,
+}
+
+/// An item the production plan should produce.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductionItem {
+    /// Class name of the item.
+    pub item: Option<String>,
+    /// How the amount is interpreted, usually "perMinute".
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Amount of the item to produce.
+    pub amount: f32,
+    /// Share of the resources given to this item when maximizing.
+    #[serde(default = "default_ratio")]
+    pub ratio: f32,
+}
+
+/// An item supplied to the production plan from outside.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductionInput {
+    /// Class name of the item.
+    pub item: Option<String>,
+    /// Amount of the item supplied per minute.
+    pub amount: f32,
+}
+
+fn default_ratio() -> f32 {
+    100.0
+}
+
+/// Read the production plans in a file exported from Satisfactory Tools.
+pub fn read_plans(data: &[u8]) -> Result<Vec<ProductionData>, ReadPlanError> {
+    let plans = match serde_json::from_slice(data)? {
+        PlanFile::Single(plan) => vec![*plan],
+        PlanFile::Multiple(plans) => plans,
+    };
+    if plans.is_empty() {
+        return Err(ReadPlanError::Empty);
+    }
+    Ok(plans)
+}
+
+impl ProductionData {
+    /// Create a production plan which produces the net outputs of the given node from its net
+    /// inputs. Raw resources extracted within the node are left for the solver to supply.
+    pub fn from_node(node: &Node, database: &Database) -> Self {
+        let name = node.group().map(|group| group.name.to_string());
+
+        let mut production = Vec::new();
+        let mut input = Vec::new();
+        for (&item, &rate) in &node.balance().balances {
+            if rate > 0.0 {
+                production.push(ProductionItem {
+                    item: Some(item.to_string()),
+                    kind: PER_MINUTE.to_owned(),
+                    amount: rate,
+                    ratio: default_ratio(),
+                });
+            } else if rate < 0.0 {
+                input.push(ProductionInput {
+                    item: Some(item.to_string()),
+                    amount: -rate,
+                });
+            }
+        }
+
+        let alternates: BTreeSet<String> = node
+            .iter()
+            .filter_map(|child| match &child.building()?.settings {
+                BuildingSettings::Manufacturer(settings) => settings.recipe,
+                _ => None,
+            })
+            .filter(|&recipe| {
+                database
+                    .get(recipe)
+                    .is_some_and(|recipe| recipe.is_alternate)
+            })
+            .map(|recipe| recipe.to_string())
+            .collect();
+
+        // Weight each resource by how scarce it is relative to the most abundant one, which
+        // matches the default weighting Satisfactory Tools uses.
+        let limits: BTreeMap<String, f32> = map_resource_limits(database)
+            .map(|(id, limit)| (id.to_string(), limit))
+            .collect();
+        let most = limits.values().copied().fold(0.0, f32::max);
+        let resource_weight = limits
+            .iter()
+            .map(|(id, &limit)| (id.clone(), (most / limit * 100.0).round() / 100.0))
+            .collect();
+
+        Self {
+            metadata: ProductionMetadata {
+                name,
+                icon: None,
+                schema_version: SCHEMA_VERSION,
+                game_version: GAME_VERSION.to_owned(),
+            },
+            request: ProductionRequest {
+                allowed_alternate_recipes: alternates.into_iter().collect(),
+                production,
+                input,
+                resource_max: limits,
+                resource_weight,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Convert this plan to a group of buildings which produces the requested items.
+    ///
+    /// Each item is made with the allowed alternate recipe that produces the most of it, or else
+    /// the standard recipe that produces the most of it. Inputs are used before anything is
+    /// built, byproducts are credited towards later needs, and raw resources are left as deficits
+    /// in the group for the user to supply.
+    pub fn build_group(&self, database: &Database) -> ImportedPlan {
+        let request = &self.request;
+        let mut unsupported = Vec::new();
+        let mut net: BTreeMap<ItemId, f32> = BTreeMap::new();
+        for target in &request.production {
+            let known = target
+                .item
+                .as_deref()
+                .map(ItemId::from)
+                .filter(|&item| database.get(item).is_some());
+            match known {
+                Some(item) if target.kind == PER_MINUTE && target.amount > 0.0 => {
+                    *net.entry(item).or_default() -= target.amount;
+                }
+                Some(_) if target.amount <= 0.0 => {}
+                _ => unsupported.push(target.item.clone().unwrap_or_default()),
+            }
+        }
+        for input in &request.input {
+            if let Some(item) = input.item.as_deref() {
+                *net.entry(ItemId::from(item)).or_default() += input.amount;
+            }
+        }
+
+        let children = plan_buildings(net, self, database);
+        let name = self
+            .metadata
+            .name
+            .clone()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "Imported Plan".to_owned());
+        ImportedPlan {
+            group: Group {
+                name: name.into(),
+                children,
+                ..Group::empty()
+            }
+            .into(),
+            unsupported,
+        }
+    }
+}
+
+impl RecipeChooser for ProductionData {
+    /// Choose the recipe used to make the given item, or None if it is a raw resource or no
+    /// allowed recipe makes it.
+    fn choose_recipe<'db>(&self, item: ItemId, database: &'db Database) -> Option<&'db Recipe> {
+        if database
+            .get(item)
+            .is_some_and(|item| !item.mined_by.is_empty())
+        {
+            return None;
+        }
+        let request = &self.request;
+        let recipes = database.recipes_producing(item);
+        let allowed = |alternate: bool| {
+            recipes
+                .iter()
+                .filter(move |rate| rate.recipe.is_alternate == alternate)
+                .filter(|rate| {
+                    let id = rate.recipe.id.to_string();
+                    if alternate {
+                        request.allowed_alternate_recipes.contains(&id)
+                    } else {
+                        !request.blocked_recipes.contains(&id)
+                    }
+                })
+                .find(|rate| self.machine_for(rate.recipe, database).is_some())
+                .map(|rate| rate.recipe)
+        };
+        allowed(true).or_else(|| allowed(false))
+    }
+
+    /// Find a building which isn't blocked and can run the given recipe.
+    fn machine_for<'db>(
+        &self,
+        recipe: &Recipe,
+        database: &'db Database,
+    ) -> Option<&'db BuildingType> {
+        recipe
+            .produced_in
+            .iter()
+            .filter(|id| !self.request.blocked_machines.contains(&id.to_string()))
+            .find_map(|&id| database.get(id))
+    }
+}
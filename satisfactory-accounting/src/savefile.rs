@@ -0,0 +1,123 @@
+//! Reading and writing the world files downloaded from the app.
+//!
+//! A world file holds the tree of groups and buildings along with the choice of database, plus
+//! notes, targets, and other settings which only the app uses. [`SaveFile`] gives typed access to
+//! the parts needed to work with the tree, and keeps everything else as-is so a file can be
+//! changed and written back without losing any of it.
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::accounting::{Group, Node};
+use crate::database::{Database, DatabaseVersion};
+
+/// Version of the world model written by this version of Satisfactory Accounting. Files with any
+/// other model version can't be read.
+pub const MODEL_VERSION: &str = "v1.2.*";
+
+/// The choice of database for a particular world.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DatabaseChoice {
+    /// Always use the latest database version.
+    #[default]
+    Latest,
+    /// Use one of the standard databases.
+    Standard(DatabaseVersion),
+    /// This world uses a custom database.
+    Custom(Database),
+}
+
+impl DatabaseChoice {
+    /// Get the database for this database choice, or None if the chosen version isn't built in
+    /// and hasn't been fetched yet with [`DatabaseVersion::store_fetched`].
+    pub fn try_load_database(&self) -> Option<Database> {
+        match *self {
+            DatabaseChoice::Latest => Some(Database::load_latest()),
+            DatabaseChoice::Standard(version) => version.try_load_database(),
+            DatabaseChoice::Custom(ref db) => Some(db.clone()),
+        }
+    }
+}
+
+impl implicit_clone::ImplicitClone for DatabaseChoice {}
+
+impl From<DatabaseVersion> for DatabaseChoice {
+    fn from(value: DatabaseVersion) -> Self {
+        Self::Standard(value)
+    }
+}
+
+/// Errors which can occur when reading a world file.
+#[derive(Debug, Error)]
+pub enum SaveFileError {
+    /// The file isn't valid JSON in the world file format.
+    #[error("Unable to parse world file: {0}")]
+    Parse(#[from] serde_json::Error),
+    /// The file was written with a world model version which isn't supported.
+    #[error("World model version {0} is not supported")]
+    UnsupportedVersion(String),
+    /// The file doesn't say which world model version it was written with.
+    #[error("The world file does not have a model version")]
+    MissingVersion,
+}
+
+/// A world file, as downloaded from the app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveFile {
+    /// Version of the world model the file was written with. Always [`MODEL_VERSION`] for files
+    /// which were read successfully.
+    pub model_version: String,
+    /// Opaque id of the world, if the file has one. The app uses it to tell whether an uploaded
+    /// file replaces an existing world.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_id: Option<String>,
+    /// Which database the world uses.
+    pub database: DatabaseChoice,
+    /// Root node of the world. Balances stored in the file may be out of date, so
+    /// [`SaveFile::rebuild`] should be used before reading them.
+    pub root: Node,
+    /// Everything else in the file, such as notes and settings, kept so that it is written back
+    /// unchanged.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl SaveFile {
+    /// Create a save file for a new world with an empty root group.
+    pub fn new(database: DatabaseChoice) -> Self {
+        Self {
+            model_version: MODEL_VERSION.to_owned(),
+            world_id: None,
+            database,
+            root: Group::empty_node(),
+            other: Map::new(),
+        }
+    }
+
+    /// Read a world file from JSON, checking that its model version is supported.
+    pub fn from_json(json: &str) -> Result<Self, SaveFileError> {
+        /// Just the model version, checked before parsing the rest so that files from other
+        /// versions give a clear error rather than a parse error.
+        #[derive(Deserialize)]
+        struct Version {
+            #[serde(default)]
+            model_version: Option<String>,
+        }
+        match serde_json::from_str::<Version>(json)?.model_version {
+            Some(version) if version == MODEL_VERSION => Ok(serde_json::from_str(json)?),
+            Some(version) => Err(SaveFileError::UnsupportedVersion(version)),
+            None => Err(SaveFileError::MissingVersion),
+        }
+    }
+
+    /// Write this world file as JSON in the format the app reads.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("World files can always be serialized")
+    }
+
+    /// Recompute the balances of the whole tree with the given database, which should usually be
+    /// the one from [`DatabaseChoice::try_load_database`].
+    pub fn rebuild(&mut self, database: &Database) {
+        self.root = self.root.rebuild(database);
+    }
+}
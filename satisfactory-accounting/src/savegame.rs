@@ -0,0 +1,759 @@
+//! Reading Satisfactory game saves (.sav files) to account for what has actually been built.
+//!
+//! Only the parts of the save needed to find production buildings are decoded. Everything else,
+//! including most object properties, is skipped using the sizes recorded in the save.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use thiserror::Error;
+
+use crate::accounting::{BuildNode, Building, BuildingSettings, Group, Node};
+use crate::database::{BuildingId, BuildingKind, Database, ItemId, RecipeId};
+
+/// Oldest save version which can be read, which is the version used by Satisfactory 1.0.
+const MIN_SAVE_VERSION: i32 = 46;
+
+/// Oldest save header version which can be read.
+const MIN_HEADER_VERSION: i32 = 13;
+
+/// Tag which starts each compressed chunk of the save body.
+const PACKAGE_FILE_TAG: u32 = 0x9E2A83C1;
+
+/// Marker following the chunk tag in the newer chunk header format.
+const ARCHIVE_V2_HEADER: u32 = 0x22222222;
+
+/// Buildings within this distance of each other, in centimeters, are placed in the same factory.
+const FACTORY_RADIUS: f32 = 5_000.0;
+
+/// Class of the objects which record which buildings are connected to each power circuit.
+const POWER_CIRCUIT_CLASS: &str = "/Script/FactoryGame.FGPowerCircuit";
+
+/// Name of the group extractors are put in when their resource node couldn't be read.
+const NEEDS_SETUP_GROUP: &str = "Extractors Needing Setup";
+
+/// Errors which can occur when reading a save.
+#[derive(Debug, Error)]
+pub enum SaveGameError {
+    /// The save ended before all of its data was read.
+    #[error("The save file ended unexpectedly")]
+    Truncated,
+    /// The save was made by a version of the game which isn't supported.
+    #[error(
+        "Save version {0} is not supported. Only saves from Satisfactory 1.0 or later can be read"
+    )]
+    UnsupportedVersion(i32),
+    /// A string in the save isn't valid.
+    #[error("The save file contains a string which could not be read")]
+    InvalidString,
+    /// A compressed chunk of the save couldn't be decompressed.
+    #[error("Unable to decompress the save data: {0}")]
+    Decompress(String),
+    /// The decompressed save data doesn't have the expected structure.
+    #[error("The save data is not in the expected format: {0}")]
+    Invalid(&'static str),
+}
+
+/// The production buildings found in a save.
+#[derive(Debug, Clone)]
+pub struct SaveGame {
+    /// Name of the session the save belongs to.
+    pub session_name: String,
+    /// Buildings found in the save which exist in the database.
+    pub buildings: Vec<SavedBuilding>,
+}
+
+/// A building found in a save.
+#[derive(Debug, Clone)]
+pub struct SavedBuilding {
+    /// Database ID of the building.
+    pub building: BuildingId,
+    /// Position of the building in the world, in centimeters.
+    pub position: [f32; 3],
+    /// Recipe the building was set to, if any.
+    pub recipe: Option<RecipeId>,
+    /// Fuel the building was burning, if any.
+    pub fuel: Option<ItemId>,
+    /// Clock speed of the building.
+    pub clock_speed: f32,
+    /// ID of the power circuit the building is connected to, if any.
+    pub circuit: Option<i32>,
+}
+
+/// How to split the buildings of a save into groups when building a tree from them.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum SaveGrouping {
+    /// One group for each cluster of nearby buildings.
+    #[default]
+    Proximity,
+    /// One group for each power circuit.
+    PowerCircuit,
+}
+
+impl SaveGame {
+    /// Read the buildings from a save which are known to the given database.
+    ///
+    /// Stations are skipped, since the save doesn't record enough to account for them.
+    pub fn read(data: &[u8], database: &Database) -> Result<Self, SaveGameError> {
+        let mut header = Reader::new(data);
+        let header_version = header.i32()?;
+        let save_version = header.i32()?;
+        let _build_version = header.i32()?;
+        if header_version < MIN_HEADER_VERSION || save_version < MIN_SAVE_VERSION {
+            return Err(SaveGameError::UnsupportedVersion(save_version));
+        }
+        if header_version >= 14 {
+            let _save_name = header.string()?;
+        }
+        let _map_name = header.string()?;
+        let _map_options = header.string()?;
+        let session_name = header.string()?;
+
+        // The rest of the header varies more between versions and isn't needed, so skip straight
+        // to the first compressed chunk.
+        let body_start = find_first_chunk(data, header.pos).ok_or(SaveGameError::Truncated)?;
+        let body = decompress_body(Reader::new(&data[body_start..]))?;
+
+        let is_wanted = |class: &str| {
+            let id = building_id(class)?;
+            match &database.get(id)?.kind {
+                BuildingKind::Station(_) => None,
+                _ => Some(id),
+            }
+        };
+        let buildings = read_body(Reader::new(&body), &is_wanted)?;
+        Ok(Self {
+            session_name,
+            buildings,
+        })
+    }
+
+    /// Build a node tree from the buildings, grouped as chosen.
+    ///
+    /// Identical buildings in the same group are combined into a single node with copies. All
+    /// buildings are marked as built. The save doesn't record which resource a node provides or
+    /// its purity, so extractors are put in a nested group to show they still need to be set up.
+    pub fn build_tree(&self, database: &Database, grouping: SaveGrouping) -> Node {
+        let children = match grouping {
+            SaveGrouping::Proximity => {
+                let mut clusters = cluster_by_proximity(&self.buildings);
+                clusters.sort_by_key(|cluster| Reverse(cluster.len()));
+                let mut name_counts: HashMap<String, u32> = HashMap::new();
+                clusters
+                    .into_iter()
+                    .map(|cluster| {
+                        let group = self.build_group(&cluster, database);
+                        let base_name = factory_name(&group, database);
+                        let count = name_counts.entry(base_name.clone()).or_default();
+                        *count += 1;
+                        let name = match *count {
+                            1 => base_name,
+                            n => format!("{base_name} {n}"),
+                        };
+                        with_name(group, name)
+                    })
+                    .collect()
+            }
+            SaveGrouping::PowerCircuit => {
+                let mut circuits: BTreeMap<Option<i32>, Vec<usize>> = BTreeMap::new();
+                for (i, building) in self.buildings.iter().enumerate() {
+                    circuits.entry(building.circuit).or_default().push(i);
+                }
+                let mut circuits: Vec<_> = circuits.into_iter().collect();
+                // Largest circuits first, with unconnected buildings at the end.
+                circuits
+                    .sort_by_key(|(circuit, members)| (circuit.is_none(), Reverse(members.len())));
+                circuits
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (circuit, members))| {
+                        let group = self.build_group(&members, database);
+                        let name = match circuit {
+                            Some(_) => format!("Power Circuit {}", i + 1),
+                            None => "Not Connected to Power".to_owned(),
+                        };
+                        with_name(group, name)
+                    })
+                    .collect()
+            }
+        };
+        Group {
+            name: self.session_name.clone().into(),
+            children,
+            ..Group::empty()
+        }
+        .into()
+    }
+
+    /// Build an unnamed group from the buildings at the given indexes, combining identical
+    /// buildings.
+    fn build_group(&self, members: &[usize], database: &Database) -> Node {
+        let (extractors, others): (Vec<_>, Vec<_>) = members
+            .iter()
+            .map(|&i| &self.buildings[i])
+            .partition(|saved| saved.is_extractor(database));
+        let mut children = merge_buildings(others, database);
+        if !extractors.is_empty() {
+            children.push(
+                Group {
+                    name: NEEDS_SETUP_GROUP.into(),
+                    children: merge_buildings(extractors, database),
+                    ..Group::empty()
+                }
+                .into(),
+            );
+        }
+        Group {
+            children,
+            ..Group::empty()
+        }
+        .into()
+    }
+}
+
+/// Build nodes for saved buildings, combining identical buildings into a single node with copies.
+fn merge_buildings(saved: Vec<&SavedBuilding>, database: &Database) -> Vec<Node> {
+    let mut merged: Vec<Building> = Vec::new();
+    for building in saved.into_iter().map(|saved| saved.to_building(database)) {
+        match merged.iter_mut().find(|existing| {
+            existing.building == building.building && existing.settings == building.settings
+        }) {
+            Some(existing) => existing.copies += 1.0,
+            None => merged.push(building),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|building| {
+            building
+                .clone()
+                .build_node(database)
+                .unwrap_or_else(|e| e.into_warning_node(building))
+        })
+        .collect()
+}
+
+/// Give the group in a node a name.
+fn with_name(node: Node, name: String) -> Node {
+    let mut group = node.group().expect("built as a group").clone();
+    group.name = name.into();
+    group.into()
+}
+
+impl SavedBuilding {
+    /// Whether this building extracts resources from a node, which the save doesn't describe.
+    fn is_extractor(&self, database: &Database) -> bool {
+        database.get(self.building).is_some_and(|building| {
+            matches!(
+                building.kind,
+                BuildingKind::Miner(_) | BuildingKind::Pump(_)
+            )
+        })
+    }
+
+    /// Get the accounting settings for this building.
+    fn to_building(&self, database: &Database) -> Building {
+        let Some(building_type) = database.get(self.building) else {
+            return Building {
+                building: Some(self.building),
+                built: true,
+                ..Building::empty()
+            };
+        };
+        let mut settings = building_type.get_default_settings();
+        if building_type.overclockable() {
+            // Round off float noise so buildings at the same clock are combined.
+            settings.set_clock_speed((self.clock_speed * 10_000.0).round() / 10_000.0);
+        }
+        match &mut settings {
+            BuildingSettings::Manufacturer(settings) if self.recipe.is_some() => {
+                settings.recipe = self.recipe;
+            }
+            BuildingSettings::Generator(settings) if self.fuel.is_some() => {
+                settings.fuel = self.fuel;
+            }
+            _ => {}
+        }
+        Building {
+            building: Some(self.building),
+            settings,
+            copies: 1.0,
+            built: true,
+            ..Building::empty()
+        }
+    }
+}
+
+/// Name a factory after the item it has the largest surplus of, or after power if it produces
+/// no items.
+fn factory_name(node: &Node, database: &Database) -> String {
+    let balance = node.balance();
+    let main_product = balance
+        .balances
+        .iter()
+        .filter(|(_, &rate)| rate > 0.0)
+        .max_by(|lhs, rhs| lhs.1.total_cmp(rhs.1))
+        .and_then(|(&item, _)| database.get(item));
+    match main_product {
+        Some(item) => format!("{} Factory", item.name),
+        None if balance.power > 0.0 => "Power Plant".to_owned(),
+        None => "Factory".to_owned(),
+    }
+}
+
+/// Group the buildings into clusters where each building is within [`FACTORY_RADIUS`] of at
+/// least one other building in the cluster. Returns the indexes of the buildings in each cluster.
+fn cluster_by_proximity(buildings: &[SavedBuilding]) -> Vec<Vec<usize>> {
+    let cell_of = |position: [f32; 3]| position.map(|axis| (axis / FACTORY_RADIUS).floor() as i32);
+    let mut cells: HashMap<[i32; 3], Vec<usize>> = HashMap::new();
+    let mut parents: Vec<usize> = (0..buildings.len()).collect();
+    for (i, building) in buildings.iter().enumerate() {
+        let cell = cell_of(building.position);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let neighbor = [cell[0] + dx, cell[1] + dy, cell[2] + dz];
+                    for &j in cells.get(&neighbor).into_iter().flatten() {
+                        let distance_squared: f32 = (0..3)
+                            .map(|axis| building.position[axis] - buildings[j].position[axis])
+                            .map(|delta| delta * delta)
+                            .sum();
+                        if distance_squared <= FACTORY_RADIUS * FACTORY_RADIUS {
+                            let (root_i, root_j) =
+                                (find_root(&mut parents, i), find_root(&mut parents, j));
+                            parents[root_i] = root_j;
+                        }
+                    }
+                }
+            }
+        }
+        cells.entry(cell).or_default().push(i);
+    }
+
+    let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..buildings.len() {
+        let root = find_root(&mut parents, i);
+        clusters.entry(root).or_default().push(i);
+    }
+    let mut clusters: Vec<_> = clusters.into_values().collect();
+    // Keep the order stable between imports of the same save.
+    clusters.sort_by_key(|cluster| cluster[0]);
+    clusters
+}
+
+/// Find the root of the set containing `i`, compressing the path along the way.
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Get the database ID of the building with the given class path, e.g.
+/// `/Game/FactoryGame/Buildable/Factory/SmelterMk1/Build_SmelterMk1.Build_SmelterMk1_C` is
+/// `Desc_SmelterMk1_C`.
+fn building_id(class: &str) -> Option<BuildingId> {
+    let name = class.rsplit('.').next()?.strip_prefix("Build_")?;
+    Some(format!("Desc_{name}").into())
+}
+
+/// Get the object name at the end of an object path, which is the ID used by the database for
+/// recipes and items.
+fn object_name(path: &str) -> Option<&str> {
+    path.rsplit('.').next().filter(|name| !name.is_empty())
+}
+
+/// Find the offset of the first compressed chunk at or after `start`.
+fn find_first_chunk(data: &[u8], start: usize) -> Option<usize> {
+    let tag = PACKAGE_FILE_TAG.to_le_bytes();
+    let v2 = ARCHIVE_V2_HEADER.to_le_bytes();
+    (start..data.len().saturating_sub(8)).find(|&i| {
+        data[i..i + 4] == tag && (data[i + 4..i + 8] == v2 || data[i + 4..i + 8] == [0; 4])
+    })
+}
+
+/// Decompress and concatenate all of the compressed chunks which make up the save body.
+fn decompress_body(mut reader: Reader) -> Result<Vec<u8>, SaveGameError> {
+    let mut body = Vec::new();
+    while reader.remaining() > 0 {
+        if reader.u32()? != PACKAGE_FILE_TAG {
+            return Err(SaveGameError::Invalid("missing compressed chunk tag"));
+        }
+        if reader.u32()? == ARCHIVE_V2_HEADER {
+            let _max_chunk_size = reader.i64()?;
+            let _compression_algorithm = reader.u8()?;
+        } else {
+            let _max_chunk_size = reader.i64()?;
+        }
+        let compressed_size = reader.size64()?;
+        let _uncompressed_size = reader.i64()?;
+        let _compressed_size = reader.i64()?;
+        let _uncompressed_size = reader.i64()?;
+        let chunk = reader.bytes(compressed_size)?;
+        let decompressed = decompress_to_vec_zlib(chunk)
+            .map_err(|e| SaveGameError::Decompress(format!("{:?}", e.status)))?;
+        body.extend_from_slice(&decompressed);
+    }
+    Ok(body)
+}
+
+/// Read the wanted buildings out of the decompressed save body.
+fn read_body(
+    mut reader: Reader,
+    is_wanted: &impl Fn(&str) -> Option<BuildingId>,
+) -> Result<Vec<SavedBuilding>, SaveGameError> {
+    let _body_size = reader.i64()?;
+
+    // Describes how the world is split into partition cells, which isn't needed.
+    let grid_count = reader.count()?;
+    for _ in 0..grid_count {
+        let _grid_name = reader.string()?;
+        let _cell_size = reader.i32()?;
+        let _grid_hash = reader.u32()?;
+        let cell_count = reader.count()?;
+        for _ in 0..cell_count {
+            let _cell_name = reader.string()?;
+            let _cell_hash = reader.u32()?;
+        }
+    }
+
+    let mut contents = LevelContents::default();
+    let sublevel_count = reader.count()?;
+    // The sublevels are followed by the persistent level, which has no name in the save.
+    for i in 0..=sublevel_count {
+        if i < sublevel_count {
+            let _level_name = reader.string()?;
+        }
+        read_level(&mut reader, is_wanted, &mut contents)?;
+    }
+
+    // Circuits list the power connection components of their buildings, which are named after
+    // the building they belong to.
+    let mut circuit_of_actor: HashMap<&str, i32> = HashMap::new();
+    for (circuit, components) in &contents.circuits {
+        for component in components {
+            if let Some((actor, _)) = component.rsplit_once('.') {
+                circuit_of_actor.insert(actor, *circuit);
+            }
+        }
+    }
+    Ok(contents
+        .buildings
+        .iter()
+        .map(|(path_name, building)| SavedBuilding {
+            circuit: circuit_of_actor.get(path_name.as_str()).copied(),
+            ..building.clone()
+        })
+        .collect())
+}
+
+/// Everything wanted from the levels of a save.
+#[derive(Default)]
+struct LevelContents {
+    /// Wanted buildings, along with their path names.
+    buildings: Vec<(String, SavedBuilding)>,
+    /// ID of each power circuit along with the path names of the components connected to it.
+    circuits: Vec<(i32, Vec<String>)>,
+}
+
+/// Kind of object whose contents need to be read.
+enum WantedObject {
+    /// A wanted building.
+    Building(BuildingId),
+    /// A power circuit.
+    PowerCircuit,
+}
+
+/// Header of an object in a level, which comes before the contents of all objects.
+struct ObjectHeader {
+    /// What kind of object this is, if it needs to be read.
+    wanted: Option<WantedObject>,
+    /// Path name of the object.
+    path_name: String,
+    /// Whether this object is an actor, rather than a component.
+    is_actor: bool,
+    /// Position of the actor, if it is one.
+    position: [f32; 3],
+}
+
+/// Read a level, adding any wanted buildings and power circuits in it to `contents`.
+fn read_level(
+    reader: &mut Reader,
+    is_wanted: &impl Fn(&str) -> Option<BuildingId>,
+    contents: &mut LevelContents,
+) -> Result<(), SaveGameError> {
+    let headers_size = reader.size64()?;
+    let headers_end = reader.offset_by(headers_size)?;
+    let object_count = reader.count()?;
+    let mut headers = Vec::with_capacity(object_count.min(reader.remaining()));
+    for _ in 0..object_count {
+        let is_actor = reader.i32()? == 1;
+        let class = reader.string()?;
+        let _level_name = reader.string()?;
+        let path_name = reader.string()?;
+        let mut position = [0.0; 3];
+        if is_actor {
+            let _need_transform = reader.i32()?;
+            let _rotation = [reader.f32()?, reader.f32()?, reader.f32()?, reader.f32()?];
+            position = [reader.f32()?, reader.f32()?, reader.f32()?];
+            let _scale = [reader.f32()?, reader.f32()?, reader.f32()?];
+            let _placed_in_level = reader.i32()?;
+        } else {
+            let _parent_actor = reader.string()?;
+        }
+        let wanted = if is_actor {
+            is_wanted(&class).map(WantedObject::Building)
+        } else if class == POWER_CIRCUIT_CLASS {
+            Some(WantedObject::PowerCircuit)
+        } else {
+            None
+        };
+        headers.push(ObjectHeader {
+            wanted,
+            path_name,
+            is_actor,
+            position,
+        });
+    }
+    // Anything left in the header section is a list of collected pickups, which isn't needed.
+    reader.seek(headers_end)?;
+
+    let contents_size = reader.size64()?;
+    let contents_end = reader.offset_by(contents_size)?;
+    if reader.count()? != headers.len() {
+        return Err(SaveGameError::Invalid(
+            "level object count doesn't match its headers",
+        ));
+    }
+    for header in headers {
+        let _object_version = reader.i32()?;
+        let _should_migrate = reader.i32()?;
+        let size = reader.count()?;
+        let object = reader.bytes(size)?;
+        let Some(wanted) = header.wanted else {
+            continue;
+        };
+        // Properties which can't be read are left at their defaults, since the object itself is
+        // still worth counting.
+        let properties =
+            read_properties(&mut Reader::new(object), header.is_actor).unwrap_or_default();
+        match wanted {
+            WantedObject::Building(building) => contents.buildings.push((
+                header.path_name,
+                SavedBuilding {
+                    building,
+                    position: header.position,
+                    recipe: properties.recipe.map(RecipeId::from),
+                    fuel: properties.fuel.map(ItemId::from),
+                    clock_speed: properties.clock_speed.unwrap_or(1.0),
+                    circuit: None,
+                },
+            )),
+            WantedObject::PowerCircuit => {
+                if let Some(circuit) = properties.circuit_id {
+                    contents.circuits.push((circuit, properties.components));
+                }
+            }
+        }
+    }
+    reader.seek(contents_end)?;
+
+    // Pickups collected in this level, which aren't needed.
+    let collected_count = reader.count()?;
+    for _ in 0..collected_count {
+        let _level_name = reader.string()?;
+        let _path_name = reader.string()?;
+    }
+    Ok(())
+}
+
+/// Properties of an object which are used for accounting.
+#[derive(Default)]
+struct ObjectProperties {
+    /// Name of the recipe a building is set to.
+    recipe: Option<String>,
+    /// Name of the item a building burns as fuel.
+    fuel: Option<String>,
+    /// Clock speed of a building, if it isn't the default.
+    clock_speed: Option<f32>,
+    /// ID of a power circuit.
+    circuit_id: Option<i32>,
+    /// Path names of the components connected to a power circuit.
+    components: Vec<String>,
+}
+
+/// Read the properties used for accounting from the contents of an object.
+fn read_properties(reader: &mut Reader, is_actor: bool) -> Result<ObjectProperties, SaveGameError> {
+    if is_actor {
+        let _parent_level = reader.string()?;
+        let _parent_path = reader.string()?;
+        let component_count = reader.count()?;
+        for _ in 0..component_count {
+            let _level_name = reader.string()?;
+            let _path_name = reader.string()?;
+        }
+    }
+
+    let mut properties = ObjectProperties::default();
+    loop {
+        let name = reader.string()?;
+        if name == "None" {
+            return Ok(properties);
+        }
+        let property_type = reader.string()?;
+        let size = reader.count()?;
+        let _index = reader.i32()?;
+        let mut inner_type = None;
+        match property_type.as_str() {
+            "BoolProperty" => {
+                let _value = reader.u8()?;
+            }
+            "ByteProperty" | "EnumProperty" | "ArrayProperty" | "SetProperty" => {
+                inner_type = Some(reader.string()?);
+            }
+            "StructProperty" => {
+                let _struct_type = reader.string()?;
+                let _struct_guid = reader.bytes(16)?;
+            }
+            "MapProperty" => {
+                let _key_type = reader.string()?;
+                let _value_type = reader.string()?;
+            }
+            _ => {}
+        }
+        if reader.u8()? != 0 {
+            let _property_guid = reader.bytes(16)?;
+        }
+        let mut value = Reader::new(reader.bytes(size)?);
+        match (name.as_str(), property_type.as_str()) {
+            ("mCurrentRecipe", "ObjectProperty") => {
+                let _level_name = value.string()?;
+                properties.recipe = object_name(&value.string()?).map(str::to_owned);
+            }
+            ("mCurrentFuelClass", "ObjectProperty") => {
+                let _level_name = value.string()?;
+                properties.fuel = object_name(&value.string()?).map(str::to_owned);
+            }
+            ("mCurrentPotential", "FloatProperty") => {
+                properties.clock_speed = Some(value.f32()?);
+            }
+            ("mCircuitID", "IntProperty") => {
+                properties.circuit_id = Some(value.i32()?);
+            }
+            ("mComponents", "ArrayProperty") if inner_type.as_deref() == Some("ObjectProperty") => {
+                let count = value.count()?;
+                for _ in 0..count {
+                    let _level_name = value.string()?;
+                    properties.components.push(value.string()?);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reads little-endian values from a byte slice.
+struct Reader<'a> {
+    /// Data being read.
+    data: &'a [u8],
+    /// Offset of the next byte to read.
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Create a reader starting at the beginning of the data.
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Number of bytes left to read.
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    /// Get the offset `len` bytes after the current one, without moving to it.
+    fn offset_by(&self, len: usize) -> Result<usize, SaveGameError> {
+        self.pos
+            .checked_add(len)
+            .ok_or(SaveGameError::Invalid("size is too large"))
+    }
+
+    /// Move to the given offset.
+    fn seek(&mut self, pos: usize) -> Result<(), SaveGameError> {
+        if pos > self.data.len() {
+            return Err(SaveGameError::Truncated);
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    /// Read the given number of bytes.
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], SaveGameError> {
+        if len > self.remaining() {
+            return Err(SaveGameError::Truncated);
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Read a fixed number of bytes.
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SaveGameError> {
+        Ok(self.bytes(N)?.try_into().expect("read exactly N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, SaveGameError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn i32(&mut self) -> Result<i32, SaveGameError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, SaveGameError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, SaveGameError> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, SaveGameError> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    /// Read a 32-bit count or size, which must not be negative.
+    fn count(&mut self) -> Result<usize, SaveGameError> {
+        usize::try_from(self.i32()?).map_err(|_| SaveGameError::Invalid("negative count"))
+    }
+
+    /// Read a 64-bit size, which must not be negative.
+    fn size64(&mut self) -> Result<usize, SaveGameError> {
+        usize::try_from(self.i64()?).map_err(|_| SaveGameError::Invalid("negative size"))
+    }
+
+    /// Read an Unreal FString, which is stored as a length including the null terminator
+    /// followed by either Latin-1 bytes, or UTF-16 code units if the length is negative.
+    fn string(&mut self) -> Result<String, SaveGameError> {
+        let len = self.i32()?;
+        if len == 0 {
+            return Ok(String::new());
+        }
+        if len > 0 {
+            let bytes = self.bytes(len as usize)?;
+            let (_, text) = bytes.split_last().ok_or(SaveGameError::InvalidString)?;
+            Ok(text.iter().map(|&byte| byte as char).collect())
+        } else {
+            let units = len.unsigned_abs() as usize;
+            let bytes = self.bytes(units.checked_mul(2).ok_or(SaveGameError::InvalidString)?)?;
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect();
+            let (_, text) = units.split_last().ok_or(SaveGameError::InvalidString)?;
+            String::from_utf16(text).map_err(|_| SaveGameError::InvalidString)
+        }
+    }
+}
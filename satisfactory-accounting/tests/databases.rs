@@ -0,0 +1,101 @@
+//! Loading each database version, from the binary files shipped for them or once fetched.
+use std::fs;
+use std::path::Path;
+
+use satisfactory_accounting::accounting::{BuildError, Building, Group, Node};
+use satisfactory_accounting::analysis::{find_diagnostics, DiagnosticKind};
+use satisfactory_accounting::database::{Database, DatabaseVersion};
+
+/// Read one of the database files next to this crate's manifest.
+fn read_db_file(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(name);
+    fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()))
+}
+
+#[test]
+fn every_version_loads_from_binary() {
+    for &version in DatabaseVersion::ALL {
+        if !version.is_embedded() {
+            version
+                .store_fetched(&read_db_file(version.file_name()))
+                .unwrap_or_else(|e| panic!("Failed to parse {}: {e}", version.file_name()));
+        }
+        let database = version
+            .try_load_database()
+            .unwrap_or_else(|| panic!("{version} did not load"));
+        assert!(
+            database.buildings().next().is_some(),
+            "{version} has no buildings"
+        );
+    }
+}
+
+#[test]
+fn binary_matches_json() {
+    for &version in DatabaseVersion::ALL {
+        let binary = Database::from_binary(&read_db_file(version.file_name()))
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {e}", version.file_name()));
+        let json_name = version.file_name().replace(".bin", ".json");
+        let json: Database = serde_json::from_slice(&read_db_file(&json_name))
+            .unwrap_or_else(|e| panic!("Failed to parse {json_name}: {e}"));
+        assert!(
+            binary.compare_ignore_prefix(&json),
+            "{} is out of date with {json_name}",
+            version.file_name()
+        );
+    }
+}
+
+/// A group pinned to a version which hasn't been fetched must not be computed with another
+/// database.
+#[test]
+fn unfetched_pinned_version_is_a_warning() {
+    let version = *DatabaseVersion::ALL
+        .iter()
+        .find(|version| !version.is_available())
+        .expect("Some versions are not built in");
+    let group: Node = Group {
+        database: Some(version),
+        ..Group::empty()
+    }
+    .into();
+    let rebuilt = group.rebuild(&Database::load_latest());
+    assert_eq!(
+        rebuilt.warning(),
+        Some(BuildError::DatabaseUnavailable(version))
+    );
+}
+
+/// Diagnostics report a group pinned to a version which hasn't been fetched, rather than checking
+/// its buildings against another database.
+#[test]
+fn unfetched_pinned_version_is_diagnosed() {
+    let version = *DatabaseVersion::ALL
+        .iter()
+        .find(|version| !version.is_available())
+        .expect("Some versions are not built in");
+    let db = Database::load_latest();
+    let pinned = Group {
+        name: "Pinned".into(),
+        database: Some(version),
+        children: vec![Building::empty_node()],
+        ..Group::empty()
+    };
+    let pinned_id = pinned.id;
+    let root: Node = Group {
+        children: vec![pinned.into()],
+        ..Group::empty()
+    }
+    .into();
+    let diagnostics = find_diagnostics(&root.rebuild(&db), &db, |_| false);
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.id, diagnostic.kind))
+            .collect::<Vec<_>>(),
+        [(
+            pinned_id,
+            DiagnosticKind::BuildError(BuildError::DatabaseUnavailable(version))
+        )]
+    );
+}
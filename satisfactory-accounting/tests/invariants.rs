@@ -0,0 +1,151 @@
+//! Properties of balances which should hold for any tree of groups and buildings.
+use std::collections::BTreeMap;
+
+use proptest::prelude::*;
+use satisfactory_accounting::accounting::{
+    Balance, BuildNode, Building, Group, ManufacturerSettings, Node, SplitCopies, MAX_CLOCK,
+    MIN_CLOCK,
+};
+use satisfactory_accounting::database::{BuildingKind, Database};
+
+thread_local! {
+    static DB: Database = Database::load_latest();
+    static MANUFACTURERS: Vec<Building> = DB.with(manufacturers);
+}
+
+/// Every manufacturer in the database paired with each recipe it can make.
+fn manufacturers(db: &Database) -> Vec<Building> {
+    db.buildings()
+        .filter_map(|building| match &building.kind {
+            BuildingKind::Manufacturer(m) => Some((building.id, m)),
+            _ => None,
+        })
+        .flat_map(|(id, m)| {
+            m.available_recipes.iter().map(move |&recipe| Building {
+                building: Some(id),
+                settings: ManufacturerSettings {
+                    recipe: Some(recipe),
+                    clock_speed: 1.0,
+                }
+                .into(),
+                ..Building::empty()
+            })
+        })
+        .collect()
+}
+
+/// Strategy for a manufacturer with any recipe, clock speed, and number of copies the building
+/// allows.
+fn building() -> impl Strategy<Value = Building> {
+    let count = MANUFACTURERS.with(Vec::len);
+    (0..count, MIN_CLOCK..=MAX_CLOCK, 0.0f32..20.0).prop_map(|(idx, clock, copies)| {
+        let mut building = MANUFACTURERS.with(|m| m[idx].create_copy());
+        let building_type = DB.with(|db| db.get(building.building.unwrap()).unwrap().clone());
+        building
+            .settings
+            .set_clock_speed(clock.min(building_type.max_clock_speed()));
+        building.copies = if building_type.overclockable() {
+            copies
+        } else {
+            copies.round()
+        };
+        building
+    })
+}
+
+/// Strategy for a tree of nested groups with buildings at any level.
+fn node() -> impl Strategy<Value = Node> {
+    let leaf = building().prop_map(|building| DB.with(|db| building.build_node(db).unwrap()));
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        (prop::collection::vec(inner, 0..8), 1u32..5).prop_map(|(children, copies)| {
+            Group {
+                children,
+                copies,
+                ..Group::empty()
+            }
+            .into()
+        })
+    })
+}
+
+/// Whether two balances are equal, allowing for rounding from summing in a different order.
+fn approx_eq(lhs: &Balance, rhs: &Balance) -> bool {
+    fn close(lhs: f32, rhs: f32) -> bool {
+        (lhs - rhs).abs() <= 1e-3 * lhs.abs().max(rhs.abs()).max(1.0)
+    }
+    let items: BTreeMap<_, _> = lhs
+        .balances
+        .keys()
+        .chain(rhs.balances.keys())
+        .map(|&item| {
+            let get = |b: &Balance| b.balances.get(&item).copied().unwrap_or(0.0);
+            (item, (get(lhs), get(rhs)))
+        })
+        .collect();
+    close(lhs.power, rhs.power) && items.values().all(|&(lhs, rhs)| close(lhs, rhs))
+}
+
+proptest! {
+    #[test]
+    fn group_balance_is_sum_of_children(node in node()) {
+        for node in node.iter() {
+            if let Some(group) = node.group() {
+                let sum: Balance = group.children.iter().map(Node::balance).sum();
+                prop_assert!(approx_eq(node.balance(), &(sum * group.copies as f32)));
+            }
+        }
+    }
+
+    #[test]
+    fn copy_keeps_balance(node in node()) {
+        let copy = node.create_copy();
+        prop_assert_eq!(copy.balance(), node.balance());
+        prop_assert_ne!(copy.id(), node.id());
+    }
+
+    #[test]
+    fn rebuild_keeps_balance(node in node()) {
+        let rebuilt = DB.with(|db| node.rebuild(db));
+        prop_assert!(approx_eq(rebuilt.balance(), node.balance()));
+    }
+
+    #[test]
+    fn share_unchanged_keeps_tree(node in node()) {
+        let copy = node.create_copy();
+        prop_assert_eq!(&node.share_unchanged(&copy), &node);
+        prop_assert!(node.share_unchanged(&node.clone()).ptr_eq(&node));
+    }
+
+    #[test]
+    fn split_copies_roundtrip(copies in 0.0f32..100.0, clock in MIN_CLOCK..=MAX_CLOCK) {
+        let split = SplitCopies::split(copies, clock);
+        prop_assert_eq!(split.whole_copies, copies.trunc());
+        // The last copy is only exact when its clock speed wasn't clamped to the minimum.
+        if copies.fract() * clock >= MIN_CLOCK {
+            let rejoined = split.whole_copies + split.last_clock / clock;
+            prop_assert!((rejoined - copies).abs() <= 1e-3 * copies.max(1.0));
+        } else if copies.fract() == 0.0 {
+            prop_assert_eq!(split.last_clock, 0.0);
+        }
+    }
+
+    /// Only whole factors are drawn: buildings which can't be overclocked are rounded to whole
+    /// copies, so a fractional factor like 2.42 on 15 copies builds 36 machines rather than
+    /// 36.3, which is intended rather than a scaling bug.
+    #[test]
+    fn item_rates_scale_with_copies(building in building(), factor in 1u8..10) {
+        let factor = factor as f32;
+        let scaled = Building {
+            copies: building.copies * factor,
+            ..building.clone()
+        };
+        let (base, scaled) = DB.with(|db| {
+            (building.build_node(db).unwrap(), scaled.build_node(db).unwrap())
+        });
+        let expected = Balance {
+            power: scaled.balance().power,
+            ..base.balance().clone() * factor
+        };
+        prop_assert!(approx_eq(scaled.balance(), &expected));
+    }
+}
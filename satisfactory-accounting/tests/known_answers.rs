@@ -0,0 +1,272 @@
+//! Results worked out by hand for small production chains, so a change which shifts what the
+//! planners build or what reports show is caught even when the result still looks plausible.
+//!
+//! With standard recipes, a Constructor makes 20 Iron Plate/min from 30 Iron Ingot/min and a
+//! Smelter makes 30 Iron Ingot/min from 30 Iron Ore/min, each using 4 MW.
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use satisfactory_accounting::accounting::{
+    BuildNode, Building, BuildingSettings, GeothermalSettings, Group, ManufacturerSettings, Node,
+};
+use satisfactory_accounting::analysis::{map_resource_limit, summarize_world};
+use satisfactory_accounting::csv::balances_csv;
+use satisfactory_accounting::database::{BuildingId, Database, ItemId, PurityCounts, RecipeId};
+use satisfactory_accounting::optimizer::{OptimizeGoal, OptimizeRequest};
+use satisfactory_accounting::planner::PlanTarget;
+use satisfactory_accounting::satisfactory_tools::read_plans;
+
+const CONSTRUCTOR: &str = "Desc_ConstructorMk1_C";
+const SMELTER: &str = "Desc_SmelterMk1_C";
+const IRON_PLATE: &str = "Desc_IronPlate_C";
+const IRON_INGOT: &str = "Desc_IronIngot_C";
+const IRON_ORE: &str = "Desc_OreIron_C";
+
+/// Buildings directly in a group, as (building, recipe, copies, clock speed).
+fn buildings(group: &Node) -> Vec<(BuildingId, RecipeId, f32, f32)> {
+    group
+        .children()
+        .map(|child| {
+            let building = child.building().expect("plans only contain buildings");
+            let BuildingSettings::Manufacturer(settings) = &building.settings else {
+                panic!("plans only contain manufacturers");
+            };
+            let recipe = settings.recipe.expect("plans set a recipe");
+            (
+                building.building.unwrap(),
+                recipe,
+                building.copies,
+                building.settings.clock_speed(),
+            )
+        })
+        .collect()
+}
+
+/// Balance of each item and power, rounded to hundredths so float noise doesn't fail the test.
+fn rounded_balance(node: &Node) -> (f32, BTreeMap<ItemId, f32>) {
+    let round = |rate: f32| (rate * 100.0).round() / 100.0;
+    let balance = node.balance();
+    (
+        round(balance.power),
+        balance
+            .balances
+            .iter()
+            .filter(|(_, &rate)| rate.abs() > 1e-3)
+            .map(|(&item, &rate)| (item, round(rate)))
+            .collect(),
+    )
+}
+
+/// The standard chain for 60 Iron Plate/min.
+fn iron_plate_chain() -> Vec<(BuildingId, RecipeId, f32, f32)> {
+    vec![
+        (CONSTRUCTOR.into(), "Recipe_IronPlate_C".into(), 3.0, 1.0),
+        (SMELTER.into(), "Recipe_IngotIron_C".into(), 3.0, 1.0),
+    ]
+}
+
+#[test]
+fn planner_makes_iron_plate_from_ore() {
+    let db = Database::load_latest();
+    let group = PlanTarget::new(IRON_PLATE.into(), 60.0, &db).build_group(&db, "60/min");
+    assert_eq!(group.group().unwrap().name.as_ref(), "60/min Iron Plate");
+    assert_eq!(buildings(&group), iron_plate_chain());
+    assert_eq!(
+        rounded_balance(&group),
+        (
+            -24.0,
+            BTreeMap::from([(IRON_PLATE.into(), 60.0), (IRON_ORE.into(), -90.0)])
+        )
+    );
+}
+
+#[test]
+fn optimizer_matches_planner_with_only_standard_recipes() {
+    let db = Database::load_latest();
+    let request = OptimizeRequest {
+        target: PlanTarget::new(IRON_PLATE.into(), 60.0, &db),
+        inputs: BTreeMap::new(),
+        goal: OptimizeGoal::RawResources,
+    };
+    let group = request.build_group(&db, "60/min").unwrap();
+    assert_eq!(
+        group.group().unwrap().name.as_ref(),
+        "Optimized 60/min Iron Plate"
+    );
+    let mut planned = buildings(&group);
+    planned.sort_by_key(|(building, ..)| *building);
+    assert_eq!(planned, iron_plate_chain());
+}
+
+#[test]
+fn world_summary_counts_machines_and_power() {
+    let db = Database::load_latest();
+    let plan = PlanTarget::new(IRON_PLATE.into(), 60.0, &db).build_group(&db, "60/min");
+    let root: Node = Group {
+        children: vec![plan],
+        copies: 2,
+        ..Group::empty()
+    }
+    .into();
+    let summary = summarize_world(&root);
+    assert_eq!(summary.power_consumed, 48.0);
+    assert_eq!(summary.power_produced, 0.0);
+    assert_eq!(
+        summary.machines,
+        BTreeMap::from([(CONSTRUCTOR.into(), 6), (SMELTER.into(), 6)])
+    );
+}
+
+/// A geyser cluster is one building standing for a generator on each geyser, so the dashboard must
+/// count it the same way build progress does. Geothermal generators are only in the databases from
+/// before 1.0.
+#[test]
+fn world_summary_counts_each_geothermal_generator() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("db-u7-initial.bin");
+    let db = Database::from_binary(&fs::read(path).unwrap()).unwrap();
+    let cluster = Building {
+        building: Some("Desc_GeneratorGeoThermal_C".into()),
+        settings: BuildingSettings::Geothermal(GeothermalSettings {
+            geysers: Some(PurityCounts {
+                impure: 1,
+                normal: 2,
+                pure: 0,
+            }),
+            ..Default::default()
+        }),
+        ..Building::empty()
+    };
+    let root: Node = Group {
+        children: vec![cluster.build_node(&db).unwrap()],
+        copies: 2,
+        ..Group::empty()
+    }
+    .into();
+    let summary = summarize_world(&root);
+    assert_eq!(
+        summary.machines,
+        BTreeMap::from([("Desc_GeneratorGeoThermal_C".into(), 6)])
+    );
+    assert_eq!(root.build_progress().planned, 6);
+}
+
+#[test]
+fn balances_csv_lists_each_group() {
+    let db = Database::load_latest();
+    let smelter = Building {
+        building: Some(SMELTER.into()),
+        settings: ManufacturerSettings {
+            recipe: Some("Recipe_IngotIron_C".into()),
+            clock_speed: 1.0,
+        }
+        .into(),
+        copies: 2.0,
+        ..Building::empty()
+    };
+    let smelters: Node = Group {
+        name: "Smelters".into(),
+        children: vec![smelter.build_node(&db).unwrap()],
+        ..Group::empty()
+    }
+    .into();
+    let root: Node = Group {
+        name: "Base".into(),
+        children: vec![smelters],
+        ..Group::empty()
+    }
+    .into();
+    assert_eq!(
+        balances_csv(&root, &db),
+        "Group,Item,Rate\r\n\
+        Base,Power (MW),-8\r\n\
+        Base,Iron Ingot,60\r\n\
+        Base,Iron Ore,-60\r\n\
+        Base / Smelters,Power (MW),-8\r\n\
+        Base / Smelters,Iron Ingot,60\r\n\
+        Base / Smelters,Iron Ore,-60\r\n"
+    );
+}
+
+#[test]
+fn satisfactory_tools_plan_builds_iron_plate_chain() {
+    let db = Database::load_latest();
+    let plans = read_plans(
+        br#"{
+            "metadata": {"name": "Plates", "schemaVersion": 1, "gameVersion": "1"},
+            "request": {
+                "production": [{"item": "Desc_IronPlate_C", "type": "perMinute", "amount": 60}],
+                "input": [{"item": "Desc_IronIngot_C", "amount": 30}]
+            }
+        }"#,
+    )
+    .unwrap();
+    let imported = plans[0].build_group(&db);
+    assert!(imported.unsupported.is_empty());
+    assert_eq!(imported.group.group().unwrap().name.as_ref(), "Plates");
+    // The 30 Iron Ingot/min supplied as an input leaves 60/min for two Smelters to make.
+    assert_eq!(
+        buildings(&imported.group),
+        [
+            (CONSTRUCTOR.into(), "Recipe_IronPlate_C".into(), 3.0, 1.0),
+            (SMELTER.into(), "Recipe_IngotIron_C".into(), 2.0, 1.0),
+        ]
+    );
+    assert_eq!(
+        rounded_balance(&imported.group),
+        (
+            -20.0,
+            BTreeMap::from([
+                (IRON_PLATE.into(), 60.0),
+                (IRON_INGOT.into(), -30.0),
+                (IRON_ORE.into(), -60.0),
+            ])
+        )
+    );
+}
+
+/// Pure Iron Ingot makes 65 Iron Ingot/min from 35 Iron Ore/min and 20 Water/min in a 30 MW
+/// Refinery, so it saves ore but uses far more power than three 4 MW Smelters.
+#[test]
+fn optimizer_goals_choose_different_recipes() {
+    let db = Database::load_latest();
+    let request = |goal| OptimizeRequest {
+        target: PlanTarget {
+            item: IRON_INGOT.into(),
+            rate: 65.0,
+            allowed_recipes: ["Recipe_IngotIron_C", "Recipe_Alternate_PureIronIngot_C"]
+                .into_iter()
+                .map(RecipeId::from)
+                .collect(),
+        },
+        inputs: BTreeMap::new(),
+        goal,
+    };
+    let recipes = |goal| -> Vec<RecipeId> {
+        let group = request(goal).build_group(&db, "65/min").unwrap();
+        buildings(&group)
+            .into_iter()
+            .map(|(_, recipe, ..)| recipe)
+            .collect()
+    };
+    assert_eq!(
+        recipes(OptimizeGoal::RawResources),
+        [RecipeId::from("Recipe_Alternate_PureIronIngot_C")]
+    );
+    assert_eq!(
+        recipes(OptimizeGoal::Power),
+        [RecipeId::from("Recipe_IngotIron_C")]
+    );
+}
+
+/// Map limits come from the node counts in the database: every node with a Miner Mk.3 or Oil
+/// Extractor and every well pad with a Resource Well Extractor, all at 250%.
+#[test]
+fn map_limits_use_database_node_counts() {
+    let db = Database::load_latest();
+    let limit = |item: &str| map_resource_limit(item.into(), &db);
+    assert_eq!(limit(IRON_ORE), Some(92_100.0));
+    assert_eq!(limit("Desc_LiquidOil_C"), Some(12_600.0));
+    assert_eq!(limit("Desc_NitrogenGas_C"), Some(12_000.0));
+    assert_eq!(limit("Desc_Water_C"), None);
+}
@@ -0,0 +1,96 @@
+//! Buildings saved before buildings had ids must get the same id every time they are loaded, so
+//! data kept outside the tree and keyed by id, like notes, survives reloading them.
+use std::collections::{BTreeMap, HashSet};
+
+use satisfactory_accounting::accounting::{Building, Group, Node};
+use satisfactory_accounting::database::Database;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// A world with buildings at the top level and in a nested group.
+fn world() -> Node {
+    let nested = Group {
+        name: "Nested".into(),
+        children: vec![Building::empty_node(), Building::empty_node()],
+        ..Group::empty()
+    };
+    Group {
+        children: vec![Building::empty_node(), nested.into()],
+        ..Group::empty()
+    }
+    .into()
+}
+
+/// Remove the ids of every building, as they were saved before buildings had ids.
+fn strip_building_ids(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Object(building)) = map.get_mut("Building") {
+                building.remove("id");
+            }
+            map.values_mut().for_each(strip_building_ids);
+        }
+        Value::Array(values) => values.iter_mut().for_each(strip_building_ids),
+        _ => {}
+    }
+}
+
+/// Ids of the buildings in a tree, in the order they are visited.
+fn building_ids(root: &Node) -> Vec<Uuid> {
+    root.iter()
+        .filter_map(|node| Some(node.building()?.id))
+        .collect()
+}
+
+#[test]
+fn legacy_building_ids_are_stable() {
+    let mut legacy = serde_json::to_value(world()).unwrap();
+    strip_building_ids(&mut legacy);
+
+    let first: Node = serde_json::from_value(legacy.clone()).unwrap();
+    let ids = building_ids(&first);
+    assert_eq!(ids.len(), 3);
+    assert!(ids.iter().all(|id| !id.is_nil()));
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+
+    // Add a note to each building. Saving only the notes leaves the stored tree as it was.
+    let mut notes: BTreeMap<Uuid, String> = ids
+        .iter()
+        .map(|id| (*id, format!("Note for {id}")))
+        .collect();
+
+    let reloaded: Node = serde_json::from_value(legacy).unwrap();
+    assert_eq!(building_ids(&reloaded), ids);
+    assert_eq!(
+        serde_json::to_value(&reloaded).unwrap(),
+        serde_json::to_value(&first).unwrap()
+    );
+
+    // Reloading prunes notes for anything not in the tree.
+    let used: HashSet<Uuid> = reloaded.iter().map(|node| node.id()).collect();
+    notes.retain(|id, _| used.contains(id));
+    assert_eq!(notes.len(), 3);
+
+    // Once the tree is saved again, the ids are stored along with it.
+    let resaved = serde_json::to_value(&reloaded).unwrap();
+    let from_resaved: Node = serde_json::from_value(resaved).unwrap();
+    assert_eq!(building_ids(&from_resaved), ids);
+}
+
+/// The app checks the stored world still matches the one in memory by loading it again and
+/// comparing them, so a world saved before buildings had ids must load the same way twice.
+#[test]
+fn legacy_world_matches_itself_when_loaded_again() {
+    let db = Database::load_latest();
+    let mut legacy = serde_json::to_value(world()).unwrap();
+    strip_building_ids(&mut legacy);
+
+    let in_memory = serde_json::from_value::<Node>(legacy.clone())
+        .unwrap()
+        .rebuild(&db);
+    let stored = serde_json::from_value::<Node>(legacy).unwrap().rebuild(&db);
+    assert_eq!(
+        serde_json::to_value(&stored).unwrap(),
+        serde_json::to_value(&in_memory).unwrap()
+    );
+}
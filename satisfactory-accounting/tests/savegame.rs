@@ -0,0 +1,327 @@
+//! Reading game saves built by hand, with the smallest header the reader accepts and one object
+//! of each kind it looks at.
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use satisfactory_accounting::accounting::{BuildingSettings, Node};
+use satisfactory_accounting::database::{Database, ItemId, RecipeId};
+use satisfactory_accounting::savegame::{SaveGame, SaveGameError, SaveGrouping};
+
+const LEVEL: &str = "Persistent_Level";
+const SMELTER: &str = "Persistent_Level:PersistentLevel.Build_SmelterMk1_C_1";
+const GENERATOR: &str = "Persistent_Level:PersistentLevel.Build_GeneratorCoal_C_2";
+const MINER: &str = "Persistent_Level:PersistentLevel.Build_MinerMk1_C_3";
+
+/// Writes values in the layout used by saves.
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn i32(&mut self, value: i32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn i64(&mut self, value: i64) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u8(&mut self, value: u8) -> &mut Self {
+        self.0.push(value);
+        self
+    }
+
+    fn f32(&mut self, value: f32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    /// Write a Latin-1 string with its null terminator.
+    fn string(&mut self, value: &str) -> &mut Self {
+        self.i32(value.len() as i32 + 1)
+            .bytes(value.as_bytes())
+            .u8(0)
+    }
+
+    /// Write a section preceded by its size as an i64.
+    fn sized64(&mut self, section: &Writer) -> &mut Self {
+        self.i64(section.0.len() as i64).bytes(&section.0)
+    }
+
+    /// Write a property with no type-specific header.
+    fn property(&mut self, name: &str, property_type: &str, value: &Writer) -> &mut Self {
+        self.string(name)
+            .string(property_type)
+            .i32(value.0.len() as i32)
+            .i32(0)
+            .u8(0)
+            .bytes(&value.0)
+    }
+
+    /// Write a reference to another object.
+    fn object_property(&mut self, name: &str, path: &str) -> &mut Self {
+        let mut value = Writer::default();
+        value.string(LEVEL).string(path);
+        self.property(name, "ObjectProperty", &value)
+    }
+}
+
+/// Header of an actor at the given position.
+fn actor_header(headers: &mut Writer, class: &str, path: &str, position: [f32; 3]) {
+    headers
+        .i32(1)
+        .string(class)
+        .string(LEVEL)
+        .string(path)
+        .i32(1);
+    headers.f32(0.0).f32(0.0).f32(0.0).f32(1.0);
+    headers.f32(position[0]).f32(position[1]).f32(position[2]);
+    headers.f32(1.0).f32(1.0).f32(1.0).i32(0);
+}
+
+/// Header of an object which isn't an actor.
+fn object_header(headers: &mut Writer, class: &str, path: &str) {
+    headers
+        .i32(0)
+        .string(class)
+        .string(LEVEL)
+        .string(path)
+        .string("");
+}
+
+/// Contents of an object, given its properties.
+fn object(contents: &mut Writer, is_actor: bool, properties: &Writer) {
+    let mut object = Writer::default();
+    if is_actor {
+        object.string(LEVEL).string("").i32(0);
+    }
+    object.bytes(&properties.0).string("None").i32(0);
+    contents
+        .i32(46)
+        .i32(0)
+        .i32(object.0.len() as i32)
+        .bytes(&object.0);
+}
+
+/// A save containing a smelter and a coal generator on one power circuit, a miner on no circuit,
+/// a wall which isn't a production building, and a component of the smelter.
+fn save_body() -> Writer {
+    let mut headers = Writer::default();
+    headers.i32(6);
+    actor_header(
+        &mut headers,
+        "/Game/FactoryGame/Buildable/Factory/SmelterMk1/Build_SmelterMk1.Build_SmelterMk1_C",
+        SMELTER,
+        [0.0, 0.0, 0.0],
+    );
+    actor_header(
+        &mut headers,
+        "/Game/FactoryGame/Buildable/Factory/GeneratorCoal/Build_GeneratorCoal.Build_GeneratorCoal_C",
+        GENERATOR,
+        [1_000.0, 0.0, 0.0],
+    );
+    actor_header(
+        &mut headers,
+        "/Game/FactoryGame/Buildable/Factory/MinerMK1/Build_MinerMk1.Build_MinerMk1_C",
+        MINER,
+        [2_000.0, 0.0, 0.0],
+    );
+    actor_header(
+        &mut headers,
+        "/Game/FactoryGame/Buildable/Building/Wall/Build_Wall_8x4.Build_Wall_8x4_C",
+        "Persistent_Level:PersistentLevel.Build_Wall_8x4_C_4",
+        [0.0, 0.0, 0.0],
+    );
+    object_header(
+        &mut headers,
+        "/Script/FactoryGame.FGPowerConnectionComponent",
+        &format!("{SMELTER}.PowerInput"),
+    );
+    object_header(
+        &mut headers,
+        "/Script/FactoryGame.FGPowerCircuit",
+        "Persistent_Level:PersistentLevel.CircuitSubsystem.FGPowerCircuit_5",
+    );
+    // Collected pickups at the end of the header section are skipped.
+    headers.i32(0);
+
+    let mut contents = Writer::default();
+    contents.i32(6);
+    let mut smelter = Writer::default();
+    smelter.object_property(
+        "mCurrentRecipe",
+        "/Game/FactoryGame/Recipes/Smelter/Recipe_IngotIron.Recipe_IngotIron_C",
+    );
+    let mut clock = Writer::default();
+    clock.f32(1.5);
+    smelter.property("mCurrentPotential", "FloatProperty", &clock);
+    object(&mut contents, true, &smelter);
+    let mut generator = Writer::default();
+    generator.object_property(
+        "mCurrentFuelClass",
+        "/Game/FactoryGame/Resource/RawResources/Coal/Desc_Coal.Desc_Coal_C",
+    );
+    object(&mut contents, true, &generator);
+    let mut miner = Writer::default();
+    miner.object_property(
+        "mExtractableResource",
+        "Persistent_Level:PersistentLevel.BP_ResourceNode7",
+    );
+    object(&mut contents, true, &miner);
+    object(&mut contents, true, &Writer::default());
+    object(&mut contents, false, &Writer::default());
+    let mut circuit = Writer::default();
+    let mut circuit_id = Writer::default();
+    circuit_id.i32(5);
+    circuit.property("mCircuitID", "IntProperty", &circuit_id);
+    let mut components = Writer::default();
+    components.i32(2);
+    for component in [SMELTER, GENERATOR] {
+        components
+            .string(LEVEL)
+            .string(&format!("{component}.PowerInput"));
+    }
+    circuit
+        .string("mComponents")
+        .string("ArrayProperty")
+        .i32(components.0.len() as i32)
+        .i32(0)
+        .string("ObjectProperty")
+        .u8(0)
+        .bytes(&components.0);
+    object(&mut contents, false, &circuit);
+
+    let mut level = Writer::default();
+    level.sized64(&headers).sized64(&contents).i32(0);
+
+    let mut body = Writer::default();
+    // No partition grids and no sublevels, just the persistent level.
+    body.i64(0).i32(0).i32(0).bytes(&level.0);
+    body
+}
+
+/// A save with the given header and version numbers wrapping the body in one compressed chunk.
+fn save_file(save_version: i32, body: &Writer) -> Vec<u8> {
+    let mut save = Writer::default();
+    save.i32(14)
+        .i32(save_version)
+        .i32(1)
+        .string("Save Name")
+        .string("Persistent_Level")
+        .string("?startloc=Grass Fields")
+        .string("My Session");
+    let compressed = compress_to_vec_zlib(&body.0, 6);
+    save.u32(0x9E2A83C1)
+        .u32(0x22222222)
+        .i64(128 * 1024)
+        .u8(3)
+        .i64(compressed.len() as i64)
+        .i64(body.0.len() as i64)
+        .i64(compressed.len() as i64)
+        .i64(body.0.len() as i64)
+        .bytes(&compressed);
+    save.0
+}
+
+/// Names of the groups directly under the root of a tree.
+fn group_names(root: &Node) -> Vec<String> {
+    root.group()
+        .unwrap()
+        .children
+        .iter()
+        .map(|child| child.group().unwrap().name.to_string())
+        .collect()
+}
+
+#[test]
+fn reads_each_kind_of_object() {
+    let db = Database::load_latest();
+    let save = SaveGame::read(&save_file(46, &save_body()), &db).unwrap();
+    assert_eq!(save.session_name, "My Session");
+    assert_eq!(save.buildings.len(), 3);
+
+    let [smelter, generator, miner] = &save.buildings[..] else {
+        unreachable!();
+    };
+    assert_eq!(smelter.building, "Desc_SmelterMk1_C".into());
+    assert_eq!(smelter.recipe, Some(RecipeId::from("Recipe_IngotIron_C")));
+    assert_eq!(smelter.clock_speed, 1.5);
+    assert_eq!(smelter.circuit, Some(5));
+    assert_eq!(generator.building, "Desc_GeneratorCoal_C".into());
+    assert_eq!(generator.fuel, Some(ItemId::from("Desc_Coal_C")));
+    assert_eq!(generator.clock_speed, 1.0);
+    assert_eq!(generator.circuit, Some(5));
+    assert_eq!(miner.building, "Desc_MinerMk1_C".into());
+    assert_eq!(miner.position, [2_000.0, 0.0, 0.0]);
+    assert_eq!(miner.circuit, None);
+}
+
+#[test]
+fn extractors_are_marked_as_needing_setup() {
+    let db = Database::load_latest();
+    let save = SaveGame::read(&save_file(46, &save_body()), &db).unwrap();
+    let tree = save.build_tree(&db, SaveGrouping::Proximity);
+    assert_eq!(group_names(&tree), ["Iron Ingot Factory"]);
+
+    let factory = tree.group().unwrap().children[0].group().unwrap();
+    assert_eq!(factory.children.len(), 3);
+    let needs_setup = factory.children[2].group().unwrap();
+    assert_eq!(needs_setup.name.as_ref(), "Extractors Needing Setup");
+    let miner = needs_setup.children[0].building().unwrap();
+    assert!(matches!(
+        &miner.settings,
+        BuildingSettings::Miner(settings) if settings.resource.is_none()
+    ));
+}
+
+#[test]
+fn groups_by_power_circuit() {
+    let db = Database::load_latest();
+    let save = SaveGame::read(&save_file(46, &save_body()), &db).unwrap();
+    let tree = save.build_tree(&db, SaveGrouping::PowerCircuit);
+    assert_eq!(
+        group_names(&tree),
+        ["Power Circuit 1", "Not Connected to Power"]
+    );
+    let circuit = tree.group().unwrap().children[0].group().unwrap();
+    let buildings: Vec<_> = circuit
+        .children
+        .iter()
+        .map(|child| child.building().unwrap().building.unwrap())
+        .collect();
+    assert_eq!(
+        buildings,
+        ["Desc_SmelterMk1_C".into(), "Desc_GeneratorCoal_C".into()]
+    );
+}
+
+#[test]
+fn rejects_saves_from_before_1_0() {
+    let db = Database::load_latest();
+    assert!(matches!(
+        SaveGame::read(&save_file(42, &save_body()), &db),
+        Err(SaveGameError::UnsupportedVersion(42))
+    ));
+}
+
+/// Level sizes near the largest the save can hold must be reported as errors rather than
+/// overflowing the offset they are added to.
+#[test]
+fn oversized_level_is_an_error() {
+    let db = Database::load_latest();
+    let mut body = Writer::default();
+    body.i64(0).i32(0).i32(0).i64(i64::MAX).i32(0);
+    assert!(matches!(
+        SaveGame::read(&save_file(46, &body), &db),
+        Err(SaveGameError::Truncated | SaveGameError::Invalid(_))
+    ));
+}
@@ -6,15 +6,24 @@
 //
 //       http://www.apache.org/licenses/LICENSE-2.0
 use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 
 use satisfactory_accounting::database::{
     BuildingKind, BuildingType, Database, Fuel, Generator, Geothermal, Item, ItemAmount, ItemId,
-    Manufacturer, Miner, Power, PowerConsumer, Pump, Recipe, Station,
+    Manufacturer, Miner, Power, PowerConsumer, Pump, PurityCounts, Recipe, ResourceNodes, Station,
+    Transport, TransportKind, VariablePower, Vehicle,
 };
 
 mod rawdata;
 
 fn main() {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("binary") {
+        convert_to_binary(args);
+        return;
+    }
+
     let raw = rawdata::RawData::load();
 
     let machine_recipes: Vec<_> = raw
@@ -87,6 +96,26 @@ fn main() {
     /// As of 1.0 Drones can use any fuel.
     const DRONE_FUELS: &[&str] = TRUCK_FUELS;
 
+    /// Vehicles which refuel at a Truck Station. Drones burn fuel per trip based on distance
+    /// rather than at a fixed rate, so the Drone Port has no vehicles here.
+    const TRUCK_VEHICLES: &[&str] = &["Desc_Truck_C", "Desc_Tractor_C", "Desc_Explorer_C"];
+    let vehicles = |class_names: &[&str]| -> Vec<Vehicle> {
+        class_names
+            .iter()
+            .map(|&class_name| {
+                let vehicle = &raw.buildings[class_name];
+                Vehicle {
+                    name: vehicle.name.as_str().into(),
+                    image: vehicle.slug.as_str().into(),
+                    power: vehicle
+                        .metadata
+                        .power_consumption
+                        .expect("Vehicle missing power consumption"),
+                }
+            })
+            .collect()
+    };
+
     let miners: HashMap<_, _> = raw
         .miners
         .values()
@@ -125,6 +154,8 @@ fn main() {
             "Desc_NuclearWaste_C".to_owned(),
             "Desc_PlutoniumWaste_C".to_owned(),
             "Desc_AlienPowerFuel_C".to_owned(),
+            // Somersloops are part of the construction cost of some buildings.
+            "Desc_WAT1_C".to_owned(),
         ])
         .collect();
 
@@ -139,6 +170,54 @@ fn main() {
         ])
         .collect();
 
+    // Effective tier of each schematic, which is the highest tier of the schematic itself and any
+    // schematics it requires. Alternates and MAM research mostly have tier 0 of their own and are
+    // gated by their requirements instead.
+    fn schematic_tier<'a>(
+        raw: &'a rawdata::RawData,
+        class_name: &'a str,
+        tiers: &mut HashMap<&'a str, u32>,
+    ) -> u32 {
+        if let Some(&tier) = tiers.get(class_name) {
+            return tier;
+        }
+        let Some(schematic) = raw.schematics.get(class_name) else {
+            return 0;
+        };
+        // Insert a placeholder first in case of cycles.
+        tiers.insert(class_name, schematic.tier);
+        let tier = schematic
+            .required_schematics
+            .iter()
+            .map(|required| schematic_tier(raw, required, tiers))
+            .fold(schematic.tier, u32::max);
+        tiers.insert(class_name, tier);
+        tier
+    }
+    let mut schematic_tiers = HashMap::new();
+    // Lowest tier at which each recipe is unlocked by any schematic.
+    let mut recipe_unlock_tiers: HashMap<&str, u32> = HashMap::new();
+    for schematic in raw.schematics.values() {
+        let tier = schematic_tier(&raw, &schematic.class_name, &mut schematic_tiers);
+        for recipe in &schematic.unlock.recipes {
+            let entry = recipe_unlock_tiers.entry(recipe.as_str()).or_insert(tier);
+            *entry = (*entry).min(tier);
+        }
+    }
+    // Buildings are unlocked by unlocking the recipe used to build them.
+    let building_unlock_tiers: HashMap<&str, u32> = raw
+        .recipes
+        .values()
+        .filter(|recipe| recipe.for_building && recipe.products.len() == 1)
+        .map(|recipe| {
+            let tier = recipe_unlock_tiers
+                .get(recipe.class_name.as_str())
+                .copied()
+                .unwrap_or(0);
+            (recipe.products[0].item.as_str(), tier)
+        })
+        .collect();
+
     let mut recipes: BTreeMap<_, _> = machine_recipes
         .iter()
         .map(|recipe| Recipe {
@@ -173,11 +252,21 @@ fn main() {
                 })
                 .collect(),
             is_alternate: recipe.alternate,
+            unlock_tier: recipe_unlock_tiers
+                .get(recipe.class_name.as_str())
+                .copied()
+                .unwrap_or(0),
             produced_in: recipe
                 .produced_in
                 .iter()
                 .map(|machine| machine.as_str().into())
                 .collect(),
+            variable_power: (recipe.is_variable_power && recipe.max_power > 0.0).then_some(
+                VariablePower {
+                    min: recipe.min_power,
+                    max: recipe.max_power,
+                },
+            ),
         })
         // Patch in missing recipes.
         .chain([
@@ -194,7 +283,9 @@ fn main() {
                     amount: 1.0,
                 }],
                 is_alternate: false,
+                unlock_tier: 0,
                 produced_in: vec!["Desc_WaterPump_C".into()],
+                variable_power: None,
             },
             // Map the Main Portal as a manufacturer that only consumes singularity cells with no
             // products.
@@ -209,9 +300,27 @@ fn main() {
                 }],
                 products: Vec::new(),
                 is_alternate: false,
+                unlock_tier: 0,
                 produced_in: vec!["Desc_Portal_C".into()],
+                variable_power: None,
             },
         ])
+        // A recipe can't be used before any of the buildings that produce it are unlocked.
+        .map(|mut recipe| {
+            let building_tier = recipe
+                .produced_in
+                .iter()
+                .map(|building| {
+                    building_unlock_tiers
+                        .get(building.to_string().as_str())
+                        .copied()
+                        .unwrap_or(0)
+                })
+                .min()
+                .unwrap_or(0);
+            recipe.unlock_tier = recipe.unlock_tier.max(building_tier);
+            recipe
+        })
         .map(|recipe| (recipe.id, recipe))
         .collect();
 
@@ -243,6 +352,8 @@ fn main() {
             } else {
                 None
             },
+            sink_points: item.sink_points.unwrap_or(0),
+            is_fluid: item.liquid,
             mining_speed: if raw.resources.contains_key(item.class_name.as_str()) {
                 raw.resources[item.class_name.as_str()].speed
             } else {
@@ -264,6 +375,8 @@ fn main() {
                     Alien Power Augmenter."
                         .into(),
                 fuel: None,
+                sink_points: 0,
+                is_fluid: false,
                 mining_speed: 0.0,
                 produced_by: Vec::new(),
                 consumed_by: Vec::new(),
@@ -280,6 +393,39 @@ fn main() {
         }
     }
 
+    let build_costs: HashMap<_, _> = raw
+        .recipes
+        .values()
+        .filter(|recipe| recipe.for_building && recipe.products.len() == 1)
+        .map(|recipe| {
+            let cost: Vec<_> = recipe
+                .ingredients
+                .iter()
+                .map(|ingredient| ItemAmount {
+                    item: ingredient.item.as_str().into(),
+                    amount: ingredient.amount,
+                })
+                .collect();
+            (recipe.products[0].item.as_str(), cost)
+        })
+        .collect();
+
+    /// Number of somersloops that can be slotted into each building that supports production
+    /// amplification.
+    const SOMERSLOOP_SLOTS: &[(&str, u32)] = &[
+        ("Desc_SmelterMk1_C", 1),
+        ("Desc_ConstructorMk1_C", 1),
+        ("Desc_AssemblerMk1_C", 2),
+        ("Desc_FoundryMk1_C", 2),
+        ("Desc_OilRefinery_C", 2),
+        ("Desc_Converter_C", 2),
+        ("Desc_ManufacturerMk1_C", 4),
+        ("Desc_Blender_C", 4),
+        ("Desc_HadronCollider_C", 4),
+        ("Desc_QuantumEncoder_C", 4),
+    ];
+    let somersloop_slots: HashMap<_, _> = SOMERSLOOP_SLOTS.iter().copied().collect();
+
     let mut buildings: BTreeMap<_, _> = raw
         .buildings
         .values()
@@ -292,6 +438,21 @@ fn main() {
             id: building.class_name.as_str().into(),
             image: building.slug.as_str().into(),
             description: building.description.clone(),
+            build_cost: build_costs
+                .get(building.class_name.as_str())
+                .cloned()
+                .unwrap_or_default(),
+            unlock_tier: building_unlock_tiers
+                .get(building.class_name.as_str())
+                .copied()
+                .unwrap_or(0),
+            // Every overclockable building has 3 power shard slots; buildings which can't be
+            // overclocked get fixed up below once their kind is known.
+            power_shard_slots: 3,
+            somersloop_slots: somersloop_slots
+                .get(building.class_name.as_str())
+                .copied()
+                .unwrap_or(0),
             kind: if manufacturers.contains(building.class_name.as_str()) {
                 BuildingKind::Manufacturer(Manufacturer {
                     manufacturing_speed: if building.class_name == "Desc_WaterPump_C"
@@ -407,6 +568,7 @@ fn main() {
                         .metadata
                         .power_consumption
                         .expect("Power consumer missing power consumption"),
+                    vehicles: vehicles(TRUCK_VEHICLES),
                 })
             } else if building.class_name == "Desc_DroneStation_C" {
                 BuildingKind::Station(Station {
@@ -415,6 +577,7 @@ fn main() {
                         .metadata
                         .power_consumption
                         .expect("Power consumer missing power consumption"),
+                    vehicles: Vec::new(),
                 })
             } else {
                 BuildingKind::PowerConsumer(PowerConsumer {
@@ -425,6 +588,12 @@ fn main() {
                 })
             },
         })
+        .map(|mut building| {
+            if !building.overclockable() {
+                building.power_shard_slots = 0;
+            }
+            building
+        })
         .map(|building| (building.id, building))
         .collect();
 
@@ -483,8 +652,93 @@ fn main() {
         }
     }
 
-    let database = Database::new("v1.0/".to_string(), recipes, items, buildings);
+    /// Throughput of each tier of conveyor belt and pipeline, which isn't available in the raw
+    /// data, along with the building whose name and image each one uses.
+    const TRANSPORTS: &[(&str, TransportKind, u8, f32)] = &[
+        ("Desc_ConveyorBeltMk1_C", TransportKind::Belt, 1, 60.0),
+        ("Desc_ConveyorBeltMk2_C", TransportKind::Belt, 2, 120.0),
+        ("Desc_ConveyorBeltMk3_C", TransportKind::Belt, 3, 270.0),
+        ("Desc_ConveyorBeltMk4_C", TransportKind::Belt, 4, 480.0),
+        ("Desc_ConveyorBeltMk5_C", TransportKind::Belt, 5, 780.0),
+        ("Desc_ConveyorBeltMk6_C", TransportKind::Belt, 6, 1200.0),
+        ("Desc_Pipeline_C", TransportKind::Pipeline, 1, 300.0),
+        ("Desc_PipelineMK2_C", TransportKind::Pipeline, 2, 600.0),
+    ];
+
+    let transports: Vec<_> = TRANSPORTS
+        .iter()
+        .map(|&(class_name, kind, tier, throughput)| {
+            let building = &raw.buildings[class_name];
+            Transport {
+                name: building.name.as_str().into(),
+                image: building.slug.as_str().into(),
+                kind,
+                tier,
+                throughput,
+            }
+        })
+        .collect();
+
+    /// Number of impure, normal, and pure resource nodes and resource well pads of each resource
+    /// on the 1.0 map, which isn't available in the raw data.
+    const RESOURCE_NODES: &[(&str, [u32; 3], [u32; 3])] = &[
+        ("Desc_OreIron_C", [39, 42, 46], [0, 0, 0]),
+        ("Desc_OreCopper_C", [13, 29, 13], [0, 0, 0]),
+        ("Desc_Stone_C", [15, 51, 29], [0, 0, 0]),
+        ("Desc_Coal_C", [15, 31, 16], [0, 0, 0]),
+        ("Desc_OreGold_C", [0, 9, 8], [0, 0, 0]),
+        ("Desc_RawQuartz_C", [3, 7, 7], [0, 0, 0]),
+        ("Desc_Sulfur_C", [6, 5, 5], [0, 0, 0]),
+        ("Desc_OreBauxite_C", [5, 6, 6], [0, 0, 0]),
+        ("Desc_OreUranium_C", [3, 2, 0], [0, 0, 0]),
+        ("Desc_SAM_C", [10, 6, 3], [0, 0, 0]),
+        ("Desc_LiquidOil_C", [10, 12, 8], [6, 3, 6]),
+        ("Desc_NitrogenGas_C", [0, 0, 0], [2, 7, 36]),
+    ];
+
+    let purity_counts = |[impure, normal, pure]: [u32; 3]| PurityCounts {
+        impure,
+        normal,
+        pure,
+    };
+    let resource_nodes: BTreeMap<_, _> = RESOURCE_NODES
+        .iter()
+        .map(|&(class_name, nodes, well_pads)| {
+            (
+                ItemId::from(class_name),
+                ResourceNodes {
+                    nodes: purity_counts(nodes),
+                    well_pads: purity_counts(well_pads),
+                },
+            )
+        })
+        .collect();
+
+    let database = Database::new(
+        "v1.0/".to_string(),
+        recipes,
+        items,
+        buildings,
+        transports,
+        resource_nodes,
+    );
 
     serde_json::to_writer_pretty(std::io::stdout().lock(), &database)
         .expect("Unable to write database");
 }
+
+/// Convert each of the given JSON databases to the binary format which is built into the app,
+/// writing the result next to the source file with a `.bin` extension.
+fn convert_to_binary(paths: impl Iterator<Item = String>) {
+    for path in paths {
+        let path = Path::new(&path);
+        let json = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Unable to read {}: {e}", path.display()));
+        let database: Database = serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("Unable to parse {}: {e}", path.display()));
+        let out = path.with_extension("bin");
+        fs::write(&out, database.to_binary())
+            .unwrap_or_else(|e| panic!("Unable to write {}: {e}", out.display()));
+        eprintln!("Wrote {}", out.display());
+    }
+}
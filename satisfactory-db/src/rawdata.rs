@@ -64,6 +64,9 @@ pub(crate) struct Recipe {
     pub(crate) in_workshop: bool,
     pub(crate) products: Vec<ItemAmount>,
     pub(crate) produced_in: Vec<String>,
+    pub(crate) is_variable_power: bool,
+    pub(crate) min_power: f32,
+    pub(crate) max_power: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]